@@ -1,15 +1,28 @@
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, AssetType as MsgAssetType};
-use crate::state::{TokenizedAsset, ASSETS, FRACTIONAL_BALANCES, NEXT_TOKEN_ID, AssetType as StateAssetType};
+use crate::msg::{AssetHoldersResponse, AssetInfoResponse, AssetsResponse, AssetSummaryResponse, DerivativesResponse, DisputeResponse, LineageResponse, LinkedAsset, DisputesResponse, ExecuteMsg, DisputeStatus as MsgDisputeStatus, HolderBalance, InstantiateMsg, MigrateMsg, LicenseResponse, QueryMsg, AssetType as MsgAssetType};
+use crate::state::{Config, Dispute, License, RoyaltyAccount, TokenizedAsset, ARBITERS, ASSETS, ASSET_DISPUTES, CONFIG, DERIVATIVES, DISPUTES, LINEAGE_DEPTHS, NEXT_DISPUTE_ID, OPEN_DISPUTES, DisputeStatus as StateDisputeStatus, FRACTIONAL_BALANCES, HOLDER_STATS, LICENSES, NEXT_LICENSE_ID, NEXT_TOKEN_ID, OWNER_ASSETS, PARENTS, LEGACY_ROYALTY_ACCOUNTS, ROYALTY_ACCOUNTS, ROYALTY_INDEX, AssetType as StateAssetType};
 use cosmwasm_std::{
-    entry_point, to_binary, BankMsg, Binary, CanonicalAddr, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Uint128, WasmMsg
+    entry_point, to_binary, Addr, BankMsg, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, Event, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128, WasmMsg
 };
-use cw2::set_contract_version;
-use crate::smarttoken::{BALANCES, TOKEN_INFO};
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
+use smarttoken::{BALANCES, TOKEN_INFO};
 use coreum_wasm_sdk::core::{CoreumMsg, CoreumQueries};
+use fractions::{fraction_balance, migrate_legacy_balances, set_fraction_balance};
+use std::collections::BTreeMap;
 
 const CONTRACT_NAME: &str = "intellectual-property-tokenization";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Last version storing fractional balances and royalty accounts keyed by holder first
+const LEGACY_VERSION: &str = "0.1.0";
+
+const MAX_ROYALTY_BPS: u16 = 10_000;
+
+const MAX_PARENTS: usize = 5;
+const MAX_LINEAGE_DEPTH: u32 = 5;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
 
 #[entry_point]
 pub fn instantiate(
@@ -19,7 +32,10 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> Result<Response<CoreumMsg>, ContractError> {
     let owner = deps.api.addr_validate(&msg.owner)?;
+    CONFIG.save(deps.storage, &Config { owner: owner.clone(), payment_denom: msg.payment_denom, dispute_bond: msg.dispute_bond })?;
     NEXT_TOKEN_ID.save(deps.storage, &1)?;
+    NEXT_LICENSE_ID.save(deps.storage, &1)?;
+    NEXT_DISPUTE_ID.save(deps.storage, &1)?;
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::new().add_attribute("method", "instantiate").add_attribute("owner", owner.to_string()))
 }
@@ -27,14 +43,23 @@ pub fn instantiate(
 #[entry_point]
 pub fn execute(
     deps: DepsMut<CoreumQueries>,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response<CoreumMsg>, ContractError> {
     match msg {
         ExecuteMsg::CreateAsset { total_supply, price, uri, asset_type } => create_asset(deps, info, total_supply, price, uri, asset_type),
-        ExecuteMsg::MintSmartToken { to, amount } => execute_mint_smart_token(deps, info, to, amount),
-        ExecuteMsg::TransferSmartToken { to, amount } => execute_transfer_smart_token(deps, info, to, amount),
+        ExecuteMsg::GrantLicense { token_id, licensee, terms_uri, royalty_bps, duration } => grant_license(deps, env, info, token_id, licensee, terms_uri, royalty_bps, duration),
+        ExecuteMsg::PayRoyalty { license_id } => pay_royalty(deps, env, info, license_id),
+        ExecuteMsg::DepositRevenue { token_id } => deposit_revenue(deps, info, token_id),
+        ExecuteMsg::ClaimRoyalties { token_id } => claim_royalties(deps, info, token_id),
+        ExecuteMsg::TransferFraction { token_id, to, amount } => transfer_fraction(deps, info, token_id, to, amount),
+        ExecuteMsg::RegisterDerivative { token_id, parents } => register_derivative(deps, info, token_id, parents),
+        ExecuteMsg::SetArbiter { arbiter, enabled } => set_arbiter(deps, info, arbiter, enabled),
+        ExecuteMsg::FileDispute { token_id, evidence_uri } => file_dispute(deps, env, info, token_id, evidence_uri),
+        ExecuteMsg::ResolveDispute { dispute_id, upheld } => resolve_dispute(deps, info, dispute_id, upheld),
+        ExecuteMsg::MintSmartToken { to, amount } => execute_mint_smart_token(deps, env, info, to, amount),
+        ExecuteMsg::TransferSmartToken { to, amount } => execute_transfer_smart_token(deps, env, info, to, amount),
      }
 }
 
@@ -63,29 +88,404 @@ fn create_asset(
     };
 
     ASSETS.save(deps.storage, token_id, &asset)?;
+    OWNER_ASSETS.save(deps.storage, (&owner, token_id), &true)?;
+    ROYALTY_INDEX.save(deps.storage, token_id, &Decimal::zero())?;
+    // The creator holds every fraction until they are transferred
+    set_fraction_balance(deps.storage, token_id, &owner, total_supply)?;
     NEXT_TOKEN_ID.save(deps.storage, &(token_id + 1))?;
 
     Ok(Response::new().add_attribute("method", "create_asset").add_attribute("token_id", token_id.to_string()).add_attribute("owner", owner.to_string()))
 }
 
+/// Grant a time-bound license on an asset; only the asset owner may grant licenses
+#[allow(clippy::too_many_arguments)]
+fn grant_license(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    token_id: u64,
+    licensee: String,
+    terms_uri: String,
+    royalty_bps: u16,
+    duration: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let asset = ASSETS.load(deps.storage, token_id)?;
+    if info.sender != asset.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    assert_undisputed(deps.storage, token_id)?;
+    if royalty_bps > MAX_ROYALTY_BPS {
+        return Err(ContractError::InvalidRoyalty {});
+    }
+    if duration == 0 {
+        return Err(ContractError::InvalidDuration {});
+    }
+
+    let license = License {
+        token_id,
+        licensee: deps.api.addr_validate(&licensee)?,
+        terms_uri,
+        royalty_bps,
+        start: env.block.time,
+        expires: env.block.time.plus_seconds(duration),
+        royalties_paid: Uint128::zero(),
+    };
+    let license_id = NEXT_LICENSE_ID.load(deps.storage)?;
+    LICENSES.save(deps.storage, license_id, &license)?;
+    NEXT_LICENSE_ID.save(deps.storage, &(license_id + 1))?;
+
+    Ok(Response::new()
+        .add_attribute("method", "grant_license")
+        .add_attribute("license_id", license_id.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("licensee", license.licensee.to_string())
+        .add_attribute("expires", license.expires.seconds().to_string()))
+}
+
+/// Pay royalties under an active license; the payment accrues to every fraction of the licensed asset
+fn pay_royalty(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    license_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut license = LICENSES.load(deps.storage, license_id)?;
+    if env.block.time >= license.expires {
+        return Err(ContractError::LicenseExpired { license_id });
+    }
+
+    let amount = income_payment(&config, &info)?;
+    accrue_income(deps.storage, license.token_id, amount)?;
+
+    license.royalties_paid = license.royalties_paid.checked_add(amount)?;
+    LICENSES.save(deps.storage, license_id, &license)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "pay_royalty")
+        .add_attribute("license_id", license_id.to_string())
+        .add_attribute("token_id", license.token_id.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// Post income earned by an asset; it accrues to the current fraction holders in proportion to their holdings
+fn deposit_revenue(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    token_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let amount = income_payment(&config, &info)?;
+    accrue_income(deps.storage, token_id, amount)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "deposit_revenue")
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("from", info.sender.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+fn income_payment(config: &Config, info: &MessageInfo) -> Result<Uint128, ContractError> {
+    match info.funds.as_slice() {
+        [coin] if coin.denom == config.payment_denom && !coin.amount.is_zero() => Ok(coin.amount),
+        _ => Err(ContractError::InvalidFunds { denom: config.payment_denom.clone() }),
+    }
+}
+
+/// Raise the income earned per fraction of an asset after passing the parents' shares upstream;
+/// holders collect their share when they claim. Rounding dust of the division stays in the contract
+fn accrue_income(storage: &mut dyn Storage, token_id: u64, amount: Uint128) -> Result<(), ContractError> {
+    // Income waiting to be accrued, keyed by (lineage depth, token_id). Parents are shallower than their
+    // derivatives, so taking the deepest asset first settles every ancestor once with all shares it is owed
+    let mut queue = BTreeMap::from([((lineage_depth(storage, token_id)?, token_id), amount)]);
+    while let Some(((_, token_id), amount)) = queue.pop_last() {
+        let asset = ASSETS.load(storage, token_id)?;
+        if asset.total_supply.is_zero() {
+            return Err(ContractError::NoHolders { token_id });
+        }
+
+        let parents = PARENTS.prefix(token_id).range(storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>()?;
+        let mut retained = amount;
+        for (parent_id, share_bps) in parents {
+            let share = amount.multiply_ratio(share_bps, MAX_ROYALTY_BPS);
+            if !share.is_zero() {
+                let queued = queue.entry((lineage_depth(storage, parent_id)?, parent_id)).or_default();
+                *queued = queued.checked_add(share)?;
+                retained = retained.checked_sub(share)?;
+            }
+        }
+
+        let index = ROYALTY_INDEX.load(storage, token_id)?;
+        ROYALTY_INDEX.save(storage, token_id, &(index + Decimal::from_ratio(retained, asset.total_supply)))?;
+    }
+    Ok(())
+}
+
+/// Link an asset to the works it derives from; only the asset owner may register it, once
+fn register_derivative(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    token_id: u64,
+    parents: Vec<LinkedAsset>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let asset = ASSETS.load(deps.storage, token_id)?;
+    if info.sender != asset.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    assert_undisputed(deps.storage, token_id)?;
+    if PARENTS.prefix(token_id).range(deps.storage, None, None, Order::Ascending).next().is_some() {
+        return Err(ContractError::AlreadyDerivative { token_id });
+    }
+    // Lineages grow downwards only: an asset nothing derives from yet can't be upstream of its parents,
+    // and the depths stored for its ancestors stay exact
+    if DERIVATIVES.prefix(token_id).keys(deps.storage, None, None, Order::Ascending).next().is_some() {
+        return Err(ContractError::HasDerivatives { token_id });
+    }
+
+    if parents.is_empty() || parents.len() > MAX_PARENTS {
+        return Err(ContractError::InvalidParents {});
+    }
+    let mut total_bps = 0u32;
+    let mut depth = 0;
+    for (i, parent) in parents.iter().enumerate() {
+        if !ASSETS.has(deps.storage, parent.token_id) || parents[..i].iter().any(|other| other.token_id == parent.token_id) {
+            return Err(ContractError::InvalidParents {});
+        }
+        if parent.token_id == token_id {
+            return Err(ContractError::LineageCycle { token_id });
+        }
+        total_bps += u32::from(parent.share_bps);
+        depth = depth.max(lineage_depth(deps.storage, parent.token_id)? + 1);
+    }
+    if total_bps > u32::from(MAX_ROYALTY_BPS) {
+        return Err(ContractError::InvalidParents {});
+    }
+    if depth > MAX_LINEAGE_DEPTH {
+        return Err(ContractError::LineageTooDeep { max_depth: MAX_LINEAGE_DEPTH });
+    }
+
+    for parent in &parents {
+        PARENTS.save(deps.storage, (token_id, parent.token_id), &parent.share_bps)?;
+        DERIVATIVES.save(deps.storage, (parent.token_id, token_id), &parent.share_bps)?;
+    }
+    LINEAGE_DEPTHS.save(deps.storage, token_id, &depth)?;
+
+    let parent_ids = parents.iter().map(|parent| parent.token_id.to_string()).collect::<Vec<_>>().join(",");
+    let event = Event::new("derivative-registered")
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("parents", parent_ids)
+        .add_attribute("share_bps", total_bps.to_string());
+    Ok(Response::new().add_attribute("method", "register_derivative").add_event(event))
+}
+
+/// Number of generations between an asset and its most distant ancestor
+fn lineage_depth(storage: &dyn Storage, token_id: u64) -> StdResult<u32> {
+    Ok(LINEAGE_DEPTHS.may_load(storage, token_id)?.unwrap_or_default())
+}
+
+/// Withdraw the royalties and revenue accrued to the sender's fractions of an asset
+fn claim_royalties(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    token_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut account = settle_royalties(deps.storage, token_id, &info.sender)?;
+
+    let amount = account.pending;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    account.pending = Uint128::zero();
+    ROYALTY_ACCOUNTS.save(deps.storage, (token_id, info.sender.clone()), &account)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send { to_address: info.sender.to_string(), amount: vec![Coin { denom: config.payment_denom, amount }] })
+        .add_attribute("method", "claim_royalties")
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("holder", info.sender.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// Bring a holder's royalty account up to the asset's income index; must run before the holder's balance changes
+fn settle_royalties(storage: &mut dyn Storage, token_id: u64, holder: &Addr) -> Result<RoyaltyAccount, ContractError> {
+    let index = ROYALTY_INDEX.load(storage, token_id)?;
+    let balance = fraction_balance(storage, token_id, holder)?;
+    let mut account = ROYALTY_ACCOUNTS.may_load(storage, (token_id, holder.clone()))?.unwrap_or_default();
+
+    account.pending = account.pending.checked_add(balance * (index - account.index))?;
+    account.index = index;
+    ROYALTY_ACCOUNTS.save(storage, (token_id, holder.clone()), &account)?;
+    Ok(account)
+}
+
+/// Move fractions of an asset from the sender to another address
+fn transfer_fraction(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    token_id: u64,
+    to: String,
+    amount: Uint128,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    ASSETS.load(deps.storage, token_id)?;
+    assert_undisputed(deps.storage, token_id)?;
+    let to_addr = deps.api.addr_validate(&to)?;
+
+    let from_balance = fraction_balance(deps.storage, token_id, &info.sender)?;
+    if from_balance < amount {
+        return Err(ContractError::InsufficientBalance { balance: from_balance });
+    }
+
+    // Royalties accrued so far stay with the previous holder
+    settle_royalties(deps.storage, token_id, &info.sender)?;
+    settle_royalties(deps.storage, token_id, &to_addr)?;
+    set_fraction_balance(deps.storage, token_id, &info.sender, from_balance.checked_sub(amount)?)?;
+    let to_balance = fraction_balance(deps.storage, token_id, &to_addr)?;
+    set_fraction_balance(deps.storage, token_id, &to_addr, to_balance.checked_add(amount)?)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "transfer_fraction")
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("from", info.sender.to_string())
+        .add_attribute("to", to_addr.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// Enable or disable an arbiter; only the contract owner may manage arbiters
+fn set_arbiter(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    arbiter: String,
+    enabled: bool,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let arbiter_addr = deps.api.addr_validate(&arbiter)?;
+    if enabled {
+        ARBITERS.save(deps.storage, &arbiter_addr, &true)?;
+    } else {
+        ARBITERS.remove(deps.storage, &arbiter_addr);
+    }
+
+    Ok(Response::new().add_attribute("method", "set_arbiter").add_attribute("arbiter", arbiter_addr.to_string()).add_attribute("enabled", enabled.to_string()))
+}
+
+/// Challenge the ownership claim of an asset, bonding the configured deposit
+fn file_dispute(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    token_id: u64,
+    evidence_uri: String,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ASSETS.load(deps.storage, token_id)?;
+
+    let expected = Coin { denom: config.payment_denom.clone(), amount: config.dispute_bond };
+    if info.funds != [expected] {
+        return Err(ContractError::InvalidFunds { denom: config.payment_denom });
+    }
+
+    let dispute = Dispute {
+        token_id,
+        challenger: info.sender.clone(),
+        evidence_uri,
+        bond: config.dispute_bond,
+        status: StateDisputeStatus::Open,
+        filed_at: env.block.time,
+        resolved_by: None,
+    };
+    let dispute_id = NEXT_DISPUTE_ID.load(deps.storage)?;
+    DISPUTES.save(deps.storage, dispute_id, &dispute)?;
+    NEXT_DISPUTE_ID.save(deps.storage, &(dispute_id + 1))?;
+    ASSET_DISPUTES.save(deps.storage, (token_id, dispute_id), &true)?;
+    let open = OPEN_DISPUTES.may_load(deps.storage, token_id)?.unwrap_or_default();
+    OPEN_DISPUTES.save(deps.storage, token_id, &(open + 1))?;
+
+    let event = Event::new("dispute-filed")
+        .add_attribute("dispute_id", dispute_id.to_string())
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("challenger", info.sender.to_string())
+        .add_attribute("bond", config.dispute_bond.to_string());
+    Ok(Response::new().add_attribute("method", "file_dispute").add_event(event))
+}
+
+/// Settle an open dispute; an upheld challenge hands the asset to the challenger, a rejected one forfeits the bond to the owner
+fn resolve_dispute(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    dispute_id: u64,
+    upheld: bool,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    if !ARBITERS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let mut dispute = DISPUTES.load(deps.storage, dispute_id)?;
+    if dispute.status != StateDisputeStatus::Open {
+        return Err(ContractError::DisputeResolved { dispute_id });
+    }
+
+    let mut asset = ASSETS.load(deps.storage, dispute.token_id)?;
+    let recipient = if upheld {
+        dispute.status = StateDisputeStatus::Upheld;
+        OWNER_ASSETS.remove(deps.storage, (&asset.owner, dispute.token_id));
+        asset.owner = dispute.challenger.clone();
+        ASSETS.save(deps.storage, dispute.token_id, &asset)?;
+        OWNER_ASSETS.save(deps.storage, (&asset.owner, dispute.token_id), &true)?;
+        dispute.challenger.clone()
+    } else {
+        dispute.status = StateDisputeStatus::Rejected;
+        asset.owner.clone()
+    };
+    dispute.resolved_by = Some(info.sender.clone());
+    DISPUTES.save(deps.storage, dispute_id, &dispute)?;
+    OPEN_DISPUTES.update(deps.storage, dispute.token_id, |open| -> StdResult<_> { Ok(open.unwrap_or_default().saturating_sub(1)) })?;
+
+    let mut response = Response::new().add_attribute("method", "resolve_dispute");
+    if !dispute.bond.is_zero() {
+        response = response.add_message(BankMsg::Send { to_address: recipient.to_string(), amount: vec![Coin { denom: config.payment_denom, amount: dispute.bond }] });
+    }
+    let event = Event::new("dispute-resolved")
+        .add_attribute("dispute_id", dispute_id.to_string())
+        .add_attribute("token_id", dispute.token_id.to_string())
+        .add_attribute("arbiter", info.sender.to_string())
+        .add_attribute("upheld", upheld.to_string())
+        .add_attribute("owner", asset.owner.to_string());
+    Ok(response.add_event(event))
+}
+
+fn assert_undisputed(storage: &dyn Storage, token_id: u64) -> Result<(), ContractError> {
+    if OPEN_DISPUTES.may_load(storage, token_id)?.unwrap_or_default() > 0 {
+        return Err(ContractError::AssetDisputed { token_id });
+    }
+    Ok(())
+}
+
 /// Mint new smart tokens
 fn execute_mint_smart_token(
     deps: DepsMut<CoreumQueries>,
+    env: Env,
     info: MessageInfo,
     to: String,
     amount: Uint128,
 ) -> Result<Response<CoreumMsg>, ContractError> {
-    let token_info = TOKEN_INFO.load(deps.storage)?;
+    let mut token_info = TOKEN_INFO.load(deps.storage)?;
 
     // Ensure the sender is the owner of the token
     if info.sender != token_info.owner {
         return Err(ContractError::Unauthorized {});
     }
 
-    // Update the recipient's balance
+    // Update the recipient's balance and the total supply
     let to_addr = deps.api.addr_validate(&to)?;
     let balance = BALANCES.may_load(deps.storage, to_addr.clone())?.unwrap_or_default();
-    BALANCES.save(deps.storage, to_addr.clone(), &(balance + amount))?;
+    BALANCES.save(deps.storage, to_addr.clone(), &balance.checked_add(amount)?, env.block.height)?;
+    token_info.total_supply = token_info.total_supply.checked_add(amount)?;
+    TOKEN_INFO.save(deps.storage, &token_info)?;
 
     Ok(Response::new()
         .add_attribute("method", "mint_smart_token")
@@ -96,6 +496,7 @@ fn execute_mint_smart_token(
 /// Transfer smart tokens
 fn execute_transfer_smart_token(
     deps: DepsMut<CoreumQueries>,
+    env: Env,
     info: MessageInfo,
     to: String,
     amount: Uint128,
@@ -110,9 +511,9 @@ fn execute_transfer_smart_token(
     }
 
     // Update the sender's and recipient's balances
-    BALANCES.save(deps.storage, sender_addr.clone(), &(sender_balance - amount))?;
+    BALANCES.save(deps.storage, sender_addr.clone(), &(sender_balance - amount), env.block.height)?;
     let recipient_balance = BALANCES.may_load(deps.storage, to_addr.clone())?.unwrap_or_default();
-    BALANCES.save(deps.storage, to_addr.clone(), &(recipient_balance + amount))?;
+    BALANCES.save(deps.storage, to_addr.clone(), &(recipient_balance + amount), env.block.height)?;
 
     Ok(Response::new()
         .add_attribute("method", "transfer_smart_token")
@@ -122,13 +523,334 @@ fn execute_transfer_smart_token(
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn migrate(deps: DepsMut<CoreumQueries>, _env: Env, _msg: MigrateMsg) -> Result<Response<CoreumMsg>, ContractError> {
+    let version = get_contract_version(deps.storage)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    if version.version != LEGACY_VERSION {
+        return Ok(Response::new().add_attribute("method", "migrate"));
+    }
+
+    // move balances and royalty accounts under their asset so its holders can be paged through
+    let migrated = migrate_legacy_balances(deps.storage)?;
+    let accounts = LEGACY_ROYALTY_ACCOUNTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for ((holder, token_id), _) in &accounts {
+        LEGACY_ROYALTY_ACCOUNTS.remove(deps.storage, (holder.clone(), *token_id));
+    }
+    for ((holder, token_id), account) in &accounts {
+        ROYALTY_ACCOUNTS.save(deps.storage, (*token_id, holder.clone()), account)?;
+    }
+    // assets created before the owner index existed are only listed once indexed here
+    let assets = ASSETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(token_id, asset)| (asset.owner, token_id)))
+        .collect::<StdResult<Vec<_>>>()?;
+    for (owner, token_id) in &assets {
+        OWNER_ASSETS.save(deps.storage, (owner, *token_id), &true)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("migrated_balances", migrated.to_string())
+        .add_attribute("migrated_royalty_accounts", accounts.len().to_string())
+        .add_attribute("indexed_assets", assets.len().to_string()))
+}
+
+#[entry_point]
+pub fn query(deps: Deps<CoreumQueries>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::TokenURI { token_id } => to_binary(&query_token_uri(deps, token_id)?),
+        QueryMsg::AssetInfo { token_id } => to_binary(&query_asset_info(deps, token_id)?),
+        QueryMsg::AssetsByOwner { owner, start_after, limit } => to_binary(&query_assets_by_owner(deps, owner, start_after, limit)?),
+        QueryMsg::AllAssets { start_after, limit } => to_binary(&query_all_assets(deps, start_after, limit)?),
+        QueryMsg::NextTokenId {} => to_binary(&NEXT_TOKEN_ID.load(deps.storage)?),
+        QueryMsg::FractionalOwnership { token_id, owner } => to_binary(&query_fractional_ownership(deps, token_id, owner)?),
+        QueryMsg::AssetHolders { token_id, start_after, limit } => to_binary(&query_asset_holders(deps, token_id, start_after, limit)?),
+        QueryMsg::AssetSummary { token_id } => to_binary(&query_asset_summary(deps, token_id)?),
+        QueryMsg::Lineage { token_id } => to_binary(&query_lineage(deps, token_id)?),
+        QueryMsg::Derivatives { token_id, start_after, limit } => to_binary(&query_derivatives(deps, token_id, start_after, limit)?),
+        QueryMsg::Dispute { dispute_id } => to_binary(&query_dispute(deps, dispute_id)?),
+        QueryMsg::AssetDisputes { token_id, start_after, limit } => to_binary(&query_asset_disputes(deps, token_id, start_after, limit)?),
+        QueryMsg::License { license_id } => to_binary(&query_license(deps, env, license_id)?),
+        QueryMsg::PendingRoyalties { token_id, holder } => to_binary(&query_pending_royalties(deps, token_id, holder)?),
     }
 }
 
-fn query_token_uri(deps: Deps, token_id: u64) -> StdResult<String> {
+fn query_token_uri(deps: Deps<CoreumQueries>, token_id: u64) -> StdResult<String> {
     let asset = ASSETS.load(deps.storage, token_id)?;
     Ok(asset.uri)
 }
+
+fn query_asset_info(deps: Deps<CoreumQueries>, token_id: u64) -> StdResult<AssetInfoResponse> {
+    let asset = ASSETS.load(deps.storage, token_id)?;
+    Ok(asset_info_response(token_id, asset))
+}
+
+fn query_assets_by_owner(deps: Deps<CoreumQueries>, owner: String, start_after: Option<u64>, limit: Option<u32>) -> StdResult<AssetsResponse> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let assets = OWNER_ASSETS
+        .prefix(&owner_addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|token_id| {
+            let token_id = token_id?;
+            Ok(asset_info_response(token_id, ASSETS.load(deps.storage, token_id)?))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(AssetsResponse { assets })
+}
+
+fn query_all_assets(deps: Deps<CoreumQueries>, start_after: Option<u64>, limit: Option<u32>) -> StdResult<AssetsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let assets = ASSETS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(token_id, asset)| asset_info_response(token_id, asset)))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(AssetsResponse { assets })
+}
+
+fn asset_info_response(token_id: u64, asset: TokenizedAsset) -> AssetInfoResponse {
+    let asset_type = match asset.asset_type {
+        StateAssetType::IntellectualProperty => MsgAssetType::IntellectualProperty,
+    };
+    AssetInfoResponse {
+        token_id,
+        owner: asset.owner.to_string(),
+        total_supply: asset.total_supply,
+        remaining_supply: asset.remaining_supply,
+        price: asset.price,
+        uri: asset.uri,
+        asset_type,
+    }
+}
+
+fn query_fractional_ownership(deps: Deps<CoreumQueries>, token_id: u64, owner: String) -> StdResult<Uint128> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    fraction_balance(deps.storage, token_id, &owner_addr)
+}
+
+fn query_asset_holders(deps: Deps<CoreumQueries>, token_id: u64, start_after: Option<String>, limit: Option<u32>) -> StdResult<AssetHoldersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = start_after.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    let start = start_addr.map(Bound::exclusive);
+    let holders = FRACTIONAL_BALANCES
+        .prefix(token_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(holder, balance)| HolderBalance { holder: holder.to_string(), balance }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(AssetHoldersResponse { token_id, holders })
+}
+
+fn query_asset_summary(deps: Deps<CoreumQueries>, token_id: u64) -> StdResult<AssetSummaryResponse> {
+    let asset = ASSETS.load(deps.storage, token_id)?;
+    let stats = HOLDER_STATS.may_load(deps.storage, token_id)?.unwrap_or_default();
+    let average_holding = if stats.holder_count == 0 {
+        Uint128::zero()
+    } else {
+        stats.held_supply / Uint128::from(stats.holder_count)
+    };
+
+    Ok(AssetSummaryResponse {
+        token_id,
+        total_supply: asset.total_supply,
+        held_supply: stats.held_supply,
+        holder_count: stats.holder_count,
+        average_holding,
+        open_disputes: OPEN_DISPUTES.may_load(deps.storage, token_id)?.unwrap_or_default(),
+    })
+}
+
+fn query_lineage(deps: Deps<CoreumQueries>, token_id: u64) -> StdResult<LineageResponse> {
+    let parents = PARENTS
+        .prefix(token_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(token_id, share_bps)| LinkedAsset { token_id, share_bps }))
+        .collect::<StdResult<Vec<_>>>()?;
+    let depth = lineage_depth(deps.storage, token_id)?;
+    Ok(LineageResponse { token_id, depth, parents })
+}
+
+fn query_derivatives(deps: Deps<CoreumQueries>, token_id: u64, start_after: Option<u64>, limit: Option<u32>) -> StdResult<DerivativesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let derivatives = DERIVATIVES
+        .prefix(token_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(token_id, share_bps)| LinkedAsset { token_id, share_bps }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(DerivativesResponse { token_id, derivatives })
+}
+
+fn query_dispute(deps: Deps<CoreumQueries>, dispute_id: u64) -> StdResult<DisputeResponse> {
+    let dispute = DISPUTES.load(deps.storage, dispute_id)?;
+    Ok(dispute_response(dispute_id, dispute))
+}
+
+fn query_asset_disputes(deps: Deps<CoreumQueries>, token_id: u64, start_after: Option<u64>, limit: Option<u32>) -> StdResult<DisputesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let disputes = ASSET_DISPUTES
+        .prefix(token_id)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|dispute_id| {
+            let dispute_id = dispute_id?;
+            Ok(dispute_response(dispute_id, DISPUTES.load(deps.storage, dispute_id)?))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(DisputesResponse { disputes })
+}
+
+fn dispute_response(dispute_id: u64, dispute: Dispute) -> DisputeResponse {
+    let status = match dispute.status {
+        StateDisputeStatus::Open => MsgDisputeStatus::Open,
+        StateDisputeStatus::Upheld => MsgDisputeStatus::Upheld,
+        StateDisputeStatus::Rejected => MsgDisputeStatus::Rejected,
+    };
+    DisputeResponse {
+        dispute_id,
+        token_id: dispute.token_id,
+        challenger: dispute.challenger.to_string(),
+        evidence_uri: dispute.evidence_uri,
+        bond: dispute.bond,
+        status,
+        filed_at: dispute.filed_at,
+        resolved_by: dispute.resolved_by.map(|arbiter| arbiter.to_string()),
+    }
+}
+
+fn query_license(deps: Deps<CoreumQueries>, env: Env, license_id: u64) -> StdResult<LicenseResponse> {
+    let license = LICENSES.load(deps.storage, license_id)?;
+    Ok(LicenseResponse {
+        license_id,
+        token_id: license.token_id,
+        licensee: license.licensee.to_string(),
+        terms_uri: license.terms_uri,
+        royalty_bps: license.royalty_bps,
+        start: license.start,
+        expires: license.expires,
+        royalties_paid: license.royalties_paid,
+        active: env.block.time < license.expires,
+    })
+}
+
+fn query_pending_royalties(deps: Deps<CoreumQueries>, token_id: u64, holder: String) -> StdResult<Uint128> {
+    let holder_addr = deps.api.addr_validate(&holder)?;
+    let index = ROYALTY_INDEX.load(deps.storage, token_id)?;
+    let balance = fraction_balance(deps.storage, token_id, &holder_addr)?;
+    let account = ROYALTY_ACCOUNTS.may_load(deps.storage, (token_id, holder_addr))?.unwrap_or_default();
+    Ok(account.pending.checked_add(balance * (index - account.index))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::from_binary;
+    use coreum_test_utils::{mock_coreum_deps, CoreumDeps};
+
+    fn setup(deps: DepsMut<CoreumQueries>) {
+        let msg = InstantiateMsg {
+            owner: "owner".to_string(),
+            symbol: "IPT".to_string(),
+            subunit: "uipt".to_string(),
+            precision: 6,
+            initial_amount: Uint128::new(1000),
+            payment_denom: "ucore".to_string(),
+            dispute_bond: Uint128::new(100),
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    fn create(deps: DepsMut<CoreumQueries>, owner: &str, uri: &str) {
+        let msg = ExecuteMsg::CreateAsset {
+            total_supply: Uint128::new(100),
+            price: Uint128::new(5),
+            uri: uri.to_string(),
+            asset_type: MsgAssetType::IntellectualProperty,
+        };
+        execute(deps, mock_env(), mock_info(owner, &[]), msg).unwrap();
+    }
+
+    fn token_ids(deps: &CoreumDeps, msg: QueryMsg) -> Vec<u64> {
+        let res: AssetsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        res.assets.into_iter().map(|asset| asset.token_id).collect()
+    }
+
+    #[test]
+    fn asset_info_describes_created_asset() {
+        let mut deps = mock_coreum_deps();
+        setup(deps.as_mut());
+        create(deps.as_mut(), "alice", "ipfs://song");
+
+        let res: AssetInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::AssetInfo { token_id: 1 }).unwrap()).unwrap();
+        assert_eq!(
+            res,
+            AssetInfoResponse {
+                token_id: 1,
+                owner: "alice".to_string(),
+                total_supply: Uint128::new(100),
+                remaining_supply: Uint128::new(100),
+                price: Uint128::new(5),
+                uri: "ipfs://song".to_string(),
+                asset_type: MsgAssetType::IntellectualProperty,
+            }
+        );
+        let next: u64 = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::NextTokenId {}).unwrap()).unwrap();
+        assert_eq!(next, 2);
+        query(deps.as_ref(), mock_env(), QueryMsg::AssetInfo { token_id: 2 }).unwrap_err();
+    }
+
+    #[test]
+    fn assets_are_paged_by_owner_and_overall() {
+        let mut deps = mock_coreum_deps();
+        setup(deps.as_mut());
+        for owner in ["alice", "bob", "alice", "alice"] {
+            create(deps.as_mut(), owner, "ipfs://asset");
+        }
+
+        let by_owner = |owner: &str, start_after, limit| QueryMsg::AssetsByOwner { owner: owner.to_string(), start_after, limit };
+        assert_eq!(token_ids(&deps, by_owner("alice", None, None)), vec![1, 3, 4]);
+        assert_eq!(token_ids(&deps, by_owner("alice", None, Some(2))), vec![1, 3]);
+        assert_eq!(token_ids(&deps, by_owner("alice", Some(3), None)), vec![4]);
+        assert_eq!(token_ids(&deps, by_owner("bob", None, None)), vec![2]);
+        assert!(token_ids(&deps, by_owner("carol", None, None)).is_empty());
+
+        assert_eq!(token_ids(&deps, QueryMsg::AllAssets { start_after: None, limit: None }), vec![1, 2, 3, 4]);
+        assert_eq!(token_ids(&deps, QueryMsg::AllAssets { start_after: Some(1), limit: Some(2) }), vec![2, 3]);
+    }
+
+    #[test]
+    fn migrate_indexes_existing_assets_by_owner() {
+        let mut deps = mock_coreum_deps();
+        setup(deps.as_mut());
+        // assets saved by the legacy contract, which kept no owner index
+        for (token_id, owner) in [(1, "alice"), (2, "bob"), (3, "alice")] {
+            let asset = TokenizedAsset {
+                owner: Addr::unchecked(owner),
+                total_supply: Uint128::new(100),
+                remaining_supply: Uint128::new(100),
+                price: Uint128::new(5),
+                uri: "ipfs://asset".to_string(),
+                asset_type: StateAssetType::IntellectualProperty,
+            };
+            ASSETS.save(deps.as_mut().storage, token_id, &asset).unwrap();
+        }
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, LEGACY_VERSION).unwrap();
+
+        let by_owner = |owner: &str| QueryMsg::AssetsByOwner { owner: owner.to_string(), start_after: None, limit: None };
+        assert!(token_ids(&deps, by_owner("alice")).is_empty());
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert!(res.attributes.iter().any(|attr| attr.key == "indexed_assets" && attr.value == "3"));
+        assert_eq!(token_ids(&deps, by_owner("alice")), vec![1, 3]);
+        assert_eq!(token_ids(&deps, by_owner("bob")), vec![2]);
+    }
+}
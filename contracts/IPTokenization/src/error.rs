@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,9 +6,54 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Insufficient balance: {balance}")]
+    InsufficientBalance { balance: Uint128 },
+
+    #[error("Invalid funds, expected a single {denom} coin")]
+    InvalidFunds { denom: String },
+
+    #[error("Royalty must be between 0 and 10000 basis points")]
+    InvalidRoyalty {},
+
+    #[error("License duration must be greater than zero")]
+    InvalidDuration {},
+
+    #[error("License {license_id} has expired")]
+    LicenseExpired { license_id: u64 },
+
+    #[error("Asset {token_id} has no fractions to distribute to")]
+    NoHolders { token_id: u64 },
+
+    #[error("Asset {token_id} has open disputes")]
+    AssetDisputed { token_id: u64 },
+
+    #[error("Dispute {dispute_id} has already been resolved")]
+    DisputeResolved { dispute_id: u64 },
+
+    #[error("Asset {token_id} is already registered as a derivative")]
+    AlreadyDerivative { token_id: u64 },
+
+    #[error("Parents must be distinct existing assets with a combined share of at most 10000 basis points")]
+    InvalidParents {},
+
+    #[error("Linking asset {token_id} to these parents would create a cycle")]
+    LineageCycle { token_id: u64 },
+
+    #[error("Asset {token_id} already has derivatives and cannot become one")]
+    HasDerivatives { token_id: u64 },
+
+    #[error("Lineage cannot be deeper than {max_depth} generations")]
+    LineageTooDeep { max_depth: u32 },
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Timestamp, Uint128};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -8,11 +8,32 @@ pub struct InstantiateMsg {
     pub subunit: String,
     pub precision: u8,
     pub initial_amount: Uint128,
+    pub payment_denom: String,
+    pub dispute_bond: Uint128,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     CreateAsset { total_supply: Uint128, price: Uint128, uri: String, asset_type: AssetType },
+    /// Grant a license on an asset for `duration` seconds; only the asset owner may grant
+    GrantLicense { token_id: u64, licensee: String, terms_uri: String, royalty_bps: u16, duration: u64 },
+    /// Pay the attached funds as royalties under a license, split across the asset's fraction holders
+    PayRoyalty { license_id: u64 },
+    /// Distribute the attached funds across the asset's fraction holders, e.g. income from off-chain deals
+    DepositRevenue { token_id: u64 },
+    /// Withdraw the royalties and revenue accrued to the sender's fractions
+    ClaimRoyalties { token_id: u64 },
+    TransferFraction { token_id: u64, to: String, amount: Uint128 },
+    /// Register an asset as a derivative work; every income it receives shares `share_bps` with each parent.
+    /// An asset other works already derive from cannot become a derivative itself
+    RegisterDerivative { token_id: u64, parents: Vec<LinkedAsset> },
+    SetArbiter { arbiter: String, enabled: bool },
+    /// Challenge the ownership claim of an asset; the configured dispute bond must be attached
+    FileDispute { token_id: u64, evidence_uri: String },
+    ResolveDispute { dispute_id: u64, upheld: bool },
     MintSmartToken { to: String, amount: Uint128 },
     TransferSmartToken { to: String, amount: Uint128 },
 }
@@ -22,7 +43,130 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
 
     #[returns(String)]
-    TokenURI { token_id: u64 }
+    TokenURI { token_id: u64 },
+    #[returns(AssetInfoResponse)]
+    AssetInfo { token_id: u64 },
+    #[returns(AssetsResponse)]
+    AssetsByOwner { owner: String, start_after: Option<u64>, limit: Option<u32> },
+    #[returns(AssetsResponse)]
+    AllAssets { start_after: Option<u64>, limit: Option<u32> },
+    /// Id the next created asset will get
+    #[returns(u64)]
+    NextTokenId {},
+    #[returns(Uint128)]
+    FractionalOwnership { token_id: u64, owner: String },
+    #[returns(AssetHoldersResponse)]
+    AssetHolders { token_id: u64, start_after: Option<String>, limit: Option<u32> },
+    #[returns(AssetSummaryResponse)]
+    AssetSummary { token_id: u64 },
+    #[returns(LineageResponse)]
+    Lineage { token_id: u64 },
+    #[returns(DerivativesResponse)]
+    Derivatives { token_id: u64, start_after: Option<u64>, limit: Option<u32> },
+    #[returns(DisputeResponse)]
+    Dispute { dispute_id: u64 },
+    #[returns(DisputesResponse)]
+    AssetDisputes { token_id: u64, start_after: Option<u64>, limit: Option<u32> },
+    #[returns(LicenseResponse)]
+    License { license_id: u64 },
+    #[returns(Uint128)]
+    PendingRoyalties { token_id: u64, holder: String },
+}
+
+#[cw_serde]
+pub struct AssetInfoResponse {
+    pub token_id: u64,
+    pub owner: String,
+    pub total_supply: Uint128,
+    pub remaining_supply: Uint128,
+    pub price: Uint128,
+    pub uri: String,
+    pub asset_type: AssetType,
+}
+
+#[cw_serde]
+pub struct AssetsResponse {
+    pub assets: Vec<AssetInfoResponse>,
+}
+
+#[cw_serde]
+pub struct LinkedAsset {
+    pub token_id: u64,
+    /// Share of the derivative's income flowing to the parent, in basis points
+    pub share_bps: u16,
+}
+
+#[cw_serde]
+pub struct LineageResponse {
+    pub token_id: u64,
+    /// Generations up to the most distant ancestor
+    pub depth: u32,
+    pub parents: Vec<LinkedAsset>,
+}
+
+#[cw_serde]
+pub struct DerivativesResponse {
+    pub token_id: u64,
+    pub derivatives: Vec<LinkedAsset>,
+}
+
+#[cw_serde]
+pub struct HolderBalance {
+    pub holder: String,
+    pub balance: Uint128,
+}
+
+#[cw_serde]
+pub struct AssetHoldersResponse {
+    pub token_id: u64,
+    pub holders: Vec<HolderBalance>,
+}
+
+#[cw_serde]
+pub struct AssetSummaryResponse {
+    pub token_id: u64,
+    pub total_supply: Uint128,
+    pub held_supply: Uint128,
+    pub holder_count: u64,
+    pub average_holding: Uint128,
+    pub open_disputes: u32,
+}
+
+#[cw_serde]
+pub enum DisputeStatus {
+    Open,
+    Upheld,
+    Rejected,
+}
+
+#[cw_serde]
+pub struct DisputeResponse {
+    pub dispute_id: u64,
+    pub token_id: u64,
+    pub challenger: String,
+    pub evidence_uri: String,
+    pub bond: Uint128,
+    pub status: DisputeStatus,
+    pub filed_at: Timestamp,
+    pub resolved_by: Option<String>,
+}
+
+#[cw_serde]
+pub struct DisputesResponse {
+    pub disputes: Vec<DisputeResponse>,
+}
+
+#[cw_serde]
+pub struct LicenseResponse {
+    pub license_id: u64,
+    pub token_id: u64,
+    pub licensee: String,
+    pub terms_uri: String,
+    pub royalty_bps: u16,
+    pub start: Timestamp,
+    pub expires: Timestamp,
+    pub royalties_paid: Uint128,
+    pub active: bool,
 }
 
 #[cw_serde]
@@ -1,8 +1,17 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map};
 use serde::{Deserialize, Serialize};
 
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,
+    /// Denom in which royalties are paid
+    pub payment_denom: String,
+    /// Deposit a challenger must bond to file a dispute
+    pub dispute_bond: Uint128,
+}
+
 #[cw_serde]
 pub struct TokenizedAsset {
     pub owner: Addr,
@@ -20,4 +29,75 @@ pub enum AssetType {
 
 pub const ASSETS: Map<u64, TokenizedAsset> = Map::new("assets");
 pub const NEXT_TOKEN_ID: Item<u64> = Item::new("next_token_id");
-pub const FRACTIONAL_BALANCES: Map<(Addr, u64), Uint128> = Map::new("fractional_balances");
\ No newline at end of file
+/// Assets owned by an address, keyed by (owner, token_id) so an owner's assets can be paged through
+pub const OWNER_ASSETS: Map<(&Addr, u64), bool> = Map::new("owner_assets");
+pub use fractions::{HolderStats, FRACTIONAL_BALANCES, HOLDER_STATS};
+
+/// Time-bound right to use an IP asset in exchange for royalties
+#[cw_serde]
+pub struct License {
+    pub token_id: u64,
+    pub licensee: Addr,
+    pub terms_uri: String,
+    /// Agreed royalty on the licensee's revenue, in basis points
+    pub royalty_bps: u16,
+    pub start: Timestamp,
+    pub expires: Timestamp,
+    pub royalties_paid: Uint128,
+}
+
+/// Royalty and revenue position of a single fraction holder
+#[cw_serde]
+#[derive(Default)]
+pub struct RoyaltyAccount {
+    /// Value of the asset's royalty index when the account was last settled
+    pub index: Decimal,
+    /// Settled royalties not claimed yet
+    pub pending: Uint128,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const LICENSES: Map<u64, License> = Map::new("licenses");
+pub const NEXT_LICENSE_ID: Item<u64> = Item::new("next_license_id");
+/// Cumulative royalties and revenue paid per fraction of an asset
+pub const ROYALTY_INDEX: Map<u64, Decimal> = Map::new("royalty_index");
+pub const ROYALTY_ACCOUNTS: Map<(u64, Addr), RoyaltyAccount> = Map::new("royalty_accounts");
+/// Layout of ROYALTY_ACCOUNTS up to version 0.1.0, keyed by (holder, token_id)
+pub const LEGACY_ROYALTY_ACCOUNTS: Map<(Addr, u64), RoyaltyAccount> = Map::new("royalty_accounts");
+
+#[cw_serde]
+pub enum DisputeStatus {
+    Open,
+    /// The challenge was found valid; the bond is returned and the challenger takes over the asset
+    Upheld,
+    /// The challenge was found invalid; the bond is forfeited to the asset owner
+    Rejected,
+}
+
+/// Challenge against the ownership claim of an asset, backed by a bond
+#[cw_serde]
+pub struct Dispute {
+    pub token_id: u64,
+    pub challenger: Addr,
+    pub evidence_uri: String,
+    pub bond: Uint128,
+    pub status: DisputeStatus,
+    pub filed_at: Timestamp,
+    pub resolved_by: Option<Addr>,
+}
+
+/// Addresses allowed to resolve disputes
+pub const ARBITERS: Map<&Addr, bool> = Map::new("arbiters");
+pub const DISPUTES: Map<u64, Dispute> = Map::new("disputes");
+pub const NEXT_DISPUTE_ID: Item<u64> = Item::new("next_dispute_id");
+/// Disputes filed against an asset, keyed by (token_id, dispute_id)
+pub const ASSET_DISPUTES: Map<(u64, u64), bool> = Map::new("asset_disputes");
+/// Number of unresolved disputes per asset; transfers and licensing are blocked while non-zero
+pub const OPEN_DISPUTES: Map<u64, u32> = Map::new("open_disputes");
+
+/// Upstream royalty share of a derivative work in basis points, keyed by (derivative token_id, parent token_id)
+pub const PARENTS: Map<(u64, u64), u16> = Map::new("parents");
+/// Same links as `PARENTS`, keyed by (parent token_id, derivative token_id) to list an asset's derivatives
+pub const DERIVATIVES: Map<(u64, u64), u16> = Map::new("derivatives");
+/// Generations between a derivative and its most distant ancestor, set once at registration; originals have none
+pub const LINEAGE_DEPTHS: Map<u64, u32> = Map::new("lineage_depths");
@@ -1,12 +1,16 @@
-use crate::msg::AmountResponse;
+use crate::msg::{AllocationResponse, AmountResponse, SnapshotAllocationResponse};
 use coreum_wasm_sdk::assetft;
 use coreum_wasm_sdk::core::{CoreumMsg, CoreumQueries};
 use cosmwasm_std::{entry_point, to_binary, Binary, Deps, QueryRequest, StdResult};
-use cosmwasm_std::{Coin, DepsMut, Env, MessageInfo, Response, StdError, Uint128};
+use cosmwasm_std::{
+    Addr, Coin, Decimal, DepsMut, Env, MessageInfo, QuerierWrapper, Response, StdError, Uint128,
+};
 use cw2::set_contract_version;
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map};
+use reputation::ReputationQuerier;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
 use std::ops::{Add, Sub};
 use thiserror::Error;
 // version info for migration info
@@ -26,9 +30,50 @@ pub struct State {
     pub owner: String,
     pub denom: String,
     pub airdrop_amount: Uint128,
+    // airdrop supply not handed out yet, it is minted straight to claimants when they claim
     pub minted_for_airdrop: Uint128,
 }
 pub const STATE: Item<State> = Item::new("state");
+// Claimants with at least `min_score` reputation get the airdrop amount scaled by `multiplier`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MultiplierTier {
+    pub min_score: u64,
+    pub multiplier: Decimal,
+}
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReputationCurve {
+    pub reputation_contract: Addr,
+    // sorted by ascending min_score, claimants below the first tier get the plain amount
+    pub tiers: Vec<MultiplierTier>,
+}
+pub const REPUTATION_CURVE: Item<ReputationCurve> = Item::new("reputation_curve");
+// Amount handed out to each ReceiveAirdrop claimant, an address can only claim once
+pub const CLAIMED: Map<&Addr, Uint128> = Map::new("claimed");
+// Upper bound on the holder entries accepted by a single RegisterAllocations call
+pub const MAX_ALLOCATION_BATCH: usize = 100;
+// Airdrop of `pool` split pro rata over the holder balances uploaded for `height`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Snapshot {
+    pub height: u64,
+    pub pool: Uint128,
+    pub total_balance: Uint128,
+    pub holders: u64,
+    pub claimed: Uint128,
+    // no more entries are accepted once finalized, claims are only possible afterwards
+    pub finalized: bool,
+}
+pub const SNAPSHOT: Item<Snapshot> = Item::new("snapshot");
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HolderBalance {
+    pub address: String,
+    pub balance: Uint128,
+}
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SnapshotHolding {
+    pub balance: Uint128,
+    pub claimed: bool,
+}
+pub const SNAPSHOT_HOLDINGS: Map<&Addr, SnapshotHolding> = Map::new("snapshot_holdings");
 #[derive(Error, Debug)]
 pub enum ContractError {
     #[error("{0}")]
@@ -43,8 +88,19 @@ pub enum ContractError {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
+    // adds to the airdrop supply, the tokens are only minted once claimed
     MintForAirdrop { amount: u128 },
     ReceiveAirdrop {},
+    SetReputationCurve {
+        reputation_contract: String,
+        tiers: Vec<MultiplierTier>,
+    },
+    RemoveReputationCurve {},
+    // reserves `pool` out of the minted airdrop supply for a holder snapshot taken at `height`
+    StartSnapshot { height: u64, pool: Uint128 },
+    RegisterAllocations { entries: Vec<HolderBalance> },
+    FinalizeSnapshot {},
+    ClaimSnapshot {},
 }
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
@@ -56,6 +112,15 @@ pub fn execute(
     match msg {
         ExecuteMsg::MintForAirdrop { amount } => mint_for_airdrop(deps, info, amount),
         ExecuteMsg::ReceiveAirdrop {} => receive_airdrop(deps, info),
+        ExecuteMsg::SetReputationCurve {
+            reputation_contract,
+            tiers,
+        } => set_reputation_curve(deps, info, reputation_contract, tiers),
+        ExecuteMsg::RemoveReputationCurve {} => remove_reputation_curve(deps, info),
+        ExecuteMsg::StartSnapshot { height, pool } => start_snapshot(deps, info, height, pool),
+        ExecuteMsg::RegisterAllocations { entries } => register_allocations(deps, info, entries),
+        ExecuteMsg::FinalizeSnapshot {} => finalize_snapshot(deps, info),
+        ExecuteMsg::ClaimSnapshot {} => claim_snapshot(deps, info),
     }
 }
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -63,12 +128,20 @@ pub fn execute(
 pub enum QueryMsg {
     Token {},
     MintedForAirdrop {},
+    ReputationCurve {},
+    Allocation { address: String },
+    Snapshot {},
+    SnapshotAllocation { address: String },
 }
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps<CoreumQueries>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Token {} => token(deps),
         QueryMsg::MintedForAirdrop {} => minted_for_airdrop(deps),
+        QueryMsg::ReputationCurve {} => to_binary(&REPUTATION_CURVE.may_load(deps.storage)?),
+        QueryMsg::Allocation { address } => allocation(deps, address),
+        QueryMsg::Snapshot {} => to_binary(&SNAPSHOT.may_load(deps.storage)?),
+        QueryMsg::SnapshotAllocation { address } => snapshot_allocation(deps, address),
     }
 }
 // ********** Instantiate **********
@@ -80,15 +153,20 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> Result<Response<CoreumMsg>, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    // The initial amount is airdrop supply. Sends are charged the 10% commission on top, so
+    // rather than holding the supply and sending it, the contract mints to each claimant as
+    // the issuer, who is exempt, and claimants receive exactly their allocation.
     let issue_msg = CoreumMsg::AssetFT(assetft::Msg::Issue {
         symbol: msg.symbol,
         subunit: msg.subunit.clone(),
         precision: msg.precision,
-        initial_amount: msg.initial_amount,
+        initial_amount: Uint128::zero(),
         description: None,
-        features: Some(vec![0]), // 0 - minting
-        burn_rate: Some("0".into()),
-        send_commission_rate: Some("0.1".into()), // 10% commission for sending
+        features: Some(vec![assetft::MINTING]),
+        burn_rate: "0".into(),
+        send_commission_rate: "0.1".into(), // 10% commission for sending
+        uri: None,
+        uri_hash: None,
     });
     let denom = format!("{}-{}", msg.subunit, env.contract.address).to_lowercase();
     let state = State {
@@ -113,38 +191,273 @@ fn mint_for_airdrop(
     if info.sender != state.owner {
         return Err(ContractError::Unauthorized {});
     }
-    let msg = CoreumMsg::AssetFT(assetft::Msg::Mint {
-        coin: Coin::new(amount, state.denom.clone()),
-    });
     state.minted_for_airdrop = state.minted_for_airdrop.add(Uint128::new(amount));
     STATE.save(deps.storage, &state)?;
     Ok(Response::new()
         .add_attribute("method", "mint_for_airdrop")
         .add_attribute("denom", state.denom)
-        .add_attribute("amount", amount.to_string())
-        .add_message(msg))
+        .add_attribute("amount", amount.to_string()))
+}
+// Minting to the recipient skips the send commission a transfer would be charged
+fn mint_to(denom: &str, amount: Uint128, recipient: String) -> CoreumMsg {
+    CoreumMsg::AssetFT(assetft::Msg::Mint {
+        coin: Coin {
+            amount,
+            denom: denom.to_string(),
+        },
+        recipient: Some(recipient),
+    })
 }
 fn receive_airdrop(deps: DepsMut, info: MessageInfo) -> Result<Response<CoreumMsg>, ContractError> {
     let mut state = STATE.load(deps.storage)?;
-    if state.minted_for_airdrop < state.airdrop_amount {
+    if CLAIMED.has(deps.storage, &info.sender) {
+        return Err(ContractError::CustomError {
+            val: "airdrop already claimed".into(),
+        });
+    }
+    let curve = REPUTATION_CURVE.may_load(deps.storage)?;
+    let (_, _, amount) = allocation_for(
+        &deps.querier,
+        curve.as_ref(),
+        state.airdrop_amount,
+        info.sender.as_str(),
+    )?;
+    if state.minted_for_airdrop < amount {
         return Err(ContractError::CustomError {
             val: "not enough minted".into(),
         });
     }
-    let send_msg = cosmwasm_std::BankMsg::Send {
-        to_address: info.sender.into(),
-        amount: vec![Coin {
-            amount: state.airdrop_amount,
-            denom: state.denom.clone(),
-        }],
-    };
-    state.minted_for_airdrop = state.minted_for_airdrop.sub(state.airdrop_amount);
+    CLAIMED.save(deps.storage, &info.sender, &amount)?;
+    let mint_msg = mint_to(&state.denom, amount, info.sender.into());
+    state.minted_for_airdrop = state.minted_for_airdrop.sub(amount);
     STATE.save(deps.storage, &state)?;
     Ok(Response::new()
         .add_attribute("method", "receive_airdrop")
         .add_attribute("denom", state.denom)
-        .add_attribute("amount", state.airdrop_amount.to_string())
-        .add_message(send_msg))
+        .add_attribute("amount", amount.to_string())
+        .add_message(mint_msg))
+}
+fn set_reputation_curve(
+    deps: DepsMut,
+    info: MessageInfo,
+    reputation_contract: String,
+    tiers: Vec<MultiplierTier>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if tiers.iter().any(|tier| tier.multiplier.is_zero()) {
+        return Err(ContractError::InvalidInput(
+            "multiplier must be positive".into(),
+        ));
+    }
+    if tiers
+        .windows(2)
+        .any(|pair| pair[0].min_score >= pair[1].min_score)
+    {
+        return Err(ContractError::InvalidInput(
+            "tiers must be sorted by ascending min_score".into(),
+        ));
+    }
+    let curve = ReputationCurve {
+        reputation_contract: deps.api.addr_validate(&reputation_contract)?,
+        tiers,
+    };
+    REPUTATION_CURVE.save(deps.storage, &curve)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_reputation_curve")
+        .add_attribute("reputation_contract", curve.reputation_contract)
+        .add_attribute("tiers", curve.tiers.len().to_string()))
+}
+fn remove_reputation_curve(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    REPUTATION_CURVE.remove(deps.storage);
+    Ok(Response::new().add_attribute("method", "remove_reputation_curve"))
+}
+fn start_snapshot(
+    deps: DepsMut,
+    info: MessageInfo,
+    height: u64,
+    pool: Uint128,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if SNAPSHOT.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::InvalidInput(
+            "snapshot already started".into(),
+        ));
+    }
+    if pool.is_zero() {
+        return Err(ContractError::InvalidInput("pool must be positive".into()));
+    }
+    if state.minted_for_airdrop < pool {
+        return Err(ContractError::CustomError {
+            val: "not enough minted".into(),
+        });
+    }
+    state.minted_for_airdrop = state.minted_for_airdrop.sub(pool);
+    STATE.save(deps.storage, &state)?;
+    SNAPSHOT.save(
+        deps.storage,
+        &Snapshot {
+            height,
+            pool,
+            total_balance: Uint128::zero(),
+            holders: 0,
+            claimed: Uint128::zero(),
+            finalized: false,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attribute("method", "start_snapshot")
+        .add_attribute("height", height.to_string())
+        .add_attribute("pool", pool))
+}
+// Registering an address twice replaces its earlier balance
+fn register_allocations(
+    deps: DepsMut,
+    info: MessageInfo,
+    entries: Vec<HolderBalance>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut snapshot = SNAPSHOT
+        .may_load(deps.storage)?
+        .ok_or_else(|| ContractError::InvalidInput("no snapshot started".into()))?;
+    if snapshot.finalized {
+        return Err(ContractError::InvalidInput("snapshot is finalized".into()));
+    }
+    if entries.is_empty() || entries.len() > MAX_ALLOCATION_BATCH {
+        return Err(ContractError::InvalidInput(format!(
+            "batch must hold between 1 and {} entries",
+            MAX_ALLOCATION_BATCH
+        )));
+    }
+    for entry in entries.iter() {
+        let address = deps.api.addr_validate(&entry.address)?;
+        match SNAPSHOT_HOLDINGS.may_load(deps.storage, &address)? {
+            Some(previous) => {
+                snapshot.total_balance = snapshot.total_balance.sub(previous.balance)
+            }
+            None => snapshot.holders += 1,
+        }
+        snapshot.total_balance = snapshot.total_balance.checked_add(entry.balance)?;
+        SNAPSHOT_HOLDINGS.save(
+            deps.storage,
+            &address,
+            &SnapshotHolding {
+                balance: entry.balance,
+                claimed: false,
+            },
+        )?;
+    }
+    SNAPSHOT.save(deps.storage, &snapshot)?;
+    Ok(Response::new()
+        .add_attribute("method", "register_allocations")
+        .add_attribute("entries", entries.len().to_string())
+        .add_attribute("total_balance", snapshot.total_balance))
+}
+fn finalize_snapshot(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut snapshot = SNAPSHOT
+        .may_load(deps.storage)?
+        .ok_or_else(|| ContractError::InvalidInput("no snapshot started".into()))?;
+    if snapshot.finalized {
+        return Err(ContractError::InvalidInput("snapshot is finalized".into()));
+    }
+    if snapshot.total_balance.is_zero() {
+        return Err(ContractError::InvalidInput("no balances registered".into()));
+    }
+    snapshot.finalized = true;
+    SNAPSHOT.save(deps.storage, &snapshot)?;
+    Ok(Response::new()
+        .add_attribute("method", "finalize_snapshot")
+        .add_attribute("holders", snapshot.holders.to_string())
+        .add_attribute("total_balance", snapshot.total_balance))
+}
+fn claim_snapshot(deps: DepsMut, info: MessageInfo) -> Result<Response<CoreumMsg>, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    let mut snapshot = SNAPSHOT
+        .may_load(deps.storage)?
+        .ok_or_else(|| ContractError::InvalidInput("no snapshot started".into()))?;
+    if !snapshot.finalized {
+        return Err(ContractError::InvalidInput(
+            "snapshot is not finalized".into(),
+        ));
+    }
+    let mut holding = SNAPSHOT_HOLDINGS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or_else(|| ContractError::CustomError {
+            val: "not part of the snapshot".into(),
+        })?;
+    if holding.claimed {
+        return Err(ContractError::CustomError {
+            val: "already claimed".into(),
+        });
+    }
+    let amount = snapshot_entitlement(&snapshot, holding.balance)?;
+    holding.claimed = true;
+    SNAPSHOT_HOLDINGS.save(deps.storage, &info.sender, &holding)?;
+    snapshot.claimed = snapshot.claimed.add(amount);
+    SNAPSHOT.save(deps.storage, &snapshot)?;
+    let mut res = Response::new()
+        .add_attribute("method", "claim_snapshot")
+        .add_attribute("denom", state.denom.clone())
+        .add_attribute("amount", amount.to_string());
+    if !amount.is_zero() {
+        res = res.add_message(mint_to(&state.denom, amount, info.sender.into()));
+    }
+    Ok(res)
+}
+// Share of the pool proportional to the holder's balance, rounded down
+fn snapshot_entitlement(snapshot: &Snapshot, balance: Uint128) -> StdResult<Uint128> {
+    if snapshot.total_balance.is_zero() {
+        return Ok(Uint128::zero());
+    }
+    Ok(snapshot
+        .pool
+        .full_mul(balance)
+        .checked_div(snapshot.total_balance.into())?
+        .try_into()?)
+}
+// Returns the claimant's reputation, the multiplier of the highest tier reached and the scaled amount
+fn allocation_for<C: cosmwasm_std::CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    curve: Option<&ReputationCurve>,
+    airdrop_amount: Uint128,
+    claimant: &str,
+) -> StdResult<(u64, Decimal, Uint128)> {
+    let curve = match curve {
+        Some(curve) => curve,
+        None => return Ok((0, Decimal::one(), airdrop_amount)),
+    };
+    let reputation =
+        ReputationQuerier::new(querier, curve.reputation_contract.clone()).reputation(claimant)?;
+    let multiplier = curve
+        .tiers
+        .iter()
+        .rev()
+        .find(|tier| reputation >= tier.min_score)
+        .map(|tier| tier.multiplier)
+        .unwrap_or_else(Decimal::one);
+    Ok((reputation, multiplier, airdrop_amount * multiplier))
 }
 // ********** Queries **********
 fn token(deps: Deps<CoreumQueries>) -> StdResult<Binary> {
@@ -154,6 +467,37 @@ fn token(deps: Deps<CoreumQueries>) -> StdResult<Binary> {
     let res: assetft::TokenResponse = deps.querier.query(&request)?;
     to_binary(&res)
 }
+fn allocation(deps: Deps<CoreumQueries>, address: String) -> StdResult<Binary> {
+    let state = STATE.load(deps.storage)?;
+    let address = deps.api.addr_validate(&address)?;
+    let curve = REPUTATION_CURVE.may_load(deps.storage)?;
+    let (reputation, multiplier, amount) = allocation_for(
+        &deps.querier,
+        curve.as_ref(),
+        state.airdrop_amount,
+        address.as_str(),
+    )?;
+    to_binary(&AllocationResponse {
+        reputation,
+        multiplier,
+        amount,
+    })
+}
+fn snapshot_allocation(deps: Deps<CoreumQueries>, address: String) -> StdResult<Binary> {
+    let address = deps.api.addr_validate(&address)?;
+    let snapshot = SNAPSHOT.load(deps.storage)?;
+    let holding = SNAPSHOT_HOLDINGS
+        .may_load(deps.storage, &address)?
+        .unwrap_or(SnapshotHolding {
+            balance: Uint128::zero(),
+            claimed: false,
+        });
+    to_binary(&SnapshotAllocationResponse {
+        balance: holding.balance,
+        amount: snapshot_entitlement(&snapshot, holding.balance)?,
+        claimed: holding.claimed,
+    })
+}
 fn minted_for_airdrop(deps: Deps<CoreumQueries>) -> StdResult<Binary> {
     let state = STATE.load(deps.storage)?;
     let res = AmountResponse {
@@ -166,7 +510,10 @@ fn minted_for_airdrop(deps: Deps<CoreumQueries>) -> StdResult<Binary> {
 mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{attr, from_binary, Addr};
+    use cosmwasm_std::{
+        attr, from_binary, Addr, ContractResult, SystemError, SystemResult, WasmQuery,
+    };
+    use reputation::{ReputationQueryMsg, ReputationRaw};
 
     #[test]
     fn proper_initialization() {
@@ -259,9 +606,22 @@ mod tests {
             attr("denom", "test-0x0000000000000000000000000000000000000000"),
             attr("amount", "100")
         ]);
+        // minted to the recipient so the send commission doesn't cut into the amount
+        assert_eq!(res.messages, vec![cosmwasm_std::SubMsg::new(mint_to(
+            "test-0x0000000000000000000000000000000000000000",
+            Uint128::new(100),
+            "recipient".to_string(),
+        ))]);
 
         let state = STATE.load(&deps.storage).unwrap();
         assert_eq!(state.minted_for_airdrop, Uint128::new(1400));
+
+        // each address claims once
+        match execute(deps.as_mut(), mock_env(), mock_info("recipient", &[]), ExecuteMsg::ReceiveAirdrop {}) {
+            Err(ContractError::CustomError { .. }) => {}
+            _ => panic!("Must return already claimed error"),
+        }
+        assert_eq!(CLAIMED.load(&deps.storage, &Addr::unchecked("recipient")).unwrap(), Uint128::new(100));
     }
 
     #[test]
@@ -303,4 +663,157 @@ mod tests {
 
         assert_eq!(amount_response.amount, Uint128::new(1000));
     }
+
+    #[test]
+    fn receive_airdrop_with_reputation_curve() {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "reputation" => {
+                let ReputationQueryMsg::GetReputationRaw { user } = from_binary(msg).unwrap();
+                let reputation = if user == "trusted" { 80 } else { 5 };
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&ReputationRaw {
+                        user: Addr::unchecked(user),
+                        reputation,
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => SystemResult::Err(SystemError::NoSuchContract {
+                addr: "unknown".to_string(),
+            }),
+        });
+        let msg = InstantiateMsg {
+            symbol: "TEST".to_string(),
+            subunit: "test".to_string(),
+            precision: 6,
+            initial_amount: Uint128::new(1000),
+            airdrop_amount: Uint128::new(100),
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let unsorted = ExecuteMsg::SetReputationCurve {
+            reputation_contract: "reputation".to_string(),
+            tiers: vec![
+                MultiplierTier { min_score: 50, multiplier: Decimal::percent(200) },
+                MultiplierTier { min_score: 10, multiplier: Decimal::percent(150) },
+            ],
+        };
+        match execute(deps.as_mut(), mock_env(), info.clone(), unsorted) {
+            Err(ContractError::InvalidInput(_)) => {}
+            _ => panic!("Must return invalid input error"),
+        }
+        let set_curve = ExecuteMsg::SetReputationCurve {
+            reputation_contract: "reputation".to_string(),
+            tiers: vec![
+                MultiplierTier { min_score: 10, multiplier: Decimal::percent(150) },
+                MultiplierTier { min_score: 50, multiplier: Decimal::percent(250) },
+            ],
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("not_creator", &[]), set_curve.clone()) {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        execute(deps.as_mut(), mock_env(), info, set_curve).unwrap();
+
+        let bin = query(deps.as_ref(), mock_env(), QueryMsg::Allocation { address: "trusted".to_string() }).unwrap();
+        let allocation: AllocationResponse = from_binary(&bin).unwrap();
+        assert_eq!(allocation, AllocationResponse {
+            reputation: 80,
+            multiplier: Decimal::percent(250),
+            amount: Uint128::new(250),
+        });
+
+        let receive_msg = ExecuteMsg::ReceiveAirdrop {};
+        let res = execute(deps.as_mut(), mock_env(), mock_info("trusted", &[]), receive_msg.clone()).unwrap();
+        assert_eq!(res.attributes[2], attr("amount", "250"));
+        // below the first tier the plain amount applies
+        let res = execute(deps.as_mut(), mock_env(), mock_info("newcomer", &[]), receive_msg).unwrap();
+        assert_eq!(res.attributes[2], attr("amount", "100"));
+
+        let state = STATE.load(&deps.storage).unwrap();
+        assert_eq!(state.minted_for_airdrop, Uint128::new(650));
+    }
+
+    #[test]
+    fn snapshot_allocations_are_proportional() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            symbol: "TEST".to_string(),
+            subunit: "test".to_string(),
+            precision: 6,
+            initial_amount: Uint128::new(1000),
+            airdrop_amount: Uint128::new(100),
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let start = ExecuteMsg::StartSnapshot { height: 42, pool: Uint128::new(600) };
+        execute(deps.as_mut(), mock_env(), info.clone(), start).unwrap();
+        let state = STATE.load(&deps.storage).unwrap();
+        assert_eq!(state.minted_for_airdrop, Uint128::new(400));
+
+        let holder = |address: &str, balance: u128| HolderBalance {
+            address: address.to_string(),
+            balance: Uint128::new(balance),
+        };
+        let register = ExecuteMsg::RegisterAllocations {
+            entries: vec![holder("alice", 100), holder("bob", 200)],
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("not_creator", &[]), register.clone()) {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        execute(deps.as_mut(), mock_env(), info.clone(), register).unwrap();
+        let oversized = ExecuteMsg::RegisterAllocations {
+            entries: (0..=MAX_ALLOCATION_BATCH).map(|i| holder(&format!("holder{}", i), 1)).collect(),
+        };
+        match execute(deps.as_mut(), mock_env(), info.clone(), oversized) {
+            Err(ContractError::InvalidInput(_)) => {}
+            _ => panic!("Must return invalid input error"),
+        }
+        // a later batch can correct an earlier entry
+        let register = ExecuteMsg::RegisterAllocations {
+            entries: vec![holder("bob", 300), holder("carol", 200)],
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), register).unwrap();
+
+        // claims only open once the snapshot is finalized
+        let claim = ExecuteMsg::ClaimSnapshot {};
+        match execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), claim.clone()) {
+            Err(ContractError::InvalidInput(_)) => {}
+            _ => panic!("Must return invalid input error"),
+        }
+        execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::FinalizeSnapshot {}).unwrap();
+        let late = ExecuteMsg::RegisterAllocations { entries: vec![holder("dave", 10)] };
+        match execute(deps.as_mut(), mock_env(), info, late) {
+            Err(ContractError::InvalidInput(_)) => {}
+            _ => panic!("Must return invalid input error"),
+        }
+
+        let bin = query(deps.as_ref(), mock_env(), QueryMsg::SnapshotAllocation { address: "bob".to_string() }).unwrap();
+        let allocation: SnapshotAllocationResponse = from_binary(&bin).unwrap();
+        assert_eq!(allocation, SnapshotAllocationResponse {
+            balance: Uint128::new(300),
+            amount: Uint128::new(300),
+            claimed: false,
+        });
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), claim.clone()).unwrap();
+        assert_eq!(res.attributes[2], attr("amount", "100"));
+        match execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), claim.clone()) {
+            Err(ContractError::CustomError { .. }) => {}
+            _ => panic!("Must return already claimed error"),
+        }
+        match execute(deps.as_mut(), mock_env(), mock_info("dave", &[]), claim) {
+            Err(ContractError::CustomError { .. }) => {}
+            _ => panic!("Must return not part of the snapshot error"),
+        }
+
+        let snapshot = SNAPSHOT.load(&deps.storage).unwrap();
+        assert_eq!(snapshot.holders, 3);
+        assert_eq!(snapshot.total_balance, Uint128::new(600));
+        assert_eq!(snapshot.claimed, Uint128::new(100));
+    }
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Decimal, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 // We define a custom struct for each query response
@@ -6,3 +6,17 @@ use serde::{Deserialize, Serialize};
 pub struct AmountResponse {
     pub amount: Uint128,
 }
+// Allocation a claimant receives once their reputation is applied
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllocationResponse {
+    pub reputation: u64,
+    pub multiplier: Decimal,
+    pub amount: Uint128,
+}
+// Pro rata share of the snapshot pool, only final once the snapshot is finalized
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SnapshotAllocationResponse {
+    pub balance: Uint128,
+    pub amount: Uint128,
+    pub claimed: bool,
+}
@@ -1,12 +1,14 @@
 use cosmwasm_schema::QueryResponses;
 use cosmwasm_std::{
-    to_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Order, QueryRequest,
+    entry_point, to_binary, Addr, BankMsg, Binary, Coin, Decimal, Deps, DepsMut, Env, MessageInfo, Order, QueryRequest,
     Response, StdError, StdResult, Storage, Uint128, WasmQuery,
 };
-use cw_storage_plus::{Item, Map};
+use cw_storage_plus::{Bound, Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::str::FromStr;
+use thiserror::Error;
 
 use crate::pagination::{PageRequest, PageResponse};
 
@@ -15,6 +17,8 @@ pub const BURNING: u32 = 1;
 pub const FREEZING: u32 = 2;
 pub const WHITELISTING: u32 = 3;
 pub const IBC: u32 = 4;
+pub const BLOCK_SMART_CONTRACTS: u32 = 5;
+pub const CLAWBACK: u32 = 6;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -60,8 +64,8 @@ pub struct TokenResponse {
 #[serde(rename_all = "snake_case")]
 pub struct BalanceResponse {
     pub balance: Uint128,
-    pub whitelisted: bool,
-    pub frozen: bool,
+    pub whitelisted: Uint128,
+    pub frozen: Uint128,
     pub locked: Uint128,
 }
 
@@ -113,11 +117,11 @@ pub enum Msg {
     },
     Freeze {
         account: String,
-        denom: String,
+        coin: Coin,
     },
     Unfreeze {
         account: String,
-        denom: String,
+        coin: Coin,
     },
     GloballyFreeze {
         denom: String,
@@ -127,7 +131,11 @@ pub enum Msg {
     },
     SetWhitelistedLimit {
         account: String,
-        denom: String,
+        coin: Coin,
+    },
+    Clawback {
+        account: String,
+        coin: Coin,
     },
     UpgradeTokenV1 {
         denom: String,
@@ -171,38 +179,166 @@ pub enum Query {
     WhitelistedBalance { account: String, denom: String },
 }
 
+// Entry point messages so the ledger can be deployed as a stand-in for the assetft module
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct InstantiateMsg {
+    pub issue_fee: Coin,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    // same payload a contract would wrap in CoreumMsg::AssetFT
+    AssetFT(Msg),
+    // bank send of an issued denom, which is where the restrictions apply
+    Transfer { recipient: String, coin: Coin },
+    // let another account run the issuer's privileged operations on a token
+    AddAdmin { denom: String, admin: String },
+    RemoveAdmin { denom: String, admin: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    AssetFT(Query),
+}
+
 // Custom error type for transfer restriction errors
-#[derive(Debug)]
+#[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Transfer restricted: {reason}")]
     TransferRestricted { reason: String },
+
+    #[error("Unauthorized: {reason}")]
     Unauthorized { reason: String },
+
+    #[error("Invalid request: {reason}")]
     InvalidRequest { reason: String },
+
+    #[error("Token not found")]
     TokenNotFound,
 }
 
 // Storage keys
 const BALANCES: Map<(&str, &str), Uint128> = Map::new("balances");
-const FROZEN_ACCOUNTS: Map<(&str, &str), bool> = Map::new("frozen_accounts");
-const WHITELISTED_ACCOUNTS: Map<(&str, &str), bool> = Map::new("whitelisted_accounts");
+// (account, denom) -> amount locked by the issuer, or the most the account may hold
+const FROZEN_BALANCES: Map<(&str, &str), Uint128> = Map::new("frozen_balances");
+const WHITELISTED_BALANCES: Map<(&str, &str), Uint128> = Map::new("whitelisted_balances");
 const TOKENS: Map<&str, Token> = Map::new("tokens");
 const GLOBAL_FREEZE: Item<HashMap<String, bool>> = Item::new("global_freeze");
+const PARAMS: Item<Params> = Item::new("params");
+// (denom, account) pairs allowed to act on behalf of the token's issuer
+const ADMINS: Map<(&str, &str), bool> = Map::new("admins");
+
+const DEFAULT_LIMIT: u64 = 10;
+const MAX_LIMIT: u64 = 100;
+
+// Entry points
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    PARAMS.save(deps.storage, &Params { issue_fee: msg.issue_fee })?;
+    GLOBAL_FREEZE.save(deps.storage, &HashMap::new())?;
+
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::AssetFT(msg) => match msg {
+            Msg::Issue {
+                symbol,
+                subunit,
+                precision,
+                initial_amount,
+                description,
+                features,
+                burn_rate,
+                send_commission_rate,
+            } => issue(
+                deps,
+                info,
+                symbol,
+                subunit,
+                precision,
+                initial_amount,
+                description,
+                features,
+                burn_rate,
+                send_commission_rate,
+            ),
+            Msg::Mint { denom, amount } => mint(deps, info, denom, amount),
+            Msg::Burn { denom, amount } => burn(deps, info, denom, amount),
+            Msg::Freeze { account, coin } => freeze(deps, env, info, account, coin),
+            Msg::Unfreeze { account, coin } => unfreeze(deps, env, info, account, coin),
+            Msg::GloballyFreeze { denom } => globally_freeze(deps, env, info, denom),
+            Msg::GloballyUnfreeze { denom } => globally_unfreeze(deps, env, info, denom),
+            Msg::SetWhitelistedLimit { account, coin } => {
+                set_whitelisted_limit(deps, env, info, account, coin)
+            }
+            Msg::Clawback { account, coin } => clawback(deps, info, account, coin),
+            Msg::UpgradeTokenV1 { denom, ibc_enabled } => {
+                upgrade_token_v1(deps, info, denom, ibc_enabled)
+            }
+        },
+        ExecuteMsg::Transfer { recipient, coin } => transfer(deps, info, recipient, coin),
+        ExecuteMsg::AddAdmin { denom, admin } => add_admin(deps, info, denom, admin),
+        ExecuteMsg::RemoveAdmin { denom, admin } => remove_admin(deps, info, denom, admin),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::AssetFT(query) => match query {
+            Query::Params {} => to_binary(&query_params(deps)?),
+            Query::Tokens { pagination, issuer } => {
+                to_binary(&query_tokens(deps, pagination, issuer)?)
+            }
+            Query::Token { denom } => to_binary(&query_token(deps, denom)?),
+            Query::Balance { account, denom } => to_binary(&query_balance(deps, account, denom)?),
+            Query::FrozenBalances { pagination, account } => {
+                to_binary(&query_frozen_balances(deps, pagination, account)?)
+            }
+            Query::FrozenBalance { account, denom } => {
+                to_binary(&query_frozen_balance(deps, account, denom)?)
+            }
+            Query::WhitelistedBalances { pagination, account } => {
+                to_binary(&query_whitelisted_balances(deps, pagination, account)?)
+            }
+            Query::WhitelistedBalance { account, denom } => {
+                to_binary(&query_whitelisted_balance(deps, account, denom)?)
+            }
+        },
+    }
+}
 
 // Implementing restrictions checks
 
-// Check if an account is frozen
-pub fn is_frozen(store: &dyn Storage, account: &str, denom: &str) -> bool {
-    FROZEN_ACCOUNTS
-        .may_load(store, (account, denom))
-        .unwrap_or_default()
-        .unwrap_or(false)
+// Amount of an account's balance that is frozen
+pub fn frozen_balance(store: &dyn Storage, account: &str, denom: &str) -> StdResult<Uint128> {
+    Ok(FROZEN_BALANCES.may_load(store, (account, denom))?.unwrap_or_default())
 }
 
-// Check if an account is whitelisted
-pub fn is_whitelisted(store: &dyn Storage, account: &str, denom: &str) -> bool {
-    WHITELISTED_ACCOUNTS
-        .may_load(store, (account, denom))
-        .unwrap_or_default()
-        .unwrap_or(false)
+// Most an account may hold of a whitelisting token
+pub fn whitelisted_balance(store: &dyn Storage, account: &str, denom: &str) -> StdResult<Uint128> {
+    Ok(WHITELISTED_BALANCES.may_load(store, (account, denom))?.unwrap_or_default())
 }
 
 // Check if a global freeze is in effect for a token
@@ -219,32 +355,109 @@ pub fn is_transfer_allowed(
     store: &dyn Storage,
     sender: &str,
     recipient: &str,
-    denom: &str,
+    coin: &Coin,
+    debit: Uint128,
 ) -> Result<(), ContractError> {
-    if is_frozen(store, sender, denom) {
+    let denom = coin.denom.as_str();
+    let token = TOKENS.may_load(store, denom)?.ok_or(ContractError::TokenNotFound)?;
+    // the issuer is never restricted on its own token
+    if sender == token.issuer {
+        return Ok(());
+    }
+
+    if is_globally_frozen(store, denom) {
         return Err(ContractError::TransferRestricted {
-            reason: "Sender account is frozen".to_string(),
+            reason: "Token is globally frozen".to_string(),
         });
     }
 
-    if is_frozen(store, recipient, denom) {
+    // only the unfrozen part of the balance can leave the account, fees included
+    let balance = BALANCES.may_load(store, (sender, denom))?.unwrap_or_default();
+    let spendable = balance.saturating_sub(frozen_balance(store, sender, denom)?);
+    if spendable < debit {
         return Err(ContractError::TransferRestricted {
-            reason: "Recipient account is frozen".to_string(),
+            reason: format!("Sender can spend only {} {}", spendable, denom),
         });
     }
 
-    if !is_whitelisted(store, recipient, denom) {
-        return Err(ContractError::TransferRestricted {
-            reason: "Recipient is not whitelisted".to_string(),
+    let whitelisting = token.features.as_ref().map_or(false, |f| f.contains(&WHITELISTING));
+    if whitelisting && recipient != token.issuer {
+        let held = BALANCES.may_load(store, (recipient, denom))?.unwrap_or_default();
+        let limit = whitelisted_balance(store, recipient, denom)?;
+        if held + coin.amount > limit {
+            return Err(ContractError::TransferRestricted {
+                reason: format!("Recipient is whitelisted for only {} {}", limit, denom),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// Burn and commission charged to the sender on top of a transfer.
+// Neither applies when the issuer sends or receives, and both round up like Coreum does.
+pub fn transfer_fees(
+    store: &dyn Storage,
+    sender: &str,
+    recipient: &str,
+    coin: &Coin,
+) -> Result<(Uint128, Uint128), ContractError> {
+    let token = TOKENS.may_load(store, &coin.denom)?.ok_or(ContractError::TokenNotFound)?;
+    if sender == token.issuer || recipient == token.issuer {
+        return Ok((Uint128::zero(), Uint128::zero()));
+    }
+
+    let burn = coin.amount.mul_ceil(parse_rate(&token.burn_rate)?);
+    let commission = coin.amount.mul_ceil(parse_rate(&token.send_commission_rate)?);
+    Ok((burn, commission))
+}
+
+// Rates are decimal strings between 0 and 1
+fn parse_rate(rate: &str) -> Result<Decimal, ContractError> {
+    let parsed = Decimal::from_str(rate).map_err(|_| ContractError::InvalidRequest {
+        reason: format!("Invalid rate {}", rate),
+    })?;
+    if parsed > Decimal::one() {
+        return Err(ContractError::InvalidRequest {
+            reason: format!("Rate {} is above 1", rate),
         });
     }
+    Ok(parsed)
+}
 
-    if is_globally_frozen(store, denom) {
-        return Err(ContractError::TransferRestricted {
-            reason: "Token is globally frozen".to_string(),
+// Only the issuer may delegate its privileges
+pub fn assert_issuer(store: &dyn Storage, sender: &Addr, denom: &str) -> Result<Token, ContractError> {
+    let token = TOKENS.may_load(store, denom)?.ok_or(ContractError::TokenNotFound)?;
+    if token.issuer != sender.as_str() {
+        return Err(ContractError::Unauthorized {
+            reason: format!("{} is not the issuer of {}", sender, denom),
         });
     }
+    Ok(token)
+}
 
+// Privileged operations are open to the issuer and the admins it delegated to
+pub fn assert_issuer_or_admin(
+    store: &dyn Storage,
+    sender: &Addr,
+    denom: &str,
+) -> Result<Token, ContractError> {
+    let token = TOKENS.may_load(store, denom)?.ok_or(ContractError::TokenNotFound)?;
+    if token.issuer != sender.as_str() && !ADMINS.has(store, (denom, sender.as_str())) {
+        return Err(ContractError::Unauthorized {
+            reason: format!("{} is not the issuer or an admin of {}", sender, denom),
+        });
+    }
+    Ok(token)
+}
+
+// Privileged operations only apply to tokens issued with the matching feature
+pub fn assert_feature(token: &Token, feature: u32, name: &str) -> Result<(), ContractError> {
+    if !token.features.as_ref().map_or(false, |f| f.contains(&feature)) {
+        return Err(ContractError::InvalidRequest {
+            reason: format!("{} is not enabled for {}", name, token.denom),
+        });
+    }
     Ok(())
 }
 
@@ -258,15 +471,76 @@ pub fn restriction_message(restriction: ContractError) -> String {
 
 // Implementing the Msg handlers with restrictions
 
+// Issuing a new token, charging the issue fee and crediting the initial amount to the issuer
+#[allow(clippy::too_many_arguments)]
+pub fn issue(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbol: String,
+    subunit: String,
+    precision: u32,
+    initial_amount: Uint128,
+    description: Option<String>,
+    features: Option<Vec<u32>>,
+    burn_rate: Option<String>,
+    send_commission_rate: Option<String>,
+) -> Result<Response, ContractError> {
+    // Coreum derives the denom from the subunit and the issuer
+    let denom = format!("{}-{}", subunit, info.sender).to_lowercase();
+    if TOKENS.has(deps.storage, &denom) {
+        return Err(ContractError::InvalidRequest {
+            reason: format!("Token {} is already issued", denom),
+        });
+    }
+
+    let burn_rate = burn_rate.unwrap_or_else(|| "0".to_string());
+    let send_commission_rate = send_commission_rate.unwrap_or_else(|| "0".to_string());
+    parse_rate(&burn_rate)?;
+    parse_rate(&send_commission_rate)?;
+
+    // The fee is taken from the attached funds, like Coreum deducts it from the issuer
+    let issue_fee = PARAMS.load(deps.storage)?.issue_fee;
+    let expected = if issue_fee.amount.is_zero() { vec![] } else { vec![issue_fee.clone()] };
+    if info.funds != expected {
+        return Err(ContractError::InvalidRequest {
+            reason: format!("Issuing requires a fee of exactly {}", issue_fee),
+        });
+    }
+
+    let token = Token {
+        denom: denom.clone(),
+        issuer: info.sender.to_string(),
+        symbol,
+        subunit,
+        precision,
+        description,
+        features,
+        burn_rate,
+        send_commission_rate,
+        version: 0,
+    };
+    TOKENS.save(deps.storage, &denom, &token)?;
+    if !initial_amount.is_zero() {
+        BALANCES.save(deps.storage, (info.sender.as_str(), denom.as_str()), &initial_amount)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "issue")
+        .add_attribute("denom", denom)
+        .add_attribute("issuer", info.sender)
+        .add_attribute("initial_amount", initial_amount.to_string())
+        .add_attribute("issue_fee", issue_fee.to_string()))
+}
+
 // Minting tokens with restrictions
 pub fn mint(
     deps: DepsMut,
     info: MessageInfo,
     denom: String,
     amount: Uint128,
-) -> Result<Response, StdError> {
-    // For simplicity, assume minting is unrestricted for this example
-    // In a real-world scenario, you might check if the sender is authorized to mint
+) -> Result<Response, ContractError> {
+    let token = assert_issuer_or_admin(deps.storage, &info.sender, &denom)?;
+    assert_feature(&token, MINTING, "minting")?;
 
     // Update the state to reflect the minted amount
     let key = (info.sender.as_str(), denom.as_str());
@@ -285,12 +559,14 @@ pub fn burn(
     info: MessageInfo,
     denom: String,
     amount: Uint128,
-) -> Result<Response, StdError> {
+) -> Result<Response, ContractError> {
     let key = (info.sender.as_str(), denom.as_str());
     let mut balance = BALANCES.load(deps.storage, key)?;
 
     if balance < amount {
-        return Err(StdError::generic_err("Insufficient balance to burn"));
+        return Err(ContractError::InvalidRequest {
+            reason: "Insufficient balance to burn".to_string(),
+        });
     }
 
     balance -= amount;
@@ -301,42 +577,57 @@ pub fn burn(
         .add_attribute("amount", amount.to_string()))
 }
 
-// Freezing an account's balance
+// Freezing part of an account's balance
 pub fn freeze(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     account: String,
-    denom: String,
-) -> Result<Response, StdError> {
-    // Only allow the contract owner or issuer to freeze
-    // Assuming `info.sender` is checked against an admin list or issuer
+    coin: Coin,
+) -> Result<Response, ContractError> {
+    let token = assert_issuer_or_admin(deps.storage, &info.sender, &coin.denom)?;
+    assert_feature(&token, FREEZING, "freezing")?;
 
-    FROZEN_ACCOUNTS.save(deps.storage, (&account, &denom), &true)?;
+    let key = (account.as_str(), coin.denom.as_str());
+    let frozen = frozen_balance(deps.storage, &account, &coin.denom)?;
+    FROZEN_BALANCES.save(deps.storage, key, &frozen.checked_add(coin.amount)?)?;
 
     Ok(Response::new()
         .add_attribute("action", "freeze")
         .add_attribute("account", account)
-        .add_attribute("denom", denom))
+        .add_attribute("denom", coin.denom)
+        .add_attribute("amount", coin.amount.to_string()))
 }
 
-// Unfreezing an account's balance
+// Unfreezing part of an account's balance
 pub fn unfreeze(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     account: String,
-    denom: String,
-) -> Result<Response, StdError> {
-    // Only allow the contract owner or issuer to unfreeze
-    // Assuming `info.sender` is checked against an admin list or issuer
-
-    FROZEN_ACCOUNTS.save(deps.storage, (&account, &denom), &false)?;
+    coin: Coin,
+) -> Result<Response, ContractError> {
+    let token = assert_issuer_or_admin(deps.storage, &info.sender, &coin.denom)?;
+    assert_feature(&token, FREEZING, "freezing")?;
+
+    let key = (account.as_str(), coin.denom.as_str());
+    let frozen = frozen_balance(deps.storage, &account, &coin.denom)?;
+    if frozen < coin.amount {
+        return Err(ContractError::InvalidRequest {
+            reason: format!("Only {} {} is frozen", frozen, coin.denom),
+        });
+    }
+    if frozen == coin.amount {
+        FROZEN_BALANCES.remove(deps.storage, key);
+    } else {
+        FROZEN_BALANCES.save(deps.storage, key, &(frozen - coin.amount))?;
+    }
 
     Ok(Response::new()
         .add_attribute("action", "unfreeze")
         .add_attribute("account", account)
-        .add_attribute("denom", denom))
+        .add_attribute("denom", coin.denom)
+        .add_attribute("amount", coin.amount.to_string()))
 }
 
 // Globally freezing a token
@@ -345,9 +636,9 @@ pub fn globally_freeze(
     _env: Env,
     info: MessageInfo,
     denom: String,
-) -> Result<Response, StdError> {
-    // Only allow the contract owner or issuer to globally freeze
-    // Assuming `info.sender` is checked against an admin list or issuer
+) -> Result<Response, ContractError> {
+    let token = assert_issuer_or_admin(deps.storage, &info.sender, &denom)?;
+    assert_feature(&token, FREEZING, "freezing")?;
 
     let mut global_freeze = GLOBAL_FREEZE.load(deps.storage)?;
     global_freeze.insert(denom.clone(), true);
@@ -364,9 +655,9 @@ pub fn globally_unfreeze(
     _env: Env,
     info: MessageInfo,
     denom: String,
-) -> Result<Response, StdError> {
-    // Only allow the contract owner or issuer to globally unfreeze
-    // Assuming `info.sender` is checked against an admin list or issuer
+) -> Result<Response, ContractError> {
+    let token = assert_issuer_or_admin(deps.storage, &info.sender, &denom)?;
+    assert_feature(&token, FREEZING, "freezing")?;
 
     let mut global_freeze = GLOBAL_FREEZE.load(deps.storage)?;
     global_freeze.insert(denom.clone(), false);
@@ -383,17 +674,116 @@ pub fn set_whitelisted_limit(
     _env: Env,
     info: MessageInfo,
     account: String,
-    denom: String,
-) -> Result<Response, StdError> {
-    // Only allow the contract owner or issuer to set whitelisted limits
-    // Assuming `info.sender` is checked against an admin list or issuer
-
-    WHITELISTED_ACCOUNTS.save(deps.storage, (&account, &denom), &true)?;
+    coin: Coin,
+) -> Result<Response, ContractError> {
+    let token = assert_issuer_or_admin(deps.storage, &info.sender, &coin.denom)?;
+    assert_feature(&token, WHITELISTING, "whitelisting")?;
+
+    let key = (account.as_str(), coin.denom.as_str());
+    if coin.amount.is_zero() {
+        WHITELISTED_BALANCES.remove(deps.storage, key);
+    } else {
+        WHITELISTED_BALANCES.save(deps.storage, key, &coin.amount)?;
+    }
 
     Ok(Response::new()
         .add_attribute("action", "set_whitelisted_limit")
         .add_attribute("account", account)
-        .add_attribute("denom", denom))
+        .add_attribute("denom", coin.denom)
+        .add_attribute("amount", coin.amount.to_string()))
+}
+
+// Clawing back coins from an account to the issuer
+pub fn clawback(
+    deps: DepsMut,
+    info: MessageInfo,
+    account: String,
+    coin: Coin,
+) -> Result<Response, ContractError> {
+    let token = assert_issuer_or_admin(deps.storage, &info.sender, &coin.denom)?;
+    assert_feature(&token, CLAWBACK, "clawback")?;
+    if account == token.issuer {
+        return Err(ContractError::InvalidRequest {
+            reason: "Can't claw back from the issuer".to_string(),
+        });
+    }
+
+    let account_key = (account.as_str(), coin.denom.as_str());
+    let balance = BALANCES.may_load(deps.storage, account_key)?.unwrap_or_default();
+    if balance < coin.amount {
+        return Err(ContractError::InvalidRequest {
+            reason: "Insufficient balance to claw back".to_string(),
+        });
+    }
+    BALANCES.save(deps.storage, account_key, &(balance - coin.amount))?;
+
+    let issuer_key = (token.issuer.as_str(), coin.denom.as_str());
+    let issuer_balance = BALANCES.may_load(deps.storage, issuer_key)?.unwrap_or_default();
+    BALANCES.save(deps.storage, issuer_key, &(issuer_balance + coin.amount))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "clawback")
+        .add_attribute("account", account)
+        .add_attribute("denom", coin.denom)
+        .add_attribute("amount", coin.amount.to_string()))
+}
+
+// Delegating the issuer's privileges on a token
+pub fn add_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    admin: String,
+) -> Result<Response, ContractError> {
+    assert_issuer(deps.storage, &info.sender, &denom)?;
+    ADMINS.save(deps.storage, (&denom, &admin), &true)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_admin")
+        .add_attribute("denom", denom)
+        .add_attribute("admin", admin))
+}
+
+// Revoking a delegated admin
+pub fn remove_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    admin: String,
+) -> Result<Response, ContractError> {
+    assert_issuer(deps.storage, &info.sender, &denom)?;
+    ADMINS.remove(deps.storage, (&denom, &admin));
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_admin")
+        .add_attribute("denom", denom)
+        .add_attribute("admin", admin))
+}
+
+// Upgrading a token to version 1, optionally enabling IBC transfers
+pub fn upgrade_token_v1(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    ibc_enabled: bool,
+) -> Result<Response, ContractError> {
+    let mut token = assert_issuer_or_admin(deps.storage, &info.sender, &denom)?;
+    if token.version >= 1 {
+        return Err(ContractError::InvalidRequest {
+            reason: "Token is already upgraded".to_string(),
+        });
+    }
+
+    token.version = 1;
+    if ibc_enabled {
+        token.features.get_or_insert_with(Vec::new).push(IBC);
+    }
+    TOKENS.save(deps.storage, &denom, &token)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "upgrade_token_v1")
+        .add_attribute("denom", denom)
+        .add_attribute("ibc_enabled", ibc_enabled.to_string()))
 }
 
 // Transferring tokens with restriction checks
@@ -402,12 +792,17 @@ pub fn transfer(
     info: MessageInfo,
     recipient: String,
     coin: Coin,
-) -> Result<Response, StdError> {
+) -> Result<Response, ContractError> {
     let sender = info.sender.clone();
     let denom = &coin.denom;
 
+    // The sender pays the burn and commission on top of the amount delivered
+    let (burn_amount, commission_amount) =
+        transfer_fees(deps.storage, sender.as_str(), &recipient, &coin)?;
+    let debit = coin.amount + burn_amount + commission_amount;
+
     // Check for transfer restrictions
-    match is_transfer_allowed(deps.storage, &sender.to_string(), &recipient, denom) {
+    match is_transfer_allowed(deps.storage, sender.as_str(), &recipient, &coin, debit) {
         Ok(_) => {
             // Perform the transfer logic
             // Update balances in contract state
@@ -415,11 +810,13 @@ pub fn transfer(
             let recipient_key = (recipient.as_str(), denom.as_str());
 
             let mut sender_balance = BALANCES.load(deps.storage, sender_key)?;
-            if sender_balance < coin.amount {
-                return Err(StdError::generic_err("Insufficient balance"));
+            if sender_balance < debit {
+                return Err(ContractError::InvalidRequest {
+                    reason: "Insufficient balance".to_string(),
+                });
             }
 
-            sender_balance -= coin.amount;
+            sender_balance -= debit;
             BALANCES.save(deps.storage, sender_key, &sender_balance)?;
 
             let mut recipient_balance = BALANCES
@@ -428,24 +825,29 @@ pub fn transfer(
             recipient_balance += coin.amount;
             BALANCES.save(deps.storage, recipient_key, &recipient_balance)?;
 
+            // the commission goes to the issuer, the burnt part simply leaves circulation
+            if !commission_amount.is_zero() {
+                let issuer = TOKENS.load(deps.storage, denom)?.issuer;
+                let issuer_key = (issuer.as_str(), denom.as_str());
+                let issuer_balance = BALANCES.may_load(deps.storage, issuer_key)?.unwrap_or_default();
+                BALANCES.save(deps.storage, issuer_key, &(issuer_balance + commission_amount))?;
+            }
+
             Ok(Response::new()
                 .add_attribute("action", "transfer")
                 .add_attribute("from", sender)
                 .add_attribute("to", recipient)
-                .add_attribute("amount", coin.amount.to_string()))
+                .add_attribute("amount", coin.amount.to_string())
+                .add_attribute("burn_amount", burn_amount.to_string())
+                .add_attribute("commission_amount", commission_amount.to_string()))
         }
-        Err(e) => Err(StdError::generic_err(restriction_message(e))),
+        Err(e) => Err(e),
     }
 }
 
 // Queries
 pub fn query_params(deps: Deps) -> StdResult<ParamsResponse> {
-    let params = Params {
-        issue_fee: Coin {
-            denom: "example_coin".to_string(),
-            amount: Uint128::new(1000),
-        },
-    };
+    let params = PARAMS.load(deps.storage)?;
     Ok(ParamsResponse { params })
 }
 
@@ -469,12 +871,11 @@ pub fn query_token(deps: Deps, denom: String) -> StdResult<TokenResponse> {
 }
 
 pub fn query_balance(deps: Deps, account: String, denom: String) -> StdResult<BalanceResponse> {
-    // Query logic for a balance
     let balance = BALANCES
         .may_load(deps.storage, (&account, &denom))?
         .unwrap_or(Uint128::zero());
-    let frozen = is_frozen(deps.storage, &account, &denom);
-    let whitelisted = is_whitelisted(deps.storage, &account, &denom);
+    let frozen = frozen_balance(deps.storage, &account, &denom)?;
+    let whitelisted = whitelisted_balance(deps.storage, &account, &denom)?;
     let locked = Uint128::zero(); // Example placeholder for locked funds
     Ok(BalanceResponse {
         balance,
@@ -484,25 +885,50 @@ pub fn query_balance(deps: Deps, account: String, denom: String) -> StdResult<Ba
     })
 }
 
+// Pages through an account's per-denom amounts; `key` is the denom to resume from
+fn paginate_balances(
+    store: &dyn Storage,
+    amounts: &Map<(&str, &str), Uint128>,
+    account: &str,
+    pagination: Option<PageRequest>,
+) -> StdResult<(Vec<Coin>, PageResponse)> {
+    let (key, limit) = pagination.map(|p| (p.key, p.limit)).unwrap_or((None, None));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = key.as_deref().map(Bound::inclusive);
+
+    let mut balances = amounts
+        .prefix(account)
+        .range(store, start, None, Order::Ascending)
+        .take(limit + 1)
+        .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+        .collect::<StdResult<Vec<_>>>()?;
+    let next_key = if balances.len() > limit {
+        balances.pop().map(|coin| coin.denom)
+    } else {
+        None
+    };
+    let total = amounts
+        .prefix(account)
+        .keys_raw(store, None, None, Order::Ascending)
+        .count() as u64;
+
+    Ok((balances, PageResponse { next_key, total }))
+}
+
 pub fn query_frozen_balances(
     deps: Deps,
     pagination: Option<PageRequest>,
     account: String,
 ) -> StdResult<FrozenBalancesResponse> {
-    // Query logic for frozen balances
-    let balances = vec![]; // Example placeholder
-    let pagination = PageResponse {
-        next_key: None,
-        total: 0,
-    };
+    let (balances, pagination) =
+        paginate_balances(deps.storage, &FROZEN_BALANCES, &account, pagination)?;
     Ok(FrozenBalancesResponse { pagination, balances })
 }
 
 pub fn query_frozen_balance(deps: Deps, account: String, denom: String) -> StdResult<FrozenBalanceResponse> {
-    // Query logic for a single frozen balance
     let balance = Coin {
-        denom: denom.clone(),
-        amount: Uint128::zero(), // Example placeholder
+        amount: frozen_balance(deps.storage, &account, &denom)?,
+        denom,
     };
     Ok(FrozenBalanceResponse { balance })
 }
@@ -512,12 +938,8 @@ pub fn query_whitelisted_balances(
     pagination: Option<PageRequest>,
     account: String,
 ) -> StdResult<WhitelistedBalancesResponse> {
-    // Query logic for whitelisted balances
-    let balances = vec![]; // Example placeholder
-    let pagination = PageResponse {
-        next_key: None,
-        total: 0,
-    };
+    let (balances, pagination) =
+        paginate_balances(deps.storage, &WHITELISTED_BALANCES, &account, pagination)?;
     Ok(WhitelistedBalancesResponse { pagination, balances })
 }
 
@@ -526,10 +948,142 @@ pub fn query_whitelisted_balance(
     account: String,
     denom: String,
 ) -> StdResult<WhitelistedBalanceResponse> {
-    // Query logic for a single whitelisted balance
     let balance = Coin {
-        denom: denom.clone(),
-        amount: Uint128::zero(), // Example placeholder
+        amount: whitelisted_balance(deps.storage, &account, &denom)?,
+        denom,
     };
     Ok(WhitelistedBalanceResponse { balance })
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{coin, OwnedDeps};
+
+    fn setup(features: Vec<u32>) -> (OwnedDeps<MockStorage, MockApi, MockQuerier>, String) {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg { issue_fee: coin(10, "ucore") };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::AssetFT(Msg::Issue {
+            symbol: "ABC".to_string(),
+            subunit: "uabc".to_string(),
+            precision: 6,
+            initial_amount: Uint128::new(1000),
+            description: None,
+            features: Some(features),
+            burn_rate: None,
+            send_commission_rate: None,
+        });
+        execute(deps.as_mut(), mock_env(), mock_info("issuer", &[coin(10, "ucore")]), msg).unwrap();
+        let msg = ExecuteMsg::AddAdmin { denom: "uabc-issuer".to_string(), admin: "admin".to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), msg).unwrap();
+        let msg = ExecuteMsg::Transfer { recipient: "alice".to_string(), coin: coin(100, "uabc-issuer") };
+        execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), msg).unwrap();
+
+        (deps, "uabc-issuer".to_string())
+    }
+
+    fn balance(deps: Deps, account: &str, denom: &str) -> Uint128 {
+        query_balance(deps, account.to_string(), denom.to_string()).unwrap().balance
+    }
+
+    #[test]
+    fn mint_is_limited_to_issuer_and_admins() {
+        let (mut deps, denom) = setup(vec![MINTING]);
+        let msg = ExecuteMsg::AssetFT(Msg::Mint { denom: denom.clone(), amount: Uint128::new(50) });
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        assert_eq!(balance(deps.as_ref(), "alice", &denom), Uint128::new(100));
+
+        execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), msg.clone()).unwrap();
+        assert_eq!(balance(deps.as_ref(), "issuer", &denom), Uint128::new(950));
+        execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg.clone()).unwrap();
+        assert_eq!(balance(deps.as_ref(), "admin", &denom), Uint128::new(50));
+
+        // a revoked admin loses the right to mint
+        let remove = ExecuteMsg::RemoveAdmin { denom: denom.clone(), admin: "admin".to_string() };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), remove.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), remove).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+
+        let msg = ExecuteMsg::AssetFT(Msg::Mint { denom: "unknown".to_string(), amount: Uint128::new(50) });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::TokenNotFound);
+    }
+
+    #[test]
+    fn privileged_operations_need_their_feature() {
+        let (mut deps, denom) = setup(vec![]);
+        let msgs = vec![
+            Msg::Mint { denom: denom.clone(), amount: Uint128::new(50) },
+            Msg::Freeze { account: "alice".to_string(), coin: coin(10, &denom) },
+            Msg::Unfreeze { account: "alice".to_string(), coin: coin(10, &denom) },
+            Msg::GloballyFreeze { denom: denom.clone() },
+            Msg::GloballyUnfreeze { denom: denom.clone() },
+            Msg::SetWhitelistedLimit { account: "alice".to_string(), coin: coin(500, &denom) },
+            Msg::Clawback { account: "alice".to_string(), coin: coin(10, &denom) },
+        ];
+        for msg in msgs.clone() {
+            let err = execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), ExecuteMsg::AssetFT(msg)).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidRequest { .. }));
+        }
+
+        let (mut deps, denom) = setup(vec![MINTING, FREEZING, WHITELISTING, CLAWBACK]);
+        for msg in msgs {
+            execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), ExecuteMsg::AssetFT(msg)).unwrap();
+        }
+        assert_eq!(frozen_balance(deps.as_ref().storage, "alice", &denom).unwrap(), Uint128::zero());
+        assert_eq!(whitelisted_balance(deps.as_ref().storage, "alice", &denom).unwrap(), Uint128::new(500));
+        assert_eq!(balance(deps.as_ref(), "alice", &denom), Uint128::new(90));
+        assert_eq!(balance(deps.as_ref(), "issuer", &denom), Uint128::new(910));
+    }
+
+    #[test]
+    fn clawback_moves_coins_to_the_issuer() {
+        let (mut deps, denom) = setup(vec![CLAWBACK]);
+
+        let msg = ExecuteMsg::AssetFT(Msg::Clawback { account: "alice".to_string(), coin: coin(40, &denom) });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+        execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), msg).unwrap();
+        assert_eq!(balance(deps.as_ref(), "alice", &denom), Uint128::new(60));
+        assert_eq!(balance(deps.as_ref(), "issuer", &denom), Uint128::new(940));
+
+        let msg = ExecuteMsg::AssetFT(Msg::Clawback { account: "alice".to_string(), coin: coin(61, &denom) });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidRequest { .. }));
+        let msg = ExecuteMsg::AssetFT(Msg::Clawback { account: "issuer".to_string(), coin: coin(1, &denom) });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidRequest { .. }));
+    }
+
+    #[test]
+    fn issue_charges_the_issue_fee() {
+        let (mut deps, _) = setup(vec![]);
+        let issue = ExecuteMsg::AssetFT(Msg::Issue {
+            symbol: "XYZ".to_string(),
+            subunit: "uxyz".to_string(),
+            precision: 6,
+            initial_amount: Uint128::new(1000),
+            description: None,
+            features: None,
+            burn_rate: None,
+            send_commission_rate: None,
+        });
+
+        for funds in [vec![], vec![coin(9, "ucore")], vec![coin(10, "uother")], vec![coin(10, "ucore"), coin(1, "uother")]] {
+            let err = execute(deps.as_mut(), mock_env(), mock_info("issuer", &funds), issue.clone()).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidRequest { .. }));
+        }
+        assert!(query_token(deps.as_ref(), "uxyz-issuer".to_string()).is_err());
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("issuer", &[coin(10, "ucore")]), issue).unwrap();
+        assert_eq!(res.attributes[4].value, "10ucore");
+        assert_eq!(balance(deps.as_ref(), "issuer", "uxyz-issuer"), Uint128::new(1000));
+    }
+}
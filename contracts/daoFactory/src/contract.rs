@@ -0,0 +1,502 @@
+use cosmwasm_schema::serde::Serialize;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Reply, Response,
+    StdError, StdResult, SubMsg, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+use cw_utils::parse_reply_instantiate_data;
+
+use crate::error::ContractError;
+use crate::msg::{
+    DaosResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SplitterExecuteMsg, SplitterInstantiateMsg,
+    TimelockConfig, TimelockExecuteMsg, TimelockInstantiateMsg, TreasuryConfig, VotingConfig,
+    VotingExecuteMsg, VotingInstantiateMsg,
+};
+use crate::state::{Config, Dao, PendingDao, CONFIG, DAOS, DAO_NAMES, DAO_SEQ, PENDING};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:dao-factory";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The bundle is instantiated in this order, each reply starting the next contract
+const TIMELOCK_REPLY_ID: u64 = 1;
+const VOTING_REPLY_ID: u64 = 2;
+const TREASURY_REPLY_ID: u64 = 3;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let config = Config {
+        owner: info.sender.clone(),
+        voting_code_id: msg.voting_code_id,
+        timelock_code_id: msg.timelock_code_id,
+        splitter_code_id: msg.splitter_code_id,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    DAO_SEQ.save(deps.storage, &0)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "instantiate")
+        .add_attribute("owner", info.sender))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreateDao {
+            name,
+            voting,
+            timelock,
+            treasury,
+        } => execute_create_dao(deps, env, info, name, voting, timelock, treasury),
+        ExecuteMsg::UpdateCodeIds {
+            voting_code_id,
+            timelock_code_id,
+            splitter_code_id,
+        } => execute_update_code_ids(
+            deps,
+            info,
+            voting_code_id,
+            timelock_code_id,
+            splitter_code_id,
+        ),
+    }
+}
+
+pub fn execute_create_dao(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    voting: VotingConfig,
+    timelock: TimelockConfig,
+    treasury: TreasuryConfig,
+) -> Result<Response, ContractError> {
+    if name.trim().is_empty() {
+        return Err(ContractError::EmptyName {});
+    }
+    if DAO_NAMES.has(deps.storage, &name) {
+        return Err(ContractError::NameTaken { name });
+    }
+    let config = CONFIG.load(deps.storage)?;
+
+    // the factory administers the timelock until the voting contract is known
+    let msg = TimelockInstantiateMsg {
+        admins: Some(vec![env.contract.address.to_string()]),
+        proposers: vec![],
+        min_delay: timelock.min_delay,
+    };
+    let instantiate = instantiate_msg(&env, config.timelock_code_id, &msg, &name, "timelock")?;
+
+    PENDING.save(
+        deps.storage,
+        &PendingDao {
+            name: name.clone(),
+            creator: info.sender.clone(),
+            voting,
+            treasury,
+            timelock_addr: None,
+            voting_addr: None,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(instantiate, TIMELOCK_REPLY_ID))
+        .add_attribute("action", "create_dao")
+        .add_attribute("name", name)
+        .add_attribute("creator", info.sender))
+}
+
+pub fn execute_update_code_ids(
+    deps: DepsMut,
+    info: MessageInfo,
+    voting_code_id: Option<u64>,
+    timelock_code_id: Option<u64>,
+    splitter_code_id: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    config.voting_code_id = voting_code_id.unwrap_or(config.voting_code_id);
+    config.timelock_code_id = timelock_code_id.unwrap_or(config.timelock_code_id);
+    config.splitter_code_id = splitter_code_id.unwrap_or(config.splitter_code_id);
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_code_ids")
+        .add_attribute("voting_code_id", config.voting_code_id.to_string())
+        .add_attribute("timelock_code_id", config.timelock_code_id.to_string())
+        .add_attribute("splitter_code_id", config.splitter_code_id.to_string()))
+}
+
+/// The factory is wasm admin of every contract it instantiates until the bundle is complete
+fn instantiate_msg<T: Serialize>(
+    env: &Env,
+    code_id: u64,
+    msg: &T,
+    name: &str,
+    part: &str,
+) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Instantiate {
+        admin: Some(env.contract.address.to_string()),
+        code_id,
+        msg: to_json_binary(msg)?,
+        funds: vec![],
+        label: format!("{} {}", name, part),
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let id = msg.id;
+    let address = deps
+        .api
+        .addr_validate(&parse_reply_instantiate_data(msg)?.contract_address)?;
+    let mut pending = PENDING.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    match id {
+        TIMELOCK_REPLY_ID => {
+            let msg = VotingInstantiateMsg {
+                denom: pending.voting.denom.clone(),
+                timelock: Some(address.to_string()),
+                fungible_token: pending.voting.fungible_token.clone(),
+            };
+            let instantiate =
+                instantiate_msg(&env, config.voting_code_id, &msg, &pending.name, "voting")?;
+            pending.timelock_addr = Some(address.clone());
+            PENDING.save(deps.storage, &pending)?;
+            Ok(Response::new()
+                .add_submessage(SubMsg::reply_on_success(instantiate, VOTING_REPLY_ID))
+                .add_attribute("timelock", address))
+        }
+        VOTING_REPLY_ID => {
+            let msg = SplitterInstantiateMsg {
+                recipients: pending.treasury.recipients.clone(),
+            };
+            let instantiate = instantiate_msg(
+                &env,
+                config.splitter_code_id,
+                &msg,
+                &pending.name,
+                "treasury",
+            )?;
+            pending.voting_addr = Some(address.clone());
+            PENDING.save(deps.storage, &pending)?;
+            Ok(Response::new()
+                .add_submessage(SubMsg::reply_on_success(instantiate, TREASURY_REPLY_ID))
+                .add_attribute("voting", address))
+        }
+        TREASURY_REPLY_ID => finish_dao(deps, env, pending, address),
+        id => Err(ContractError::UnknownReply { id }),
+    }
+}
+
+/// Hands the timelock to the voting contract and the rest of the bundle to the timelock
+fn finish_dao(
+    deps: DepsMut,
+    env: Env,
+    pending: PendingDao,
+    treasury: Addr,
+) -> Result<Response, ContractError> {
+    let (Some(timelock), Some(voting)) = (pending.timelock_addr, pending.voting_addr) else {
+        return Err(StdError::generic_err("treasury instantiated out of order").into());
+    };
+
+    let id = DAO_SEQ.load(deps.storage)? + 1;
+    DAO_SEQ.save(deps.storage, &id)?;
+    let dao = Dao {
+        id,
+        name: pending.name,
+        creator: pending.creator,
+        voting: voting.clone(),
+        timelock: timelock.clone(),
+        treasury: treasury.clone(),
+        created_at: env.block.time,
+    };
+    DAOS.save(deps.storage, id, &dao)?;
+    DAO_NAMES.save(deps.storage, &dao.name, &id)?;
+    PENDING.remove(deps.storage);
+
+    let timelock_msgs = [
+        TimelockExecuteMsg::AddProposer {
+            proposer_address: voting.to_string(),
+        },
+        TimelockExecuteMsg::RevokeAdmin {
+            admin_address: env.contract.address.to_string(),
+        },
+    ];
+    let mut messages = vec![];
+    for msg in timelock_msgs {
+        messages.push(WasmMsg::Execute {
+            contract_addr: timelock.to_string(),
+            msg: to_json_binary(&msg)?,
+            funds: vec![],
+        });
+    }
+    messages.push(WasmMsg::Execute {
+        contract_addr: voting.to_string(),
+        msg: to_json_binary(&VotingExecuteMsg::UpdateOwner {
+            owner: timelock.to_string(),
+        })?,
+        funds: vec![],
+    });
+    messages.push(WasmMsg::Execute {
+        contract_addr: treasury.to_string(),
+        msg: to_json_binary(&SplitterExecuteMsg::UpdateOwner {
+            owner: timelock.to_string(),
+        })?,
+        funds: vec![],
+    });
+    for contract in [&voting, &timelock, &treasury] {
+        messages.push(WasmMsg::UpdateAdmin {
+            contract_addr: contract.to_string(),
+            admin: timelock.to_string(),
+        });
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "dao_created")
+        .add_attribute("dao_id", id.to_string())
+        .add_attribute("treasury", treasury))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Dao { id } => to_json_binary(&DAOS.load(deps.storage, id)?),
+        QueryMsg::DaoByName { name } => {
+            let id = DAO_NAMES.load(deps.storage, &name)?;
+            to_json_binary(&DAOS.load(deps.storage, id)?)
+        }
+        QueryMsg::Daos { start_after, limit } => {
+            to_json_binary(&query_daos(deps, start_after, limit)?)
+        }
+    }
+}
+
+fn query_daos(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<DaosResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let daos = DAOS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, dao)| dao))
+        .collect::<StdResult<_>>()?;
+    Ok(DaosResponse { daos })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::RecipientMsg;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{from_json, CosmosMsg, SubMsgResponse, SubMsgResult};
+    use cw_utils::Duration;
+
+    const OWNER: &str = "owner";
+    const CREATOR: &str = "creator";
+
+    fn create_msg(name: &str) -> ExecuteMsg {
+        ExecuteMsg::CreateDao {
+            name: name.to_string(),
+            voting: VotingConfig {
+                denom: "ucore".to_string(),
+                fungible_token: None,
+            },
+            timelock: TimelockConfig {
+                min_delay: Duration::Time(3600),
+            },
+            treasury: TreasuryConfig {
+                recipients: vec![RecipientMsg {
+                    address: "grants".to_string(),
+                    share_bps: 10_000,
+                    push: false,
+                }],
+            },
+        }
+    }
+
+    /// Reply carrying a protobuf encoded `MsgInstantiateContractResponse`
+    fn instantiate_reply(id: u64, address: &str) -> Reply {
+        let mut data = vec![0x0a, address.len() as u8];
+        data.extend_from_slice(address.as_bytes());
+        Reply {
+            id,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(Binary::from(data)),
+            }),
+        }
+    }
+
+    fn instantiated_code_id(submsg: &SubMsg) -> u64 {
+        match &submsg.msg {
+            CosmosMsg::Wasm(WasmMsg::Instantiate { code_id, admin, .. }) => {
+                assert_eq!(admin.as_deref(), Some(mock_env().contract.address.as_str()));
+                *code_id
+            }
+            m => panic!("unexpected message: {:?}", m),
+        }
+    }
+
+    #[test]
+    fn creates_and_wires_bundle() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            voting_code_id: 1,
+            timelock_code_id: 2,
+            splitter_code_id: 3,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+
+        let info = mock_info(CREATOR, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, create_msg("guild")).unwrap();
+        assert_eq!(res.messages[0].id, TIMELOCK_REPLY_ID);
+        assert_eq!(instantiated_code_id(&res.messages[0]), 2);
+
+        let res = reply(
+            deps.as_mut(),
+            mock_env(),
+            instantiate_reply(TIMELOCK_REPLY_ID, "timelock0"),
+        )
+        .unwrap();
+        assert_eq!(res.messages[0].id, VOTING_REPLY_ID);
+        assert_eq!(instantiated_code_id(&res.messages[0]), 1);
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Instantiate { msg, .. }) => {
+                let msg: VotingInstantiateMsg = from_json(msg).unwrap();
+                assert_eq!(msg.timelock, Some("timelock0".to_string()));
+            }
+            m => panic!("unexpected message: {:?}", m),
+        }
+
+        let res = reply(
+            deps.as_mut(),
+            mock_env(),
+            instantiate_reply(VOTING_REPLY_ID, "voting0"),
+        )
+        .unwrap();
+        assert_eq!(res.messages[0].id, TREASURY_REPLY_ID);
+        assert_eq!(instantiated_code_id(&res.messages[0]), 3);
+
+        let res = reply(
+            deps.as_mut(),
+            mock_env(),
+            instantiate_reply(TREASURY_REPLY_ID, "treasury0"),
+        )
+        .unwrap();
+        // proposer, admin revocation, voting and treasury owners and three wasm admins
+        assert_eq!(res.messages.len(), 7);
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "timelock0".to_string(),
+                msg: to_json_binary(&TimelockExecuteMsg::RevokeAdmin {
+                    admin_address: mock_env().contract.address.to_string(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        assert_eq!(
+            res.messages[2].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "voting0".to_string(),
+                msg: to_json_binary(&VotingExecuteMsg::UpdateOwner {
+                    owner: "timelock0".to_string(),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        assert_eq!(
+            res.messages[6].msg,
+            CosmosMsg::Wasm(WasmMsg::UpdateAdmin {
+                contract_addr: "treasury0".to_string(),
+                admin: "timelock0".to_string(),
+            })
+        );
+        assert!(PENDING.may_load(&deps.storage).unwrap().is_none());
+
+        let dao: Dao = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::DaoByName {
+                    name: "guild".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(dao.id, 1);
+        assert_eq!(dao.creator, Addr::unchecked(CREATOR));
+        assert_eq!(dao.voting, Addr::unchecked("voting0"));
+        assert_eq!(dao.timelock, Addr::unchecked("timelock0"));
+        assert_eq!(dao.treasury, Addr::unchecked("treasury0"));
+
+        // names are unique
+        let info = mock_info(CREATOR, &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, create_msg("guild")).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NameTaken {
+                name: "guild".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn only_owner_updates_code_ids() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            voting_code_id: 1,
+            timelock_code_id: 2,
+            splitter_code_id: 3,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateCodeIds {
+            voting_code_id: Some(7),
+            timelock_code_id: None,
+            splitter_code_id: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(CREATOR, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(config.voting_code_id, 7);
+        assert_eq!(config.timelock_code_id, 2);
+    }
+}
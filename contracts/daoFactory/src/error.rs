@@ -0,0 +1,24 @@
+use cosmwasm_std::StdError;
+use cw_utils::ParseReplyError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    ParseReply(#[from] ParseReplyError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("DAO name must not be empty")]
+    EmptyName {},
+
+    #[error("A DAO named {name} already exists")]
+    NameTaken { name: String },
+
+    #[error("Unknown reply id {id}")]
+    UnknownReply { id: u64 },
+}
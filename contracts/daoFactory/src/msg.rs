@@ -0,0 +1,111 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cw_utils::Duration;
+
+use crate::state::{Config, Dao};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub voting_code_id: u64,
+    pub timelock_code_id: u64,
+    pub splitter_code_id: u64,
+}
+
+#[cw_serde]
+pub struct VotingConfig {
+    pub denom: String,
+    /// fungibleToken contract that issued `denom`, see the voting contract
+    pub fungible_token: Option<String>,
+}
+
+#[cw_serde]
+pub struct TimelockConfig {
+    pub min_delay: Duration,
+}
+
+#[cw_serde]
+pub struct TreasuryConfig {
+    pub recipients: Vec<RecipientMsg>,
+}
+
+/// Same as the splitter `RecipientMsg`
+#[cw_serde]
+pub struct RecipientMsg {
+    pub address: String,
+    pub share_bps: u16,
+    pub push: bool,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Instantiates the voting contract, a timelock and a splitter treasury, then hands the
+    /// timelock to the voting contract and everything else to the timelock. Anyone can call it.
+    CreateDao {
+        name: String,
+        voting: VotingConfig,
+        timelock: TimelockConfig,
+        treasury: TreasuryConfig,
+    },
+    /// Owner only, `None` keeps the current code id
+    UpdateCodeIds {
+        voting_code_id: Option<u64>,
+        timelock_code_id: Option<u64>,
+        splitter_code_id: Option<u64>,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(Config)]
+    Config {},
+    #[returns(Dao)]
+    Dao { id: u64 },
+    #[returns(Dao)]
+    DaoByName { name: String },
+    #[returns(DaosResponse)]
+    Daos {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct DaosResponse {
+    pub daos: Vec<Dao>,
+}
+
+/// Subset of the instantiate and execute messages of the bundled contracts
+#[cw_serde]
+pub struct VotingInstantiateMsg {
+    pub denom: String,
+    pub timelock: Option<String>,
+    pub fungible_token: Option<String>,
+}
+
+#[cw_serde]
+pub struct TimelockInstantiateMsg {
+    pub admins: Option<Vec<String>>,
+    pub proposers: Vec<String>,
+    pub min_delay: Duration,
+}
+
+#[cw_serde]
+pub enum TimelockExecuteMsg {
+    AddProposer { proposer_address: String },
+    RevokeAdmin { admin_address: String },
+}
+
+#[cw_serde]
+pub struct SplitterInstantiateMsg {
+    pub recipients: Vec<RecipientMsg>,
+}
+
+#[cw_serde]
+pub enum VotingExecuteMsg {
+    UpdateOwner { owner: String },
+}
+
+#[cw_serde]
+pub enum SplitterExecuteMsg {
+    UpdateOwner { owner: String },
+}
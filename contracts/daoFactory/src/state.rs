@@ -0,0 +1,43 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Timestamp};
+use cw_storage_plus::{Item, Map};
+
+use crate::msg::{TreasuryConfig, VotingConfig};
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,
+    pub voting_code_id: u64,
+    pub timelock_code_id: u64,
+    pub splitter_code_id: u64,
+}
+
+#[cw_serde]
+pub struct Dao {
+    pub id: u64,
+    pub name: String,
+    pub creator: Addr,
+    pub voting: Addr,
+    /// Only proposer and admin of itself, owns the treasury and is wasm admin of all three
+    pub timelock: Addr,
+    pub treasury: Addr,
+    pub created_at: Timestamp,
+}
+
+/// DAO being assembled across the instantiate replies of a single `CreateDao`
+#[cw_serde]
+pub struct PendingDao {
+    pub name: String,
+    pub creator: Addr,
+    pub voting: VotingConfig,
+    pub treasury: TreasuryConfig,
+    pub timelock_addr: Option<Addr>,
+    pub voting_addr: Option<Addr>,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const PENDING: Item<PendingDao> = Item::new("pending");
+pub const DAO_SEQ: Item<u64> = Item::new("dao_seq");
+pub const DAOS: Map<u64, Dao> = Map::new("daos");
+/// DAO ids by name, names are unique
+pub const DAO_NAMES: Map<&str, u64> = Map::new("dao_names");
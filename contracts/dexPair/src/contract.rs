@@ -0,0 +1,708 @@
+use coreum_wasm_sdk::assetft;
+use coreum_wasm_sdk::core::{CoreumMsg, CoreumQueries, CoreumResult};
+use cosmwasm_std::{
+    coin, entry_point, to_json_binary, BankMsg, Binary, Coin, Decimal, Deps, DepsMut, Env, Isqrt,
+    MessageInfo, Response, StdError, StdResult, Uint128, Uint256,
+};
+use cw2::set_contract_version;
+use cw_ownable::{assert_owner, initialize_owner};
+use cw_utils::{must_pay, one_coin};
+use std::str::FromStr;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ConfigResponse, CumulativePricesResponse, ExecuteMsg, InstantiateMsg, PoolResponse, QueryMsg,
+    SimulationResponse,
+};
+use crate::state::{Config, Pool, CONFIG, POOL};
+
+// version info for migration info
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const LP_SYMBOL: &str = "LP";
+const LP_SUBUNIT: &str = "ulp";
+const LP_PRECISION: u32 = 6;
+/// Shares locked forever on the first deposit so the share price can't be inflated
+const MINIMUM_LIQUIDITY: Uint128 = Uint128::new(1_000);
+/// Fixed point scale of the TWAP accumulators
+const PRICE_SCALE: Uint256 = Uint256::from_u128(1_000_000_000_000_000_000);
+
+// ********** Instantiate **********
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> CoreumResult<ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    initialize_owner(deps.storage, deps.api, Some(info.sender.as_ref()))?;
+
+    if msg.denoms[0] == msg.denoms[1] {
+        return Err(ContractError::IdenticalDenoms {});
+    }
+    if msg.swap_fee >= Decimal::one() {
+        return Err(ContractError::InvalidFee {});
+    }
+    for denom in &msg.denoms {
+        assert_fee_free(deps.as_ref(), denom)?;
+    }
+
+    // the pair issues its own LP share token and stays its only minter and burner
+    let issue_msg = CoreumMsg::AssetFT(assetft::Msg::Issue {
+        symbol: LP_SYMBOL.to_string(),
+        subunit: LP_SUBUNIT.to_string(),
+        precision: LP_PRECISION,
+        initial_amount: Uint128::zero(),
+        description: Some(format!(
+            "{}/{} liquidity shares",
+            msg.denoms[0], msg.denoms[1]
+        )),
+        features: Some(vec![assetft::MINTING, assetft::BURNING]),
+        burn_rate: "0".to_string(),
+        send_commission_rate: "0".to_string(),
+        uri: None,
+        uri_hash: None,
+    });
+    let lp_denom = format!("{}-{}", LP_SUBUNIT, env.contract.address).to_lowercase();
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            denoms: msg.denoms,
+            lp_denom: lp_denom.clone(),
+            swap_fee: msg.swap_fee,
+        },
+    )?;
+    POOL.save(
+        deps.storage,
+        &Pool {
+            reserves: [Uint128::zero(); 2],
+            total_share: Uint128::zero(),
+            price_cumulative: [Uint256::zero(); 2],
+            block_time_last: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("owner", info.sender)
+        .add_attribute("lp_denom", lp_denom)
+        .add_message(issue_msg))
+}
+
+// ********** Execute **********
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> CoreumResult<ContractError> {
+    match msg {
+        ExecuteMsg::ProvideLiquidity { min_shares } => {
+            provide_liquidity(deps, env, info, min_shares)
+        }
+        ExecuteMsg::WithdrawLiquidity { min_amounts } => {
+            withdraw_liquidity(deps, env, info, min_amounts)
+        }
+        ExecuteMsg::Swap { min_return, to } => swap(deps, env, info, min_return, to),
+        ExecuteMsg::UpdateSwapFee { swap_fee } => update_swap_fee(deps, info, swap_fee),
+    }
+}
+
+// ********** Transactions **********
+
+fn provide_liquidity(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    min_shares: Option<Uint128>,
+) -> CoreumResult<ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut pool = POOL.load(deps.storage)?;
+
+    let deposits = [
+        attached(&info, &config.denoms[0]),
+        attached(&info, &config.denoms[1]),
+    ];
+    if info.funds.len() != 2 || deposits.iter().any(Uint128::is_zero) {
+        return Err(ContractError::InvalidDeposit(
+            config.denoms[0].clone(),
+            config.denoms[1].clone(),
+        ));
+    }
+
+    accumulate_prices(&mut pool, env.block.time.seconds());
+
+    let (minted, used) = if pool.total_share.is_zero() {
+        let share: Uint128 = Uint256::from(deposits[0])
+            .checked_mul(Uint256::from(deposits[1]))?
+            .isqrt()
+            .try_into()
+            .map_err(StdError::from)?;
+        if share <= MINIMUM_LIQUIDITY {
+            return Err(ContractError::InsufficientLiquidity(MINIMUM_LIQUIDITY));
+        }
+        pool.total_share = share;
+        (share - MINIMUM_LIQUIDITY, deposits)
+    } else {
+        let share = std::cmp::min(
+            deposits[0].multiply_ratio(pool.total_share, pool.reserves[0]),
+            deposits[1].multiply_ratio(pool.total_share, pool.reserves[1]),
+        );
+        // take what the minted shares are worth, rounding in favour of the pool
+        let used = [
+            ceil_ratio(share, pool.reserves[0], pool.total_share)?.min(deposits[0]),
+            ceil_ratio(share, pool.reserves[1], pool.total_share)?.min(deposits[1]),
+        ];
+        pool.total_share = pool.total_share.checked_add(share)?;
+        (share, used)
+    };
+
+    let min_shares = min_shares.unwrap_or_else(Uint128::one);
+    if minted < min_shares {
+        return Err(ContractError::MinimumReceive {
+            expected: min_shares,
+            actual: minted,
+        });
+    }
+
+    pool.reserves[0] = pool.reserves[0].checked_add(used[0])?;
+    pool.reserves[1] = pool.reserves[1].checked_add(used[1])?;
+    POOL.save(deps.storage, &pool)?;
+
+    let refund: Vec<Coin> = (0..2)
+        .filter(|&i| deposits[i] > used[i])
+        .map(|i| coin((deposits[i] - used[i]).u128(), &config.denoms[i]))
+        .collect();
+    let mut response = Response::new();
+    if !refund.is_empty() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: refund,
+        });
+    }
+
+    Ok(response
+        .add_message(CoreumMsg::AssetFT(assetft::Msg::Mint {
+            coin: coin(minted.u128(), &config.lp_denom),
+            recipient: Some(info.sender.to_string()),
+        }))
+        .add_attribute("method", "provide_liquidity")
+        .add_attribute("provider", info.sender)
+        .add_attribute("shares", minted)
+        .add_attribute("amounts", format!("{},{}", used[0], used[1])))
+}
+
+fn withdraw_liquidity(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    min_amounts: Option<[Uint128; 2]>,
+) -> CoreumResult<ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut pool = POOL.load(deps.storage)?;
+    let shares = must_pay(&info, &config.lp_denom)?;
+
+    accumulate_prices(&mut pool, env.block.time.seconds());
+
+    let amounts = [
+        pool.reserves[0].multiply_ratio(shares, pool.total_share),
+        pool.reserves[1].multiply_ratio(shares, pool.total_share),
+    ];
+    if let Some(min_amounts) = min_amounts {
+        for (&actual, expected) in amounts.iter().zip(min_amounts) {
+            if actual < expected {
+                return Err(ContractError::MinimumReceive { expected, actual });
+            }
+        }
+    }
+
+    pool.reserves[0] -= amounts[0];
+    pool.reserves[1] -= amounts[1];
+    pool.total_share = pool.total_share.checked_sub(shares)?;
+    POOL.save(deps.storage, &pool)?;
+
+    let payout: Vec<Coin> = (0..2)
+        .filter(|&i| !amounts[i].is_zero())
+        .map(|i| coin(amounts[i].u128(), &config.denoms[i]))
+        .collect();
+    let mut response = Response::new().add_message(CoreumMsg::AssetFT(assetft::Msg::Burn {
+        coin: coin(shares.u128(), &config.lp_denom),
+    }));
+    if !payout.is_empty() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: payout,
+        });
+    }
+
+    Ok(response
+        .add_attribute("method", "withdraw_liquidity")
+        .add_attribute("provider", info.sender)
+        .add_attribute("shares", shares)
+        .add_attribute("amounts", format!("{},{}", amounts[0], amounts[1])))
+}
+
+fn swap(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    min_return: Option<Uint128>,
+    to: Option<String>,
+) -> CoreumResult<ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut pool = POOL.load(deps.storage)?;
+    let offer = one_coin(&info)?;
+    let to = to
+        .map(|to| deps.api.addr_validate(&to))
+        .transpose()?
+        .unwrap_or(info.sender);
+
+    let (offer_index, ask_index) = pair_indexes(&config, &offer.denom)?;
+    let (return_amount, fee_amount) = compute_swap(
+        pool.reserves[offer_index],
+        pool.reserves[ask_index],
+        offer.amount,
+        config.swap_fee,
+    )?;
+
+    let min_return = min_return.unwrap_or_else(Uint128::one);
+    if return_amount < min_return {
+        return Err(ContractError::MinimumReceive {
+            expected: min_return,
+            actual: return_amount,
+        });
+    }
+
+    accumulate_prices(&mut pool, env.block.time.seconds());
+    pool.reserves[offer_index] = pool.reserves[offer_index].checked_add(offer.amount)?;
+    pool.reserves[ask_index] -= return_amount;
+    POOL.save(deps.storage, &pool)?;
+
+    let ask_denom = &config.denoms[ask_index];
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: to.to_string(),
+            amount: vec![coin(return_amount.u128(), ask_denom)],
+        })
+        .add_attribute("method", "swap")
+        .add_attribute("offer", offer.to_string())
+        .add_attribute("return", coin(return_amount.u128(), ask_denom).to_string())
+        .add_attribute("fee", fee_amount)
+        .add_attribute("to", to))
+}
+
+fn update_swap_fee(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    swap_fee: Decimal,
+) -> CoreumResult<ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    if swap_fee >= Decimal::one() {
+        return Err(ContractError::InvalidFee {});
+    }
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.swap_fee = swap_fee;
+        Ok(config)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_swap_fee")
+        .add_attribute("swap_fee", swap_fee.to_string()))
+}
+
+// ********** Helpers **********
+
+fn attached(info: &MessageInfo, denom: &str) -> Uint128 {
+    info.funds
+        .iter()
+        .find(|coin| coin.denom == denom)
+        .map_or_else(Uint128::zero, |coin| coin.amount)
+}
+
+/// Smart FTs charge their burn rate and send commission to the sender on top of every send, so
+/// the pair would pay out more than its reserves account for. Only fee-free denoms are traded.
+fn assert_fee_free(deps: Deps<CoreumQueries>, denom: &str) -> Result<(), ContractError> {
+    // smart FT denoms are the subunit followed by the issuer address
+    let smart_ft = denom
+        .rsplit_once('-')
+        .map_or(false, |(_, issuer)| deps.api.addr_validate(issuer).is_ok());
+    if !smart_ft {
+        return Ok(());
+    }
+
+    let request = CoreumQueries::AssetFT(assetft::Query::Token {
+        denom: denom.to_string(),
+    })
+    .into();
+    let res: assetft::TokenResponse = deps.querier.query(&request)?;
+    for rate in [&res.token.burn_rate, &res.token.send_commission_rate] {
+        if !Decimal::from_str(rate)?.is_zero() {
+            return Err(ContractError::TransferFees(denom.to_string()));
+        }
+    }
+    Ok(())
+}
+
+fn pair_indexes(config: &Config, denom: &str) -> Result<(usize, usize), ContractError> {
+    match config.denoms.iter().position(|d| d == denom) {
+        Some(0) => Ok((0, 1)),
+        Some(_) => Ok((1, 0)),
+        None => Err(ContractError::UnknownDenom(denom.to_string())),
+    }
+}
+
+fn ceil_ratio(value: Uint128, numerator: Uint128, denominator: Uint128) -> StdResult<Uint128> {
+    let product = Uint256::from(value).checked_mul(Uint256::from(numerator))?;
+    let denominator = Uint256::from(denominator);
+    let result = (product + denominator - Uint256::one()) / denominator;
+    Ok(result.try_into()?)
+}
+
+/// x * y = k with the fee taken from the offer and left in the pool
+fn compute_swap(
+    offer_reserve: Uint128,
+    ask_reserve: Uint128,
+    offer_amount: Uint128,
+    swap_fee: Decimal,
+) -> Result<(Uint128, Uint128), ContractError> {
+    if offer_reserve.is_zero() || ask_reserve.is_zero() {
+        return Err(ContractError::EmptyPool {});
+    }
+
+    let fee_amount = offer_amount.mul_floor(swap_fee);
+    let net_offer = offer_amount - fee_amount;
+    let return_amount =
+        ask_reserve.multiply_ratio(net_offer, offer_reserve.checked_add(net_offer)?);
+    Ok((return_amount, fee_amount))
+}
+
+/// Adds the prices that held since the last update, weighted by the seconds elapsed
+fn accumulate_prices(pool: &mut Pool, now: u64) {
+    let elapsed = now.saturating_sub(pool.block_time_last);
+    if elapsed == 0 {
+        return;
+    }
+    if !pool.reserves[0].is_zero() && !pool.reserves[1].is_zero() {
+        for (i, j) in [(0, 1), (1, 0)] {
+            let price =
+                Uint256::from(pool.reserves[j]) * PRICE_SCALE / Uint256::from(pool.reserves[i]);
+            pool.price_cumulative[i] =
+                pool.price_cumulative[i].wrapping_add(price.wrapping_mul(Uint256::from(elapsed)));
+        }
+    }
+    pool.block_time_last = now;
+}
+
+// ********** Queries **********
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps<CoreumQueries>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Pool {} => to_json_binary(&query_pool(deps)?),
+        QueryMsg::Simulation { offer } => to_json_binary(&query_simulation(deps, offer)?),
+        QueryMsg::CumulativePrices {} => to_json_binary(&query_cumulative_prices(deps, env)?),
+    }
+}
+
+fn query_config(deps: Deps<CoreumQueries>) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        denoms: config.denoms,
+        lp_denom: config.lp_denom,
+        swap_fee: config.swap_fee,
+    })
+}
+
+fn query_pool(deps: Deps<CoreumQueries>) -> StdResult<PoolResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pool = POOL.load(deps.storage)?;
+    Ok(PoolResponse {
+        assets: vec![
+            coin(pool.reserves[0].u128(), &config.denoms[0]),
+            coin(pool.reserves[1].u128(), &config.denoms[1]),
+        ],
+        total_share: pool.total_share,
+    })
+}
+
+fn query_simulation(deps: Deps<CoreumQueries>, offer: Coin) -> StdResult<SimulationResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pool = POOL.load(deps.storage)?;
+    let (offer_index, ask_index) = pair_indexes(&config, &offer.denom)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let (return_amount, fee_amount) = compute_swap(
+        pool.reserves[offer_index],
+        pool.reserves[ask_index],
+        offer.amount,
+        config.swap_fee,
+    )
+    .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    Ok(SimulationResponse {
+        return_amount: coin(return_amount.u128(), &config.denoms[ask_index]),
+        fee_amount: coin(fee_amount.u128(), offer.denom),
+    })
+}
+
+fn query_cumulative_prices(deps: Deps<CoreumQueries>, env: Env) -> StdResult<CumulativePricesResponse> {
+    let mut pool = POOL.load(deps.storage)?;
+    let block_time = env.block.time.seconds();
+    accumulate_prices(&mut pool, block_time);
+    Ok(CumulativePricesResponse {
+        price_cumulative: pool.price_cumulative,
+        block_time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{
+        coins, from_json, ContractResult, CosmosMsg, OwnedDeps, SystemError, SystemResult,
+        Timestamp,
+    };
+    use std::marker::PhantomData;
+
+    type CoreumDeps = OwnedDeps<MockStorage, MockApi, MockQuerier<CoreumQueries>, CoreumQueries>;
+
+    const OWNER: &str = "owner";
+    const ALICE: &str = "alice";
+    const CORE: &str = "ucore";
+    const TOKEN: &str = "utkn-issuer";
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    }
+
+    fn lp_denom() -> String {
+        format!("ulp-{}", mock_env().contract.address)
+    }
+
+    // answers smart FT token queries with the given burn rate
+    fn mock_deps(burn_rate: &'static str) -> CoreumDeps {
+        let querier = MockQuerier::new(&[]).with_custom_handler(move |query| match query {
+            CoreumQueries::AssetFT(assetft::Query::Token { denom }) => {
+                let token = format!(
+                    r#"{{"token":{{"denom":"{}","issuer":"issuer","symbol":"TKN","subunit":"utkn","precision":6,"description":null,"globally_frozen":false,"features":[],"burn_rate":"{}","send_commission_rate":"0","version":1,"uri":null,"uri_hash":null}}}}"#,
+                    denom, burn_rate
+                );
+                SystemResult::Ok(ContractResult::Ok(token.into_bytes().into()))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "coreum".to_string(),
+            }),
+        });
+        OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier,
+            custom_query_type: PhantomData,
+        }
+    }
+
+    fn setup() -> CoreumDeps {
+        let mut deps = mock_deps("0");
+        let msg = InstantiateMsg {
+            denoms: [CORE.to_string(), TOKEN.to_string()],
+            swap_fee: Decimal::permille(3),
+        };
+        let res = instantiate(deps.as_mut(), env_at(0), mock_info(OWNER, &[]), msg).unwrap();
+        assert!(matches!(
+            &res.messages[0].msg,
+            CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Issue { subunit, .. })) if subunit == LP_SUBUNIT
+        ));
+        deps
+    }
+
+    fn provide(
+        deps: &mut CoreumDeps,
+        seconds: u64,
+        core: u128,
+        token: u128,
+    ) -> Response<CoreumMsg> {
+        let funds = [coin(core, CORE), coin(token, TOKEN)];
+        let msg = ExecuteMsg::ProvideLiquidity { min_shares: None };
+        execute(
+            deps.as_mut(),
+            env_at(seconds),
+            mock_info(ALICE, &funds),
+            msg,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn rejects_smart_ft_with_transfer_fees() {
+        let mut deps = mock_deps("0.01");
+        let msg = InstantiateMsg {
+            denoms: [CORE.to_string(), TOKEN.to_string()],
+            swap_fee: Decimal::permille(3),
+        };
+        let err = instantiate(deps.as_mut(), env_at(0), mock_info(OWNER, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::TransferFees(TOKEN.to_string()));
+
+        // bank denoms are never looked up as smart FTs
+        let msg = InstantiateMsg {
+            denoms: [CORE.to_string(), "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2".to_string()],
+            swap_fee: Decimal::permille(3),
+        };
+        instantiate(deps.as_mut(), env_at(0), mock_info(OWNER, &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn provide_and_withdraw() {
+        let mut deps = setup();
+
+        // first deposit mints sqrt(x * y) minus the locked minimum
+        let res = provide(&mut deps, 0, 1_000_000, 4_000_000);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Mint {
+                coin: coin(1_999_000, lp_denom()),
+                recipient: Some(ALICE.to_string()),
+            }))
+        );
+
+        // unbalanced deposits get the excess back
+        let res = provide(&mut deps, 0, 1_000_000, 5_000_000);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: ALICE.to_string(),
+                amount: coins(1_000_000, TOKEN),
+            })
+        );
+        let pool = query_pool(deps.as_ref()).unwrap();
+        assert_eq!(
+            pool.assets,
+            vec![coin(2_000_000, CORE), coin(8_000_000, TOKEN)]
+        );
+        assert_eq!(pool.total_share, Uint128::new(4_000_000));
+
+        // a single denom is not a deposit
+        let msg = ExecuteMsg::ProvideLiquidity { min_shares: None };
+        let err = execute(
+            deps.as_mut(),
+            env_at(0),
+            mock_info(ALICE, &coins(10, CORE)),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidDeposit(CORE.to_string(), TOKEN.to_string())
+        );
+
+        let msg = ExecuteMsg::WithdrawLiquidity { min_amounts: None };
+        let info = mock_info(ALICE, &coins(1_000_000, lp_denom()));
+        let res = execute(deps.as_mut(), env_at(0), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Burn {
+                coin: coin(1_000_000, lp_denom()),
+            }))
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: ALICE.to_string(),
+                amount: vec![coin(500_000, CORE), coin(2_000_000, TOKEN)],
+            })
+        );
+    }
+
+    #[test]
+    fn swap_charges_fee_and_checks_slippage() {
+        let mut deps = setup();
+        provide(&mut deps, 0, 1_000_000, 1_000_000);
+
+        let offer = coin(1_000, CORE);
+        let sim = query_simulation(deps.as_ref(), offer.clone()).unwrap();
+        // 997 after the 0.3% fee: 1_000_000 * 997 / 1_000_997
+        assert_eq!(sim.return_amount, coin(996, TOKEN));
+        assert_eq!(sim.fee_amount, coin(3, CORE));
+
+        let msg = ExecuteMsg::Swap {
+            min_return: Some(Uint128::new(997)),
+            to: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(0),
+            mock_info(ALICE, &[offer.clone()]),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::MinimumReceive {
+                expected: Uint128::new(997),
+                actual: Uint128::new(996),
+            }
+        );
+
+        let msg = ExecuteMsg::Swap {
+            min_return: None,
+            to: Some(OWNER.to_string()),
+        };
+        let res = execute(deps.as_mut(), env_at(0), mock_info(ALICE, &[offer]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: OWNER.to_string(),
+                amount: coins(996, TOKEN),
+            })
+        );
+
+        let msg = ExecuteMsg::Swap {
+            min_return: None,
+            to: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(0),
+            mock_info(ALICE, &coins(10, "uatom")),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::UnknownDenom("uatom".to_string()));
+    }
+
+    #[test]
+    fn prices_accumulate_over_time() {
+        let mut deps = setup();
+        provide(&mut deps, 100, 1_000_000, 2_000_000);
+
+        // price of ucore is 2 utkn for 50 seconds
+        let res: CumulativePricesResponse =
+            from_json(query(deps.as_ref(), env_at(150), QueryMsg::CumulativePrices {}).unwrap())
+                .unwrap();
+        assert_eq!(res.price_cumulative[0], PRICE_SCALE * Uint256::from(100u64));
+        assert_eq!(res.price_cumulative[1], PRICE_SCALE * Uint256::from(25u64));
+        assert_eq!(res.block_time, 150);
+    }
+
+    #[test]
+    fn only_owner_updates_fee() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::UpdateSwapFee {
+            swap_fee: Decimal::percent(1),
+        };
+        execute(deps.as_mut(), env_at(0), mock_info(ALICE, &[]), msg.clone()).unwrap_err();
+        execute(deps.as_mut(), env_at(0), mock_info(OWNER, &[]), msg).unwrap();
+        assert_eq!(
+            query_config(deps.as_ref()).unwrap().swap_fee,
+            Decimal::percent(1)
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use cw_ownable::OwnershipError;
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error(transparent)]
+    Ownership(#[from] OwnershipError),
+
+    #[error("Pair denoms must differ")]
+    IdenticalDenoms {},
+
+    #[error("Swap fee must be below 1")]
+    InvalidFee {},
+
+    #[error("Expected deposits of exactly {0} and {1}")]
+    InvalidDeposit(String, String),
+
+    #[error("Denom {0} charges a burn rate or send commission")]
+    TransferFees(String),
+
+    #[error("Denom {0} is not traded by this pair")]
+    UnknownDenom(String),
+
+    #[error("Initial liquidity must exceed {0} shares")]
+    InsufficientLiquidity(Uint128),
+
+    #[error("Pool has no liquidity")]
+    EmptyPool {},
+
+    #[error("Slippage: expected at least {expected}, got {actual}")]
+    MinimumReceive { expected: Uint128, actual: Uint128 },
+}
@@ -0,0 +1,70 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Coin, Decimal, Uint128, Uint256};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Denoms traded by the pair, e.g. `ucore` and a smart FT issued through fungibleToken.
+    /// Smart FTs with a burn rate or send commission are rejected.
+    pub denoms: [String; 2],
+    pub swap_fee: Decimal,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Deposits both denoms attached and mints LP shares to the sender. The denom provided in
+    /// excess of the pool ratio is refunded.
+    ProvideLiquidity { min_shares: Option<Uint128> },
+    /// Burns the attached LP shares and pays out the matching part of both reserves.
+    WithdrawLiquidity { min_amounts: Option<[Uint128; 2]> },
+    /// Swaps the single attached coin for the other denom of the pair.
+    Swap {
+        min_return: Option<Uint128>,
+        to: Option<String>,
+    },
+    /// Owner only
+    UpdateSwapFee { swap_fee: Decimal },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(PoolResponse)]
+    Pool {},
+    /// Outcome of swapping `offer` right now
+    #[returns(SimulationResponse)]
+    Simulation { offer: Coin },
+    /// TWAP accumulators, brought up to the current block
+    #[returns(CumulativePricesResponse)]
+    CumulativePrices {},
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub denoms: [String; 2],
+    pub lp_denom: String,
+    pub swap_fee: Decimal,
+}
+
+#[cw_serde]
+pub struct PoolResponse {
+    pub assets: Vec<Coin>,
+    pub total_share: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulationResponse {
+    pub return_amount: Coin,
+    /// Part of the offer kept by the pool
+    pub fee_amount: Coin,
+}
+
+/// A TWAP over a window is the difference of two readings divided by the elapsed seconds
+/// and by 1e18.
+#[cw_serde]
+pub struct CumulativePricesResponse {
+    /// Price of `denoms[0]` in `denoms[1]`, and the reverse
+    pub price_cumulative: [Uint256; 2],
+    pub block_time: u64,
+}
@@ -0,0 +1,29 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal, Uint128, Uint256};
+use cw_storage_plus::Item;
+
+#[cw_serde]
+pub struct Config {
+    /// The two traded denoms, bank coins or smart FTs
+    pub denoms: [String; 2],
+    /// Smart FT issued by the pair for liquidity shares
+    pub lp_denom: String,
+    /// Fraction of every offer left in the pool for liquidity providers
+    pub swap_fee: Decimal,
+}
+
+#[cw_serde]
+pub struct Pool {
+    /// Reserves in the order of `Config::denoms`
+    pub reserves: [Uint128; 2],
+    /// Shares ever minted minus burnt, including the locked minimum liquidity
+    pub total_share: Uint128,
+    /// Sum of each denom's price in the other one, scaled by 1e18, times the seconds it held.
+    /// Wraps on overflow like Uniswap v2 accumulators, only differences are meaningful.
+    pub price_cumulative: [Uint256; 2],
+    /// Block time in seconds the accumulators were last updated at
+    pub block_time_last: u64,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const POOL: Item<Pool> = Item::new("pool");
@@ -1,119 +1,287 @@
 use cosmwasm_std::{
-    entry_point, to_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult,
+    entry_point, to_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Empty, Env, MessageInfo,
+    Order, Response, StdResult, Storage,
 };
+use cw_storage_plus::Bound;
+use cw_utils::{Expiration, NativeBalance};
 
 use crate::error::ContractError;
-use crate::msg::{ArbiterResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, CONFIG};
+use crate::msg::{
+    ArbiterResponse, EscrowResponse, EscrowsResponse, ExecuteMsg, InstantiateMsg, MigrateMsg,
+    QueryMsg,
+};
+use crate::state::{
+    Consent, Escrow, EscrowStatus, ESCROWS, LEGACY_CONFIG, NEXT_ESCROW_ID, PARTY_ESCROWS,
+};
 use cw2::set_contract_version;
 
 // Version info, for migration info
-const CONTRACT_NAME: &str = "crates.io:cw20-merkle-airdrop";
+const CONTRACT_NAME: &str = "crates.io:cw-escrow";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    NEXT_ESCROW_ID.save(deps.storage, &1)?;
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    msg: InstantiateMsg,
+    msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    let config = Config {
-        arbiter: deps.api.addr_validate(&msg.arbiter)?,
-        recipient: deps.api.addr_validate(&msg.recipient)?,
-        source: info.sender,
-        expiration: msg.expiration,
-    };
-
-    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    match msg {
+        ExecuteMsg::Create {
+            payee,
+            arbiter,
+            expiration,
+        } => execute_create(deps, env, info, payee, arbiter, expiration),
+        ExecuteMsg::TopUp { id } => execute_top_up(deps, env, info, id),
+        ExecuteMsg::Approve { id, quantity } => execute_approve(deps, env, info, id, quantity),
+        ExecuteMsg::Release { id } => execute_release(deps, env, info, id),
+        ExecuteMsg::Refund { id } => execute_refund(deps, env, info, id),
+    }
+}
 
-    if let Some(expiration) = msg.expiration {
+fn execute_create(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    payee: String,
+    arbiter: Option<String>,
+    expiration: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let payee = deps.api.addr_validate(&payee)?;
+    let arbiter = arbiter
+        .map(|arbiter| deps.api.addr_validate(&arbiter))
+        .transpose()?;
+    if payee == info.sender || arbiter.as_ref() == Some(&info.sender) {
+        return Err(ContractError::InvalidParties {});
+    }
+    if let Some(expiration) = expiration {
         if expiration.is_expired(&env.block) {
             return Err(ContractError::Expired { expiration });
         }
-        let minimum_expiration = env.block.time.plus_seconds(86400); // Example: Require at least 1 hour from now
-        if expiration < minimum_expiration {
-            return Err(ContractError::InvalidExpiration {
-                provided: expiration,
-                minimum: minimum_expiration,
-            });
-        }
     }
-    CONFIG.save(deps.storage, &config)?;
-    Ok(Response::default())
+    let balance = deposit(vec![], &info.funds)?;
+
+    let id = NEXT_ESCROW_ID.load(deps.storage)?;
+    NEXT_ESCROW_ID.save(deps.storage, &(id + 1))?;
+    let escrow = Escrow {
+        payer: info.sender.clone(),
+        payee: payee.clone(),
+        arbiter,
+        balance,
+        expiration,
+        release_consent: Default::default(),
+        refund_consent: Default::default(),
+        status: EscrowStatus::Open,
+    };
+    save_new(deps.storage, id, &escrow)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create")
+        .add_attribute("id", id.to_string())
+        .add_attribute("payer", info.sender)
+        .add_attribute("payee", payee))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(
+fn execute_top_up(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    msg: ExecuteMsg,
+    id: u64,
 ) -> Result<Response, ContractError> {
-    match msg {
-        ExecuteMsg::Approve { quantity } => execute_approve(deps, env, info, quantity),
-        ExecuteMsg::Refund {} => execute_refund(deps, env, info),
+    let mut escrow = load_open(deps.as_ref(), id)?;
+    if info.sender != escrow.payer {
+        return Err(ContractError::Unauthorized {});
+    }
+    // throws error if the escrow is expired
+    if let Some(expiration) = escrow.expiration {
+        if expiration.is_expired(&env.block) {
+            return Err(ContractError::Expired { expiration });
+        }
     }
+
+    escrow.balance = deposit(escrow.balance, &info.funds)?;
+    ESCROWS.save(deps.storage, id, &escrow)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "top_up")
+        .add_attribute("id", id.to_string()))
 }
 
 fn execute_approve(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    id: u64,
     quantity: Option<Vec<Coin>>,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
+    let mut escrow = load_open(deps.as_ref(), id)?;
+    // throws error if the escrow is expired
+    if let Some(expiration) = escrow.expiration {
+        if expiration.is_expired(&env.block) {
+            return Err(ContractError::Expired { expiration });
+        }
+    }
+    if escrow.arbiter.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
 
-        // throws error if the contract is expired
-        if let Some(expiration) = config.expiration {
-            if expiration.is_expired(&env.block) {
-                return Err(ContractError::Expired { expiration });
+    let amount = match quantity {
+        Some(quantity) => {
+            let mut balance = NativeBalance(std::mem::take(&mut escrow.balance));
+            for coin in &quantity {
+                balance = (balance - coin.clone())?;
             }
+            escrow.balance = balance.into_vec();
+            quantity
         }
-    
-    if info.sender != config.arbiter {
-        return Err(ContractError::Unauthorized {});
+        // release everything
+        None => std::mem::take(&mut escrow.balance),
+    };
+    if amount.is_empty() {
+        return Err(ContractError::EmptyBalance {});
     }
+    if escrow.balance.is_empty() {
+        escrow.status = EscrowStatus::Released;
+    }
+    ESCROWS.save(deps.storage, id, &escrow)?;
+    Ok(send_tokens(escrow.payee, amount, "approve").add_attribute("id", id.to_string()))
+}
 
+fn execute_release(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut escrow = load_open(deps.as_ref(), id)?;
+    // throws error if the escrow is expired
+    if let Some(expiration) = escrow.expiration {
+        if expiration.is_expired(&env.block) {
+            return Err(ContractError::Expired { expiration });
+        }
+    }
 
-    let amount = if let Some(quantity) = quantity {
-        quantity
-    } else {
-        // release everything
-        // Querier guarantees to return up-to-date data, including funds sent in this handle message
-        // https://github.com/CosmWasm/wasmd/blob/master/x/wasm/internal/keeper/keeper.go#L185-L192
-        let balance = deps.querier.query_all_balances(&env.contract.address)?;
-        if balance.is_empty() {
-            return Err(ContractError::EmptyBalance {});
+    if escrow.arbiter.as_ref() != Some(&info.sender) {
+        consent(
+            &mut escrow.release_consent,
+            &escrow.payer,
+            &escrow.payee,
+            &info.sender,
+        )?;
+        if !escrow.release_consent.is_mutual() {
+            ESCROWS.save(deps.storage, id, &escrow)?;
+            return Ok(Response::new()
+                .add_attribute("action", "consent_release")
+                .add_attribute("id", id.to_string())
+                .add_attribute("by", info.sender));
         }
-        balance
-    };
-    Ok(send_tokens(config.recipient, amount, "approve"))
+    }
+
+    escrow.status = EscrowStatus::Released;
+    let amount = std::mem::take(&mut escrow.balance);
+    ESCROWS.save(deps.storage, id, &escrow)?;
+    Ok(send_tokens(escrow.payee, amount, "release").add_attribute("id", id.to_string()))
 }
 
-fn execute_refund(deps: DepsMut, env: Env, _info: MessageInfo) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    // anyone can try to refund, as long as the contract is expired
-    if let Some(expiration) = config.expiration {
-        if !expiration.is_expired(&env.block) {
+fn execute_refund(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut escrow = load_open(deps.as_ref(), id)?;
+    // anyone can refund an expired escrow
+    let expired = escrow
+        .expiration
+        .map_or(false, |expiration| expiration.is_expired(&env.block));
+
+    if !expired && escrow.arbiter.as_ref() != Some(&info.sender) {
+        if info.sender != escrow.payer && info.sender != escrow.payee {
             return Err(ContractError::NotExpired {});
         }
-    } else {
-        return Err(ContractError::NotExpired {});
+        consent(
+            &mut escrow.refund_consent,
+            &escrow.payer,
+            &escrow.payee,
+            &info.sender,
+        )?;
+        if !escrow.refund_consent.is_mutual() {
+            ESCROWS.save(deps.storage, id, &escrow)?;
+            return Ok(Response::new()
+                .add_attribute("action", "consent_refund")
+                .add_attribute("id", id.to_string())
+                .add_attribute("by", info.sender));
+        }
     }
 
-    if info.sender != config.arbiter {
-        return Err(ContractError::Unauthorized {});
+    escrow.status = EscrowStatus::Refunded;
+    let amount = std::mem::take(&mut escrow.balance);
+    ESCROWS.save(deps.storage, id, &escrow)?;
+    Ok(send_tokens(escrow.payer, amount, "refund").add_attribute("id", id.to_string()))
+}
+
+// stores a new escrow and indexes it under each of its parties
+fn save_new(storage: &mut dyn Storage, id: u64, escrow: &Escrow) -> StdResult<()> {
+    ESCROWS.save(storage, id, escrow)?;
+    PARTY_ESCROWS.save(storage, (&escrow.payer, id), &Empty {})?;
+    PARTY_ESCROWS.save(storage, (&escrow.payee, id), &Empty {})?;
+    if let Some(arbiter) = &escrow.arbiter {
+        PARTY_ESCROWS.save(storage, (arbiter, id), &Empty {})?;
     }
+    Ok(())
+}
 
-    // Querier guarantees to return up-to-date data, including funds sent in this handle message
-    // https://github.com/CosmWasm/wasmd/blob/master/x/wasm/internal/keeper/keeper.go#L185-L192
-    let balance = deps.querier.query_all_balances(&env.contract.address)?;
+fn load_open(deps: Deps, id: u64) -> Result<Escrow, ContractError> {
+    let escrow = ESCROWS.load(deps.storage, id)?;
+    if escrow.status != EscrowStatus::Open {
+        return Err(ContractError::Settled { id });
+    }
+    Ok(escrow)
+}
+
+// adds the attached funds to a balance; smart-FT denoms are bank coins like any other
+fn deposit(balance: Vec<Coin>, funds: &[Coin]) -> Result<Vec<Coin>, ContractError> {
+    let mut balance = NativeBalance(balance);
+    for coin in funds {
+        balance += coin.clone();
+    }
+    balance.normalize();
     if balance.is_empty() {
         return Err(ContractError::EmptyBalance {});
     }
-    Ok(send_tokens(config.source, balance, "refund"))
+    Ok(balance.into_vec())
+}
+
+// records the consent of the payer or payee, the only parties who can agree to an outcome
+fn consent(
+    consent: &mut Consent,
+    payer: &Addr,
+    payee: &Addr,
+    sender: &Addr,
+) -> Result<(), ContractError> {
+    if sender == payer {
+        consent.payer = true;
+    } else if sender == payee {
+        consent.payee = true;
+    } else {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
 }
 
 // this is a helper to move the tokens, so the business logic is easy to read
@@ -127,271 +295,449 @@ fn send_tokens(to_address: Addr, amount: Vec<Coin>, action: &str) -> Response {
         .add_attribute("to", to_address)
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let legacy = match LEGACY_CONFIG.may_load(deps.storage)? {
+        Some(legacy) => legacy,
+        None => return Ok(Response::new().add_attribute("action", "migrate")),
+    };
+
+    // the single escrow held everything the contract owns; it becomes escrow 1
+    let balance = deps.querier.query_all_balances(&env.contract.address)?;
+    let escrow = Escrow {
+        payer: legacy.source,
+        payee: legacy.recipient,
+        arbiter: Some(legacy.arbiter),
+        status: if balance.is_empty() {
+            EscrowStatus::Released
+        } else {
+            EscrowStatus::Open
+        },
+        balance,
+        expiration: legacy.expiration,
+        release_consent: Default::default(),
+        refund_consent: Default::default(),
+    };
+    save_new(deps.storage, 1, &escrow)?;
+    NEXT_ESCROW_ID.save(deps.storage, &2)?;
+    LEGACY_CONFIG.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("migrated_escrow", "1"))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::Arbiter {} => to_binary(&query_arbiter(deps)?),
+        QueryMsg::Escrow { id } => to_binary(&query_escrow(deps, id)?),
+        QueryMsg::Arbiter { id } => to_binary(&query_arbiter(deps, id)?),
+        QueryMsg::Escrows {
+            party,
+            start_after,
+            limit,
+        } => to_binary(&query_escrows(deps, party, start_after, limit)?),
     }
 }
 
-fn query_arbiter(deps: Deps) -> StdResult<ArbiterResponse> {
-    let config = CONFIG.load(deps.storage)?;
-    let addr = config.arbiter;
-    Ok(ArbiterResponse { arbiter: addr })
+fn query_escrow(deps: Deps, id: u64) -> StdResult<EscrowResponse> {
+    let escrow = ESCROWS.load(deps.storage, id)?;
+    Ok(to_response(id, escrow))
+}
+
+fn query_arbiter(deps: Deps, id: u64) -> StdResult<ArbiterResponse> {
+    let escrow = ESCROWS.load(deps.storage, id)?;
+    Ok(ArbiterResponse {
+        arbiter: escrow.arbiter,
+    })
+}
+
+fn query_escrows(
+    deps: Deps,
+    party: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<EscrowsResponse> {
+    let party = party
+        .map(|party| deps.api.addr_validate(&party))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let escrows = match party {
+        Some(party) => PARTY_ESCROWS
+            .prefix(&party)
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|id| {
+                let id = id?;
+                Ok(to_response(id, ESCROWS.load(deps.storage, id)?))
+            })
+            .collect::<StdResult<Vec<_>>>()?,
+        None => ESCROWS
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(id, escrow)| to_response(id, escrow)))
+            .collect::<StdResult<Vec<_>>>()?,
+    };
+    Ok(EscrowsResponse { escrows })
+}
+
+fn to_response(id: u64, escrow: Escrow) -> EscrowResponse {
+    EscrowResponse {
+        id,
+        payer: escrow.payer,
+        payee: escrow.payee,
+        arbiter: escrow.arbiter,
+        balance: escrow.balance,
+        expiration: escrow.expiration,
+        release_consent: escrow.release_consent,
+        refund_consent: escrow.refund_consent,
+        status: escrow.status,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, CosmosMsg, Timestamp};
-    use cw_utils::Expiration;
-
-    fn init_msg_expire_by_height(expiration: Option<Expiration>) -> InstantiateMsg {
-        InstantiateMsg {
-            arbiter: String::from("verifies"),
-            recipient: String::from("benefits"),
-            expiration,
-        }
-    }
-
-    #[test]
-    fn proper_initialization() {
-        let mut deps = mock_dependencies();
+    use crate::state::LegacyConfig;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{coins, CosmosMsg, OwnedDeps, Timestamp};
 
-        let msg = init_msg_expire_by_height(Some(Expiration::AtHeight(1000)));
+    fn env_at(height: u64) -> Env {
         let mut env = mock_env();
-        env.block.height = 876;
+        env.block.height = height;
         env.block.time = Timestamp::from_seconds(0);
+        env
+    }
+
+    // creates escrow 1: "creator" pays 1000 earth to "benefits", "verifies" arbitrates
+    fn setup(expiration: Option<Expiration>) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            env_at(876),
+            mock_info("creator", &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::Create {
+            payee: String::from("benefits"),
+            arbiter: Some(String::from("verifies")),
+            expiration,
+        };
         let info = mock_info("creator", &coins(1000, "earth"));
+        execute(deps.as_mut(), env_at(876), info, msg).unwrap();
+        deps
+    }
 
-        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+    fn assert_sent(res: &Response, to: &str, amount: Vec<Coin>) {
+        assert_eq!(1, res.messages.len());
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: to.into(),
+                amount,
+            })
+        );
+    }
+
+    #[test]
+    fn proper_initialization() {
+        let deps = setup(Some(Expiration::AtHeight(1000)));
 
-        // it worked, let's query the state
-        let state = CONFIG.load(&deps.storage).unwrap();
+        let escrow = query_escrow(deps.as_ref(), 1).unwrap();
         assert_eq!(
-            state,
-            Config {
-                arbiter: Addr::unchecked("verifies"),
-                recipient: Addr::unchecked("benefits"),
-                source: Addr::unchecked("creator"),
-                expiration: Some(Expiration::AtHeight(1000))
+            escrow,
+            EscrowResponse {
+                id: 1,
+                payer: Addr::unchecked("creator"),
+                payee: Addr::unchecked("benefits"),
+                arbiter: Some(Addr::unchecked("verifies")),
+                balance: coins(1000, "earth"),
+                expiration: Some(Expiration::AtHeight(1000)),
+                release_consent: Consent::default(),
+                refund_consent: Consent::default(),
+                status: EscrowStatus::Open,
             }
         );
     }
 
     #[test]
-    fn cannot_initialize_expired() {
-        let mut deps = mock_dependencies();
+    fn cannot_create_expired_or_empty() {
+        let mut deps = setup(None);
 
-        let msg = init_msg_expire_by_height(Some(Expiration::AtHeight(1000)));
-        let mut env = mock_env();
-        env.block.height = 1001;
-        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Create {
+            payee: String::from("benefits"),
+            arbiter: None,
+            expiration: Some(Expiration::AtHeight(1000)),
+        };
         let info = mock_info("creator", &coins(1000, "earth"));
+        let err = execute(deps.as_mut(), env_at(1001), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Expired { .. }));
 
-        let res = instantiate(deps.as_mut(), env, info, msg);
-        match res.unwrap_err() {
-            ContractError::Expired { .. } => {}
-            e => panic!("unexpected error: {:?}", e),
-        }
+        let err = execute(deps.as_mut(), env_at(900), mock_info("creator", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::EmptyBalance {}));
     }
 
     #[test]
-    fn init_and_query() {
-        let mut deps = mock_dependencies();
+    fn arbiter_releases() {
+        let mut deps = setup(Some(Expiration::AtHeight(1000)));
+
+        // beneficiary cannot release it alone
+        let msg = ExecuteMsg::Release { id: 1 };
+        let res = execute(
+            deps.as_mut(),
+            env_at(900),
+            mock_info("benefits", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(0, res.messages.len());
 
-        let arbiter = Addr::unchecked("arbiters");
-        let recipient = Addr::unchecked("receives");
-        let creator = Addr::unchecked("creates");
-        let msg = InstantiateMsg {
-            arbiter: arbiter.clone().into(),
-            recipient: recipient.into(),
+        // strangers have no say
+        let err = execute(
+            deps.as_mut(),
+            env_at(900),
+            mock_info("anybody", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // verifier cannot release it when expired
+        let err = execute(
+            deps.as_mut(),
+            env_at(1100),
+            mock_info("verifies", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Expired { .. }));
+
+        // complete release by verifier, before expiration
+        let res = execute(
+            deps.as_mut(),
+            env_at(999),
+            mock_info("verifies", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_sent(&res, "benefits", coins(1000, "earth"));
+
+        let err = execute(deps.as_mut(), env_at(999), mock_info("verifies", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Settled { id: 1 }));
+    }
+
+    #[test]
+    fn init_and_query() {
+        let mut deps = setup(None);
+        let msg = ExecuteMsg::Create {
+            payee: String::from("receives"),
+            arbiter: None,
             expiration: None,
         };
-        let mut env = mock_env();
-        env.block.height = 876;
-        env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info(creator.as_str(), &[]);
-        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        execute(deps.as_mut(), env_at(876), mock_info("creates", &coins(10, "earth")), msg).unwrap();
 
         // now let's query
-        let query_response = query_arbiter(deps.as_ref()).unwrap();
-        assert_eq!(query_response.arbiter, arbiter);
+        let query_response = query_arbiter(deps.as_ref(), 1).unwrap();
+        assert_eq!(query_response.arbiter, Some(Addr::unchecked("verifies")));
+        let query_response = query_arbiter(deps.as_ref(), 2).unwrap();
+        assert_eq!(query_response.arbiter, None);
     }
 
     #[test]
     fn execute_approve() {
-        let mut deps = mock_dependencies();
-
-        // initialize the store
-        let init_amount = coins(1000, "earth");
-        let msg = init_msg_expire_by_height(Some(Expiration::AtHeight(1000)));
-        let mut env = mock_env();
-        env.block.height = 876;
-        env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info("creator", &init_amount);
-        let contract_addr = env.clone().contract.address;
-        let init_res = instantiate(deps.as_mut(), env, info, msg).unwrap();
-        assert_eq!(0, init_res.messages.len());
-
-        // balance changed in init
-        deps.querier.update_balance(&contract_addr, init_amount);
+        let mut deps = setup(Some(Expiration::AtHeight(1000)));
 
         // beneficiary cannot release it
-        let msg = ExecuteMsg::Approve { quantity: None };
-        let mut env = mock_env();
-        env.block.height = 900;
-        env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info("beneficiary", &[]);
-        let execute_res = execute(deps.as_mut(), env, info, msg.clone());
-        match execute_res.unwrap_err() {
-            ContractError::Unauthorized { .. } => {}
-            e => panic!("unexpected error: {:?}", e),
-        }
+        let msg = ExecuteMsg::Approve { id: 1, quantity: None };
+        let err = execute(deps.as_mut(), env_at(900), mock_info("benefits", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
 
         // verifier cannot release it when expired
-        let mut env = mock_env();
-        env.block.height = 1100;
-        env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info("verifies", &[]);
-        let execute_res = execute(deps.as_mut(), env, info, msg.clone());
-        match execute_res.unwrap_err() {
-            ContractError::Expired { .. } => {}
-            e => panic!("unexpected error: {:?}", e),
-        }
-
-        // complete release by verifier, before expiration
-        let mut env = mock_env();
-        env.block.height = 999;
-        env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info("verifies", &[]);
-        let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
-        assert_eq!(1, execute_res.messages.len());
-        let msg = execute_res.messages.get(0).expect("no message");
-        assert_eq!(
-            msg.msg,
-            CosmosMsg::Bank(BankMsg::Send {
-                to_address: "benefits".into(),
-                amount: coins(1000, "earth"),
-            })
-        );
+        let err = execute(deps.as_mut(), env_at(1100), mock_info("verifies", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Expired { .. }));
 
         // partial release by verifier, before expiration
         let partial_msg = ExecuteMsg::Approve {
+            id: 1,
             quantity: Some(coins(500, "earth")),
         };
-        let mut env = mock_env();
-        env.block.height = 999;
-        env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info("verifies", &[]);
-        let execute_res = execute(deps.as_mut(), env, info, partial_msg).unwrap();
-        assert_eq!(1, execute_res.messages.len());
-        let msg = execute_res.messages.get(0).expect("no message");
-        assert_eq!(
-            msg.msg,
-            CosmosMsg::Bank(BankMsg::Send {
-                to_address: "benefits".into(),
-                amount: coins(500, "earth"),
-            })
-        );
+        let res = execute(deps.as_mut(), env_at(999), mock_info("verifies", &[]), partial_msg).unwrap();
+        assert_sent(&res, "benefits", coins(500, "earth"));
+        let escrow = query_escrow(deps.as_ref(), 1).unwrap();
+        assert_eq!(escrow.balance, coins(500, "earth"));
+        assert_eq!(escrow.status, EscrowStatus::Open);
+
+        // cannot release more than is left
+        let too_much = ExecuteMsg::Approve {
+            id: 1,
+            quantity: Some(coins(501, "earth")),
+        };
+        execute(deps.as_mut(), env_at(999), mock_info("verifies", &[]), too_much).unwrap_err();
+
+        // complete release by verifier, before expiration
+        let res = execute(deps.as_mut(), env_at(999), mock_info("verifies", &[]), msg.clone()).unwrap();
+        assert_sent(&res, "benefits", coins(500, "earth"));
+        let escrow = query_escrow(deps.as_ref(), 1).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Released);
+
+        let err = execute(deps.as_mut(), env_at(999), mock_info("verifies", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Settled { id: 1 }));
     }
 
     #[test]
-    fn handle_refund() {
+    fn migrate_single_escrow() {
         let mut deps = mock_dependencies();
+        let legacy = LegacyConfig {
+            arbiter: Addr::unchecked("verifies"),
+            recipient: Addr::unchecked("benefits"),
+            source: Addr::unchecked("creator"),
+            expiration: Some(Expiration::AtHeight(1000)),
+        };
+        LEGACY_CONFIG.save(deps.as_mut().storage, &legacy).unwrap();
+        let env = env_at(900);
+        deps.querier.update_balance(&env.contract.address, coins(1000, "earth"));
+
+        migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+        assert!(LEGACY_CONFIG.may_load(&deps.storage).unwrap().is_none());
+        let escrow = query_escrow(deps.as_ref(), 1).unwrap();
+        assert_eq!(escrow.payer, Addr::unchecked("creator"));
+        assert_eq!(escrow.balance, coins(1000, "earth"));
+        assert_eq!(escrow.status, EscrowStatus::Open);
+        let page = query_escrows(deps.as_ref(), Some("verifies".to_string()), None, None).unwrap();
+        assert_eq!(page.escrows.len(), 1);
+
+        // new escrows continue after the migrated one, and migrating again is a no-op
+        let msg = ExecuteMsg::Create {
+            payee: String::from("benefits"),
+            arbiter: None,
+            expiration: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &coins(10, "earth")), msg).unwrap();
+        assert_eq!(res.attributes[1].value, "2");
+        migrate(deps.as_mut(), env, MigrateMsg {}).unwrap();
+        assert_eq!(query_escrow(deps.as_ref(), 1).unwrap().balance, coins(1000, "earth"));
+    }
 
-        // initialize the store
-        let init_amount = coins(1000, "earth");
-        let msg = init_msg_expire_by_height(Some(Expiration::AtHeight(1000)));
-        let mut env = mock_env();
-        env.block.height = 876;
-        env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info("creator", &init_amount);
-        let contract_addr = env.clone().contract.address;
-        let init_res = instantiate(deps.as_mut(), env, info, msg).unwrap();
-        assert_eq!(0, init_res.messages.len());
-
-        // balance changed in init
-        deps.querier.update_balance(&contract_addr, init_amount);
-
-        // cannot release when unexpired (height < Expiration::AtHeight(1000))
-        let msg = ExecuteMsg::Refund {};
-        let mut env = mock_env();
-        env.block.height = 800;
-        env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info("anybody", &[]);
-        let execute_res = execute(deps.as_mut(), env, info, msg);
-        match execute_res.unwrap_err() {
-            ContractError::NotExpired { .. } => {}
-            e => panic!("unexpected error: {:?}", e),
-        }
-
-        // Contract expires when height == Expiration::AtHeight(1000)
-        let msg = ExecuteMsg::Refund {};
-        let mut env = mock_env();
-        env.block.height = 1000;
-        env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info("anybody", &[]);
-        let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
-        assert_eq!(1, execute_res.messages.len());
-        let msg = execute_res.messages.get(0).expect("no message");
-        assert_eq!(
-            msg.msg,
-            CosmosMsg::Bank(BankMsg::Send {
-                to_address: "creator".into(),
-                amount: coins(1000, "earth"),
-            })
+    #[test]
+    fn parties_agree_on_release() {
+        let mut deps = setup(None);
+        let msg = ExecuteMsg::TopUp { id: 1 };
+        execute(
+            deps.as_mut(),
+            env_at(880),
+            mock_info("creator", &coins(500, "ucore")),
+            msg,
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::Release { id: 1 };
+        let res = execute(
+            deps.as_mut(),
+            env_at(900),
+            mock_info("creator", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(0, res.messages.len());
+        let res = execute(deps.as_mut(), env_at(901), mock_info("benefits", &[]), msg).unwrap();
+        assert_sent(
+            &res,
+            "benefits",
+            vec![Coin::new(1000, "earth"), Coin::new(500, "ucore")],
         );
 
-        // anyone can release after expiration
-        let msg = ExecuteMsg::Refund {};
-        let mut env = mock_env();
-        env.block.height = 1001;
-        env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info("anybody", &[]);
-        let execute_res = execute(deps.as_mut(), env, info, msg).unwrap();
-        assert_eq!(1, execute_res.messages.len());
-        let msg = execute_res.messages.get(0).expect("no message");
-        assert_eq!(
-            msg.msg,
-            CosmosMsg::Bank(BankMsg::Send {
-                to_address: "creator".into(),
-                amount: coins(1000, "earth"),
-            })
-        );
+        let escrow = query_escrow(deps.as_ref(), 1).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Released);
+        assert!(escrow.balance.is_empty());
+    }
+
+    #[test]
+    fn handle_refund() {
+        let mut deps = setup(Some(Expiration::AtHeight(1000)));
+
+        // cannot refund when unexpired (height < Expiration::AtHeight(1000))
+        let msg = ExecuteMsg::Refund { id: 1 };
+        let err = execute(
+            deps.as_mut(),
+            env_at(800),
+            mock_info("anybody", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotExpired {}));
+
+        // Contract expires when height == Expiration::AtHeight(1000), then anyone can refund
+        let res = execute(deps.as_mut(), env_at(1000), mock_info("anybody", &[]), msg).unwrap();
+        assert_sent(&res, "creator", coins(1000, "earth"));
     }
 
     #[test]
     fn handle_refund_no_expiration() {
-        let mut deps = mock_dependencies();
+        let mut deps = setup(None);
+
+        // cannot refund when unexpired (no expiration)
+        let msg = ExecuteMsg::Refund { id: 1 };
+        let err = execute(
+            deps.as_mut(),
+            env_at(800),
+            mock_info("anybody", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotExpired {}));
+
+        // both parties agreeing is enough
+        execute(
+            deps.as_mut(),
+            env_at(800),
+            mock_info("benefits", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        let res = execute(deps.as_mut(), env_at(800), mock_info("creator", &[]), msg).unwrap();
+        assert_sent(&res, "creator", coins(1000, "earth"));
+    }
 
-        // initialize the store
-        let init_amount = coins(1000, "earth");
-        let msg = init_msg_expire_by_height(None);
-        let mut env = mock_env();
-        env.block.height = 876;
-        env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info("creator", &init_amount);
-        let contract_addr = env.clone().contract.address;
-        let init_res = instantiate(deps.as_mut(), env, info, msg).unwrap();
-        assert_eq!(0, init_res.messages.len());
+    #[test]
+    fn list_escrows() {
+        let mut deps = setup(None);
+        for payee in ["other", "benefits"] {
+            let msg = ExecuteMsg::Create {
+                payee: payee.to_string(),
+                arbiter: None,
+                expiration: None,
+            };
+            execute(
+                deps.as_mut(),
+                env_at(900),
+                mock_info("creator", &coins(10, "earth")),
+                msg,
+            )
+            .unwrap();
+        }
 
-        // balance changed in init
-        deps.querier.update_balance(&contract_addr, init_amount);
+        let page = query_escrows(deps.as_ref(), None, None, Some(2)).unwrap();
+        assert_eq!(
+            page.escrows.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        let page = query_escrows(deps.as_ref(), None, Some(2), None).unwrap();
+        assert_eq!(
+            page.escrows.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![3]
+        );
 
-        // cannot release when unexpired (no expiration)
-        let msg = ExecuteMsg::Refund {};
-        let mut env = mock_env();
-        env.block.height = 800;
-        env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info("anybody", &[]);
-        let execute_res = execute(deps.as_mut(), env, info, msg);
-        match execute_res.unwrap_err() {
-            ContractError::NotExpired { .. } => {}
-            e => panic!("unexpected error: {:?}", e),
-        }
+        let page = query_escrows(deps.as_ref(), Some("benefits".to_string()), None, None).unwrap();
+        assert_eq!(
+            page.escrows.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
     }
-}
\ No newline at end of file
+}
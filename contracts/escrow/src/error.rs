@@ -17,5 +17,11 @@ pub enum ContractError {
     NotExpired {},
 
     #[error("Balance is empty!")]
-    EmptyBalance {}
-}
\ No newline at end of file
+    EmptyBalance {},
+
+    #[error("Escrow {id} is already settled")]
+    Settled { id: u64 },
+
+    #[error("Payee and arbiter must differ from the payer")]
+    InvalidParties {},
+}
@@ -2,37 +2,81 @@ use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Coin};
 use cw_utils::Expiration;
 
+use crate::state::{Consent, EscrowStatus};
+
 #[cw_serde]
-pub struct InstantiateMsg {
-    pub arbiter: String,
-    pub recipient: String,
-    /// When end height set and block height exceeds this value, the escrow is expired.
-    /// Once an escrow is expired, it can be returned to the original funder (via "refund").
-    ///
-    /// When end time (in seconds since epoch 00:00:00 UTC on 1 January 1970) is set and
-    /// block time exceeds this value, the escrow is expired.
-    /// Once an escrow is expired, it can be returned to the original funder (via "refund").
-    pub expiration: Option<Expiration>,
-}
+pub struct InstantiateMsg {}
 
 #[cw_serde]
 pub enum ExecuteMsg {
+    /// Opens an escrow holding the attached native or smart-FT funds, with the sender as payer.
+    Create {
+        payee: String,
+        /// Can settle the escrow on its own, either way.
+        arbiter: Option<String>,
+        /// When end height set and block height exceeds this value, the escrow is expired.
+        /// Once an escrow is expired, it can be returned to the original funder (via "refund").
+        ///
+        /// When end time (in seconds since epoch 00:00:00 UTC on 1 January 1970) is set and
+        /// block time exceeds this value, the escrow is expired.
+        /// Once an escrow is expired, it can be returned to the original funder (via "refund").
+        expiration: Option<Expiration>,
+    },
+    /// Adds the attached funds to an open escrow; payer only.
+    TopUp { id: u64 },
+    /// Arbiter only, pays `quantity` out to the payee; if quantity is None, releases the whole
+    /// balance.
     Approve {
-        // release some coins - if quantity is None, release all coins in balance
+        id: u64,
         quantity: Option<Vec<Coin>>,
     },
-    Refund {},
+    /// Pays the escrow out to the payee once both payer and payee agreed, or at once when sent
+    /// by the arbiter.
+    Release { id: u64 },
+    /// Returns the escrow to the payer once both payer and payee agreed, at once when sent by
+    /// the arbiter, or by anyone after expiration.
+    Refund { id: u64 },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
-    /// Returns a human-readable representation of the arbiter.
+    #[returns(EscrowResponse)]
+    Escrow { id: u64 },
+    /// Returns a human-readable representation of the escrow's arbiter.
     #[returns(ArbiterResponse)]
-    Arbiter {},
+    Arbiter { id: u64 },
+    /// Escrows in id order, optionally only those `party` is payer, payee or arbiter of.
+    #[returns(EscrowsResponse)]
+    Escrows {
+        party: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub struct ArbiterResponse {
-    pub arbiter: Addr,
-}
\ No newline at end of file
+    pub arbiter: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct EscrowResponse {
+    pub id: u64,
+    pub payer: Addr,
+    pub payee: Addr,
+    pub arbiter: Option<Addr>,
+    pub balance: Vec<Coin>,
+    pub expiration: Option<Expiration>,
+    pub release_consent: Consent,
+    pub refund_consent: Consent,
+    pub status: EscrowStatus,
+}
+
+#[cw_serde]
+pub struct EscrowsResponse {
+    pub escrows: Vec<EscrowResponse>,
+}
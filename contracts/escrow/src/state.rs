@@ -1,15 +1,55 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Coin, Empty};
+use cw_storage_plus::{Item, Map};
 use cw_utils::Expiration;
 
 #[cw_serde]
-pub struct Config {
+pub enum EscrowStatus {
+    Open,
+    Released,
+    Refunded,
+}
+
+/// Which of the two parties have agreed to an outcome
+#[cw_serde]
+#[derive(Default)]
+pub struct Consent {
+    pub payer: bool,
+    pub payee: bool,
+}
+
+impl Consent {
+    pub fn is_mutual(&self) -> bool {
+        self.payer && self.payee
+    }
+}
+
+#[cw_serde]
+pub struct Escrow {
+    pub payer: Addr,
+    pub payee: Addr,
+    pub arbiter: Option<Addr>,
+    /// Native and smart-FT coins held for this escrow
+    pub balance: Vec<Coin>,
+    /// Once expired the escrow can no longer be released, only refunded to the payer
+    pub expiration: Option<Expiration>,
+    pub release_consent: Consent,
+    pub refund_consent: Consent,
+    pub status: EscrowStatus,
+}
+
+pub const NEXT_ESCROW_ID: Item<u64> = Item::new("next_escrow_id");
+pub const ESCROWS: Map<u64, Escrow> = Map::new("escrows");
+/// (party, escrow id) for every payer, payee and arbiter, so a party's escrows can be paged
+pub const PARTY_ESCROWS: Map<(&Addr, u64), Empty> = Map::new("party_escrows");
+
+/// Single escrow kept by the contract before it held many; migrated into escrow 1
+#[cw_serde]
+pub struct LegacyConfig {
     pub arbiter: Addr,
     pub recipient: Addr,
     pub source: Addr,
     pub expiration: Option<Expiration>,
 }
 
-pub const CONFIG_KEY: &str = "config";
-pub const CONFIG: Item<Config> = Item::new(CONFIG_KEY);
\ No newline at end of file
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
@@ -1,15 +1,23 @@
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, CustomMsg, RequestFlashLoan, RepayFlashLoan};
-use crate::state::{State, STATE};
+use crate::msg::{ExecuteMsg, InstantiateMsg, LoanEntry, QueryMsg, QuoteFeeResponse, RecentLoansResponse, CustomMsg, RequestFlashLoan, RepayFlashLoan};
+use crate::state::{LoanRecord, State, BORROWERS, FEES_ACCRUED, LOANS, LOAN_CAPS, LOAN_COUNT, OUTSTANDING_LOANS, PREMIUM_RATES, STATE, TOTAL_VOLUME};
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128, CosmosMsg, BankMsg, Coin, StdError,
+    entry_point, to_binary, Binary, Deps, DepsMut, Env, Event, MessageInfo, Order, Response, StdResult, Storage, Uint128, CosmosMsg, BankMsg, Coin, StdError,
 };
 use cw2::set_contract_version;
+use cw_storage_plus::Bound;
 use coreum_wasm_sdk::core::{CoreumMsg, CoreumQueries};
 
 const CONTRACT_NAME: &str = "flash-loan";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Denominator of basis-point rates.
+const BPS_DENOMINATOR: u16 = 10_000;
+
+/// Default and maximum page sizes of list queries.
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 /// Initialize the contract with the given state and save it in storage.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -21,14 +29,29 @@ pub fn instantiate(
     // Set the contract version in storage
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    // Create a new state object with the provided owner and lending pool addresses
+    // The premium cap and the protocol share are both fractions of a whole
+    if msg.max_premium_bps > BPS_DENOMINATOR {
+        return Err(ContractError::InvalidRate { rate_bps: msg.max_premium_bps, max_bps: BPS_DENOMINATOR });
+    }
+    if msg.protocol_fee_bps > BPS_DENOMINATOR {
+        return Err(ContractError::InvalidRate { rate_bps: msg.protocol_fee_bps, max_bps: BPS_DENOMINATOR });
+    }
+
+    // Create a new state object with the provided owner, lending pool and fee parameters
     let state = State {
         owner: deps.api.addr_validate(&msg.owner)?,
         lending_pool: deps.api.addr_validate(&msg.lending_pool)?,
+        treasury: deps.api.addr_validate(&msg.treasury)?,
+        max_premium_bps: msg.max_premium_bps,
+        protocol_fee_bps: msg.protocol_fee_bps,
+        guardian: None,
+        paused: false,
+        allowlist_enabled: false,
     };
 
     // Save the state in storage
     STATE.save(deps.storage, &state)?;
+    LOAN_COUNT.save(deps.storage, &0)?;
 
     // Return a response with attributes
     Ok(Response::new()
@@ -40,91 +63,173 @@ pub fn instantiate(
 #[entry_point]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response<CustomMsg>, ContractError> {
     match msg {
         // Route RequestFlashLoan message
-        ExecuteMsg::RequestFlashLoan { token, amount, collateral } => request_flash_loan(deps, info, token, amount, collateral),
+        ExecuteMsg::RequestFlashLoan { loans } => request_flash_loan(deps, info, loans),
         // Route ExecuteOperation message
-        ExecuteMsg::ExecuteOperation { token, amount, premium } => execute_operation(deps, info, token, amount, premium),
+        ExecuteMsg::ExecuteOperation { loans } => execute_operation(deps, env, info, loans),
+        // Route SetPremiumRate message
+        ExecuteMsg::SetPremiumRate { denom, rate_bps } => set_premium_rate(deps, info, denom, rate_bps),
+        // Route SetProtocolFee message
+        ExecuteMsg::SetProtocolFee { protocol_fee_bps, treasury } => set_protocol_fee(deps, info, protocol_fee_bps, treasury),
+        // Route SetLoanCap message
+        ExecuteMsg::SetLoanCap { denom, cap } => set_loan_cap(deps, info, denom, cap),
+        // Route SetAllowlistEnabled message
+        ExecuteMsg::SetAllowlistEnabled { enabled } => set_allowlist_enabled(deps, info, enabled),
+        // Route UpdateBorrower message
+        ExecuteMsg::UpdateBorrower { borrower, allowed } => update_borrower(deps, info, borrower, allowed),
+        // Route SetGuardian message
+        ExecuteMsg::SetGuardian { guardian } => set_guardian(deps, info, guardian),
+        // Route Pause message
+        ExecuteMsg::Pause {} => set_paused(deps, info, true),
+        // Route Unpause message
+        ExecuteMsg::Unpause {} => set_paused(deps, info, false),
         // Route Withdraw message
         ExecuteMsg::Withdraw { token } => withdraw(deps, info, token),
     }
 }
 
-/// Handle a request for a flash loan.
+/// Handle a request for a flash loan of one or more denoms.
 pub fn request_flash_loan(
     deps: DepsMut,
     info: MessageInfo,
-    token: String,
-    amount: Uint128,
-    collateral: Uint128,
+    loans: Vec<Coin>,
 ) -> Result<Response<CustomMsg>, ContractError> {
     // Load the contract state
     let state = STATE.load(deps.storage)?;
 
-    // Transfer collateral to the contract
-    let collateral_transfer = BankMsg::Send {
-        to_address: state.lending_pool.clone().into(),
-        amount: vec![Coin { denom: token.clone(), amount: collateral }],
-    };
+    // Every denom must have a configured premium and pass the risk controls
+    assert_valid_loans(&loans)?;
+    for loan in &loans {
+        premium_rate(deps.storage, &loan.denom)?;
+        assert_can_borrow(deps.storage, &state, &info, &loan.denom, loan.amount)?;
+    }
 
-    // Create a custom flash loan request message
+    // Remember what was lent so only these loans can be repaid and recorded
+    for loan in &loans {
+        OUTSTANDING_LOANS.save(deps.storage, (&info.sender, &loan.denom), &loan.amount)?;
+    }
+
+    // Create a single custom flash loan request message for all denoms
     let flash_loan_request = CustomMsg::RequestFlashLoan(RequestFlashLoan {
         recipient: info.sender.to_string(),
-        token: token.clone(),
-        amount,
+        loans,
     });
 
+    // Transfer the attached collateral to the lending pool
+    let mut response = Response::new().add_attribute("method", "request_flash_loan");
+    if !info.funds.is_empty() {
+        let collateral_transfer = BankMsg::Send {
+            to_address: state.lending_pool.into(),
+            amount: info.funds,
+        };
+        response = response.add_message(CosmosMsg::Bank(collateral_transfer));
+    }
+
     // Return a response with the transfer and custom messages
-    Ok(Response::new()
-        .add_attribute("method", "request_flash_loan")
-        .add_message(CosmosMsg::Bank(collateral_transfer))
-        .add_message(CosmosMsg::Custom(flash_loan_request)))
+    Ok(response.add_message(CosmosMsg::Custom(flash_loan_request)))
 }
 
-/// Execute the flash loan operation, ensuring repayment with premium.
+/// Execute the flash loan operation, verifying the combined repayment of every loan with the configured premium.
 pub fn execute_operation(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    token: String,
-    amount: Uint128,
-    premium: Uint128,
+    loans: Vec<Coin>,
 ) -> Result<Response<CustomMsg>, ContractError> {
     // Load the contract state
-    let _state = STATE.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+    assert_valid_loans(&loans)?;
+
+    let mut repayments = vec![];
+    let mut protocol_fees = vec![];
+    let mut response = Response::new().add_attribute("method", "execute_operation");
+    for loan in &loans {
+        // Only a loan requested by the sender can be repaid, and only once
+        let key = (&info.sender, loan.denom.as_str());
+        if OUTSTANDING_LOANS.may_load(deps.storage, key)? != Some(loan.amount) {
+            return Err(ContractError::NoOutstandingLoan { denom: loan.denom.clone(), amount: loan.amount });
+        }
+        OUTSTANDING_LOANS.remove(deps.storage, key);
+
+        // Split the premium between the lending pool and the treasury
+        let quote = quote_fee(deps.storage, &state, &loan.denom, loan.amount)?;
 
-    // Calculate the total repayment amount
-    let repay_amount = amount + premium;
+        // The pool is repaid the principal plus its share of the premium
+        let repay_amount = loan.amount + quote.lp_fee;
 
-    // Create a custom repay flash loan message
+        // Query the sender's balance to ensure sufficient funds
+        let balance = deps.querier.query_balance(&info.sender, &loan.denom)?;
+        if balance.amount < repay_amount {
+            return Err(ContractError::Std(StdError::generic_err(format!("Insufficient {} to repay loan with premium", loan.denom))));
+        }
+
+        repayments.push(Coin { denom: loan.denom.clone(), amount: repay_amount });
+        if !quote.protocol_fee.is_zero() {
+            protocol_fees.push(Coin { denom: loan.denom.clone(), amount: quote.protocol_fee });
+        }
+
+        // Record the repaid loan for statistics
+        let loan_id = record_loan(deps.storage, LoanRecord {
+            borrower: info.sender.clone(),
+            denom: loan.denom.clone(),
+            amount: loan.amount,
+            fee: quote.premium,
+            height: env.block.height,
+        })?;
+        response = response
+            .add_attribute("loan_id", loan_id.to_string())
+            .add_attribute("premium", Coin { denom: loan.denom.clone(), amount: quote.premium }.to_string());
+    }
+
+    // The protocol shares of the premiums are paid with the call and forwarded to the treasury
+    let mut attached = info.funds.clone();
+    attached.sort_by(|a, b| a.denom.cmp(&b.denom));
+    let mut expected = protocol_fees.clone();
+    expected.sort_by(|a, b| a.denom.cmp(&b.denom));
+    if attached != expected {
+        let expected = expected.iter().map(Coin::to_string).collect::<Vec<_>>().join(",");
+        return Err(ContractError::InvalidFee { expected });
+    }
+    if !protocol_fees.is_empty() {
+        response = response.add_message(CosmosMsg::Bank(BankMsg::Send { to_address: state.treasury.into(), amount: protocol_fees }));
+    }
+
+    // Create a single custom repay flash loan message for all denoms
     let repay_msg = CustomMsg::RepayFlashLoan(RepayFlashLoan {
         sender: info.sender.to_string(),
-        token: token.clone(),
-        amount: repay_amount,
+        amounts: repayments.clone(),
     });
 
-    // Query the sender's balance to ensure sufficient funds
-    let balance = deps.querier.query_balance(&info.sender, &token)?;
-    if balance.amount < repay_amount {
-        return Err(ContractError::Std(StdError::generic_err("Insufficient funds to repay loan with premium")));
-    }
-
-    // Return the collateral if the loan is repaid
+    // Return the collateral if the loans are repaid
     let return_collateral = BankMsg::Send {
         to_address: info.sender.into(),
-        amount: vec![Coin { denom: token.clone(), amount: repay_amount }],
+        amount: repayments,
     };
 
-    // Return a response with the repay and collateral return messages
-    Ok(Response::new()
-        .add_attribute("method", "execute_operation")
+    // Return a response with the repay, fee and collateral return messages
+    Ok(response
         .add_message(CosmosMsg::Custom(repay_msg))
         .add_message(CosmosMsg::Bank(return_collateral)))
 }
 
+/// Reject empty loan lists, zero amounts and repeated denoms.
+fn assert_valid_loans(loans: &[Coin]) -> Result<(), ContractError> {
+    if loans.is_empty() {
+        return Err(ContractError::InvalidLoans {});
+    }
+    for (i, loan) in loans.iter().enumerate() {
+        if loan.amount.is_zero() || loans[..i].iter().any(|other| other.denom == loan.denom) {
+            return Err(ContractError::InvalidLoans {});
+        }
+    }
+    Ok(())
+}
+
 /// Withdraw the specified token's balance if the sender is the contract owner.
 fn withdraw(
     deps: DepsMut,
@@ -154,6 +259,222 @@ fn withdraw(
         .add_message(CosmosMsg::Bank(withdraw_msg)))
 }
 
+/// Set the premium rate of a denom if the sender is the contract owner.
+fn set_premium_rate(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    rate_bps: u16,
+) -> Result<Response<CustomMsg>, ContractError> {
+    // Load the contract state
+    let state = STATE.load(deps.storage)?;
+
+    // Ensure the sender is the contract owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Keep the rate under the cap fixed at instantiation
+    if rate_bps > state.max_premium_bps {
+        return Err(ContractError::InvalidRate { rate_bps, max_bps: state.max_premium_bps });
+    }
+
+    // Save the new rate
+    PREMIUM_RATES.save(deps.storage, &denom, &rate_bps)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_premium_rate")
+        .add_attribute("denom", denom)
+        .add_attribute("rate_bps", rate_bps.to_string()))
+}
+
+/// Set the treasury share of premiums, and optionally the treasury, if the sender is the contract owner.
+fn set_protocol_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    protocol_fee_bps: u16,
+    treasury: Option<String>,
+) -> Result<Response<CustomMsg>, ContractError> {
+    // Load the contract state
+    let mut state = STATE.load(deps.storage)?;
+
+    // Ensure the sender is the contract owner
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // The protocol share cannot exceed the whole premium
+    if protocol_fee_bps > BPS_DENOMINATOR {
+        return Err(ContractError::InvalidRate { rate_bps: protocol_fee_bps, max_bps: BPS_DENOMINATOR });
+    }
+
+    // Update the fee split and save the state
+    state.protocol_fee_bps = protocol_fee_bps;
+    if let Some(treasury) = treasury {
+        state.treasury = deps.api.addr_validate(&treasury)?;
+    }
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_protocol_fee")
+        .add_attribute("protocol_fee_bps", protocol_fee_bps.to_string())
+        .add_attribute("treasury", state.treasury))
+}
+
+/// Set or clear the maximum loan size of a denom if the sender is the contract owner.
+fn set_loan_cap(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    cap: Option<Uint128>,
+) -> Result<Response<CustomMsg>, ContractError> {
+    // Ensure the sender is the contract owner
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Save or clear the cap
+    match cap {
+        Some(cap) => LOAN_CAPS.save(deps.storage, &denom, &cap)?,
+        None => LOAN_CAPS.remove(deps.storage, &denom),
+    }
+
+    let event = Event::new("flash-loan-cap")
+        .add_attribute("denom", denom)
+        .add_attribute("cap", cap.map(|cap| cap.to_string()).unwrap_or_else(|| "none".to_string()));
+    Ok(Response::new().add_attribute("method", "set_loan_cap").add_event(event))
+}
+
+/// Enable or disable the borrower allowlist if the sender is the contract owner.
+fn set_allowlist_enabled(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response<CustomMsg>, ContractError> {
+    // Ensure the sender is the contract owner
+    let mut state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    state.allowlist_enabled = enabled;
+    STATE.save(deps.storage, &state)?;
+
+    let event = Event::new("flash-loan-allowlist").add_attribute("enabled", enabled.to_string());
+    Ok(Response::new().add_attribute("method", "set_allowlist_enabled").add_event(event))
+}
+
+/// Add or remove a borrower contract from the allowlist if the sender is the contract owner.
+fn update_borrower(
+    deps: DepsMut,
+    info: MessageInfo,
+    borrower: String,
+    allowed: bool,
+) -> Result<Response<CustomMsg>, ContractError> {
+    // Ensure the sender is the contract owner
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let borrower = deps.api.addr_validate(&borrower)?;
+    if allowed {
+        BORROWERS.save(deps.storage, &borrower, &true)?;
+    } else {
+        BORROWERS.remove(deps.storage, &borrower);
+    }
+
+    let event = Event::new("flash-loan-borrower")
+        .add_attribute("borrower", borrower)
+        .add_attribute("allowed", allowed.to_string());
+    Ok(Response::new().add_attribute("method", "update_borrower").add_event(event))
+}
+
+/// Set or clear the guardian if the sender is the contract owner.
+fn set_guardian(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardian: Option<String>,
+) -> Result<Response<CustomMsg>, ContractError> {
+    // Ensure the sender is the contract owner
+    let mut state = STATE.load(deps.storage)?;
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    state.guardian = guardian.map(|guardian| deps.api.addr_validate(&guardian)).transpose()?;
+    STATE.save(deps.storage, &state)?;
+
+    let event = Event::new("flash-loan-guardian")
+        .add_attribute("guardian", state.guardian.map(String::from).unwrap_or_else(|| "none".to_string()));
+    Ok(Response::new().add_attribute("method", "set_guardian").add_event(event))
+}
+
+/// Pause or resume new loans; the guardian may only pause.
+fn set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response<CustomMsg>, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+
+    // The owner can always toggle the switch, the guardian can only pull it
+    let is_guardian = state.guardian.as_ref() == Some(&info.sender);
+    if info.sender != state.owner && !(paused && is_guardian) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    state.paused = paused;
+    STATE.save(deps.storage, &state)?;
+
+    let event = Event::new(if paused { "flash-loan-paused" } else { "flash-loan-unpaused" })
+        .add_attribute("by", info.sender);
+    Ok(Response::new().add_attribute("method", if paused { "pause" } else { "unpause" }).add_event(event))
+}
+
+/// Reject loans while paused, above the denom cap, or from borrowers missing from an enabled allowlist.
+fn assert_can_borrow(storage: &dyn Storage, state: &State, info: &MessageInfo, denom: &str, amount: Uint128) -> Result<(), ContractError> {
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    if let Some(cap) = LOAN_CAPS.may_load(storage, denom)? {
+        if amount > cap {
+            return Err(ContractError::LoanCapExceeded { denom: denom.to_string(), cap });
+        }
+    }
+    if state.allowlist_enabled && !BORROWERS.has(storage, &info.sender) {
+        return Err(ContractError::BorrowerNotAllowed {});
+    }
+    Ok(())
+}
+
+/// Store a repaid loan and add it to the per-denom totals, returning its id.
+fn record_loan(storage: &mut dyn Storage, loan: LoanRecord) -> StdResult<u64> {
+    let id = LOAN_COUNT.load(storage)?;
+    LOAN_COUNT.save(storage, &(id + 1))?;
+
+    TOTAL_VOLUME.update(storage, &loan.denom, |volume| -> StdResult<_> { Ok(volume.unwrap_or_default().checked_add(loan.amount)?) })?;
+    FEES_ACCRUED.update(storage, &loan.denom, |fees| -> StdResult<_> { Ok(fees.unwrap_or_default().checked_add(loan.fee)?) })?;
+    LOANS.save(storage, id, &loan)?;
+    Ok(id)
+}
+
+/// Load the premium rate of a denom, rejecting denoms that were never configured.
+fn premium_rate(storage: &dyn Storage, denom: &str) -> Result<u16, ContractError> {
+    PREMIUM_RATES
+        .may_load(storage, denom)?
+        .ok_or_else(|| ContractError::UnsupportedDenom { denom: denom.to_string() })
+}
+
+/// Compute the premium owed on a loan and its split between the lending pool and the treasury.
+fn quote_fee(storage: &dyn Storage, state: &State, denom: &str, amount: Uint128) -> Result<QuoteFeeResponse, ContractError> {
+    let rate_bps = premium_rate(storage, denom)?;
+    let premium = amount.multiply_ratio(rate_bps, BPS_DENOMINATOR);
+    let protocol_fee = premium.multiply_ratio(state.protocol_fee_bps, BPS_DENOMINATOR);
+    Ok(QuoteFeeResponse { rate_bps, premium, lp_fee: premium - protocol_fee, protocol_fee })
+}
+
 /// Handle query messages and route them to the appropriate function.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps<CoreumQueries>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
@@ -162,6 +483,18 @@ pub fn query(deps: Deps<CoreumQueries>, _env: Env, msg: QueryMsg) -> StdResult<B
         QueryMsg::LoanInfo {} => loan_info(deps),
         // Route GetBalance query
         QueryMsg::GetBalance { token } => query_balance(deps, token),
+        // Route LoanCap query
+        QueryMsg::LoanCap { denom } => to_binary(&LOAN_CAPS.may_load(deps.storage, &denom)?),
+        // Route IsAllowedBorrower query
+        QueryMsg::IsAllowedBorrower { borrower } => to_binary(&BORROWERS.has(deps.storage, &deps.api.addr_validate(&borrower)?)),
+        // Route RecentLoans query
+        QueryMsg::RecentLoans { start_before, limit } => query_recent_loans(deps, start_before, limit),
+        // Route TotalVolume query
+        QueryMsg::TotalVolume { denom } => to_binary(&TOTAL_VOLUME.may_load(deps.storage, &denom)?.unwrap_or_default()),
+        // Route FeesAccrued query
+        QueryMsg::FeesAccrued { denom } => to_binary(&FEES_ACCRUED.may_load(deps.storage, &denom)?.unwrap_or_default()),
+        // Route QuoteFee query
+        QueryMsg::QuoteFee { denom, amount } => query_quote_fee(deps, denom, amount),
     }
 }
 
@@ -181,4 +514,194 @@ fn query_balance(deps: Deps<CoreumQueries>, token: String) -> StdResult<Binary>
 
     // Return the balance amount as binary
     to_binary(&balance.amount)
-}
\ No newline at end of file
+}
+
+/// Query the premium owed on a loan of the given denom and amount.
+fn query_quote_fee(deps: Deps<CoreumQueries>, denom: String, amount: Uint128) -> StdResult<Binary> {
+    // Load the contract state
+    let state = STATE.load(deps.storage)?;
+
+    // Quote the fee, surfacing unsupported denoms as query errors
+    let quote = quote_fee(deps.storage, &state, &denom, amount).map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    // Return the quote as binary
+    to_binary(&quote)
+}
+
+/// Query repaid loans, newest first, starting before the given id.
+fn query_recent_loans(deps: Deps<CoreumQueries>, start_before: Option<u64>, limit: Option<u32>) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let end = start_before.map(Bound::exclusive);
+
+    // Walk the loans backwards from the most recent one
+    let loans = LOANS
+        .range(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .map(|item| item.map(|(id, loan)| LoanEntry { id, loan }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // Return the page as binary
+    to_binary(&RecentLoansResponse { loans })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{coin, coins, Addr, OwnedDeps};
+
+    type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+    fn setup() -> TestDeps {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            owner: "owner".to_string(),
+            lending_pool: "pool".to_string(),
+            treasury: "treasury".to_string(),
+            max_premium_bps: 100,
+            protocol_fee_bps: 2_000,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        let msg = ExecuteMsg::SetPremiumRate { denom: "ucore".to_string(), rate_bps: 50 };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        deps
+    }
+
+    fn request(deps: &mut TestDeps, borrower: &str, amount: u128) -> Result<Response<CustomMsg>, ContractError> {
+        let msg = ExecuteMsg::RequestFlashLoan { loans: coins(amount, "ucore") };
+        execute(deps.as_mut(), mock_env(), mock_info(borrower, &[]), msg)
+    }
+
+    // repays a loan of 10_000ucore, whose 50ucore premium sends 10ucore to the treasury
+    fn repay(deps: &mut TestDeps, borrower: &str) -> Result<Response<CustomMsg>, ContractError> {
+        deps.querier.update_balance(borrower, coins(10_040, "ucore"));
+        let msg = ExecuteMsg::ExecuteOperation { loans: coins(10_000, "ucore") };
+        execute(deps.as_mut(), mock_env(), mock_info(borrower, &coins(10, "ucore")), msg)
+    }
+
+    #[test]
+    fn only_requested_loans_are_repaid() {
+        let mut deps = setup();
+
+        // nothing was lent, so nothing can be recorded
+        let err = repay(&mut deps, "borrower").unwrap_err();
+        assert!(matches!(err, ContractError::NoOutstandingLoan { .. }));
+
+        request(&mut deps, "borrower", 10_000).unwrap();
+        // another address can't settle the borrower's loan, nor a different amount be repaid
+        let err = repay(&mut deps, "intruder").unwrap_err();
+        assert!(matches!(err, ContractError::NoOutstandingLoan { .. }));
+        deps.querier.update_balance("borrower", coins(20_000, "ucore"));
+        let msg = ExecuteMsg::ExecuteOperation { loans: coins(5_000, "ucore") };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("borrower", &coins(5, "ucore")), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoOutstandingLoan { .. }));
+
+        let res = repay(&mut deps, "borrower").unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send { to_address: "treasury".to_string(), amount: vec![coin(10, "ucore")] }));
+        assert!(!OUTSTANDING_LOANS.has(&deps.storage, (&Addr::unchecked("borrower"), "ucore")));
+
+        // the loan is settled once
+        let err = repay(&mut deps, "borrower").unwrap_err();
+        assert!(matches!(err, ContractError::NoOutstandingLoan { .. }));
+        assert_eq!(LOAN_COUNT.load(&deps.storage).unwrap(), 1);
+    }
+
+    #[test]
+    fn loan_caps_limit_requests() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::SetLoanCap { denom: "ucore".to_string(), cap: Some(Uint128::new(1_000)) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("borrower", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+        let err = request(&mut deps, "borrower", 1_001).unwrap_err();
+        assert!(matches!(err, ContractError::LoanCapExceeded { cap, .. } if cap == Uint128::new(1_000)));
+        request(&mut deps, "borrower", 1_000).unwrap();
+
+        // clearing the cap lifts the limit
+        let msg = ExecuteMsg::SetLoanCap { denom: "ucore".to_string(), cap: None };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        request(&mut deps, "borrower", 1_000_000).unwrap();
+    }
+
+    #[test]
+    fn paused_contract_rejects_requests() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::SetGuardian { guardian: Some("guardian".to_string()) };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("borrower", &[]), ExecuteMsg::Pause {}).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), ExecuteMsg::Pause {}).unwrap();
+        let err = request(&mut deps, "borrower", 10_000).unwrap_err();
+        assert!(matches!(err, ContractError::Paused {}));
+
+        // the guardian can only pull the switch, the owner resumes lending
+        let err = execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), ExecuteMsg::Unpause {}).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), ExecuteMsg::Unpause {}).unwrap();
+        request(&mut deps, "borrower", 10_000).unwrap();
+    }
+
+    #[test]
+    fn allowlist_limits_borrowers() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::SetAllowlistEnabled { enabled: true };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        let err = request(&mut deps, "borrower", 10_000).unwrap_err();
+        assert!(matches!(err, ContractError::BorrowerNotAllowed {}));
+
+        let msg = ExecuteMsg::UpdateBorrower { borrower: "borrower".to_string(), allowed: true };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("borrower", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        request(&mut deps, "borrower", 10_000).unwrap();
+
+        let msg = ExecuteMsg::UpdateBorrower { borrower: "borrower".to_string(), allowed: false };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        let err = request(&mut deps, "borrower", 10_000).unwrap_err();
+        assert!(matches!(err, ContractError::BorrowerNotAllowed {}));
+
+        // disabling the allowlist opens lending to everyone again
+        let msg = ExecuteMsg::SetAllowlistEnabled { enabled: false };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        request(&mut deps, "borrower", 10_000).unwrap();
+    }
+
+    #[test]
+    fn stats_follow_repaid_loans() {
+        let mut deps = setup();
+        let volume = |deps: &TestDeps| TOTAL_VOLUME.may_load(&deps.storage, "ucore").unwrap().unwrap_or_default();
+        let fees = |deps: &TestDeps| FEES_ACCRUED.may_load(&deps.storage, "ucore").unwrap().unwrap_or_default();
+
+        // requesting alone records nothing
+        request(&mut deps, "borrower", 10_000).unwrap();
+        assert_eq!(volume(&deps), Uint128::zero());
+        assert_eq!(LOAN_COUNT.load(&deps.storage).unwrap(), 0);
+
+        let mut env = mock_env();
+        env.block.height = 42;
+        deps.querier.update_balance("borrower", coins(10_040, "ucore"));
+        let msg = ExecuteMsg::ExecuteOperation { loans: coins(10_000, "ucore") };
+        execute(deps.as_mut(), env, mock_info("borrower", &coins(10, "ucore")), msg).unwrap();
+        assert_eq!(volume(&deps), Uint128::new(10_000));
+        assert_eq!(fees(&deps), Uint128::new(50));
+        assert_eq!(
+            LOANS.load(&deps.storage, 0).unwrap(),
+            LoanRecord { borrower: Addr::unchecked("borrower"), denom: "ucore".to_string(), amount: Uint128::new(10_000), fee: Uint128::new(50), height: 42 }
+        );
+
+        // a second cycle adds to the totals
+        request(&mut deps, "borrower", 10_000).unwrap();
+        repay(&mut deps, "borrower").unwrap();
+        assert_eq!(volume(&deps), Uint128::new(20_000));
+        assert_eq!(fees(&deps), Uint128::new(100));
+        assert_eq!(LOAN_COUNT.load(&deps.storage).unwrap(), 2);
+
+        // a repayment must carry the protocol fee
+        request(&mut deps, "borrower", 10_000).unwrap();
+        let msg = ExecuteMsg::ExecuteOperation { loans: coins(10_000, "ucore") };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("borrower", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFee { .. }));
+    }
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,6 +9,30 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Rate of {rate_bps} bps exceeds the cap of {max_bps} bps")]
+    InvalidRate { rate_bps: u16, max_bps: u16 },
+
+    #[error("No premium rate is configured for {denom}")]
+    UnsupportedDenom { denom: String },
+
+    #[error("Expected the protocol fees {expected} to be attached")]
+    InvalidFee { expected: String },
+
+    #[error("Loans must be non-empty, non-zero and use each denom at most once")]
+    InvalidLoans {},
+
+    #[error("Flash loans are paused")]
+    Paused {},
+
+    #[error("Loan exceeds the cap of {cap}{denom}")]
+    LoanCapExceeded { denom: String, cap: Uint128 },
+
+    #[error("Borrower is not on the allowlist")]
+    BorrowerNotAllowed {},
+
+    #[error("No outstanding loan of {amount}{denom} to repay")]
+    NoOutstandingLoan { denom: String, amount: Uint128 },
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
 }
\ No newline at end of file
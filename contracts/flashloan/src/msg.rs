@@ -1,22 +1,42 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{CosmosMsg, Uint128};
+use cosmwasm_std::{Coin, CosmosMsg, Uint128};
 
-use crate::state::State;
+use crate::state::{LoanRecord, State};
 
-/// Message used to instantiate the contract, setting the owner and lending pool addresses.
+/// Message used to instantiate the contract, setting the owner, lending pool and fee parameters.
 #[cw_serde]
 pub struct InstantiateMsg {
     pub owner: String,
     pub lending_pool: String,
+    pub treasury: String,
+    pub max_premium_bps: u16,
+    pub protocol_fee_bps: u16,
 }
 
 /// Enumeration of messages that can be executed by the contract.
 #[cw_serde]
 pub enum ExecuteMsg {
-    /// Request a flash loan with specified token, amount, and collateral.
-    RequestFlashLoan { token: String, amount: Uint128, collateral: Uint128 },
-    /// Execute the flash loan operation, repaying the loan with a premium.
-    ExecuteOperation { token: String, amount: Uint128, premium: Uint128 },
+    /// Request a flash loan of one or more denoms; attached funds are forwarded to the lending pool as collateral.
+    RequestFlashLoan { loans: Vec<Coin> },
+    /// Execute the flash loan operation, repaying every loan with the configured premium.
+    /// The protocol share of each premium must be attached.
+    ExecuteOperation { loans: Vec<Coin> },
+    /// Set the premium rate of a denom (only callable by the owner, capped at `max_premium_bps`).
+    SetPremiumRate { denom: String, rate_bps: u16 },
+    /// Set the share of premiums sent to the treasury (only callable by the owner).
+    SetProtocolFee { protocol_fee_bps: u16, treasury: Option<String> },
+    /// Set or clear the maximum loan size of a denom (only callable by the owner).
+    SetLoanCap { denom: String, cap: Option<Uint128> },
+    /// Enable or disable the borrower allowlist (only callable by the owner).
+    SetAllowlistEnabled { enabled: bool },
+    /// Add or remove a borrower contract from the allowlist (only callable by the owner).
+    UpdateBorrower { borrower: String, allowed: bool },
+    /// Set or clear the guardian allowed to pause loans (only callable by the owner).
+    SetGuardian { guardian: Option<String> },
+    /// Reject new loans (callable by the owner or the guardian).
+    Pause {},
+    /// Accept new loans again (only callable by the owner).
+    Unpause {},
     /// Withdraw the specified token's balance (only callable by the owner).
     Withdraw { token: String },
 }
@@ -31,22 +51,60 @@ pub enum QueryMsg {
     /// Query the current state of the loan.
     #[returns(State)]
     LoanInfo {},
+    /// Query the maximum loan size of a denom, if any.
+    #[returns(Option<Uint128>)]
+    LoanCap { denom: String },
+    /// Query whether a borrower is on the allowlist.
+    #[returns(bool)]
+    IsAllowedBorrower { borrower: String },
+    /// Query repaid loans, newest first.
+    #[returns(RecentLoansResponse)]
+    RecentLoans { start_before: Option<u64>, limit: Option<u32> },
+    /// Query the total amount lent of a denom.
+    #[returns(Uint128)]
+    TotalVolume { denom: String },
+    /// Query the total premiums earned on a denom.
+    #[returns(Uint128)]
+    FeesAccrued { denom: String },
+    /// Query the premium owed on a loan and how it is split.
+    #[returns(QuoteFeeResponse)]
+    QuoteFee { denom: String, amount: Uint128 },
+}
+
+/// A repaid loan and its id.
+#[cw_serde]
+pub struct LoanEntry {
+    pub id: u64,
+    pub loan: LoanRecord,
+}
+
+/// Page of repaid loans, newest first.
+#[cw_serde]
+pub struct RecentLoansResponse {
+    pub loans: Vec<LoanEntry>,
+}
+
+/// Premium owed on a loan, split between the lending pool and the treasury.
+#[cw_serde]
+pub struct QuoteFeeResponse {
+    pub rate_bps: u16,
+    pub premium: Uint128,
+    pub lp_fee: Uint128,
+    pub protocol_fee: Uint128,
 }
 
-/// Structure representing a request for a flash loan.
+/// Structure representing a request for a flash loan of one or more denoms.
 #[cw_serde]
 pub struct RequestFlashLoan {
     pub recipient: String,
-    pub token: String,
-    pub amount: Uint128,
+    pub loans: Vec<Coin>,
 }
 
-/// Structure representing the repayment of a flash loan.
+/// Structure representing the repayment of a flash loan of one or more denoms.
 #[cw_serde]
 pub struct RepayFlashLoan {
     pub sender: String,
-    pub token: String,
-    pub amount: Uint128,
+    pub amounts: Vec<Coin>,
 }
 
 /// Enumeration of custom messages used by the contract.
@@ -1,6 +1,6 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
 
 /// State struct to hold contract state data
 #[cw_serde]
@@ -9,7 +9,54 @@ pub struct State {
     pub owner: Addr,
     /// Address of the lending pool
     pub lending_pool: Addr,
+    /// Address receiving the protocol share of loan premiums
+    pub treasury: Addr,
+    /// Upper bound for any premium rate, in basis points
+    pub max_premium_bps: u16,
+    /// Share of each premium sent to the treasury, in basis points; the rest goes to the lending pool
+    pub protocol_fee_bps: u16,
+    /// Address allowed to pause loans besides the owner
+    pub guardian: Option<Addr>,
+    /// Whether new loans are rejected
+    pub paused: bool,
+    /// Whether only allowlisted borrower contracts may request loans
+    pub allowlist_enabled: bool,
 }
 
 /// Constant to store the state data in the contract's storage
-pub const STATE: Item<State> = Item::new("state");
\ No newline at end of file
+pub const STATE: Item<State> = Item::new("state");
+
+/// Premium charged on loans of each denom, in basis points of the borrowed amount
+pub const PREMIUM_RATES: Map<&str, u16> = Map::new("premium_rates");
+
+/// Maximum amount that can be borrowed of each denom in a single loan
+pub const LOAN_CAPS: Map<&str, Uint128> = Map::new("loan_caps");
+
+/// Borrower contracts allowed to request loans while the allowlist is enabled
+pub const BORROWERS: Map<&Addr, bool> = Map::new("borrowers");
+
+/// Loans requested and not yet repaid, keyed by borrower and denom
+pub const OUTSTANDING_LOANS: Map<(&Addr, &str), Uint128> = Map::new("outstanding_loans");
+
+/// A repaid flash loan
+#[cw_serde]
+pub struct LoanRecord {
+    pub borrower: Addr,
+    pub denom: String,
+    pub amount: Uint128,
+    /// Premium paid on the loan, including the protocol share
+    pub fee: Uint128,
+    pub height: u64,
+}
+
+/// Repaid loans keyed by a sequential id
+pub const LOANS: Map<u64, LoanRecord> = Map::new("loans");
+
+/// Number of loans recorded so far, also the id of the next loan
+pub const LOAN_COUNT: Item<u64> = Item::new("loan_count");
+
+/// Total amount lent per denom
+pub const TOTAL_VOLUME: Map<&str, Uint128> = Map::new("total_volume");
+
+/// Total premiums earned per denom
+pub const FEES_ACCRUED: Map<&str, Uint128> = Map::new("fees_accrued");
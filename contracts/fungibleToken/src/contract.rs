@@ -4,19 +4,41 @@ use coreum_wasm_sdk::assetft::{
 };
 use coreum_wasm_sdk::core::{CoreumMsg, CoreumQueries, CoreumResult};
 use coreum_wasm_sdk::pagination::PageRequest;
-use cosmwasm_std::{coin, entry_point, to_json_binary, Binary, Deps, QueryRequest, StdResult};
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{
+    coin, entry_point, to_json_binary, Binary, CosmosMsg, Deps, Empty, Order, QueryRequest, Reply,
+    StdError, StdResult, SubMsg, SubMsgResponse, SubMsgResult, Uint128,
+};
+use cosmwasm_std::{DepsMut, Env, Event, MessageInfo, Response};
 use cw2::set_contract_version;
 use cw_ownable::{assert_owner, initialize_owner};
+use cw_storage_plus::Bound;
+use ibc_proto::cosmos::bank::v1beta1::QueryDenomOwnersRequest;
+use ibc_proto::cosmos::base::query::v1beta1::PageRequest as ProtoPageRequest;
+use ibc_proto::cosmos::base::v1beta1::Coin as ProtoCoin;
+use ibc_proto::ibc::apps::transfer::v1::{MsgTransfer, MsgTransferResponse};
+use prost::Message;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::DENOM;
+use crate::msg::{
+    CirculatingSupplyResponse, DenomOwnersResponse, ExecuteMsg, HolderCountEstimateResponse,
+    IbcLifecycleComplete, InstantiateMsg, PendingIbcTransfersResponse, QueryMsg, SudoMsg,
+    TotalFrozenResponse,
+};
+use crate::state::{
+    IbcTransfer, TransferStatus, COUNTED_FROZEN, DENOM, FROZEN, FROZEN_ACCOUNTS,
+    PENDING_TRANSFERS, TOTAL_FROZEN, TRANSFERS, TRANSFER_PACKETS, TRANSFER_SEQ,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+const IBC_TRANSFER_REPLY_ID: u64 = 1;
+const DENOM_OWNERS_PATH: &str = "/cosmos.bank.v1beta1.Query/DenomOwners";
+const TRANSFER_PORT: &str = "transfer";
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 // ********** Instantiate **********
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -45,10 +67,13 @@ pub fn instantiate(
     let denom = format!("{}-{}", msg.subunit, env.contract.address).to_lowercase();
 
     DENOM.save(deps.storage, &denom)?;
+    TOTAL_FROZEN.save(deps.storage, &Uint128::zero())?;
+    FROZEN_ACCOUNTS.save(deps.storage, &0)?;
 
     Ok(Response::new()
-        .add_attribute("owner", info.sender)
-        .add_attribute("denom", denom)
+        .add_attribute("owner", info.sender.as_str())
+        .add_attribute("denom", denom.as_str())
+        .add_event(ft_event("issue", &info, &denom).add_attribute("amount", msg.initial_amount))
         .add_message(issue_msg))
 }
 
@@ -57,13 +82,13 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> CoreumResult<ContractError> {
     match msg {
-        ExecuteMsg::Mint { amount, recipient } => mint(deps, info, amount, recipient),
-        ExecuteMsg::Burn { amount } => burn(deps, info, amount),
+        ExecuteMsg::Mint { amount, recipient } => mint(deps, env, info, amount, recipient),
+        ExecuteMsg::Burn { amount } => burn(deps, env, info, amount),
         ExecuteMsg::Freeze { account, amount } => freeze(deps, info, account, amount),
         ExecuteMsg::Unfreeze { account, amount } => unfreeze(deps, info, account, amount),
         ExecuteMsg::SetFrozen { account, amount } => set_frozen(deps, info, account, amount),
@@ -72,16 +97,123 @@ pub fn execute(
         ExecuteMsg::SetWhitelistedLimit { account, amount } => {
             set_whitelisted_limit(deps, info, account, amount)
         }
+        ExecuteMsg::RefreshFrozen { account } => refresh_frozen(deps, account),
         ExecuteMsg::UpgradeTokenV1 { ibc_enabled } => upgrade_token_v1(deps, info, ibc_enabled),
+        ExecuteMsg::IbcTransfer {
+            channel,
+            to,
+            amount,
+            timeout,
+        } => ibc_transfer(deps, env, info, channel, to, amount, timeout),
+    }
+}
+
+// ********** Callbacks **********
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> CoreumResult<ContractError> {
+    match msg {
+        Reply {
+            id: IBC_TRANSFER_REPLY_ID,
+            result:
+                SubMsgResult::Ok(SubMsgResponse {
+                    data: Some(data), ..
+                }),
+        } => {
+            let sequence = MsgTransferResponse::decode(data.as_slice())
+                .map_err(|e| StdError::parse_err("MsgTransferResponse", e))?
+                .sequence;
+            record_transfer_sequence(deps, sequence)
+        }
+        _ => Err(StdError::generic_err("unexpected reply").into()),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> CoreumResult<ContractError> {
+    match msg {
+        SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+            channel,
+            sequence,
+            success,
+            ..
+        }) => settle_transfer(deps, channel, sequence, success),
+        SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout { channel, sequence }) => {
+            settle_transfer(deps, channel, sequence, false)
+        }
     }
 }
 
 // ********** Transactions **********
 
+// Every execute path emits an `ft_<action>` event so explorers can index issuer actions
+fn ft_event(action: &str, info: &MessageInfo, denom: &str) -> Event {
+    Event::new(format!("ft_{}", action))
+        .add_attribute("denom", denom)
+        .add_attribute("initiator", info.sender.as_str())
+}
+
+// Keeps the local frozen total in step with the amount now frozen for the account. Coreum lets
+// the issuer freeze more than an account holds, only the part its balance covers is counted.
+fn record_frozen(deps: DepsMut, denom: &str, account: &str, amount: Uint128) -> StdResult<()> {
+    let balance = deps.querier.query_balance(account, denom)?.amount;
+    let counted = amount.min(balance);
+    let was_frozen = FROZEN.has(deps.storage, account);
+    // amounts recorded before balances were counted were counted in full
+    let previous = match COUNTED_FROZEN.may_load(deps.storage, account)? {
+        Some(previous) => previous,
+        None => FROZEN.may_load(deps.storage, account)?.unwrap_or_default(),
+    };
+    let mut total = TOTAL_FROZEN.may_load(deps.storage)?.unwrap_or_default();
+    total = total - previous + counted;
+    TOTAL_FROZEN.save(deps.storage, &total)?;
+
+    let accounts = FROZEN_ACCOUNTS.may_load(deps.storage)?.unwrap_or_default();
+    if amount.is_zero() {
+        FROZEN.remove(deps.storage, account);
+        COUNTED_FROZEN.remove(deps.storage, account);
+        if was_frozen {
+            FROZEN_ACCOUNTS.save(deps.storage, &accounts.saturating_sub(1))?;
+        }
+    } else {
+        FROZEN.save(deps.storage, account, &amount)?;
+        COUNTED_FROZEN.save(deps.storage, account, &counted)?;
+        if !was_frozen {
+            FROZEN_ACCOUNTS.save(deps.storage, &(accounts + 1))?;
+        }
+    }
+    Ok(())
+}
+
+// Frozen coins can't leave the account, so the count only falls behind when it receives more
+fn refresh_frozen(deps: DepsMut, account: String) -> CoreumResult<ContractError> {
+    let denom = DENOM.load(deps.storage)?;
+    let frozen = FROZEN
+        .may_load(deps.storage, &account)?
+        .ok_or_else(|| ContractError::NotFrozen {
+            account: account.clone(),
+        })?;
+    record_frozen(deps, &denom, &account, frozen)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "refresh_frozen")
+        .add_attribute("account", account))
+}
+
 // Function to mint the token
-fn mint(deps: DepsMut, info: MessageInfo, amount: u128, recipient: Option<String>) -> CoreumResult<ContractError> {
+fn mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: u128,
+    recipient: Option<String>,
+) -> CoreumResult<ContractError> {
     assert_owner(deps.storage, &info.sender)?;
     let denom = DENOM.load(deps.storage)?;
+    // tokens are minted to the issuer, i.e. this contract, when no recipient is given
+    let account = recipient
+        .clone()
+        .unwrap_or_else(|| env.contract.address.to_string());
     let msg = CoreumMsg::AssetFT(assetft::Msg::Mint {
         coin: coin(amount, denom.clone()),
         recipient,
@@ -89,13 +221,16 @@ fn mint(deps: DepsMut, info: MessageInfo, amount: u128, recipient: Option<String
 
     Ok(Response::new()
         .add_attribute("method", "mint")
-        .add_attribute("denom", denom)
-        .add_attribute("amount", amount.to_string())
+        .add_event(
+            ft_event("mint", &info, &denom)
+                .add_attribute("account", account)
+                .add_attribute("amount", amount.to_string()),
+        )
         .add_message(msg))
 }
 
 // Function to burn the token
-fn burn(deps: DepsMut, info: MessageInfo, amount: u128) -> CoreumResult<ContractError> {
+fn burn(deps: DepsMut, env: Env, info: MessageInfo, amount: u128) -> CoreumResult<ContractError> {
     assert_owner(deps.storage, &info.sender)?;
     let denom = DENOM.load(deps.storage)?;
 
@@ -105,14 +240,17 @@ fn burn(deps: DepsMut, info: MessageInfo, amount: u128) -> CoreumResult<Contract
 
     Ok(Response::new()
         .add_attribute("method", "burn")
-        .add_attribute("denom", denom)
-        .add_attribute("amount", amount.to_string())
+        .add_event(
+            ft_event("burn", &info, &denom)
+                .add_attribute("account", env.contract.address)
+                .add_attribute("amount", amount.to_string()),
+        )
         .add_message(msg))
 }
 
 //Function to freeze token
 fn freeze(
-    deps: DepsMut,
+    mut deps: DepsMut,
     info: MessageInfo,
     account: String,
     amount: u128,
@@ -120,21 +258,27 @@ fn freeze(
     assert_owner(deps.storage, &info.sender)?;
     let denom = DENOM.load(deps.storage)?;
 
+    let frozen = FROZEN.may_load(deps.storage, &account)?.unwrap_or_default();
+    record_frozen(deps.branch(), &denom, &account, frozen + Uint128::new(amount))?;
+
     let msg = CoreumMsg::AssetFT(assetft::Msg::Freeze {
-        account,
+        account: account.clone(),
         coin: coin(amount, denom.clone()),
     });
 
     Ok(Response::new()
         .add_attribute("method", "freeze")
-        .add_attribute("denom", denom)
-        .add_attribute("amount", amount.to_string())
+        .add_event(
+            ft_event("freeze", &info, &denom)
+                .add_attribute("account", account)
+                .add_attribute("amount", amount.to_string()),
+        )
         .add_message(msg))
 }
 
 //Function to unfreeze token
 fn unfreeze(
-    deps: DepsMut,
+    mut deps: DepsMut,
     info: MessageInfo,
     account: String,
     amount: u128,
@@ -142,20 +286,27 @@ fn unfreeze(
     assert_owner(deps.storage, &info.sender)?;
     let denom = DENOM.load(deps.storage)?;
 
+    let frozen = FROZEN.may_load(deps.storage, &account)?.unwrap_or_default();
+    let remaining = frozen.saturating_sub(Uint128::new(amount));
+    record_frozen(deps.branch(), &denom, &account, remaining)?;
+
     let msg = CoreumMsg::AssetFT(assetft::Msg::Unfreeze {
-        account,
+        account: account.clone(),
         coin: coin(amount, denom.clone()),
     });
 
     Ok(Response::new()
         .add_attribute("method", "unfreeze")
-        .add_attribute("denom", denom)
-        .add_attribute("amount", amount.to_string())
+        .add_event(
+            ft_event("unfreeze", &info, &denom)
+                .add_attribute("account", account)
+                .add_attribute("amount", amount.to_string()),
+        )
         .add_message(msg))
 }
 
 fn set_frozen(
-    deps: DepsMut,
+    mut deps: DepsMut,
     info: MessageInfo,
     account: String,
     amount: u128,
@@ -163,15 +314,20 @@ fn set_frozen(
     assert_owner(deps.storage, &info.sender)?;
     let denom = DENOM.load(deps.storage)?;
 
+    record_frozen(deps.branch(), &denom, &account, Uint128::new(amount))?;
+
     let msg = CoreumMsg::AssetFT(assetft::Msg::SetFrozen {
-        account,
+        account: account.clone(),
         coin: coin(amount, denom.clone()),
     });
 
     Ok(Response::new()
         .add_attribute("method", "set_frozen")
-        .add_attribute("denom", denom)
-        .add_attribute("amount", amount.to_string())
+        .add_event(
+            ft_event("set_frozen", &info, &denom)
+                .add_attribute("account", account)
+                .add_attribute("amount", amount.to_string()),
+        )
         .add_message(msg))
 }
 
@@ -185,7 +341,7 @@ fn globally_freeze(deps: DepsMut, info: MessageInfo) -> CoreumResult<ContractErr
 
     Ok(Response::new()
         .add_attribute("method", "globally_freeze")
-        .add_attribute("denom", denom)
+        .add_event(ft_event("globally_freeze", &info, &denom))
         .add_message(msg))
 }
 
@@ -199,7 +355,7 @@ fn globally_unfreeze(deps: DepsMut, info: MessageInfo) -> CoreumResult<ContractE
 
     Ok(Response::new()
         .add_attribute("method", "globally_unfreeze")
-        .add_attribute("denom", denom)
+        .add_event(ft_event("globally_unfreeze", &info, &denom))
         .add_message(msg))
 }
 
@@ -213,14 +369,17 @@ fn set_whitelisted_limit(
     let denom = DENOM.load(deps.storage)?;
 
     let msg = CoreumMsg::AssetFT(assetft::Msg::SetWhitelistedLimit {
-        account,
+        account: account.clone(),
         coin: coin(amount, denom.clone()),
     });
 
     Ok(Response::new()
         .add_attribute("method", "set_whitelisted_limit")
-        .add_attribute("denom", denom)
-        .add_attribute("amount", amount.to_string())
+        .add_event(
+            ft_event("set_whitelisted_limit", &info, &denom)
+                .add_attribute("account", account)
+                .add_attribute("amount", amount.to_string()),
+        )
         .add_message(msg))
 }
 
@@ -239,14 +398,120 @@ fn upgrade_token_v1(
 
     Ok(Response::new()
         .add_attribute("method", "upgrade_token_v1")
-        .add_attribute("denom", denom)
-        .add_attribute("ibc_enabled", ibc_enabled.to_string())
+        .add_event(
+            ft_event("upgrade_token_v1", &info, &denom)
+                .add_attribute("ibc_enabled", ibc_enabled.to_string()),
+        )
         .add_message(upgrade_msg))
 }
 
+fn ibc_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    channel: String,
+    to: String,
+    amount: u128,
+    timeout: u64,
+) -> CoreumResult<ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    if timeout == 0 {
+        return Err(ContractError::InvalidTimeout {});
+    }
+    let denom = DENOM.load(deps.storage)?;
+
+    let id = TRANSFER_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    TRANSFER_SEQ.save(deps.storage, &id)?;
+    let transfer = IbcTransfer {
+        id,
+        channel: channel.clone(),
+        to: to.clone(),
+        amount: Uint128::new(amount),
+        timeout: env.block.time.plus_seconds(timeout),
+        sequence: None,
+        status: TransferStatus::Pending,
+    };
+    TRANSFERS.save(deps.storage, id, &transfer)?;
+    PENDING_TRANSFERS.save(deps.storage, id, &Empty {})?;
+
+    // The memo asks ibc-hooks to report the ack or timeout of the packet back through sudo
+    let msg = MsgTransfer {
+        source_port: TRANSFER_PORT.to_string(),
+        source_channel: channel.clone(),
+        token: Some(ProtoCoin {
+            denom: denom.clone(),
+            amount: amount.to_string(),
+        }),
+        sender: env.contract.address.to_string(),
+        receiver: to.clone(),
+        timeout_height: None,
+        timeout_timestamp: transfer.timeout.nanos(),
+        memo: format!(r#"{{"ibc_callback":"{}"}}"#, env.contract.address),
+    };
+    let msg: CosmosMsg<CoreumMsg> = CosmosMsg::Stargate {
+        type_url: "/ibc.applications.transfer.v1.MsgTransfer".to_string(),
+        value: Binary::from(msg.encode_to_vec()),
+    };
+
+    Ok(Response::new()
+        .add_attribute("method", "ibc_transfer")
+        .add_event(
+            ft_event("ibc_transfer", &info, &denom)
+                .add_attribute("id", id.to_string())
+                .add_attribute("channel", channel)
+                .add_attribute("account", to)
+                .add_attribute("amount", amount.to_string()),
+        )
+        .add_submessage(SubMsg::reply_on_success(msg, IBC_TRANSFER_REPLY_ID)))
+}
+
+// The reply follows the transfer in the same transaction, so it belongs to the last one sent
+fn record_transfer_sequence(deps: DepsMut, sequence: u64) -> CoreumResult<ContractError> {
+    let id = TRANSFER_SEQ.load(deps.storage)?;
+    let mut transfer = TRANSFERS.load(deps.storage, id)?;
+    transfer.sequence = Some(sequence);
+    TRANSFERS.save(deps.storage, id, &transfer)?;
+    TRANSFER_PACKETS.save(deps.storage, (transfer.channel.as_str(), sequence), &id)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "ibc_transfer_sent")
+        .add_attribute("id", id.to_string())
+        .add_attribute("sequence", sequence.to_string()))
+}
+
+// Tokens of a failed transfer are refunded to this contract by the transfer module itself
+fn settle_transfer(
+    deps: DepsMut,
+    channel: String,
+    sequence: u64,
+    success: bool,
+) -> CoreumResult<ContractError> {
+    let id = TRANSFER_PACKETS
+        .may_load(deps.storage, (channel.as_str(), sequence))?
+        .ok_or_else(|| ContractError::UnknownTransfer {
+            channel: channel.clone(),
+            sequence,
+        })?;
+    TRANSFER_PACKETS.remove(deps.storage, (channel.as_str(), sequence));
+    PENDING_TRANSFERS.remove(deps.storage, id);
+
+    let mut transfer = TRANSFERS.load(deps.storage, id)?;
+    transfer.status = if success {
+        TransferStatus::Completed
+    } else {
+        TransferStatus::Failed
+    };
+    TRANSFERS.save(deps.storage, id, &transfer)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "ibc_transfer_settled")
+        .add_attribute("id", id.to_string())
+        .add_attribute("success", success.to_string()))
+}
+
 // ********** Queries **********
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps<CoreumQueries>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps<CoreumQueries>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Params {} => to_json_binary(&query_params(deps)?),
         QueryMsg::Token {} => to_json_binary(&query_token(deps)?),
@@ -260,6 +525,13 @@ pub fn query(deps: Deps<CoreumQueries>, _env: Env, msg: QueryMsg) -> StdResult<B
         QueryMsg::WhitelistedBalances { account } => {
             to_json_binary(&query_whitelisted_balances(deps, account)?)
         }
+        QueryMsg::CirculatingSupply {} => to_json_binary(&query_circulating_supply(deps, env)?),
+        QueryMsg::TotalFrozen {} => to_json_binary(&query_total_frozen(deps)?),
+        QueryMsg::HolderCountEstimate {} => to_json_binary(&query_holder_count_estimate(deps)?),
+        QueryMsg::IbcTransfer { id } => to_json_binary(&TRANSFERS.load(deps.storage, id)?),
+        QueryMsg::PendingIbcTransfers { start_after, limit } => {
+            to_json_binary(&query_pending_ibc_transfers(deps, start_after, limit)?)
+        }
     }
 }
 
@@ -404,6 +676,80 @@ fn query_whitelisted_balances(
     Ok(res)
 }
 
+fn query_circulating_supply(
+    deps: Deps<CoreumQueries>,
+    env: Env,
+) -> StdResult<CirculatingSupplyResponse> {
+    let denom = DENOM.load(deps.storage)?;
+    let total_supply = deps.querier.query_supply(&denom)?.amount;
+    let contract_held = deps
+        .querier
+        .query_balance(&env.contract.address, &denom)?
+        .amount;
+    let total_frozen = TOTAL_FROZEN.may_load(deps.storage)?.unwrap_or_default();
+    let res = CirculatingSupplyResponse {
+        total_supply,
+        total_frozen,
+        contract_held,
+        circulating_supply: total_supply.saturating_sub(total_frozen + contract_held),
+    };
+    Ok(res)
+}
+
+fn query_total_frozen(deps: Deps<CoreumQueries>) -> StdResult<TotalFrozenResponse> {
+    let res = TotalFrozenResponse {
+        total_frozen: TOTAL_FROZEN.may_load(deps.storage)?.unwrap_or_default(),
+        frozen_accounts: FROZEN_ACCOUNTS.may_load(deps.storage)?.unwrap_or_default(),
+        globally_frozen: query_token(deps)?.token.globally_frozen,
+    };
+    Ok(res)
+}
+
+// Asks the bank module for a single owner page, its total is the number of holders
+fn query_holder_count_estimate(
+    deps: Deps<CoreumQueries>,
+) -> StdResult<HolderCountEstimateResponse> {
+    let denom = DENOM.load(deps.storage)?;
+    let request = QueryDenomOwnersRequest {
+        denom,
+        pagination: Some(ProtoPageRequest {
+            key: vec![],
+            offset: 0,
+            limit: 1,
+            count_total: true,
+            reverse: false,
+        }),
+    };
+    let request: QueryRequest<CoreumQueries> = QueryRequest::Stargate {
+        path: DENOM_OWNERS_PATH.to_string(),
+        data: Binary::from(request.encode_to_vec()),
+    };
+    let res: DenomOwnersResponse = deps.querier.query(&request)?;
+    let res = HolderCountEstimateResponse {
+        holders: res.pagination.map(|page| page.total.u64()).unwrap_or_default(),
+    };
+    Ok(res)
+}
+
+fn query_pending_ibc_transfers(
+    deps: Deps<CoreumQueries>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PendingIbcTransfersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let transfers = PENDING_TRANSFERS
+        .keys(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|id| TRANSFERS.load(deps.storage, id?))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(PendingIbcTransfersResponse { transfers })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -684,4 +1030,192 @@ mod tests {
 
         assert_eq!(whitelisted_balance.whitelisted_balance.amount.u128(), 200);
     }
+
+    #[test]
+    fn test_ibc_transfer_lifecycle() {
+        use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            symbol: "TEST".to_string(),
+            subunit: "utest".to_string(),
+            precision: 6,
+            initial_amount: Uint128::new(1000),
+            description: None,
+            features: None,
+            burn_rate: "0".to_string(),
+            send_commission_rate: "0".to_string(),
+            uri: None,
+            uri_hash: None,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+        let transfer_msg = ExecuteMsg::IbcTransfer {
+            channel: "channel-0".to_string(),
+            to: "osmo1recipient".to_string(),
+            amount: 300,
+            timeout: 600,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("intruder", &[]),
+            transfer_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Ownership(_)));
+
+        let info = mock_info("owner", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, transfer_msg).unwrap();
+        assert_eq!(res.messages[0].id, IBC_TRANSFER_REPLY_ID);
+        match &res.messages[0].msg {
+            CosmosMsg::Stargate { type_url, value } => {
+                assert_eq!(type_url, "/ibc.applications.transfer.v1.MsgTransfer");
+                let sent = MsgTransfer::decode(value.as_slice()).unwrap();
+                assert_eq!(sent.source_channel, "channel-0");
+                assert_eq!(sent.token.unwrap().amount, "300");
+                assert_eq!(
+                    sent.timeout_timestamp,
+                    mock_env().block.time.plus_seconds(600).nanos()
+                );
+            }
+            m => panic!("unexpected message: {:?}", m),
+        }
+
+        // the transfer module answers with the packet sequence
+        let data = MsgTransferResponse { sequence: 7 }.encode_to_vec();
+        let reply_msg = Reply {
+            id: IBC_TRANSFER_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(Binary::from(data)),
+            }),
+        };
+        reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+        let transfer = TRANSFERS.load(&deps.storage, 1).unwrap();
+        assert_eq!(transfer.sequence, Some(7));
+        assert_eq!(transfer.status, TransferStatus::Pending);
+        assert!(PENDING_TRANSFERS.has(&deps.storage, 1));
+
+        // a timeout marks it failed and takes it off the pending list
+        let timeout = SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout {
+            channel: "channel-0".to_string(),
+            sequence: 7,
+        });
+        sudo(deps.as_mut(), mock_env(), timeout.clone()).unwrap();
+        let transfer = TRANSFERS.load(&deps.storage, 1).unwrap();
+        assert_eq!(transfer.status, TransferStatus::Failed);
+        assert!(!PENDING_TRANSFERS.has(&deps.storage, 1));
+
+        // the packet cannot be settled twice
+        let err = sudo(deps.as_mut(), mock_env(), timeout).unwrap_err();
+        assert!(matches!(err, ContractError::UnknownTransfer { sequence: 7, .. }));
+    }
+
+    #[test]
+    fn test_frozen_total_counts_the_covered_balance() {
+        use cosmwasm_std::testing::{mock_dependencies_with_balances, mock_env, mock_info};
+
+        let denom = "utest-cosmos2contract";
+        let mut deps = mock_dependencies_with_balances(&[("alice", &[coin(60, denom)])]);
+        let msg = InstantiateMsg {
+            symbol: "TEST".to_string(),
+            subunit: "utest".to_string(),
+            precision: 6,
+            initial_amount: Uint128::new(1000),
+            description: None,
+            features: None,
+            burn_rate: "0".to_string(),
+            send_commission_rate: "0".to_string(),
+            uri: None,
+            uri_hash: None,
+        };
+        let owner = mock_info("owner", &[]);
+        instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        // only the 60 alice holds count towards the total
+        let freeze_msg = ExecuteMsg::Freeze {
+            account: "alice".to_string(),
+            amount: 100,
+        };
+        execute(deps.as_mut(), mock_env(), owner.clone(), freeze_msg).unwrap();
+        assert_eq!(FROZEN.load(&deps.storage, "alice").unwrap(), Uint128::new(100));
+        assert_eq!(TOTAL_FROZEN.load(&deps.storage).unwrap(), Uint128::new(60));
+        assert_eq!(FROZEN_ACCOUNTS.load(&deps.storage).unwrap(), 1);
+
+        // once she received more, anyone can have the rest counted
+        deps.querier.update_balance("alice", vec![coin(150, denom)]);
+        let refresh_msg = ExecuteMsg::RefreshFrozen {
+            account: "alice".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), refresh_msg).unwrap();
+        assert_eq!(TOTAL_FROZEN.load(&deps.storage).unwrap(), Uint128::new(100));
+
+        let set_frozen_msg = ExecuteMsg::SetFrozen {
+            account: "bob".to_string(),
+            amount: 50,
+        };
+        execute(deps.as_mut(), mock_env(), owner.clone(), set_frozen_msg).unwrap();
+        assert_eq!(TOTAL_FROZEN.load(&deps.storage).unwrap(), Uint128::new(100));
+        assert_eq!(FROZEN_ACCOUNTS.load(&deps.storage).unwrap(), 2);
+
+        let unfreeze_msg = ExecuteMsg::Unfreeze {
+            account: "alice".to_string(),
+            amount: 100,
+        };
+        execute(deps.as_mut(), mock_env(), owner, unfreeze_msg).unwrap();
+        assert!(!FROZEN.has(&deps.storage, "alice"));
+        assert_eq!(TOTAL_FROZEN.load(&deps.storage).unwrap(), Uint128::zero());
+        assert_eq!(FROZEN_ACCOUNTS.load(&deps.storage).unwrap(), 1);
+
+        let refresh_msg = ExecuteMsg::RefreshFrozen {
+            account: "alice".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), refresh_msg)
+            .unwrap_err();
+        assert!(matches!(err, ContractError::NotFrozen { .. }));
+    }
+
+    #[test]
+    fn test_holder_count_comes_from_the_bank_module() {
+        use cosmwasm_std::testing::{MockApi, MockStorage};
+        use cosmwasm_std::{from_json, ContractResult, OwnedDeps, Querier, QuerierResult, SystemError, SystemResult};
+        use std::marker::PhantomData;
+
+        // answers the DenomOwners stargate query in json, the way the chain does
+        struct BankQuerier {
+            holders: u64,
+        }
+        impl Querier for BankQuerier {
+            fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+                let request: QueryRequest<CoreumQueries> = from_json(bin_request).unwrap();
+                match request {
+                    QueryRequest::Stargate { path, data } if path == DENOM_OWNERS_PATH => {
+                        let request = QueryDenomOwnersRequest::decode(data.as_slice()).unwrap();
+                        assert!(request.pagination.unwrap().count_total);
+                        let res = format!(
+                            r#"{{"denom_owners":[{{"address":"alice","balance":{{"denom":"{}","amount":"1"}}}}],"pagination":{{"next_key":"YQ==","total":"{}"}}}}"#,
+                            request.denom, self.holders
+                        );
+                        SystemResult::Ok(ContractResult::Ok(Binary::from(res.into_bytes())))
+                    }
+                    _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                        kind: "bank".to_string(),
+                    }),
+                }
+            }
+        }
+
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: BankQuerier { holders: 42 },
+            custom_query_type: PhantomData::<CoreumQueries>,
+        };
+        DENOM
+            .save(&mut deps.storage, &"utest-cosmos2contract".to_string())
+            .unwrap();
+        let res = query_holder_count_estimate(deps.as_ref()).unwrap();
+        assert_eq!(res.holders, 42);
+    }
 }
@@ -9,4 +9,13 @@ pub enum ContractError {
 
     #[error(transparent)]
     Ownership(#[from] OwnershipError),
+
+    #[error("IBC transfer timeout must be at least one second")]
+    InvalidTimeout {},
+
+    #[error("No IBC transfer was sent as packet {sequence} on {channel}")]
+    UnknownTransfer { channel: String, sequence: u64 },
+
+    #[error("This contract froze nothing for {account}")]
+    NotFrozen { account: String },
 }
\ No newline at end of file
@@ -1,5 +1,8 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Uint128;
+use cosmwasm_schema::serde::Deserialize;
+use cosmwasm_std::{Uint128, Uint64};
+
+use crate::state::IbcTransfer;
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -25,7 +28,36 @@ pub enum ExecuteMsg {
     GloballyFreeze {},
     GloballyUnfreeze {},
     SetWhitelistedLimit { account: String, amount: u128 },
+    // Recounts the part of an account's frozen amount its balance covers, callable by anyone
+    RefreshFrozen { account: String },
     UpgradeTokenV1 { ibc_enabled: bool },
+    // Sends tokens held by this contract over an ICS-20 channel, timing out after `timeout` seconds
+    IbcTransfer {
+        channel: String,
+        to: String,
+        amount: u128,
+        timeout: u64,
+    },
+}
+
+// Callbacks of the ibc-hooks module for packets sent with an `ibc_callback` memo
+#[cw_serde]
+pub enum SudoMsg {
+    IbcLifecycleComplete(IbcLifecycleComplete),
+}
+
+#[cw_serde]
+pub enum IbcLifecycleComplete {
+    IbcAck {
+        channel: String,
+        sequence: u64,
+        ack: String,
+        success: bool,
+    },
+    IbcTimeout {
+        channel: String,
+        sequence: u64,
+    },
 }
 
 #[cw_serde]
@@ -38,4 +70,49 @@ pub enum QueryMsg {
     FrozenBalance { account: String },
     WhitelistedBalances { account: String },
     WhitelistedBalance { account: String },
+    CirculatingSupply {},
+    TotalFrozen {},
+    HolderCountEstimate {},
+    IbcTransfer { id: u64 },
+    PendingIbcTransfers { start_after: Option<u64>, limit: Option<u32> },
+}
+
+#[cw_serde]
+pub struct CirculatingSupplyResponse {
+    pub total_supply: Uint128,
+    pub total_frozen: Uint128,
+    pub contract_held: Uint128,
+    // total supply minus what is frozen or still held by the contract
+    pub circulating_supply: Uint128,
+}
+
+#[cw_serde]
+pub struct TotalFrozenResponse {
+    pub total_frozen: Uint128,
+    pub frozen_accounts: u64,
+    pub globally_frozen: bool,
+}
+
+#[cw_serde]
+pub struct HolderCountEstimateResponse {
+    // accounts holding a non-zero balance, as counted by the bank module
+    pub holders: u64,
+}
+
+// The fields read from the bank module's DenomOwners answer, which carries more
+#[derive(Deserialize)]
+#[serde(crate = "cosmwasm_schema::serde")]
+pub struct DenomOwnersResponse {
+    pub pagination: Option<DenomOwnersPagination>,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "cosmwasm_schema::serde")]
+pub struct DenomOwnersPagination {
+    pub total: Uint64,
+}
+
+#[cw_serde]
+pub struct PendingIbcTransfersResponse {
+    pub transfers: Vec<IbcTransfer>,
 }
\ No newline at end of file
@@ -1,3 +1,38 @@
-use cw_storage_plus::Item;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Empty, Timestamp, Uint128};
+use cw_storage_plus::{Item, Map};
 
-pub const DENOM: Item<String> = Item::new("state");
\ No newline at end of file
+pub const DENOM: Item<String> = Item::new("state");
+
+// Only the issuer can freeze, so the amounts this contract froze are the frozen total
+pub const FROZEN: Map<&str, Uint128> = Map::new("frozen");
+// Part of each frozen amount the balance covered when last recorded, which TOTAL_FROZEN sums
+pub const COUNTED_FROZEN: Map<&str, Uint128> = Map::new("counted_frozen");
+pub const TOTAL_FROZEN: Item<Uint128> = Item::new("total_frozen");
+pub const FROZEN_ACCOUNTS: Item<u64> = Item::new("frozen_accounts");
+
+#[cw_serde]
+pub enum TransferStatus {
+    Pending,
+    Completed,
+    // timed out or rejected by the counterparty, ICS-20 refunds the tokens to this contract
+    Failed,
+}
+
+#[cw_serde]
+pub struct IbcTransfer {
+    pub id: u64,
+    pub channel: String,
+    pub to: String,
+    pub amount: Uint128,
+    pub timeout: Timestamp,
+    // packet sequence assigned by the transfer module, known once the message succeeded
+    pub sequence: Option<u64>,
+    pub status: TransferStatus,
+}
+
+pub const TRANSFERS: Map<u64, IbcTransfer> = Map::new("ibc_transfers");
+pub const TRANSFER_SEQ: Item<u64> = Item::new("ibc_transfer_seq");
+// Lifecycle callbacks name the packet, this maps it back to the transfer
+pub const TRANSFER_PACKETS: Map<(&str, u64), u64> = Map::new("ibc_transfer_packets");
+pub const PENDING_TRANSFERS: Map<u64, Empty> = Map::new("ibc_pending_transfers");
@@ -1,29 +1,49 @@
+use std::convert::TryInto;
+
 use cosmwasm_std::{
-    entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, WasmMsg,
+    entry_point, from_binary, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
-use cw721::Cw721ReceiveMsg;
-use crate::error::{self, ContractError};
-use crate::msg::{ExecuteMsg, InstantiateMsg, MintMsg, PolicyMetadata, QueryMsg, PolicyResponse, AllPoliciesResponse, ConfigResponse};
-use crate::state::{InsurancePolicy, INSURANCE_POLICIES, CW721_CONTRACT_ADDRESS, TREASURY_ADDRESS};
+use cw_storage_plus::{Bound, U64Key};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw721::{Cw721QueryMsg, Cw721ReceiveMsg, OwnerOfResponse};
+use crate::error::ContractError;
+use crate::msg::{ClaimResponse, ClaimsResponse, ExecuteMsg, IndexValueResponse, InstantiateMsg, ClaimStatus as MsgClaimStatus, MintMsg, ParametricTrigger, PolicyStatus, QuoteResponse, PendingRidersResponse, Rider, RiderResponse, QuoteStatus, QuotesResponse, TriggerComparison, Cw20HookMsg, ExposureResponse, ReserveStatusResponse, PolicyMetadata, QueryMsg, PolicyResponse, AllPoliciesResponse, ConfigResponse, InstallmentResponse, PremiumScheduleResponse, CessionResponse, CessionsResponse, ReinsurerResponse};
+use crate::state::{Cession, ClaimRecord, Exposure, IndexReading, Installment, InsurancePolicy, GRACE_PERIOD, INSTALLMENTS, PolicyRider, Quote, Reserve, PENDING_QUOTES, QUOTES, RIDER_QUOTES, UNDERWRITERS, ADMIN, ASSESSORS, CLAIMS, EXPOSURE, MAX_EXPOSURE_MULTIPLE, RESERVE, INDEX_POLICIES, INDEX_VALUES, LEGACY_INDEX_POLICIES, INSURANCE_POLICIES, NEXT_CLAIM_ID, ORACLES, PENDING_CLAIMS, ClaimStatus as StateClaimStatus, CW20_TOKEN_ADDRESS, CW721_CONTRACT_ADDRESS, PREMIUM_DENOM, TREASURY_ADDRESS, CESSIONS, REINSURERS};
 
 // version info for migration
 const CONTRACT_NAME: &str = "crates.io:cosmwasm-insurance-policy";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+const DAY: u64 = 24 * 60 * 60;
+
+/// Exposure may be ten times the reserve until the admin configures otherwise
+const DEFAULT_EXPOSURE_MULTIPLE: u64 = 10;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    CW20_TOKEN_ADDRESS.save(deps.storage, &msg.cw20_token_address)?;
     CW721_CONTRACT_ADDRESS.save(deps.storage, &msg.cw721_contract_address)?;
     TREASURY_ADDRESS.save(deps.storage, &msg.treasury_address)?;
+    PREMIUM_DENOM.save(deps.storage, &msg.premium_denom)?;
+    ADMIN.save(deps.storage, &info.sender)?;
+    NEXT_CLAIM_ID.save(deps.storage, &1)?;
+    RESERVE.save(deps.storage, &Reserve::default())?;
+    EXPOSURE.save(deps.storage, &Exposure::default())?;
+    MAX_EXPOSURE_MULTIPLE.save(deps.storage, &Decimal::from_ratio(DEFAULT_EXPOSURE_MULTIPLE, 1u64))?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
+        .add_attribute("cw20_token_address", msg.cw20_token_address)
         .add_attribute("cw721_contract_address", msg.cw721_contract_address)
         .add_attribute("treasury_address", msg.treasury_address))
 }
@@ -31,7 +51,7 @@ pub fn instantiate(
 #[entry_point]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
@@ -44,24 +64,55 @@ pub fn execute(
             premium_frequency,
             policy_term,
             riders,
+            trigger,
         } => execute_create_policy(
             deps,
+            env,
             info,
             policy_id,
             insured_amount,
             premium,
-            condition,
             premium_frequency,
             policy_term,
+            condition,
             riders,
+            trigger,
         ),
+        ExecuteMsg::PayPremium { policy_id, amount } => execute_pay_premium(deps, env, info, policy_id, amount),
+        ExecuteMsg::FileClaim { policy_id, evidence_uri } => execute_file_claim(deps, env, info, policy_id, evidence_uri),
+        ExecuteMsg::AssessClaim { claim_id, approve, reason } => execute_assess_claim(deps, env, info, claim_id, approve, reason),
+        ExecuteMsg::SetAssessor { assessor, enabled } => execute_set_assessor(deps, info, assessor, enabled),
+        ExecuteMsg::SetUnderwriter { underwriter, enabled } => execute_set_underwriter(deps, info, underwriter, enabled),
+        ExecuteMsg::ApproveQuote { policy_id, premium } => execute_approve_quote(deps, env, info, policy_id, premium),
+        ExecuteMsg::DeclineQuote { policy_id, reason } => execute_decline_quote(deps, env, info, policy_id, reason),
+        ExecuteMsg::SetOracle { oracle, enabled } => execute_set_oracle(deps, info, oracle, enabled),
+        ExecuteMsg::PostIndexValue { index, value } => execute_post_index_value(deps, env, info, index, value),
+        ExecuteMsg::ProcessTriggers { index, start_after, limit } => execute_process_triggers(deps, env, index, start_after, limit),
+        ExecuteMsg::ClaimParametric { policy_id } => execute_claim_parametric(deps, env, info, policy_id),
+        ExecuteMsg::FundReserve {} => execute_fund_reserve(deps, info),
+        ExecuteMsg::WithdrawSurplus { amount } => execute_withdraw_surplus(deps, info, amount),
+        ExecuteMsg::RenewPolicy { policy_id } => execute_renew_policy(deps, env, info, policy_id),
+        ExecuteMsg::CancelPolicy { policy_id } => execute_cancel_policy(deps, env, info, policy_id),
+        ExecuteMsg::ClosePolicy { policy_id } => execute_close_policy(deps, env, policy_id),
+        ExecuteMsg::SyncPolicyOwner { policy_id } => execute_sync_policy_owner(deps, policy_id),
+        ExecuteMsg::AttachRider { policy_id, rider } => execute_attach_rider(deps, env, info, policy_id, rider),
+        ExecuteMsg::ApproveRider { policy_id, rider_id, premium } => execute_approve_rider(deps, env, info, policy_id, rider_id, premium),
+        ExecuteMsg::DeclineRider { policy_id, rider_id, reason } => execute_decline_rider(deps, info, policy_id, rider_id, reason),
+        ExecuteMsg::DetachRider { policy_id, rider_id } => execute_detach_rider(deps, env, info, policy_id, rider_id),
+        ExecuteMsg::SetExposureMultiple { multiple } => execute_set_exposure_multiple(deps, info, multiple),
+        ExecuteMsg::SetGracePeriod { grace_period } => execute_set_grace_period(deps, info, grace_period),
+        ExecuteMsg::CedeExposure { policy_id, reinsurer, share } => execute_cede_exposure(deps, env, info, policy_id, reinsurer, share),
+        ExecuteMsg::SettleRecoverable {} => execute_settle_recoverable(deps, info),
+        ExecuteMsg::Receive(cw20_msg) => execute_receive_cw20(deps, env, info, cw20_msg),
         ExecuteMsg::ReceiveNft(cw721_msg) => execute_receive_nft(deps, info, cw721_msg),
-        _ => Err(error::ContractError::Std(StdError::generic_err("Unsupported ExecuteMsg"))),
     }
 }
 
+/// Record a quote for a new policy for an underwriter to approve or decline
+#[allow(clippy::too_many_arguments)]
 pub fn execute_create_policy(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     policy_id: String,
     insured_amount: u128,
@@ -69,51 +120,1233 @@ pub fn execute_create_policy(
     premium_frequency: String,
     policy_term: String,
     condition: String,
-    riders: Vec<String>,
+    riders: Vec<Rider>,
+    trigger: Option<ParametricTrigger>,
 ) -> Result<Response, ContractError> {
-    let policy = InsurancePolicy {
+    parse_premium_period(&premium_frequency)?;
+    parse_policy_term(&policy_term)?;
+    for (i, rider) in riders.iter().enumerate() {
+        parse_policy_term(&rider.term)?;
+        if riders[..i].iter().any(|other| other.rider_id == rider.rider_id) {
+            return Err(ContractError::RiderExists { rider_id: rider.rider_id.clone() });
+        }
+    }
+    if QUOTES.may_load(deps.storage, &policy_id)?.is_some()
+        || INSURANCE_POLICIES.may_load(deps.storage, &policy_id)?.is_some()
+    {
+        return Err(ContractError::PolicyExists { policy_id });
+    }
+
+    let quote = Quote {
         policy_id: policy_id.clone(),
+        applicant: info.sender.clone(),
         insured_amount,
         premium,
-        premium_frequency: premium_frequency.clone(),
-        policy_term: policy_term.clone(),
-        owner: info.sender.clone(),
+        premium_frequency,
+        policy_term,
+        condition,
+        riders,
+        trigger,
+        status: QuoteStatus::Pending,
+        requested_at: env.block.time.seconds(),
+        underwriter: None,
+        reason: None,
+    };
+    QUOTES.save(deps.storage, &policy_id, &quote)?;
+    PENDING_QUOTES.save(deps.storage, &policy_id, &true)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_create_policy")
+        .add_attribute("policy_id", policy_id)
+        .add_attribute("insured_amount", insured_amount.to_string())
+        .add_attribute("premium", premium.to_string())
+        .add_attribute("owner", info.sender.to_string()))
+}
+
+/// Enable or disable an underwriter; only the admin may manage underwriters
+pub fn execute_set_underwriter(
+    deps: DepsMut,
+    info: MessageInfo,
+    underwriter: String,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    if info.sender != ADMIN.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let underwriter = deps.api.addr_validate(&underwriter)?;
+    if enabled {
+        UNDERWRITERS.save(deps.storage, underwriter.as_str(), &true)?;
+    } else {
+        UNDERWRITERS.remove(deps.storage, underwriter.as_str());
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_set_underwriter")
+        .add_attribute("underwriter", underwriter.to_string())
+        .add_attribute("enabled", enabled.to_string()))
+}
+
+/// Approve a pending quote: the policy is activated, its exposure is booked against the reserve
+/// and the policy NFT is minted to the applicant
+pub fn execute_approve_quote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    policy_id: String,
+    premium: Option<u128>,
+) -> Result<Response, ContractError> {
+    let mut quote = pending_quote(deps.storage, &info.sender, &policy_id)?;
+    if let Some(premium) = premium {
+        quote.premium = premium;
+    }
+
+    let now = env.block.time.seconds();
+    let premium_period = parse_premium_period(&quote.premium_frequency)?;
+    let term_length = parse_policy_term(&quote.policy_term)?;
+    let grace_period = GRACE_PERIOD.may_load(deps.storage)?.unwrap_or(premium_period);
+    let riders = quote
+        .riders
+        .iter()
+        .map(|rider| policy_rider(rider.clone(), now))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut policy = InsurancePolicy {
+        policy_id: policy_id.clone(),
+        insured_amount: quote.insured_amount,
+        premium: quote.premium,
+        premium_frequency: quote.premium_frequency.clone(),
+        policy_term: quote.policy_term.clone(),
+        owner: quote.applicant.clone(),
         claimed: false,
-        condition: condition.clone(),
-        riders: riders.clone(),
+        condition: quote.condition.clone(),
+        riders,
+        premium_period,
+        // The first premium is due right away
+        next_premium_due: env.block.time.seconds(),
+        grace_period: Some(grace_period),
+        installments_paid: 0,
+        premiums_paid: 0,
+        claim_ids: vec![],
+        term_length,
+        term_end: env.block.time.seconds() + term_length,
+        cancelled_at: None,
+        exposure_released: true,
+        booked_exposure: 0,
+        trigger: quote.trigger.clone(),
+        triggered_at: None,
     };
+    book_exposure(deps.storage, &mut policy, now)?;
 
     INSURANCE_POLICIES.save(deps.storage, &policy_id, &policy)?;
+    watch_trigger(deps.storage, &policy)?;
+
+    quote.status = QuoteStatus::Approved;
+    quote.underwriter = Some(info.sender.clone());
+    QUOTES.save(deps.storage, &policy_id, &quote)?;
+    PENDING_QUOTES.remove(deps.storage, &policy_id);
 
     // Mint NFT
     let cw721_contract_address = CW721_CONTRACT_ADDRESS.load(deps.storage)?;
     let mint_msg = MintMsg::<PolicyMetadata> {
         token_id: policy_id.clone(),
-        owner: info.sender.to_string(),
+        owner: quote.applicant.to_string(),
         token_uri: None,
         extension: PolicyMetadata {
             policy_id: policy_id.clone(),
-            insured_amount,
-            premium,
-            premium_frequency,
-            policy_term,
-            condition,
-            riders,
+            insured_amount: quote.insured_amount,
+            premium: quote.premium,
+            premium_frequency: quote.premium_frequency,
+            policy_term: quote.policy_term,
+            condition: quote.condition,
+            riders: quote.riders,
         },
     };
     let wasm_msg = WasmMsg::Execute {
-        contract_addr: cw721_contract_address.into(),
+        contract_addr: cw721_contract_address,
         msg: to_binary(&mint_msg)?,
         funds: vec![],
     };
 
     Ok(Response::new()
         .add_message(wasm_msg)
-        .add_attribute("method", "execute_create_policy")
+        .add_attribute("method", "execute_approve_quote")
         .add_attribute("policy_id", policy_id)
-        .add_attribute("insured_amount", insured_amount.to_string())
-        .add_attribute("premium", premium.to_string())
-        .add_attribute("owner", info.sender.to_string()))
+        .add_attribute("underwriter", info.sender.to_string())
+        .add_attribute("premium", quote.premium.to_string())
+        .add_attribute("owner", quote.applicant.to_string()))
+}
+
+pub fn execute_decline_quote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    policy_id: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    let mut quote = pending_quote(deps.storage, &info.sender, &policy_id)?;
+    quote.status = QuoteStatus::Declined;
+    quote.underwriter = Some(info.sender.clone());
+    quote.reason = Some(reason.clone());
+    QUOTES.save(deps.storage, &policy_id, &quote)?;
+    PENDING_QUOTES.remove(deps.storage, &policy_id);
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_decline_quote")
+        .add_attribute("policy_id", policy_id)
+        .add_attribute("underwriter", info.sender.to_string())
+        .add_attribute("reason", reason)
+        .add_attribute("declined_at", env.block.time.seconds().to_string()))
+}
+
+/// Load a quote awaiting a decision, checking that `sender` is an underwriter
+fn pending_quote(storage: &dyn Storage, sender: &Addr, policy_id: &str) -> Result<Quote, ContractError> {
+    if UNDERWRITERS.may_load(storage, sender.as_str())?.is_none() {
+        return Err(ContractError::Unauthorized {});
+    }
+    let quote = QUOTES.load(storage, policy_id)?;
+    if quote.status != QuoteStatus::Pending {
+        return Err(ContractError::QuoteNotPending {});
+    }
+    Ok(quote)
+}
+
+/// Pay the next premium of a policy with native funds
+pub fn execute_pay_premium(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    policy_id: String,
+    amount: u128,
+) -> Result<Response, ContractError> {
+    let denom = PREMIUM_DENOM.load(deps.storage)?.ok_or(ContractError::InvalidPremium {})?;
+    let expected = Coin { denom, amount: Uint128::from(amount) };
+    if info.funds != [expected] {
+        return Err(ContractError::InvalidPremium {});
+    }
+
+    pay_premium(deps.storage, &env, &policy_id, amount, &info.sender)
+}
+
+/// Pay a premium or fund the reserve with the cw20 token; the hook message is a `Cw20HookMsg`
+pub fn execute_receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let cw20_token_address = CW20_TOKEN_ADDRESS.load(deps.storage)?;
+    if info.sender != cw20_token_address || PREMIUM_DENOM.load(deps.storage)?.is_some() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let payer = deps.api.addr_validate(&cw20_msg.sender)?;
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::PayPremium { policy_id, amount } => {
+            if cw20_msg.amount != Uint128::from(amount) {
+                return Err(ContractError::InvalidPremium {});
+            }
+            pay_premium(deps.storage, &env, &policy_id, amount, &payer)
+        }
+        Cw20HookMsg::FundReserve {} => fund_reserve(deps.storage, cw20_msg.amount.u128(), &payer),
+        Cw20HookMsg::SettleRecoverable {} => settle_recoverable(deps.storage, cw20_msg.amount.u128(), &payer),
+    }
+}
+
+/// Record a premium payment as the next installment, extending coverage by one premium period.
+/// The share ceded to reinsurers is forwarded to them, the rest goes to the reserve
+fn pay_premium(
+    storage: &mut dyn Storage,
+    env: &Env,
+    policy_id: &str,
+    amount: u128,
+    payer: &Addr,
+) -> Result<Response, ContractError> {
+    let mut policy = INSURANCE_POLICIES.load(storage, policy_id)?;
+    assert_active(&policy, env.block.time.seconds())?;
+    if amount != current_premium(&policy, env.block.time.seconds()) {
+        return Err(ContractError::InvalidPremium {});
+    }
+
+    let installment = Installment {
+        amount,
+        payer: payer.clone(),
+        paid_at: env.block.time.seconds(),
+        covers_from: policy.next_premium_due,
+        covers_until: policy.next_premium_due + policy.premium_period,
+    };
+    policy.installments_paid += 1;
+    policy.premiums_paid += amount;
+    INSTALLMENTS.save(storage, (policy_id, U64Key::new(policy.installments_paid)), &installment)?;
+    policy.next_premium_due = installment.covers_until;
+    INSURANCE_POLICIES.save(storage, policy_id, &policy)?;
+
+    let (ceded, cession_msgs) = cede_premium(storage, policy_id, amount)?;
+    let mut reserve = RESERVE.load(storage)?;
+    reserve.balance += amount - ceded;
+    reserve.premiums_collected += amount;
+    reserve.premiums_ceded += ceded;
+    RESERVE.save(storage, &reserve)?;
+
+    Ok(Response::new()
+        .add_messages(cession_msgs)
+        .add_attribute("method", "execute_pay_premium")
+        .add_attribute("policy_id", policy_id)
+        .add_attribute("payer", payer.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("installment", policy.installments_paid.to_string())
+        .add_attribute("premium_ceded", ceded.to_string())
+        .add_attribute("next_premium_due", policy.next_premium_due.to_string()))
+}
+
+/// File a claim against a paid-up policy for an assessor to decide on
+pub fn execute_file_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    policy_id: String,
+    evidence_uri: String,
+) -> Result<Response, ContractError> {
+    let mut policy = INSURANCE_POLICIES.load(deps.storage, &policy_id)?;
+    assert_policy_owner(deps.as_ref(), &mut policy, &info.sender)?;
+    assert_active(&policy, env.block.time.seconds())?;
+    if env.block.time.seconds() >= policy.next_premium_due {
+        return Err(ContractError::NotCovered {});
+    }
+    if has_pending_claim(deps.storage, &policy)? {
+        return Err(ContractError::ClaimPending {});
+    }
+
+    let claim_id = NEXT_CLAIM_ID.load(deps.storage)?;
+    let claim = ClaimRecord {
+        claim_id,
+        policy_id: policy_id.clone(),
+        claimant: info.sender.clone(),
+        evidence_uri,
+        status: StateClaimStatus::Pending,
+        filed_at: env.block.time.seconds(),
+        assessor: None,
+        reason: None,
+        decided_at: None,
+    };
+    CLAIMS.save(deps.storage, U64Key::new(claim_id), &claim)?;
+    PENDING_CLAIMS.save(deps.storage, U64Key::new(claim_id), &true)?;
+    NEXT_CLAIM_ID.save(deps.storage, &(claim_id + 1))?;
+    policy.claim_ids.push(claim_id);
+    INSURANCE_POLICIES.save(deps.storage, &policy_id, &policy)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_file_claim")
+        .add_attribute("policy_id", policy_id)
+        .add_attribute("claim_id", claim_id.to_string())
+        .add_attribute("claimant", info.sender.to_string()))
+}
+
+/// Approve or reject a pending claim; approval pays the insured amount to the policy owner
+pub fn execute_assess_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    claim_id: u64,
+    approve: bool,
+    reason: String,
+) -> Result<Response, ContractError> {
+    if ASSESSORS.may_load(deps.storage, info.sender.as_str())?.is_none() {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut claim = CLAIMS.load(deps.storage, U64Key::new(claim_id))?;
+    if claim.status != StateClaimStatus::Pending {
+        return Err(ContractError::ClaimDecided {});
+    }
+
+    claim.status = if approve { StateClaimStatus::Approved } else { StateClaimStatus::Rejected };
+    claim.assessor = Some(info.sender.clone());
+    claim.reason = Some(reason.clone());
+    claim.decided_at = Some(env.block.time.seconds());
+    CLAIMS.save(deps.storage, U64Key::new(claim_id), &claim)?;
+    PENDING_CLAIMS.remove(deps.storage, U64Key::new(claim_id));
+
+    let mut response = Response::new()
+        .add_attribute("method", "execute_assess_claim")
+        .add_attribute("claim_id", claim_id.to_string())
+        .add_attribute("policy_id", claim.policy_id.clone())
+        .add_attribute("assessor", info.sender.to_string())
+        .add_attribute("approved", approve.to_string())
+        .add_attribute("reason", reason);
+
+    if approve {
+        let mut policy = INSURANCE_POLICIES.load(deps.storage, &claim.policy_id)?;
+        if policy.claimed {
+            return Err(ContractError::AlreadyClaimed {});
+        }
+        // The payout goes to whoever holds the policy NFT now
+        sync_owner(deps.as_ref(), &mut policy)?;
+        let (payout, amount) = settle_claim(deps.storage, &mut policy, env.block.time.seconds())?;
+        INSURANCE_POLICIES.save(deps.storage, &claim.policy_id, &policy)?;
+
+        response = response
+            .add_message(payout)
+            .add_attribute("payout", amount.to_string());
+    }
+    Ok(response)
+}
+
+/// Enable or disable a claims assessor; only the admin may manage assessors
+pub fn execute_set_assessor(
+    deps: DepsMut,
+    info: MessageInfo,
+    assessor: String,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    if info.sender != ADMIN.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let assessor = deps.api.addr_validate(&assessor)?;
+    if enabled {
+        ASSESSORS.save(deps.storage, assessor.as_str(), &true)?;
+    } else {
+        ASSESSORS.remove(deps.storage, assessor.as_str());
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_set_assessor")
+        .add_attribute("assessor", assessor.to_string())
+        .add_attribute("enabled", enabled.to_string()))
+}
+
+/// Enable or disable an index oracle; only the admin may manage oracles
+pub fn execute_set_oracle(
+    deps: DepsMut,
+    info: MessageInfo,
+    oracle: String,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    if info.sender != ADMIN.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let oracle = deps.api.addr_validate(&oracle)?;
+    if enabled {
+        ORACLES.save(deps.storage, oracle.as_str(), &true)?;
+    } else {
+        ORACLES.remove(deps.storage, oracle.as_str());
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_set_oracle")
+        .add_attribute("oracle", oracle.to_string())
+        .add_attribute("enabled", enabled.to_string()))
+}
+
+/// Record an index value and fire the trigger of every parametric policy on that index that is
+/// covered right now and whose threshold the value crosses
+pub fn execute_post_index_value(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    index: String,
+    value: Decimal,
+) -> Result<Response, ContractError> {
+    if ORACLES.may_load(deps.storage, info.sender.as_str())?.is_none() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let now = env.block.time.seconds();
+    INDEX_VALUES.save(deps.storage, &index, &IndexReading { value, updated_at: now })?;
+    let (triggered, next_policy) = fire_triggers(deps.storage, &index, value, now, None, MAX_LIMIT)?;
+
+    let mut response = Response::new()
+        .add_attribute("method", "execute_post_index_value")
+        .add_attribute("index", index)
+        .add_attribute("value", value.to_string())
+        .add_attribute("triggered_policies", triggered.join(","));
+    if let Some(next_policy) = next_policy {
+        response = response.add_attribute("next_policy", next_policy);
+    }
+    Ok(response)
+}
+
+/// Check a further page of the policies watching an index against the latest posted value
+pub fn execute_process_triggers(
+    deps: DepsMut,
+    env: Env,
+    index: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let reading = INDEX_VALUES.load(deps.storage, &index)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let (triggered, next_policy) = fire_triggers(deps.storage, &index, reading.value, env.block.time.seconds(), start_after, limit)?;
+
+    let mut response = Response::new()
+        .add_attribute("method", "execute_process_triggers")
+        .add_attribute("index", index)
+        .add_attribute("triggered_policies", triggered.join(","));
+    if let Some(next_policy) = next_policy {
+        response = response.add_attribute("next_policy", next_policy);
+    }
+    Ok(response)
+}
+
+/// Fire the triggers of up to `limit` policies watching an index, returning the fired policies and,
+/// when more remain, the last policy checked. Fired and no longer active policies stop watching the index
+fn fire_triggers(
+    storage: &mut dyn Storage,
+    index: &str,
+    value: Decimal,
+    now: u64,
+    start_after: Option<String>,
+    limit: u32,
+) -> StdResult<(Vec<String>, Option<String>)> {
+    if let Some(policy_ids) = LEGACY_INDEX_POLICIES.may_load(storage, index)? {
+        for policy_id in &policy_ids {
+            INDEX_POLICIES.save(storage, (index, policy_id.as_str()), &Empty {})?;
+        }
+        LEGACY_INDEX_POLICIES.remove(storage, index);
+    }
+
+    let limit = limit as usize;
+    let start = start_after.map(|policy_id| Bound::Exclusive(policy_id.into_bytes()));
+    let mut policy_ids = INDEX_POLICIES
+        .prefix(index)
+        .range(storage, start, None, cosmwasm_std::Order::Ascending)
+        .take(limit + 1)
+        .map(|item| {
+            let (key, _) = item?;
+            String::from_utf8(key).map_err(|_| StdError::generic_err("Invalid policy key"))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let more = policy_ids.len() > limit;
+    policy_ids.truncate(limit);
+
+    let mut triggered = vec![];
+    for policy_id in &policy_ids {
+        let mut policy = INSURANCE_POLICIES.load(storage, policy_id)?;
+        // Only active policies can fire; renewing an expired policy watches the index again
+        if policy_status(&policy, now) != PolicyStatus::Active {
+            INDEX_POLICIES.remove(storage, (index, policy_id.as_str()));
+            continue;
+        }
+        let fired = match &policy.trigger {
+            Some(trigger) => now < policy.next_premium_due && is_crossed(trigger, value),
+            None => false,
+        };
+        if fired {
+            policy.triggered_at = Some(now);
+            INSURANCE_POLICIES.save(storage, policy_id, &policy)?;
+            INDEX_POLICIES.remove(storage, (index, policy_id.as_str()));
+            triggered.push(policy_id.clone());
+        }
+    }
+    let next_policy = if more { policy_ids.pop() } else { None };
+    Ok((triggered, next_policy))
+}
+
+/// Pay the insured amount of a parametric policy whose trigger has fired to the policy owner
+pub fn execute_claim_parametric(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    policy_id: String,
+) -> Result<Response, ContractError> {
+    let mut policy = INSURANCE_POLICIES.load(deps.storage, &policy_id)?;
+    assert_policy_owner(deps.as_ref(), &mut policy, &info.sender)?;
+    if policy.trigger.is_none() {
+        return Err(ContractError::NotParametric {});
+    }
+    if policy.claimed {
+        return Err(ContractError::AlreadyClaimed {});
+    }
+    if policy.triggered_at.is_none() {
+        return Err(ContractError::NotTriggered {});
+    }
+
+    let (payout, amount) = settle_claim(deps.storage, &mut policy, env.block.time.seconds())?;
+    INSURANCE_POLICIES.save(deps.storage, &policy_id, &policy)?;
+
+    Ok(Response::new()
+        .add_message(payout)
+        .add_attribute("method", "execute_claim_parametric")
+        .add_attribute("policy_id", policy_id)
+        .add_attribute("payout", amount.to_string()))
+}
+
+fn is_crossed(trigger: &ParametricTrigger, value: Decimal) -> bool {
+    match trigger.comparison {
+        TriggerComparison::Above => value >= trigger.threshold,
+        TriggerComparison::Below => value <= trigger.threshold,
+    }
+}
+
+/// Add capital to the reserve with the native premium denom
+pub fn execute_fund_reserve(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let denom = PREMIUM_DENOM.load(deps.storage)?.ok_or(ContractError::InvalidFunds {})?;
+    let amount = match info.funds.as_slice() {
+        [coin] if coin.denom == denom && !coin.amount.is_zero() => coin.amount.u128(),
+        _ => return Err(ContractError::InvalidFunds {}),
+    };
+
+    fund_reserve(deps.storage, amount, &info.sender)
+}
+
+fn fund_reserve(storage: &mut dyn Storage, amount: u128, funder: &Addr) -> Result<Response, ContractError> {
+    let mut reserve = RESERVE.load(storage)?;
+    reserve.balance += amount;
+    reserve.capital_contributed += amount;
+    RESERVE.save(storage, &reserve)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_fund_reserve")
+        .add_attribute("funder", funder.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("reserve", reserve.balance.to_string()))
+}
+
+/// Pay part of the reserve to the treasury, as long as what is left still covers the current exposure
+pub fn execute_withdraw_surplus(deps: DepsMut, info: MessageInfo, amount: u128) -> Result<Response, ContractError> {
+    let treasury = deps.api.addr_validate(&TREASURY_ADDRESS.load(deps.storage)?)?;
+    if info.sender != treasury {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut reserve = RESERVE.load(deps.storage)?;
+    if amount == 0 || reserve.balance < amount {
+        return Err(ContractError::InsufficientReserve { reserve: reserve.balance, payout: amount });
+    }
+    reserve.balance -= amount;
+    let max_exposure = (Uint128::from(reserve.balance) * MAX_EXPOSURE_MULTIPLE.load(deps.storage)?).u128();
+    if EXPOSURE.load(deps.storage)?.total > max_exposure {
+        return Err(ContractError::ExposureLimit { max_exposure });
+    }
+    reserve.surplus_withdrawn += amount;
+    RESERVE.save(deps.storage, &reserve)?;
+
+    Ok(Response::new()
+        .add_message(payout_msg(deps.storage, &treasury, amount)?)
+        .add_attribute("method", "execute_withdraw_surplus")
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("reserve", reserve.balance.to_string()))
+}
+
+/// Set how many times the reserve balance the total insured exposure may reach
+pub fn execute_set_exposure_multiple(
+    deps: DepsMut,
+    info: MessageInfo,
+    multiple: Decimal,
+) -> Result<Response, ContractError> {
+    if info.sender != ADMIN.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    MAX_EXPOSURE_MULTIPLE.save(deps.storage, &multiple)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_set_exposure_multiple")
+        .add_attribute("multiple", multiple.to_string()))
+}
+
+/// Set the grace period of policies issued from now on; policies already issued keep theirs
+pub fn execute_set_grace_period(
+    deps: DepsMut,
+    info: MessageInfo,
+    grace_period: Option<u64>,
+) -> Result<Response, ContractError> {
+    if info.sender != ADMIN.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match grace_period {
+        Some(grace_period) => GRACE_PERIOD.save(deps.storage, &grace_period)?,
+        None => GRACE_PERIOD.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_set_grace_period")
+        .add_attribute("grace_period", grace_period.map_or_else(|| "premium_period".to_string(), |grace_period| grace_period.to_string())))
+}
+
+/// Largest total exposure the current reserve balance allows
+fn max_exposure(storage: &dyn Storage) -> StdResult<u128> {
+    let reserve = RESERVE.load(storage)?;
+    let multiple = MAX_EXPOSURE_MULTIPLE.load(storage)?;
+    Ok((Uint128::from(reserve.balance) * multiple).u128())
+}
+
+/// Add an amount to the exposure, which must stay covered by the reserve
+fn add_exposure(storage: &mut dyn Storage, amount: u128) -> Result<Exposure, ContractError> {
+    let mut exposure = EXPOSURE.load(storage)?;
+    let max_exposure = max_exposure(storage)?;
+    exposure.total = exposure
+        .total
+        .checked_add(amount)
+        .filter(|total| *total <= max_exposure)
+        .ok_or(ContractError::ExposureLimit { max_exposure })?;
+    EXPOSURE.save(storage, &exposure)?;
+    Ok(exposure)
+}
+
+/// Count the insured amount of a policy and its riders towards the exposure. Riders whose term
+/// is over are dropped from the policy first, so renewing a policy does not bring them back
+fn book_exposure(storage: &mut dyn Storage, policy: &mut InsurancePolicy, now: u64) -> Result<(), ContractError> {
+    policy.riders.retain(|rider| now < rider.term_end);
+    let amount = policy.insured_amount + policy.riders.iter().map(|rider| rider.coverage).sum::<u128>();
+    let mut exposure = add_exposure(storage, amount)?;
+    exposure.active_policies += 1;
+    EXPOSURE.save(storage, &exposure)?;
+    rebook_ceded(storage, &policy.policy_id, policy.booked_exposure, amount)?;
+    policy.booked_exposure = amount;
+    policy.exposure_released = false;
+    Ok(())
+}
+
+/// Stop counting a policy towards the exposure
+fn release_exposure(storage: &mut dyn Storage, policy: &mut InsurancePolicy) -> StdResult<()> {
+    if policy.exposure_released {
+        return Ok(());
+    }
+    let mut exposure = EXPOSURE.load(storage)?;
+    exposure.total -= policy.booked_exposure;
+    exposure.active_policies -= 1;
+    EXPOSURE.save(storage, &exposure)?;
+    rebook_ceded(storage, &policy.policy_id, policy.booked_exposure, 0)?;
+    policy.booked_exposure = 0;
+    policy.exposure_released = true;
+    Ok(())
+}
+
+/// Pay the coverage of a policy out of the reserve, marking it claimed and releasing its exposure
+fn settle_claim(
+    storage: &mut dyn Storage,
+    policy: &mut InsurancePolicy,
+    now: u64,
+) -> Result<(CosmosMsg, u128), ContractError> {
+    let amount = coverage(policy, now);
+    let mut reserve = RESERVE.load(storage)?;
+    if reserve.balance < amount {
+        return Err(ContractError::InsufficientReserve { reserve: reserve.balance, payout: amount });
+    }
+    reserve.balance -= amount;
+    reserve.claims_paid += amount;
+    RESERVE.save(storage, &reserve)?;
+
+    book_recoverables(storage, &policy.policy_id, amount)?;
+
+    policy.claimed = true;
+    release_exposure(storage, policy)?;
+    Ok((payout_msg(storage, &policy.owner, amount)?, amount))
+}
+
+/// Cede a share of an active policy to a reinsurer; the shares of all reinsurers may not exceed one
+pub fn execute_cede_exposure(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    policy_id: String,
+    reinsurer: String,
+    share: Decimal,
+) -> Result<Response, ContractError> {
+    if info.sender != TREASURY_ADDRESS.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    let policy = INSURANCE_POLICIES.load(deps.storage, &policy_id)?;
+    assert_active(&policy, env.block.time.seconds())?;
+
+    let reinsurer = deps.api.addr_validate(&reinsurer)?;
+    let mut cessions = CESSIONS.may_load(deps.storage, &policy_id)?.unwrap_or_default();
+    if cessions.iter().any(|cession| cession.reinsurer == reinsurer) {
+        return Err(ContractError::CessionExists { reinsurer: reinsurer.to_string() });
+    }
+    let ceded_share = cessions.iter().fold(share, |total, cession| total + cession.share);
+    if share.is_zero() || ceded_share > Decimal::one() {
+        return Err(ContractError::InvalidCession {});
+    }
+    cessions.push(Cession { reinsurer: reinsurer.clone(), share, ceded_at: env.block.time.seconds() });
+    CESSIONS.save(deps.storage, &policy_id, &cessions)?;
+
+    let part = (Uint128::from(policy.booked_exposure) * share).u128();
+    let mut account = REINSURERS.may_load(deps.storage, reinsurer.as_str())?.unwrap_or_default();
+    account.ceded_exposure += part;
+    REINSURERS.save(deps.storage, reinsurer.as_str(), &account)?;
+    let mut exposure = EXPOSURE.load(deps.storage)?;
+    exposure.ceded += part;
+    EXPOSURE.save(deps.storage, &exposure)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_cede_exposure")
+        .add_attribute("policy_id", policy_id)
+        .add_attribute("reinsurer", reinsurer.to_string())
+        .add_attribute("share", share.to_string())
+        .add_attribute("ceded_share", ceded_share.to_string()))
+}
+
+/// Pay back the reinsurer share of claims and refunds with the native premium denom
+pub fn execute_settle_recoverable(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let denom = PREMIUM_DENOM.load(deps.storage)?.ok_or(ContractError::InvalidFunds {})?;
+    let amount = match info.funds.as_slice() {
+        [coin] if coin.denom == denom && !coin.amount.is_zero() => coin.amount.u128(),
+        _ => return Err(ContractError::InvalidFunds {}),
+    };
+
+    settle_recoverable(deps.storage, amount, &info.sender)
+}
+
+fn settle_recoverable(storage: &mut dyn Storage, amount: u128, reinsurer: &Addr) -> Result<Response, ContractError> {
+    let mut account = REINSURERS.may_load(storage, reinsurer.as_str())?.ok_or(ContractError::Unauthorized {})?;
+    if amount > account.claims_recoverable {
+        return Err(ContractError::InvalidFunds {});
+    }
+    account.claims_recoverable -= amount;
+    account.claims_recovered += amount;
+    REINSURERS.save(storage, reinsurer.as_str(), &account)?;
+
+    let mut reserve = RESERVE.load(storage)?;
+    reserve.balance += amount;
+    reserve.reinsurance_recovered += amount;
+    RESERVE.save(storage, &reserve)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_settle_recoverable")
+        .add_attribute("reinsurer", reinsurer.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("claims_recoverable", account.claims_recoverable.to_string()))
+}
+
+/// Forward the ceded shares of a premium to the reinsurers of a policy, returning the total ceded
+fn cede_premium(storage: &mut dyn Storage, policy_id: &str, amount: u128) -> StdResult<(u128, Vec<CosmosMsg>)> {
+    let mut ceded = 0;
+    let mut msgs = vec![];
+    for cession in CESSIONS.may_load(storage, policy_id)?.unwrap_or_default() {
+        let part = (Uint128::from(amount) * cession.share).u128();
+        if part == 0 {
+            continue;
+        }
+        let mut account = REINSURERS.load(storage, cession.reinsurer.as_str())?;
+        account.premiums_ceded += part;
+        REINSURERS.save(storage, cession.reinsurer.as_str(), &account)?;
+        msgs.push(payout_msg(storage, &cession.reinsurer, part)?);
+        ceded += part;
+    }
+    Ok((ceded, msgs))
+}
+
+/// Book the ceded shares of an amount paid out of the reserve as owed by the reinsurers
+fn book_recoverables(storage: &mut dyn Storage, policy_id: &str, amount: u128) -> StdResult<()> {
+    for cession in CESSIONS.may_load(storage, policy_id)?.unwrap_or_default() {
+        let mut account = REINSURERS.load(storage, cession.reinsurer.as_str())?;
+        account.claims_recoverable += (Uint128::from(amount) * cession.share).u128();
+        REINSURERS.save(storage, cession.reinsurer.as_str(), &account)?;
+    }
+    Ok(())
+}
+
+/// Move the reinsured parts of a policy's booked exposure from `old_booked` to `new_booked` in the
+/// running totals of its reinsurers and of the contract
+fn rebook_ceded(storage: &mut dyn Storage, policy_id: &str, old_booked: u128, new_booked: u128) -> StdResult<()> {
+    let cessions = CESSIONS.may_load(storage, policy_id)?.unwrap_or_default();
+    if cessions.is_empty() {
+        return Ok(());
+    }
+    let mut exposure = EXPOSURE.load(storage)?;
+    for cession in cessions {
+        let old_part = (Uint128::from(old_booked) * cession.share).u128();
+        let new_part = (Uint128::from(new_booked) * cession.share).u128();
+        let mut account = REINSURERS.load(storage, cession.reinsurer.as_str())?;
+        account.ceded_exposure = account.ceded_exposure.saturating_sub(old_part) + new_part;
+        REINSURERS.save(storage, cession.reinsurer.as_str(), &account)?;
+        exposure.ceded = exposure.ceded.saturating_sub(old_part) + new_part;
+    }
+    EXPOSURE.save(storage, &exposure)
+}
+
+/// Part of a policy's booked exposure carried by its reinsurers
+fn ceded_exposure(policy: &InsurancePolicy, cessions: &[Cession]) -> u128 {
+    cessions
+        .iter()
+        .map(|cession| (Uint128::from(policy.booked_exposure) * cession.share).u128())
+        .sum()
+}
+
+/// Request a rider for an active policy for an underwriter to approve or decline
+pub fn execute_attach_rider(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    policy_id: String,
+    rider: Rider,
+) -> Result<Response, ContractError> {
+    let now = env.block.time.seconds();
+    let mut policy = INSURANCE_POLICIES.load(deps.storage, &policy_id)?;
+    assert_policy_owner(deps.as_ref(), &mut policy, &info.sender)?;
+    assert_active(&policy, now)?;
+    parse_policy_term(&rider.term)?;
+    if policy.riders.iter().any(|attached| attached.rider_id == rider.rider_id)
+        || RIDER_QUOTES.may_load(deps.storage, (policy_id.as_str(), rider.rider_id.as_str()))?.is_some()
+    {
+        return Err(ContractError::RiderExists { rider_id: rider.rider_id });
+    }
+    RIDER_QUOTES.save(deps.storage, (policy_id.as_str(), rider.rider_id.as_str()), &rider)?;
+    INSURANCE_POLICIES.save(deps.storage, &policy_id, &policy)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_attach_rider")
+        .add_attribute("policy_id", policy_id)
+        .add_attribute("rider_id", rider.rider_id)
+        .add_attribute("rider_premium", rider.premium.to_string()))
+}
+
+/// Approve a requested rider: it is attached from now on and its coverage is booked against the reserve
+pub fn execute_approve_rider(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    policy_id: String,
+    rider_id: String,
+    premium: Option<u128>,
+) -> Result<Response, ContractError> {
+    let mut rider = pending_rider(deps.storage, &info.sender, &policy_id, &rider_id)?;
+    if let Some(premium) = premium {
+        rider.premium = premium;
+    }
+    let now = env.block.time.seconds();
+    let mut policy = INSURANCE_POLICIES.load(deps.storage, &policy_id)?;
+    assert_active(&policy, now)?;
+
+    let rider = policy_rider(rider, now)?;
+    add_exposure(deps.storage, rider.coverage)?;
+    rebook_ceded(deps.storage, &policy_id, policy.booked_exposure, policy.booked_exposure + rider.coverage)?;
+    policy.booked_exposure += rider.coverage;
+    let rider_premium = rider.premium;
+    policy.riders.push(rider);
+    INSURANCE_POLICIES.save(deps.storage, &policy_id, &policy)?;
+    RIDER_QUOTES.remove(deps.storage, (policy_id.as_str(), rider_id.as_str()));
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_approve_rider")
+        .add_attribute("policy_id", policy_id)
+        .add_attribute("rider_id", rider_id)
+        .add_attribute("underwriter", info.sender.to_string())
+        .add_attribute("rider_premium", rider_premium.to_string())
+        .add_attribute("premium", current_premium(&policy, now).to_string()))
+}
+
+pub fn execute_decline_rider(
+    deps: DepsMut,
+    info: MessageInfo,
+    policy_id: String,
+    rider_id: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    pending_rider(deps.storage, &info.sender, &policy_id, &rider_id)?;
+    RIDER_QUOTES.remove(deps.storage, (policy_id.as_str(), rider_id.as_str()));
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_decline_rider")
+        .add_attribute("policy_id", policy_id)
+        .add_attribute("rider_id", rider_id)
+        .add_attribute("underwriter", info.sender.to_string())
+        .add_attribute("reason", reason))
+}
+
+/// Load a rider awaiting a decision, checking that `sender` is an underwriter
+fn pending_rider(storage: &dyn Storage, sender: &Addr, policy_id: &str, rider_id: &str) -> Result<Rider, ContractError> {
+    if UNDERWRITERS.may_load(storage, sender.as_str())?.is_none() {
+        return Err(ContractError::Unauthorized {});
+    }
+    RIDER_QUOTES.may_load(storage, (policy_id, rider_id))?.ok_or(ContractError::QuoteNotPending {})
+}
+
+/// Detach a rider from an active policy, releasing its coverage
+pub fn execute_detach_rider(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    policy_id: String,
+    rider_id: String,
+) -> Result<Response, ContractError> {
+    let now = env.block.time.seconds();
+    let mut policy = INSURANCE_POLICIES.load(deps.storage, &policy_id)?;
+    assert_policy_owner(deps.as_ref(), &mut policy, &info.sender)?;
+    assert_active(&policy, now)?;
+    let position = policy
+        .riders
+        .iter()
+        .position(|rider| rider.rider_id == rider_id)
+        .ok_or_else(|| ContractError::RiderNotFound { rider_id: rider_id.clone() })?;
+
+    let rider = policy.riders.remove(position);
+    let mut exposure = EXPOSURE.load(deps.storage)?;
+    exposure.total -= rider.coverage;
+    EXPOSURE.save(deps.storage, &exposure)?;
+    rebook_ceded(deps.storage, &policy_id, policy.booked_exposure, policy.booked_exposure - rider.coverage)?;
+    policy.booked_exposure -= rider.coverage;
+    INSURANCE_POLICIES.save(deps.storage, &policy_id, &policy)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_detach_rider")
+        .add_attribute("policy_id", policy_id)
+        .add_attribute("rider_id", rider_id)
+        .add_attribute("premium", current_premium(&policy, now).to_string()))
+}
+
+fn policy_rider(rider: Rider, now: u64) -> Result<PolicyRider, ContractError> {
+    let term_end = now + parse_policy_term(&rider.term)?;
+    Ok(PolicyRider {
+        rider_id: rider.rider_id,
+        description: rider.description,
+        premium: rider.premium,
+        coverage: rider.coverage,
+        term: rider.term,
+        attached_at: now,
+        term_end,
+    })
+}
+
+/// Premium due per period: the base premium plus the premiums of riders still in force
+fn current_premium(policy: &InsurancePolicy, now: u64) -> u128 {
+    policy.premium
+        + policy
+            .riders
+            .iter()
+            .filter(|rider| now < rider.term_end)
+            .map(|rider| rider.premium)
+            .sum::<u128>()
+}
+
+/// Payout of a claim: the insured amount plus the coverage of riders still in force
+fn coverage(policy: &InsurancePolicy, now: u64) -> u128 {
+    policy.insured_amount
+        + policy
+            .riders
+            .iter()
+            .filter(|rider| now < rider.term_end)
+            .map(|rider| rider.coverage)
+            .sum::<u128>()
+}
+
+/// Start a new policy term. A policy in good standing is extended from the end of its current term;
+/// an expired one starts over now, with any premiums not yet paid due right away
+pub fn execute_renew_policy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    policy_id: String,
+) -> Result<Response, ContractError> {
+    let now = env.block.time.seconds();
+    let mut policy = INSURANCE_POLICIES.load(deps.storage, &policy_id)?;
+    assert_policy_owner(deps.as_ref(), &mut policy, &info.sender)?;
+    match policy_status(&policy, now) {
+        PolicyStatus::Active => policy.term_end += policy.term_length,
+        PolicyStatus::Expired => {
+            policy.term_end = now + policy.term_length;
+            policy.next_premium_due = policy.next_premium_due.max(now);
+            policy.triggered_at = None;
+            if policy.exposure_released {
+                book_exposure(deps.storage, &mut policy, now)?;
+            }
+            watch_trigger(deps.storage, &policy)?;
+        }
+        PolicyStatus::Lapsed => return Err(ContractError::PolicyLapsed {}),
+        PolicyStatus::Cancelled => return Err(ContractError::PolicyCancelled {}),
+        PolicyStatus::Claimed => return Err(ContractError::AlreadyClaimed {}),
+    }
+    INSURANCE_POLICIES.save(deps.storage, &policy_id, &policy)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_renew_policy")
+        .add_attribute("policy_id", policy_id)
+        .add_attribute("term_end", policy.term_end.to_string()))
+}
+
+/// Cancel an active policy, refunding the premiums paid for coverage beyond the current time
+pub fn execute_cancel_policy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    policy_id: String,
+) -> Result<Response, ContractError> {
+    let now = env.block.time.seconds();
+    let mut policy = INSURANCE_POLICIES.load(deps.storage, &policy_id)?;
+    assert_policy_owner(deps.as_ref(), &mut policy, &info.sender)?;
+    assert_active(&policy, now)?;
+    if has_pending_claim(deps.storage, &policy)? || policy.triggered_at.is_some() {
+        return Err(ContractError::ClaimPending {});
+    }
+
+    // Pro rata share of the premiums paid for the time left until the next premium is due
+    let unused = policy.next_premium_due.saturating_sub(now);
+    let refund = Uint128::from(current_premium(&policy, now))
+        .multiply_ratio(unused, policy.premium_period)
+        .u128();
+
+    policy.cancelled_at = Some(now);
+    release_exposure(deps.storage, &mut policy)?;
+    INSURANCE_POLICIES.save(deps.storage, &policy_id, &policy)?;
+
+    let mut response = Response::new()
+        .add_attribute("method", "execute_cancel_policy")
+        .add_attribute("policy_id", policy_id)
+        .add_attribute("refund", refund.to_string());
+    if refund > 0 {
+        let mut reserve = RESERVE.load(deps.storage)?;
+        if reserve.balance < refund {
+            return Err(ContractError::InsufficientReserve { reserve: reserve.balance, payout: refund });
+        }
+        reserve.balance -= refund;
+        reserve.premiums_refunded += refund;
+        RESERVE.save(deps.storage, &reserve)?;
+        book_recoverables(deps.storage, &policy.policy_id, refund)?;
+        response = response.add_message(payout_msg(deps.storage, &policy.owner, refund)?);
+    }
+    Ok(response)
+}
+
+/// Release the exposure of a policy that expired or lapsed; anyone may call this
+pub fn execute_close_policy(deps: DepsMut, env: Env, policy_id: String) -> Result<Response, ContractError> {
+    let mut policy = INSURANCE_POLICIES.load(deps.storage, &policy_id)?;
+    match policy_status(&policy, env.block.time.seconds()) {
+        PolicyStatus::Expired | PolicyStatus::Lapsed => {}
+        _ => return Err(ContractError::PolicyActive {}),
+    }
+    release_exposure(deps.storage, &mut policy)?;
+    INSURANCE_POLICIES.save(deps.storage, &policy_id, &policy)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_close_policy")
+        .add_attribute("policy_id", policy_id))
+}
+
+/// Transfer a policy to whoever holds its NFT on the cw721 contract; anyone may call this after a sale
+pub fn execute_sync_policy_owner(deps: DepsMut, policy_id: String) -> Result<Response, ContractError> {
+    let mut policy = INSURANCE_POLICIES.load(deps.storage, &policy_id)?;
+    let previous_owner = policy.owner.clone();
+    sync_owner(deps.as_ref(), &mut policy)?;
+    INSURANCE_POLICIES.save(deps.storage, &policy_id, &policy)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_sync_policy_owner")
+        .add_attribute("policy_id", policy_id)
+        .add_attribute("previous_owner", previous_owner.to_string())
+        .add_attribute("owner", policy.owner.to_string()))
+}
+
+/// Make the current holder of the policy NFT the policy owner, so that rights and payouts follow the NFT
+fn sync_owner(deps: Deps, policy: &mut InsurancePolicy) -> StdResult<()> {
+    let holder: OwnerOfResponse = deps.querier.query_wasm_smart(
+        CW721_CONTRACT_ADDRESS.load(deps.storage)?,
+        &Cw721QueryMsg::OwnerOf { token_id: policy.policy_id.clone(), include_expired: None },
+    )?;
+    policy.owner = deps.api.addr_validate(&holder.owner)?;
+    Ok(())
+}
+
+/// Check that `sender` holds the policy NFT, syncing the stored owner with it
+fn assert_policy_owner(deps: Deps, policy: &mut InsurancePolicy, sender: &Addr) -> Result<(), ContractError> {
+    sync_owner(deps, policy)?;
+    if *sender != policy.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+fn has_pending_claim(storage: &dyn Storage, policy: &InsurancePolicy) -> StdResult<bool> {
+    for claim_id in &policy.claim_ids {
+        if PENDING_CLAIMS.may_load(storage, U64Key::new(*claim_id))?.is_some() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Watch the trigger index of a parametric policy for new values
+fn watch_trigger(storage: &mut dyn Storage, policy: &InsurancePolicy) -> StdResult<()> {
+    if let Some(trigger) = &policy.trigger {
+        INDEX_POLICIES.save(storage, (trigger.index.as_str(), policy.policy_id.as_str()), &Empty {})?;
+    }
+    Ok(())
+}
+
+/// Transfer `amount` of the premium currency held by the contract to `recipient`
+fn payout_msg(storage: &dyn Storage, recipient: &Addr, amount: u128) -> StdResult<CosmosMsg> {
+    let msg = match PREMIUM_DENOM.load(storage)? {
+        Some(denom) => CosmosMsg::Bank(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin { denom, amount: Uint128::from(amount) }],
+        }),
+        None => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: CW20_TOKEN_ADDRESS.load(storage)?,
+            msg: to_binary(&Cw20ExecuteMsg::Transfer { recipient: recipient.to_string(), amount: Uint128::from(amount) })?,
+            funds: vec![],
+        }),
+    };
+    Ok(msg)
+}
+
+/// Seconds between premium payments for a `premium_frequency` such as "monthly" or "annually"
+fn parse_premium_period(frequency: &str) -> Result<u64, ContractError> {
+    match frequency.to_lowercase().as_str() {
+        "monthly" => Ok(30 * DAY),
+        "quarterly" => Ok(91 * DAY),
+        "semi-annually" | "semiannually" | "semi-annual" | "semiannual" => Ok(182 * DAY),
+        "annually" | "annual" | "yearly" => Ok(365 * DAY),
+        _ => Err(ContractError::InvalidFrequency { frequency: frequency.to_string() }),
+    }
+}
+
+/// Length in seconds of a `policy_term` such as "1 year" or "6 months"
+fn parse_policy_term(term: &str) -> Result<u64, ContractError> {
+    let invalid = || ContractError::InvalidTerm { term: term.to_string() };
+    let parts: Vec<&str> = term.split_whitespace().collect();
+    if parts.len() != 2 {
+        return Err(invalid());
+    }
+    let count: u64 = parts[0].parse().map_err(|_| invalid())?;
+    let unit = match parts[1].to_lowercase().trim_end_matches('s') {
+        "day" => DAY,
+        "week" => 7 * DAY,
+        "month" => 30 * DAY,
+        "year" => 365 * DAY,
+        _ => return Err(invalid()),
+    };
+    if count == 0 {
+        return Err(invalid());
+    }
+    Ok(count * unit)
+}
+
+/// A policy lapses once its grace period has passed without the due premium being paid
+fn is_lapsed(policy: &InsurancePolicy, now: u64) -> bool {
+    now >= lapses_at(policy)
+}
+
+/// Time at which the policy lapses unless the next premium is paid
+fn lapses_at(policy: &InsurancePolicy) -> u64 {
+    policy.next_premium_due + policy.grace_period.unwrap_or(policy.premium_period)
+}
+
+fn policy_status(policy: &InsurancePolicy, now: u64) -> PolicyStatus {
+    if policy.claimed {
+        PolicyStatus::Claimed
+    } else if policy.cancelled_at.is_some() {
+        PolicyStatus::Cancelled
+    } else if is_lapsed(policy, now) && lapses_at(policy) < policy.term_end {
+        // A policy that lapsed during its term stays lapsed once the term is over, so renewing cannot forgive its arrears
+        PolicyStatus::Lapsed
+    } else if now >= policy.term_end {
+        PolicyStatus::Expired
+    } else {
+        PolicyStatus::Active
+    }
+}
+
+fn assert_active(policy: &InsurancePolicy, now: u64) -> Result<(), ContractError> {
+    match policy_status(policy, now) {
+        PolicyStatus::Active => Ok(()),
+        PolicyStatus::Lapsed => Err(ContractError::PolicyLapsed {}),
+        PolicyStatus::Cancelled => Err(ContractError::PolicyCancelled {}),
+        PolicyStatus::Expired => Err(ContractError::PolicyExpired {}),
+        PolicyStatus::Claimed => Err(ContractError::AlreadyClaimed {}),
+    }
 }
 
 pub fn execute_receive_nft(
@@ -133,55 +1366,309 @@ pub fn execute_receive_nft(
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::GetPolicy { policy_id } => to_binary(&query_policy(deps, policy_id)?),
-        QueryMsg::GetAllPolicies {} => to_binary(&query_all_policies(deps)?),
+        QueryMsg::GetPolicy { policy_id } => to_binary(&query_policy(deps, env, policy_id)?),
+        QueryMsg::GetAllPolicies {} => to_binary(&query_all_policies(deps, env)?),
         QueryMsg::GetConfig {} => to_binary(&query_config(deps)?),
+        QueryMsg::GetClaim { claim_id } => to_binary(&query_claim(deps, claim_id)?),
+        QueryMsg::ClaimsByPolicy { policy_id } => to_binary(&query_claims_by_policy(deps, policy_id)?),
+        QueryMsg::PendingClaims { start_after, limit } => to_binary(&query_pending_claims(deps, start_after, limit)?),
+        QueryMsg::IndexValue { index } => to_binary(&query_index_value(deps, index)?),
+        QueryMsg::GetQuote { policy_id } => to_binary(&query_quote(deps, policy_id)?),
+        QueryMsg::PendingRiders { policy_id } => to_binary(&query_pending_riders(deps, policy_id)?),
+        QueryMsg::PendingQuotes { start_after, limit } => to_binary(&query_pending_quotes(deps, start_after, limit)?),
+        QueryMsg::ReserveStatus {} => to_binary(&query_reserve_status(deps)?),
+        QueryMsg::Exposure {} => to_binary(&query_exposure(deps)?),
+        QueryMsg::PremiumSchedule { policy_id, start_after, limit } => to_binary(&query_premium_schedule(deps, env, policy_id, start_after, limit)?),
+        QueryMsg::Cessions { policy_id } => to_binary(&query_cessions(deps, policy_id)?),
+        QueryMsg::Reinsurer { reinsurer } => to_binary(&query_reinsurer(deps, reinsurer)?),
     }
 }
 
-fn query_policy(deps: Deps, policy_id: String) -> StdResult<PolicyResponse> {
+fn query_policy(deps: Deps, env: Env, policy_id: String) -> StdResult<PolicyResponse> {
     let policy = INSURANCE_POLICIES.load(deps.storage, &policy_id)?;
-    Ok(PolicyResponse {
+    Ok(policy_response(policy, env.block.time.seconds()))
+}
+
+fn query_all_policies(deps: Deps, env: Env) -> StdResult<AllPoliciesResponse> {
+    let policies = INSURANCE_POLICIES
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| {
+            let (_key, policy) = item?;
+            Ok(policy_response(policy, env.block.time.seconds()))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(AllPoliciesResponse { policies })
+}
+
+fn policy_response(policy: InsurancePolicy, now: u64) -> PolicyResponse {
+    let lapsed = is_lapsed(&policy, now);
+    let status = policy_status(&policy, now);
+    let premium = current_premium(&policy, now);
+    let coverage = coverage(&policy, now);
+    PolicyResponse {
         policy_id: policy.policy_id,
         insured_amount: policy.insured_amount,
-        premium: policy.premium,
+        premium,
+        base_premium: policy.premium,
+        coverage,
         premium_frequency: policy.premium_frequency,
         policy_term: policy.policy_term,
         owner: policy.owner.to_string(),
         claimed: policy.claimed,
         condition: policy.condition,
-        riders: policy.riders,
-    })
+        riders: policy
+            .riders
+            .into_iter()
+            .map(|rider| RiderResponse {
+                in_force: now < rider.term_end,
+                rider_id: rider.rider_id,
+                description: rider.description,
+                premium: rider.premium,
+                coverage: rider.coverage,
+                term: rider.term,
+                attached_at: rider.attached_at,
+                term_end: rider.term_end,
+            })
+            .collect(),
+        next_premium_due: policy.next_premium_due,
+        lapsed,
+        status,
+        term_end: policy.term_end,
+        trigger: policy.trigger,
+        triggered_at: policy.triggered_at,
+    }
 }
 
-fn query_all_policies(deps: Deps) -> StdResult<AllPoliciesResponse> {
-    let policies = INSURANCE_POLICIES
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+fn query_premium_schedule(
+    deps: Deps,
+    env: Env,
+    policy_id: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PremiumScheduleResponse> {
+    let policy = INSURANCE_POLICIES.load(deps.storage, &policy_id)?;
+    let now = env.block.time.seconds();
+    let status = policy_status(&policy, now);
+
+    let upcoming_due_dates = match status {
+        PolicyStatus::Active => (0..)
+            .map(|period| policy.next_premium_due + period * policy.premium_period)
+            .take_while(|due| *due < policy.term_end)
+            .take(MAX_LIMIT as usize)
+            .collect(),
+        _ => vec![],
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|installment| Bound::Exclusive(installment.to_be_bytes().to_vec()));
+    let installments = INSTALLMENTS
+        .prefix(&policy_id)
+        .range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
+        .take(limit)
         .map(|item| {
-            let (_key, policy) = item?;
-            Ok(PolicyResponse {
-                policy_id: policy.policy_id,
-                insured_amount: policy.insured_amount,
-                premium: policy.premium,
-                premium_frequency: policy.premium_frequency,
-                policy_term: policy.policy_term,
-                owner: policy.owner.to_string(),
-                claimed: policy.claimed,
-                condition: policy.condition,
-                riders: policy.riders,
+            let (key, installment) = item?;
+            let number = u64::from_be_bytes(key.as_slice().try_into().map_err(|_| StdError::generic_err("Invalid installment key"))?);
+            Ok(InstallmentResponse {
+                installment: number,
+                amount: installment.amount,
+                payer: installment.payer.to_string(),
+                paid_at: installment.paid_at,
+                covers_from: installment.covers_from,
+                covers_until: installment.covers_until,
             })
         })
         .collect::<StdResult<Vec<_>>>()?;
-    Ok(AllPoliciesResponse { policies })
+
+    Ok(PremiumScheduleResponse {
+        premium: current_premium(&policy, now),
+        lapses_at: lapses_at(&policy),
+        total_paid: policy.premiums_paid,
+        policy_id,
+        premium_frequency: policy.premium_frequency,
+        premium_period: policy.premium_period,
+        next_premium_due: policy.next_premium_due,
+        grace_period: policy.grace_period.unwrap_or(policy.premium_period),
+        status,
+        upcoming_due_dates,
+        installments,
+    })
 }
 
 fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let cw20_token_address = CW20_TOKEN_ADDRESS.load(deps.storage)?;
     let cw721_contract_address = CW721_CONTRACT_ADDRESS.load(deps.storage)?;
     let treasury_address = TREASURY_ADDRESS.load(deps.storage)?;
+    let premium_denom = PREMIUM_DENOM.load(deps.storage)?;
     Ok(ConfigResponse {
+        cw20_token_address,
         cw721_contract_address,
         treasury_address,
+        premium_denom,
+    })
+}
+
+fn query_reserve_status(deps: Deps) -> StdResult<ReserveStatusResponse> {
+    let reserve = RESERVE.load(deps.storage)?;
+    Ok(ReserveStatusResponse {
+        balance: reserve.balance,
+        premiums_collected: reserve.premiums_collected,
+        capital_contributed: reserve.capital_contributed,
+        claims_paid: reserve.claims_paid,
+        premiums_refunded: reserve.premiums_refunded,
+        premiums_ceded: reserve.premiums_ceded,
+        reinsurance_recovered: reserve.reinsurance_recovered,
+        surplus_withdrawn: reserve.surplus_withdrawn,
+    })
+}
+
+fn query_exposure(deps: Deps) -> StdResult<ExposureResponse> {
+    let exposure = EXPOSURE.load(deps.storage)?;
+    let max_exposure = max_exposure(deps.storage)?;
+    Ok(ExposureResponse {
+        total_exposure: exposure.total,
+        active_policies: exposure.active_policies,
+        max_exposure_multiple: MAX_EXPOSURE_MULTIPLE.load(deps.storage)?,
+        max_exposure,
+        available_capacity: max_exposure.saturating_sub(exposure.total),
+        ceded_exposure: exposure.ceded,
+        net_exposure: exposure.total - exposure.ceded,
+    })
+}
+
+fn query_cessions(deps: Deps, policy_id: String) -> StdResult<CessionsResponse> {
+    let policy = INSURANCE_POLICIES.load(deps.storage, &policy_id)?;
+    let cessions = CESSIONS.may_load(deps.storage, &policy_id)?.unwrap_or_default();
+    let ceded = ceded_exposure(&policy, &cessions);
+    Ok(CessionsResponse {
+        ceded_share: cessions.iter().fold(Decimal::zero(), |total, cession| total + cession.share),
+        gross_exposure: policy.booked_exposure,
+        net_exposure: policy.booked_exposure - ceded,
+        cessions: cessions
+            .into_iter()
+            .map(|cession| CessionResponse {
+                reinsurer: cession.reinsurer.to_string(),
+                share: cession.share,
+                ceded_at: cession.ceded_at,
+            })
+            .collect(),
+        policy_id,
+    })
+}
+
+fn query_reinsurer(deps: Deps, reinsurer: String) -> StdResult<ReinsurerResponse> {
+    let account = REINSURERS.load(deps.storage, &reinsurer)?;
+    Ok(ReinsurerResponse {
+        reinsurer,
+        premiums_ceded: account.premiums_ceded,
+        claims_recoverable: account.claims_recoverable,
+        claims_recovered: account.claims_recovered,
+        ceded_exposure: account.ceded_exposure,
+    })
+}
+
+fn query_quote(deps: Deps, policy_id: String) -> StdResult<QuoteResponse> {
+    let quote = QUOTES.load(deps.storage, &policy_id)?;
+    Ok(quote_response(quote))
+}
+
+fn query_pending_riders(deps: Deps, policy_id: String) -> StdResult<PendingRidersResponse> {
+    let riders = RIDER_QUOTES
+        .prefix(&policy_id)
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .take(MAX_LIMIT as usize)
+        .map(|item| item.map(|(_, rider)| rider))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(PendingRidersResponse { policy_id, riders })
+}
+
+fn query_pending_quotes(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<QuotesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|policy_id| Bound::Exclusive(policy_id.into_bytes()));
+    let quotes = PENDING_QUOTES
+        .range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (key, _) = item?;
+            let policy_id = String::from_utf8(key).map_err(|_| StdError::generic_err("Invalid quote key"))?;
+            Ok(quote_response(QUOTES.load(deps.storage, &policy_id)?))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(QuotesResponse { quotes })
+}
+
+fn quote_response(quote: Quote) -> QuoteResponse {
+    QuoteResponse {
+        policy_id: quote.policy_id,
+        applicant: quote.applicant.to_string(),
+        insured_amount: quote.insured_amount,
+        premium: quote.premium,
+        premium_frequency: quote.premium_frequency,
+        policy_term: quote.policy_term,
+        condition: quote.condition,
+        riders: quote.riders,
+        trigger: quote.trigger,
+        status: quote.status,
+        requested_at: quote.requested_at,
+        underwriter: quote.underwriter.map(|underwriter| underwriter.to_string()),
+        reason: quote.reason,
+    }
+}
+
+fn query_index_value(deps: Deps, index: String) -> StdResult<IndexValueResponse> {
+    let reading = INDEX_VALUES.load(deps.storage, &index)?;
+    Ok(IndexValueResponse {
+        index,
+        value: reading.value,
+        updated_at: reading.updated_at,
     })
 }
+
+fn query_claim(deps: Deps, claim_id: u64) -> StdResult<ClaimResponse> {
+    let claim = CLAIMS.load(deps.storage, U64Key::new(claim_id))?;
+    Ok(claim_response(claim))
+}
+
+fn query_claims_by_policy(deps: Deps, policy_id: String) -> StdResult<ClaimsResponse> {
+    let policy = INSURANCE_POLICIES.load(deps.storage, &policy_id)?;
+    let claims = policy
+        .claim_ids
+        .iter()
+        .map(|claim_id| Ok(claim_response(CLAIMS.load(deps.storage, U64Key::new(*claim_id))?)))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ClaimsResponse { claims })
+}
+
+fn query_pending_claims(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<ClaimsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|claim_id| Bound::Exclusive(claim_id.to_be_bytes().to_vec()));
+    let claims = PENDING_CLAIMS
+        .range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (key, _) = item?;
+            let claim_id = u64::from_be_bytes(key.as_slice().try_into().map_err(|_| StdError::generic_err("Invalid claim key"))?);
+            Ok(claim_response(CLAIMS.load(deps.storage, U64Key::new(claim_id))?))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ClaimsResponse { claims })
+}
+
+fn claim_response(claim: ClaimRecord) -> ClaimResponse {
+    let status = match claim.status {
+        StateClaimStatus::Pending => MsgClaimStatus::Pending,
+        StateClaimStatus::Approved => MsgClaimStatus::Approved,
+        StateClaimStatus::Rejected => MsgClaimStatus::Rejected,
+    };
+    ClaimResponse {
+        claim_id: claim.claim_id,
+        policy_id: claim.policy_id,
+        claimant: claim.claimant.to_string(),
+        evidence_uri: claim.evidence_uri,
+        status,
+        filed_at: claim.filed_at,
+        assessor: claim.assessor.map(|assessor| assessor.to_string()),
+        reason: claim.reason,
+        decided_at: claim.decided_at,
+    }
+}
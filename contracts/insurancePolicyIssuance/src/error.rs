@@ -14,6 +14,66 @@ pub enum ContractError {
 
     #[error("Invalid premium")]
     InvalidPremium{},
+
+    #[error("Unsupported premium frequency: {frequency}")]
+    InvalidFrequency { frequency: String },
+
+    #[error("Policy has lapsed for non-payment")]
+    PolicyLapsed {},
+
+    #[error("Policy premiums are not paid up")]
+    NotCovered {},
+
+    #[error("Policy already has a claim awaiting assessment")]
+    ClaimPending {},
+
+    #[error("Claim has already been decided")]
+    ClaimDecided {},
+
+    #[error("Unsupported policy term: {term}")]
+    InvalidTerm { term: String },
+
+    #[error("Policy has no parametric trigger")]
+    NotParametric {},
+
+    #[error("Parametric trigger has not fired")]
+    NotTriggered {},
+
+    #[error("Insured exposure would exceed {max_exposure} allowed by reserves")]
+    ExposureLimit { max_exposure: u128 },
+
+    #[error("Reserve of {reserve} cannot cover a payout of {payout}")]
+    InsufficientReserve { reserve: u128, payout: u128 },
+
+    #[error("Invalid funds")]
+    InvalidFunds {},
+
+    #[error("Policy has been cancelled")]
+    PolicyCancelled {},
+
+    #[error("Policy term has ended")]
+    PolicyExpired {},
+
+    #[error("Policy is still active")]
+    PolicyActive {},
+
+    #[error("Policy id {policy_id} is already in use")]
+    PolicyExists { policy_id: String },
+
+    #[error("Quote is not awaiting underwriting")]
+    QuoteNotPending {},
+
+    #[error("Rider {rider_id} is already attached")]
+    RiderExists { rider_id: String },
+
+    #[error("Rider {rider_id} is not attached")]
+    RiderNotFound { rider_id: String },
+
+    #[error("Ceded shares must be positive and may not exceed the whole exposure")]
+    InvalidCession {},
+
+    #[error("Policy is already ceded to {reinsurer}")]
+    CessionExists { reinsurer: String },
     
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
@@ -1,4 +1,4 @@
-use cosmwasm_std::Binary;
+use cosmwasm_std::{Binary, Decimal};
 use cw20::Cw20ReceiveMsg;
 use cw721::Cw721ReceiveMsg;
 use schemars::JsonSchema;
@@ -9,11 +9,13 @@ pub struct InstantiateMsg {
     pub cw20_token_address: String,
     pub cw721_contract_address: String,
     pub treasury_address: String,
+    pub premium_denom: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
+    /// Request a policy; coverage starts once an underwriter approves the quote
     CreatePolicy {
         policy_id: String,
         insured_amount: u128,
@@ -21,14 +23,88 @@ pub enum ExecuteMsg {
         premium_frequency: String,
         policy_term: String,
         condition: String,
-        riders: Vec<String>,
+        riders: Vec<Rider>,
+        /// Pays out without adjudication once the oracle index crosses the threshold
+        trigger: Option<ParametricTrigger>,
     },
-    Claim { policy_id: String },
+    /// File a claim against a paid-up policy; it is paid out once an assessor approves it
+    FileClaim { policy_id: String, evidence_uri: String },
+    AssessClaim { claim_id: u64, approve: bool, reason: String },
+    SetAssessor { assessor: String, enabled: bool },
+    SetUnderwriter { underwriter: String, enabled: bool },
+    /// Activate a quoted policy, optionally at a different premium, and mint its NFT
+    ApproveQuote { policy_id: String, premium: Option<u128> },
+    DeclineQuote { policy_id: String, reason: String },
+    SetOracle { oracle: String, enabled: bool },
+    /// Record a new value for an index, firing the triggers of covered parametric policies.
+    /// At most one page of policies is processed; the `next_policy` attribute tells where `ProcessTriggers` continues
+    PostIndexValue { index: String, value: Decimal },
+    /// Check the next page of policies watching an index against its latest value; anyone may call this
+    ProcessTriggers { index: String, start_after: Option<String>, limit: Option<u32> },
+    /// Pay out a parametric policy whose trigger has fired
+    ClaimParametric { policy_id: String },
+    /// Add capital to the reserve with native funds
+    FundReserve {},
+    /// Pay reserve funds not needed to back the current exposure to the treasury; only the treasury may withdraw
+    WithdrawSurplus { amount: u128 },
+    /// Start a new policy term; the current term must be in good standing or have ended
+    RenewPolicy { policy_id: String },
+    /// Cancel a policy, refunding the unused part of the paid premiums pro rata
+    CancelPolicy { policy_id: String },
+    /// Release the reserve backing of a policy that expired or lapsed
+    ClosePolicy { policy_id: String },
+    /// Make the current holder of the policy NFT the policy owner and beneficiary
+    SyncPolicyOwner { policy_id: String },
+    /// Request a rider for an active policy; it is attached once an underwriter approves it,
+    /// and its premium is due from the next premium payment on
+    AttachRider { policy_id: String, rider: Rider },
+    /// Attach a requested rider, optionally at a different premium
+    ApproveRider { policy_id: String, rider_id: String, premium: Option<u128> },
+    DeclineRider { policy_id: String, rider_id: String, reason: String },
+    DetachRider { policy_id: String, rider_id: String },
+    SetExposureMultiple { multiple: Decimal },
+    /// Set the grace period (in seconds) of policies issued from now on; `None` allows one premium period
+    SetGracePeriod { grace_period: Option<u64> },
+    /// Cede a share of an active policy's exposure to a reinsurer; only the treasury may cede.
+    /// The reinsurer receives that share of every later premium and owes it of every payout
+    CedeExposure { policy_id: String, reinsurer: String, share: Decimal },
+    /// Pay back the reinsurer share of claims and refunds with native funds
+    SettleRecoverable {},
     Receive(Cw20ReceiveMsg),
     ReceiveNft(Cw721ReceiveMsg),
     PayPremium { policy_id: String, amount: u128 },
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerComparison {
+    /// Fires when the index value is at or above the threshold
+    Above,
+    /// Fires when the index value is at or below the threshold
+    Below,
+}
+
+/// Oracle index condition a parametric policy pays out on, e.g. rainfall below a threshold
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParametricTrigger {
+    pub index: String,
+    pub comparison: TriggerComparison,
+    pub threshold: Decimal,
+}
+
+/// Add-on cover with its own premium component, coverage amount and term
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Rider {
+    pub rider_id: String,
+    pub description: String,
+    /// Added to every premium payment while the rider is in force
+    pub premium: u128,
+    /// Added to the payout of a claim while the rider is in force
+    pub coverage: u128,
+    /// How long the rider stays in force once attached, e.g. "6 months"
+    pub term: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PolicyMetadata {
     pub policy_id: String,
@@ -37,7 +113,7 @@ pub struct PolicyMetadata {
     pub premium_frequency: String, 
     pub policy_term: String, 
     pub condition: String,  
-    pub riders: Vec<String>, 
+    pub riders: Vec<Rider>, 
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -60,19 +136,218 @@ pub enum QueryMsg {
     GetPolicy { policy_id: String },
     GetAllPolicies {},
     GetConfig {},
+    GetClaim { claim_id: u64 },
+    ClaimsByPolicy { policy_id: String },
+    PendingClaims { start_after: Option<u64>, limit: Option<u32> },
+    IndexValue { index: String },
+    GetQuote { policy_id: String },
+    /// Riders requested for a policy that await underwriting
+    PendingRiders { policy_id: String },
+    PendingQuotes { start_after: Option<String>, limit: Option<u32> },
+    ReserveStatus {},
+    Exposure {},
+    /// Premium terms of a policy with a page of its paid installments, by installment number
+    PremiumSchedule { policy_id: String, start_after: Option<u64>, limit: Option<u32> },
+    Cessions { policy_id: String },
+    Reinsurer { reinsurer: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RiderResponse {
+    pub rider_id: String,
+    pub description: String,
+    pub premium: u128,
+    pub coverage: u128,
+    pub term: String,
+    pub attached_at: u64,
+    pub term_end: u64,
+    pub in_force: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStatus {
+    Pending,
+    Approved,
+    Declined,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QuoteResponse {
+    pub policy_id: String,
+    pub applicant: String,
+    pub insured_amount: u128,
+    pub premium: u128,
+    pub premium_frequency: String,
+    pub policy_term: String,
+    pub condition: String,
+    pub riders: Vec<Rider>,
+    pub trigger: Option<ParametricTrigger>,
+    pub status: QuoteStatus,
+    pub requested_at: u64,
+    pub underwriter: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingRidersResponse {
+    pub policy_id: String,
+    pub riders: Vec<Rider>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QuotesResponse {
+    pub quotes: Vec<QuoteResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyStatus {
+    Active,
+    Lapsed,
+    Cancelled,
+    Expired,
+    Claimed,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PolicyResponse {
     pub policy_id: String,
     pub insured_amount: u128,
+    /// Premium currently due each period, riders in force included
     pub premium: u128,
+    pub base_premium: u128,
+    /// Payout of a claim right now, riders in force included
+    pub coverage: u128,
     pub premium_frequency: String, // New field
     pub policy_term: String, // New field
     pub owner: String,
     pub claimed: bool,
     pub condition: String,
-    pub riders: Vec<String>, // New field
+    pub riders: Vec<RiderResponse>,
+    pub next_premium_due: u64,
+    pub lapsed: bool,
+    pub status: PolicyStatus,
+    pub term_end: u64,
+    pub trigger: Option<ParametricTrigger>,
+    pub triggered_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaimStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimResponse {
+    pub claim_id: u64,
+    pub policy_id: String,
+    pub claimant: String,
+    pub evidence_uri: String,
+    pub status: ClaimStatus,
+    pub filed_at: u64,
+    pub assessor: Option<String>,
+    pub reason: Option<String>,
+    pub decided_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimsResponse {
+    pub claims: Vec<ClaimResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IndexValueResponse {
+    pub index: String,
+    pub value: Decimal,
+    pub updated_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReserveStatusResponse {
+    /// Funds currently held to pay claims
+    pub balance: u128,
+    pub premiums_collected: u128,
+    pub capital_contributed: u128,
+    pub claims_paid: u128,
+    pub premiums_refunded: u128,
+    pub premiums_ceded: u128,
+    pub reinsurance_recovered: u128,
+    pub surplus_withdrawn: u128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExposureResponse {
+    /// Sum of the insured amounts of unclaimed policies
+    pub total_exposure: u128,
+    pub active_policies: u64,
+    pub max_exposure_multiple: Decimal,
+    /// Largest exposure the current reserve allows
+    pub max_exposure: u128,
+    pub available_capacity: u128,
+    /// Part of the total exposure carried by reinsurers
+    pub ceded_exposure: u128,
+    pub net_exposure: u128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CessionResponse {
+    pub reinsurer: String,
+    pub share: Decimal,
+    pub ceded_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CessionsResponse {
+    pub policy_id: String,
+    pub cessions: Vec<CessionResponse>,
+    /// Sum of the ceded shares
+    pub ceded_share: Decimal,
+    pub gross_exposure: u128,
+    pub net_exposure: u128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReinsurerResponse {
+    pub reinsurer: String,
+    pub premiums_ceded: u128,
+    pub claims_recoverable: u128,
+    pub claims_recovered: u128,
+    /// Exposure of active policies currently carried by the reinsurer
+    pub ceded_exposure: u128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstallmentResponse {
+    pub installment: u64,
+    pub amount: u128,
+    pub payer: String,
+    pub paid_at: u64,
+    pub covers_from: u64,
+    pub covers_until: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PremiumScheduleResponse {
+    pub policy_id: String,
+    pub premium_frequency: String,
+    /// Seconds between premium payments
+    pub premium_period: u64,
+    /// Premium currently due each period, riders in force included
+    pub premium: u128,
+    pub next_premium_due: u64,
+    pub grace_period: u64,
+    /// Time at which the policy lapses unless the next premium is paid
+    pub lapses_at: u64,
+    pub status: PolicyStatus,
+    /// Due dates of the remaining premiums of the current term, at most the next 30
+    pub upcoming_due_dates: Vec<u64>,
+    /// Sum of all installments paid, not only those in `installments`
+    pub total_paid: u128,
+    pub installments: Vec<InstallmentResponse>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -85,10 +360,14 @@ pub struct ConfigResponse {
     pub cw20_token_address: String,
     pub cw721_contract_address: String,
     pub treasury_address: String,
+    pub premium_denom: Option<String>,
 }
 
+/// Hook messages expected in `Cw20ReceiveMsg::msg` when paying with the cw20 token
 #[derive(Serialize, Deserialize)]
-pub struct PayPremiumMsg {
-    pub policy_id: String,
-    pub amount: u128,
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    PayPremium { policy_id: String, amount: u128 },
+    FundReserve {},
+    SettleRecoverable {},
 }
\ No newline at end of file
@@ -1,22 +1,209 @@
-use cosmwasm_std::Addr;
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Decimal, Empty};
+use cw_storage_plus::{Item, Map, U64Key};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::msg::{ParametricTrigger, QuoteStatus, Rider};
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InsurancePolicy {
     pub policy_id: String,
     pub insured_amount: u128,
+    /// Base premium, without riders
     pub premium: u128,
     pub premium_frequency: String, 
     pub policy_term: String, 
-    pub riders: Vec<String>, 
+    pub riders: Vec<PolicyRider>, 
     pub owner: Addr,
     pub claimed: bool,
     pub condition: String,  
+    /// Seconds between premium payments, parsed from `premium_frequency`
+    pub premium_period: u64,
+    /// Time (in seconds) until which premiums are paid; coverage ends here unless the next premium is paid
+    pub next_premium_due: u64,
+    /// Seconds after `next_premium_due` before the policy lapses, fixed when the policy is issued;
+    /// one premium period when unset, as for policies issued before grace periods were configurable
+    #[serde(default)]
+    pub grace_period: Option<u64>,
+    /// Number of premium installments paid so far
+    #[serde(default)]
+    pub installments_paid: u64,
+    /// Sum of the premium installments paid so far
+    #[serde(default)]
+    pub premiums_paid: u128,
+    /// Ids of every claim filed against the policy
+    pub claim_ids: Vec<u64>,
+    /// Length of one policy term in seconds, parsed from `policy_term`
+    pub term_length: u64,
+    /// End of the current policy term (in seconds)
+    pub term_end: u64,
+    pub cancelled_at: Option<u64>,
+    /// Whether the insured amount no longer counts towards the contract's exposure
+    pub exposure_released: bool,
+    /// Amount this policy adds to the contract's exposure, riders included
+    pub booked_exposure: u128,
+    pub trigger: Option<ParametricTrigger>,
+    /// Time the parametric trigger fired while the policy was covered
+    pub triggered_at: Option<u64>,
+}
+
+/// Premium payment recorded against a policy
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Installment {
+    pub amount: u128,
+    pub payer: Addr,
+    pub paid_at: u64,
+    /// Start of the premium period the payment covers
+    pub covers_from: u64,
+    pub covers_until: u64,
+}
+
+/// Funds held by the contract to pay claims
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Reserve {
+    pub balance: u128,
+    pub premiums_collected: u128,
+    pub capital_contributed: u128,
+    pub claims_paid: u128,
+    pub premiums_refunded: u128,
+    /// Premiums forwarded to reinsurers
+    #[serde(default)]
+    pub premiums_ceded: u128,
+    /// Reinsurer shares of claims and refunds paid back into the reserve
+    #[serde(default)]
+    pub reinsurance_recovered: u128,
+    /// Surplus paid out to the treasury
+    #[serde(default)]
+    pub surplus_withdrawn: u128,
+}
+
+/// Insured amounts the contract may have to pay out
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Exposure {
+    pub total: u128,
+    pub active_policies: u64,
+    /// Part of the total carried by reinsurers
+    #[serde(default)]
+    pub ceded: u128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IndexReading {
+    pub value: Decimal,
+    pub updated_at: u64,
+}
+
+/// Rider attached to a policy
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PolicyRider {
+    pub rider_id: String,
+    pub description: String,
+    pub premium: u128,
+    pub coverage: u128,
+    pub term: String,
+    pub attached_at: u64,
+    pub term_end: u64,
+}
+
+/// Share of a policy's exposure ceded to a reinsurer
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cession {
+    pub reinsurer: Addr,
+    /// Fraction of the exposure, premiums and payouts carried by the reinsurer
+    pub share: Decimal,
+    pub ceded_at: u64,
+}
+
+/// Running balances between the contract and a reinsurer
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ReinsurerAccount {
+    pub premiums_ceded: u128,
+    /// Reinsurer share of paid claims and refunds not yet paid back to the reserve
+    pub claims_recoverable: u128,
+    pub claims_recovered: u128,
+    /// Exposure of active policies currently carried by the reinsurer
+    #[serde(default)]
+    pub ceded_exposure: u128,
+}
+
+/// Policy request awaiting an underwriting decision
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Quote {
+    pub policy_id: String,
+    pub applicant: Addr,
+    pub insured_amount: u128,
+    pub premium: u128,
+    pub premium_frequency: String,
+    pub policy_term: String,
+    pub condition: String,
+    pub riders: Vec<Rider>,
+    pub trigger: Option<ParametricTrigger>,
+    pub status: QuoteStatus,
+    pub requested_at: u64,
+    pub underwriter: Option<Addr>,
+    /// Reason given when the quote was declined
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaimStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// Claim filed by a policyholder and decided by an assessor
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimRecord {
+    pub claim_id: u64,
+    pub policy_id: String,
+    pub claimant: Addr,
+    pub evidence_uri: String,
+    pub status: ClaimStatus,
+    pub filed_at: u64,
+    pub assessor: Option<Addr>,
+    pub reason: Option<String>,
+    pub decided_at: Option<u64>,
 }
 
 pub const INSURANCE_POLICIES: Map<&str, InsurancePolicy> = Map::new("insurance_policies");
+/// Premium payments of each policy, keyed by (policy_id, installment number)
+pub const INSTALLMENTS: Map<(&str, U64Key), Installment> = Map::new("installments");
+/// Grace period granted to newly issued policies; one premium period when unset
+pub const GRACE_PERIOD: Item<u64> = Item::new("grace_period");
 pub const CW20_TOKEN_ADDRESS: Item<String> = Item::new("cw20_token_address");
 pub const CW721_CONTRACT_ADDRESS: Item<String> = Item::new("cw721_contract_address");
-pub const TREASURY_ADDRESS: Item<String> = Item::new("treasury_address");
\ No newline at end of file
+pub const TREASURY_ADDRESS: Item<String> = Item::new("treasury_address");
+/// Native denom premiums and payouts are made in; the cw20 token is used when unset
+pub const PREMIUM_DENOM: Item<Option<String>> = Item::new("premium_denom");
+/// Address managing the contract roles
+pub const ADMIN: Item<Addr> = Item::new("admin");
+/// Addresses allowed to approve or reject claims
+pub const ASSESSORS: Map<&str, bool> = Map::new("assessors");
+pub const CLAIMS: Map<U64Key, ClaimRecord> = Map::new("claims");
+pub const NEXT_CLAIM_ID: Item<u64> = Item::new("next_claim_id");
+/// Claims awaiting a decision, keyed by claim id
+pub const PENDING_CLAIMS: Map<U64Key, bool> = Map::new("pending_claims");
+/// Addresses allowed to post index values
+pub const ORACLES: Map<&str, bool> = Map::new("oracles");
+/// Latest value posted for each index
+pub const INDEX_VALUES: Map<&str, IndexReading> = Map::new("index_values");
+/// Parametric policies whose trigger has not fired yet, keyed by (index, policy_id)
+pub const INDEX_POLICIES: Map<(&str, &str), Empty> = Map::new("index_policy_ids");
+/// Watched policies as one list per index, as stored before `INDEX_POLICIES`; moved over when the index is next processed
+pub const LEGACY_INDEX_POLICIES: Map<&str, Vec<String>> = Map::new("index_policies");
+pub const RESERVE: Item<Reserve> = Item::new("reserve");
+pub const EXPOSURE: Item<Exposure> = Item::new("exposure");
+/// Total exposure may not exceed this multiple of the reserve balance
+pub const MAX_EXPOSURE_MULTIPLE: Item<Decimal> = Item::new("max_exposure_multiple");
+/// Reinsurance cessions of each policy
+pub const CESSIONS: Map<&str, Vec<Cession>> = Map::new("cessions");
+pub const REINSURERS: Map<&str, ReinsurerAccount> = Map::new("reinsurers");
+/// Addresses allowed to approve or decline quotes
+pub const UNDERWRITERS: Map<&str, bool> = Map::new("underwriters");
+pub const QUOTES: Map<&str, Quote> = Map::new("quotes");
+/// Quotes awaiting a decision, keyed by policy id
+pub const PENDING_QUOTES: Map<&str, bool> = Map::new("pending_quotes");
+/// Riders requested for active policies awaiting a decision, keyed by (policy_id, rider_id)
+pub const RIDER_QUOTES: Map<(&str, &str), Rider> = Map::new("rider_quotes");
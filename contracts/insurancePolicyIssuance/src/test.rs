@@ -1,14 +1,22 @@
 #[cfg(test)]
 mod tests {
     use crate::contract::{execute, execute_receive_cw20, execute_receive_nft, instantiate, query};
-    use crate::msg::{ClaimMsg, ExecuteMsg, InstantiateMsg, PolicyResponse, QueryMsg};
+    use crate::msg::{
+        ClaimMsg, ClaimStatus, ClaimsResponse, ExecuteMsg, InstantiateMsg, ParametricTrigger, PolicyResponse, QueryMsg, Rider,
+        ExposureResponse, PolicyStatus, PremiumScheduleResponse, QuoteStatus, QuotesResponse, ReserveStatusResponse, TriggerComparison,
+        CessionsResponse, PendingRidersResponse, ReinsurerResponse,
+    };
     use crate::state::{InsurancePolicy, INSURANCE_POLICIES};
 
     use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary, to_binary};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use crate::error::ContractError;
+    use cosmwasm_std::{
+        coins, from_binary, to_binary, BankMsg, ContractResult, CosmosMsg, Decimal, DepsMut, OwnedDeps, SystemError, SystemResult,
+        WasmQuery,
+    };
     use cw20::Cw20ReceiveMsg;
-    use cw721::Cw721ReceiveMsg;
+    use cw721::{Cw721ReceiveMsg, OwnerOfResponse};
 
     #[test]
     fn test_instantiate() {
@@ -18,6 +26,7 @@ mod tests {
             cw20_token_address: "token0000".to_string(),
             cw721_contract_address: "nft0000".to_string(),
             treasury_address: "treasury0000".to_string(),
+            premium_denom: Some("ucore".to_string()),
         };
         let info = mock_info("creator", &coins(1000, "earth"));
 
@@ -34,20 +43,29 @@ mod tests {
             cw20_token_address: "token0000".to_string(),
             cw721_contract_address: "nft0000".to_string(),
             treasury_address: "treasury0000".to_string(),
+            premium_denom: Some("ucore".to_string()),
         };
         let info = mock_info("creator", &coins(1000, "earth"));
         instantiate(deps.as_mut(), mock_env(), info.clone(), instantiate_msg).unwrap();
+        fund_reserve(deps.as_mut());
+        add_underwriter(deps.as_mut());
 
         let msg = ExecuteMsg::CreatePolicy {
             policy_id: "policy0001".to_string(),
             insured_amount: 1000,
             premium: 100,
+            premium_frequency: "monthly".to_string(),
+            policy_term: "1 year".to_string(),
             condition: "standard_condition".to_string(),
+            riders: vec![],
+            trigger: None,
         };
         let info = mock_info("policy_holder", &[]);
         let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
         assert_eq!(res.attributes.len(), 5);
         assert_eq!(res.attributes[0].value, "execute_create_policy");
+        assert!(INSURANCE_POLICIES.may_load(&deps.storage, "policy0001").unwrap().is_none());
+        approve_quote(deps.as_mut(), "policy0001");
 
         let policy: InsurancePolicy = INSURANCE_POLICIES.load(&deps.storage, "policy0001").unwrap();
         assert_eq!(policy.policy_id, "policy0001");
@@ -66,6 +84,7 @@ mod tests {
             cw20_token_address: "token0000".to_string(),
             cw721_contract_address: "nft0000".to_string(),
             treasury_address: "treasury0000".to_string(),
+            premium_denom: Some("ucore".to_string()),
         };
         let info = mock_info("creator", &coins(1000, "earth"));
         instantiate(deps.as_mut(), mock_env(), info.clone(), instantiate_msg).unwrap();
@@ -89,18 +108,26 @@ mod tests {
             cw20_token_address: "token0000".to_string(),
             cw721_contract_address: "nft0000".to_string(),
             treasury_address: "treasury0000".to_string(),
+            premium_denom: Some("ucore".to_string()),
         };
         let info = mock_info("creator", &coins(1000, "earth"));
         instantiate(deps.as_mut(), mock_env(), info.clone(), instantiate_msg).unwrap();
+        fund_reserve(deps.as_mut());
+        add_underwriter(deps.as_mut());
 
         let create_msg = ExecuteMsg::CreatePolicy {
             policy_id: "policy0001".to_string(),
             insured_amount: 1000,
             premium: 100,
+            premium_frequency: "monthly".to_string(),
+            policy_term: "1 year".to_string(),
             condition: "standard_condition".to_string(),
+            riders: vec![],
+            trigger: None,
         };
         let info = mock_info("policy_holder", &[]);
         execute(deps.as_mut(), mock_env(), info.clone(), create_msg).unwrap();
+        approve_quote(deps.as_mut(), "policy0001");
 
         let query_msg = QueryMsg::GetPolicy {
             policy_id: "policy0001".to_string(),
@@ -113,4 +140,731 @@ mod tests {
         assert_eq!(policy_response.premium, 100);
         assert_eq!(policy_response.condition, "standard_condition");
     }
-}
\ No newline at end of file
+
+    fn setup_policy(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>) {
+        set_nft_owner(deps, "policy_holder");
+        let instantiate_msg = InstantiateMsg {
+            cw20_token_address: "token0000".to_string(),
+            cw721_contract_address: "nft0000".to_string(),
+            treasury_address: "treasury0000".to_string(),
+            premium_denom: Some("ucore".to_string()),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+        fund_reserve(deps.as_mut());
+        add_underwriter(deps.as_mut());
+    }
+
+    /// Answer `OwnerOf` queries of the policy NFT contract with `owner`
+    fn set_nft_owner(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, owner: &str) {
+        let owner = owner.to_string();
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == "nft0000" => SystemResult::Ok(ContractResult::Ok(
+                to_binary(&OwnerOfResponse { owner: owner.clone(), approvals: vec![] }).unwrap(),
+            )),
+            _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "wasm".to_string() }),
+        });
+    }
+
+    fn fund_reserve(deps: DepsMut) {
+        let msg = ExecuteMsg::FundReserve {};
+        execute(deps, mock_env(), mock_info("creator", &coins(1000, "ucore")), msg).unwrap();
+    }
+
+    fn add_underwriter(deps: DepsMut) {
+        let msg = ExecuteMsg::SetUnderwriter { underwriter: "underwriter".to_string(), enabled: true };
+        execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    fn approve_quote(deps: DepsMut, policy_id: &str) {
+        let msg = ExecuteMsg::ApproveQuote { policy_id: policy_id.to_string(), premium: None };
+        execute(deps, mock_env(), mock_info("underwriter", &[]), msg).unwrap();
+    }
+
+    fn approve_rider(deps: DepsMut, policy_id: &str, rider_id: &str) {
+        let msg = ExecuteMsg::ApproveRider { policy_id: policy_id.to_string(), rider_id: rider_id.to_string(), premium: None };
+        execute(deps, mock_env(), mock_info("underwriter", &[]), msg).unwrap();
+    }
+
+    fn create_policy(mut deps: DepsMut) {
+        let msg = ExecuteMsg::CreatePolicy {
+            policy_id: "policy0001".to_string(),
+            insured_amount: 1000,
+            premium: 100,
+            premium_frequency: "monthly".to_string(),
+            policy_term: "1 year".to_string(),
+            condition: "standard_condition".to_string(),
+            riders: vec![],
+            trigger: None,
+        };
+        execute(deps.branch(), mock_env(), mock_info("policy_holder", &[]), msg).unwrap();
+        approve_quote(deps, "policy0001");
+    }
+
+    #[test]
+    fn test_pay_premium_and_claim() {
+        let mut deps = mock_dependencies();
+        setup_policy(&mut deps);
+        create_policy(deps.as_mut());
+        let msg = ExecuteMsg::SetAssessor { assessor: "assessor".to_string(), enabled: true };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // no coverage before the first premium is paid
+        let claim = ExecuteMsg::FileClaim { policy_id: "policy0001".to_string(), evidence_uri: "ipfs://evidence".to_string() };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), claim.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotCovered {}));
+
+        // the attached funds must match the premium
+        let pay = ExecuteMsg::PayPremium { policy_id: "policy0001".to_string(), amount: 100 };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &coins(50, "ucore")), pay.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPremium {}));
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &coins(100, "ucore")), pay).unwrap();
+
+        // only the owner can file, and filing pays nothing by itself
+        let err = execute(deps.as_mut(), mock_env(), mock_info("someone_else", &[]), claim.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), claim.clone()).unwrap();
+        assert!(res.messages.is_empty());
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), claim).unwrap_err();
+        assert!(matches!(err, ContractError::ClaimPending {}));
+
+        let pending: ClaimsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PendingClaims { start_after: None, limit: None }).unwrap()).unwrap();
+        assert_eq!(pending.claims.len(), 1);
+
+        // only assessors decide, and approval pays the insured amount
+        let assess = ExecuteMsg::AssessClaim { claim_id: 1, approve: true, reason: "covered event".to_string() };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), assess.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("assessor", &[]), assess.clone()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: "policy_holder".to_string(), amount: coins(1000, "ucore") })
+        );
+        let err = execute(deps.as_mut(), mock_env(), mock_info("assessor", &[]), assess).unwrap_err();
+        assert!(matches!(err, ContractError::ClaimDecided {}));
+
+        let claims: ClaimsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::ClaimsByPolicy { policy_id: "policy0001".to_string() }).unwrap()).unwrap();
+        assert_eq!(claims.claims[0].status, ClaimStatus::Approved);
+        assert_eq!(claims.claims[0].reason, Some("covered event".to_string()));
+    }
+
+    #[test]
+    fn test_policy_lapses_on_non_payment() {
+        let mut deps = mock_dependencies();
+        setup_policy(&mut deps);
+        create_policy(deps.as_mut());
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(31 * 24 * 60 * 60);
+        let pay = ExecuteMsg::PayPremium { policy_id: "policy0001".to_string(), amount: 100 };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("policy_holder", &coins(100, "ucore")), pay).unwrap_err();
+        assert!(matches!(err, ContractError::PolicyLapsed {}));
+
+        let res = query(deps.as_ref(), env, QueryMsg::GetPolicy { policy_id: "policy0001".to_string() }).unwrap();
+        let policy_response: PolicyResponse = from_binary(&res).unwrap();
+        assert!(policy_response.lapsed);
+
+        // the end of the term does not turn the lapse into a renewable expiry
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(366 * 24 * 60 * 60);
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetPolicy { policy_id: "policy0001".to_string() }).unwrap();
+        let policy_response: PolicyResponse = from_binary(&res).unwrap();
+        assert_eq!(policy_response.status, PolicyStatus::Lapsed);
+        let renew = ExecuteMsg::RenewPolicy { policy_id: "policy0001".to_string() };
+        let err = execute(deps.as_mut(), env, mock_info("policy_holder", &[]), renew).unwrap_err();
+        assert!(matches!(err, ContractError::PolicyLapsed {}));
+    }
+
+    #[test]
+    fn test_parametric_trigger_pays_without_adjudication() {
+        let mut deps = mock_dependencies();
+        setup_policy(&mut deps);
+        let msg = ExecuteMsg::CreatePolicy {
+            policy_id: "rain0001".to_string(),
+            insured_amount: 1000,
+            premium: 100,
+            premium_frequency: "monthly".to_string(),
+            policy_term: "6 months".to_string(),
+            condition: "rainfall below 10mm".to_string(),
+            riders: vec![],
+            trigger: Some(ParametricTrigger {
+                index: "rainfall".to_string(),
+                comparison: TriggerComparison::Below,
+                threshold: Decimal::from_ratio(10u128, 1u128),
+            }),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), msg).unwrap();
+        approve_quote(deps.as_mut(), "rain0001");
+        let msg = ExecuteMsg::SetOracle { oracle: "oracle".to_string(), enabled: true };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // only oracles post values, and nothing fires while premiums are unpaid
+        let post = ExecuteMsg::PostIndexValue { index: "rainfall".to_string(), value: Decimal::from_ratio(4u128, 1u128) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), post.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info("oracle", &[]), post.clone()).unwrap();
+        let claim = ExecuteMsg::ClaimParametric { policy_id: "rain0001".to_string() };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), claim.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotTriggered {}));
+
+        let pay = ExecuteMsg::PayPremium { policy_id: "rain0001".to_string(), amount: 100 };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &coins(100, "ucore")), pay).unwrap();
+
+        // a value on the wrong side of the threshold does not fire
+        let dry = ExecuteMsg::PostIndexValue { index: "rainfall".to_string(), value: Decimal::from_ratio(25u128, 1u128) };
+        execute(deps.as_mut(), mock_env(), mock_info("oracle", &[]), dry).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), claim.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotTriggered {}));
+
+        execute(deps.as_mut(), mock_env(), mock_info("oracle", &[]), post).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetPolicy { policy_id: "rain0001".to_string() }).unwrap();
+        let policy_response: PolicyResponse = from_binary(&res).unwrap();
+        assert_eq!(policy_response.triggered_at, Some(mock_env().block.time.seconds()));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), claim.clone()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: "policy_holder".to_string(), amount: coins(1000, "ucore") })
+        );
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), claim).unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyClaimed {}));
+    }
+
+    #[test]
+    fn test_triggers_are_processed_in_pages() {
+        let mut deps = mock_dependencies();
+        setup_policy(&mut deps);
+        let msg = ExecuteMsg::SetOracle { oracle: "oracle".to_string(), enabled: true };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        for policy_id in ["rain0001", "rain0002"] {
+            let msg = ExecuteMsg::CreatePolicy {
+                policy_id: policy_id.to_string(),
+                insured_amount: 400,
+                premium: 100,
+                premium_frequency: "monthly".to_string(),
+                policy_term: "6 months".to_string(),
+                condition: "rainfall below 10mm".to_string(),
+                riders: vec![],
+                trigger: Some(ParametricTrigger {
+                    index: "rainfall".to_string(),
+                    comparison: TriggerComparison::Below,
+                    threshold: Decimal::from_ratio(10u128, 1u128),
+                }),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), msg).unwrap();
+            approve_quote(deps.as_mut(), policy_id);
+        }
+
+        // the value is posted while the premiums are unpaid, so nothing fires yet
+        let post = ExecuteMsg::PostIndexValue { index: "rainfall".to_string(), value: Decimal::from_ratio(4u128, 1u128) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("oracle", &[]), post).unwrap();
+        assert!(!res.attributes.iter().any(|attr| attr.key == "next_policy"));
+        for policy_id in ["rain0001", "rain0002"] {
+            let pay = ExecuteMsg::PayPremium { policy_id: policy_id.to_string(), amount: 100 };
+            execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &coins(100, "ucore")), pay).unwrap();
+        }
+
+        // anyone re-checks the watchers against the latest value, one page at a time
+        let process = |start_after: Option<&str>| ExecuteMsg::ProcessTriggers {
+            index: "rainfall".to_string(),
+            start_after: start_after.map(|policy_id| policy_id.to_string()),
+            limit: Some(1),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), process(None)).unwrap();
+        assert_eq!(res.attributes[2].value, "rain0001");
+        assert_eq!(res.attributes[3].value, "rain0001");
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), process(Some("rain0001"))).unwrap();
+        assert_eq!(res.attributes[2].value, "rain0002");
+        assert_eq!(res.attributes.len(), 3);
+
+        // fired policies no longer watch the index
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), process(None)).unwrap();
+        assert_eq!(res.attributes[2].value, "");
+        let claim = ExecuteMsg::ClaimParametric { policy_id: "rain0002".to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), claim).unwrap();
+    }
+
+    #[test]
+    fn test_exposure_is_limited_by_reserves() {
+        let mut deps = mock_dependencies();
+        setup_policy(&mut deps);
+        let msg = ExecuteMsg::SetExposureMultiple { multiple: Decimal::percent(150) };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        create_policy(deps.as_mut());
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Exposure {}).unwrap();
+        let exposure: ExposureResponse = from_binary(&res).unwrap();
+        assert_eq!(exposure.total_exposure, 1000);
+        assert_eq!(exposure.max_exposure, 1500);
+        assert_eq!(exposure.available_capacity, 500);
+
+        let msg = ExecuteMsg::CreatePolicy {
+            policy_id: "policy0002".to_string(),
+            insured_amount: 600,
+            premium: 50,
+            premium_frequency: "monthly".to_string(),
+            policy_term: "1 year".to_string(),
+            condition: "standard_condition".to_string(),
+            riders: vec![],
+            trigger: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), msg).unwrap();
+        let approve = ExecuteMsg::ApproveQuote { policy_id: "policy0002".to_string(), premium: None };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("underwriter", &[]), approve.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::ExposureLimit { max_exposure: 1500 }));
+
+        // premiums grow the reserve and with it the capacity
+        let pay = ExecuteMsg::PayPremium { policy_id: "policy0001".to_string(), amount: 100 };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &coins(100, "ucore")), pay).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("underwriter", &[]), approve).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ReserveStatus {}).unwrap();
+        let reserve: ReserveStatusResponse = from_binary(&res).unwrap();
+        assert_eq!(reserve.balance, 1100);
+        assert_eq!(reserve.premiums_collected, 100);
+        assert_eq!(reserve.capital_contributed, 1000);
+    }
+
+    #[test]
+    fn test_treasury_withdraws_surplus() {
+        let mut deps = mock_dependencies();
+        setup_policy(&mut deps);
+        let msg = ExecuteMsg::SetExposureMultiple { multiple: Decimal::percent(200) };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        create_policy(deps.as_mut());
+
+        // only the treasury withdraws, and no more than the exposure leaves unbacked
+        let withdraw = |amount: u128| ExecuteMsg::WithdrawSurplus { amount };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), withdraw(500)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("treasury0000", &[]), withdraw(501)).unwrap_err();
+        assert!(matches!(err, ContractError::ExposureLimit { max_exposure: 998 }));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("treasury0000", &[]), withdraw(500)).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: "treasury0000".to_string(), amount: coins(500, "ucore") })
+        );
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ReserveStatus {}).unwrap();
+        let reserve: ReserveStatusResponse = from_binary(&res).unwrap();
+        assert_eq!(reserve.balance, 500);
+        assert_eq!(reserve.surplus_withdrawn, 500);
+    }
+
+    #[test]
+    fn test_cancel_policy_refunds_unused_premium() {
+        let mut deps = mock_dependencies();
+        setup_policy(&mut deps);
+        create_policy(deps.as_mut());
+        let pay = ExecuteMsg::PayPremium { policy_id: "policy0001".to_string(), amount: 100 };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &coins(100, "ucore")), pay).unwrap();
+
+        // 12 of the 30 paid days have been used
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(12 * 24 * 60 * 60);
+        let cancel = ExecuteMsg::CancelPolicy { policy_id: "policy0001".to_string() };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("someone_else", &[]), cancel.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("policy_holder", &[]), cancel.clone()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: "policy_holder".to_string(), amount: coins(60, "ucore") })
+        );
+        let err = execute(deps.as_mut(), env.clone(), mock_info("policy_holder", &[]), cancel).unwrap_err();
+        assert!(matches!(err, ContractError::PolicyCancelled {}));
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetPolicy { policy_id: "policy0001".to_string() }).unwrap();
+        let policy_response: PolicyResponse = from_binary(&res).unwrap();
+        assert_eq!(policy_response.status, PolicyStatus::Cancelled);
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Exposure {}).unwrap();
+        let exposure: ExposureResponse = from_binary(&res).unwrap();
+        assert_eq!(exposure.total_exposure, 0);
+        let res = query(deps.as_ref(), env, QueryMsg::ReserveStatus {}).unwrap();
+        let reserve: ReserveStatusResponse = from_binary(&res).unwrap();
+        assert_eq!(reserve.balance, 1040);
+        assert_eq!(reserve.premiums_refunded, 60);
+    }
+
+    #[test]
+    fn test_policy_expires_and_renews() {
+        let mut deps = mock_dependencies();
+        setup_policy(&mut deps);
+        let msg = ExecuteMsg::CreatePolicy {
+            policy_id: "policy0001".to_string(),
+            insured_amount: 1000,
+            premium: 100,
+            premium_frequency: "monthly".to_string(),
+            policy_term: "30 days".to_string(),
+            condition: "standard_condition".to_string(),
+            riders: vec![],
+            trigger: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), msg).unwrap();
+        approve_quote(deps.as_mut(), "policy0001");
+        let pay = ExecuteMsg::PayPremium { policy_id: "policy0001".to_string(), amount: 100 };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &coins(100, "ucore")), pay.clone()).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(30 * 24 * 60 * 60);
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetPolicy { policy_id: "policy0001".to_string() }).unwrap();
+        let policy_response: PolicyResponse = from_binary(&res).unwrap();
+        assert_eq!(policy_response.status, PolicyStatus::Expired);
+        let err = execute(deps.as_mut(), env.clone(), mock_info("policy_holder", &coins(100, "ucore")), pay.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::PolicyExpired {}));
+
+        // closing an expired policy frees its reserve backing
+        let close = ExecuteMsg::ClosePolicy { policy_id: "policy0001".to_string() };
+        execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), close).unwrap();
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Exposure {}).unwrap();
+        let exposure: ExposureResponse = from_binary(&res).unwrap();
+        assert_eq!(exposure.total_exposure, 0);
+
+        let renew = ExecuteMsg::RenewPolicy { policy_id: "policy0001".to_string() };
+        execute(deps.as_mut(), env.clone(), mock_info("policy_holder", &[]), renew).unwrap();
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetPolicy { policy_id: "policy0001".to_string() }).unwrap();
+        let policy_response: PolicyResponse = from_binary(&res).unwrap();
+        assert_eq!(policy_response.status, PolicyStatus::Active);
+        assert_eq!(policy_response.term_end, env.block.time.seconds() + 30 * 24 * 60 * 60);
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Exposure {}).unwrap();
+        let exposure: ExposureResponse = from_binary(&res).unwrap();
+        assert_eq!(exposure.total_exposure, 1000);
+        execute(deps.as_mut(), env, mock_info("policy_holder", &coins(100, "ucore")), pay).unwrap();
+    }
+
+    #[test]
+    fn test_underwriter_decides_quotes() {
+        let mut deps = mock_dependencies();
+        setup_policy(&mut deps);
+        for policy_id in ["policy0001", "policy0002"] {
+            let msg = ExecuteMsg::CreatePolicy {
+                policy_id: policy_id.to_string(),
+                insured_amount: 1000,
+                premium: 100,
+                premium_frequency: "monthly".to_string(),
+                policy_term: "1 year".to_string(),
+                condition: "standard_condition".to_string(),
+                riders: vec![],
+                trigger: None,
+            };
+            let res = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), msg.clone()).unwrap();
+            assert!(res.messages.is_empty());
+            let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), msg).unwrap_err();
+            assert!(matches!(err, ContractError::PolicyExists { .. }));
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::PendingQuotes { start_after: None, limit: None }).unwrap();
+        let pending: QuotesResponse = from_binary(&res).unwrap();
+        assert_eq!(pending.quotes.len(), 2);
+
+        // only underwriters decide, and approval may adjust the premium
+        let approve = ExecuteMsg::ApproveQuote { policy_id: "policy0001".to_string(), premium: Some(120) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), approve.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("underwriter", &[]), approve.clone()).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        let policy: InsurancePolicy = INSURANCE_POLICIES.load(&deps.storage, "policy0001").unwrap();
+        assert_eq!(policy.premium, 120);
+        let err = execute(deps.as_mut(), mock_env(), mock_info("underwriter", &[]), approve).unwrap_err();
+        assert!(matches!(err, ContractError::QuoteNotPending {}));
+
+        let decline = ExecuteMsg::DeclineQuote { policy_id: "policy0002".to_string(), reason: "too risky".to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("underwriter", &[]), decline).unwrap();
+        assert!(INSURANCE_POLICIES.may_load(&deps.storage, "policy0002").unwrap().is_none());
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::PendingQuotes { start_after: None, limit: None }).unwrap();
+        let pending: QuotesResponse = from_binary(&res).unwrap();
+        assert!(pending.quotes.is_empty());
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetQuote { policy_id: "policy0002".to_string() }).unwrap();
+        let quote: crate::msg::QuoteResponse = from_binary(&res).unwrap();
+        assert_eq!(quote.status, QuoteStatus::Declined);
+        assert_eq!(quote.reason, Some("too risky".to_string()));
+    }
+
+    #[test]
+    fn test_nft_buyer_becomes_beneficiary() {
+        let mut deps = mock_dependencies();
+        setup_policy(&mut deps);
+        create_policy(deps.as_mut());
+        let pay = ExecuteMsg::PayPremium { policy_id: "policy0001".to_string(), amount: 100 };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &coins(100, "ucore")), pay).unwrap();
+
+        // the policy NFT was sold on a marketplace; the seller loses its rights even before anyone syncs
+        set_nft_owner(&mut deps, "buyer");
+        let cancel = ExecuteMsg::CancelPolicy { policy_id: "policy0001".to_string() };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), cancel.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let sync = ExecuteMsg::SyncPolicyOwner { policy_id: "policy0001".to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), sync).unwrap();
+        let policy: InsurancePolicy = INSURANCE_POLICIES.load(&deps.storage, "policy0001").unwrap();
+        assert_eq!(policy.owner, "buyer");
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("buyer", &[]), cancel).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: "buyer".to_string(), amount: coins(100, "ucore") })
+        );
+    }
+
+    #[test]
+    fn test_riders_add_premium_and_coverage() {
+        let mut deps = mock_dependencies();
+        setup_policy(&mut deps);
+        create_policy(deps.as_mut());
+        let msg = ExecuteMsg::SetAssessor { assessor: "assessor".to_string(), enabled: true };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let rider = Rider {
+            rider_id: "theft".to_string(),
+            description: "theft cover".to_string(),
+            premium: 20,
+            coverage: 500,
+            term: "3 months".to_string(),
+        };
+        let attach = ExecuteMsg::AttachRider { policy_id: "policy0001".to_string(), rider };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), attach.clone()).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), attach.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::RiderExists { .. }));
+
+        // nothing changes until an underwriter signs off on the rider
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetPolicy { policy_id: "policy0001".to_string() }).unwrap();
+        let policy_response: PolicyResponse = from_binary(&res).unwrap();
+        assert_eq!(policy_response.premium, 100);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::PendingRiders { policy_id: "policy0001".to_string() }).unwrap();
+        let pending: PendingRidersResponse = from_binary(&res).unwrap();
+        assert_eq!(pending.riders.len(), 1);
+        let approve = ExecuteMsg::ApproveRider { policy_id: "policy0001".to_string(), rider_id: "theft".to_string(), premium: None };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), approve).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        approve_rider(deps.as_mut(), "policy0001", "theft");
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), attach).unwrap_err();
+        assert!(matches!(err, ContractError::RiderExists { .. }));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetPolicy { policy_id: "policy0001".to_string() }).unwrap();
+        let policy_response: PolicyResponse = from_binary(&res).unwrap();
+        assert_eq!(policy_response.premium, 120);
+        assert_eq!(policy_response.base_premium, 100);
+        assert_eq!(policy_response.coverage, 1500);
+        assert!(policy_response.riders[0].in_force);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Exposure {}).unwrap();
+        let exposure: ExposureResponse = from_binary(&res).unwrap();
+        assert_eq!(exposure.total_exposure, 1500);
+
+        // the base premium alone no longer pays up the policy
+        let pay = ExecuteMsg::PayPremium { policy_id: "policy0001".to_string(), amount: 100 };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &coins(100, "ucore")), pay).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPremium {}));
+        let pay = ExecuteMsg::PayPremium { policy_id: "policy0001".to_string(), amount: 120 };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &coins(120, "ucore")), pay).unwrap();
+
+        let claim = ExecuteMsg::FileClaim { policy_id: "policy0001".to_string(), evidence_uri: "ipfs://evidence".to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), claim).unwrap();
+        let assess = ExecuteMsg::AssessClaim { claim_id: 1, approve: true, reason: "stolen".to_string() };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("assessor", &[]), assess).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: "policy_holder".to_string(), amount: coins(1500, "ucore") })
+        );
+    }
+
+    #[test]
+    fn test_detach_rider_restores_premium() {
+        let mut deps = mock_dependencies();
+        setup_policy(&mut deps);
+        create_policy(deps.as_mut());
+        let rider = Rider {
+            rider_id: "theft".to_string(),
+            description: "theft cover".to_string(),
+            premium: 20,
+            coverage: 500,
+            term: "3 months".to_string(),
+        };
+        let attach = ExecuteMsg::AttachRider { policy_id: "policy0001".to_string(), rider };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), attach).unwrap();
+        approve_rider(deps.as_mut(), "policy0001", "theft");
+
+        let detach = ExecuteMsg::DetachRider { policy_id: "policy0001".to_string(), rider_id: "theft".to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), detach.clone()).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), detach).unwrap_err();
+        assert!(matches!(err, ContractError::RiderNotFound { .. }));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetPolicy { policy_id: "policy0001".to_string() }).unwrap();
+        let policy_response: PolicyResponse = from_binary(&res).unwrap();
+        assert_eq!(policy_response.premium, 100);
+        assert!(policy_response.riders.is_empty());
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Exposure {}).unwrap();
+        let exposure: ExposureResponse = from_binary(&res).unwrap();
+        assert_eq!(exposure.total_exposure, 1000);
+    }
+
+    #[test]
+    fn test_declined_and_expired_riders_are_not_booked() {
+        let mut deps = mock_dependencies();
+        setup_policy(&mut deps);
+        let rider = |rider_id: &str, term: &str| Rider {
+            rider_id: rider_id.to_string(),
+            description: "extra cover".to_string(),
+            premium: 20,
+            coverage: 500,
+            term: term.to_string(),
+        };
+        let msg = ExecuteMsg::CreatePolicy {
+            policy_id: "policy0001".to_string(),
+            insured_amount: 1000,
+            premium: 100,
+            premium_frequency: "monthly".to_string(),
+            policy_term: "30 days".to_string(),
+            condition: "standard_condition".to_string(),
+            riders: vec![rider("storm", "10 days")],
+            trigger: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), msg).unwrap();
+        approve_quote(deps.as_mut(), "policy0001");
+
+        let attach = ExecuteMsg::AttachRider { policy_id: "policy0001".to_string(), rider: rider("theft", "3 months") };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), attach).unwrap();
+        let decline = ExecuteMsg::DeclineRider { policy_id: "policy0001".to_string(), rider_id: "theft".to_string(), reason: "too risky".to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("underwriter", &[]), decline.clone()).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("underwriter", &[]), decline).unwrap_err();
+        assert!(matches!(err, ContractError::QuoteNotPending {}));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Exposure {}).unwrap();
+        let exposure: ExposureResponse = from_binary(&res).unwrap();
+        assert_eq!(exposure.total_exposure, 1500);
+
+        // the storm rider ran out before the policy expired, so renewal books the policy alone
+        let pay = ExecuteMsg::PayPremium { policy_id: "policy0001".to_string(), amount: 120 };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &coins(120, "ucore")), pay).unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(30 * 24 * 60 * 60);
+        let close = ExecuteMsg::ClosePolicy { policy_id: "policy0001".to_string() };
+        execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), close).unwrap();
+        let renew = ExecuteMsg::RenewPolicy { policy_id: "policy0001".to_string() };
+        execute(deps.as_mut(), env.clone(), mock_info("policy_holder", &[]), renew).unwrap();
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Exposure {}).unwrap();
+        let exposure: ExposureResponse = from_binary(&res).unwrap();
+        assert_eq!(exposure.total_exposure, 1000);
+        let res = query(deps.as_ref(), env, QueryMsg::GetPolicy { policy_id: "policy0001".to_string() }).unwrap();
+        let policy_response: PolicyResponse = from_binary(&res).unwrap();
+        assert!(policy_response.riders.is_empty());
+    }
+
+    #[test]
+    fn test_premium_schedule_and_grace_period() {
+        let mut deps = mock_dependencies();
+        setup_policy(&mut deps);
+
+        // only the admin sets the grace period, which applies to policies issued afterwards
+        let grace = ExecuteMsg::SetGracePeriod { grace_period: Some(5 * 24 * 60 * 60) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), grace.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), grace).unwrap();
+        create_policy(deps.as_mut());
+
+        let pay = ExecuteMsg::PayPremium { policy_id: "policy0001".to_string(), amount: 100 };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &coins(100, "ucore")), pay.clone()).unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(29 * 24 * 60 * 60);
+        execute(deps.as_mut(), env.clone(), mock_info("policy_holder", &coins(100, "ucore")), pay.clone()).unwrap();
+
+        let query_msg = QueryMsg::PremiumSchedule { policy_id: "policy0001".to_string(), start_after: None, limit: None };
+        let schedule: PremiumScheduleResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap()).unwrap();
+        let start = mock_env().block.time.seconds();
+        let month = 30 * 24 * 60 * 60;
+        assert_eq!(schedule.installments.len(), 2);
+        assert_eq!(schedule.installments[1].covers_from, start + month);
+        assert_eq!(schedule.total_paid, 200);
+        assert_eq!(schedule.next_premium_due, start + 2 * month);
+        assert_eq!(schedule.lapses_at, start + 2 * month + 5 * 24 * 60 * 60);
+        // the remaining monthly premiums of the 365 day term
+        assert_eq!(schedule.upcoming_due_dates.len(), 11);
+        let page = QueryMsg::PremiumSchedule { policy_id: "policy0001".to_string(), start_after: Some(1), limit: Some(5) };
+        let schedule_page: PremiumScheduleResponse = from_binary(&query(deps.as_ref(), env.clone(), page).unwrap()).unwrap();
+        assert_eq!(schedule_page.installments.len(), 1);
+        assert_eq!(schedule_page.installments[0].installment, 2);
+        assert_eq!(schedule_page.total_paid, 200);
+
+        // the policy lapses once the grace period has passed, well before a whole premium period
+        env.block.time = mock_env().block.time.plus_seconds(2 * month + 5 * 24 * 60 * 60);
+        let err = execute(deps.as_mut(), env.clone(), mock_info("policy_holder", &coins(100, "ucore")), pay).unwrap_err();
+        assert!(matches!(err, ContractError::PolicyLapsed {}));
+        let schedule: PremiumScheduleResponse = from_binary(&query(deps.as_ref(), env, query_msg).unwrap()).unwrap();
+        assert_eq!(schedule.status, PolicyStatus::Lapsed);
+        assert!(schedule.upcoming_due_dates.is_empty());
+    }
+
+    #[test]
+    fn test_reinsurance_splits_premiums_and_claims() {
+        let mut deps = mock_dependencies();
+        setup_policy(&mut deps);
+        create_policy(deps.as_mut());
+        let msg = ExecuteMsg::SetAssessor { assessor: "assessor".to_string(), enabled: true };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // only the treasury cedes, and the shares may not exceed the whole exposure
+        let cede = |reinsurer: &str, percent: u64| ExecuteMsg::CedeExposure {
+            policy_id: "policy0001".to_string(),
+            reinsurer: reinsurer.to_string(),
+            share: Decimal::percent(percent),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), cede("re_a", 30)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info("treasury0000", &[]), cede("re_a", 30)).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("treasury0000", &[]), cede("re_b", 20)).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("treasury0000", &[]), cede("re_a", 10)).unwrap_err();
+        assert!(matches!(err, ContractError::CessionExists { .. }));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("treasury0000", &[]), cede("re_c", 60)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidCession {}));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Cessions { policy_id: "policy0001".to_string() }).unwrap();
+        let cessions: CessionsResponse = from_binary(&res).unwrap();
+        assert_eq!(cessions.ceded_share, Decimal::percent(50));
+        assert_eq!(cessions.gross_exposure, 1000);
+        assert_eq!(cessions.net_exposure, 500);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Exposure {}).unwrap();
+        let exposure: ExposureResponse = from_binary(&res).unwrap();
+        assert_eq!(exposure.total_exposure, 1000);
+        assert_eq!(exposure.ceded_exposure, 500);
+        assert_eq!(exposure.net_exposure, 500);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Reinsurer { reinsurer: "re_b".to_string() }).unwrap();
+        let account: ReinsurerResponse = from_binary(&res).unwrap();
+        assert_eq!(account.ceded_exposure, 200);
+
+        // the ceded premium shares are forwarded, the reserve keeps the rest
+        let pay = ExecuteMsg::PayPremium { policy_id: "policy0001".to_string(), amount: 100 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &coins(100, "ucore")), pay).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: "re_a".to_string(), amount: coins(30, "ucore") })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: "re_b".to_string(), amount: coins(20, "ucore") })
+        );
+
+        // the policyholder is paid in full, the reinsurer shares are owed back to the reserve
+        let claim = ExecuteMsg::FileClaim { policy_id: "policy0001".to_string(), evidence_uri: "ipfs://evidence".to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("policy_holder", &[]), claim).unwrap();
+        let assess = ExecuteMsg::AssessClaim { claim_id: 1, approve: true, reason: "covered event".to_string() };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("assessor", &[]), assess).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: "policy_holder".to_string(), amount: coins(1000, "ucore") })
+        );
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Reinsurer { reinsurer: "re_a".to_string() }).unwrap();
+        let account: ReinsurerResponse = from_binary(&res).unwrap();
+        assert_eq!(account.premiums_ceded, 30);
+        assert_eq!(account.claims_recoverable, 300);
+        assert_eq!(account.ceded_exposure, 0);
+
+        let settle = ExecuteMsg::SettleRecoverable {};
+        let err = execute(deps.as_mut(), mock_env(), mock_info("re_a", &coins(301, "ucore")), settle.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFunds {}));
+        execute(deps.as_mut(), mock_env(), mock_info("re_a", &coins(300, "ucore")), settle).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ReserveStatus {}).unwrap();
+        let reserve: ReserveStatusResponse = from_binary(&res).unwrap();
+        assert_eq!(reserve.premiums_collected, 100);
+        assert_eq!(reserve.premiums_ceded, 50);
+        assert_eq!(reserve.reinsurance_recovered, 300);
+        // capital plus retained premium, less the claim, plus the recovery
+        assert_eq!(reserve.balance, 1000 + 50 - 1000 + 300);
+    }
+}
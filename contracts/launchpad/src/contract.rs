@@ -0,0 +1,656 @@
+use coreum_wasm_sdk::assetft;
+use coreum_wasm_sdk::core::{CoreumMsg, CoreumResult};
+use cosmwasm_std::{
+    coin, entry_point, to_json_binary, BankMsg, Binary, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult, Uint128, Uint256,
+};
+use cw2::set_contract_version;
+use cw_ownable::{assert_owner, initialize_owner};
+use cw_utils::must_pay;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, Pricing, PurchaseResponse, QueryMsg, QuoteResponse,
+    SaleResponse, SaleStatus,
+};
+use crate::state::{Config, Purchase, Sale, CONFIG, PURCHASES, SALE};
+
+// version info for migration info
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// ********** Instantiate **********
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> CoreumResult<ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    initialize_owner(deps.storage, deps.api, Some(info.sender.as_ref()))?;
+
+    let denom = format!("{}-{}", msg.token.subunit, env.contract.address).to_lowercase();
+    let config = Config {
+        denom: denom.clone(),
+        payment_denom: msg.payment_denom,
+        pricing: msg.pricing,
+        soft_cap: msg.soft_cap,
+        hard_cap: msg.hard_cap,
+        wallet_limit: msg.wallet_limit,
+        start_time: msg.start_time,
+        end_time: msg.end_time,
+        vesting: msg.vesting,
+    };
+    validate(&config, &env)?;
+    CONFIG.save(deps.storage, &config)?;
+    SALE.save(deps.storage, &Sale::default())?;
+
+    // purchased tokens are minted on claim, so nothing exists beyond what was sold
+    let mut features = msg.token.features.unwrap_or_default();
+    if !features.contains(&assetft::MINTING) {
+        features.push(assetft::MINTING);
+    }
+    let issue_msg = CoreumMsg::AssetFT(assetft::Msg::Issue {
+        symbol: msg.token.symbol,
+        subunit: msg.token.subunit,
+        precision: msg.token.precision,
+        initial_amount: Uint128::zero(),
+        description: msg.token.description,
+        features: Some(features),
+        burn_rate: "0".to_string(),
+        send_commission_rate: "0".to_string(),
+        uri: msg.token.uri,
+        uri_hash: msg.token.uri_hash,
+    });
+
+    Ok(Response::new()
+        .add_attribute("owner", info.sender)
+        .add_attribute("denom", denom)
+        .add_message(issue_msg))
+}
+
+fn validate(config: &Config, env: &Env) -> Result<(), ContractError> {
+    let invalid = |reason: &str| Err(ContractError::InvalidSale(reason.to_string()));
+
+    if config.payment_denom.is_empty() {
+        return invalid("payment denom is empty");
+    }
+    if config.start_time >= config.end_time || config.end_time <= env.block.time.seconds() {
+        return invalid("sale must end after it starts and in the future");
+    }
+    if config.hard_cap.is_zero() || config.soft_cap > config.hard_cap {
+        return invalid("soft cap must not exceed a positive hard cap");
+    }
+    if config.wallet_limit == Some(Uint128::zero()) {
+        return invalid("wallet limit must be positive");
+    }
+    match config.pricing {
+        Pricing::Fixed { price } if price.is_zero() => return invalid("price must be positive"),
+        Pricing::LinearCurve {
+            initial_price,
+            slope,
+        } if initial_price.is_zero() && slope.is_zero() => {
+            return invalid("price must be positive")
+        }
+        _ => {}
+    }
+    if let Some(vesting) = &config.vesting {
+        if vesting.duration == 0 || vesting.cliff > vesting.duration {
+            return invalid("vesting cliff must not exceed a positive duration");
+        }
+    }
+    Ok(())
+}
+
+// ********** Execute **********
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> CoreumResult<ContractError> {
+    match msg {
+        ExecuteMsg::Buy { amount } => buy(deps, env, info, amount),
+        ExecuteMsg::Claim {} => claim(deps, env, info),
+        ExecuteMsg::Refund {} => refund(deps, env, info),
+        ExecuteMsg::WithdrawRaised {} => withdraw_raised(deps, env, info),
+    }
+}
+
+// ********** Transactions **********
+
+fn buy(deps: DepsMut, env: Env, info: MessageInfo, amount: Uint128) -> CoreumResult<ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut sale = SALE.load(deps.storage)?;
+    if status(&config, &sale, &env) != SaleStatus::Active {
+        return Err(ContractError::SaleNotActive {});
+    }
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let paid = must_pay(&info, &config.payment_denom)?;
+    let cost = cost(&config.pricing, sale.sold, amount)?;
+    if paid < cost {
+        return Err(ContractError::InsufficientPayment { required: cost });
+    }
+    let remaining = config.hard_cap - sale.raised;
+    if cost > remaining {
+        return Err(ContractError::HardCapExceeded { remaining });
+    }
+
+    let mut purchase = PURCHASES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    if let Some(limit) = config.wallet_limit {
+        if purchase.paid + cost > limit {
+            return Err(ContractError::WalletLimitExceeded {
+                remaining: limit.saturating_sub(purchase.paid),
+            });
+        }
+    }
+
+    purchase.paid += cost;
+    purchase.tokens = purchase.tokens.checked_add(amount)?;
+    PURCHASES.save(deps.storage, &info.sender, &purchase)?;
+    sale.sold = sale.sold.checked_add(amount)?;
+    sale.raised += cost;
+    SALE.save(deps.storage, &sale)?;
+
+    let mut response = Response::new();
+    if paid > cost {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin((paid - cost).u128(), &config.payment_denom)],
+        });
+    }
+
+    Ok(response
+        .add_attribute("method", "buy")
+        .add_attribute("buyer", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("cost", cost))
+}
+
+fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> CoreumResult<ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let sale = SALE.load(deps.storage)?;
+    if status(&config, &sale, &env) != SaleStatus::Succeeded {
+        return Err(ContractError::NotSucceeded {});
+    }
+
+    let mut purchase = PURCHASES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    let claimable = vested(&config, purchase.tokens, &env) - purchase.claimed;
+    if claimable.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    purchase.claimed += claimable;
+    PURCHASES.save(deps.storage, &info.sender, &purchase)?;
+
+    Ok(Response::new()
+        .add_message(CoreumMsg::AssetFT(assetft::Msg::Mint {
+            coin: coin(claimable.u128(), &config.denom),
+            recipient: Some(info.sender.to_string()),
+        }))
+        .add_attribute("method", "claim")
+        .add_attribute("buyer", info.sender)
+        .add_attribute("amount", claimable))
+}
+
+fn refund(deps: DepsMut, env: Env, info: MessageInfo) -> CoreumResult<ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let sale = SALE.load(deps.storage)?;
+    if status(&config, &sale, &env) != SaleStatus::Failed {
+        return Err(ContractError::NotFailed {});
+    }
+
+    let purchase = PURCHASES
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NothingToClaim {})?;
+    PURCHASES.remove(deps.storage, &info.sender);
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(purchase.paid.u128(), &config.payment_denom)],
+        })
+        .add_attribute("method", "refund")
+        .add_attribute("buyer", info.sender)
+        .add_attribute("amount", purchase.paid))
+}
+
+fn withdraw_raised(deps: DepsMut, env: Env, info: MessageInfo) -> CoreumResult<ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let config = CONFIG.load(deps.storage)?;
+    let mut sale = SALE.load(deps.storage)?;
+    if status(&config, &sale, &env) != SaleStatus::Succeeded {
+        return Err(ContractError::NotSucceeded {});
+    }
+    if sale.withdrawn {
+        return Err(ContractError::AlreadyWithdrawn {});
+    }
+    sale.withdrawn = true;
+    SALE.save(deps.storage, &sale)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(sale.raised.u128(), &config.payment_denom)],
+        })
+        .add_attribute("method", "withdraw_raised")
+        .add_attribute("amount", sale.raised))
+}
+
+// ********** Helpers **********
+
+fn status(config: &Config, sale: &Sale, env: &Env) -> SaleStatus {
+    let now = env.block.time.seconds();
+    if now < config.start_time {
+        SaleStatus::Upcoming
+    } else if now < config.end_time && sale.raised < config.hard_cap {
+        SaleStatus::Active
+    } else if sale.raised >= config.soft_cap {
+        SaleStatus::Succeeded
+    } else {
+        SaleStatus::Failed
+    }
+}
+
+/// Payment for `amount` tokens after `sold` were sold, rounded up
+fn cost(pricing: &Pricing, sold: Uint128, amount: Uint128) -> Result<Uint128, ContractError> {
+    match *pricing {
+        Pricing::Fixed { price } => Ok(amount.mul_ceil(price)),
+        Pricing::LinearCurve {
+            initial_price,
+            slope,
+        } => {
+            // area under initial_price + slope * x between sold and sold + amount
+            let base = amount.mul_ceil(initial_price);
+            let span = Uint256::from(amount)
+                .checked_mul(Uint256::from(sold) * Uint256::from(2u8) + Uint256::from(amount))?;
+            let curve = (span.mul_ceil(slope) + Uint256::one()) / Uint256::from(2u8);
+            let curve: Uint128 = curve.try_into().map_err(StdError::from)?;
+            Ok(base.checked_add(curve)?)
+        }
+    }
+}
+
+/// Part of `tokens` released at the current block
+fn vested(config: &Config, tokens: Uint128, env: &Env) -> Uint128 {
+    let Some(vesting) = &config.vesting else {
+        return tokens;
+    };
+    let elapsed = env.block.time.seconds().saturating_sub(config.end_time);
+    if elapsed < vesting.cliff {
+        Uint128::zero()
+    } else if elapsed >= vesting.duration {
+        tokens
+    } else {
+        tokens.multiply_ratio(elapsed, vesting.duration)
+    }
+}
+
+// ********** Queries **********
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Sale {} => to_json_binary(&query_sale(deps, env)?),
+        QueryMsg::Quote { amount } => to_json_binary(&query_quote(deps, amount)?),
+        QueryMsg::Purchase { address } => to_json_binary(&query_purchase(deps, env, address)?),
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        denom: config.denom,
+        payment_denom: config.payment_denom,
+        pricing: config.pricing,
+        soft_cap: config.soft_cap,
+        hard_cap: config.hard_cap,
+        wallet_limit: config.wallet_limit,
+        start_time: config.start_time,
+        end_time: config.end_time,
+        vesting: config.vesting,
+    })
+}
+
+fn query_sale(deps: Deps, env: Env) -> StdResult<SaleResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let sale = SALE.load(deps.storage)?;
+    Ok(SaleResponse {
+        status: status(&config, &sale, &env),
+        sold: sale.sold,
+        raised: sale.raised,
+    })
+}
+
+fn query_quote(deps: Deps, amount: Uint128) -> StdResult<QuoteResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let sale = SALE.load(deps.storage)?;
+    let cost = cost(&config.pricing, sale.sold, amount)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(QuoteResponse { cost })
+}
+
+fn query_purchase(deps: Deps, env: Env, address: String) -> StdResult<PurchaseResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let config = CONFIG.load(deps.storage)?;
+    let sale = SALE.load(deps.storage)?;
+    let purchase: Purchase = PURCHASES
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+    let claimable = if status(&config, &sale, &env) == SaleStatus::Succeeded {
+        vested(&config, purchase.tokens, &env) - purchase.claimed
+    } else {
+        Uint128::zero()
+    };
+    Ok(PurchaseResponse {
+        paid: purchase.paid,
+        tokens: purchase.tokens,
+        claimed: purchase.claimed,
+        claimable,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::{TokenParams, VestingTerms};
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{coins, CosmosMsg, OwnedDeps, Timestamp};
+
+    const OWNER: &str = "owner";
+    const ALICE: &str = "alice";
+    const BOB: &str = "bob";
+    const CORE: &str = "ucore";
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    }
+
+    fn denom() -> String {
+        format!("ulaunch-{}", mock_env().contract.address)
+    }
+
+    fn instantiate_msg(pricing: Pricing, vesting: Option<VestingTerms>) -> InstantiateMsg {
+        InstantiateMsg {
+            token: TokenParams {
+                symbol: "LAUNCH".to_string(),
+                subunit: "ulaunch".to_string(),
+                precision: 6,
+                description: None,
+                features: None,
+                uri: None,
+                uri_hash: None,
+            },
+            payment_denom: CORE.to_string(),
+            pricing,
+            soft_cap: Uint128::new(1_000),
+            hard_cap: Uint128::new(5_000),
+            wallet_limit: Some(Uint128::new(3_000)),
+            start_time: 100,
+            end_time: 200,
+            vesting,
+        }
+    }
+
+    fn setup(
+        pricing: Pricing,
+        vesting: Option<VestingTerms>,
+    ) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        let res = instantiate(
+            deps.as_mut(),
+            env_at(0),
+            mock_info(OWNER, &[]),
+            instantiate_msg(pricing, vesting),
+        )
+        .unwrap();
+        assert!(matches!(
+            &res.messages[0].msg,
+            CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Issue { features: Some(features), .. }))
+                if features.contains(&assetft::MINTING)
+        ));
+        deps
+    }
+
+    fn buy_at(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        seconds: u64,
+        buyer: &str,
+        amount: u128,
+        paid: u128,
+    ) -> Result<Response<CoreumMsg>, ContractError> {
+        let msg = ExecuteMsg::Buy {
+            amount: Uint128::new(amount),
+        };
+        execute(
+            deps.as_mut(),
+            env_at(seconds),
+            mock_info(buyer, &coins(paid, CORE)),
+            msg,
+        )
+    }
+
+    #[test]
+    fn rejects_invalid_sale() {
+        let mut deps = mock_dependencies();
+        let mut msg = instantiate_msg(
+            Pricing::Fixed {
+                price: Decimal::zero(),
+            },
+            None,
+        );
+        let err =
+            instantiate(deps.as_mut(), env_at(0), mock_info(OWNER, &[]), msg.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidSale("price must be positive".to_string())
+        );
+
+        msg.pricing = Pricing::Fixed {
+            price: Decimal::one(),
+        };
+        msg.soft_cap = Uint128::new(10_000);
+        let err = instantiate(deps.as_mut(), env_at(0), mock_info(OWNER, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidSale(_)));
+    }
+
+    #[test]
+    fn fixed_price_sale_with_limits() {
+        let price = Decimal::percent(50);
+        let mut deps = setup(Pricing::Fixed { price }, None);
+
+        let err = buy_at(&mut deps, 50, ALICE, 1_000, 500).unwrap_err();
+        assert_eq!(err, ContractError::SaleNotActive {});
+        let err = buy_at(&mut deps, 100, ALICE, 1_000, 499).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientPayment {
+                required: Uint128::new(500)
+            }
+        );
+
+        // overpayment comes back
+        let res = buy_at(&mut deps, 100, ALICE, 1_000, 600).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: ALICE.to_string(),
+                amount: coins(100, CORE),
+            })
+        );
+
+        let err = buy_at(&mut deps, 110, ALICE, 6_000, 3_000).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::WalletLimitExceeded {
+                remaining: Uint128::new(2_500)
+            }
+        );
+        buy_at(&mut deps, 110, ALICE, 5_000, 2_500).unwrap();
+        buy_at(&mut deps, 120, BOB, 4_000, 2_000).unwrap();
+
+        // hard cap reached: the sale closes early
+        let err = buy_at(&mut deps, 120, BOB, 2, 1).unwrap_err();
+        assert_eq!(err, ContractError::SaleNotActive {});
+        let sale = query_sale(deps.as_ref(), env_at(130)).unwrap();
+        assert_eq!(sale.status, SaleStatus::Succeeded);
+        assert_eq!(sale.raised, Uint128::new(5_000));
+
+        let res = execute(
+            deps.as_mut(),
+            env_at(130),
+            mock_info(ALICE, &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Mint {
+                coin: coin(6_000, denom()),
+                recipient: Some(ALICE.to_string()),
+            }))
+        );
+
+        let msg = ExecuteMsg::WithdrawRaised {};
+        execute(
+            deps.as_mut(),
+            env_at(130),
+            mock_info(ALICE, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        execute(
+            deps.as_mut(),
+            env_at(130),
+            mock_info(OWNER, &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        let err = execute(deps.as_mut(), env_at(130), mock_info(OWNER, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::AlreadyWithdrawn {});
+    }
+
+    #[test]
+    fn bonding_curve_price_grows() {
+        let pricing = Pricing::LinearCurve {
+            initial_price: Decimal::one(),
+            slope: Decimal::percent(1),
+        };
+        let deps = setup(pricing.clone(), None);
+
+        // 100 tokens: 100 * 1 + 0.01 * 100^2 / 2
+        assert_eq!(
+            query_quote(deps.as_ref(), Uint128::new(100)).unwrap().cost,
+            Uint128::new(150)
+        );
+        // the next 100 cost more: 100 + 0.01 * (2 * 100 * 100 + 100^2) / 2
+        assert_eq!(
+            cost(&pricing, Uint128::new(100), Uint128::new(100)).unwrap(),
+            Uint128::new(250)
+        );
+    }
+
+    #[test]
+    fn refund_when_soft_cap_missed() {
+        let mut deps = setup(
+            Pricing::Fixed {
+                price: Decimal::one(),
+            },
+            None,
+        );
+        buy_at(&mut deps, 150, ALICE, 500, 500).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env_at(150),
+            mock_info(ALICE, &[]),
+            ExecuteMsg::Refund {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotFailed {});
+        let err = execute(
+            deps.as_mut(),
+            env_at(200),
+            mock_info(ALICE, &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotSucceeded {});
+
+        let res = execute(
+            deps.as_mut(),
+            env_at(200),
+            mock_info(ALICE, &[]),
+            ExecuteMsg::Refund {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: ALICE.to_string(),
+                amount: coins(500, CORE),
+            })
+        );
+        let err = execute(
+            deps.as_mut(),
+            env_at(200),
+            mock_info(ALICE, &[]),
+            ExecuteMsg::Refund {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NothingToClaim {});
+    }
+
+    #[test]
+    fn purchases_vest_after_sale() {
+        let mut deps = setup(
+            Pricing::Fixed {
+                price: Decimal::one(),
+            },
+            Some(VestingTerms {
+                cliff: 10,
+                duration: 100,
+            }),
+        );
+        buy_at(&mut deps, 150, ALICE, 2_000, 2_000).unwrap();
+
+        let claim = ExecuteMsg::Claim {};
+        let err = execute(
+            deps.as_mut(),
+            env_at(205),
+            mock_info(ALICE, &[]),
+            claim.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NothingToClaim {});
+
+        let purchase = query_purchase(deps.as_ref(), env_at(250), ALICE.to_string()).unwrap();
+        assert_eq!(purchase.claimable, Uint128::new(1_000));
+        execute(
+            deps.as_mut(),
+            env_at(250),
+            mock_info(ALICE, &[]),
+            claim.clone(),
+        )
+        .unwrap();
+
+        let purchase = query_purchase(deps.as_ref(), env_at(400), ALICE.to_string()).unwrap();
+        assert_eq!(purchase.claimed, Uint128::new(1_000));
+        assert_eq!(purchase.claimable, Uint128::new(1_000));
+    }
+}
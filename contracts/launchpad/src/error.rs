@@ -0,0 +1,49 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use cw_ownable::OwnershipError;
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error(transparent)]
+    Ownership(#[from] OwnershipError),
+
+    #[error("Invalid sale: {0}")]
+    InvalidSale(String),
+
+    #[error("Amount must be positive")]
+    ZeroAmount {},
+
+    #[error("Sale is not active")]
+    SaleNotActive {},
+
+    #[error("Purchase exceeds the hard cap, {remaining} left to raise")]
+    HardCapExceeded { remaining: Uint128 },
+
+    #[error("Purchase exceeds the wallet limit, {remaining} left for this wallet")]
+    WalletLimitExceeded { remaining: Uint128 },
+
+    #[error("Purchase costs {required}")]
+    InsufficientPayment { required: Uint128 },
+
+    #[error("Sale did not succeed")]
+    NotSucceeded {},
+
+    #[error("Sale did not fail")]
+    NotFailed {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("Raised funds were already withdrawn")]
+    AlreadyWithdrawn {},
+}
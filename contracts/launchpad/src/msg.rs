@@ -0,0 +1,114 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Decimal, Uint128};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Issuance of the sold token; the launchpad adds the minting feature and stays the issuer
+    pub token: TokenParams,
+    pub payment_denom: String,
+    pub pricing: Pricing,
+    pub soft_cap: Uint128,
+    pub hard_cap: Uint128,
+    pub wallet_limit: Option<Uint128>,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub vesting: Option<VestingTerms>,
+}
+
+#[cw_serde]
+pub struct TokenParams {
+    pub symbol: String,
+    pub subunit: String,
+    pub precision: u32,
+    pub description: Option<String>,
+    pub features: Option<Vec<u32>>,
+    pub uri: Option<String>,
+    pub uri_hash: Option<String>,
+}
+
+/// Price of one token subunit in payment subunits
+#[cw_serde]
+pub enum Pricing {
+    Fixed {
+        price: Decimal,
+    },
+    /// Price grows by `slope` with every token sold, starting at `initial_price`
+    LinearCurve {
+        initial_price: Decimal,
+        slope: Decimal,
+    },
+}
+
+/// Tokens vest linearly over `duration` seconds after the sale ends, nothing before `cliff`
+#[cw_serde]
+pub struct VestingTerms {
+    pub cliff: u64,
+    pub duration: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Buys `amount` tokens paying with the attached payment coin; overpayment is refunded.
+    Buy { amount: Uint128 },
+    /// Mints the vested part of the sender's purchase once the sale succeeded.
+    Claim {},
+    /// Pays the sender back once the sale failed to reach its soft cap.
+    Refund {},
+    /// Owner only; sends the raised funds to the owner once the sale succeeded.
+    WithdrawRaised {},
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(SaleResponse)]
+    Sale {},
+    /// Cost of buying `amount` tokens now
+    #[returns(QuoteResponse)]
+    Quote { amount: Uint128 },
+    #[returns(PurchaseResponse)]
+    Purchase { address: String },
+}
+
+#[cw_serde]
+pub enum SaleStatus {
+    Upcoming,
+    Active,
+    Succeeded,
+    Failed,
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub denom: String,
+    pub payment_denom: String,
+    pub pricing: Pricing,
+    pub soft_cap: Uint128,
+    pub hard_cap: Uint128,
+    pub wallet_limit: Option<Uint128>,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub vesting: Option<VestingTerms>,
+}
+
+#[cw_serde]
+pub struct SaleResponse {
+    pub status: SaleStatus,
+    pub sold: Uint128,
+    pub raised: Uint128,
+}
+
+#[cw_serde]
+pub struct QuoteResponse {
+    pub cost: Uint128,
+}
+
+#[cw_serde]
+pub struct PurchaseResponse {
+    pub paid: Uint128,
+    pub tokens: Uint128,
+    pub claimed: Uint128,
+    pub claimable: Uint128,
+}
@@ -0,0 +1,45 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+
+use crate::msg::{Pricing, VestingTerms};
+
+#[cw_serde]
+pub struct Config {
+    /// Smart FT issued by the launchpad and minted to buyers as it vests
+    pub denom: String,
+    /// Denom buyers pay in
+    pub payment_denom: String,
+    pub pricing: Pricing,
+    /// Raised amounts, in the payment denom
+    pub soft_cap: Uint128,
+    pub hard_cap: Uint128,
+    /// Most a single wallet can pay in
+    pub wallet_limit: Option<Uint128>,
+    /// Seconds since epoch
+    pub start_time: u64,
+    pub end_time: u64,
+    /// Vesting of purchased tokens, counted from `end_time`
+    pub vesting: Option<VestingTerms>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Sale {
+    /// Tokens sold, which sets the bonding curve price
+    pub sold: Uint128,
+    pub raised: Uint128,
+    pub withdrawn: bool,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Purchase {
+    pub paid: Uint128,
+    pub tokens: Uint128,
+    pub claimed: Uint128,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const SALE: Item<Sale> = Item::new("sale");
+pub const PURCHASES: Map<&Addr, Purchase> = Map::new("purchases");
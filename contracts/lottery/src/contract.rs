@@ -0,0 +1,695 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdError, StdResult, Storage, Uint128,
+};
+use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+use cw_utils::must_pay;
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+use crate::msg::{
+    CurrentRoundResponse, ExecuteMsg, InstantiateMsg, PlayerResponse, QueryMsg, RoundsResponse,
+};
+use crate::state::{
+    Config, Entry, Player, Round, RoundResult, Winner, CONFIG, ENTRIES, HISTORY, PLAYERS,
+    REVEALED_ENTRIES, ROUND,
+};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:lottery";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    if msg.ticket_price.amount.is_zero()
+        || msg.sale_duration == 0
+        || msg.reveal_duration == 0
+        || msg.winners == 0
+    {
+        return Err(ContractError::InvalidConfig {});
+    }
+
+    let config = Config {
+        ticket_price: msg.ticket_price,
+        sale_duration: msg.sale_duration,
+        reveal_duration: msg.reveal_duration,
+        winners: msg.winners,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    ROUND.save(
+        deps.storage,
+        &new_round(&config, 1, env.block.time.seconds(), Uint128::zero()),
+    )?;
+
+    Ok(Response::new().add_attribute("round", "1"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::BuyTickets { count, commitment } => {
+            execute_buy_tickets(deps, env, info, count, commitment)
+        }
+        ExecuteMsg::Reveal { secret } => execute_reveal(deps, env, info, secret),
+        ExecuteMsg::Draw {} => execute_draw(deps, env),
+    }
+}
+
+pub fn execute_buy_tickets(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    count: u32,
+    commitment: Option<Binary>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut round = ROUND.load(deps.storage)?;
+    if env.block.time.seconds() >= round.sale_end {
+        return Err(ContractError::SalesClosed {});
+    }
+    if count == 0 {
+        return Err(ContractError::NoTickets {});
+    }
+
+    let required = config
+        .ticket_price
+        .amount
+        .checked_mul(count.into())
+        .map_err(StdError::from)?;
+    if must_pay(&info, &config.ticket_price.denom)? != required {
+        return Err(ContractError::WrongPayment { required });
+    }
+
+    let first = round.tickets;
+    round.tickets = first
+        .checked_add(count)
+        .ok_or(ContractError::TooManyTickets {})?;
+
+    // later purchases in the same round keep the first commitment
+    let mut player = match PLAYERS.may_load(deps.storage, (round.id, &info.sender))? {
+        Some(player) => player,
+        None => match commitment {
+            Some(commitment) if commitment.len() == 32 => Player {
+                tickets: 0,
+                commitment,
+                revealed: false,
+            },
+            _ => return Err(ContractError::InvalidCommitment {}),
+        },
+    };
+    player.tickets = player
+        .tickets
+        .checked_add(count)
+        .ok_or(ContractError::TooManyTickets {})?;
+    PLAYERS.save(deps.storage, (round.id, &info.sender), &player)?;
+
+    ENTRIES.save(
+        deps.storage,
+        (round.id, first),
+        &Entry {
+            owner: info.sender.clone(),
+            count,
+        },
+    )?;
+    round.pot += required;
+    ROUND.save(deps.storage, &round)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "buy_tickets")
+        .add_attribute("round", round.id.to_string())
+        .add_attribute("player", info.sender)
+        .add_attribute("first_ticket", first.to_string())
+        .add_attribute("count", count.to_string()))
+}
+
+pub fn execute_reveal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    secret: Binary,
+) -> Result<Response, ContractError> {
+    let mut round = ROUND.load(deps.storage)?;
+    let now = env.block.time.seconds();
+    if now < round.sale_end || now >= round.reveal_end {
+        return Err(ContractError::NotRevealPhase {});
+    }
+
+    let mut player = PLAYERS
+        .may_load(deps.storage, (round.id, &info.sender))?
+        .ok_or(ContractError::NotPlaying {})?;
+    if player.revealed {
+        return Err(ContractError::AlreadyRevealed {});
+    }
+    if sha256(&[secret.as_slice()]) != player.commitment {
+        return Err(ContractError::InvalidSecret {});
+    }
+    player.revealed = true;
+    PLAYERS.save(deps.storage, (round.id, &info.sender), &player)?;
+
+    // the player's tickets become drawable, numbered after the ones revealed before
+    REVEALED_ENTRIES.save(
+        deps.storage,
+        (round.id, round.revealed_tickets),
+        &info.sender,
+    )?;
+    round.revealed_tickets += player.tickets;
+    round.seed = sha256(&[round.seed.as_slice(), secret.as_slice()]);
+    ROUND.save(deps.storage, &round)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "reveal")
+        .add_attribute("round", round.id.to_string())
+        .add_attribute("player", info.sender))
+}
+
+pub fn execute_draw(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let round = ROUND.load(deps.storage)?;
+    let now = env.block.time.seconds();
+    if now < round.reveal_end {
+        return Err(ContractError::DrawTooEarly {
+            reveal_end: round.reveal_end,
+        });
+    }
+
+    let winners = draw_winners(deps.storage, &config, &round)?;
+    let carried_over = if winners.is_empty() {
+        round.pot
+    } else {
+        Uint128::zero()
+    };
+    let messages: Vec<BankMsg> = winners
+        .iter()
+        .map(|winner| BankMsg::Send {
+            to_address: winner.address.to_string(),
+            amount: coins(winner.prize.u128(), &config.ticket_price.denom),
+        })
+        .collect();
+
+    HISTORY.save(
+        deps.storage,
+        round.id,
+        &RoundResult {
+            id: round.id,
+            tickets: round.tickets,
+            revealed_tickets: round.revealed_tickets,
+            pot: round.pot,
+            seed: round.seed,
+            winners,
+            drawn_at: now,
+        },
+    )?;
+    ROUND.save(
+        deps.storage,
+        &new_round(&config, round.id + 1, now, carried_over),
+    )?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "draw")
+        .add_attribute("round", round.id.to_string())
+        .add_attribute("tickets", round.tickets.to_string())
+        .add_attribute("revealed_tickets", round.revealed_tickets.to_string())
+        .add_attribute("pot", round.pot)
+        .add_attribute("carried_over", carried_over))
+}
+
+fn new_round(config: &Config, id: u64, now: u64, pot: Uint128) -> Round {
+    let sale_end = now + config.sale_duration;
+    Round {
+        id,
+        sale_end,
+        reveal_end: sale_end + config.reveal_duration,
+        tickets: 0,
+        pot,
+        seed: Binary::default(),
+        revealed_tickets: 0,
+    }
+}
+
+/// Picks distinct winning tickets among the revealed ones from the round seed and splits the
+/// pot between their owners, the first winner also gets the rounding remainder. The seed only
+/// depends on revealed secrets, so without any there is nothing to draw from
+fn draw_winners(storage: &dyn Storage, config: &Config, round: &Round) -> StdResult<Vec<Winner>> {
+    let count = config.winners.min(round.revealed_tickets);
+    if count == 0 {
+        return Ok(vec![]);
+    }
+
+    let mut tickets: Vec<u32> = Vec::with_capacity(count as usize);
+    let mut nonce: u32 = 0;
+    while tickets.len() < count as usize {
+        let hash = sha256(&[round.seed.as_slice(), nonce.to_be_bytes().as_slice()]);
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&hash[..8]);
+        let ticket = (u64::from_be_bytes(bytes) % round.revealed_tickets as u64) as u32;
+        if !tickets.contains(&ticket) {
+            tickets.push(ticket);
+        }
+        nonce += 1;
+    }
+
+    let prize = round.pot / Uint128::from(count);
+    let remainder = round.pot - prize * Uint128::from(count);
+    tickets
+        .into_iter()
+        .enumerate()
+        .map(|(i, ticket)| {
+            Ok(Winner {
+                address: ticket_owner(storage, round.id, ticket)?,
+                ticket,
+                prize: if i == 0 { prize + remainder } else { prize },
+            })
+        })
+        .collect()
+}
+
+fn ticket_owner(storage: &dyn Storage, round: u64, ticket: u32) -> StdResult<Addr> {
+    // the entry holding `ticket` is the last one starting at or before it
+    REVEALED_ENTRIES
+        .prefix(round)
+        .range(
+            storage,
+            None,
+            Some(Bound::inclusive(ticket)),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?
+        .map(|(_, owner)| owner)
+        .ok_or_else(|| StdError::not_found("ticket entry"))
+}
+
+fn sha256(parts: &[&[u8]]) -> Binary {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    Binary::from(hasher.finalize().to_vec())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::CurrentRound {} => to_json_binary(&query_current_round(deps)?),
+        QueryMsg::Player { round, address } => to_json_binary(&query_player(deps, round, address)?),
+        QueryMsg::Round { id } => to_json_binary(&HISTORY.load(deps.storage, id)?),
+        QueryMsg::Rounds {
+            start_before,
+            limit,
+        } => to_json_binary(&query_rounds(deps, start_before, limit)?),
+    }
+}
+
+fn query_current_round(deps: Deps) -> StdResult<CurrentRoundResponse> {
+    let round = ROUND.load(deps.storage)?;
+    Ok(CurrentRoundResponse {
+        id: round.id,
+        sale_end: round.sale_end,
+        reveal_end: round.reveal_end,
+        tickets: round.tickets,
+        pot: round.pot,
+    })
+}
+
+fn query_player(deps: Deps, round: u64, address: String) -> StdResult<PlayerResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    Ok(match PLAYERS.may_load(deps.storage, (round, &address))? {
+        Some(player) => PlayerResponse {
+            tickets: player.tickets,
+            revealed: player.revealed,
+        },
+        None => PlayerResponse {
+            tickets: 0,
+            revealed: false,
+        },
+    })
+}
+
+fn query_rounds(
+    deps: Deps,
+    start_before: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<RoundsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let end = start_before.map(Bound::exclusive);
+
+    let rounds = HISTORY
+        .range(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .map(|item| item.map(|(_, result)| result))
+        .collect::<StdResult<_>>()?;
+
+    Ok(RoundsResponse { rounds })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{coin, from_json, CosmosMsg, OwnedDeps, Timestamp};
+
+    const ALICE: &str = "alice";
+    const BOB: &str = "bob";
+    const DENOM: &str = "ucore";
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    }
+
+    fn setup(winners: u32) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            ticket_price: coin(10, DENOM),
+            sale_duration: 100,
+            reveal_duration: 50,
+            winners,
+        };
+        instantiate(deps.as_mut(), env_at(0), mock_info("creator", &[]), msg).unwrap();
+        deps
+    }
+
+    fn commitment(secret: &[u8]) -> Option<Binary> {
+        Some(sha256(&[secret]))
+    }
+
+    fn buy(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        player: &str,
+        count: u32,
+        secret: &[u8],
+    ) {
+        let msg = ExecuteMsg::BuyTickets {
+            count,
+            commitment: commitment(secret),
+        };
+        let info = mock_info(player, &coins(10 * count as u128, DENOM));
+        execute(deps.as_mut(), env_at(10), info, msg).unwrap();
+    }
+
+    fn reveal(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        seconds: u64,
+        player: &str,
+        secret: &[u8],
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Reveal {
+            secret: Binary::from(secret),
+        };
+        execute(deps.as_mut(), env_at(seconds), mock_info(player, &[]), msg)
+    }
+
+    #[test]
+    fn buying_tickets() {
+        let mut deps = setup(1);
+
+        let msg = ExecuteMsg::BuyTickets {
+            count: 2,
+            commitment: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(10),
+            mock_info(ALICE, &coins(20, DENOM)),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidCommitment {});
+
+        let msg = ExecuteMsg::BuyTickets {
+            count: 2,
+            commitment: commitment(b"alice"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(10),
+            mock_info(ALICE, &coins(25, DENOM)),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::WrongPayment {
+                required: Uint128::new(20)
+            }
+        );
+        let err = execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info(ALICE, &coins(20, DENOM)),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::SalesClosed {});
+
+        buy(&mut deps, ALICE, 2, b"alice");
+        // the commitment is only needed once per round
+        let msg = ExecuteMsg::BuyTickets {
+            count: 1,
+            commitment: None,
+        };
+        execute(
+            deps.as_mut(),
+            env_at(20),
+            mock_info(ALICE, &coins(10, DENOM)),
+            msg,
+        )
+        .unwrap();
+
+        let player = query_player(deps.as_ref(), 1, ALICE.to_string()).unwrap();
+        assert_eq!(player.tickets, 3);
+        let round = query_current_round(deps.as_ref()).unwrap();
+        assert_eq!(round.tickets, 3);
+        assert_eq!(round.pot, Uint128::new(30));
+    }
+
+    #[test]
+    fn reveal_phase() {
+        let mut deps = setup(1);
+        buy(&mut deps, ALICE, 1, b"alice");
+
+        let err = reveal(&mut deps, 50, ALICE, b"alice").unwrap_err();
+        assert_eq!(err, ContractError::NotRevealPhase {});
+        let err = reveal(&mut deps, 120, BOB, b"bob").unwrap_err();
+        assert_eq!(err, ContractError::NotPlaying {});
+        let err = reveal(&mut deps, 120, ALICE, b"bob").unwrap_err();
+        assert_eq!(err, ContractError::InvalidSecret {});
+
+        let seed = ROUND.load(&deps.storage).unwrap().seed;
+        reveal(&mut deps, 120, ALICE, b"alice").unwrap();
+        assert_ne!(ROUND.load(&deps.storage).unwrap().seed, seed);
+        let err = reveal(&mut deps, 130, ALICE, b"alice").unwrap_err();
+        assert_eq!(err, ContractError::AlreadyRevealed {});
+
+        let err = execute(
+            deps.as_mut(),
+            env_at(149),
+            mock_info(BOB, &[]),
+            ExecuteMsg::Draw {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::DrawTooEarly { reveal_end: 150 });
+        let err = reveal(&mut deps, 150, ALICE, b"alice").unwrap_err();
+        assert_eq!(err, ContractError::NotRevealPhase {});
+    }
+
+    #[test]
+    fn draw_pays_winners_and_opens_next_round() {
+        let mut deps = setup(2);
+        buy(&mut deps, ALICE, 2, b"alice");
+        buy(&mut deps, BOB, 1, b"bob");
+        reveal(&mut deps, 110, ALICE, b"alice").unwrap();
+        reveal(&mut deps, 120, BOB, b"bob").unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env_at(160),
+            mock_info(BOB, &[]),
+            ExecuteMsg::Draw {},
+        )
+        .unwrap();
+        let result = HISTORY.load(&deps.storage, 1).unwrap();
+        assert_eq!(result.winners.len(), 2);
+        assert_ne!(result.winners[0].ticket, result.winners[1].ticket);
+        assert_eq!(result.winners[0].prize, Uint128::new(15));
+        assert_eq!(result.winners[1].prize, Uint128::new(15));
+        for (winner, msg) in result.winners.iter().zip(&res.messages) {
+            let owner = if winner.ticket < 2 { ALICE } else { BOB };
+            assert_eq!(winner.address, Addr::unchecked(owner));
+            assert_eq!(
+                msg.msg,
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: owner.to_string(),
+                    amount: coins(15, DENOM),
+                })
+            );
+        }
+
+        let round = query_current_round(deps.as_ref()).unwrap();
+        assert_eq!(round.id, 2);
+        assert_eq!(round.sale_end, 260);
+        assert_eq!(round.tickets, 0);
+        // players commit again in every round
+        assert_eq!(
+            query_player(deps.as_ref(), 2, ALICE.to_string()).unwrap(),
+            PlayerResponse {
+                tickets: 0,
+                revealed: false
+            }
+        );
+    }
+
+    #[test]
+    fn unrevealed_tickets_are_forfeited() {
+        let mut deps = setup(1);
+        buy(&mut deps, ALICE, 5, b"alice");
+        buy(&mut deps, BOB, 1, b"bob");
+        reveal(&mut deps, 120, BOB, b"bob").unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env_at(160),
+            mock_info(ALICE, &[]),
+            ExecuteMsg::Draw {},
+        )
+        .unwrap();
+        let result = HISTORY.load(&deps.storage, 1).unwrap();
+        assert_eq!(result.tickets, 6);
+        assert_eq!(result.revealed_tickets, 1);
+        assert_eq!(
+            result.winners,
+            vec![Winner {
+                address: Addr::unchecked(BOB),
+                ticket: 0,
+                prize: Uint128::new(60),
+            }]
+        );
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: BOB.to_string(),
+                amount: coins(60, DENOM),
+            })
+        );
+        assert_eq!(
+            query_current_round(deps.as_ref()).unwrap().pot,
+            Uint128::zero()
+        );
+    }
+
+    #[test]
+    fn ticket_count_overflow() {
+        let mut deps = setup(1);
+        buy(&mut deps, ALICE, u32::MAX, b"alice");
+
+        let msg = ExecuteMsg::BuyTickets {
+            count: 1,
+            commitment: commitment(b"bob"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(20),
+            mock_info(BOB, &coins(10, DENOM)),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::TooManyTickets {});
+        let msg = ExecuteMsg::BuyTickets {
+            count: 1,
+            commitment: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(20),
+            mock_info(ALICE, &coins(10, DENOM)),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::TooManyTickets {});
+    }
+
+    #[test]
+    fn round_history() {
+        let mut deps = setup(3);
+        buy(&mut deps, ALICE, 1, b"alice");
+        execute(
+            deps.as_mut(),
+            env_at(150),
+            mock_info(BOB, &[]),
+            ExecuteMsg::Draw {},
+        )
+        .unwrap();
+        // an empty round has no winners either and carries the pot on
+        execute(
+            deps.as_mut(),
+            env_at(300),
+            mock_info(BOB, &[]),
+            ExecuteMsg::Draw {},
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            env_at(300),
+            QueryMsg::Rounds {
+                start_before: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let rounds: RoundsResponse = from_json(res).unwrap();
+        assert_eq!(
+            rounds
+                .rounds
+                .iter()
+                .map(|round| round.id)
+                .collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+        // an unrevealed ticket can't win, its price carries over to the next round
+        assert!(rounds.rounds[1].winners.is_empty());
+        assert_eq!(rounds.rounds[1].revealed_tickets, 0);
+        assert!(rounds.rounds[0].winners.is_empty());
+        assert_eq!(rounds.rounds[0].pot, Uint128::new(10));
+        assert_eq!(
+            query_current_round(deps.as_ref()).unwrap().pot,
+            Uint128::new(10)
+        );
+
+        let res = query(
+            deps.as_ref(),
+            env_at(300),
+            QueryMsg::Rounds {
+                start_before: Some(2),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let rounds: RoundsResponse = from_json(res).unwrap();
+        assert_eq!(rounds.rounds.len(), 1);
+        assert_eq!(rounds.rounds[0].id, 1);
+    }
+}
@@ -0,0 +1,45 @@
+use cosmwasm_std::{StdError, Uint128};
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("Ticket price, round durations and number of winners must be positive")]
+    InvalidConfig {},
+
+    #[error("Must buy at least one ticket")]
+    NoTickets {},
+
+    #[error("Ticket sales are closed for this round")]
+    SalesClosed {},
+
+    #[error("Too many tickets in this round")]
+    TooManyTickets {},
+
+    #[error("Tickets cost {required}")]
+    WrongPayment { required: Uint128 },
+
+    #[error("First purchase in a round needs a 32 byte sha256 commitment")]
+    InvalidCommitment {},
+
+    #[error("Secrets can only be revealed between the end of sales and the draw")]
+    NotRevealPhase {},
+
+    #[error("Sender has no tickets in this round")]
+    NotPlaying {},
+
+    #[error("Secret was already revealed")]
+    AlreadyRevealed {},
+
+    #[error("Secret does not match the commitment")]
+    InvalidSecret {},
+
+    #[error("Round can only be drawn after {reveal_end}")]
+    DrawTooEarly { reveal_end: u64 },
+}
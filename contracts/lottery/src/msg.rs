@@ -0,0 +1,68 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Binary, Coin, Uint128};
+
+use crate::state::{Config, RoundResult};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub ticket_price: Coin,
+    pub sale_duration: u64,
+    pub reveal_duration: u64,
+    pub winners: u32,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Buys `count` tickets in the current round. The first purchase of a round must carry
+    /// `commitment`, the sha256 hash of a secret revealed once sales close.
+    BuyTickets {
+        count: u32,
+        commitment: Option<Binary>,
+    },
+    /// Reveals the sender's secret, mixing it into the round's randomness. Tickets of players
+    /// who don't reveal can't win, their price stays in the pot.
+    Reveal { secret: Binary },
+    /// Draws and pays the winners once the reveal phase is over and opens the next round.
+    /// Anyone can call it. When nobody revealed, the pot carries over to the next round.
+    Draw {},
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(Config)]
+    Config {},
+    #[returns(CurrentRoundResponse)]
+    CurrentRound {},
+    #[returns(PlayerResponse)]
+    Player { round: u64, address: String },
+    /// Result of a drawn round
+    #[returns(RoundResult)]
+    Round { id: u64 },
+    /// Drawn rounds, most recent first
+    #[returns(RoundsResponse)]
+    Rounds {
+        start_before: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct CurrentRoundResponse {
+    pub id: u64,
+    pub sale_end: u64,
+    pub reveal_end: u64,
+    pub tickets: u32,
+    pub pot: Uint128,
+}
+
+#[cw_serde]
+pub struct PlayerResponse {
+    pub tickets: u32,
+    pub revealed: bool,
+}
+
+#[cw_serde]
+pub struct RoundsResponse {
+    pub rounds: Vec<RoundResult>,
+}
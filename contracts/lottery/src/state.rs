@@ -0,0 +1,74 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Binary, Coin, Uint128};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    pub ticket_price: Coin,
+    /// Seconds tickets are sold for in every round
+    pub sale_duration: u64,
+    /// Seconds players have after the sale to reveal their secrets
+    pub reveal_duration: u64,
+    /// Winning tickets drawn per round, the pot is split evenly between them
+    pub winners: u32,
+}
+
+#[cw_serde]
+pub struct Round {
+    pub id: u64,
+    pub sale_end: u64,
+    pub reveal_end: u64,
+    pub tickets: u32,
+    pub pot: Uint128,
+    /// Running hash of every revealed secret
+    pub seed: Binary,
+    /// Tickets of the players who revealed, only these can win
+    #[serde(default)]
+    pub revealed_tickets: u32,
+}
+
+#[cw_serde]
+pub struct Player {
+    pub tickets: u32,
+    /// sha256 of the secret the player reveals after the sale
+    pub commitment: Binary,
+    pub revealed: bool,
+}
+
+/// Consecutive tickets bought in one purchase, keyed by the first ticket number
+#[cw_serde]
+pub struct Entry {
+    pub owner: Addr,
+    pub count: u32,
+}
+
+#[cw_serde]
+pub struct Winner {
+    pub address: Addr,
+    /// Position of the winning ticket among the revealed tickets
+    pub ticket: u32,
+    pub prize: Uint128,
+}
+
+#[cw_serde]
+pub struct RoundResult {
+    pub id: u64,
+    pub tickets: u32,
+    #[serde(default)]
+    pub revealed_tickets: u32,
+    /// Without revealed tickets there are no winners and the pot carries over to the next round
+    pub pot: Uint128,
+    pub seed: Binary,
+    pub winners: Vec<Winner>,
+    /// Block time of the draw
+    pub drawn_at: u64,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const ROUND: Item<Round> = Item::new("round");
+pub const PLAYERS: Map<(u64, &Addr), Player> = Map::new("players");
+pub const ENTRIES: Map<(u64, u32), Entry> = Map::new("entries");
+/// Owner of the revealed tickets of a round, keyed by the position of their first ticket
+/// among the revealed ones
+pub const REVEALED_ENTRIES: Map<(u64, u32), Addr> = Map::new("revealed_entries");
+pub const HISTORY: Map<u64, RoundResult> = Map::new("history");
@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    coin, to_json_binary, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Uint128, WasmMsg,
+};
+use cw2::set_contract_version;
+
+use crate::error::ContractError;
+use crate::msg::{
+    CartItem, CartQuoteResponse, ExecuteMsg, InstantiateMsg, ItemQuote, MarketplaceExecuteMsg,
+    MarketplaceQueryMsg, QueryMsg, SaleQuoteResponse,
+};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:marketplace-router";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Checkout { items } => execute_checkout(deps, info, items),
+    }
+}
+
+pub fn execute_checkout(
+    deps: DepsMut,
+    info: MessageInfo,
+    items: Vec<CartItem>,
+) -> Result<Response, ContractError> {
+    if items.is_empty() {
+        return Err(ContractError::EmptyCart {});
+    }
+    for (i, item) in items.iter().enumerate() {
+        let duplicate = items[..i]
+            .iter()
+            .any(|other| other.marketplace == item.marketplace && other.token_id == item.token_id);
+        if duplicate {
+            return Err(ContractError::DuplicateItem {
+                marketplace: item.marketplace.clone(),
+                token_id: item.token_id.clone(),
+            });
+        }
+    }
+
+    let quotes = quote_items(deps.as_ref(), &items)?;
+    let mut remaining: BTreeMap<String, Uint128> = BTreeMap::new();
+    for funds in &info.funds {
+        *remaining.entry(funds.denom.clone()).or_default() += funds.amount;
+    }
+
+    let sent = remaining.clone();
+    // denoms whose transfers are charged fees, the cost of an item exceeds its price
+    let mut fee_bearing = BTreeSet::new();
+    let mut messages = vec![];
+    for (quote, item) in quotes.into_iter().zip(items) {
+        if !quote.within_max {
+            return Err(ContractError::PriceAboveMax {
+                token_id: quote.token_id,
+                cost: quote.cost,
+                max_price: item.max_price,
+            });
+        }
+        // the smart FT fees on top of the price are charged to this contract's balance
+        if quote.cost > quote.price {
+            fee_bearing.insert(quote.denom.clone());
+        }
+        let available = remaining.entry(quote.denom.clone()).or_default();
+        let sent = *available;
+        *available =
+            sent.checked_sub(quote.cost)
+                .map_err(|_| ContractError::InsufficientFunds {
+                    denom: quote.denom.clone(),
+                    needed: quote.cost,
+                    sent,
+                })?;
+        messages.push(WasmMsg::Execute {
+            contract_addr: quote.marketplace.to_string(),
+            msg: to_json_binary(&MarketplaceExecuteMsg::BuyNFT {
+                id: quote.token_id,
+                recipient: Some(info.sender.to_string()),
+            })?,
+            funds: vec![coin(quote.price.u128(), quote.denom)],
+        });
+    }
+
+    for denom in fee_bearing {
+        let left = remaining[&denom];
+        if !left.is_zero() {
+            let sent = sent[&denom];
+            return Err(ContractError::InexactFeeBearingFunds {
+                needed: sent - left,
+                sent,
+                denom,
+            });
+        }
+    }
+
+    let refund: Vec<_> = remaining
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(denom, amount)| coin(amount.u128(), denom))
+        .collect();
+    let mut res = Response::new()
+        .add_attribute("action", "checkout")
+        .add_attribute("buyer", info.sender.as_str())
+        .add_attribute("items", messages.len().to_string())
+        .add_messages(messages);
+    if !refund.is_empty() {
+        res = res.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: refund,
+        });
+    }
+    Ok(res)
+}
+
+/// Asks each marketplace for the current price of its item
+fn quote_items(deps: Deps, items: &[CartItem]) -> StdResult<Vec<ItemQuote>> {
+    items
+        .iter()
+        .map(|item| {
+            let marketplace = deps.api.addr_validate(&item.marketplace)?;
+            let quote: SaleQuoteResponse = deps.querier.query_wasm_smart(
+                &marketplace,
+                &MarketplaceQueryMsg::QuoteSale {
+                    id: item.token_id.clone(),
+                },
+            )?;
+            Ok(ItemQuote {
+                marketplace,
+                token_id: item.token_id.clone(),
+                denom: quote.denom,
+                price: quote.price,
+                cost: quote.buyer_cost,
+                within_max: quote.buyer_cost <= item.max_price,
+            })
+        })
+        .collect()
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::QuoteCart { items } => to_json_binary(&query_quote_cart(deps, items)?),
+    }
+}
+
+fn query_quote_cart(deps: Deps, items: Vec<CartItem>) -> StdResult<CartQuoteResponse> {
+    let items = quote_items(deps, &items)?;
+    let mut total: BTreeMap<&str, Uint128> = BTreeMap::new();
+    for item in &items {
+        *total.entry(&item.denom).or_default() += item.cost;
+    }
+    let total = total
+        .into_iter()
+        .map(|(denom, amount)| coin(amount.u128(), denom))
+        .collect();
+    Ok(CartQuoteResponse { items, total })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{
+        coins, from_json, ContractResult, CosmosMsg, OwnedDeps, SystemError, SystemResult,
+        WasmQuery,
+    };
+
+    const BUYER: &str = "buyer";
+
+    fn item(marketplace: &str, token_id: &str, max_price: u128) -> CartItem {
+        CartItem {
+            marketplace: marketplace.to_string(),
+            token_id: token_id.to_string(),
+            max_price: Uint128::new(max_price),
+        }
+    }
+
+    /// Two marketplaces listing "a" for 100 and "b" for 250 ucore, and a third listing "c" for
+    /// 100 of a smart FT charging 10% on transfers
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } => {
+                let MarketplaceQueryMsg::QuoteSale { id } = from_json(msg).unwrap();
+                let (price, denom, fees) = match (contract_addr.as_str(), id.as_str()) {
+                    ("market1", "a") => (100u128, "ucore", 0u128),
+                    ("market2", "b") => (250, "ucore", 0),
+                    ("market3", "c") => (100, "ufan", 10),
+                    _ => {
+                        return SystemResult::Err(SystemError::InvalidRequest {
+                            error: "not listed".to_string(),
+                            request: msg.clone(),
+                        })
+                    }
+                };
+                let quote = SaleQuoteResponse {
+                    denom: denom.to_string(),
+                    price: Uint128::new(price),
+                    buyer_cost: Uint128::new(price + fees),
+                    royalty: Uint128::zero(),
+                    seller_net: Uint128::new(price),
+                    fees_deducted: Uint128::zero(),
+                };
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&quote).unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg {}).unwrap();
+        deps
+    }
+
+    #[test]
+    fn checkout_buys_across_marketplaces_and_refunds() {
+        let mut deps = setup();
+        let items = vec![item("market1", "a", 100), item("market2", "b", 300)];
+        let msg = ExecuteMsg::Checkout { items };
+        let info = mock_info(BUYER, &coins(400, "ucore"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        assert_eq!(res.messages.len(), 3);
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "market2".to_string(),
+                msg: to_json_binary(&MarketplaceExecuteMsg::BuyNFT {
+                    id: "b".to_string(),
+                    recipient: Some(BUYER.to_string()),
+                })
+                .unwrap(),
+                funds: coins(250, "ucore"),
+            })
+        );
+        assert_eq!(
+            res.messages[2].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: BUYER.to_string(),
+                amount: coins(50, "ucore"),
+            })
+        );
+    }
+
+    #[test]
+    fn checkout_fails_as_a_whole() {
+        let mut deps = setup();
+
+        let msg = ExecuteMsg::Checkout {
+            items: vec![item("market1", "a", 100), item("market2", "b", 200)],
+        };
+        let info = mock_info(BUYER, &coins(400, "ucore"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::PriceAboveMax {
+                token_id: "b".to_string(),
+                cost: Uint128::new(250),
+                max_price: Uint128::new(200),
+            }
+        );
+
+        let msg = ExecuteMsg::Checkout {
+            items: vec![item("market1", "a", 100), item("market2", "b", 300)],
+        };
+        let info = mock_info(BUYER, &coins(300, "ucore"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientFunds {
+                denom: "ucore".to_string(),
+                needed: Uint128::new(250),
+                sent: Uint128::new(200),
+            }
+        );
+
+        let msg = ExecuteMsg::Checkout {
+            items: vec![item("market1", "a", 100), item("market1", "a", 100)],
+        };
+        let info = mock_info(BUYER, &coins(200, "ucore"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::DuplicateItem { .. }));
+
+        let quote: CartQuoteResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::QuoteCart {
+                    items: vec![item("market1", "a", 100), item("market2", "b", 200)],
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(!quote.items[1].within_max);
+        assert_eq!(quote.total, coins(350, "ucore"));
+    }
+
+    #[test]
+    fn fee_bearing_denoms_are_paid_exactly() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::Checkout {
+            items: vec![item("market1", "a", 100), item("market3", "c", 110)],
+        };
+
+        let info = mock_info(BUYER, &[coin(150, "ucore"), coin(120, "ufan")]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InexactFeeBearingFunds {
+                denom: "ufan".to_string(),
+                needed: Uint128::new(110),
+                sent: Uint128::new(120),
+            }
+        );
+
+        // the plain denom is still refunded
+        let info = mock_info(BUYER, &[coin(150, "ucore"), coin(110, "ufan")]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 3);
+        assert_eq!(
+            res.messages[2].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: BUYER.to_string(),
+                amount: coins(50, "ucore"),
+            })
+        );
+    }
+}
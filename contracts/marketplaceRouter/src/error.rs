@@ -0,0 +1,38 @@
+use cosmwasm_std::{StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Cart is empty")]
+    EmptyCart {},
+
+    #[error("Token {token_id} of {marketplace} is in the cart more than once")]
+    DuplicateItem {
+        marketplace: String,
+        token_id: String,
+    },
+
+    #[error("Token {token_id} costs {cost}, more than the maximum of {max_price}")]
+    PriceAboveMax {
+        token_id: String,
+        cost: Uint128,
+        max_price: Uint128,
+    },
+
+    #[error("Cart costs {needed}{denom}, only {sent}{denom} sent")]
+    InsufficientFunds {
+        denom: String,
+        needed: Uint128,
+        sent: Uint128,
+    },
+
+    #[error("{denom} is charged send fees and can't be refunded, send exactly {needed}{denom}")]
+    InexactFeeBearingFunds {
+        denom: String,
+        needed: Uint128,
+        sent: Uint128,
+    },
+}
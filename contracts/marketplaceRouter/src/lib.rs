@@ -0,0 +1,5 @@
+pub mod contract;
+mod error;
+pub mod msg;
+
+pub use crate::error::ContractError;
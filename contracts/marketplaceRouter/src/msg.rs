@@ -0,0 +1,74 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Coin, Uint128};
+
+#[cw_serde]
+pub struct InstantiateMsg {}
+
+#[cw_serde]
+pub struct CartItem {
+    /// nftMarketPlace instance the token is listed on
+    pub marketplace: String,
+    pub token_id: String,
+    /// Most the buyer pays for the token, smart FT fees charged on the payment included
+    pub max_price: Uint128,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Buys every item for the sender, failing as a whole when a listing is gone, reserved for
+    /// someone else or above its maximum. Funds not spent are refunded, except in a smart FT
+    /// with send fees: refunding it would be charged fees the router doesn't hold, so those
+    /// denoms must be sent exactly the quoted total.
+    Checkout { items: Vec<CartItem> },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Prices a cart without buying it
+    #[returns(CartQuoteResponse)]
+    QuoteCart { items: Vec<CartItem> },
+}
+
+#[cw_serde]
+pub struct ItemQuote {
+    pub marketplace: Addr,
+    pub token_id: String,
+    pub denom: String,
+    /// Listing price, paid to the marketplace
+    pub price: Uint128,
+    /// Price plus the smart FT fees charged on paying it
+    pub cost: Uint128,
+    pub within_max: bool,
+}
+
+#[cw_serde]
+pub struct CartQuoteResponse {
+    pub items: Vec<ItemQuote>,
+    /// Cost of the whole cart by denom
+    pub total: Vec<Coin>,
+}
+
+/// Subset of the nftMarketPlace messages the router uses
+#[cw_serde]
+pub enum MarketplaceExecuteMsg {
+    BuyNFT {
+        id: String,
+        recipient: Option<String>,
+    },
+}
+
+#[cw_serde]
+pub enum MarketplaceQueryMsg {
+    QuoteSale { id: String },
+}
+
+#[cw_serde]
+pub struct SaleQuoteResponse {
+    pub denom: String,
+    pub price: Uint128,
+    pub buyer_cost: Uint128,
+    pub royalty: Uint128,
+    pub seller_net: Uint128,
+    pub fees_deducted: Uint128,
+}
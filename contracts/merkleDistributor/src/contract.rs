@@ -0,0 +1,465 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    coins, to_json_binary, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Uint128,
+};
+use cw2::set_contract_version;
+use cw_utils::one_coin;
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, IsClaimedResponse, LatestEpochResponse, QueryMsg};
+use crate::state::{Config, Epoch, CLAIMED, CONFIG, EPOCHS, LATEST_EPOCH};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:merkle-distributor";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let owner = match msg.owner {
+        Some(owner) => deps.api.addr_validate(&owner)?,
+        None => info.sender,
+    };
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: owner.clone(),
+        },
+    )?;
+    LATEST_EPOCH.save(deps.storage, &0)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "instantiate")
+        .add_attribute("owner", owner))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::RegisterEpoch {
+            merkle_root,
+            deadline,
+        } => execute_register_epoch(deps, env, info, merkle_root, deadline),
+        ExecuteMsg::Claim {
+            epoch,
+            amount,
+            proof,
+        } => execute_claim(deps, env, info, epoch, amount, proof),
+        ExecuteMsg::Clawback { epoch, recipient } => {
+            execute_clawback(deps, env, info, epoch, recipient)
+        }
+        ExecuteMsg::UpdateOwner { owner } => execute_update_owner(deps, info, owner),
+    }
+}
+
+pub fn execute_register_epoch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    merkle_root: Binary,
+    deadline: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if merkle_root.len() != 32 {
+        return Err(ContractError::InvalidHash {});
+    }
+    if deadline <= env.block.time.seconds() {
+        return Err(ContractError::InvalidDeadline {});
+    }
+    let funds = one_coin(&info)?;
+
+    let id = LATEST_EPOCH.load(deps.storage)? + 1;
+    let epoch = Epoch {
+        id,
+        merkle_root,
+        denom: funds.denom,
+        total_amount: funds.amount,
+        claimed_amount: Default::default(),
+        deadline,
+        clawed_back: false,
+    };
+    EPOCHS.save(deps.storage, id, &epoch)?;
+    LATEST_EPOCH.save(deps.storage, &id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "register_epoch")
+        .add_attribute("epoch", id.to_string())
+        .add_attribute("total_amount", epoch.total_amount)
+        .add_attribute("denom", epoch.denom)
+        .add_attribute("deadline", deadline.to_string()))
+}
+
+pub fn execute_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    amount: Uint128,
+    proof: Vec<Binary>,
+) -> Result<Response, ContractError> {
+    let mut epoch = EPOCHS.load(deps.storage, id)?;
+    if env.block.time.seconds() > epoch.deadline {
+        return Err(ContractError::ClaimClosed {
+            epoch: id,
+            deadline: epoch.deadline,
+        });
+    }
+    if CLAIMED.has(deps.storage, (id, &info.sender)) {
+        return Err(ContractError::AlreadyClaimed { epoch: id });
+    }
+
+    let leaf = sha256(&[info.sender.as_bytes(), amount.to_string().as_bytes()]);
+    if !verify_proof(&epoch.merkle_root, leaf, &proof) {
+        return Err(ContractError::InvalidProof { epoch: id });
+    }
+
+    epoch.claimed_amount = epoch.claimed_amount.checked_add(amount)?;
+    if epoch.claimed_amount > epoch.total_amount {
+        return Err(ContractError::InsufficientFunds { epoch: id });
+    }
+    EPOCHS.save(deps.storage, id, &epoch)?;
+    CLAIMED.save(deps.storage, (id, &info.sender), &true)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(amount.u128(), &epoch.denom),
+        })
+        .add_attribute("action", "claim")
+        .add_attribute("epoch", id.to_string())
+        .add_attribute("address", info.sender)
+        .add_attribute("amount", amount))
+}
+
+pub fn execute_clawback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut epoch = EPOCHS.load(deps.storage, id)?;
+    if env.block.time.seconds() <= epoch.deadline {
+        return Err(ContractError::ClawbackTooEarly {
+            deadline: epoch.deadline,
+        });
+    }
+    if epoch.clawed_back {
+        return Err(ContractError::AlreadyClawedBack { epoch: id });
+    }
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => config.owner,
+    };
+
+    let unclaimed = epoch.total_amount.checked_sub(epoch.claimed_amount)?;
+    epoch.clawed_back = true;
+    EPOCHS.save(deps.storage, id, &epoch)?;
+
+    let mut response = Response::new();
+    if !unclaimed.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: coins(unclaimed.u128(), &epoch.denom),
+        });
+    }
+    Ok(response
+        .add_attribute("action", "clawback")
+        .add_attribute("epoch", id.to_string())
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", unclaimed))
+}
+
+pub fn execute_update_owner(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    config.owner = deps.api.addr_validate(&owner)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_owner")
+        .add_attribute("owner", config.owner))
+}
+
+// Walks the proof from the leaf to the root, hashing every pair in sorted order so proofs
+// don't need to carry left/right positions.
+fn verify_proof(root: &Binary, leaf: Binary, proof: &[Binary]) -> bool {
+    let mut hash = leaf;
+    for node in proof {
+        if node.len() != 32 {
+            return false;
+        }
+        hash = if hash.as_slice() <= node.as_slice() {
+            sha256(&[&hash, node])
+        } else {
+            sha256(&[node, &hash])
+        };
+    }
+    hash == *root
+}
+
+fn sha256(parts: &[&[u8]]) -> Binary {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    Binary::from(hasher.finalize().to_vec())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Epoch { id } => to_json_binary(&EPOCHS.load(deps.storage, id)?),
+        QueryMsg::LatestEpoch {} => to_json_binary(&query_latest_epoch(deps)?),
+        QueryMsg::IsClaimed { epoch, address } => {
+            to_json_binary(&query_is_claimed(deps, epoch, address)?)
+        }
+    }
+}
+
+fn query_latest_epoch(deps: Deps) -> StdResult<LatestEpochResponse> {
+    let id = LATEST_EPOCH.load(deps.storage)?;
+    let epoch = EPOCHS.may_load(deps.storage, id)?;
+    Ok(LatestEpochResponse { epoch })
+}
+
+fn query_is_claimed(deps: Deps, epoch: u64, address: String) -> StdResult<IsClaimedResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let claimed = CLAIMED.has(deps.storage, (epoch, &address));
+    Ok(IsClaimedResponse { claimed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{from_json, CosmosMsg, OwnedDeps, Timestamp};
+
+    const OWNER: &str = "owner";
+    const ALICE: &str = "alice";
+    const BOB: &str = "bob";
+    const DENOM: &str = "ucore";
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    }
+
+    fn leaf(address: &str, amount: u128) -> Binary {
+        sha256(&[address.as_bytes(), amount.to_string().as_bytes()])
+    }
+
+    fn pair(a: &Binary, b: &Binary) -> Binary {
+        if a.as_slice() <= b.as_slice() {
+            sha256(&[a, b])
+        } else {
+            sha256(&[b, a])
+        }
+    }
+
+    // two leaf tree: alice gets 60, bob gets 40
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            env_at(0),
+            mock_info(OWNER, &[]),
+            InstantiateMsg { owner: None },
+        )
+        .unwrap();
+        let msg = ExecuteMsg::RegisterEpoch {
+            merkle_root: pair(&leaf(ALICE, 60), &leaf(BOB, 40)),
+            deadline: 100,
+        };
+        execute(
+            deps.as_mut(),
+            env_at(0),
+            mock_info(OWNER, &coins(100, DENOM)),
+            msg,
+        )
+        .unwrap();
+        deps
+    }
+
+    fn claim(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        seconds: u64,
+        sender: &str,
+        amount: u128,
+        proof: Vec<Binary>,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Claim {
+            epoch: 1,
+            amount: Uint128::new(amount),
+            proof,
+        };
+        execute(deps.as_mut(), env_at(seconds), mock_info(sender, &[]), msg)
+    }
+
+    #[test]
+    fn registering_epochs() {
+        let mut deps = setup();
+
+        let msg = ExecuteMsg::RegisterEpoch {
+            merkle_root: leaf(ALICE, 1),
+            deadline: 100,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(0),
+            mock_info(ALICE, &coins(1, DENOM)),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let err = execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info(OWNER, &coins(1, DENOM)),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidDeadline {});
+
+        let bad_root = ExecuteMsg::RegisterEpoch {
+            merkle_root: Binary::from(b"short"),
+            deadline: 100,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(0),
+            mock_info(OWNER, &coins(1, DENOM)),
+            bad_root,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidHash {});
+
+        execute(
+            deps.as_mut(),
+            env_at(0),
+            mock_info(OWNER, &coins(1, DENOM)),
+            msg,
+        )
+        .unwrap();
+        let res: LatestEpochResponse =
+            from_json(query(deps.as_ref(), env_at(0), QueryMsg::LatestEpoch {}).unwrap()).unwrap();
+        let epoch = res.epoch.unwrap();
+        assert_eq!(epoch.id, 2);
+        assert_eq!(epoch.total_amount, Uint128::new(1));
+    }
+
+    #[test]
+    fn claiming() {
+        let mut deps = setup();
+
+        // wrong amount does not match the root
+        let err = claim(&mut deps, 10, ALICE, 100, vec![leaf(BOB, 40)]).unwrap_err();
+        assert_eq!(err, ContractError::InvalidProof { epoch: 1 });
+
+        let res = claim(&mut deps, 10, ALICE, 60, vec![leaf(BOB, 40)]).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: ALICE.to_string(),
+                amount: coins(60, DENOM),
+            })
+        );
+        let err = claim(&mut deps, 10, ALICE, 60, vec![leaf(BOB, 40)]).unwrap_err();
+        assert_eq!(err, ContractError::AlreadyClaimed { epoch: 1 });
+
+        let msg = QueryMsg::IsClaimed {
+            epoch: 1,
+            address: ALICE.to_string(),
+        };
+        let res: IsClaimedResponse =
+            from_json(query(deps.as_ref(), env_at(10), msg).unwrap()).unwrap();
+        assert!(res.claimed);
+
+        // claims close at the deadline
+        let err = claim(&mut deps, 101, BOB, 40, vec![leaf(ALICE, 60)]).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ClaimClosed {
+                epoch: 1,
+                deadline: 100
+            }
+        );
+    }
+
+    #[test]
+    fn clawback() {
+        let mut deps = setup();
+        claim(&mut deps, 10, ALICE, 60, vec![leaf(BOB, 40)]).unwrap();
+
+        let msg = ExecuteMsg::Clawback {
+            epoch: 1,
+            recipient: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info(OWNER, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ClawbackTooEarly { deadline: 100 });
+
+        let err = execute(
+            deps.as_mut(),
+            env_at(101),
+            mock_info(ALICE, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let res = execute(
+            deps.as_mut(),
+            env_at(101),
+            mock_info(OWNER, &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: OWNER.to_string(),
+                amount: coins(40, DENOM),
+            })
+        );
+
+        let err = execute(deps.as_mut(), env_at(101), mock_info(OWNER, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::AlreadyClawedBack { epoch: 1 });
+    }
+}
@@ -0,0 +1,42 @@
+use cosmwasm_std::{OverflowError, StdError};
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Merkle root must be a 32 byte sha256 hash")]
+    InvalidHash {},
+
+    #[error("Claim deadline must be in the future")]
+    InvalidDeadline {},
+
+    #[error("Proof does not match the merkle root of epoch {epoch}")]
+    InvalidProof { epoch: u64 },
+
+    #[error("Rewards of epoch {epoch} were already claimed")]
+    AlreadyClaimed { epoch: u64 },
+
+    #[error("Claims for epoch {epoch} closed at {deadline}")]
+    ClaimClosed { epoch: u64, deadline: u64 },
+
+    #[error("Unclaimed rewards can only be clawed back after {deadline}")]
+    ClawbackTooEarly { deadline: u64 },
+
+    #[error("Unclaimed rewards of epoch {epoch} were already clawed back")]
+    AlreadyClawedBack { epoch: u64 },
+
+    #[error("Claim exceeds the funds left in epoch {epoch}")]
+    InsufficientFunds { epoch: u64 },
+}
@@ -0,0 +1,58 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Binary, Uint128};
+
+use crate::state::{Config, Epoch};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Defaults to the sender
+    pub owner: Option<String>,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Opens a new epoch funded with the single coin sent along. Owner only.
+    RegisterEpoch {
+        merkle_root: Binary,
+        deadline: u64,
+    },
+    /// Pays the sender `amount` if `proof` links sha256(sender + amount) to the epoch's root.
+    Claim {
+        epoch: u64,
+        amount: Uint128,
+        proof: Vec<Binary>,
+    },
+    /// Sends what is left of an epoch past its deadline to `recipient`, or the owner.
+    /// Owner only.
+    Clawback {
+        epoch: u64,
+        recipient: Option<String>,
+    },
+    UpdateOwner {
+        owner: String,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(Config)]
+    Config {},
+    #[returns(Epoch)]
+    Epoch { id: u64 },
+    #[returns(LatestEpochResponse)]
+    LatestEpoch {},
+    #[returns(IsClaimedResponse)]
+    IsClaimed { epoch: u64, address: String },
+}
+
+#[cw_serde]
+pub struct LatestEpochResponse {
+    /// None until the first epoch is registered
+    pub epoch: Option<Epoch>,
+}
+
+#[cw_serde]
+pub struct IsClaimedResponse {
+    pub claimed: bool,
+}
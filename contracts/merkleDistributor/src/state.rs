@@ -0,0 +1,27 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Binary, Uint128};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    /// Registers epochs and claws back unclaimed rewards, usually a DAO or another contract
+    pub owner: Addr,
+}
+
+#[cw_serde]
+pub struct Epoch {
+    pub id: u64,
+    /// Root of a sorted-pair sha256 tree whose leaves are sha256(address + amount)
+    pub merkle_root: Binary,
+    pub denom: String,
+    pub total_amount: Uint128,
+    pub claimed_amount: Uint128,
+    /// Block time in seconds after which claims close and the rest can be clawed back
+    pub deadline: u64,
+    pub clawed_back: bool,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const LATEST_EPOCH: Item<u64> = Item::new("latest_epoch");
+pub const EPOCHS: Map<u64, Epoch> = Map::new("epochs");
+pub const CLAIMED: Map<(u64, &Addr), bool> = Map::new("claimed");
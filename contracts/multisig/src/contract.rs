@@ -0,0 +1,702 @@
+use std::cmp::Ordering;
+
+use coreum_wasm_sdk::core::{CoreumMsg, CoreumResult};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Binary, BlockInfo, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdResult,
+};
+
+use cw2::set_contract_version;
+use cw3::{
+    Ballot, Proposal, ProposalListResponse, ProposalResponse, Status, Vote, VoteInfo,
+    VoteListResponse, VoteResponse, VoterDetail, VoterListResponse, VoterResponse, Votes,
+};
+use cw_storage_plus::Bound;
+use cw_utils::{Expiration, ThresholdResponse};
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::state::{next_id, Config, BALLOTS, CONFIG, PROPOSALS, PROPOSAL_MSGS, VOTERS};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:coreum-multisig";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> CoreumResult<ContractError> {
+    if msg.voters.is_empty() {
+        return Err(ContractError::NoVoters {});
+    }
+    let total_weight = msg.voters.iter().map(|v| v.weight).sum();
+
+    msg.threshold.validate(total_weight)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let cfg = Config {
+        threshold: msg.threshold,
+        total_weight,
+        max_voting_period: msg.max_voting_period,
+    };
+    CONFIG.save(deps.storage, &cfg)?;
+
+    // add all voters
+    for voter in msg.voters.iter() {
+        let key = deps.api.addr_validate(&voter.addr)?;
+        VOTERS.save(deps.storage, &key, &voter.weight)?;
+    }
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> CoreumResult<ContractError> {
+    match msg {
+        ExecuteMsg::Propose {
+            title,
+            description,
+            msgs,
+            latest,
+        } => execute_propose(deps, env, info, title, description, msgs, latest),
+        ExecuteMsg::Vote { proposal_id, vote } => execute_vote(deps, env, info, proposal_id, vote),
+        ExecuteMsg::Execute { proposal_id } => execute_execute(deps, env, info, proposal_id),
+        ExecuteMsg::Close { proposal_id } => execute_close(deps, env, info, proposal_id),
+    }
+}
+
+pub fn execute_propose(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    title: String,
+    description: String,
+    msgs: Vec<CosmosMsg<CoreumMsg>>,
+    // we ignore earliest
+    latest: Option<Expiration>,
+) -> CoreumResult<ContractError> {
+    // only members of the multisig can create a proposal
+    let vote_power = VOTERS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::Unauthorized {})?;
+
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // max expires also used as default
+    let max_expires = cfg.max_voting_period.after(&env.block);
+    let mut expires = latest.unwrap_or(max_expires);
+    let comp = expires.partial_cmp(&max_expires);
+    if let Some(Ordering::Greater) = comp {
+        expires = max_expires;
+    } else if comp.is_none() {
+        return Err(ContractError::WrongExpiration {});
+    }
+
+    // create a proposal
+    let mut prop = Proposal {
+        title,
+        description,
+        start_height: env.block.height,
+        expires,
+        msgs: vec![],
+        status: Status::Open,
+        votes: Votes::yes(vote_power),
+        threshold: cfg.threshold,
+        total_weight: cfg.total_weight,
+        proposer: info.sender.clone(),
+        deposit: None,
+    };
+    prop.update_status(&env.block);
+    let id = next_id(deps.storage)?;
+    PROPOSALS.save(deps.storage, id, &prop)?;
+    PROPOSAL_MSGS.save(deps.storage, id, &msgs)?;
+
+    // add the first yes vote from voter
+    let ballot = Ballot {
+        weight: vote_power,
+        vote: Vote::Yes,
+    };
+    BALLOTS.save(deps.storage, (id, &info.sender), &ballot)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "propose")
+        .add_attribute("sender", info.sender)
+        .add_attribute("proposal_id", id.to_string())
+        .add_attribute("status", format!("{:?}", prop.status)))
+}
+
+pub fn execute_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    vote: Vote,
+) -> CoreumResult<ContractError> {
+    // only members of the multisig with weight >= 1 can vote
+    let voter_power = VOTERS.may_load(deps.storage, &info.sender)?;
+    let vote_power = match voter_power {
+        Some(power) if power >= 1 => power,
+        _ => return Err(ContractError::Unauthorized {}),
+    };
+
+    // ensure proposal exists and can be voted on
+    let mut prop = PROPOSALS.load(deps.storage, proposal_id)?;
+    // Allow voting on Passed and Rejected proposals too,
+    if ![Status::Open, Status::Passed, Status::Rejected].contains(&prop.status) {
+        return Err(ContractError::NotOpen {});
+    }
+    // if they are not expired
+    if prop.expires.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+
+    // cast vote if no vote previously cast
+    BALLOTS.update(deps.storage, (proposal_id, &info.sender), |bal| match bal {
+        Some(_) => Err(ContractError::AlreadyVoted {}),
+        None => Ok(Ballot {
+            weight: vote_power,
+            vote,
+        }),
+    })?;
+
+    // update vote tally
+    prop.votes.add_vote(vote, vote_power);
+    prop.update_status(&env.block);
+    PROPOSALS.save(deps.storage, proposal_id, &prop)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "vote")
+        .add_attribute("sender", info.sender)
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("status", format!("{:?}", prop.status)))
+}
+
+pub fn execute_execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> CoreumResult<ContractError> {
+    // anyone can trigger this if the vote passed
+
+    let mut prop = PROPOSALS.load(deps.storage, proposal_id)?;
+    // we allow execution even after the proposal "expiration" as long as all vote come in before
+    // that point. If it was approved on time, it can be executed any time.
+    prop.update_status(&env.block);
+    if prop.status != Status::Passed {
+        return Err(ContractError::WrongExecuteStatus {});
+    }
+
+    // set it to executed
+    prop.status = Status::Executed;
+    PROPOSALS.save(deps.storage, proposal_id, &prop)?;
+
+    // dispatch all proposed messages
+    Ok(Response::new()
+        .add_messages(PROPOSAL_MSGS.load(deps.storage, proposal_id)?)
+        .add_attribute("action", "execute")
+        .add_attribute("sender", info.sender)
+        .add_attribute("proposal_id", proposal_id.to_string()))
+}
+
+pub fn execute_close(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> CoreumResult<ContractError> {
+    // anyone can trigger this if the vote passed
+
+    let mut prop = PROPOSALS.load(deps.storage, proposal_id)?;
+    if [Status::Executed, Status::Rejected, Status::Passed].contains(&prop.status) {
+        return Err(ContractError::WrongCloseStatus {});
+    }
+    // Avoid closing of Passed due to expiration proposals
+    if prop.current_status(&env.block) == Status::Passed {
+        return Err(ContractError::WrongCloseStatus {});
+    }
+    if !prop.expires.is_expired(&env.block) {
+        return Err(ContractError::NotExpired {});
+    }
+
+    // set it to failed
+    prop.status = Status::Rejected;
+    PROPOSALS.save(deps.storage, proposal_id, &prop)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "close")
+        .add_attribute("sender", info.sender)
+        .add_attribute("proposal_id", proposal_id.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Threshold {} => to_json_binary(&query_threshold(deps)?),
+        QueryMsg::Proposal { proposal_id } => {
+            to_json_binary(&query_proposal(deps, env, proposal_id)?)
+        }
+        QueryMsg::Vote { proposal_id, voter } => {
+            to_json_binary(&query_vote(deps, proposal_id, voter)?)
+        }
+        QueryMsg::ListProposals { start_after, limit } => {
+            to_json_binary(&list_proposals(deps, env, start_after, limit)?)
+        }
+        QueryMsg::ReverseProposals {
+            start_before,
+            limit,
+        } => to_json_binary(&reverse_proposals(deps, env, start_before, limit)?),
+        QueryMsg::ListVotes {
+            proposal_id,
+            start_after,
+            limit,
+        } => to_json_binary(&list_votes(deps, proposal_id, start_after, limit)?),
+        QueryMsg::Voter { address } => to_json_binary(&query_voter(deps, address)?),
+        QueryMsg::ListVoters { start_after, limit } => {
+            to_json_binary(&list_voters(deps, start_after, limit)?)
+        }
+    }
+}
+
+fn query_threshold(deps: Deps) -> StdResult<ThresholdResponse> {
+    let cfg = CONFIG.load(deps.storage)?;
+    Ok(cfg.threshold.to_response(cfg.total_weight))
+}
+
+fn query_proposal(deps: Deps, env: Env, id: u64) -> StdResult<ProposalResponse<CoreumMsg>> {
+    let prop = PROPOSALS.load(deps.storage, id)?;
+    let status = prop.current_status(&env.block);
+    let threshold = prop.threshold.to_response(prop.total_weight);
+    Ok(ProposalResponse {
+        id,
+        title: prop.title,
+        description: prop.description,
+        msgs: PROPOSAL_MSGS.load(deps.storage, id)?,
+        status,
+        expires: prop.expires,
+        deposit: None,
+        proposer: prop.proposer,
+        threshold,
+    })
+}
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+fn list_proposals(
+    deps: Deps,
+    env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProposalListResponse<CoreumMsg>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let proposals = PROPOSALS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|p| map_proposal(deps, &env.block, p))
+        .collect::<StdResult<_>>()?;
+
+    Ok(ProposalListResponse { proposals })
+}
+
+fn reverse_proposals(
+    deps: Deps,
+    env: Env,
+    start_before: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProposalListResponse<CoreumMsg>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let end = start_before.map(Bound::exclusive);
+    let props: StdResult<Vec<_>> = PROPOSALS
+        .range(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .map(|p| map_proposal(deps, &env.block, p))
+        .collect();
+
+    Ok(ProposalListResponse { proposals: props? })
+}
+
+fn map_proposal(
+    deps: Deps,
+    block: &BlockInfo,
+    item: StdResult<(u64, Proposal)>,
+) -> StdResult<ProposalResponse<CoreumMsg>> {
+    let (id, prop) = item?;
+    let status = prop.current_status(block);
+    let threshold = prop.threshold.to_response(prop.total_weight);
+    Ok(ProposalResponse {
+        id,
+        title: prop.title,
+        description: prop.description,
+        msgs: PROPOSAL_MSGS.load(deps.storage, id)?,
+        status,
+        deposit: None,
+        proposer: prop.proposer,
+        expires: prop.expires,
+        threshold,
+    })
+}
+
+fn query_vote(deps: Deps, proposal_id: u64, voter: String) -> StdResult<VoteResponse> {
+    let voter = deps.api.addr_validate(&voter)?;
+    let ballot = BALLOTS.may_load(deps.storage, (proposal_id, &voter))?;
+    let vote = ballot.map(|b| VoteInfo {
+        proposal_id,
+        voter: voter.into(),
+        vote: b.vote,
+        weight: b.weight,
+    });
+    Ok(VoteResponse { vote })
+}
+
+fn list_votes(
+    deps: Deps,
+    proposal_id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<VoteListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let votes = BALLOTS
+        .prefix(proposal_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(addr, ballot)| VoteInfo {
+                proposal_id,
+                voter: addr.into(),
+                vote: ballot.vote,
+                weight: ballot.weight,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(VoteListResponse { votes })
+}
+
+fn query_voter(deps: Deps, voter: String) -> StdResult<VoterResponse> {
+    let voter = deps.api.addr_validate(&voter)?;
+    let weight = VOTERS.may_load(deps.storage, &voter)?;
+    Ok(VoterResponse { weight })
+}
+
+fn list_voters(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<VoterListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let voters = VOTERS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(addr, weight)| VoterDetail {
+                addr: addr.into(),
+                weight,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(VoterListResponse { voters })
+}
+
+#[cfg(test)]
+mod tests {
+    use coreum_wasm_sdk::assetft;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coin, from_json, BankMsg, Decimal};
+
+    use cw2::{get_contract_version, ContractVersion};
+    use cw_utils::{Duration, Threshold};
+
+    use crate::msg::Voter;
+
+    use super::*;
+
+    fn mock_env_height(height_delta: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height += height_delta;
+        env
+    }
+
+    fn mock_env_time(time_delta: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(time_delta);
+        env
+    }
+
+    const OWNER: &str = "admin0001";
+    const VOTER1: &str = "voter0001";
+    const VOTER2: &str = "voter0002";
+    const VOTER3: &str = "voter0003";
+    const VOTER4: &str = "voter0004";
+    const SOMEBODY: &str = "somebody";
+
+    fn voter<T: Into<String>>(addr: T, weight: u64) -> Voter {
+        Voter {
+            addr: addr.into(),
+            weight,
+        }
+    }
+
+    // this will set up the instantiation for other tests
+    #[track_caller]
+    fn setup_test_case(
+        deps: DepsMut,
+        info: MessageInfo,
+        threshold: Threshold,
+        max_voting_period: Duration,
+    ) -> CoreumResult<ContractError> {
+        let voters = vec![
+            voter(&info.sender, 1),
+            voter(VOTER1, 1),
+            voter(VOTER2, 2),
+            voter(VOTER3, 3),
+            voter(VOTER4, 4),
+        ];
+
+        let instantiate_msg = InstantiateMsg {
+            voters,
+            threshold,
+            max_voting_period,
+        };
+        instantiate(deps, mock_env(), info, instantiate_msg)
+    }
+
+    // a treasury batch: mint a smart token and pay it out
+    fn treasury_msgs() -> Vec<CosmosMsg<CoreumMsg>> {
+        vec![
+            CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Mint {
+                coin: coin(100, "utkn-admin0001"),
+                recipient: None,
+            })),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: SOMEBODY.into(),
+                amount: vec![coin(100, "utkn-admin0001")],
+            }),
+        ]
+    }
+
+    fn propose(deps: DepsMut, sender: &str, latest: Option<Expiration>) -> u64 {
+        let proposal = ExecuteMsg::Propose {
+            title: "Pay somebody".to_string(),
+            description: "Mint and pay the grant".to_string(),
+            msgs: treasury_msgs(),
+            latest,
+        };
+        let res = execute(deps, mock_env(), mock_info(sender, &[]), proposal).unwrap();
+        // Get the proposal id from the logs
+        res.attributes[2].value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_instantiate_works() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(OWNER, &[]);
+
+        let max_voting_period = Duration::Time(1234567);
+
+        // No voters fails
+        let instantiate_msg = InstantiateMsg {
+            voters: vec![],
+            threshold: Threshold::ThresholdQuorum {
+                threshold: Decimal::zero(),
+                quorum: Decimal::percent(1),
+            },
+            max_voting_period,
+        };
+        let err =
+            instantiate(deps.as_mut(), mock_env(), info.clone(), instantiate_msg).unwrap_err();
+        assert_eq!(err, ContractError::NoVoters {});
+
+        // Total weight less than required weight not allowed
+        let threshold = Threshold::AbsoluteCount { weight: 100 };
+        let err =
+            setup_test_case(deps.as_mut(), info.clone(), threshold, max_voting_period).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Threshold(cw_utils::ThresholdError::UnreachableWeight {})
+        );
+
+        // All valid
+        let threshold = Threshold::AbsoluteCount { weight: 1 };
+        setup_test_case(deps.as_mut(), info, threshold, max_voting_period).unwrap();
+
+        // Verify
+        assert_eq!(
+            ContractVersion {
+                contract: CONTRACT_NAME.to_string(),
+                version: CONTRACT_VERSION.to_string(),
+            },
+            get_contract_version(&deps.storage).unwrap()
+        )
+    }
+
+    #[test]
+    fn test_execute_works() {
+        let mut deps = mock_dependencies();
+
+        let threshold = Threshold::AbsoluteCount { weight: 3 };
+        let voting_period = Duration::Time(2000000);
+
+        let info = mock_info(OWNER, &[]);
+        setup_test_case(deps.as_mut(), info.clone(), threshold, voting_period).unwrap();
+
+        // Only voters can propose
+        let proposal = ExecuteMsg::Propose {
+            title: "Pay somebody".to_string(),
+            description: "Mint and pay the grant".to_string(),
+            msgs: treasury_msgs(),
+            latest: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(SOMEBODY, &[]),
+            proposal,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let proposal_id = propose(deps.as_mut(), OWNER, None);
+
+        // Only Passed can be executed
+        let execution = ExecuteMsg::Execute { proposal_id };
+        let err = execute(deps.as_mut(), mock_env(), info, execution.clone()).unwrap_err();
+        assert_eq!(err, ContractError::WrongExecuteStatus {});
+
+        // Vote it, so it passes
+        let vote = ExecuteMsg::Vote {
+            proposal_id,
+            vote: Vote::Yes,
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(VOTER3, &[]),
+            vote.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[3].value, "Passed");
+
+        // Cannot vote twice
+        let err = execute(deps.as_mut(), mock_env(), mock_info(VOTER3, &[]), vote).unwrap_err();
+        assert_eq!(err, ContractError::AlreadyVoted {});
+
+        // Execute works. Anybody can execute Passed proposals, the Coreum messages go out as is
+        let info = mock_info(SOMEBODY, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), execution).unwrap();
+        assert_eq!(
+            res,
+            Response::new()
+                .add_messages(treasury_msgs())
+                .add_attribute("action", "execute")
+                .add_attribute("sender", SOMEBODY)
+                .add_attribute("proposal_id", proposal_id.to_string())
+        );
+
+        // In passing: Try to close Executed fails
+        let closing = ExecuteMsg::Close { proposal_id };
+        let err = execute(deps.as_mut(), mock_env(), info, closing).unwrap_err();
+        assert_eq!(err, ContractError::WrongCloseStatus {});
+    }
+
+    #[test]
+    fn proposal_pass_on_expiration() {
+        let mut deps = mock_dependencies();
+
+        let threshold = Threshold::ThresholdQuorum {
+            threshold: Decimal::percent(51),
+            quorum: Decimal::percent(1),
+        };
+        let voting_period = Duration::Time(2000000);
+
+        let info = mock_info(OWNER, &[]);
+        setup_test_case(deps.as_mut(), info, threshold, voting_period).unwrap();
+        let proposal_id = propose(deps.as_mut(), OWNER, None);
+
+        // Vote it, so it passes after voting period is over
+        let vote = ExecuteMsg::Vote {
+            proposal_id,
+            vote: Vote::Yes,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info(VOTER3, &[]), vote).unwrap();
+        assert_eq!(res.attributes[3].value, "Open");
+
+        // Wait until the voting period is over
+        let env = mock_env_time(2000001);
+
+        // Proposal should now be passed
+        let prop: ProposalResponse<CoreumMsg> = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::Proposal { proposal_id },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(prop.status, Status::Passed);
+        assert_eq!(prop.msgs, treasury_msgs());
+
+        // Execution should now be possible
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(SOMEBODY, &[]),
+            ExecuteMsg::Execute { proposal_id },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 2);
+    }
+
+    #[test]
+    fn test_close_works() {
+        let mut deps = mock_dependencies();
+
+        let threshold = Threshold::AbsoluteCount { weight: 3 };
+        let voting_period = Duration::Height(2000000);
+
+        let info = mock_info(OWNER, &[]);
+        setup_test_case(deps.as_mut(), info, threshold, voting_period).unwrap();
+
+        // Non-expired proposals cannot be closed
+        let proposal_id = propose(deps.as_mut(), OWNER, None);
+        let closing = ExecuteMsg::Close { proposal_id };
+        let err =
+            execute(deps.as_mut(), mock_env(), mock_info(SOMEBODY, &[]), closing).unwrap_err();
+        assert_eq!(err, ContractError::NotExpired {});
+
+        // Expired proposals can be closed
+        let proposal_id = propose(deps.as_mut(), OWNER, Some(Expiration::AtHeight(123456)));
+        let closing = ExecuteMsg::Close { proposal_id };
+        let env = mock_env_height(1234567);
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(SOMEBODY, &[]),
+            closing.clone(),
+        )
+        .unwrap();
+
+        // Trying to close it again fails
+        let err =
+            execute(deps.as_mut(), mock_env(), mock_info(SOMEBODY, &[]), closing).unwrap_err();
+        assert_eq!(err, ContractError::WrongCloseStatus {});
+    }
+}
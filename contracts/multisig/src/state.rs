@@ -0,0 +1,33 @@
+use coreum_wasm_sdk::core::CoreumMsg;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, CosmosMsg, StdResult, Storage};
+
+use cw3::{Ballot, Proposal};
+use cw_storage_plus::{Item, Map};
+use cw_utils::{Duration, Threshold};
+
+#[cw_serde]
+pub struct Config {
+    pub threshold: Threshold,
+    pub total_weight: u64,
+    pub max_voting_period: Duration,
+}
+
+// unique items
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const PROPOSAL_COUNT: Item<u64> = Item::new("proposal_count");
+
+// multiple-item map
+pub const BALLOTS: Map<(u64, &Addr), Ballot> = Map::new("votes");
+pub const PROPOSALS: Map<u64, Proposal> = Map::new("proposals");
+// cw3's `Proposal` only holds `CosmosMsg<Empty>`, so the Coreum messages are kept beside it
+pub const PROPOSAL_MSGS: Map<u64, Vec<CosmosMsg<CoreumMsg>>> = Map::new("proposal_msgs");
+
+// multiple-item maps
+pub const VOTERS: Map<&Addr, u64> = Map::new("voters");
+
+pub fn next_id(store: &mut dyn Storage) -> StdResult<u64> {
+    let id: u64 = PROPOSAL_COUNT.may_load(store)?.unwrap_or_default() + 1;
+    PROPOSAL_COUNT.save(store, &id)?;
+    Ok(id)
+}
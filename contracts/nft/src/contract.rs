@@ -54,21 +54,38 @@ use coreum_wasm_sdk::types::coreum::asset::nft::v1::{
     MsgUpdateData,
 };
 use cosmwasm_std::{
-    entry_point, to_json_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, QueryRequest,
-    Response, StdResult,
+    attr, entry_point, from_json, to_json_binary, to_json_vec, Api, Attribute, Binary, CanonicalAddr, CosmosMsg, Deps,
+    DepsMut, Env, MessageInfo, Order, QuerierWrapper, QueryRequest, Response, StdResult, Storage,
 };
 use cw2::set_contract_version;
 use cw_ownable::{assert_owner, initialize_owner};
+use cw_storage_plus::Bound;
+use prost::Message;
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::CLASS_ID;
+use crate::msg::{
+    ExecuteMsg, IbcEscrowedNft, IbcEscrowedNftsResponse, InstantiateMsg, QueryMsg, RoyaltySplit,
+    RoyaltySplitsResponse, SimulateTransferResponse, TokenMetadataResponse, WhitelistEntry,
+};
+use crate::nft_transfer::{self, MsgTransfer};
+use crate::state::{
+    IbcNft, IbcNftStatus, RoyaltyRecipient, TokenMetadata, TokenUri, CACHE_METADATA, CLASS_ID,
+    IBC_NFTS, ROYALTY_SPLITS, TOKEN_METADATA,
+};
 // version info for migration info
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+// class features that restrict transfers
+const WHITELISTING: u32 = 2;
+const DISABLE_SENDING: u32 = 3;
+// index of the TokenUri item in the dynamic data of mutable tokens
+const URI_ITEM: u32 = 1;
+const TOTAL_BPS: u32 = 10_000;
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
 // ********** Instantiate **********
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
@@ -87,6 +104,9 @@ pub fn instantiate(
     });
     let class_id = format!("{}-{}", msg.symbol, env.contract.address).to_lowercase();
     CLASS_ID.save(deps.storage, &class_id)?;
+    CACHE_METADATA.save(deps.storage, &msg.cache_metadata.unwrap_or(false))?;
+    let royalty_splits = validate_royalty_splits(deps.api, msg.royalty_splits.unwrap_or_default())?;
+    ROYALTY_SPLITS.save(deps.storage, &royalty_splits)?;
     Ok(Response::new()
         .add_attribute("owner", info.sender)
         .add_attribute("class_id", class_id)
@@ -95,7 +115,7 @@ pub fn instantiate(
 // ********** Execute **********
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
@@ -124,8 +144,10 @@ pub fn execute(
         } => mint_mutable(deps, info, env, id, uri, uri_hash, data, recipient),
         ExecuteMsg::Burn { id } => burn(deps, info, id),
         ExecuteMsg::Freeze { id } => freeze(deps, info, id),
+        ExecuteMsg::BatchFreeze { ids } => batch_freeze(deps, info, ids),
         ExecuteMsg::Unfreeze { id } => unfreeze(deps, info, id),
         ExecuteMsg::AddToWhitelist { id, account } => add_to_white_list(deps, info, id, account),
+        ExecuteMsg::BatchAddToWhitelist { entries } => batch_add_to_white_list(deps, info, entries),
         ExecuteMsg::RemoveFromWhitelist { id, account } => {
             remove_from_white_list(deps, info, id, account)
         }
@@ -137,11 +159,23 @@ pub fn execute(
             remove_from_class_whitelist(deps, info, account)
         }
         ExecuteMsg::ModifyData { id, data } => modify_data(deps, info, env, id, data),
+        ExecuteMsg::SetTokenUri { id, uri, uri_hash } => {
+            set_token_uri(deps, info, env, id, uri, uri_hash)
+        }
+        ExecuteMsg::RefreshTokenMetadata { id } => refresh_token_metadata(deps, id),
+        ExecuteMsg::SetRoyaltySplits { splits } => set_royalty_splits(deps, info, splits),
+        ExecuteMsg::IbcSend {
+            id,
+            channel,
+            receiver,
+            timeout,
+        } => ibc_send(deps, info, env, id, channel, receiver, timeout),
+        ExecuteMsg::SyncIbcNft { id } => sync_ibc_nft(deps, env, id),
     }
 }
 // ********** Transactions **********
 fn mint_legacy(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     info: MessageInfo,
     id: String,
     uri: Option<String>,
@@ -151,6 +185,8 @@ fn mint_legacy(
 ) -> CoreumResult<ContractError> {
     assert_owner(deps.storage, &info.sender)?;
     let class_id = CLASS_ID.load(deps.storage)?;
+    let royalty_splits = royalty_splits_attribute(deps.storage)?;
+    cache_metadata(deps, &id, uri.clone(), uri_hash.clone(), false)?;
     let msg = CoreumMsg::AssetNFT(assetnft::Msg::Mint {
         class_id: class_id.clone(),
         id: id.clone(),
@@ -163,10 +199,11 @@ fn mint_legacy(
         .add_attribute("method", "mint_legacy")
         .add_attribute("class_id", class_id)
         .add_attribute("id", id)
+        .add_attributes(royalty_splits)
         .add_message(msg))
 }
 fn mint_immutable(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     info: MessageInfo,
     env: Env,
     id: String,
@@ -177,6 +214,7 @@ fn mint_immutable(
 ) -> CoreumResult<ContractError> {
     assert_owner(deps.storage, &info.sender)?;
     let class_id = CLASS_ID.load(deps.storage)?;
+    let royalty_splits = royalty_splits_attribute(deps.storage)?;
     let data = match data {
         Some(data) => Some(
             DataBytes {
@@ -186,6 +224,7 @@ fn mint_immutable(
         ),
         None => None,
     };
+    cache_metadata(deps, &id, uri.clone(), uri_hash.clone(), false)?;
     let mint = MsgMint {
         sender: env.contract.address.to_string(),
         class_id: class_id.clone(),
@@ -204,10 +243,11 @@ fn mint_immutable(
         .add_attribute("method", "mint_immutable")
         .add_attribute("class_id", class_id)
         .add_attribute("id", id)
+        .add_attributes(royalty_splits)
         .add_message(msg))
 }
 fn mint_mutable(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     info: MessageInfo,
     env: Env,
     id: String,
@@ -218,26 +258,34 @@ fn mint_mutable(
 ) -> CoreumResult<ContractError> {
     assert_owner(deps.storage, &info.sender)?;
     let class_id = CLASS_ID.load(deps.storage)?;
-    let data = match data {
-        Some(data) => Some(
-            DataDynamic {
-                items: [DataDynamicItem {
-                    editors: [DataEditor::Admin as i32, DataEditor::Owner as i32].to_vec(),
-                    data: data.to_vec(),
-                }]
-                .to_vec(),
-            }
-            .to_any(),
-        ),
-        None => None,
+    let royalty_splits = royalty_splits_attribute(deps.storage)?;
+    let token_uri = TokenUri {
+        uri: uri.clone(),
+        uri_hash: uri_hash.clone(),
     };
+    let data = DataDynamic {
+        items: [
+            DataDynamicItem {
+                editors: [DataEditor::Admin as i32, DataEditor::Owner as i32].to_vec(),
+                data: data.map(|data| data.to_vec()).unwrap_or_default(),
+            },
+            // only the contract, as the class admin, can change the uri
+            DataDynamicItem {
+                editors: [DataEditor::Admin as i32].to_vec(),
+                data: to_json_vec(&token_uri)?,
+            },
+        ]
+        .to_vec(),
+    }
+    .to_any();
+    cache_metadata(deps, &id, uri.clone(), uri_hash.clone(), true)?;
     let mint = MsgMint {
         sender: env.contract.address.to_string(),
         class_id: class_id.clone(),
         id: id.clone(),
         uri: uri.unwrap_or_default(),
         uri_hash: uri_hash.unwrap_or_default(),
-        data,
+        data: Some(data),
         recipient: recipient.unwrap_or_default(),
     };
     let mint_bytes = mint.to_proto_bytes();
@@ -249,10 +297,127 @@ fn mint_mutable(
         .add_attribute("method", "mint_mutable")
         .add_attribute("class_id", class_id)
         .add_attribute("id", id)
+        .add_attributes(royalty_splits)
         .add_message(msg))
 }
+fn cache_metadata(
+    deps: DepsMut<CoreumQueries>,
+    id: &str,
+    uri: Option<String>,
+    uri_hash: Option<String>,
+    mutable: bool,
+) -> StdResult<()> {
+    if CACHE_METADATA.may_load(deps.storage)?.unwrap_or(false) {
+        let metadata = TokenMetadata {
+            uri,
+            uri_hash,
+            mutable,
+        };
+        TOKEN_METADATA.save(deps.storage, id, &metadata)?;
+    }
+    Ok(())
+}
+fn set_royalty_splits(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    splits: Vec<RoyaltySplit>,
+) -> CoreumResult<ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let royalty_splits = validate_royalty_splits(deps.api, splits)?;
+    ROYALTY_SPLITS.save(deps.storage, &royalty_splits)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_royalty_splits")
+        .add_attributes(royalty_splits_attribute(deps.storage)?))
+}
+fn validate_royalty_splits(
+    api: &dyn Api,
+    splits: Vec<RoyaltySplit>,
+) -> Result<Vec<RoyaltyRecipient>, ContractError> {
+    if splits.is_empty() {
+        return Ok(vec![]);
+    }
+    let total: u32 = splits.iter().map(|split| u32::from(split.bps)).sum();
+    if total != TOTAL_BPS || splits.iter().any(|split| split.bps == 0) {
+        return Err(ContractError::InvalidRoyaltySplits {});
+    }
+    splits
+        .into_iter()
+        .map(|split| {
+            Ok(RoyaltyRecipient {
+                address: api.addr_validate(&split.address)?,
+                bps: split.bps,
+            })
+        })
+        .collect()
+}
+// "address:bps" pairs marketplaces split the royalty by, nothing when no registry is set
+fn royalty_splits_attribute(storage: &dyn Storage) -> StdResult<Option<Attribute>> {
+    let splits = ROYALTY_SPLITS.may_load(storage)?.unwrap_or_default();
+    if splits.is_empty() {
+        return Ok(None);
+    }
+    let value = splits
+        .iter()
+        .map(|split| format!("{}:{}", split.address, split.bps))
+        .collect::<Vec<_>>()
+        .join(",");
+    Ok(Some(attr("royalty_splits", value)))
+}
+fn set_token_uri(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    env: Env,
+    id: String,
+    uri: Option<String>,
+    uri_hash: Option<String>,
+) -> CoreumResult<ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    // asked from the chain, tokens minted mutable before the uri item existed can't be updated
+    let (_, mutable) = chain_token_uri(deps.as_ref(), &id)?;
+    if !mutable {
+        return Err(ContractError::Immutable { id });
+    }
+    let class_id = CLASS_ID.load(deps.storage)?;
+    let token_uri = TokenUri {
+        uri: uri.clone(),
+        uri_hash: uri_hash.clone(),
+    };
+    let update_data = MsgUpdateData {
+        sender: env.contract.address.to_string(),
+        class_id: class_id.clone(),
+        id: id.clone(),
+        items: [DataDynamicIndexedItem {
+            index: URI_ITEM,
+            data: to_json_vec(&token_uri)?,
+        }]
+        .to_vec(),
+    };
+    let update_data_bytes = update_data.to_proto_bytes();
+    let msg = CosmosMsg::Stargate {
+        type_url: update_data.to_any().type_url,
+        value: Binary::from(update_data_bytes),
+    };
+    cache_metadata(deps, &id, uri, uri_hash, true)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_token_uri")
+        .add_attribute("class_id", class_id)
+        .add_attribute("id", id)
+        .add_message(msg))
+}
+// rewrites the cached metadata of a token from the chain, dropping it once burnt, callable by anyone
+fn refresh_token_metadata(deps: DepsMut<CoreumQueries>, id: String) -> CoreumResult<ContractError> {
+    if query_burnt_nft(deps.as_ref(), id.clone())?.burnt {
+        TOKEN_METADATA.remove(deps.storage, &id);
+    } else {
+        let (token_uri, mutable) = chain_token_uri(deps.as_ref(), &id)?;
+        cache_metadata(deps, &id, token_uri.uri, token_uri.uri_hash, mutable)?;
+    }
+    Ok(Response::new()
+        .add_attribute("method", "refresh_token_metadata")
+        .add_attribute("id", id))
+}
 fn modify_data(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     info: MessageInfo,
     env: Env,
     id: String,
@@ -281,20 +446,21 @@ fn modify_data(
         .add_attribute("id", id)
         .add_message(msg))
 }
-fn burn(deps: DepsMut, info: MessageInfo, id: String) -> CoreumResult<ContractError> {
+fn burn(deps: DepsMut<CoreumQueries>, info: MessageInfo, id: String) -> CoreumResult<ContractError> {
     assert_owner(deps.storage, &info.sender)?;
     let class_id = CLASS_ID.load(deps.storage)?;
     let msg = CoreumMsg::AssetNFT(assetnft::Msg::Burn {
         class_id: class_id.clone(),
         id: id.clone(),
     });
+    TOKEN_METADATA.remove(deps.storage, &id);
     Ok(Response::new()
         .add_attribute("method", "burn")
         .add_attribute("class_id", class_id)
         .add_attribute("id", id)
         .add_message(msg))
 }
-fn freeze(deps: DepsMut, info: MessageInfo, id: String) -> CoreumResult<ContractError> {
+fn freeze(deps: DepsMut<CoreumQueries>, info: MessageInfo, id: String) -> CoreumResult<ContractError> {
     assert_owner(deps.storage, &info.sender)?;
     let class_id = CLASS_ID.load(deps.storage)?;
     let msg = CoreumMsg::AssetNFT(assetnft::Msg::Freeze {
@@ -307,7 +473,28 @@ fn freeze(deps: DepsMut, info: MessageInfo, id: String) -> CoreumResult<Contract
         .add_attribute("id", id)
         .add_message(msg))
 }
-fn unfreeze(deps: DepsMut, info: MessageInfo, id: String) -> CoreumResult<ContractError> {
+fn batch_freeze(deps: DepsMut<CoreumQueries>, info: MessageInfo, ids: Vec<String>) -> CoreumResult<ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    if ids.is_empty() {
+        return Err(ContractError::EmptyBatch {});
+    }
+    let class_id = CLASS_ID.load(deps.storage)?;
+    let msgs: Vec<CoreumMsg> = ids
+        .iter()
+        .map(|id| {
+            CoreumMsg::AssetNFT(assetnft::Msg::Freeze {
+                class_id: class_id.clone(),
+                id: id.clone(),
+            })
+        })
+        .collect();
+    Ok(Response::new()
+        .add_attribute("method", "batch_freeze")
+        .add_attribute("class_id", class_id)
+        .add_attribute("ids", ids.join(","))
+        .add_messages(msgs))
+}
+fn unfreeze(deps: DepsMut<CoreumQueries>, info: MessageInfo, id: String) -> CoreumResult<ContractError> {
     assert_owner(deps.storage, &info.sender)?;
     let class_id = CLASS_ID.load(deps.storage)?;
     let msg = CoreumMsg::AssetNFT(assetnft::Msg::Unfreeze {
@@ -321,7 +508,7 @@ fn unfreeze(deps: DepsMut, info: MessageInfo, id: String) -> CoreumResult<Contra
         .add_message(msg))
 }
 fn add_to_white_list(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     info: MessageInfo,
     id: String,
     account: String,
@@ -339,8 +526,34 @@ fn add_to_white_list(
         .add_attribute("id", id)
         .add_message(msg))
 }
+fn batch_add_to_white_list(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    entries: Vec<WhitelistEntry>,
+) -> CoreumResult<ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    if entries.is_empty() {
+        return Err(ContractError::EmptyBatch {});
+    }
+    let class_id = CLASS_ID.load(deps.storage)?;
+    let msgs: Vec<CoreumMsg> = entries
+        .iter()
+        .map(|entry| {
+            CoreumMsg::AssetNFT(assetnft::Msg::AddToWhitelist {
+                class_id: class_id.clone(),
+                id: entry.id.clone(),
+                account: entry.account.clone(),
+            })
+        })
+        .collect();
+    Ok(Response::new()
+        .add_attribute("method", "batch_add_to_white_list")
+        .add_attribute("class_id", class_id)
+        .add_attribute("count", entries.len().to_string())
+        .add_messages(msgs))
+}
 fn remove_from_white_list(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     info: MessageInfo,
     id: String,
     account: String,
@@ -359,7 +572,7 @@ fn remove_from_white_list(
         .add_message(msg))
 }
 fn send(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     info: MessageInfo,
     id: String,
     receiver: String,
@@ -377,7 +590,94 @@ fn send(
         .add_attribute("id", id)
         .add_message(msg))
 }
-fn class_freeze(deps: DepsMut, info: MessageInfo, account: String) -> CoreumResult<ContractError> {
+fn ibc_send(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    env: Env,
+    id: String,
+    channel: String,
+    receiver: String,
+    timeout: u64,
+) -> CoreumResult<ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    if timeout == 0 {
+        return Err(ContractError::InvalidTimeout {});
+    }
+    let class_id = CLASS_ID.load(deps.storage)?;
+    // the module escrows the token from the sender of the transfer, i.e. this contract
+    if nft_owner(&deps.querier, &class_id, &id)? != env.contract.address.as_str() {
+        return Err(ContractError::NotHeldByContract { id });
+    }
+    let timeout = env.block.time.plus_seconds(timeout);
+    let ibc_nft = IbcNft {
+        channel: channel.clone(),
+        receiver: receiver.clone(),
+        timeout,
+        status: IbcNftStatus::InFlight,
+    };
+    IBC_NFTS.save(deps.storage, &id, &ibc_nft)?;
+    let transfer = MsgTransfer {
+        source_port: nft_transfer::PORT.to_string(),
+        source_channel: channel.clone(),
+        class_id: class_id.clone(),
+        token_ids: vec![id.clone()],
+        sender: env.contract.address.to_string(),
+        receiver: receiver.clone(),
+        timeout_timestamp: timeout.nanos(),
+        memo: String::new(),
+    };
+    let msg = CosmosMsg::Stargate {
+        type_url: nft_transfer::MSG_TRANSFER_TYPE_URL.to_string(),
+        value: Binary::from(transfer.encode_to_vec()),
+    };
+    Ok(Response::new()
+        .add_attribute("method", "ibc_send")
+        .add_attribute("class_id", class_id)
+        .add_attribute("id", id)
+        .add_attribute("channel", channel)
+        .add_attribute("receiver", receiver)
+        .add_message(msg))
+}
+// the module reports acks and timeouts only to itself, so the outcome is read off the owner
+fn sync_ibc_nft(deps: DepsMut<CoreumQueries>, env: Env, id: String) -> CoreumResult<ContractError> {
+    let mut ibc_nft = IBC_NFTS
+        .may_load(deps.storage, &id)?
+        .ok_or_else(|| ContractError::NotSentOverIbc { id: id.clone() })?;
+    let class_id = CLASS_ID.load(deps.storage)?;
+    let owner = nft_owner(&deps.querier, &class_id, &id)?;
+    let escrow = CanonicalAddr::from(nft_transfer::escrow_address(&ibc_nft.channel));
+    let status = if owner != deps.api.addr_humanize(&escrow)?.as_str() {
+        // refunded after a timeout or a failed ack, or back from the other chain
+        IBC_NFTS.remove(deps.storage, &id);
+        "released"
+    } else if env.block.time > ibc_nft.timeout {
+        // a relayed timeout would have refunded it, a later one is caught by the next sync
+        ibc_nft.status = IbcNftStatus::Away;
+        IBC_NFTS.save(deps.storage, &id, &ibc_nft)?;
+        "away"
+    } else {
+        "in_flight"
+    };
+    Ok(Response::new()
+        .add_attribute("method", "sync_ibc_nft")
+        .add_attribute("class_id", class_id)
+        .add_attribute("id", id)
+        .add_attribute("status", status))
+}
+fn nft_owner(
+    querier: &QuerierWrapper<CoreumQueries>,
+    class_id: &str,
+    id: &str,
+) -> StdResult<String> {
+    let request: QueryRequest<CoreumQueries> = CoreumQueries::NFT(nft::Query::Owner {
+        class_id: class_id.to_string(),
+        id: id.to_string(),
+    })
+    .into();
+    let res: nft::OwnerResponse = querier.query(&request)?;
+    Ok(res.owner)
+}
+fn class_freeze(deps: DepsMut<CoreumQueries>, info: MessageInfo, account: String) -> CoreumResult<ContractError> {
     assert_owner(deps.storage, &info.sender)?;
     let class_id = CLASS_ID.load(deps.storage)?;
     let msg = CoreumMsg::AssetNFT(assetnft::Msg::ClassFreeze {
@@ -391,7 +691,7 @@ fn class_freeze(deps: DepsMut, info: MessageInfo, account: String) -> CoreumResu
         .add_message(msg))
 }
 fn class_unfreeze(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     info: MessageInfo,
     account: String,
 ) -> CoreumResult<ContractError> {
@@ -408,7 +708,7 @@ fn class_unfreeze(
         .add_message(msg))
 }
 fn add_to_class_whitelist(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     info: MessageInfo,
     account: String,
 ) -> CoreumResult<ContractError> {
@@ -425,7 +725,7 @@ fn add_to_class_whitelist(
         .add_message(msg))
 }
 fn remove_from_class_whitelist(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     info: MessageInfo,
     account: String,
 ) -> CoreumResult<ContractError> {
@@ -464,11 +764,19 @@ pub fn query(deps: Deps<CoreumQueries>, _env: Env, msg: QueryMsg) -> StdResult<B
         QueryMsg::ClassesNft {} => to_json_binary(&query_nft_classes(deps)?),
         QueryMsg::BurntNft { nft_id } => to_json_binary(&query_burnt_nft(deps, nft_id)?),
         QueryMsg::BurntNftsInClass {} => to_json_binary(&query_burnt_nfts_in_class(deps)?),
+        QueryMsg::TokenMetadata { id } => to_json_binary(&query_token_metadata(deps, id)?),
         QueryMsg::ClassFrozen { account } => to_json_binary(&query_class_frozen(deps, account)?),
         QueryMsg::ClassFrozenAccounts {} => to_json_binary(&query_class_frozen_accounts(deps)?),
         QueryMsg::ClassWhitelistedAccounts {} => {
             to_json_binary(&query_class_whitelisted_accounts(deps)?)
         }
+        QueryMsg::SimulateTransfer { id, from, to } => {
+            to_json_binary(&query_simulate_transfer(deps, id, from, to)?)
+        }
+        QueryMsg::RoyaltySplits {} => to_json_binary(&query_royalty_splits(deps)?),
+        QueryMsg::IbcEscrowedNfts { start_after, limit } => {
+            to_json_binary(&query_ibc_escrowed_nfts(deps, start_after, limit)?)
+        }
     }
 }
 fn query_params(deps: Deps<CoreumQueries>) -> StdResult<ParamsResponse> {
@@ -686,6 +994,71 @@ fn query_class_whitelisted_accounts(
     };
     Ok(res)
 }
+// mirrors the checks the chain runs on nft send, the issuer is exempt from all of them
+fn query_simulate_transfer(
+    deps: Deps<CoreumQueries>,
+    id: String,
+    from: String,
+    to: String,
+) -> StdResult<SimulateTransferResponse> {
+    let class = query_class(deps)?.class;
+    let features = class.features.unwrap_or_default();
+    let mut reasons = vec![];
+    let owner = query_owner(deps, id.clone())?.owner;
+    if owner != from {
+        reasons.push(format!("{} doesn't own {}", from, id));
+    }
+    if from != class.issuer {
+        if features.contains(&DISABLE_SENDING) {
+            reasons.push("sending is disabled for the class".to_string());
+        }
+        if query_frozen(deps, id.clone())?.frozen {
+            reasons.push(format!("{} is frozen", id));
+        }
+        if query_class_frozen(deps, from.clone())?.frozen {
+            reasons.push(format!("{} is frozen for the class", from));
+        }
+    }
+    if to != class.issuer && features.contains(&WHITELISTING) {
+        let whitelisted = query_whitelisted(deps, id.clone(), to.clone())?.whitelisted
+            || class_whitelisted(deps, &to)?;
+        if !whitelisted {
+            reasons.push(format!("{} is not whitelisted for {}", to, id));
+        }
+    }
+    Ok(SimulateTransferResponse {
+        allowed: reasons.is_empty(),
+        reasons,
+    })
+}
+// pages through the class whitelist until the account shows up
+fn class_whitelisted(deps: Deps<CoreumQueries>, account: &str) -> StdResult<bool> {
+    let class_id = CLASS_ID.load(deps.storage)?;
+    let mut pagination = None;
+    loop {
+        let request = CoreumQueries::AssetNFT(assetnft::Query::ClassWhitelistedAccounts {
+            pagination,
+            class_id: class_id.clone(),
+        })
+        .into();
+        let res: ClassWhitelistedAccountsResponse = deps.querier.query(&request)?;
+        if res.accounts.iter().any(|whitelisted| whitelisted == account) {
+            return Ok(true);
+        }
+        match res.pagination.next_key {
+            Some(key) => {
+                pagination = Some(PageRequest {
+                    key: Some(key),
+                    offset: None,
+                    limit: None,
+                    count_total: None,
+                    reverse: None,
+                })
+            }
+            None => return Ok(false),
+        }
+    }
+}
 // ********** NFT **********
 fn query_balance(deps: Deps<CoreumQueries>, owner: String) -> StdResult<nft::BalanceResponse> {
     let class_id = CLASS_ID.load(deps.storage)?;
@@ -715,6 +1088,80 @@ fn query_nft(deps: Deps<CoreumQueries>, id: String) -> StdResult<nft::NFTRespons
     let res = deps.querier.query(&request)?;
     Ok(res)
 }
+fn query_token_metadata(deps: Deps<CoreumQueries>, id: String) -> StdResult<TokenMetadataResponse> {
+    if let Some(metadata) = TOKEN_METADATA.may_load(deps.storage, &id)? {
+        return Ok(TokenMetadataResponse {
+            id,
+            uri: metadata.uri,
+            uri_hash: metadata.uri_hash,
+            cached: true,
+        });
+    }
+    let (token_uri, _) = chain_token_uri(deps, &id)?;
+    Ok(TokenMetadataResponse {
+        id,
+        uri: token_uri.uri,
+        uri_hash: token_uri.uri_hash,
+        cached: false,
+    })
+}
+// the uri a token has on chain and whether it can be updated, mutable tokens keep it in their data
+fn chain_token_uri(deps: Deps<CoreumQueries>, id: &str) -> StdResult<(TokenUri, bool)> {
+    let nft = query_nft(deps, id.to_string())?.nft;
+    // the DataBytes of immutable tokens never decode to a TokenUri at this index
+    let updated = nft
+        .data
+        .and_then(|data| DataDynamic::decode(data.as_slice()).ok())
+        .and_then(|data| data.items.into_iter().nth(URI_ITEM as usize))
+        .and_then(|item| from_json::<TokenUri>(&item.data).ok());
+    match updated {
+        Some(token_uri) => Ok((token_uri, true)),
+        None => Ok((
+            TokenUri {
+                uri: nft.uri,
+                uri_hash: nft.uri_hash,
+            },
+            false,
+        )),
+    }
+}
+fn query_royalty_splits(deps: Deps<CoreumQueries>) -> StdResult<RoyaltySplitsResponse> {
+    let splits = ROYALTY_SPLITS
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|split| RoyaltySplit {
+            address: split.address.into_string(),
+            bps: split.bps,
+        })
+        .collect();
+    Ok(RoyaltySplitsResponse { splits })
+}
+fn query_ibc_escrowed_nfts(
+    deps: Deps<CoreumQueries>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<IbcEscrowedNftsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let nfts = IBC_NFTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(id, ibc_nft)| IbcEscrowedNft {
+                id,
+                channel: ibc_nft.channel,
+                receiver: ibc_nft.receiver,
+                timeout: ibc_nft.timeout,
+                status: ibc_nft.status,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(IbcEscrowedNftsResponse {
+        class_id: CLASS_ID.load(deps.storage)?,
+        nfts,
+    })
+}
 fn query_nfts(deps: Deps<CoreumQueries>, owner: Option<String>) -> StdResult<nft::NFTsResponse> {
     let class_id = CLASS_ID.load(deps.storage)?;
     let mut pagination = None;
@@ -808,4 +1255,447 @@ fn query_nft_classes(deps: Deps<CoreumQueries>) -> StdResult<nft::ClassesRespons
         pagination: res.pagination,
     };
     Ok(res)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_schema::serde::Serialize;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{
+        to_json_string, ContractResult, OwnedDeps, QuerierResult, SystemError, SystemResult,
+    };
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::marker::PhantomData;
+    use std::rc::Rc;
+    type CoreumDeps = OwnedDeps<MockStorage, MockApi, MockQuerier<CoreumQueries>, CoreumQueries>;
+    const OWNER: &str = "owner";
+    // state of the mocked chain, shared with the tests so they can move tokens around
+    #[derive(Default)]
+    struct Chain {
+        owners: HashMap<String, String>,
+        features: Vec<u32>,
+        frozen: Vec<String>,
+        class_frozen: Vec<String>,
+        // (id, account)
+        whitelisted: Vec<(String, String)>,
+        // served two accounts per page
+        class_whitelist: Vec<String>,
+        // uri and data of minted tokens
+        minted: HashMap<String, (Option<String>, Option<Binary>)>,
+        burnt: Vec<String>,
+    }
+    type SharedChain = Rc<RefCell<Chain>>;
+    fn respond<T: Serialize>(res: &T) -> QuerierResult {
+        SystemResult::Ok(ContractResult::Ok(to_json_binary(res).unwrap()))
+    }
+    fn respond_json(json: String) -> QuerierResult {
+        SystemResult::Ok(ContractResult::Ok(Binary::from(json.into_bytes())))
+    }
+    fn handle(chain: &Chain, query: &CoreumQueries) -> QuerierResult {
+        match query {
+            CoreumQueries::AssetNFT(assetnft::Query::Class { id }) => respond_json(format!(
+                r#"{{"class":{{"id":"{}","issuer":"{}","name":"Badges","symbol":"badge","description":null,"uri":null,"uri_hash":null,"data":null,"features":{:?}}}}}"#,
+                id,
+                mock_env().contract.address,
+                chain.features
+            )),
+            CoreumQueries::AssetNFT(assetnft::Query::Frozen { id, .. }) => respond(&FrozenResponse {
+                frozen: chain.frozen.contains(id),
+            }),
+            CoreumQueries::AssetNFT(assetnft::Query::ClassFrozen { account, .. }) => {
+                respond(&ClassFrozenResponse {
+                    frozen: chain.class_frozen.contains(account),
+                })
+            }
+            CoreumQueries::AssetNFT(assetnft::Query::Whitelisted { id, account, .. }) => {
+                respond(&WhitelistedResponse {
+                    whitelisted: chain.whitelisted.contains(&(id.clone(), account.clone())),
+                })
+            }
+            CoreumQueries::AssetNFT(assetnft::Query::ClassWhitelistedAccounts { pagination, .. }) => {
+                let start: usize = pagination
+                    .as_ref()
+                    .and_then(|page| page.key.as_ref())
+                    .map(|key| String::from_utf8(key.to_vec()).unwrap().parse().unwrap())
+                    .unwrap_or_default();
+                let end = (start + 2).min(chain.class_whitelist.len());
+                let next_key = if end < chain.class_whitelist.len() {
+                    format!(r#""{}""#, Binary::from(end.to_string().as_bytes()).to_base64())
+                } else {
+                    "null".to_string()
+                };
+                respond_json(format!(
+                    r#"{{"pagination":{{"next_key":{},"total":{}}},"accounts":{:?}}}"#,
+                    next_key,
+                    chain.class_whitelist.len(),
+                    &chain.class_whitelist[start..end]
+                ))
+            }
+            CoreumQueries::AssetNFT(assetnft::Query::BurntNFT { nft_id, .. }) => {
+                respond_json(format!(r#"{{"burnt":{}}}"#, chain.burnt.contains(nft_id)))
+            }
+            CoreumQueries::NFT(nft::Query::NFT { class_id, id }) => match chain.minted.get(id) {
+                Some((uri, data)) => respond_json(format!(
+                    r#"{{"nft":{{"class_id":"{}","id":"{}","uri":{},"uri_hash":null,"data":{}}}}}"#,
+                    class_id,
+                    id,
+                    to_json_string(uri).unwrap(),
+                    to_json_string(data).unwrap()
+                )),
+                None => SystemResult::Ok(ContractResult::Err(format!("nft {} not found", id))),
+            },
+            CoreumQueries::NFT(nft::Query::Owner { id, .. }) => match chain.owners.get(id) {
+                Some(owner) => respond(&nft::OwnerResponse {
+                    owner: owner.clone(),
+                }),
+                None => SystemResult::Ok(ContractResult::Err(format!("nft {} not found", id))),
+            },
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "coreum".to_string(),
+            }),
+        }
+    }
+    fn setup(chain: SharedChain) -> CoreumDeps {
+        let querier = MockQuerier::new(&[]).with_custom_handler(move |query| handle(&chain.borrow(), query));
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier,
+            custom_query_type: PhantomData,
+        };
+        let msg = InstantiateMsg {
+            name: "Badges".to_string(),
+            symbol: "badge".to_string(),
+            description: None,
+            uri: None,
+            uri_hash: None,
+            data: None,
+            features: None,
+            royalty_rate: None,
+            cache_metadata: Some(true),
+            royalty_splits: None,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+        deps
+    }
+    fn attribute(res: &Response<CoreumMsg>, key: &str) -> String {
+        res.attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.clone())
+            .unwrap_or_default()
+    }
+    #[test]
+    fn msg_transfer_encodes_ics721_fields() {
+        let transfer = MsgTransfer {
+            source_port: "p".to_string(),
+            source_channel: "c".to_string(),
+            class_id: "k".to_string(),
+            token_ids: vec!["a".to_string(), "b".to_string()],
+            sender: "s".to_string(),
+            receiver: "r".to_string(),
+            timeout_timestamp: 300,
+            memo: "m".to_string(),
+        };
+        // field numbers of ibc.applications.nft_transfer.v1.MsgTransfer, tag 7 left out
+        let expected = [
+            &[0x0a, 1, b'p'][..],
+            &[0x12, 1, b'c'],
+            &[0x1a, 1, b'k'],
+            &[0x22, 1, b'a'],
+            &[0x22, 1, b'b'],
+            &[0x2a, 1, b's'],
+            &[0x32, 1, b'r'],
+            &[0x40, 0xac, 0x02],
+            &[0x4a, 1, b'm'],
+        ]
+        .concat();
+        assert_eq!(transfer.encode_to_vec(), expected);
+        assert_eq!(MsgTransfer::decode(expected.as_slice()).unwrap(), transfer);
+    }
+    #[test]
+    fn escrow_address_is_derived_from_the_channel() {
+        // sha256("ics721-1" || 0x00 || "nft-transfer/channel-0"), truncated to 20 bytes
+        let expected = [
+            0x9d, 0xda, 0x02, 0xe3, 0xe8, 0xb4, 0x85, 0xe1, 0x2a, 0x04, 0x43, 0xd8, 0x54, 0xea,
+            0xea, 0x0f, 0x7e, 0x01, 0xb2, 0x04,
+        ];
+        assert_eq!(nft_transfer::escrow_address("channel-0"), expected);
+        assert_ne!(
+            nft_transfer::escrow_address("channel-1"),
+            nft_transfer::escrow_address("channel-0")
+        );
+    }
+    #[test]
+    fn ibc_send_and_sync() {
+        let chain = SharedChain::default();
+        let mut deps = setup(chain.clone());
+        let env = mock_env();
+        let contract = env.contract.address.to_string();
+        let escrow = deps
+            .api
+            .addr_humanize(&CanonicalAddr::from(nft_transfer::escrow_address("channel-0")))
+            .unwrap()
+            .to_string();
+        let send = |timeout| ExecuteMsg::IbcSend {
+            id: "a".to_string(),
+            channel: "channel-0".to_string(),
+            receiver: "stars1receiver".to_string(),
+            timeout,
+        };
+        let sync = ExecuteMsg::SyncIbcNft { id: "a".to_string() };
+        chain.borrow_mut().owners.insert("a".to_string(), "someone".to_string());
+        let err = execute(deps.as_mut(), env.clone(), mock_info("someone", &[]), send(600)).unwrap_err();
+        assert!(matches!(err, ContractError::Ownership(_)));
+        let err = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), send(0)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidTimeout {}));
+        let err = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), send(600)).unwrap_err();
+        assert!(matches!(err, ContractError::NotHeldByContract { .. }));
+        let err = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), sync.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotSentOverIbc { .. }));
+
+        // the contract holds the token, it is sent from the contract over nft-transfer
+        chain.borrow_mut().owners.insert("a".to_string(), contract.clone());
+        let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), send(600)).unwrap();
+        let timeout = env.block.time.plus_seconds(600);
+        let transfer = MsgTransfer {
+            source_port: nft_transfer::PORT.to_string(),
+            source_channel: "channel-0".to_string(),
+            class_id: format!("badge-{}", contract),
+            token_ids: vec!["a".to_string()],
+            sender: contract.clone(),
+            receiver: "stars1receiver".to_string(),
+            timeout_timestamp: timeout.nanos(),
+            memo: String::new(),
+        };
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Stargate {
+                type_url: nft_transfer::MSG_TRANSFER_TYPE_URL.to_string(),
+                value: Binary::from(transfer.encode_to_vec()),
+            }
+        );
+        let escrowed = |deps: &CoreumDeps| -> Vec<IbcNftStatus> {
+            let res: IbcEscrowedNftsResponse = from_json(
+                query(deps.as_ref(), mock_env(), QueryMsg::IbcEscrowedNfts { start_after: None, limit: None }).unwrap(),
+            )
+            .unwrap();
+            res.nfts.into_iter().map(|nft| nft.status).collect()
+        };
+        assert_eq!(escrowed(&deps), vec![IbcNftStatus::InFlight]);
+
+        // escrowed by the module until the packet is acknowledged or times out
+        chain.borrow_mut().owners.insert("a".to_string(), escrow);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), sync.clone()).unwrap();
+        assert_eq!(attribute(&res, "status"), "in_flight");
+        let mut later = env.clone();
+        later.block.time = timeout.plus_seconds(1);
+        let res = execute(deps.as_mut(), later.clone(), mock_info("anyone", &[]), sync.clone()).unwrap();
+        assert_eq!(attribute(&res, "status"), "away");
+        assert_eq!(escrowed(&deps), vec![IbcNftStatus::Away]);
+
+        // back on this chain, tracking stops
+        chain.borrow_mut().owners.insert("a".to_string(), "collector".to_string());
+        let res = execute(deps.as_mut(), later.clone(), mock_info("anyone", &[]), sync.clone()).unwrap();
+        assert_eq!(attribute(&res, "status"), "released");
+        assert!(escrowed(&deps).is_empty());
+        let err = execute(deps.as_mut(), later, mock_info("anyone", &[]), sync).unwrap_err();
+        assert!(matches!(err, ContractError::NotSentOverIbc { .. }));
+    }
+    fn restricted_chain() -> SharedChain {
+        let chain = SharedChain::default();
+        {
+            let mut chain = chain.borrow_mut();
+            chain.owners.insert("a".to_string(), "alice".to_string());
+            chain.features = vec![WHITELISTING];
+            chain.class_whitelist = ["amy", "ben", "cat", "dan", "zed"].map(String::from).to_vec();
+            chain.whitelisted.push(("a".to_string(), "bob".to_string()));
+        }
+        chain
+    }
+    fn simulate(deps: &CoreumDeps, from: &str, to: &str) -> SimulateTransferResponse {
+        let msg = QueryMsg::SimulateTransfer {
+            id: "a".to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+    }
+    #[test]
+    fn simulate_transfer_checks_every_whitelist_page() {
+        let chain = restricted_chain();
+        let deps = setup(chain.clone());
+        // whitelisted for the token, or anywhere in the paged class whitelist
+        assert!(simulate(&deps, "alice", "bob").allowed);
+        assert!(simulate(&deps, "alice", "amy").allowed);
+        assert!(simulate(&deps, "alice", "zed").allowed);
+        let res = simulate(&deps, "alice", "eve");
+        assert_eq!(res.reasons, vec!["eve is not whitelisted for a".to_string()]);
+        // the issuer, i.e. this contract, can always receive
+        assert!(simulate(&deps, "alice", mock_env().contract.address.as_str()).allowed);
+    }
+    #[test]
+    fn simulate_transfer_reports_every_restriction() {
+        let chain = restricted_chain();
+        let deps = setup(chain.clone());
+        {
+            let mut chain = chain.borrow_mut();
+            chain.features.push(DISABLE_SENDING);
+            chain.frozen.push("a".to_string());
+            chain.class_frozen.push("alice".to_string());
+        }
+        let res = simulate(&deps, "alice", "eve");
+        assert!(!res.allowed);
+        assert_eq!(
+            res.reasons,
+            vec![
+                "sending is disabled for the class".to_string(),
+                "a is frozen".to_string(),
+                "alice is frozen for the class".to_string(),
+                "eve is not whitelisted for a".to_string(),
+            ]
+        );
+        let res = simulate(&deps, "mallory", "zed");
+        assert_eq!(res.reasons[0], "mallory doesn't own a");
+    }
+    #[test]
+    fn batch_freeze_and_whitelist() {
+        let mut deps = setup(SharedChain::default());
+        let class_id = format!("badge-{}", mock_env().contract.address);
+        let freeze = ExecuteMsg::BatchFreeze {
+            ids: vec!["a".to_string(), "b".to_string()],
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), freeze.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Ownership(_)));
+        let err = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), ExecuteMsg::BatchFreeze { ids: vec![] }).unwrap_err();
+        assert!(matches!(err, ContractError::EmptyBatch {}));
+        let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), freeze).unwrap();
+        let msgs: Vec<_> = res.messages.into_iter().map(|msg| msg.msg).collect();
+        assert_eq!(
+            msgs,
+            ["a", "b"].map(|id| {
+                CosmosMsg::Custom(CoreumMsg::AssetNFT(assetnft::Msg::Freeze {
+                    class_id: class_id.clone(),
+                    id: id.to_string(),
+                }))
+            })
+        );
+
+        let entries = vec![
+            WhitelistEntry { id: "a".to_string(), account: "bob".to_string() },
+            WhitelistEntry { id: "b".to_string(), account: "carol".to_string() },
+        ];
+        let whitelist = ExecuteMsg::BatchAddToWhitelist { entries };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), whitelist.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Ownership(_)));
+        let err = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), ExecuteMsg::BatchAddToWhitelist { entries: vec![] }).unwrap_err();
+        assert!(matches!(err, ContractError::EmptyBatch {}));
+        let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), whitelist).unwrap();
+        assert_eq!(attribute(&res, "count"), "2");
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Custom(CoreumMsg::AssetNFT(assetnft::Msg::AddToWhitelist {
+                class_id,
+                id: "b".to_string(),
+                account: "carol".to_string(),
+            }))
+        );
+    }
+    // executes a mint and applies the MsgMint it sends to the mocked chain
+    fn mint(deps: &mut CoreumDeps, chain: &SharedChain, msg: ExecuteMsg) {
+        let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+        let CosmosMsg::Stargate { value, .. } = &res.messages[0].msg else {
+            panic!("expected a MsgMint");
+        };
+        let mint = MsgMint::try_from(value.clone()).unwrap();
+        let uri = Some(mint.uri).filter(|uri| !uri.is_empty());
+        let data = mint.data.map(|data| Binary::from(data.value));
+        chain.borrow_mut().minted.insert(mint.id, (uri, data));
+    }
+    fn token_metadata(deps: &CoreumDeps, id: &str) -> TokenMetadataResponse {
+        let msg = QueryMsg::TokenMetadata { id: id.to_string() };
+        from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+    }
+    #[test]
+    fn set_token_uri_updates_chain_and_cache() {
+        let chain = SharedChain::default();
+        let mut deps = setup(chain.clone());
+        let msg = ExecuteMsg::MintMutable {
+            id: "m".to_string(),
+            uri: Some("ipfs://first".to_string()),
+            uri_hash: None,
+            data: Some(Binary::from(b"level 1")),
+            recipient: None,
+        };
+        mint(&mut deps, &chain, msg);
+        let res = token_metadata(&deps, "m");
+        assert_eq!((res.uri.as_deref(), res.cached), (Some("ipfs://first"), true));
+
+        let set_uri = ExecuteMsg::SetTokenUri {
+            id: "m".to_string(),
+            uri: Some("ipfs://second".to_string()),
+            uri_hash: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), set_uri.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Ownership(_)));
+        let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), set_uri).unwrap();
+        let CosmosMsg::Stargate { value, .. } = &res.messages[0].msg else {
+            panic!("expected a MsgUpdateData");
+        };
+        let update = MsgUpdateData::try_from(value.clone()).unwrap();
+        assert_eq!(update.items.len(), 1);
+        assert_eq!(update.items[0].index, URI_ITEM);
+        // the user data item is left alone, the uri item is rewritten on chain
+        {
+            let mut chain = chain.borrow_mut();
+            let (_, data) = chain.minted.get_mut("m").unwrap();
+            let mut dynamic = DataDynamic::decode(data.as_ref().unwrap().as_slice()).unwrap();
+            assert_eq!(dynamic.items[0].data, b"level 1".to_vec());
+            dynamic.items[URI_ITEM as usize].data = update.items[0].data.clone();
+            *data = Some(Binary::from(dynamic.encode_to_vec()));
+        }
+        let res = token_metadata(&deps, "m");
+        assert_eq!((res.uri.as_deref(), res.cached), (Some("ipfs://second"), true));
+
+        // without a cache entry the updated uri is read from the token data
+        TOKEN_METADATA.remove(deps.as_mut().storage, "m");
+        let res = token_metadata(&deps, "m");
+        assert_eq!((res.uri.as_deref(), res.cached), (Some("ipfs://second"), false));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::RefreshTokenMetadata { id: "m".to_string() },
+        )
+        .unwrap();
+        let res = token_metadata(&deps, "m");
+        assert_eq!((res.uri.as_deref(), res.cached), (Some("ipfs://second"), true));
+    }
+    #[test]
+    fn immutable_and_burnt_tokens() {
+        let chain = SharedChain::default();
+        let mut deps = setup(chain.clone());
+        let msg = ExecuteMsg::MintImmutable {
+            id: "i".to_string(),
+            uri: Some("ipfs://fixed".to_string()),
+            uri_hash: None,
+            data: Some(Binary::from(b"level 1")),
+            recipient: None,
+        };
+        mint(&mut deps, &chain, msg);
+        let set_uri = ExecuteMsg::SetTokenUri {
+            id: "i".to_string(),
+            uri: Some("ipfs://other".to_string()),
+            uri_hash: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), set_uri).unwrap_err();
+        assert!(matches!(err, ContractError::Immutable { .. }));
+
+        // burnt by its owner outside the contract, refreshing drops the stale entry
+        chain.borrow_mut().burnt.push("i".to_string());
+        let refresh = ExecuteMsg::RefreshTokenMetadata { id: "i".to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), refresh).unwrap();
+        assert!(!TOKEN_METADATA.has(deps.as_ref().storage, "i"));
+    }
+}
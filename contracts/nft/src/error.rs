@@ -7,4 +7,16 @@ pub enum ContractError {
     Std(#[from] StdError),
     #[error(transparent)]
     Ownership(#[from] OwnershipError),
+    #[error("Token {id} is immutable")]
+    Immutable { id: String },
+    #[error("Batch must not be empty")]
+    EmptyBatch {},
+    #[error("Royalty split shares must be positive and add up to 10000 bps")]
+    InvalidRoyaltySplits {},
+    #[error("Token {id} must be held by the contract to be sent over IBC")]
+    NotHeldByContract { id: String },
+    #[error("IBC transfer timeout must be at least one second")]
+    InvalidTimeout {},
+    #[error("Token {id} was not sent over IBC")]
+    NotSentOverIbc { id: String },
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 pub mod contract;
 pub mod error;
 pub mod msg;
+pub mod nft_transfer;
 pub mod state;
\ No newline at end of file
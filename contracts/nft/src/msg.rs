@@ -1,5 +1,6 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Binary;
+use cosmwasm_std::{Binary, Timestamp};
+use crate::state::IbcNftStatus;
 #[cw_serde]
 pub struct InstantiateMsg {
     pub name: String,
@@ -10,6 +11,9 @@ pub struct InstantiateMsg {
     pub data: Option<Binary>,
     pub features: Option<Vec<u32>>,
     pub royalty_rate: Option<String>,
+    pub cache_metadata: Option<bool>,
+    // recipients sharing the royalty, emitted on every mint
+    pub royalty_splits: Option<Vec<RoyaltySplit>>,
 }
 #[cw_serde]
 pub enum ExecuteMsg {
@@ -45,6 +49,9 @@ pub enum ExecuteMsg {
     Freeze {
         id: String,
     },
+    BatchFreeze {
+        ids: Vec<String>,
+    },
     Unfreeze {
         id: String,
     },
@@ -58,6 +65,9 @@ pub enum ExecuteMsg {
         id: String,
         account: String,
     },
+    BatchAddToWhitelist {
+        entries: Vec<WhitelistEntry>,
+    },
     RemoveFromWhitelist {
         id: String,
         account: String,
@@ -72,6 +82,31 @@ pub enum ExecuteMsg {
         id: String,
         receiver: String,
     },
+    // updates the uri of a token minted with MintMutable in its data on chain and in the cache
+    SetTokenUri {
+        id: String,
+        uri: Option<String>,
+        uri_hash: Option<String>,
+    },
+    // replaces the royalty split registry, an empty list clears it
+    SetRoyaltySplits {
+        splits: Vec<RoyaltySplit>,
+    },
+    // sends a token held by the contract to another chain, timing out after `timeout` seconds
+    IbcSend {
+        id: String,
+        channel: String,
+        receiver: String,
+        timeout: u64,
+    },
+    // updates the tracking of a token sent over IBC from its current owner, callable by anyone
+    SyncIbcNft {
+        id: String,
+    },
+    // rewrites the cached metadata of a token from the chain, callable by anyone
+    RefreshTokenMetadata {
+        id: String,
+    },
 }
 #[cw_serde]
 pub enum QueryMsg {
@@ -93,4 +128,52 @@ pub enum QueryMsg {
     ClassesNft {}, // we use ClassesNft instead of Class because there is already a Classes query being used
     BurntNft { nft_id: String },
     BurntNftsInClass {},
-}
\ No newline at end of file
+    TokenMetadata { id: String }, // served from the local cache, falls back to the chain
+    // checks freezing and whitelisting without sending
+    SimulateTransfer { id: String, from: String, to: String },
+    RoyaltySplits {},
+    IbcEscrowedNfts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+#[cw_serde]
+pub struct WhitelistEntry {
+    pub id: String,
+    pub account: String,
+}
+#[cw_serde]
+pub struct RoyaltySplit {
+    pub address: String,
+    pub bps: u16,
+}
+#[cw_serde]
+pub struct RoyaltySplitsResponse {
+    pub splits: Vec<RoyaltySplit>,
+}
+#[cw_serde]
+pub struct TokenMetadataResponse {
+    pub id: String,
+    pub uri: Option<String>,
+    pub uri_hash: Option<String>,
+    pub cached: bool,
+}
+#[cw_serde]
+pub struct SimulateTransferResponse {
+    pub allowed: bool,
+    // every restriction that would block the transfer, empty when allowed
+    pub reasons: Vec<String>,
+}
+#[cw_serde]
+pub struct IbcEscrowedNft {
+    pub id: String,
+    pub channel: String,
+    pub receiver: String,
+    pub timeout: Timestamp,
+    pub status: IbcNftStatus,
+}
+#[cw_serde]
+pub struct IbcEscrowedNftsResponse {
+    pub class_id: String,
+    pub nfts: Vec<IbcEscrowedNft>,
+}
@@ -0,0 +1,33 @@
+// Messages of the ICS-721 nft-transfer module, not covered by coreum-wasm-sdk
+use sha2::{Digest, Sha256};
+pub const PORT: &str = "nft-transfer";
+pub const VERSION: &str = "ics721-1";
+pub const MSG_TRANSFER_TYPE_URL: &str = "/ibc.applications.nft_transfer.v1.MsgTransfer";
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgTransfer {
+    #[prost(string, tag = "1")]
+    pub source_port: String,
+    #[prost(string, tag = "2")]
+    pub source_channel: String,
+    #[prost(string, tag = "3")]
+    pub class_id: String,
+    #[prost(string, repeated, tag = "4")]
+    pub token_ids: Vec<String>,
+    #[prost(string, tag = "5")]
+    pub sender: String,
+    #[prost(string, tag = "6")]
+    pub receiver: String,
+    // tag 7 is the timeout height, transfers sent here only time out by timestamp
+    #[prost(uint64, tag = "8")]
+    pub timeout_timestamp: u64,
+    #[prost(string, tag = "9")]
+    pub memo: String,
+}
+// the module escrows outgoing nfts in an account derived from the channel, like ICS-20 does
+pub fn escrow_address(channel: &str) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(VERSION.as_bytes());
+    hasher.update([0]);
+    hasher.update(format!("{}/{}", PORT, channel).as_bytes());
+    hasher.finalize()[..20].to_vec()
+}
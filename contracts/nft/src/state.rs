@@ -1,2 +1,42 @@
-use cw_storage_plus::Item;
-pub const CLASS_ID: Item<String> = Item::new("class_id");
\ No newline at end of file
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Timestamp};
+use cw_storage_plus::{Item, Map};
+pub const CLASS_ID: Item<String> = Item::new("class_id");
+// whether token uri/uri_hash are kept locally at mint
+pub const CACHE_METADATA: Item<bool> = Item::new("cache_metadata");
+#[cw_serde]
+pub struct TokenMetadata {
+    pub uri: Option<String>,
+    pub uri_hash: Option<String>,
+    pub mutable: bool,
+}
+pub const TOKEN_METADATA: Map<&str, TokenMetadata> = Map::new("token_metadata");
+// kept as json in the dynamic data of mutable tokens, the chain can't change a token uri itself
+#[cw_serde]
+pub struct TokenUri {
+    pub uri: Option<String>,
+    pub uri_hash: Option<String>,
+}
+#[cw_serde]
+pub struct RoyaltyRecipient {
+    pub address: Addr,
+    pub bps: u16,
+}
+// how the class royalty is shared, the chain itself only pays a single royalty rate
+pub const ROYALTY_SPLITS: Item<Vec<RoyaltyRecipient>> = Item::new("royalty_splits");
+#[cw_serde]
+pub enum IbcNftStatus {
+    // escrowed until the packet is acknowledged or times out
+    InFlight,
+    // received on the other chain, the escrow holds it until it comes back
+    Away,
+}
+#[cw_serde]
+pub struct IbcNft {
+    pub channel: String,
+    pub receiver: String,
+    pub timeout: Timestamp,
+    pub status: IbcNftStatus,
+}
+// tokens of the class sent out through nft-transfer and still escrowed on this chain
+pub const IBC_NFTS: Map<&str, IbcNft> = Map::new("ibc_nfts");
@@ -1,14 +1,27 @@
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{SaleInfo, State, EDITIONS, NFT, NFTS, RENTALS, SALES, STATE};
+use crate::msg::{
+    ExecuteMsg, FungibleTokenQueryMsg, InstantiateMsg, Listing, ListingsResponse, QueryMsg,
+    SaleQuoteResponse,
+};
+use crate::state::{
+    CollateralStatus, RentalDeposit, RentalOffer, SaleInfo, SmartFt, State, EDITIONS, NATIVE_DENOM,
+    NFT, NFTS, PRIVATE_LISTINGS, RENTALS, RENTAL_DEPOSITS, RENTAL_OFFERS, SALES, SMART_FT, STATE,
+};
 use coreum_wasm_sdk::{assetft, core::{CoreumMsg, CoreumQueries}};
 use cosmwasm_std::{
     entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128, CosmosMsg, BankMsg, Coin, StdError,
+    Decimal, Empty, Order, Storage,
 };
 use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+use std::str::FromStr;
 
 const CONTRACT_NAME: &str = "nft-marketplace";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Settings for paginating listings
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
 /// Initialize the contract with owner and marketplace address
 #[entry_point]
 pub fn instantiate(
@@ -24,6 +37,15 @@ pub fn instantiate(
     };
     STATE.save(deps.storage, &state)?;
 
+    // Resolve the smart FT denom from the fungibleToken contract
+    if let Some(fungible_token) = msg.fungible_token {
+        let contract = deps.api.addr_validate(&fungible_token)?;
+        let token: assetft::TokenResponse = deps
+            .querier
+            .query_wasm_smart(&contract, &FungibleTokenQueryMsg::Token {})?;
+        SMART_FT.save(deps.storage, &SmartFt { contract, denom: token.token.denom })?;
+    }
+
     // Set the contract version
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
@@ -36,16 +58,25 @@ pub fn instantiate(
 #[entry_point]
 pub fn execute(
     deps: DepsMut<CoreumQueries>,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response<CoreumMsg>, ContractError> {
     match msg {
         ExecuteMsg::CreateNFT { id, metadata, royalties } => create_nft(deps, info, id, metadata, royalties),
-        ExecuteMsg::ListForSale { id, price } => list_for_sale(deps, info, id, price),
-        ExecuteMsg::BuyNFT { id } => buy_nft(deps, info, id),
-        ExecuteMsg::RentNFT { id, duration } => rent_nft(deps, info, id, duration),
-        ExecuteMsg::ReturnNFT { id } => return_nft(deps, info, id),
+        ExecuteMsg::ListForSale { id, price, denom } => list_for_sale(deps, info, id, price, denom),
+        ExecuteMsg::ListPrivate { id, price, buyer, denom, expires_at } => {
+            list_private(deps, env, info, id, price, buyer, denom, expires_at)
+        }
+        ExecuteMsg::CancelListing { id } => cancel_listing(deps, info, id),
+        ExecuteMsg::BuyNFT { id, recipient } => buy_nft(deps, env, info, id, recipient),
+        ExecuteMsg::OfferRental { id, price_per_second, collateral, max_duration, denom } => {
+            offer_rental(deps, info, id, price_per_second, collateral, max_duration, denom)
+        }
+        ExecuteMsg::CancelRentalOffer { id } => cancel_rental_offer(deps, info, id),
+        ExecuteMsg::RentNFT { id, duration } => rent_nft(deps, env, info, id, duration),
+        ExecuteMsg::ReturnNFT { id } => return_nft(deps, env, info, id),
+        ExecuteMsg::SettleRental { id } => settle_rental(deps, env, id),
         ExecuteMsg::MintEdition { id, edition } => mint_edition(deps, info, id, edition),
         ExecuteMsg::UpdateNFT { id, new_metadata } => update_nft(deps, info, id, new_metadata),
         ExecuteMsg::WithdrawFunds {} => withdraw_funds(deps, info),
@@ -78,6 +109,7 @@ fn list_for_sale(
     info: MessageInfo,
     id: String,
     price: Uint128,
+    denom: Option<String>,
 ) -> Result<Response<CoreumMsg>, ContractError> {
     // Load the NFT from storage
     let nft = NFTS.load(deps.storage, id.clone())?;
@@ -87,104 +119,372 @@ fn list_for_sale(
         return Err(ContractError::Unauthorized {});
     }
 
+    let denom = listing_denom(deps.storage, denom)?;
+
     // Save the sale information
     let sale_info = SaleInfo {
         price,
+        denom: denom.clone(),
         royalty: nft.royalties,
+        buyer: None,
+        expires_at: None,
     };
+    remove_sale(deps.storage, &id)?;
     SALES.save(deps.storage, id.clone(), &sale_info)?;
 
     Ok(Response::new()
         .add_attribute("method", "list_for_sale")
         .add_attribute("nft_id", id)
-        .add_attribute("price", price.to_string()))
+        .add_attribute("price", price.to_string())
+        .add_attribute("denom", denom))
+}
+
+/// List an NFT for an OTC sale to a single buyer, optionally expiring
+#[allow(clippy::too_many_arguments)]
+fn list_private(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    price: Uint128,
+    buyer: String,
+    denom: Option<String>,
+    expires_at: Option<u64>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let nft = NFTS.load(deps.storage, id.clone())?;
+    if nft.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if expires_at.map_or(false, |expires_at| expires_at <= env.block.time.seconds()) {
+        return Err(ContractError::InvalidExpiration {});
+    }
+    let buyer = deps.api.addr_validate(&buyer)?;
+    let denom = listing_denom(deps.storage, denom)?;
+
+    let sale_info = SaleInfo {
+        price,
+        denom: denom.clone(),
+        royalty: nft.royalties,
+        buyer: Some(buyer.clone()),
+        expires_at,
+    };
+    remove_sale(deps.storage, &id)?;
+    SALES.save(deps.storage, id.clone(), &sale_info)?;
+    PRIVATE_LISTINGS.save(deps.storage, (&buyer, &id), &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("method", "list_private")
+        .add_attribute("nft_id", id)
+        .add_attribute("buyer", buyer)
+        .add_attribute("price", price.to_string())
+        .add_attribute("denom", denom))
+}
+
+/// Only the native denom and the smart FT are accepted
+fn listing_denom(storage: &dyn Storage, denom: Option<String>) -> Result<String, ContractError> {
+    match denom {
+        None => Ok(NATIVE_DENOM.to_string()),
+        Some(denom) if denom == NATIVE_DENOM => Ok(denom),
+        Some(denom) => {
+            let smart_ft = SMART_FT.may_load(storage)?;
+            if smart_ft.map(|ft| ft.denom) != Some(denom.clone()) {
+                return Err(ContractError::UnsupportedDenom {});
+            }
+            Ok(denom)
+        }
+    }
+}
+
+/// Remove a listing, if any, along with its entry in the private listings index
+fn remove_sale(storage: &mut dyn Storage, id: &str) -> StdResult<()> {
+    if let Some(SaleInfo { buyer: Some(buyer), .. }) = SALES.may_load(storage, id.to_string())? {
+        PRIVATE_LISTINGS.remove(storage, (&buyer, id));
+    }
+    SALES.remove(storage, id.to_string());
+    Ok(())
+}
+
+/// Remove a public or private listing
+fn cancel_listing(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    id: String,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let nft = NFTS.load(deps.storage, id.clone())?;
+    if nft.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !SALES.has(deps.storage, id.clone()) {
+        return Err(ContractError::InvalidNFT {});
+    }
+    remove_sale(deps.storage, &id)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "cancel_listing")
+        .add_attribute("nft_id", id))
 }
 
 /// Buy an NFT that is listed for sale
 fn buy_nft(
     deps: DepsMut<CoreumQueries>,
+    env: Env,
     info: MessageInfo,
     id: String,
+    recipient: Option<String>,
 ) -> Result<Response<CoreumMsg>, ContractError> {
+    let buyer = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender.clone(),
+    };
+
     // Load the sale information from storage
     let sale_info = SALES.load(deps.storage, id.clone())
         .map_err(|_| ContractError::InvalidNFT {})?;
+
+    // Private listings are reserved for their buyer until they expire
+    if sale_info.buyer.as_ref().map_or(false, |buyer| buyer != &info.sender) {
+        return Err(ContractError::NotReservedBuyer {});
+    }
+    if sale_info.expires_at.map_or(false, |expires_at| env.block.time.seconds() > expires_at) {
+        return Err(ContractError::ListingExpired {});
+    }
     
     // Load the NFT from storage
     let mut nft = NFTS.load(deps.storage, id.clone())?;
 
     // Ensure the buyer has sent enough funds
-    let sent_funds = info.funds.iter().find(|c| c.denom == "uscrt").map(|c| c.amount).unwrap_or(Uint128::zero());
+    let sent_funds = info.funds.iter().find(|c| c.denom == sale_info.denom).map(|c| c.amount).unwrap_or(Uint128::zero());
     if sent_funds < sale_info.price {
         return Err(ContractError::InsufficientBalance {});
     }
 
+    // Smart FT transfers out of the contract are charged burn rate and send commission on top
+    let quote = quote_sale(deps.as_ref(), &sale_info, &nft.owner)?;
+
     // Handle the royalty payment if applicable
     let mut messages: Vec<CosmosMsg<CoreumMsg>> = vec![];
-    let royalty_amount = if let Some(royalty) = sale_info.royalty {
-        let royalty_amount = sale_info.price.multiply_ratio(royalty, 100u128);
+    if sale_info.royalty.is_some() {
         let royalty_msg = BankMsg::Send {
             to_address: nft.owner.clone().into(),
             amount: vec![Coin {
-                denom: "uscrt".to_string(),
-                amount: royalty_amount,
+                denom: sale_info.denom.clone(),
+                amount: quote.royalty,
             }],
         };
         messages.push(CosmosMsg::Bank(royalty_msg));
-        royalty_amount
-    } else {
-        Uint128::zero()
-    };
+    }
 
     // Transfer the remaining amount to the seller
-    let seller_payment = sale_info.price.checked_sub(royalty_amount)
-        .map_err(|_| ContractError::Overflow {})?;
+    let seller_payment = quote.seller_net;
     let seller_msg = BankMsg::Send {
         to_address: nft.owner.clone().into(),
         amount: vec![Coin {
-            denom: "uscrt".to_string(),
+            denom: sale_info.denom.clone(),
             amount: seller_payment,
         }],
     };
     messages.push(CosmosMsg::Bank(seller_msg));
 
-    // Update the NFT owner
-    nft.owner = info.sender.clone();
+    // Update the NFT owner, the rental terms were offered by the previous one
+    nft.owner = buyer.clone();
     NFTS.save(deps.storage, id.clone(), &nft)?;
+    RENTAL_OFFERS.remove(deps.storage, id.clone());
 
     // Remove the sale information
-    SALES.remove(deps.storage, id.clone());
+    remove_sale(deps.storage, &id)?;
 
     Ok(Response::new()
         .add_attribute("method", "buy_nft")
         .add_attribute("nft_id", id)
-        .add_attribute("buyer", info.sender.to_string())
+        .add_attribute("buyer", buyer.to_string())
+        .add_attribute("denom", sale_info.denom)
+        .add_attribute("seller_proceeds", seller_payment.to_string())
+        .add_attribute("royalty", quote.royalty.to_string())
+        .add_attribute("fees_deducted", quote.fees_deducted.to_string())
         .add_messages(messages))
 }
 
+/// Splits the price of a sale into royalty and seller proceeds net of smart FT fees
+fn quote_sale(deps: Deps<CoreumQueries>, sale_info: &SaleInfo, seller: &Addr) -> StdResult<SaleQuoteResponse> {
+    let fees = smart_ft_fees(deps, &sale_info.denom)?;
 
-/// Rent an NFT for a specified duration
-fn rent_nft(
+    let royalty_gross = match sale_info.royalty {
+        Some(royalty) => sale_info.price.multiply_ratio(royalty, 100u128),
+        None => Uint128::zero(),
+    };
+    let seller_gross = sale_info.price.checked_sub(royalty_gross)?;
+    let royalty = net_of_fees(royalty_gross, &fees, seller);
+    let seller_net = net_of_fees(seller_gross, &fees, seller);
+
+    // the buyer pays the fees on top of the price when sending it to the contract
+    let buyer_fees = match &fees {
+        Some(fees) => fee_on(sale_info.price, fees.burn_rate) + fee_on(sale_info.price, fees.send_commission_rate),
+        None => Uint128::zero(),
+    };
+
+    Ok(SaleQuoteResponse {
+        denom: sale_info.denom.clone(),
+        price: sale_info.price,
+        buyer_cost: sale_info.price + buyer_fees,
+        royalty,
+        seller_net,
+        fees_deducted: sale_info.price - royalty - seller_net,
+    })
+}
+
+/// Burn rate, send commission and issuer of a smart FT, none for other denoms
+struct SmartFtFees {
+    issuer: Addr,
+    burn_rate: Decimal,
+    send_commission_rate: Decimal,
+}
+
+fn smart_ft_fees(deps: Deps<CoreumQueries>, denom: &str) -> StdResult<Option<SmartFtFees>> {
+    match SMART_FT.may_load(deps.storage)? {
+        Some(smart_ft) if smart_ft.denom == denom => {
+            let request = CoreumQueries::AssetFT(assetft::Query::Token { denom: smart_ft.denom }).into();
+            let res: assetft::TokenResponse = deps.querier.query(&request)?;
+            Ok(Some(SmartFtFees {
+                issuer: Addr::unchecked(res.token.issuer),
+                burn_rate: Decimal::from_str(&res.token.burn_rate)?,
+                send_commission_rate: Decimal::from_str(&res.token.send_commission_rate)?,
+            }))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Largest amount that can be sent out of `gross` once the smart FT fees, which Coreum
+/// rounds up and charges to the sender, are paid from it as well. Transfers to the issuer are exempt.
+fn net_of_fees(gross: Uint128, fees: &Option<SmartFtFees>, recipient: &Addr) -> Uint128 {
+    let fees = match fees {
+        Some(fees) if &fees.issuer != recipient => fees,
+        _ => return gross,
+    };
+    let rate = Decimal::one() + fees.burn_rate + fees.send_commission_rate;
+    let net = gross.multiply_ratio(Decimal::one().atomics(), rate.atomics());
+    // Rounding each fee up can overshoot `gross` by a unit or two. Every unit taken off `net`
+    // lowers the cost by at least one, so taking off the overshoot is enough
+    let cost = net + fee_on(net, fees.burn_rate) + fee_on(net, fees.send_commission_rate);
+    net - cost.saturating_sub(gross)
+}
+
+/// Fee charged on `amount` at `rate`, rounded up. Coreum caps the rates at one, so
+/// ceil(amount * rate) = amount - floor(amount * (1 - rate))
+fn fee_on(amount: Uint128, rate: Decimal) -> Uint128 {
+    amount - amount * (Decimal::one() - rate)
+}
+
+
+/// Offer an NFT for rent with a per second price and a collateral
+fn offer_rental(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    id: String,
+    price_per_second: Uint128,
+    collateral: Uint128,
+    max_duration: u64,
+    denom: Option<String>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let nft = NFTS.load(deps.storage, id.clone())?;
+    if nft.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if max_duration == 0 {
+        return Err(ContractError::InvalidDuration {});
+    }
+    let denom = listing_denom(deps.storage, denom)?;
+
+    let offer = RentalOffer {
+        denom: denom.clone(),
+        price_per_second,
+        collateral,
+        max_duration,
+    };
+    RENTAL_OFFERS.save(deps.storage, id.clone(), &offer)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "offer_rental")
+        .add_attribute("nft_id", id)
+        .add_attribute("price_per_second", price_per_second.to_string())
+        .add_attribute("collateral", collateral.to_string())
+        .add_attribute("denom", denom))
+}
+
+/// Stop offering an NFT for rent, a running rental is not affected
+fn cancel_rental_offer(
     deps: DepsMut<CoreumQueries>,
     info: MessageInfo,
     id: String,
-    duration: u64,
 ) -> Result<Response<CoreumMsg>, ContractError> {
     let nft = NFTS.load(deps.storage, id.clone())?;
     if nft.owner != info.sender {
         return Err(ContractError::Unauthorized {});
     }
+    if !RENTAL_OFFERS.has(deps.storage, id.clone()) {
+        return Err(ContractError::RentalNotOffered {});
+    }
+    RENTAL_OFFERS.remove(deps.storage, id.clone());
+
+    Ok(Response::new()
+        .add_attribute("method", "cancel_rental_offer")
+        .add_attribute("nft_id", id))
+}
+
+/// Rent an NFT for a specified duration, depositing the fee and the collateral
+fn rent_nft(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    duration: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let offer = RENTAL_OFFERS.may_load(deps.storage, id.clone())?
+        .ok_or(ContractError::RentalNotOffered {})?;
+    if RENTALS.has(deps.storage, id.clone()) {
+        return Err(ContractError::AlreadyRented {});
+    }
+    if duration == 0 || duration > offer.max_duration {
+        return Err(ContractError::InvalidDuration {});
+    }
+    let nft = NFTS.load(deps.storage, id.clone())?;
+
+    // Ensure the renter has sent exactly the fee and the collateral, anything else would be
+    // stuck in the contract
+    let fee = offer.price_per_second.checked_mul(Uint128::from(duration))
+        .map_err(|_| ContractError::Overflow {})?;
+    let deposit = fee.checked_add(offer.collateral).map_err(|_| ContractError::Overflow {})?;
+    if info.funds != [Coin { denom: offer.denom.clone(), amount: deposit }] {
+        return Err(ContractError::InvalidDeposit {});
+    }
+
+    let start = env.block.time.seconds();
     RENTALS.save(deps.storage, id.clone(), &(info.sender.clone(), duration))?;
+    RENTAL_DEPOSITS.save(deps.storage, id.clone(), &RentalDeposit {
+        renter: info.sender.clone(),
+        owner: nft.owner,
+        denom: offer.denom,
+        fee,
+        collateral: offer.collateral,
+        start,
+        expires_at: start + duration,
+        status: CollateralStatus::Held,
+        refunded: Uint128::zero(),
+    })?;
+
     Ok(Response::new()
         .add_attribute("method", "rent_nft")
         .add_attribute("nft_id", id)
         .add_attribute("renter", info.sender.to_string())
-        .add_attribute("duration", duration.to_string()))
+        .add_attribute("duration", duration.to_string())
+        .add_attribute("fee", fee.to_string())
+        .add_attribute("collateral", offer.collateral.to_string()))
 }
 
 /// Return a rented NFT
 fn return_nft(
     deps: DepsMut<CoreumQueries>,
+    env: Env,
     info: MessageInfo,
     id: String,
 ) -> Result<Response<CoreumMsg>, ContractError> {
@@ -193,9 +493,90 @@ fn return_nft(
         return Err(ContractError::Unauthorized {});
     }
     RENTALS.remove(deps.storage, id.clone());
-    Ok(Response::new()
+
+    let mut res = Response::new()
         .add_attribute("method", "return_nft")
-        .add_attribute("nft_id", id))
+        .add_attribute("nft_id", id.clone());
+    // rentals started before deposits were taken have nothing to settle
+    if let Some(mut deposit) = RENTAL_DEPOSITS.may_load(deps.storage, id.clone())? {
+        let messages = settle_deposit(deps.as_ref(), &env, &mut deposit)?;
+        RENTAL_DEPOSITS.save(deps.storage, id, &deposit)?;
+        res = res
+            .add_attribute("collateral_status", collateral_status(&deposit.status))
+            .add_attribute("refunded", deposit.refunded.to_string())
+            .add_messages(messages);
+    }
+    Ok(res)
+}
+
+/// Settle a rental that wasn't returned by expiry, slashing the collateral to the owner
+fn settle_rental(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    id: String,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut deposit = match RENTAL_DEPOSITS.may_load(deps.storage, id.clone())? {
+        Some(deposit) if deposit.status == CollateralStatus::Held => deposit,
+        _ => return Err(ContractError::InvalidNFT {}),
+    };
+    if env.block.time.seconds() < deposit.expires_at {
+        return Err(ContractError::RentalNotExpired {});
+    }
+    RENTALS.remove(deps.storage, id.clone());
+
+    let messages = settle_deposit(deps.as_ref(), &env, &mut deposit)?;
+    RENTAL_DEPOSITS.save(deps.storage, id.clone(), &deposit)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "settle_rental")
+        .add_attribute("nft_id", id)
+        .add_attribute("collateral_status", collateral_status(&deposit.status))
+        .add_messages(messages))
+}
+
+/// Pays out a rental deposit. Before expiry the owner gets the fee for the time used and the
+/// renter the rest of the fee plus the collateral, afterwards the owner gets everything
+fn settle_deposit(
+    deps: Deps<CoreumQueries>,
+    env: &Env,
+    deposit: &mut RentalDeposit,
+) -> StdResult<Vec<CosmosMsg<CoreumMsg>>> {
+    let now = env.block.time.seconds();
+    let to_renter = if now < deposit.expires_at {
+        deposit.status = CollateralStatus::Refunded;
+        let unused = deposit.fee.multiply_ratio(deposit.expires_at - now, deposit.expires_at - deposit.start);
+        unused + deposit.collateral
+    } else {
+        deposit.status = CollateralStatus::Slashed;
+        Uint128::zero()
+    };
+    let to_owner = deposit.fee + deposit.collateral - to_renter;
+    deposit.refunded = to_renter;
+
+    // smart FT payouts are charged burn rate and send commission like sale proceeds
+    let fees = smart_ft_fees(deps, &deposit.denom)?;
+    let messages = [(&deposit.owner, to_owner), (&deposit.renter, to_renter)]
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(recipient, amount)| {
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![Coin {
+                    denom: deposit.denom.clone(),
+                    amount: net_of_fees(amount, &fees, recipient),
+                }],
+            })
+        })
+        .collect();
+    Ok(messages)
+}
+
+fn collateral_status(status: &CollateralStatus) -> &'static str {
+    match status {
+        CollateralStatus::Held => "held",
+        CollateralStatus::Refunded => "refunded",
+        CollateralStatus::Slashed => "slashed",
+    }
 }
 
 /// Mint a limited edition of an existing NFT
@@ -262,19 +643,92 @@ pub fn query(deps: Deps<CoreumQueries>, _env: Env, msg: QueryMsg) -> StdResult<B
         QueryMsg::GetNFT { id } => to_binary(&query_nft(deps, id)?),
         QueryMsg::GetNFTPrice { id } => to_binary(&query_nft_price(deps, id)?),
         QueryMsg::GetRentalInfo { id } => to_binary(&query_rental_info(deps, id)?),
+        QueryMsg::GetRentalOffer { id } => to_binary(&RENTAL_OFFERS.may_load(deps.storage, id)?),
+        QueryMsg::GetRentalCollateral { id } => to_binary(&RENTAL_DEPOSITS.load(deps.storage, id)?),
+        QueryMsg::GetSmartFt {} => to_binary(&SMART_FT.may_load(deps.storage)?),
+        QueryMsg::QuoteSale { id } => to_binary(&query_quote_sale(deps, id)?),
+        QueryMsg::ListedNFTs { start_after, limit } => {
+            to_binary(&query_listed_nfts(deps, start_after, limit)?)
+        }
+        QueryMsg::PrivateListings { buyer, start_after, limit } => {
+            to_binary(&query_private_listings(deps, buyer, start_after, limit)?)
+        }
+    }
+}
+
+/// Query public listings, private ones are never enumerated here
+fn query_listed_nfts(
+    deps: Deps<CoreumQueries>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let listings = SALES
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| item.as_ref().map_or(true, |(_, sale)| sale.buyer.is_none()))
+        .take(limit)
+        .map(|item| item.map(|(id, sale)| listing(id, sale)))
+        .collect::<StdResult<_>>()?;
+    Ok(ListingsResponse { listings })
+}
+
+/// Query the private listings reserved for a buyer
+fn query_private_listings(
+    deps: Deps<CoreumQueries>,
+    buyer: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let buyer = deps.api.addr_validate(&buyer)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let listings = PRIVATE_LISTINGS
+        .prefix(&buyer)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|id| {
+            let id = id?;
+            let sale = SALES.load(deps.storage, id.clone())?;
+            Ok(listing(id, sale))
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(ListingsResponse { listings })
+}
+
+fn listing(id: String, sale: SaleInfo) -> Listing {
+    Listing {
+        id,
+        price: sale.price,
+        denom: sale.denom,
+        expires_at: sale.expires_at,
     }
 }
 
+/// Load a public listing, private listings are treated as not listed
+fn public_sale(deps: Deps<CoreumQueries>, id: String) -> StdResult<SaleInfo> {
+    match SALES.may_load(deps.storage, id)? {
+        Some(sale) if sale.buyer.is_none() => Ok(sale),
+        _ => Err(StdError::not_found("listing")),
+    }
+}
+
+/// Query what buying a publicly listed NFT costs the buyer and pays the seller
+fn query_quote_sale(deps: Deps<CoreumQueries>, id: String) -> StdResult<SaleQuoteResponse> {
+    let sale_info = public_sale(deps, id.clone())?;
+    let nft = NFTS.load(deps.storage, id)?;
+    quote_sale(deps, &sale_info, &nft.owner)
+}
+
 /// Query information about a specific NFT
 fn query_nft(deps: Deps<CoreumQueries>, id: String) -> StdResult<NFT> {
     let nft = NFTS.load(deps.storage, id)?;
     Ok(nft)
 }
 
-/// Query the price of a specific NFT
+/// Query the price of a publicly listed NFT
 fn query_nft_price(deps: Deps<CoreumQueries>, id: String) -> StdResult<Uint128> {
-    // Placeholder implementation for querying NFT price
-    Ok(Uint128::zero())
+    Ok(public_sale(deps, id)?.price)
 }
 
 /// Query rental information for a specific NFT
@@ -291,10 +745,378 @@ pub enum ContractError {
     InsufficientBalance {},
     Overflow {},
     InvalidNFT {},
+    UnsupportedDenom {},
+    InvalidExpiration {},
+    NotReservedBuyer {},
+    ListingExpired {},
+    RentalNotOffered {},
+    AlreadyRented {},
+    InvalidDuration {},
+    RentalNotExpired {},
+    InvalidDeposit {},
 }
 
 impl From<StdError> for ContractError {
     fn from(err: StdError) -> ContractError {
         ContractError::Std(err)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coreum_test_utils::{mock_coreum_deps, mock_coreum_deps_with_querier, CoreumDeps};
+    use cosmwasm_std::testing::{mock_env, mock_info, MockQuerier};
+    use cosmwasm_std::{coins, from_binary, from_slice, ContractResult, SystemResult};
+
+    const FT: &str = "ufan-fungible";
+
+    fn setup(deps: &mut CoreumDeps, fungible_token: Option<String>) {
+        let msg = InstantiateMsg {
+            owner: "owner".to_string(),
+            marketplace: "marketplace".to_string(),
+            fungible_token,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        let msg = ExecuteMsg::CreateNFT { id: "punk".to_string(), metadata: "ipfs://punk".to_string(), royalties: Some(10) };
+        execute(deps.as_mut(), mock_env(), mock_info("seller", &[]), msg).unwrap();
+    }
+
+    /// Marketplace accepting the smart FT of `fungible_token`, issued by `issuer` with the given rates
+    fn smart_ft_deps(issuer: &str, burn_rate: &str, send_commission_rate: &str) -> CoreumDeps {
+        let token = Binary::from(format!(
+            r#"{{"token":{{"denom":"{}","issuer":"{}","symbol":"FAN","subunit":"ufan","precision":6,"burn_rate":"{}","send_commission_rate":"{}"}}}}"#,
+            FT, issuer, burn_rate, send_commission_rate
+        ).into_bytes());
+        let wasm_token = token.clone();
+        let mut querier = MockQuerier::<CoreumQueries>::new(&[])
+            .with_custom_handler(move |_| SystemResult::Ok(ContractResult::Ok(token.clone())));
+        querier.update_wasm(move |_| SystemResult::Ok(ContractResult::Ok(wasm_token.clone())));
+        let mut deps = mock_coreum_deps_with_querier(querier);
+        setup(&mut deps, Some("fungible_token".to_string()));
+        deps
+    }
+
+    fn plain_deps() -> CoreumDeps {
+        let mut deps = mock_coreum_deps();
+        setup(&mut deps, None);
+        deps
+    }
+
+    fn list(deps: &mut CoreumDeps, price: u128, denom: Option<&str>) {
+        let msg = ExecuteMsg::ListForSale { id: "punk".to_string(), price: Uint128::new(price), denom: denom.map(String::from) };
+        execute(deps.as_mut(), mock_env(), mock_info("seller", &[]), msg).unwrap();
+    }
+
+    fn buy(deps: &mut CoreumDeps, buyer: &str, funds: &[Coin]) -> Result<Response<CoreumMsg>, ContractError> {
+        let msg = ExecuteMsg::BuyNFT { id: "punk".to_string(), recipient: None };
+        execute(deps.as_mut(), mock_env(), mock_info(buyer, funds), msg)
+    }
+
+    /// Recipient and amount of each bank send in a response
+    fn payouts(res: &Response<CoreumMsg>) -> Vec<(String, u128)> {
+        res.messages
+            .iter()
+            .map(|sub| match &sub.msg {
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => (to_address.clone(), amount[0].amount.u128()),
+                msg => panic!("unexpected message {:?}", msg),
+            })
+            .collect()
+    }
+
+    fn fees(burn_rate: u64, send_commission_rate: u64) -> Option<SmartFtFees> {
+        Some(SmartFtFees {
+            issuer: Addr::unchecked("issuer"),
+            burn_rate: Decimal::percent(burn_rate),
+            send_commission_rate: Decimal::percent(send_commission_rate),
+        })
+    }
+
+    #[test]
+    fn net_of_fees_is_the_largest_affordable_amount() {
+        let seller = Addr::unchecked("seller");
+        let fees = fees(1, 10);
+        for gross in [0u128, 1, 2, 100, 999, 1000, 1001, 123_457] {
+            let gross = Uint128::new(gross);
+            let net = net_of_fees(gross, &fees, &seller);
+            let cost = |net: Uint128| net + fee_on(net, Decimal::percent(1)) + fee_on(net, Decimal::percent(10));
+            assert!(cost(net) <= gross, "{} does not fit in {}", net, gross);
+            assert!(cost(net + Uint128::one()) > gross, "{} is not the largest amount for {}", net, gross);
+        }
+        assert_eq!(net_of_fees(Uint128::new(1001), &fees, &seller), Uint128::new(900));
+        // transfers to the issuer are exempt
+        assert_eq!(net_of_fees(Uint128::new(1001), &fees, &Addr::unchecked("issuer")), Uint128::new(1001));
+        assert_eq!(net_of_fees(Uint128::new(1001), &None, &seller), Uint128::new(1001));
+    }
+
+    #[test]
+    fn fee_on_rounds_up() {
+        assert_eq!(fee_on(Uint128::new(901), Decimal::percent(1)), Uint128::new(10));
+        assert_eq!(fee_on(Uint128::new(900), Decimal::percent(1)), Uint128::new(9));
+        assert_eq!(fee_on(Uint128::new(900), Decimal::zero()), Uint128::zero());
+    }
+
+    #[test]
+    fn buy_pays_royalty_and_seller_in_plain_denom() {
+        let mut deps = plain_deps();
+        list(&mut deps, 1000, None);
+
+        let err = buy(&mut deps, "buyer", &coins(999, NATIVE_DENOM)).unwrap_err();
+        assert_eq!(err, ContractError::InsufficientBalance {});
+        let res = buy(&mut deps, "buyer", &coins(1000, NATIVE_DENOM)).unwrap();
+        assert_eq!(payouts(&res), vec![("seller".to_string(), 100), ("seller".to_string(), 900)]);
+        assert_eq!(NFTS.load(&deps.storage, "punk".to_string()).unwrap().owner, "buyer");
+        assert!(!SALES.has(&deps.storage, "punk".to_string()));
+    }
+
+    #[test]
+    fn buy_pays_out_smart_ft_net_of_fees() {
+        let mut deps = smart_ft_deps("fungible_token", "0.01", "0.1");
+        list(&mut deps, 1000, Some(FT));
+
+        let res = buy(&mut deps, "buyer", &coins(1000, FT)).unwrap();
+        // the payouts plus the fees charged on them stay within the price
+        assert_eq!(payouts(&res), vec![("seller".to_string(), 90), ("seller".to_string(), 810)]);
+        assert_eq!(res.attributes[6], ("fees_deducted", "100"));
+    }
+
+    #[test]
+    fn buy_pays_the_issuer_without_fees() {
+        let mut deps = smart_ft_deps("seller", "0.01", "0.1");
+        list(&mut deps, 1000, Some(FT));
+
+        let res = buy(&mut deps, "buyer", &coins(1000, FT)).unwrap();
+        assert_eq!(payouts(&res), vec![("seller".to_string(), 100), ("seller".to_string(), 900)]);
+    }
+
+    fn quote(deps: &CoreumDeps) -> SaleQuoteResponse {
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::QuoteSale { id: "punk".to_string() }).unwrap();
+        from_binary(&res).unwrap()
+    }
+
+    #[test]
+    fn quote_sale_in_plain_denom_has_no_fees() {
+        let mut deps = plain_deps();
+        list(&mut deps, 1000, None);
+
+        assert_eq!(quote(&deps), SaleQuoteResponse {
+            denom: NATIVE_DENOM.to_string(),
+            price: Uint128::new(1000),
+            buyer_cost: Uint128::new(1000),
+            royalty: Uint128::new(100),
+            seller_net: Uint128::new(900),
+            fees_deducted: Uint128::zero(),
+        });
+    }
+
+    #[test]
+    fn quote_sale_in_smart_ft_discloses_fees() {
+        let mut deps = smart_ft_deps("fungible_token", "0.01", "0.1");
+        list(&mut deps, 1000, Some(FT));
+
+        let quote = quote(&deps);
+        assert_eq!(quote, SaleQuoteResponse {
+            denom: FT.to_string(),
+            price: Uint128::new(1000),
+            // 1% burn and 10% commission on top of the price
+            buyer_cost: Uint128::new(1110),
+            royalty: Uint128::new(90),
+            seller_net: Uint128::new(810),
+            fees_deducted: Uint128::new(100),
+        });
+        // the sale pays out exactly what was quoted
+        let res = buy(&mut deps, "buyer", &coins(1000, FT)).unwrap();
+        assert_eq!(payouts(&res), vec![
+            ("seller".to_string(), quote.royalty.u128()),
+            ("seller".to_string(), quote.seller_net.u128()),
+        ]);
+    }
+
+    fn list_private(deps: &mut CoreumDeps, id: &str, buyer: &str) {
+        let msg = ExecuteMsg::CreateNFT { id: id.to_string(), metadata: String::new(), royalties: None };
+        execute(deps.as_mut(), mock_env(), mock_info("seller", &[]), msg).unwrap();
+        let msg = ExecuteMsg::ListPrivate {
+            id: id.to_string(),
+            price: Uint128::new(500),
+            buyer: buyer.to_string(),
+            denom: None,
+            expires_at: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("seller", &[]), msg).unwrap();
+    }
+
+    fn private_listings(deps: &CoreumDeps, buyer: &str, start_after: Option<&str>, limit: u32) -> Vec<String> {
+        let msg = QueryMsg::PrivateListings { buyer: buyer.to_string(), start_after: start_after.map(String::from), limit: Some(limit) };
+        let res: ListingsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        res.listings.into_iter().map(|listing| listing.id).collect()
+    }
+
+    #[test]
+    fn private_listings_are_paged_per_buyer() {
+        let mut deps = plain_deps();
+        for id in ["a", "b", "c"] {
+            list_private(&mut deps, id, "alice");
+        }
+        list_private(&mut deps, "d", "bob");
+
+        assert_eq!(private_listings(&deps, "alice", None, 2), vec!["a", "b"]);
+        assert_eq!(private_listings(&deps, "alice", Some("b"), 2), vec!["c"]);
+        assert_eq!(private_listings(&deps, "bob", None, 10), vec!["d"]);
+
+        // relisting publicly, cancelling and buying drop the listing from the index
+        let msg = ExecuteMsg::ListForSale { id: "a".to_string(), price: Uint128::new(500), denom: None };
+        execute(deps.as_mut(), mock_env(), mock_info("seller", &[]), msg).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("seller", &[]), ExecuteMsg::CancelListing { id: "b".to_string() }).unwrap();
+        let msg = ExecuteMsg::BuyNFT { id: "c".to_string(), recipient: None };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &coins(500, NATIVE_DENOM)), msg).unwrap();
+        assert!(private_listings(&deps, "alice", None, 10).is_empty());
+    }
+
+    #[test]
+    fn private_listings_are_hidden_from_public_queries() {
+        let mut deps = plain_deps();
+        list_private(&mut deps, "secret", "alice");
+        list(&mut deps, 1000, None);
+
+        for msg in [QueryMsg::GetNFTPrice { id: "secret".to_string() }, QueryMsg::QuoteSale { id: "secret".to_string() }] {
+            assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+        }
+        let res: ListingsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::ListedNFTs { start_after: None, limit: None }).unwrap()).unwrap();
+        assert_eq!(res.listings.len(), 1);
+        let price: Uint128 = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetNFTPrice { id: "punk".to_string() }).unwrap()).unwrap();
+        assert_eq!(price, Uint128::new(1000));
+
+        // only the reserved buyer can buy
+        let msg = ExecuteMsg::BuyNFT { id: "secret".to_string(), recipient: None };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("bob", &coins(500, NATIVE_DENOM)), msg).unwrap_err();
+        assert_eq!(err, ContractError::NotReservedBuyer {});
+    }
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = cosmwasm_std::Timestamp::from_seconds(seconds);
+        env
+    }
+
+    /// Offers the NFT at 2 per second with a 500 collateral and rents it to the renter for 100 seconds from t=1000
+    fn rent(deps: &mut CoreumDeps, denom: Option<&str>) {
+        let msg = ExecuteMsg::OfferRental {
+            id: "punk".to_string(),
+            price_per_second: Uint128::new(2),
+            collateral: Uint128::new(500),
+            max_duration: 1000,
+            denom: denom.map(String::from),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("seller", &[]), msg).unwrap();
+        let denom = denom.unwrap_or(NATIVE_DENOM);
+        let msg = ExecuteMsg::RentNFT { id: "punk".to_string(), duration: 100 };
+        execute(deps.as_mut(), env_at(1000), mock_info("renter", &coins(700, denom)), msg).unwrap();
+    }
+
+    fn collateral(deps: &CoreumDeps) -> RentalDeposit {
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetRentalCollateral { id: "punk".to_string() }).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn rent_requires_the_exact_deposit() {
+        let mut deps = plain_deps();
+        let msg = ExecuteMsg::OfferRental {
+            id: "punk".to_string(),
+            price_per_second: Uint128::new(2),
+            collateral: Uint128::new(500),
+            max_duration: 1000,
+            denom: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("seller", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::RentNFT { id: "punk".to_string(), duration: 100 };
+        for funds in [coins(699, NATIVE_DENOM), coins(701, NATIVE_DENOM), vec![Coin::new(700, NATIVE_DENOM), Coin::new(1, FT)]] {
+            let err = execute(deps.as_mut(), mock_env(), mock_info("renter", &funds), msg.clone()).unwrap_err();
+            assert_eq!(err, ContractError::InvalidDeposit {});
+        }
+        execute(deps.as_mut(), mock_env(), mock_info("renter", &coins(700, NATIVE_DENOM)), msg.clone()).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("other", &coins(700, NATIVE_DENOM)), msg).unwrap_err();
+        assert_eq!(err, ContractError::AlreadyRented {});
+    }
+
+    #[test]
+    fn early_return_refunds_the_unused_fee_pro_rata() {
+        let mut deps = plain_deps();
+        rent(&mut deps, None);
+
+        let msg = ExecuteMsg::ReturnNFT { id: "punk".to_string() };
+        let err = execute(deps.as_mut(), env_at(1030), mock_info("seller", &[]), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let res = execute(deps.as_mut(), env_at(1030), mock_info("renter", &[]), msg).unwrap();
+        // 30 of 100 seconds used: 60 of the 200 fee to the owner, 140 and the collateral back
+        assert_eq!(payouts(&res), vec![("seller".to_string(), 60), ("renter".to_string(), 640)]);
+        let deposit = collateral(&deps);
+        assert_eq!(deposit.status, CollateralStatus::Refunded);
+        assert_eq!(deposit.refunded, Uint128::new(640));
+    }
+
+    #[test]
+    fn overdue_rental_slashes_the_collateral() {
+        let mut deps = plain_deps();
+        rent(&mut deps, None);
+
+        let msg = ExecuteMsg::SettleRental { id: "punk".to_string() };
+        let err = execute(deps.as_mut(), env_at(1099), mock_info("anyone", &[]), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::RentalNotExpired {});
+        let res = execute(deps.as_mut(), env_at(1100), mock_info("anyone", &[]), msg.clone()).unwrap();
+        assert_eq!(payouts(&res), vec![("seller".to_string(), 700)]);
+        assert_eq!(collateral(&deps).status, CollateralStatus::Slashed);
+
+        // settled once, and the renter can no longer return it
+        let err = execute(deps.as_mut(), env_at(1200), mock_info("anyone", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidNFT {});
+        let msg = ExecuteMsg::ReturnNFT { id: "punk".to_string() };
+        assert!(execute(deps.as_mut(), env_at(1200), mock_info("renter", &[]), msg).is_err());
+    }
+
+    #[test]
+    fn smart_ft_rental_payouts_are_net_of_fees() {
+        let mut deps = smart_ft_deps("fungible_token", "0.01", "0.1");
+        rent(&mut deps, Some(FT));
+
+        let msg = ExecuteMsg::ReturnNFT { id: "punk".to_string() };
+        let res = execute(deps.as_mut(), env_at(1030), mock_info("renter", &[]), msg).unwrap();
+        assert_eq!(payouts(&res), vec![("seller".to_string(), 53), ("renter".to_string(), 576)]);
+    }
+
+    #[test]
+    fn selling_an_nft_withdraws_its_rental_offer() {
+        let mut deps = plain_deps();
+        let msg = ExecuteMsg::OfferRental {
+            id: "punk".to_string(),
+            price_per_second: Uint128::new(2),
+            collateral: Uint128::new(500),
+            max_duration: 1000,
+            denom: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("seller", &[]), msg).unwrap();
+        list(&mut deps, 1000, None);
+        buy(&mut deps, "buyer", &coins(1000, NATIVE_DENOM)).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetRentalOffer { id: "punk".to_string() }).unwrap();
+        assert_eq!(from_binary::<Option<RentalOffer>>(&res).unwrap(), None);
+        let msg = ExecuteMsg::RentNFT { id: "punk".to_string(), duration: 100 };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("renter", &coins(700, NATIVE_DENOM)), msg).unwrap_err();
+        assert_eq!(err, ContractError::RentalNotOffered {});
+    }
+
+    #[test]
+    fn only_the_native_denom_and_the_smart_ft_are_listed() {
+        let mut deps = plain_deps();
+        let msg = ExecuteMsg::ListForSale { id: "punk".to_string(), price: Uint128::new(1000), denom: Some(FT.to_string()) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("seller", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::UnsupportedDenom {});
+    }
+
+    #[test]
+    fn listings_without_denom_are_native() {
+        let sale: SaleInfo = from_slice(br#"{"price":"1000","royalty":null}"#).unwrap();
+        assert_eq!(sale.denom, NATIVE_DENOM);
+        let res = query(plain_deps().as_ref(), mock_env(), QueryMsg::GetSmartFt {}).unwrap();
+        assert_eq!(from_binary::<Option<SmartFt>>(&res).unwrap(), None);
+    }
+}
@@ -1,21 +1,50 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Uint128, Addr};
 
-use crate::state::NFT;
+use crate::state::{RentalDeposit, RentalOffer, SmartFt, NFT};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub owner: String,
     pub marketplace: String,
+    /// fungibleToken contract whose smart FT is accepted as payment
+    pub fungible_token: Option<String>,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
     CreateNFT { id: String, metadata: String, royalties: Option<u64> },
-    ListForSale { id: String, price: Uint128 },
-    BuyNFT { id: String },
+    ListForSale { id: String, price: Uint128, denom: Option<String> },
+    /// Lists an NFT only `buyer` can purchase, until `expires_at` (block time in seconds) if set
+    ListPrivate {
+        id: String,
+        price: Uint128,
+        buyer: String,
+        denom: Option<String>,
+        expires_at: Option<u64>,
+    },
+    /// Removes a public or private listing, seller only
+    CancelListing { id: String },
+    /// Pays from the sender and hands the NFT to `recipient` when set, e.g. by a checkout router.
+    /// Private listings are still reserved for the sender
+    BuyNFT { id: String, recipient: Option<String> },
+    /// Offers an NFT for rent, owner only. Renters pay `price_per_second` for the duration
+    /// they rent for and lock `collateral` until they return it
+    OfferRental {
+        id: String,
+        price_per_second: Uint128,
+        collateral: Uint128,
+        max_duration: u64,
+        denom: Option<String>,
+    },
+    CancelRentalOffer { id: String },
+    /// Rents an offered NFT, the fee for the whole duration and the collateral are sent along
     RentNFT { id: String, duration: u64 },
+    /// Ends a rental, renter only. Before expiry the collateral and the fee for the unused time
+    /// are refunded, afterwards the collateral is slashed to the owner
     ReturnNFT { id: String },
+    /// Slashes the collateral of a rental that wasn't returned by expiry to the owner
+    SettleRental { id: String },
     MintEdition { id: String, edition: u32 },
     UpdateNFT { id: String, new_metadata: String },
     WithdrawFunds {},
@@ -30,4 +59,51 @@ pub enum QueryMsg {
     GetNFTPrice { id: String },
     #[returns((Addr, u64))]
     GetRentalInfo { id: String },
+    #[returns(Option<RentalOffer>)]
+    GetRentalOffer { id: String },
+    /// Deposit and collateral status of the latest rental of an NFT
+    #[returns(RentalDeposit)]
+    GetRentalCollateral { id: String },
+    #[returns(Option<SmartFt>)]
+    GetSmartFt {},
+    #[returns(SaleQuoteResponse)]
+    QuoteSale { id: String },
+    /// Public listings only
+    #[returns(ListingsResponse)]
+    ListedNFTs { start_after: Option<String>, limit: Option<u32> },
+    /// Private listings reserved for `buyer`
+    #[returns(ListingsResponse)]
+    PrivateListings { buyer: String, start_after: Option<String>, limit: Option<u32> },
+}
+
+#[cw_serde]
+pub struct Listing {
+    pub id: String,
+    pub price: Uint128,
+    pub denom: String,
+    pub expires_at: Option<u64>,
+}
+
+#[cw_serde]
+pub struct ListingsResponse {
+    pub listings: Vec<Listing>,
+}
+
+/// What buying a listed NFT costs and pays out once smart FT fees are applied
+#[cw_serde]
+pub struct SaleQuoteResponse {
+    pub denom: String,
+    pub price: Uint128,
+    /// price plus the burn rate and send commission the buyer is charged on top when paying it
+    pub buyer_cost: Uint128,
+    pub royalty: Uint128,
+    pub seller_net: Uint128,
+    /// taken out of the price by the fees on the payouts
+    pub fees_deducted: Uint128,
+}
+
+/// Query interface of the fungibleToken contract used to resolve its denom
+#[cw_serde]
+pub enum FungibleTokenQueryMsg {
+    Token {},
 }
\ No newline at end of file
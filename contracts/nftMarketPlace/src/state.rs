@@ -1,5 +1,5 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Empty, Uint128};
 use cw_storage_plus::{Item, Map};
 
 #[cw_serde]
@@ -10,6 +10,15 @@ pub struct State {
 
 pub const STATE: Item<State> = Item::new("state");
 
+/// Smart FT issued by the fungibleToken contract that listings can be priced in
+#[cw_serde]
+pub struct SmartFt {
+    pub contract: Addr,
+    pub denom: String,
+}
+
+pub const SMART_FT: Item<SmartFt> = Item::new("smart_ft");
+
 #[cw_serde]
 pub struct NFT {
     pub id: String,
@@ -18,13 +27,70 @@ pub struct NFT {
     pub royalties: Option<u64>,
 }
 
+/// Denom listings are priced in unless another one is requested
+pub const NATIVE_DENOM: &str = "uscrt";
+
+fn native_denom() -> String {
+    NATIVE_DENOM.to_string()
+}
+
 #[cw_serde]
 pub struct SaleInfo {
     pub price: Uint128,
+    /// Listings made before smart FT payments were accepted are priced in the native denom
+    #[serde(default = "native_denom")]
+    pub denom: String,
     pub royalty: Option<u64>,
+    /// Only buyer of a private listing, private listings are left out of public enumeration
+    #[serde(default)]
+    pub buyer: Option<Addr>,
+    /// Block time in seconds after which the listing can no longer be bought
+    #[serde(default)]
+    pub expires_at: Option<u64>,
 }
 
 pub const SALES: Map<String, SaleInfo> = Map::new("sales");
+/// Ids of the private listings reserved for each buyer
+pub const PRIVATE_LISTINGS: Map<(&Addr, &str), Empty> = Map::new("private_listings");
 pub const NFTS: Map<String, NFT> = Map::new("nfts");
 pub const EDITIONS: Map<String, u32> = Map::new("editions");
-pub const RENTALS: Map<String, (Addr, u64)> = Map::new("rentals");
\ No newline at end of file
+/// Renter and duration in seconds of each active rental
+pub const RENTALS: Map<String, (Addr, u64)> = Map::new("rentals");
+
+/// Terms an owner offers an NFT for rent on
+#[cw_serde]
+pub struct RentalOffer {
+    pub denom: String,
+    pub price_per_second: Uint128,
+    /// Locked by the renter and slashed to the owner if the NFT isn't returned by expiry
+    pub collateral: Uint128,
+    pub max_duration: u64,
+}
+
+#[cw_serde]
+pub enum CollateralStatus {
+    Held,
+    Refunded,
+    Slashed,
+}
+
+/// Fee and collateral a renter deposited, held until the rental is returned or settled
+#[cw_serde]
+pub struct RentalDeposit {
+    pub renter: Addr,
+    pub owner: Addr,
+    pub denom: String,
+    /// Fee for the whole duration, the unused part is refunded on early return
+    pub fee: Uint128,
+    pub collateral: Uint128,
+    /// Block time in seconds the rental started and expires at
+    pub start: u64,
+    pub expires_at: u64,
+    pub status: CollateralStatus,
+    /// Sent back to the renter once settled
+    pub refunded: Uint128,
+}
+
+pub const RENTAL_OFFERS: Map<String, RentalOffer> = Map::new("rental_offers");
+/// Deposit of the latest rental of each NFT, kept after settlement for its status
+pub const RENTAL_DEPOSITS: Map<String, RentalDeposit> = Map::new("rental_deposits");
\ No newline at end of file
@@ -0,0 +1,818 @@
+use coreum_wasm_sdk::core::{CoreumMsg, CoreumQueries, CoreumResult};
+use coreum_wasm_sdk::nft;
+use cosmwasm_std::{
+    coins, entry_point, to_json_binary, Addr, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo,
+    Order, QueryRequest, Response, StdResult, Uint128,
+};
+use cw2::set_contract_version;
+use cw_ownable::{assert_owner, get_ownership, initialize_owner};
+use cw_storage_plus::Bound;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ClassResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, PendingRewardsResponse, QueryMsg,
+    StakeResponse, StakesResponse,
+};
+use crate::state::{
+    ClassRewards, Config, Reservation, Stake, CLASSES, CONFIG, RESERVATIONS, STAKERS, STAKES,
+    UNCLAIMED,
+};
+
+// version info for migration info
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+// NFTs settled by a single claim
+const MAX_CLAIM_LIMIT: u32 = 100;
+
+// ********** Instantiate **********
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> CoreumResult<ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    initialize_owner(deps.storage, deps.api, Some(info.sender.as_ref()))?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            reward_denom: msg.reward_denom,
+            unstake_cooldown: msg.unstake_cooldown,
+        },
+    )?;
+    let now = env.block.time.seconds();
+    for class in msg.classes {
+        let rewards = ClassRewards {
+            rate: class.rate,
+            index: Uint128::zero(),
+            last_update: now,
+            staked: 0,
+        };
+        CLASSES.save(deps.storage, &class.class_id, &rewards)?;
+    }
+
+    Ok(Response::new().add_attribute("owner", info.sender))
+}
+
+// ********** Execute **********
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> CoreumResult<ContractError> {
+    match msg {
+        ExecuteMsg::Reserve { class_id, id } => reserve(deps, env, info, class_id, id),
+        ExecuteMsg::Stake { class_id, id } => stake(deps, env, info, class_id, id),
+        ExecuteMsg::Unstake { class_id, id } => unstake(deps, env, info, class_id, id),
+        ExecuteMsg::Withdraw { class_id, id } => withdraw(deps, env, info, class_id, id),
+        ExecuteMsg::Claim { start_after, limit } => claim(deps, env, info, start_after, limit),
+        ExecuteMsg::SetClassRate { class_id, rate } => {
+            set_class_rate(deps, env, info, class_id, rate)
+        }
+    }
+}
+
+// ********** Transactions **********
+
+fn reserve(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    class_id: String,
+    id: String,
+) -> CoreumResult<ContractError> {
+    if !CLASSES.has(deps.storage, &class_id) {
+        return Err(ContractError::UnknownClass { class_id });
+    }
+    if nft_owner(deps.as_ref(), &class_id, &id)? != info.sender.as_str() {
+        return Err(ContractError::NotOwner { class_id, id });
+    }
+    RESERVATIONS.save(
+        deps.storage,
+        (class_id.as_str(), id.as_str()),
+        &Reservation {
+            staker: info.sender.clone(),
+            height: env.block.height,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("method", "reserve")
+        .add_attribute("staker", info.sender)
+        .add_attribute("class_id", class_id)
+        .add_attribute("id", id))
+}
+
+fn stake(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    class_id: String,
+    id: String,
+) -> CoreumResult<ContractError> {
+    let mut class =
+        CLASSES
+            .may_load(deps.storage, &class_id)?
+            .ok_or_else(|| ContractError::UnknownClass {
+                class_id: class_id.clone(),
+            })?;
+    if STAKERS.has(deps.storage, (class_id.as_str(), id.as_str())) {
+        return Err(ContractError::AlreadyStaked { class_id, id });
+    }
+    // only the owner who reserved the NFT before sending it can stake it
+    match RESERVATIONS.may_load(deps.storage, (class_id.as_str(), id.as_str()))? {
+        Some(reservation)
+            if reservation.staker == info.sender && reservation.height == env.block.height => {}
+        _ => return Err(ContractError::NotReserved { class_id, id }),
+    }
+
+    // the sender transferred the NFT right before this message, the contract must own it now
+    if nft_owner(deps.as_ref(), &class_id, &id)? != env.contract.address.as_str() {
+        return Err(ContractError::NotReceived { class_id, id });
+    }
+    RESERVATIONS.remove(deps.storage, (class_id.as_str(), id.as_str()));
+
+    let now = env.block.time.seconds();
+    let stake = Stake {
+        index: class.index_at(now),
+        staked_at: now,
+        withdrawable_at: None,
+    };
+    STAKES.save(
+        deps.storage,
+        (&info.sender, class_id.as_str(), id.as_str()),
+        &stake,
+    )?;
+    STAKERS.save(deps.storage, (class_id.as_str(), id.as_str()), &info.sender)?;
+    class.staked += 1;
+    CLASSES.save(deps.storage, &class_id, &class)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "stake")
+        .add_attribute("staker", info.sender)
+        .add_attribute("class_id", class_id)
+        .add_attribute("id", id))
+}
+
+fn unstake(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    class_id: String,
+    id: String,
+) -> CoreumResult<ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let key = (&info.sender, class_id.as_str(), id.as_str());
+    let mut stake =
+        STAKES
+            .may_load(deps.storage, key)?
+            .ok_or_else(|| ContractError::NotStaked {
+                class_id: class_id.clone(),
+                id: id.clone(),
+            })?;
+    if stake.withdrawable_at.is_some() {
+        return Err(ContractError::AlreadyUnstaking { class_id, id });
+    }
+
+    let now = env.block.time.seconds();
+    let mut class = CLASSES.load(deps.storage, &class_id)?;
+    let index = class.index_at(now);
+    let pending = index - stake.index;
+    stake.index = index;
+    stake.withdrawable_at = Some(now + config.unstake_cooldown);
+    STAKES.save(deps.storage, key, &stake)?;
+    class.staked -= 1;
+    CLASSES.save(deps.storage, &class_id, &class)?;
+    // kept for the next claim so an empty reward pool never locks the NFT in
+    UNCLAIMED.update(deps.storage, &info.sender, |unclaimed| -> StdResult<_> {
+        Ok(unclaimed.unwrap_or_default() + pending)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "unstake")
+        .add_attribute("staker", info.sender)
+        .add_attribute("class_id", class_id)
+        .add_attribute("id", id)
+        .add_attribute(
+            "withdrawable_at",
+            (now + config.unstake_cooldown).to_string(),
+        ))
+}
+
+fn withdraw(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    class_id: String,
+    id: String,
+) -> CoreumResult<ContractError> {
+    let key = (&info.sender, class_id.as_str(), id.as_str());
+    let stake = STAKES
+        .may_load(deps.storage, key)?
+        .ok_or_else(|| ContractError::NotStaked {
+            class_id: class_id.clone(),
+            id: id.clone(),
+        })?;
+    let until = stake
+        .withdrawable_at
+        .ok_or_else(|| ContractError::StillStaked {
+            class_id: class_id.clone(),
+            id: id.clone(),
+        })?;
+    if env.block.time.seconds() < until {
+        return Err(ContractError::CooldownActive { until });
+    }
+    STAKES.remove(deps.storage, key);
+    STAKERS.remove(deps.storage, (class_id.as_str(), id.as_str()));
+
+    Ok(Response::new()
+        .add_message(CoreumMsg::NFT(nft::Msg::Send {
+            class_id: class_id.clone(),
+            id: id.clone(),
+            receiver: info.sender.to_string(),
+        }))
+        .add_attribute("method", "withdraw")
+        .add_attribute("staker", info.sender)
+        .add_attribute("class_id", class_id)
+        .add_attribute("id", id))
+}
+
+fn claim(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> CoreumResult<ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+    let limit = limit.unwrap_or(MAX_CLAIM_LIMIT).min(MAX_CLAIM_LIMIT) as usize;
+    let start = start_after
+        .as_ref()
+        .map(|(class_id, id)| Bound::exclusive((class_id.as_str(), id.as_str())));
+    let stakes = STAKES
+        .prefix(&info.sender)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let last = stakes
+        .last()
+        .map(|((class_id, id), _)| format!("{}/{}", class_id, id))
+        .unwrap_or_default();
+
+    let mut total = UNCLAIMED
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    for ((class_id, id), mut stake) in stakes {
+        if stake.withdrawable_at.is_some() {
+            continue;
+        }
+        let index = CLASSES.load(deps.storage, &class_id)?.index_at(now);
+        total = total.checked_add(index - stake.index)?;
+        stake.index = index;
+        STAKES.save(
+            deps.storage,
+            (&info.sender, class_id.as_str(), id.as_str()),
+            &stake,
+        )?;
+    }
+    if total.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    UNCLAIMED.remove(deps.storage, &info.sender);
+
+    Ok(Response::new()
+        .add_message(pay_rewards(
+            deps.as_ref(),
+            &env,
+            &config,
+            &info.sender,
+            total,
+        )?)
+        .add_attribute("method", "claim")
+        .add_attribute("staker", info.sender)
+        .add_attribute("rewards", total)
+        .add_attribute("last", last))
+}
+
+fn set_class_rate(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    class_id: String,
+    rate: Uint128,
+) -> CoreumResult<ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let now = env.block.time.seconds();
+    let class = match CLASSES.may_load(deps.storage, &class_id)? {
+        // settle what staked NFTs earned at the old rate
+        Some(class) => ClassRewards {
+            rate,
+            index: class.index_at(now),
+            last_update: now,
+            staked: class.staked,
+        },
+        None => ClassRewards {
+            rate,
+            index: Uint128::zero(),
+            last_update: now,
+            staked: 0,
+        },
+    };
+    CLASSES.save(deps.storage, &class_id, &class)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_class_rate")
+        .add_attribute("class_id", class_id)
+        .add_attribute("rate", rate))
+}
+
+// ********** Helpers **********
+
+fn nft_owner(deps: Deps<CoreumQueries>, class_id: &str, id: &str) -> StdResult<String> {
+    let request: QueryRequest<CoreumQueries> = CoreumQueries::NFT(nft::Query::Owner {
+        class_id: class_id.to_string(),
+        id: id.to_string(),
+    })
+    .into();
+    let res: nft::OwnerResponse = deps.querier.query(&request)?;
+    Ok(res.owner)
+}
+
+fn pay_rewards(
+    deps: Deps<CoreumQueries>,
+    env: &Env,
+    config: &Config,
+    recipient: &Addr,
+    amount: Uint128,
+) -> Result<BankMsg, ContractError> {
+    let available = deps
+        .querier
+        .query_balance(&env.contract.address, &config.reward_denom)?
+        .amount;
+    if available < amount {
+        return Err(ContractError::InsufficientRewards { available });
+    }
+    Ok(BankMsg::Send {
+        to_address: recipient.to_string(),
+        amount: coins(amount.u128(), &config.reward_denom),
+    })
+}
+
+fn pending(
+    deps: Deps<CoreumQueries>,
+    stake: &Stake,
+    class_id: &str,
+    now: u64,
+) -> StdResult<Uint128> {
+    if stake.withdrawable_at.is_some() {
+        return Ok(Uint128::zero());
+    }
+    Ok(CLASSES.load(deps.storage, class_id)?.index_at(now) - stake.index)
+}
+
+// ********** Queries **********
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps<CoreumQueries>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Class { class_id } => to_json_binary(&query_class(deps, class_id)?),
+        QueryMsg::Stakes {
+            owner,
+            start_after,
+            limit,
+        } => to_json_binary(&query_stakes(deps, env, owner, start_after, limit)?),
+        QueryMsg::PendingRewards { owner } => {
+            to_json_binary(&query_pending_rewards(deps, env, owner)?)
+        }
+    }
+}
+
+fn query_config(deps: Deps<CoreumQueries>) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: get_ownership(deps.storage)?.owner,
+        reward_denom: config.reward_denom,
+        unstake_cooldown: config.unstake_cooldown,
+    })
+}
+
+fn query_class(deps: Deps<CoreumQueries>, class_id: String) -> StdResult<ClassResponse> {
+    let class = CLASSES.load(deps.storage, &class_id)?;
+    Ok(ClassResponse {
+        class_id,
+        rate: class.rate,
+        staked: class.staked,
+    })
+}
+
+fn query_stakes(
+    deps: Deps<CoreumQueries>,
+    env: Env,
+    owner: String,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> StdResult<StakesResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .as_ref()
+        .map(|(class_id, id)| Bound::exclusive((class_id.as_str(), id.as_str())));
+    let now = env.block.time.seconds();
+
+    let stakes = STAKES
+        .prefix(&owner)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| -> StdResult<StakeResponse> {
+            let ((class_id, id), stake) = item?;
+            Ok(StakeResponse {
+                pending: pending(deps, &stake, &class_id, now)?,
+                class_id,
+                id,
+                staked_at: stake.staked_at,
+                withdrawable_at: stake.withdrawable_at,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(StakesResponse { stakes })
+}
+
+fn query_pending_rewards(
+    deps: Deps<CoreumQueries>,
+    env: Env,
+    owner: String,
+) -> StdResult<PendingRewardsResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let now = env.block.time.seconds();
+    let pending = STAKES
+        .prefix(&owner)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| -> StdResult<Uint128> {
+            let ((class_id, _), stake) = item?;
+            pending(deps, &stake, &class_id, now)
+        })
+        .sum::<StdResult<Uint128>>()?;
+    let unclaimed = UNCLAIMED
+        .may_load(deps.storage, &owner)?
+        .unwrap_or_default();
+
+    Ok(PendingRewardsResponse {
+        pending: pending + unclaimed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::ClassRate;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{from_json, ContractResult, CosmosMsg, OwnedDeps, SystemResult, Timestamp};
+    use std::cell::RefCell;
+    use std::marker::PhantomData;
+    use std::rc::Rc;
+
+    const OWNER: &str = "owner";
+    const ALICE: &str = "alice";
+    const BOB: &str = "bob";
+    const CLASS: &str = "punks";
+    const REWARD: &str = "ureward";
+
+    type CoreumDeps = OwnedDeps<MockStorage, MockApi, MockQuerier<CoreumQueries>, CoreumQueries>;
+    /// Ids of the NFTs sent to the contract, all others are owned by alice
+    type Held = Rc<RefCell<Vec<String>>>;
+
+    /// Every transaction of the tests is in its own block
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env.block.height = seconds;
+        env
+    }
+
+    fn mock_coreum_deps(pool: u128) -> (CoreumDeps, Held) {
+        let contract = mock_env().contract.address;
+        let held = Held::default();
+        let nfts = held.clone();
+        let querier = MockQuerier::new(&[(contract.as_str(), coins(pool, REWARD).as_slice())]);
+        let querier = querier.with_custom_handler(move |query| {
+            let owner = match query {
+                CoreumQueries::NFT(nft::Query::Owner { id, .. })
+                    if nfts.borrow().contains(id) =>
+                {
+                    contract.to_string()
+                }
+                _ => ALICE.to_string(),
+            };
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&nft::OwnerResponse { owner }).unwrap(),
+            ))
+        });
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier,
+            custom_query_type: PhantomData,
+        };
+        let msg = InstantiateMsg {
+            reward_denom: REWARD.to_string(),
+            unstake_cooldown: 100,
+            classes: vec![ClassRate {
+                class_id: CLASS.to_string(),
+                rate: Uint128::new(10),
+            }],
+        };
+        instantiate(deps.as_mut(), env_at(0), mock_info(OWNER, &[]), msg).unwrap();
+        (deps, held)
+    }
+
+    fn reserve_at(
+        deps: &mut CoreumDeps,
+        seconds: u64,
+        sender: &str,
+        id: &str,
+    ) -> CoreumResult<ContractError> {
+        let msg = ExecuteMsg::Reserve {
+            class_id: CLASS.to_string(),
+            id: id.to_string(),
+        };
+        execute(deps.as_mut(), env_at(seconds), mock_info(sender, &[]), msg)
+    }
+
+    fn stake_at(
+        deps: &mut CoreumDeps,
+        seconds: u64,
+        sender: &str,
+        id: &str,
+    ) -> CoreumResult<ContractError> {
+        let msg = ExecuteMsg::Stake {
+            class_id: CLASS.to_string(),
+            id: id.to_string(),
+        };
+        execute(deps.as_mut(), env_at(seconds), mock_info(sender, &[]), msg)
+    }
+
+    /// Alice's transaction reserving, sending and staking an NFT
+    fn stake_nft_at(deps: &mut CoreumDeps, held: &Held, seconds: u64, id: &str) {
+        reserve_at(deps, seconds, ALICE, id).unwrap();
+        held.borrow_mut().push(id.to_string());
+        stake_at(deps, seconds, ALICE, id).unwrap();
+    }
+
+    fn claim_msg() -> ExecuteMsg {
+        ExecuteMsg::Claim {
+            start_after: None,
+            limit: None,
+        }
+    }
+
+    fn reward_send(amount: u128) -> CosmosMsg<CoreumMsg> {
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: ALICE.to_string(),
+            amount: coins(amount, REWARD),
+        })
+    }
+
+    #[test]
+    fn staking_requires_transfer_and_known_class() {
+        let (mut deps, held) = mock_coreum_deps(1_000);
+
+        let msg = ExecuteMsg::Reserve {
+            class_id: "apes".to_string(),
+            id: "nft-1".to_string(),
+        };
+        let err = execute(deps.as_mut(), env_at(10), mock_info(ALICE, &[]), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UnknownClass {
+                class_id: "apes".to_string()
+            }
+        );
+        reserve_at(&mut deps, 10, ALICE, "nft-1").unwrap();
+        let err = stake_at(&mut deps, 10, ALICE, "nft-1").unwrap_err();
+        assert!(matches!(err, ContractError::NotReceived { .. }));
+
+        held.borrow_mut().push("nft-1".to_string());
+        stake_at(&mut deps, 10, ALICE, "nft-1").unwrap();
+        let err = stake_at(&mut deps, 10, ALICE, "nft-1").unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyStaked { .. }));
+        assert_eq!(
+            query_class(deps.as_ref(), CLASS.to_string())
+                .unwrap()
+                .staked,
+            1
+        );
+    }
+
+    #[test]
+    fn only_the_reserving_owner_can_stake() {
+        let (mut deps, held) = mock_coreum_deps(1_000);
+
+        // bob can neither reserve alice's NFT nor stake it once she sent it
+        let err = reserve_at(&mut deps, 10, BOB, "nft-1").unwrap_err();
+        assert!(matches!(err, ContractError::NotOwner { .. }));
+        reserve_at(&mut deps, 10, ALICE, "nft-1").unwrap();
+        held.borrow_mut().push("nft-1".to_string());
+        let err = stake_at(&mut deps, 10, BOB, "nft-1").unwrap_err();
+        assert!(matches!(err, ContractError::NotReserved { .. }));
+
+        // a reservation only holds for its own transaction
+        let err = stake_at(&mut deps, 11, ALICE, "nft-1").unwrap_err();
+        assert!(matches!(err, ContractError::NotReserved { .. }));
+        let msg = ExecuteMsg::Stake {
+            class_id: CLASS.to_string(),
+            id: "nft-2".to_string(),
+        };
+        held.borrow_mut().push("nft-2".to_string());
+        let err = execute(deps.as_mut(), env_at(11), mock_info(ALICE, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NotReserved { .. }));
+
+        stake_nft_at(&mut deps, &held, 20, "nft-3");
+        assert!(!RESERVATIONS.has(&deps.storage, (CLASS, "nft-3")));
+        assert_eq!(
+            STAKERS.load(&deps.storage, (CLASS, "nft-3")).unwrap(),
+            Addr::unchecked(ALICE)
+        );
+    }
+
+    #[test]
+    fn claims_are_paged() {
+        let (mut deps, held) = mock_coreum_deps(10_000);
+        stake_nft_at(&mut deps, &held, 0, "nft-1");
+        stake_nft_at(&mut deps, &held, 0, "nft-2");
+
+        let msg = ExecuteMsg::Claim {
+            start_after: None,
+            limit: Some(1),
+        };
+        let res = execute(deps.as_mut(), env_at(10), mock_info(ALICE, &[]), msg).unwrap();
+        assert_eq!(res.messages[0].msg, reward_send(100));
+        let msg = ExecuteMsg::Claim {
+            start_after: Some((CLASS.to_string(), "nft-1".to_string())),
+            limit: Some(1),
+        };
+        let res = execute(deps.as_mut(), env_at(20), mock_info(ALICE, &[]), msg).unwrap();
+        assert_eq!(res.messages[0].msg, reward_send(200));
+
+        let pending = query_pending_rewards(deps.as_ref(), env_at(20), ALICE.to_string()).unwrap();
+        assert_eq!(pending.pending, Uint128::new(100));
+    }
+
+    #[test]
+    fn rewards_follow_rate_changes() {
+        let (mut deps, held) = mock_coreum_deps(10_000);
+        stake_nft_at(&mut deps, &held, 10, "nft-1");
+        stake_nft_at(&mut deps, &held, 20, "nft-2");
+
+        // 10/s over 90s plus 10/s over 80s
+        let pending = query_pending_rewards(deps.as_ref(), env_at(100), ALICE.to_string()).unwrap();
+        assert_eq!(pending.pending, Uint128::new(1_700));
+
+        let msg = ExecuteMsg::SetClassRate {
+            class_id: CLASS.to_string(),
+            rate: Uint128::new(1),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info(ALICE, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Ownership(_)));
+        execute(deps.as_mut(), env_at(100), mock_info(OWNER, &[]), msg).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env_at(150),
+            mock_info(ALICE, &[]),
+            claim_msg(),
+        )
+        .unwrap();
+        assert_eq!(res.messages[0].msg, reward_send(1_700 + 2 * 50));
+        let err = execute(
+            deps.as_mut(),
+            env_at(150),
+            mock_info(ALICE, &[]),
+            claim_msg(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NothingToClaim {});
+    }
+
+    #[test]
+    fn unstake_cooldown_before_withdraw() {
+        let (mut deps, held) = mock_coreum_deps(10_000);
+        stake_nft_at(&mut deps, &held, 0, "nft-1");
+
+        let unstake = ExecuteMsg::Unstake {
+            class_id: CLASS.to_string(),
+            id: "nft-1".to_string(),
+        };
+        let withdraw = ExecuteMsg::Withdraw {
+            class_id: CLASS.to_string(),
+            id: "nft-1".to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(10),
+            mock_info(ALICE, &[]),
+            withdraw.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::StillStaked { .. }));
+
+        execute(
+            deps.as_mut(),
+            env_at(50),
+            mock_info(ALICE, &[]),
+            unstake.clone(),
+        )
+        .unwrap();
+        let err = execute(deps.as_mut(), env_at(60), mock_info(ALICE, &[]), unstake).unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyUnstaking { .. }));
+
+        // nothing accrues during the cooldown
+        let res = query(
+            deps.as_ref(),
+            env_at(120),
+            QueryMsg::Stakes {
+                owner: ALICE.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let stakes: StakesResponse = from_json(res).unwrap();
+        assert_eq!(stakes.stakes[0].withdrawable_at, Some(150));
+        assert_eq!(stakes.stakes[0].pending, Uint128::zero());
+        let pending = query_pending_rewards(deps.as_ref(), env_at(120), ALICE.to_string()).unwrap();
+        assert_eq!(pending.pending, Uint128::new(500));
+
+        let err = execute(
+            deps.as_mut(),
+            env_at(149),
+            mock_info(ALICE, &[]),
+            withdraw.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::CooldownActive { until: 150 });
+        let res = execute(deps.as_mut(), env_at(150), mock_info(ALICE, &[]), withdraw).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(CoreumMsg::NFT(nft::Msg::Send {
+                class_id: CLASS.to_string(),
+                id: "nft-1".to_string(),
+                receiver: ALICE.to_string(),
+            }))
+        );
+        assert_eq!(
+            query_class(deps.as_ref(), CLASS.to_string())
+                .unwrap()
+                .staked,
+            0
+        );
+
+        // rewards earned before unstaking are still claimable
+        let res = execute(
+            deps.as_mut(),
+            env_at(150),
+            mock_info(ALICE, &[]),
+            claim_msg(),
+        )
+        .unwrap();
+        assert_eq!(res.messages[0].msg, reward_send(500));
+    }
+
+    #[test]
+    fn claims_are_capped_by_the_pool() {
+        let (mut deps, held) = mock_coreum_deps(100);
+        stake_nft_at(&mut deps, &held, 0, "nft-1");
+
+        let err = execute(
+            deps.as_mut(),
+            env_at(50),
+            mock_info(ALICE, &[]),
+            claim_msg(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientRewards {
+                available: Uint128::new(100)
+            }
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use cw_ownable::OwnershipError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error(transparent)]
+    Ownership(#[from] OwnershipError),
+
+    #[error("Class {class_id} is not configured for staking")]
+    UnknownClass { class_id: String },
+
+    #[error("NFT {id} of class {class_id} is not owned by the sender")]
+    NotOwner { class_id: String, id: String },
+
+    #[error("NFT {id} of class {class_id} must be reserved by the sender in the same transaction")]
+    NotReserved { class_id: String, id: String },
+
+    #[error("NFT {id} of class {class_id} must be sent to the contract first")]
+    NotReceived { class_id: String, id: String },
+
+    #[error("NFT {id} of class {class_id} is already staked")]
+    AlreadyStaked { class_id: String, id: String },
+
+    #[error("NFT {id} of class {class_id} is not staked by the sender")]
+    NotStaked { class_id: String, id: String },
+
+    #[error("NFT {id} of class {class_id} is already unstaking")]
+    AlreadyUnstaking { class_id: String, id: String },
+
+    #[error("NFT {id} of class {class_id} must be unstaked first")]
+    StillStaked { class_id: String, id: String },
+
+    #[error("NFT can be withdrawn after {until}")]
+    CooldownActive { until: u64 },
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("Only {available} left in the reward pool")]
+    InsufficientRewards { available: Uint128 },
+}
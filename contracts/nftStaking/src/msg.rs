@@ -0,0 +1,89 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Uint128};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Denom rewards are paid in; the pool is funded by sending it to the contract
+    pub reward_denom: String,
+    pub unstake_cooldown: u64,
+    pub classes: Vec<ClassRate>,
+}
+
+#[cw_serde]
+pub struct ClassRate {
+    pub class_id: String,
+    /// Reward per staked NFT and second
+    pub rate: Uint128,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Reserves an NFT the sender owns for staking. Coreum NFTs have no send hook, so a
+    /// transaction stakes an NFT with `Reserve`, the NFT transfer to the contract and `Stake`.
+    Reserve { class_id: String, id: String },
+    /// Stakes an NFT the sender reserved and sent to the contract earlier in the same transaction.
+    Stake { class_id: String, id: String },
+    /// Stops the NFT earning and starts its cooldown, its rewards stay claimable.
+    Unstake { class_id: String, id: String },
+    /// Sends an unstaked NFT back to the sender once the cooldown is over.
+    Withdraw { class_id: String, id: String },
+    /// Pays the rewards of the sender's NFTs after `start_after`, at most `limit` of them, and
+    /// the ones of all NFTs the sender unstaked.
+    Claim {
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+    /// Owner only; adds a class or changes its rate, accrued rewards are kept.
+    SetClassRate { class_id: String, rate: Uint128 },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(ClassResponse)]
+    Class { class_id: String },
+    /// NFTs staked by `owner`, including the ones cooling down
+    #[returns(StakesResponse)]
+    Stakes {
+        owner: String,
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+    #[returns(PendingRewardsResponse)]
+    PendingRewards { owner: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Option<Addr>,
+    pub reward_denom: String,
+    pub unstake_cooldown: u64,
+}
+
+#[cw_serde]
+pub struct ClassResponse {
+    pub class_id: String,
+    pub rate: Uint128,
+    pub staked: u32,
+}
+
+#[cw_serde]
+pub struct StakeResponse {
+    pub class_id: String,
+    pub id: String,
+    pub staked_at: u64,
+    pub withdrawable_at: Option<u64>,
+    pub pending: Uint128,
+}
+
+#[cw_serde]
+pub struct StakesResponse {
+    pub stakes: Vec<StakeResponse>,
+}
+
+#[cw_serde]
+pub struct PendingRewardsResponse {
+    pub pending: Uint128,
+}
@@ -0,0 +1,53 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    pub reward_denom: String,
+    /// Seconds between unstaking an NFT and being able to withdraw it
+    pub unstake_cooldown: u64,
+}
+
+#[cw_serde]
+pub struct ClassRewards {
+    /// Reward paid per staked NFT and second
+    pub rate: Uint128,
+    /// Reward a single NFT accrued from the first stake up to `last_update`
+    pub index: Uint128,
+    pub last_update: u64,
+    pub staked: u32,
+}
+
+impl ClassRewards {
+    pub fn index_at(&self, now: u64) -> Uint128 {
+        self.index + self.rate * Uint128::from(now.saturating_sub(self.last_update))
+    }
+}
+
+#[cw_serde]
+pub struct Stake {
+    /// Class index rewards were last settled at
+    pub index: Uint128,
+    pub staked_at: u64,
+    /// Set on unstake; the NFT stops earning and is withdrawable from then on
+    pub withdrawable_at: Option<u64>,
+}
+
+#[cw_serde]
+pub struct Reservation {
+    pub staker: Addr,
+    /// Only a stake at the same height, so in the same transaction, can use the reservation
+    pub height: u64,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const CLASSES: Map<&str, ClassRewards> = Map::new("classes");
+/// Staked NFTs by owner, class id and NFT id
+pub const STAKES: Map<(&Addr, &str, &str), Stake> = Map::new("stakes");
+/// Staker of every NFT held by the contract, keyed by class id and NFT id
+pub const STAKERS: Map<(&str, &str), Addr> = Map::new("stakers");
+/// Owner of an NFT about to be sent to the contract, keyed by class id and NFT id
+pub const RESERVATIONS: Map<(&str, &str), Reservation> = Map::new("reservations");
+/// Rewards of unstaked NFTs waiting for the next claim
+pub const UNCLAIMED: Map<&Addr, Uint128> = Map::new("unclaimed");
@@ -1,13 +1,14 @@
 use cosmwasm_std::{
-    entry_point, BankMsg, SubMsg, Coin, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128, StdError,
+    entry_point, BankMsg, SubMsg, Coin, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult, Uint128, Uint64, StdError, WasmMsg,
 };
 use cosmwasm_std::to_binary;
 use cw2::set_contract_version;
+use cw_utils::Scheduled;
 use cosmwasm_std::Addr;
 use thiserror::Error;
 use serde::{Deserialize, Serialize};
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Proposal, PROPOSAL_COUNT, PROPOSALS};
+use crate::msg::{ExecuteMsg, InstantiateMsg, OperationStatus, QueryMsg, TimelockExecuteMsg, TimelockQueryMsg};
+use crate::state::{CouncilConfig, Proposal, TimelockConfig, COUNCIL, PROPOSAL_COUNT, PROPOSALS, TIMELOCK};
 
 const CONTRACT_NAME: &str = "workshop-dao";
 const CONTRACT_VERSION: &str = "0.1.0";
@@ -22,6 +23,22 @@ pub enum ContractError {
     InvalidInput(String),
     #[error("Already Executed")]
     AlreadyExecuted {},
+    #[error("Proposal is not scheduled on the timelock")]
+    NotScheduled {},
+    #[error("Proposal was vetoed: {reason}")]
+    Vetoed { reason: String },
+    #[error("Proposal has not passed")]
+    NotPassed {},
+    #[error("Veto period is over")]
+    VetoPeriodExpired {},
+    #[error("Voting is still open")]
+    VotingNotEnded {},
+    #[error("Voting is closed")]
+    VotingEnded {},
+    #[error("Proposal can still be vetoed until {veto_end}")]
+    VetoPeriodActive { veto_end: u64 },
+    #[error("Timelock operation {operation_id} is still scheduled")]
+    StillScheduled { operation_id: u64 },
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -29,10 +46,25 @@ pub fn instantiate(
     deps: DepsMut,
     _env: Env,
     _info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> StdResult<Response> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    if let Some(timelock) = msg.timelock {
+        let config = TimelockConfig {
+            address: deps.api.addr_validate(&timelock.address)?,
+            delay: timelock.delay,
+        };
+        TIMELOCK.save(deps.storage, &config)?;
+    }
+    if let Some(council) = msg.council {
+        let config = CouncilConfig {
+            address: deps.api.addr_validate(&council.address)?,
+            veto_period: council.veto_period,
+        };
+        COUNCIL.save(deps.storage, &config)?;
+    }
+
     // Initialize proposal count with 0
     PROPOSAL_COUNT.save(deps.storage, &0u64)?;
 
@@ -48,8 +80,11 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Propose { title, description, recipient, amount } => execute_propose(deps, env, info, title, description, recipient, amount),
-        ExecuteMsg::Vote { proposal_id, approve } => execute_vote(deps, info, proposal_id, approve),
+        ExecuteMsg::Vote { proposal_id, approve } => execute_vote(deps, env, info, proposal_id, approve),
         ExecuteMsg::Execute { proposal_id } => execute_execute(deps, env, proposal_id),
+        ExecuteMsg::Release { proposal_id } => execute_release(deps, info, proposal_id),
+        ExecuteMsg::Veto { proposal_id, reason } => execute_veto(deps, env, info, proposal_id, reason),
+        ExecuteMsg::Reschedule { proposal_id } => execute_reschedule(deps, env, proposal_id),
     }
 }
 
@@ -80,6 +115,9 @@ fn execute_propose(
         amount: amount.unwrap_or_else(Uint128::zero),
         recipient: recipient.unwrap_or(info.sender.clone()),
         voting_end: env.block.time.seconds() + voting_period,
+        scheduled: false,
+        veto_reason: None,
+        operation_id: None,
     };
 
     PROPOSALS.save(deps.storage, &proposal.id.to_string(), &proposal)?;
@@ -89,11 +127,16 @@ fn execute_propose(
 
 fn execute_vote(
     deps: DepsMut,
-    info: MessageInfo,
+    env: Env,
+    _info: MessageInfo,
     proposal_id: u64,
     approve: bool,
 ) -> Result<Response, ContractError> {
     let mut proposal = PROPOSALS.load(deps.storage, &proposal_id.to_string())?;
+    // the tally is final once voting ends, so the council vetoes the outcome that gets paid
+    if env.block.time.seconds() > proposal.voting_end {
+        return Err(ContractError::VotingEnded {});
+    }
 
     if approve {
         proposal.votes_for += Uint128::new(1);
@@ -108,12 +151,43 @@ fn execute_vote(
 
 fn execute_execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     proposal_id: u64,
 ) -> Result<Response, ContractError> {
     let mut proposal = PROPOSALS.load(deps.storage, &proposal_id.to_string())?;
 
+    if let Some(reason) = proposal.veto_reason {
+        return Err(ContractError::Vetoed { reason });
+    }
+    if proposal.executed || proposal.scheduled {
+        return Err(ContractError::AlreadyExecuted {});
+    }
+    let now = env.block.time.seconds();
+    if now <= proposal.voting_end {
+        return Err(ContractError::VotingNotEnded {});
+    }
+    // nothing leaves the treasury while the council can still veto it
+    if let Some(council) = COUNCIL.may_load(deps.storage)? {
+        let veto_end = proposal.voting_end + council.veto_period;
+        if now <= veto_end {
+            return Err(ContractError::VetoPeriodActive { veto_end });
+        }
+    }
+
     if proposal.votes_for > proposal.votes_against {
+        // with a timelock the payout is released by the timelock calling back after the delay
+        if let Some(timelock) = TIMELOCK.may_load(deps.storage)? {
+            let schedule = schedule_release(&env, &timelock, &proposal)?;
+
+            proposal.scheduled = true;
+            PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
+
+            return Ok(Response::new()
+                .add_submessage(schedule)
+                .add_attribute("method", "execute_execute")
+                .add_attribute("scheduled", "true"));
+        }
+
         let recipient = &proposal.recipient;
         let amount = &proposal.amount;
 
@@ -140,6 +214,171 @@ fn execute_execute(
     Ok(Response::default())
 }
 
+/// Schedules the release of a proposal on the timelock, replying with the operation id
+fn schedule_release(env: &Env, timelock: &TimelockConfig, proposal: &Proposal) -> StdResult<SubMsg> {
+    let release = ExecuteMsg::Release { proposal_id: proposal.id };
+    let schedule = TimelockExecuteMsg::Schedule {
+        target_address: env.contract.address.to_string(),
+        data: to_binary(&release)?,
+        title: proposal.title.clone(),
+        description: format!("Release proposal {}", proposal.id),
+        execution_time: Scheduled::AtTime(env.block.time.plus_seconds(timelock.delay)),
+        executors: None,
+    };
+    let msg = WasmMsg::Execute {
+        contract_addr: timelock.address.to_string(),
+        msg: to_binary(&schedule)?,
+        funds: vec![],
+    };
+    Ok(SubMsg::reply_on_success(msg, proposal.id))
+}
+
+fn execute_reschedule(
+    deps: DepsMut,
+    env: Env,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let mut proposal = PROPOSALS.load(deps.storage, &proposal_id.to_string())?;
+    if let Some(reason) = proposal.veto_reason {
+        return Err(ContractError::Vetoed { reason });
+    }
+    if proposal.executed {
+        return Err(ContractError::AlreadyExecuted {});
+    }
+    let timelock = TIMELOCK.may_load(deps.storage)?.ok_or(ContractError::NotScheduled {})?;
+    if !proposal.scheduled {
+        return Err(ContractError::NotScheduled {});
+    }
+
+    // the timelock removes cancelled operations, so a failing query means there is nothing left
+    // to release the funds. Proposals scheduled before ids were tracked can always be
+    // rescheduled, Release only ever pays out once.
+    if let Some(operation_id) = proposal.operation_id {
+        let status: StdResult<OperationStatus> = deps.querier.query_wasm_smart(
+            timelock.address.to_string(),
+            &TimelockQueryMsg::GetOperationStatus { operation_id: Uint64::new(operation_id) },
+        );
+        if matches!(status, Ok(OperationStatus::Pending) | Ok(OperationStatus::Ready)) {
+            return Err(ContractError::StillScheduled { operation_id });
+        }
+    }
+
+    let schedule = schedule_release(&env, &timelock, &proposal)?;
+    proposal.operation_id = None;
+    PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
+
+    Ok(Response::new()
+        .add_submessage(schedule)
+        .add_attribute("method", "execute_reschedule")
+        .add_attribute("proposal_id", proposal_id.to_string()))
+}
+
+/// Records the timelock operation id of a scheduled proposal, the reply id is the proposal id
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let response = msg.result.into_result().map_err(StdError::generic_err)?;
+    let operation_id = response
+        .events
+        .iter()
+        .filter(|event| event.ty == "wasm-timelock_operation")
+        .flat_map(|event| event.attributes.iter())
+        .find(|attribute| attribute.key == "operation_id")
+        .ok_or_else(|| StdError::generic_err("timelock did not report the operation id"))?
+        .value
+        .parse::<u64>()
+        .map_err(|_| StdError::generic_err("invalid timelock operation id"))?;
+
+    let mut proposal = PROPOSALS.load(deps.storage, &msg.id.to_string())?;
+    proposal.operation_id = Some(operation_id);
+    PROPOSALS.save(deps.storage, &msg.id.to_string(), &proposal)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "reply")
+        .add_attribute("proposal_id", msg.id.to_string())
+        .add_attribute("operation_id", operation_id.to_string()))
+}
+
+fn execute_release(
+    deps: DepsMut,
+    info: MessageInfo,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let timelock = TIMELOCK.may_load(deps.storage)?.ok_or(ContractError::Unauthorized {})?;
+    if info.sender != timelock.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut proposal = PROPOSALS.load(deps.storage, &proposal_id.to_string())?;
+    if let Some(reason) = proposal.veto_reason {
+        return Err(ContractError::Vetoed { reason });
+    }
+    if proposal.executed {
+        return Err(ContractError::AlreadyExecuted {});
+    }
+    if !proposal.scheduled {
+        return Err(ContractError::NotScheduled {});
+    }
+
+    proposal.executed = true;
+    PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
+
+    let transfer = BankMsg::Send {
+        to_address: proposal.recipient.to_string(),
+        amount: vec![Coin {
+            denom: "udevcore".to_string(),
+            amount: proposal.amount,
+        }],
+    };
+
+    Ok(Response::new()
+        .add_message(transfer)
+        .add_attribute("method", "execute_release")
+        .add_attribute("recipient", proposal.recipient.to_string())
+        .add_attribute("amount", proposal.amount.to_string()))
+}
+
+fn execute_veto(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    reason: String,
+) -> Result<Response, ContractError> {
+    let council = COUNCIL.may_load(deps.storage)?.ok_or(ContractError::Unauthorized {})?;
+    if info.sender != council.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    if reason.trim().is_empty() {
+        return Err(ContractError::InvalidInput("veto reason is required".to_string()));
+    }
+
+    let mut proposal = PROPOSALS.load(deps.storage, &proposal_id.to_string())?;
+    if let Some(reason) = proposal.veto_reason {
+        return Err(ContractError::Vetoed { reason });
+    }
+    if proposal.executed {
+        return Err(ContractError::AlreadyExecuted {});
+    }
+    let now = env.block.time.seconds();
+    if now <= proposal.voting_end {
+        return Err(ContractError::VotingNotEnded {});
+    }
+    if proposal.votes_for <= proposal.votes_against {
+        return Err(ContractError::NotPassed {});
+    }
+    if now > proposal.voting_end + council.veto_period {
+        return Err(ContractError::VetoPeriodExpired {});
+    }
+
+    proposal.veto_reason = Some(reason.clone());
+    PROPOSALS.save(deps.storage, &proposal_id.to_string(), &proposal)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_veto")
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("reason", reason))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -167,14 +406,25 @@ fn query_all_proposals(deps: Deps) -> StdResult<Binary> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::msg::{CouncilInfo, TimelockInfo};
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{from_binary, Addr, Uint128};
+    use cosmwasm_std::{
+        from_binary, Addr, Event, SubMsgResponse, SubMsgResult, SystemError, SystemResult, Uint128, WasmQuery,
+    };
+
+    const VOTING_PERIOD: u64 = 604800;
+
+    fn env_after(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(seconds);
+        env
+    }
 
     #[test]
     fn proper_instantiation() {
         let mut deps = mock_dependencies();
         
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg { timelock: None, council: None };
         let info = mock_info("creator", &[]);
         let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
         assert_eq!(0, res.messages.len());
@@ -184,7 +434,7 @@ mod tests {
     fn proposal_creation() {
         let mut deps = mock_dependencies();
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg { timelock: None, council: None };
         let info = mock_info("creator", &[]);
         instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -204,7 +454,7 @@ mod tests {
     fn vote_for_proposal() {
         let mut deps = mock_dependencies();
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg { timelock: None, council: None };
         let info = mock_info("creator", &[]);
         instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -231,7 +481,7 @@ mod tests {
     fn execute_proposal() {
         let mut deps = mock_dependencies();
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg { timelock: None, council: None };
         let info = mock_info("creator", &[]);
         instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -250,8 +500,292 @@ mod tests {
         };
         execute(deps.as_mut(), mock_env(), info.clone(), vote_msg).unwrap();
 
+        // the proposal only pays out once voting is over
         let exec_msg = ExecuteMsg::Execute { proposal_id: 0 };
-        let res = execute(deps.as_mut(), mock_env(), info, exec_msg).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), exec_msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::VotingNotEnded {}));
+        let res = execute(deps.as_mut(), env_after(VOTING_PERIOD + 1), info, exec_msg).unwrap();
+        assert_eq!(1, res.messages.len());
+    }
+
+    #[test]
+    fn execute_schedules_on_timelock() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            timelock: Some(TimelockInfo {
+                address: "timelock".to_string(),
+                delay: 86400,
+            }),
+            council: None,
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        let proposal_msg = ExecuteMsg::Propose {
+            title: "Treasury".to_string(),
+            description: "Pay the recipient".to_string(),
+            amount: Some(Uint128::from(100_u128)),
+            recipient: Some(Addr::unchecked("recipient_address")),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), proposal_msg).unwrap();
+        let vote_msg = ExecuteMsg::Vote {
+            proposal_id: 1,
+            approve: true,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), vote_msg).unwrap();
+
+        // passing schedules the release on the timelock rather than paying out
+        let env = env_after(VOTING_PERIOD + 1);
+        let exec_msg = ExecuteMsg::Execute { proposal_id: 1 };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), exec_msg.clone()).unwrap();
+        let schedule = TimelockExecuteMsg::Schedule {
+            target_address: env.contract.address.to_string(),
+            data: to_binary(&ExecuteMsg::Release { proposal_id: 1 }).unwrap(),
+            title: "Treasury".to_string(),
+            description: "Release proposal 1".to_string(),
+            execution_time: Scheduled::AtTime(env.block.time.plus_seconds(86400)),
+            executors: None,
+        };
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_on_success(
+                WasmMsg::Execute {
+                    contract_addr: "timelock".to_string(),
+                    msg: to_binary(&schedule).unwrap(),
+                    funds: vec![],
+                },
+                1
+            )]
+        );
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), exec_msg).unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyExecuted {}));
+
+        // only the timelock can release the funds
+        let release_msg = ExecuteMsg::Release { proposal_id: 1 };
+        let err = execute(deps.as_mut(), env.clone(), info, release_msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let info = mock_info("timelock", &[]);
+        let res = execute(deps.as_mut(), env, info, release_msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "recipient_address".to_string(),
+                amount: vec![Coin {
+                    denom: "udevcore".to_string(),
+                    amount: Uint128::from(100_u128),
+                }],
+            })]
+        );
+    }
+
+    #[test]
+    fn council_vetoes_passed_proposal() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            timelock: Some(TimelockInfo {
+                address: "timelock".to_string(),
+                delay: 86400,
+            }),
+            council: Some(CouncilInfo {
+                address: "council".to_string(),
+                veto_period: 86400,
+            }),
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        for title in ["Treasury", "Rejected"] {
+            let proposal_msg = ExecuteMsg::Propose {
+                title: title.to_string(),
+                description: "Pay the recipient".to_string(),
+                amount: Some(Uint128::from(100_u128)),
+                recipient: Some(Addr::unchecked("recipient_address")),
+            };
+            execute(deps.as_mut(), mock_env(), info.clone(), proposal_msg).unwrap();
+        }
+        let vote_msg = ExecuteMsg::Vote {
+            proposal_id: 1,
+            approve: true,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), vote_msg).unwrap();
+
+        // the council vetoes the final tally, so not while voting is open
+        let veto_msg = ExecuteMsg::Veto {
+            proposal_id: 1,
+            reason: "drains the treasury".to_string(),
+        };
+        let council = mock_info("council", &[]);
+        let err = execute(deps.as_mut(), mock_env(), council.clone(), veto_msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::VotingNotEnded {}));
+
+        let voted = env_after(VOTING_PERIOD + 1);
+        let vote_msg = ExecuteMsg::Vote {
+            proposal_id: 1,
+            approve: false,
+        };
+        let err = execute(deps.as_mut(), voted.clone(), info.clone(), vote_msg).unwrap_err();
+        assert!(matches!(err, ContractError::VotingEnded {}));
+
+        // nothing to veto on a proposal that did not pass
+        let reject_msg = ExecuteMsg::Veto {
+            proposal_id: 2,
+            reason: "drains the treasury".to_string(),
+        };
+        let err = execute(deps.as_mut(), voted.clone(), council.clone(), reject_msg).unwrap_err();
+        assert!(matches!(err, ContractError::NotPassed {}));
+
+        // the payout waits for the veto period
+        let exec_msg = ExecuteMsg::Execute { proposal_id: 1 };
+        let err = execute(deps.as_mut(), voted.clone(), info.clone(), exec_msg.clone()).unwrap_err();
+        let veto_end = mock_env().block.time.seconds() + VOTING_PERIOD + 86400;
+        assert!(matches!(err, ContractError::VetoPeriodActive { veto_end: end } if end == veto_end));
+
+        let err = execute(deps.as_mut(), voted.clone(), info.clone(), veto_msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), voted, council.clone(), veto_msg.clone()).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetProposal { proposal_id: 1 }).unwrap();
+        let proposal: Proposal = from_binary(&res).unwrap();
+        assert_eq!(proposal.veto_reason, Some("drains the treasury".to_string()));
+
+        // the vetoed proposal is never paid out, even after the veto period
+        let after = env_after(VOTING_PERIOD + 86400 + 1);
+        let err = execute(deps.as_mut(), after.clone(), info, exec_msg).unwrap_err();
+        assert!(matches!(err, ContractError::Vetoed { .. }));
+        let release_msg = ExecuteMsg::Release { proposal_id: 1 };
+        let err = execute(deps.as_mut(), after, mock_info("timelock", &[]), release_msg).unwrap_err();
+        assert!(matches!(err, ContractError::Vetoed { .. }));
+    }
+
+    #[test]
+    fn execute_after_veto_period() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            timelock: None,
+            council: Some(CouncilInfo {
+                address: "council".to_string(),
+                veto_period: 86400,
+            }),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        let proposal_msg = ExecuteMsg::Propose {
+            title: "Treasury".to_string(),
+            description: "Pay the recipient".to_string(),
+            amount: Some(Uint128::from(100_u128)),
+            recipient: Some(Addr::unchecked("recipient_address")),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), proposal_msg).unwrap();
+        let vote_msg = ExecuteMsg::Vote {
+            proposal_id: 1,
+            approve: true,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), vote_msg).unwrap();
+
+        let exec_msg = ExecuteMsg::Execute { proposal_id: 1 };
+        let err = execute(deps.as_mut(), env_after(VOTING_PERIOD + 86400), info.clone(), exec_msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::VetoPeriodActive { .. }));
+        let res = execute(deps.as_mut(), env_after(VOTING_PERIOD + 86400 + 1), info, exec_msg).unwrap();
         assert_eq!(1, res.messages.len());
+
+        // once paid out it is too late to veto
+        let veto_msg = ExecuteMsg::Veto {
+            proposal_id: 1,
+            reason: "drains the treasury".to_string(),
+        };
+        let err = execute(deps.as_mut(), env_after(VOTING_PERIOD + 86400 + 1), mock_info("council", &[]), veto_msg).unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyExecuted {}));
+    }
+
+    #[test]
+    fn reschedule_after_cancelled_operation() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {
+            timelock: Some(TimelockInfo {
+                address: "timelock".to_string(),
+                delay: 86400,
+            }),
+            council: None,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        let proposal_msg = ExecuteMsg::Propose {
+            title: "Treasury".to_string(),
+            description: "Pay the recipient".to_string(),
+            amount: Some(Uint128::from(100_u128)),
+            recipient: Some(Addr::unchecked("recipient_address")),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), proposal_msg).unwrap();
+        let vote_msg = ExecuteMsg::Vote {
+            proposal_id: 1,
+            approve: true,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), vote_msg).unwrap();
+
+        let reschedule_msg = ExecuteMsg::Reschedule { proposal_id: 1 };
+        let err = execute(deps.as_mut(), env_after(VOTING_PERIOD + 1), info.clone(), reschedule_msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotScheduled {}));
+        let exec_msg = ExecuteMsg::Execute { proposal_id: 1 };
+        execute(deps.as_mut(), env_after(VOTING_PERIOD + 1), info.clone(), exec_msg).unwrap();
+
+        // the timelock reports the operation id in its event
+        let reply_msg = Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![Event::new("wasm-timelock_operation").add_attribute("operation_id", "7")],
+                data: None,
+            }),
+        };
+        reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetProposal { proposal_id: 1 }).unwrap();
+        let proposal: Proposal = from_binary(&res).unwrap();
+        assert_eq!(proposal.operation_id, Some(7));
+
+        // nothing to do while the operation can still release the funds
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "timelock" => {
+                let msg: TimelockQueryMsg = from_binary(msg).unwrap();
+                assert_eq!(msg, TimelockQueryMsg::GetOperationStatus { operation_id: Uint64::new(7) });
+                SystemResult::Ok(to_binary(&OperationStatus::Pending).into())
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "wasm".to_string() }),
+        });
+        let err = execute(deps.as_mut(), env_after(VOTING_PERIOD + 2), info.clone(), reschedule_msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::StillScheduled { operation_id: 7 }));
+
+        // once cancelled the timelock no longer knows the operation and the release is scheduled again
+        deps.querier.update_wasm(|_| SystemResult::Ok(Err("operation not found".to_string()).into()));
+        let env = env_after(VOTING_PERIOD + 2);
+        let res = execute(deps.as_mut(), env.clone(), info, reschedule_msg).unwrap();
+        let schedule = TimelockExecuteMsg::Schedule {
+            target_address: env.contract.address.to_string(),
+            data: to_binary(&ExecuteMsg::Release { proposal_id: 1 }).unwrap(),
+            title: "Treasury".to_string(),
+            description: "Release proposal 1".to_string(),
+            execution_time: Scheduled::AtTime(env.block.time.plus_seconds(86400)),
+            executors: None,
+        };
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::reply_on_success(
+                WasmMsg::Execute {
+                    contract_addr: "timelock".to_string(),
+                    msg: to_binary(&schedule).unwrap(),
+                    funds: vec![],
+                },
+                1
+            )]
+        );
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetProposal { proposal_id: 1 }).unwrap();
+        let proposal: Proposal = from_binary(&res).unwrap();
+        assert_eq!(proposal.operation_id, None);
     }
 }
@@ -1,11 +1,31 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Binary, Uint128, Uint64};
+use cw_utils::Scheduled;
 use crate::state::{Member};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct InstantiateMsg {
+    /// Passed proposals are scheduled on this timelock instead of paying out right away.
+    /// The DAO must be one of the timelock's proposers.
+    pub timelock: Option<TimelockInfo>,
+    /// Emergency council allowed to veto passed proposals before they are paid out
+    pub council: Option<CouncilInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TimelockInfo {
+    pub address: String,
+    /// seconds between a proposal passing and its funds being released
+    pub delay: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CouncilInfo {
+    pub address: String,
+    /// seconds after a proposal's voting end during which the council can still veto it
+    pub veto_period: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -24,6 +44,48 @@ pub enum ExecuteMsg {
     Execute {
         proposal_id: u64,
     },
+    /// Called back by the timelock once a scheduled proposal's delay is over
+    Release {
+        proposal_id: u64,
+    },
+    /// Council only, blocks a passed proposal that was not paid out yet
+    Veto {
+        proposal_id: u64,
+        reason: String,
+    },
+    /// Schedules the release again once its timelock operation was cancelled, callable by anyone
+    Reschedule {
+        proposal_id: u64,
+    },
+}
+
+/// Subset of the timelock contract's execute messages the DAO sends
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelockExecuteMsg {
+    Schedule {
+        target_address: String,
+        data: Binary,
+        title: String,
+        description: String,
+        execution_time: Scheduled,
+        executors: Option<Vec<String>>,
+    },
+}
+
+/// Subset of the timelock contract's queries the DAO sends
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelockQueryMsg {
+    GetOperationStatus { operation_id: Uint64 },
+}
+
+/// Status of a timelock operation as the timelock reports it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum OperationStatus {
+    Pending,
+    Ready,
+    Done,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
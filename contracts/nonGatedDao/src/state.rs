@@ -14,6 +14,24 @@ pub struct Proposal {
     pub amount: Uint128,
     pub recipient: Addr,
     pub voting_end: u64, // UNIX timestamp
+    #[serde(default)]
+    pub scheduled: bool, // waiting on the timelock to release the funds
+    #[serde(default)]
+    pub veto_reason: Option<String>, // set when the council vetoed the proposal
+    #[serde(default)]
+    pub operation_id: Option<u64>, // timelock operation releasing the funds, read from the schedule reply
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TimelockConfig {
+    pub address: Addr,
+    pub delay: u64, // seconds
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CouncilConfig {
+    pub address: Addr,
+    pub veto_period: u64, // seconds after voting_end
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -26,4 +44,6 @@ pub struct Member {
 pub const STATE: Item<()> = Item::new("state");
 pub const PROPOSALS: Map<&str, Proposal> = Map::new("proposals");
 pub const PROPOSAL_COUNT: Item<u64> = Item::new("proposal_count");
-pub const MEMBERS: Map<&str, Member> = Map::new("members");
\ No newline at end of file
+pub const MEMBERS: Map<&str, Member> = Map::new("members");
+pub const TIMELOCK: Item<TimelockConfig> = Item::new("timelock");
+pub const COUNCIL: Item<CouncilConfig> = Item::new("council");
\ No newline at end of file
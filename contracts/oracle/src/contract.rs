@@ -0,0 +1,390 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Order, Response,
+    StdError, StdResult,
+};
+use cw2::set_contract_version;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ConfigResponse, ExecuteMsg, FeedersResponse, InstantiateMsg, ObservationResponse,
+    ObservationsResponse, PriceFeed, PriceResponse, QueryMsg,
+};
+use crate::state::{Config, Observation, CONFIG, FEEDERS, OBSERVATIONS};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:oracle";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    if msg.max_age == 0 || msg.min_feeders == 0 {
+        return Err(ContractError::InvalidConfig {});
+    }
+
+    let config = Config {
+        owner: info.sender.clone(),
+        max_age: msg.max_age,
+        min_feeders: msg.min_feeders,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    for feeder in msg.feeders {
+        let feeder = deps.api.addr_validate(&feeder)?;
+        FEEDERS.save(deps.storage, &feeder, &Empty {})?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "instantiate")
+        .add_attribute("owner", info.sender))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::FeedPrices { prices } => execute_feed_prices(deps, env, info, prices),
+        ExecuteMsg::AddFeeder { address } => execute_add_feeder(deps, info, address),
+        ExecuteMsg::RemoveFeeder { address } => execute_remove_feeder(deps, info, address),
+        ExecuteMsg::UpdateConfig {
+            owner,
+            max_age,
+            min_feeders,
+        } => execute_update_config(deps, info, owner, max_age, min_feeders),
+    }
+}
+
+pub fn execute_feed_prices(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    prices: Vec<PriceFeed>,
+) -> Result<Response, ContractError> {
+    if !FEEDERS.has(deps.storage, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let now = env.block.time.seconds();
+    for feed in &prices {
+        if feed.price.is_zero() {
+            return Err(ContractError::InvalidPrice {
+                pair: feed.pair.clone(),
+            });
+        }
+        if feed.timestamp > now {
+            return Err(ContractError::FutureTimestamp {
+                pair: feed.pair.clone(),
+            });
+        }
+        let key = (feed.pair.as_str(), &info.sender);
+        if let Some(last) = OBSERVATIONS.may_load(deps.storage, key)? {
+            if feed.timestamp <= last.timestamp {
+                return Err(ContractError::OutdatedTimestamp {
+                    pair: feed.pair.clone(),
+                });
+            }
+        }
+        OBSERVATIONS.save(
+            deps.storage,
+            key,
+            &Observation {
+                price: feed.price,
+                timestamp: feed.timestamp,
+            },
+        )?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "feed_prices")
+        .add_attribute("feeder", info.sender)
+        .add_attribute("prices", prices.len().to_string()))
+}
+
+pub fn execute_add_feeder(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.as_ref(), &info.sender)?;
+    let feeder = deps.api.addr_validate(&address)?;
+    FEEDERS.save(deps.storage, &feeder, &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_feeder")
+        .add_attribute("feeder", feeder))
+}
+
+pub fn execute_remove_feeder(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.as_ref(), &info.sender)?;
+    let feeder = deps.api.addr_validate(&address)?;
+    FEEDERS.remove(deps.storage, &feeder);
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_feeder")
+        .add_attribute("feeder", feeder))
+}
+
+pub fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    max_age: Option<u64>,
+    min_feeders: Option<u32>,
+) -> Result<Response, ContractError> {
+    let mut config = assert_owner(deps.as_ref(), &info.sender)?;
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+    config.max_age = max_age.unwrap_or(config.max_age);
+    config.min_feeders = min_feeders.unwrap_or(config.min_feeders);
+    if config.max_age == 0 || config.min_feeders == 0 {
+        return Err(ContractError::InvalidConfig {});
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+fn assert_owner(deps: Deps, sender: &Addr) -> Result<Config, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if &config.owner != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(config)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Feeders {} => to_json_binary(&query_feeders(deps)?),
+        QueryMsg::Price { pair } => to_json_binary(&query_price(deps, env, pair)?),
+        QueryMsg::Observations { pair } => to_json_binary(&query_observations(deps, pair)?),
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: config.owner,
+        max_age: config.max_age,
+        min_feeders: config.min_feeders,
+    })
+}
+
+fn query_feeders(deps: Deps) -> StdResult<FeedersResponse> {
+    let feeders = FEEDERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    Ok(FeedersResponse { feeders })
+}
+
+fn query_price(deps: Deps, env: Env, pair: String) -> StdResult<PriceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+
+    let mut fresh: Vec<Observation> = current_observations(deps, &pair)?
+        .into_iter()
+        .map(|(_, observation)| observation)
+        .filter(|observation| now.saturating_sub(observation.timestamp) <= config.max_age)
+        .collect();
+    if fresh.len() < config.min_feeders as usize {
+        return Err(StdError::generic_err(format!(
+            "Price for {} is stale: {} of {} required feeders are fresh",
+            pair,
+            fresh.len(),
+            config.min_feeders
+        )));
+    }
+
+    fresh.sort_by_key(|observation| observation.price);
+    let middle = fresh.len() / 2;
+    let price = if fresh.len() % 2 == 1 {
+        fresh[middle].price
+    } else {
+        (fresh[middle - 1].price + fresh[middle].price) * Decimal::percent(50)
+    };
+    let updated_at = fresh
+        .iter()
+        .map(|observation| observation.timestamp)
+        .min()
+        .unwrap_or_default();
+
+    Ok(PriceResponse {
+        pair,
+        price,
+        updated_at,
+        sources: fresh.len() as u32,
+    })
+}
+
+fn query_observations(deps: Deps, pair: String) -> StdResult<ObservationsResponse> {
+    let observations = current_observations(deps, &pair)?
+        .into_iter()
+        .map(|(feeder, observation)| ObservationResponse {
+            feeder,
+            price: observation.price,
+            timestamp: observation.timestamp,
+        })
+        .collect();
+    Ok(ObservationsResponse { observations })
+}
+
+/// Observations for `pair` pushed by feeders that are still whitelisted
+fn current_observations(deps: Deps, pair: &str) -> StdResult<Vec<(Addr, Observation)>> {
+    OBSERVATIONS
+        .prefix(pair)
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((feeder, _)) => FEEDERS.has(deps.storage, feeder),
+            Err(_) => true,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{OwnedDeps, Timestamp};
+
+    const OWNER: &str = "owner";
+    const PAIR: &str = "ucore/uusdc";
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    }
+
+    fn setup(min_feeders: u32) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            feeders: vec!["feeder1".into(), "feeder2".into(), "feeder3".into()],
+            max_age: 60,
+            min_feeders,
+        };
+        instantiate(deps.as_mut(), env_at(0), mock_info(OWNER, &[]), msg).unwrap();
+        deps
+    }
+
+    fn feed(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        feeder: &str,
+        price: Decimal,
+        timestamp: u64,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::FeedPrices {
+            prices: vec![PriceFeed {
+                pair: PAIR.to_string(),
+                price,
+                timestamp,
+            }],
+        };
+        execute(deps.as_mut(), env_at(1_000), mock_info(feeder, &[]), msg)
+    }
+
+    #[test]
+    fn only_feeders_push_ordered_prices() {
+        let mut deps = setup(1);
+
+        let err = feed(&mut deps, "stranger", Decimal::one(), 900).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let err = feed(&mut deps, "feeder1", Decimal::zero(), 900).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPrice { .. }));
+        let err = feed(&mut deps, "feeder1", Decimal::one(), 1_001).unwrap_err();
+        assert!(matches!(err, ContractError::FutureTimestamp { .. }));
+
+        feed(&mut deps, "feeder1", Decimal::one(), 900).unwrap();
+        let err = feed(&mut deps, "feeder1", Decimal::one(), 900).unwrap_err();
+        assert!(matches!(err, ContractError::OutdatedTimestamp { .. }));
+        feed(&mut deps, "feeder1", Decimal::percent(120), 950).unwrap();
+
+        let observations = query_observations(deps.as_ref(), PAIR.to_string()).unwrap();
+        assert_eq!(observations.observations.len(), 1);
+        assert_eq!(observations.observations[0].price, Decimal::percent(120));
+    }
+
+    #[test]
+    fn median_across_feeders() {
+        let mut deps = setup(2);
+        feed(&mut deps, "feeder1", Decimal::percent(100), 990).unwrap();
+        feed(&mut deps, "feeder2", Decimal::percent(300), 995).unwrap();
+
+        let price = query_price(deps.as_ref(), env_at(1_000), PAIR.to_string()).unwrap();
+        assert_eq!(
+            price,
+            PriceResponse {
+                pair: PAIR.to_string(),
+                price: Decimal::percent(200),
+                updated_at: 990,
+                sources: 2,
+            }
+        );
+
+        // an outlier doesn't move the median of three
+        feed(&mut deps, "feeder3", Decimal::percent(10_000), 999).unwrap();
+        let price = query_price(deps.as_ref(), env_at(1_000), PAIR.to_string()).unwrap();
+        assert_eq!(price.price, Decimal::percent(300));
+        assert_eq!(price.sources, 3);
+    }
+
+    #[test]
+    fn stale_prices_are_dropped() {
+        let mut deps = setup(2);
+        feed(&mut deps, "feeder1", Decimal::percent(100), 900).unwrap();
+        feed(&mut deps, "feeder2", Decimal::percent(110), 980).unwrap();
+        feed(&mut deps, "feeder3", Decimal::percent(120), 990).unwrap();
+
+        // feeder1 is over a minute old
+        let price = query_price(deps.as_ref(), env_at(1_000), PAIR.to_string()).unwrap();
+        assert_eq!(price.price, Decimal::percent(115));
+        assert_eq!(price.updated_at, 980);
+
+        query_price(deps.as_ref(), env_at(1_045), PAIR.to_string()).unwrap_err();
+        query_price(deps.as_ref(), env_at(1_000), "ucore/uatom".to_string()).unwrap_err();
+    }
+
+    #[test]
+    fn removed_feeders_no_longer_count() {
+        let mut deps = setup(1);
+        feed(&mut deps, "feeder1", Decimal::percent(100), 990).unwrap();
+        feed(&mut deps, "feeder2", Decimal::percent(200), 990).unwrap();
+
+        let msg = ExecuteMsg::RemoveFeeder {
+            address: "feeder2".to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(1_000),
+            mock_info("feeder1", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), env_at(1_000), mock_info(OWNER, &[]), msg).unwrap();
+
+        let price = query_price(deps.as_ref(), env_at(1_000), PAIR.to_string()).unwrap();
+        assert_eq!(price.price, Decimal::percent(100));
+        assert_eq!(price.sources, 1);
+        let err = feed(&mut deps, "feeder2", Decimal::one(), 995).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        assert_eq!(query_feeders(deps.as_ref()).unwrap().feeders.len(), 2);
+    }
+}
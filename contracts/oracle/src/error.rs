@@ -0,0 +1,23 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Max age and minimum number of feeders must be positive")]
+    InvalidConfig {},
+
+    #[error("Price for {pair} must be positive")]
+    InvalidPrice { pair: String },
+
+    #[error("Price for {pair} is timestamped in the future")]
+    FutureTimestamp { pair: String },
+
+    #[error("Price for {pair} is older than the last one pushed")]
+    OutdatedTimestamp { pair: String },
+}
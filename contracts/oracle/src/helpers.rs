@@ -0,0 +1,30 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, CustomQuery, QuerierWrapper, QueryRequest, StdResult, WasmQuery,
+};
+
+use crate::msg::{PriceResponse, QueryMsg};
+
+/// Handle for contracts reading prices from a deployed oracle. Depend on this crate with the
+/// `library` feature to use it.
+#[cw_serde]
+pub struct OracleContract(pub Addr);
+
+impl OracleContract {
+    pub fn addr(&self) -> Addr {
+        self.0.clone()
+    }
+
+    /// Median price of `pair`, fails when the feed is stale
+    pub fn price<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        pair: impl Into<String>,
+    ) -> StdResult<PriceResponse> {
+        let msg = QueryMsg::Price { pair: pair.into() };
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&msg)?,
+        }))
+    }
+}
@@ -0,0 +1,82 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Decimal};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub feeders: Vec<String>,
+    pub max_age: u64,
+    pub min_feeders: u32,
+}
+
+#[cw_serde]
+pub struct PriceFeed {
+    /// Pair the price is quoted for, e.g. `ucore/uusdc`
+    pub pair: String,
+    pub price: Decimal,
+    pub timestamp: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Feeders only; records the sender's latest price for each pair.
+    FeedPrices { prices: Vec<PriceFeed> },
+    /// Owner only
+    AddFeeder { address: String },
+    /// Owner only
+    RemoveFeeder { address: String },
+    /// Owner only
+    UpdateConfig {
+        owner: Option<String>,
+        max_age: Option<u64>,
+        min_feeders: Option<u32>,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(FeedersResponse)]
+    Feeders {},
+    /// Median of the fresh prices for `pair`, fails while fewer than `min_feeders` are fresh
+    #[returns(PriceResponse)]
+    Price { pair: String },
+    /// Latest price each current feeder pushed for `pair`, fresh or not
+    #[returns(ObservationsResponse)]
+    Observations { pair: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub max_age: u64,
+    pub min_feeders: u32,
+}
+
+#[cw_serde]
+pub struct FeedersResponse {
+    pub feeders: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct PriceResponse {
+    pub pair: String,
+    pub price: Decimal,
+    /// Timestamp of the oldest price the median was taken over
+    pub updated_at: u64,
+    /// Number of fresh prices the median was taken over
+    pub sources: u32,
+}
+
+#[cw_serde]
+pub struct ObservationResponse {
+    pub feeder: Addr,
+    pub price: Decimal,
+    pub timestamp: u64,
+}
+
+#[cw_serde]
+pub struct ObservationsResponse {
+    pub observations: Vec<ObservationResponse>,
+}
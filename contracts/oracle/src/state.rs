@@ -0,0 +1,24 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Empty};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,
+    /// Seconds after which a pushed price no longer counts
+    pub max_age: u64,
+    /// Fresh prices needed before the median is served
+    pub min_feeders: u32,
+}
+
+#[cw_serde]
+pub struct Observation {
+    pub price: Decimal,
+    /// Seconds since epoch the price was observed at
+    pub timestamp: u64,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const FEEDERS: Map<&Addr, Empty> = Map::new("feeders");
+/// Latest price pushed by each feeder, observations of removed feeders are ignored
+pub const OBSERVATIONS: Map<(&str, &Addr), Observation> = Map::new("observations");
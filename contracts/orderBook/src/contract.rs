@@ -0,0 +1,536 @@
+use std::str::FromStr;
+
+use coreum_wasm_sdk::core::CoreumResult;
+use coreum_wasm_sdk::types::coreum::dex::v1::{
+    GoodTil, MsgCancelOrder, MsgPlaceOrder, Order, OrderType, QueryOrderBookOrdersRequest,
+    QueryOrderRequest, Side, TimeInForce,
+};
+use coreum_wasm_sdk::types::cosmos::base::query::v1beta1::PageRequest;
+use cosmwasm_std::{
+    entry_point, to_json_binary, to_json_vec, BankMsg, Binary, Coin, ContractResult, CosmosMsg,
+    Deps, DepsMut, Env, MessageInfo, Order as StorageOrder, QueryRequest, Response, StdError,
+    StdResult, SystemResult, Uint128,
+};
+use cw2::set_contract_version;
+use cw_ownable::{assert_owner, get_ownership, initialize_owner, update_ownership};
+use cw_storage_plus::Bound;
+
+use crate::error::ContractError;
+use crate::msg::{
+    BookOrder, ConfigResponse, ExecuteMsg, InstantiateMsg, LimitOrder, OrderBookResponse,
+    OrderSide, OrdersResponse, PlacedOrder, QueryMsg,
+};
+use crate::state::{Config, CONFIG, ORDERS, ORDER_SEQ};
+
+// version info for migration info
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// settings for pagination
+const MAX_LIMIT: u32 = 100;
+const DEFAULT_LIMIT: u32 = 20;
+
+const ORDER_QUERY_PATH: &str = "/coreum.dex.v1.Query/Order";
+
+// ********** Instantiate **********
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> CoreumResult<ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    initialize_owner(deps.storage, deps.api, Some(info.sender.as_ref()))?;
+
+    if msg.base_denom == msg.quote_denom {
+        return Err(ContractError::IdenticalDenoms {});
+    }
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            base_denom: msg.base_denom.clone(),
+            quote_denom: msg.quote_denom.clone(),
+        },
+    )?;
+    ORDER_SEQ.save(deps.storage, &0)?;
+
+    Ok(Response::new()
+        .add_attribute("owner", info.sender)
+        .add_attribute("base_denom", msg.base_denom)
+        .add_attribute("quote_denom", msg.quote_denom))
+}
+
+// ********** Execute **********
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> CoreumResult<ContractError> {
+    match msg {
+        ExecuteMsg::PlaceOrders { orders } => place_orders(deps, env, info, orders),
+        ExecuteMsg::CancelOrders { ids } => cancel_orders(deps, env, info, ids),
+        ExecuteMsg::PruneOrders { start_after, limit } => {
+            prune_orders(deps, env, start_after, limit)
+        }
+        ExecuteMsg::Withdraw { amount, recipient } => withdraw(deps, info, amount, recipient),
+        ExecuteMsg::UpdateOwnership(action) => {
+            update_ownership(deps, &env.block, &info.sender, action)?;
+            Ok(Response::new().add_attribute("action", "update_ownership"))
+        }
+    }
+}
+
+// ********** Transactions **********
+
+fn place_orders(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    orders: Vec<LimitOrder>,
+) -> CoreumResult<ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut seq = ORDER_SEQ.load(deps.storage)?;
+    let mut messages = Vec::with_capacity(orders.len());
+    for order in orders {
+        if order.price.is_empty() || order.quantity.is_zero() {
+            return Err(ContractError::InvalidOrder {});
+        }
+        seq += 1;
+        let id = format!("order-{}", seq);
+        let place = MsgPlaceOrder {
+            sender: env.contract.address.to_string(),
+            r#type: OrderType::Limit as i32,
+            id: id.clone(),
+            base_denom: config.base_denom.clone(),
+            quote_denom: config.quote_denom.clone(),
+            price: order.price.clone(),
+            quantity: order.quantity.to_string(),
+            side: dex_side(&order.side) as i32,
+            good_til: order.good_til_height.map(|height| GoodTil {
+                good_til_block_height: height,
+                good_til_block_time: None,
+            }),
+            time_in_force: TimeInForce::Gtc as i32,
+        };
+        messages.push(CosmosMsg::Stargate {
+            type_url: place.to_any().type_url,
+            value: Binary::from(place.to_proto_bytes()),
+        });
+        ORDERS.save(deps.storage, &id, &order)?;
+    }
+    ORDER_SEQ.save(deps.storage, &seq)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "place_orders")
+        .add_attribute("count", messages.len().to_string())
+        .add_messages(messages))
+}
+
+fn cancel_orders(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ids: Vec<String>,
+) -> CoreumResult<ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+
+    let mut messages = Vec::with_capacity(ids.len());
+    for id in ids {
+        if !ORDERS.has(deps.storage, &id) {
+            return Err(ContractError::UnknownOrder(id));
+        }
+        ORDERS.remove(deps.storage, &id);
+        // cancelling an order the DEX already filled or expired would fail the whole batch
+        if !order_open(deps.as_ref(), &env, &id)? {
+            continue;
+        }
+        let cancel = MsgCancelOrder {
+            sender: env.contract.address.to_string(),
+            id,
+        };
+        messages.push(CosmosMsg::Stargate {
+            type_url: cancel.to_any().type_url,
+            value: Binary::from(cancel.to_proto_bytes()),
+        });
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_orders")
+        .add_attribute("count", messages.len().to_string())
+        .add_messages(messages))
+}
+
+fn prune_orders(
+    deps: DepsMut,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> CoreumResult<ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let ids = ORDERS
+        .keys(
+            deps.storage,
+            start_after.as_deref().map(Bound::exclusive),
+            None,
+            StorageOrder::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut pruned = 0;
+    for id in &ids {
+        if !order_open(deps.as_ref(), &env, id)? {
+            ORDERS.remove(deps.storage, id);
+            pruned += 1;
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "prune_orders")
+        .add_attribute("pruned", pruned.to_string())
+        .add_attribute("last", ids.last().cloned().unwrap_or_default()))
+}
+
+fn withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Vec<Coin>,
+    recipient: Option<String>,
+) -> CoreumResult<ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    if amount.is_empty() || amount.iter().any(|coin| coin.amount.is_zero()) {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender,
+    };
+
+    // funds locked in open orders can't be sent, the bank send fails for them
+    Ok(Response::new()
+        .add_attribute("action", "withdraw")
+        .add_attribute("recipient", recipient.as_str())
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount,
+        }))
+}
+
+// ********** Helpers **********
+
+fn dex_side(side: &OrderSide) -> Side {
+    match side {
+        OrderSide::Buy => Side::Buy,
+        OrderSide::Sell => Side::Sell,
+    }
+}
+
+// The DEX removes orders once filled or expired, so it only answers for open ones
+fn order_open(deps: Deps, env: &Env, id: &str) -> StdResult<bool> {
+    let request = QueryOrderRequest {
+        creator: env.contract.address.to_string(),
+        id: id.to_string(),
+    };
+    let request: QueryRequest = QueryRequest::Stargate {
+        path: ORDER_QUERY_PATH.to_string(),
+        data: Binary::from(request.to_proto_bytes()),
+    };
+    let res = deps.querier.raw_query(&to_json_vec(&request)?);
+    Ok(matches!(res, SystemResult::Ok(ContractResult::Ok(_))))
+}
+
+fn book_order(order: Order) -> StdResult<BookOrder> {
+    let parse = |amount: &str| {
+        Uint128::from_str(amount).map_err(|_| StdError::parse_err("Uint128", amount))
+    };
+    Ok(BookOrder {
+        quantity: parse(&order.quantity)?,
+        remaining_quantity: parse(&order.remaining_base_quantity)?,
+        id: order.id,
+        creator: order.creator,
+        price: order.price,
+    })
+}
+
+// ********** Queries **********
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Orders { start_after, limit } => {
+            to_json_binary(&query_orders(deps, start_after, limit)?)
+        }
+        QueryMsg::OrderBook { side, limit } => {
+            to_json_binary(&query_order_book(deps, side, limit)?)
+        }
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let owner = get_ownership(deps.storage)?.owner;
+    Ok(ConfigResponse {
+        owner: owner.map(String::from),
+        base_denom: config.base_denom,
+        quote_denom: config.quote_denom,
+    })
+}
+
+fn query_orders(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<OrdersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let orders = ORDERS
+        .range(
+            deps.storage,
+            start_after.as_deref().map(Bound::exclusive),
+            None,
+            StorageOrder::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(id, order)| PlacedOrder { id, order }))
+        .collect::<StdResult<_>>()?;
+    Ok(OrdersResponse { orders })
+}
+
+fn query_order_book(
+    deps: Deps,
+    side: OrderSide,
+    limit: Option<u32>,
+) -> StdResult<OrderBookResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let response = QueryOrderBookOrdersRequest {
+        base_denom: config.base_denom,
+        quote_denom: config.quote_denom,
+        side: dex_side(&side) as i32,
+        pagination: Some(PageRequest {
+            limit: limit.into(),
+            ..Default::default()
+        }),
+    }
+    .query(&deps.querier)?;
+    let orders = response
+        .orders
+        .into_iter()
+        .map(book_order)
+        .collect::<StdResult<_>>()?;
+    Ok(OrderBookResponse { orders })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+    use coreum_wasm_sdk::core::CoreumMsg;
+    use cosmwasm_std::{from_json, Empty, OwnedDeps, Querier, QuerierResult, SystemError};
+    use std::marker::PhantomData;
+
+    const OWNER: &str = "owner";
+    const ALICE: &str = "alice";
+    const CORE: &str = "ucore";
+    const TOKEN: &str = "utkn-issuer";
+
+    // answers the DEX order query for the ids still open, like the chain does for this contract
+    #[derive(Default)]
+    struct DexQuerier {
+        open: Vec<String>,
+    }
+
+    impl Querier for DexQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            match from_json(bin_request).unwrap() {
+                QueryRequest::<Empty>::Stargate { path, data } if path == ORDER_QUERY_PATH => {
+                    let request = QueryOrderRequest::try_from(data).unwrap();
+                    assert_eq!(request.creator, mock_env().contract.address.as_str());
+                    if self.open.contains(&request.id) {
+                        SystemResult::Ok(ContractResult::Ok(Binary::from(b"{}")))
+                    } else {
+                        SystemResult::Ok(ContractResult::Err("order not found".to_string()))
+                    }
+                }
+                _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "dex".to_string(),
+                }),
+            }
+        }
+    }
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, DexQuerier> {
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: DexQuerier::default(),
+            custom_query_type: PhantomData,
+        };
+        let msg = InstantiateMsg {
+            base_denom: TOKEN.to_string(),
+            quote_denom: CORE.to_string(),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+        deps
+    }
+
+    fn sell(price: &str, quantity: u128) -> LimitOrder {
+        LimitOrder {
+            side: OrderSide::Sell,
+            price: price.to_string(),
+            quantity: Uint128::new(quantity),
+            good_til_height: None,
+        }
+    }
+
+    fn orders_msg() -> QueryMsg {
+        QueryMsg::Orders {
+            start_after: None,
+            limit: None,
+        }
+    }
+
+    fn cancel_ids(res: &Response<CoreumMsg>) -> Vec<String> {
+        res.messages
+            .iter()
+            .map(|msg| match &msg.msg {
+                CosmosMsg::Stargate { type_url, value } => {
+                    assert_eq!(type_url, "/coreum.dex.v1.MsgCancelOrder");
+                    MsgCancelOrder::try_from(value.clone()).unwrap().id
+                }
+                msg => panic!("unexpected message {:?}", msg),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn placing_orders() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::PlaceOrders {
+            orders: vec![sell("1e-1", 100), sell("2e-1", 50)],
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ALICE, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Ownership(_)));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        match &res.messages[1].msg {
+            CosmosMsg::Stargate { type_url, value } => {
+                assert_eq!(type_url, "/coreum.dex.v1.MsgPlaceOrder");
+                let place = MsgPlaceOrder::try_from(value.clone()).unwrap();
+                assert_eq!(place.id, "order-2");
+                assert_eq!(place.base_denom, TOKEN);
+                assert_eq!(place.price, "2e-1");
+                assert_eq!(place.quantity, "50");
+                assert_eq!(place.side, Side::Sell as i32);
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        }
+
+        let res: OrdersResponse =
+            from_json(query(deps.as_ref(), mock_env(), orders_msg()).unwrap()).unwrap();
+        let ids: Vec<_> = res.orders.iter().map(|placed| placed.id.as_str()).collect();
+        assert_eq!(ids, ["order-1", "order-2"]);
+
+        let msg = ExecuteMsg::PlaceOrders {
+            orders: vec![sell("1e-1", 0)],
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidOrder {});
+    }
+
+    #[test]
+    fn cancelling_orders() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::PlaceOrders {
+            orders: vec![sell("1e-1", 100)],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::CancelOrders {
+            ids: vec!["order-7".to_string()],
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::UnknownOrder("order-7".to_string()));
+
+        let msg = ExecuteMsg::CancelOrders {
+            ids: vec!["order-1".to_string()],
+        };
+        deps.querier.open = vec!["order-1".to_string()];
+        let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+        assert_eq!(cancel_ids(&res), ["order-1"]);
+        let res: OrdersResponse =
+            from_json(query(deps.as_ref(), mock_env(), orders_msg()).unwrap()).unwrap();
+        assert!(res.orders.is_empty());
+    }
+
+    #[test]
+    fn cancelling_filled_orders() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::PlaceOrders {
+            orders: vec![sell("1e-1", 100), sell("2e-1", 50), sell("3e-1", 10)],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+
+        // order-2 was filled, the batch still cancels the open ones and forgets it
+        deps.querier.open = vec!["order-1".to_string(), "order-3".to_string()];
+        let msg = ExecuteMsg::CancelOrders {
+            ids: vec!["order-1".to_string(), "order-2".to_string(), "order-3".to_string()],
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+        assert_eq!(cancel_ids(&res), ["order-1", "order-3"]);
+        let res: OrdersResponse =
+            from_json(query(deps.as_ref(), mock_env(), orders_msg()).unwrap()).unwrap();
+        assert!(res.orders.is_empty());
+    }
+
+    #[test]
+    fn pruning_and_listing_orders() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::PlaceOrders {
+            orders: (1..=5).map(|quantity| sell("1e-1", quantity)).collect(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+
+        let page = |deps: &OwnedDeps<MockStorage, MockApi, DexQuerier>, start_after: Option<&str>| {
+            let msg = QueryMsg::Orders {
+                start_after: start_after.map(String::from),
+                limit: Some(2),
+            };
+            let res: OrdersResponse = from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            res.orders.into_iter().map(|placed| placed.id).collect::<Vec<_>>()
+        };
+        assert_eq!(page(&deps, None), ["order-1", "order-2"]);
+        assert_eq!(page(&deps, Some("order-2")), ["order-3", "order-4"]);
+
+        // anyone can prune, a page at a time, the orders the DEX no longer holds
+        deps.querier.open = vec!["order-2".to_string(), "order-5".to_string()];
+        let msg = ExecuteMsg::PruneOrders {
+            start_after: None,
+            limit: Some(3),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info(ALICE, &[]), msg).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(res.attributes[1].value, "2");
+        assert_eq!(res.attributes[2].value, "order-3");
+        let msg = ExecuteMsg::PruneOrders {
+            start_after: Some("order-3".to_string()),
+            limit: Some(3),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(ALICE, &[]), msg).unwrap();
+        assert_eq!(page(&deps, None), ["order-2", "order-5"]);
+    }
+}
@@ -0,0 +1,24 @@
+use cosmwasm_std::StdError;
+use cw_ownable::OwnershipError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error(transparent)]
+    Ownership(#[from] OwnershipError),
+
+    #[error("Base and quote denoms must differ")]
+    IdenticalDenoms {},
+
+    #[error("Orders need a price and a positive quantity")]
+    InvalidOrder {},
+
+    #[error("Order {0} was not placed by this contract")]
+    UnknownOrder(String),
+
+    #[error("Nothing to withdraw")]
+    NothingToWithdraw {},
+}
@@ -0,0 +1,99 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Coin, Uint128};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub base_denom: String,
+    pub quote_denom: String,
+}
+
+#[cw_serde]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+#[cw_serde]
+pub struct LimitOrder {
+    pub side: OrderSide,
+    /// Quote units per base unit in the DEX notation, e.g. `15e-3`
+    pub price: String,
+    /// Base units to buy or sell
+    pub quantity: Uint128,
+    /// Block height the order expires at, good till cancelled when unset
+    pub good_til_height: Option<u64>,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Places limit orders funded from the contract balance, including any funds attached.
+    /// Owner only.
+    PlaceOrders {
+        orders: Vec<LimitOrder>,
+    },
+    /// Cancels the orders still open on the DEX and forgets the filled or expired ones.
+    /// Owner only.
+    CancelOrders {
+        ids: Vec<String>,
+    },
+    /// Forgets up to `limit` orders after `start_after` the DEX no longer holds, i.e. filled or
+    /// expired ones. Callable by anyone, the `last` attribute is where the next call continues.
+    PruneOrders {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Sends funds not locked in orders to `recipient`, or the owner. Owner only.
+    Withdraw {
+        amount: Vec<Coin>,
+        recipient: Option<String>,
+    },
+    UpdateOwnership(cw_ownable::Action),
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    /// Orders placed by the contract
+    #[returns(OrdersResponse)]
+    Orders {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Best priced orders of every creator on one side of the pair, read from the DEX
+    #[returns(OrderBookResponse)]
+    OrderBook { side: OrderSide, limit: Option<u32> },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Option<String>,
+    pub base_denom: String,
+    pub quote_denom: String,
+}
+
+#[cw_serde]
+pub struct PlacedOrder {
+    pub id: String,
+    pub order: LimitOrder,
+}
+
+#[cw_serde]
+pub struct OrdersResponse {
+    pub orders: Vec<PlacedOrder>,
+}
+
+#[cw_serde]
+pub struct BookOrder {
+    pub id: String,
+    pub creator: String,
+    pub price: String,
+    pub quantity: Uint128,
+    pub remaining_quantity: Uint128,
+}
+
+#[cw_serde]
+pub struct OrderBookResponse {
+    pub orders: Vec<BookOrder>,
+}
@@ -0,0 +1,18 @@
+use cosmwasm_schema::cw_serde;
+use cw_storage_plus::{Item, Map};
+
+use crate::msg::LimitOrder;
+
+#[cw_serde]
+pub struct Config {
+    /// Denom bought and sold, usually a smart FT issued by the owner
+    pub base_denom: String,
+    /// Denom prices are expressed in
+    pub quote_denom: String,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+/// Used to derive the DEX order ids, which must be unique per creator
+pub const ORDER_SEQ: Item<u64> = Item::new("order_seq");
+/// Orders placed by the contract, until cancelled or pruned once the DEX filled or expired them
+pub const ORDERS: Map<&str, LimitOrder> = Map::new("orders");
@@ -0,0 +1,364 @@
+use anyhow::{bail, ensure, Result as AnyResult};
+use coreum_wasm_sdk::assetft::{self, BURNING, FREEZING, IBC, MINTING, WHITELISTING};
+use coreum_wasm_sdk::pagination::PageRequest;
+use cosmwasm_std::{
+    to_json_binary, Addr, Api, BankMsg, Binary, BlockInfo, Coin, CustomMsg, CustomQuery, Decimal,
+    Empty, Event, Order, Querier, QuerierWrapper, Storage, Uint128,
+};
+use cw_multi_test::{AppResponse, BankSudo, CosmosRouter, SudoMsg};
+use cw_storage_plus::Map;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::str::FromStr;
+
+use crate::ft_denom;
+
+const DEFAULT_LIMIT: u64 = 10;
+const MAX_LIMIT: u64 = 100;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Token {
+    denom: String,
+    issuer: String,
+    symbol: String,
+    subunit: String,
+    precision: u32,
+    description: Option<String>,
+    features: Vec<u32>,
+    burn_rate: Decimal,
+    send_commission_rate: Decimal,
+    version: u32,
+    globally_frozen: bool,
+    uri: Option<String>,
+    uri_hash: Option<String>,
+}
+
+impl Token {
+    fn has_feature(&self, feature: u32) -> bool {
+        self.features.contains(&feature)
+    }
+}
+
+const TOKENS: Map<&str, Token> = Map::new("coreum_mock_ft_tokens");
+// (account, denom) -> amount
+const FROZEN: Map<(&str, &str), Uint128> = Map::new("coreum_mock_ft_frozen");
+const WHITELISTED: Map<(&str, &str), Uint128> = Map::new("coreum_mock_ft_whitelisted");
+
+pub fn execute<ExecC, QueryC>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    block: &BlockInfo,
+    sender: Addr,
+    msg: assetft::Msg,
+) -> AnyResult<AppResponse>
+where
+    ExecC: CustomMsg + DeserializeOwned + 'static,
+    QueryC: CustomQuery + DeserializeOwned + 'static,
+{
+    match msg {
+        assetft::Msg::Issue {
+            symbol,
+            subunit,
+            precision,
+            initial_amount,
+            description,
+            features,
+            burn_rate,
+            send_commission_rate,
+            uri,
+            uri_hash,
+            ..
+        } => {
+            let denom = ft_denom(&subunit, sender.as_str());
+            ensure!(!TOKENS.has(storage, &denom), "token {} is already issued", denom);
+            let token = Token {
+                denom: denom.clone(),
+                issuer: sender.to_string(),
+                symbol,
+                subunit,
+                precision,
+                description,
+                features: features.unwrap_or_default(),
+                burn_rate: parse_rate(&burn_rate)?,
+                send_commission_rate: parse_rate(&send_commission_rate)?,
+                version: 0,
+                globally_frozen: false,
+                uri,
+                uri_hash,
+            };
+            TOKENS.save(storage, &denom, &token)?;
+            if !initial_amount.is_zero() {
+                let initial = Coin::new(initial_amount.u128(), &denom);
+                mint(api, storage, router, block, &sender, initial)?;
+            }
+            Ok(event(Event::new("issue").add_attribute("denom", denom)))
+        }
+        assetft::Msg::Mint { coin, recipient, .. } => {
+            let token = load_as_issuer(storage, &sender, &coin.denom)?;
+            ensure!(token.has_feature(MINTING), "minting is disabled for {}", coin.denom);
+            let recipient = match recipient {
+                Some(recipient) => api.addr_validate(&recipient)?,
+                None => sender,
+            };
+            mint(api, storage, router, block, &recipient, coin.clone())?;
+            Ok(event(
+                Event::new("mint")
+                    .add_attribute("recipient", recipient)
+                    .add_attribute("amount", coin.to_string()),
+            ))
+        }
+        assetft::Msg::Burn { coin } => {
+            let token = load(storage, &coin.denom)?;
+            ensure!(
+                sender.as_str() == token.issuer || token.has_feature(BURNING),
+                "burning is disabled for {}",
+                coin.denom
+            );
+            router.execute(
+                api,
+                storage,
+                block,
+                sender,
+                BankMsg::Burn { amount: vec![coin.clone()] }.into(),
+            )?;
+            Ok(event(Event::new("burn").add_attribute("amount", coin.to_string())))
+        }
+        assetft::Msg::Freeze { account, coin } => {
+            let token = load_as_issuer(storage, &sender, &coin.denom)?;
+            ensure!(token.has_feature(FREEZING), "freezing is disabled for {}", coin.denom);
+            let frozen = amount_of(storage, &FROZEN, &account, &coin.denom)?;
+            set_amount(storage, &FROZEN, &account, &coin.denom, frozen.checked_add(coin.amount)?)?;
+            Ok(event(Event::new("freeze").add_attribute("account", account)))
+        }
+        assetft::Msg::Unfreeze { account, coin } => {
+            let token = load_as_issuer(storage, &sender, &coin.denom)?;
+            ensure!(token.has_feature(FREEZING), "freezing is disabled for {}", coin.denom);
+            let frozen = amount_of(storage, &FROZEN, &account, &coin.denom)?;
+            ensure!(frozen >= coin.amount, "only {} {} is frozen", frozen, coin.denom);
+            set_amount(storage, &FROZEN, &account, &coin.denom, frozen - coin.amount)?;
+            Ok(event(Event::new("unfreeze").add_attribute("account", account)))
+        }
+        assetft::Msg::SetFrozen { account, coin } => {
+            let token = load_as_issuer(storage, &sender, &coin.denom)?;
+            ensure!(token.has_feature(FREEZING), "freezing is disabled for {}", coin.denom);
+            set_amount(storage, &FROZEN, &account, &coin.denom, coin.amount)?;
+            Ok(event(Event::new("set_frozen").add_attribute("account", account)))
+        }
+        assetft::Msg::GloballyFreeze { denom } => set_globally_frozen(storage, &sender, denom, true),
+        assetft::Msg::GloballyUnfreeze { denom } => {
+            set_globally_frozen(storage, &sender, denom, false)
+        }
+        assetft::Msg::SetWhitelistedLimit { account, coin } => {
+            let token = load_as_issuer(storage, &sender, &coin.denom)?;
+            ensure!(
+                token.has_feature(WHITELISTING),
+                "whitelisting is disabled for {}",
+                coin.denom
+            );
+            set_amount(storage, &WHITELISTED, &account, &coin.denom, coin.amount)?;
+            Ok(event(Event::new("set_whitelisted_limit").add_attribute("account", account)))
+        }
+        assetft::Msg::UpgradeTokenV1 { denom, ibc_enabled } => {
+            let mut token = load_as_issuer(storage, &sender, &denom)?;
+            ensure!(token.version == 0, "token {} is already upgraded", denom);
+            token.version = 1;
+            if ibc_enabled && !token.has_feature(IBC) {
+                token.features.push(IBC);
+            }
+            TOKENS.save(storage, &denom, &token)?;
+            Ok(event(Event::new("upgrade_token_v1").add_attribute("denom", denom)))
+        }
+        msg => bail!("coreum-mock doesn't support {:?}", msg),
+    }
+}
+
+pub fn query(
+    storage: &dyn Storage,
+    querier: &dyn Querier,
+    request: assetft::Query,
+) -> AnyResult<Binary> {
+    let res = match request {
+        assetft::Query::Token { denom } => {
+            to_json_binary(&json!({ "token": token_json(&load(storage, &denom)?) }))?
+        }
+        assetft::Query::Tokens { pagination, issuer } => {
+            let tokens = TOKENS
+                .range(storage, None, None, Order::Ascending)
+                .filter(|item| item.as_ref().map_or(true, |(_, token)| token.issuer == issuer))
+                .map(|item| item.map(|(_, token)| token))
+                .collect::<Result<Vec<_>, _>>()?;
+            let (tokens, pagination) = page(tokens, pagination, |token| token.denom.clone());
+            let tokens: Vec<_> = tokens.iter().map(token_json).collect();
+            to_json_binary(&json!({ "pagination": pagination, "tokens": tokens }))?
+        }
+        assetft::Query::Balance { account, denom } => {
+            let balance = QuerierWrapper::<Empty>::new(querier).query_balance(&account, &denom)?;
+            to_json_binary(&json!({
+                "balance": balance.amount,
+                "whitelisted": amount_of(storage, &WHITELISTED, &account, &denom)?,
+                "frozen": amount_of(storage, &FROZEN, &account, &denom)?,
+                "locked": Uint128::zero(),
+            }))?
+        }
+        assetft::Query::FrozenBalance { account, denom } => {
+            let amount = amount_of(storage, &FROZEN, &account, &denom)?;
+            to_json_binary(&json!({ "balance": Coin { denom, amount } }))?
+        }
+        assetft::Query::FrozenBalances { pagination, account } => {
+            to_json_binary(&balances_json(storage, &FROZEN, &account, pagination)?)?
+        }
+        assetft::Query::WhitelistedBalance { account, denom } => {
+            let amount = amount_of(storage, &WHITELISTED, &account, &denom)?;
+            to_json_binary(&json!({ "balance": Coin { denom, amount } }))?
+        }
+        assetft::Query::WhitelistedBalances { pagination, account } => {
+            to_json_binary(&balances_json(storage, &WHITELISTED, &account, pagination)?)?
+        }
+        request => bail!("coreum-mock doesn't support {:?}", request),
+    };
+    Ok(res)
+}
+
+fn mint<ExecC, QueryC>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    block: &BlockInfo,
+    to: &Addr,
+    coin: Coin,
+) -> AnyResult<AppResponse>
+where
+    ExecC: CustomMsg + DeserializeOwned + 'static,
+    QueryC: CustomQuery + DeserializeOwned + 'static,
+{
+    router.sudo(
+        api,
+        storage,
+        block,
+        SudoMsg::Bank(BankSudo::Mint { to_address: to.to_string(), amount: vec![coin] }),
+    )
+}
+
+fn set_globally_frozen(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    denom: String,
+    frozen: bool,
+) -> AnyResult<AppResponse> {
+    let mut token = load_as_issuer(storage, sender, &denom)?;
+    ensure!(token.has_feature(FREEZING), "freezing is disabled for {}", denom);
+    token.globally_frozen = frozen;
+    TOKENS.save(storage, &denom, &token)?;
+    Ok(event(
+        Event::new("globally_freeze")
+            .add_attribute("denom", denom)
+            .add_attribute("frozen", frozen.to_string()),
+    ))
+}
+
+fn load(storage: &dyn Storage, denom: &str) -> AnyResult<Token> {
+    match TOKENS.may_load(storage, denom)? {
+        Some(token) => Ok(token),
+        None => bail!("token {} not found", denom),
+    }
+}
+
+fn load_as_issuer(storage: &dyn Storage, sender: &Addr, denom: &str) -> AnyResult<Token> {
+    let token = load(storage, denom)?;
+    ensure!(token.issuer == sender.as_str(), "{} is not the issuer of {}", sender, denom);
+    Ok(token)
+}
+
+fn parse_rate(rate: &str) -> AnyResult<Decimal> {
+    if rate.is_empty() {
+        return Ok(Decimal::zero());
+    }
+    let parsed = Decimal::from_str(rate)?;
+    ensure!(parsed <= Decimal::one(), "rate {} is above 1", rate);
+    Ok(parsed)
+}
+
+fn amount_of(
+    storage: &dyn Storage,
+    amounts: &Map<(&str, &str), Uint128>,
+    account: &str,
+    denom: &str,
+) -> AnyResult<Uint128> {
+    Ok(amounts.may_load(storage, (account, denom))?.unwrap_or_default())
+}
+
+fn set_amount(
+    storage: &mut dyn Storage,
+    amounts: &Map<(&str, &str), Uint128>,
+    account: &str,
+    denom: &str,
+    amount: Uint128,
+) -> AnyResult<()> {
+    if amount.is_zero() {
+        amounts.remove(storage, (account, denom));
+    } else {
+        amounts.save(storage, (account, denom), &amount)?;
+    }
+    Ok(())
+}
+
+fn balances_json(
+    storage: &dyn Storage,
+    amounts: &Map<(&str, &str), Uint128>,
+    account: &str,
+    pagination: Option<PageRequest>,
+) -> AnyResult<serde_json::Value> {
+    let balances = amounts
+        .prefix(account)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (balances, pagination) = page(balances, pagination, |coin| coin.denom.clone());
+    Ok(json!({ "pagination": pagination, "balances": balances }))
+}
+
+// Cuts one page out of items sorted by key; `next_key` is the first key left out
+pub(crate) fn page<T>(
+    items: Vec<T>,
+    pagination: Option<PageRequest>,
+    key_of: impl Fn(&T) -> String,
+) -> (Vec<T>, serde_json::Value) {
+    let (start, limit) = pagination.map(|p| (p.key, p.limit)).unwrap_or((None, None));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let total = items.len() as u64;
+
+    let mut items: Vec<T> = match start {
+        Some(start) => items.into_iter().filter(|item| key_of(item) >= start).collect(),
+        None => items,
+    };
+    let next_key = if items.len() > limit {
+        items.split_off(limit).first().map(&key_of)
+    } else {
+        None
+    };
+    (items, json!({ "next_key": next_key, "total": total }))
+}
+
+fn token_json(token: &Token) -> serde_json::Value {
+    json!({
+        "denom": token.denom,
+        "issuer": token.issuer,
+        "symbol": token.symbol,
+        "subunit": token.subunit,
+        "precision": token.precision,
+        "description": token.description,
+        "globally_frozen": token.globally_frozen,
+        "features": token.features,
+        "burn_rate": token.burn_rate.to_string(),
+        "send_commission_rate": token.send_commission_rate.to_string(),
+        "version": token.version,
+        "uri": token.uri,
+        "uri_hash": token.uri_hash,
+    })
+}
+
+fn event(event: Event) -> AppResponse {
+    AppResponse { events: vec![event], data: None }
+}
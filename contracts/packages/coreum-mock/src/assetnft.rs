@@ -0,0 +1,333 @@
+use anyhow::{bail, ensure, Result as AnyResult};
+use coreum_wasm_sdk::{assetnft, nft};
+use cosmwasm_std::{to_json_binary, Addr, Binary, Empty, Event, Order, Storage};
+use cw_multi_test::AppResponse;
+use cw_storage_plus::Map;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::assetft::page;
+use crate::nft_class_id;
+
+// assetnft class features
+const BURNING: u32 = 0;
+const FREEZING: u32 = 1;
+const WHITELISTING: u32 = 2;
+const DISABLE_SENDING: u32 = 3;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Class {
+    id: String,
+    issuer: String,
+    name: String,
+    symbol: String,
+    description: Option<String>,
+    uri: Option<String>,
+    uri_hash: Option<String>,
+    data: Option<Binary>,
+    features: Vec<u32>,
+}
+
+impl Class {
+    fn has_feature(&self, feature: u32) -> bool {
+        self.features.contains(&feature)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Nft {
+    pub class_id: String,
+    pub id: String,
+    pub uri: Option<String>,
+    pub uri_hash: Option<String>,
+    pub data: Option<Binary>,
+    pub owner: String,
+}
+
+const CLASSES: Map<&str, Class> = Map::new("coreum_mock_nft_classes");
+// (class_id, id)
+const NFTS: Map<(&str, &str), Nft> = Map::new("coreum_mock_nfts");
+const BURNT: Map<(&str, &str), Empty> = Map::new("coreum_mock_nft_burnt");
+const FROZEN: Map<(&str, &str), Empty> = Map::new("coreum_mock_nft_frozen");
+// (class_id, account)
+const CLASS_FROZEN: Map<(&str, &str), Empty> = Map::new("coreum_mock_nft_class_frozen");
+const CLASS_WHITELISTED: Map<(&str, &str), Empty> = Map::new("coreum_mock_nft_class_whitelisted");
+// (class_id, id, account)
+const WHITELISTED: Map<(&str, &str, &str), Empty> = Map::new("coreum_mock_nft_whitelisted");
+
+pub fn execute(storage: &mut dyn Storage, sender: Addr, msg: assetnft::Msg) -> AnyResult<AppResponse> {
+    match msg {
+        assetnft::Msg::IssueClass {
+            name,
+            symbol,
+            description,
+            uri,
+            uri_hash,
+            data,
+            features,
+            ..
+        } => {
+            let id = nft_class_id(&symbol, sender.as_str());
+            ensure!(!CLASSES.has(storage, &id), "class {} is already issued", id);
+            let class = Class {
+                id: id.clone(),
+                issuer: sender.to_string(),
+                name,
+                symbol,
+                description,
+                uri,
+                uri_hash,
+                data,
+                features: features.unwrap_or_default(),
+            };
+            CLASSES.save(storage, &id, &class)?;
+            Ok(event(Event::new("issue_class").add_attribute("class_id", id)))
+        }
+        assetnft::Msg::Mint { class_id, id, uri, uri_hash, data, recipient, .. } => {
+            let owner = recipient.unwrap_or_default();
+            mint(storage, &sender, Nft { class_id, id, uri, uri_hash, data, owner })
+        }
+        assetnft::Msg::Burn { class_id, id } => {
+            let class = load_class(storage, &class_id)?;
+            let nft = load_nft(storage, &class_id, &id)?;
+            ensure!(nft.owner == sender.as_str(), "{} doesn't own {}/{}", sender, class_id, id);
+            ensure!(
+                sender.as_str() == class.issuer || class.has_feature(BURNING),
+                "burning is disabled for {}",
+                class_id
+            );
+            ensure!(!is_frozen(storage, &class, &nft), "{}/{} is frozen", class_id, id);
+            NFTS.remove(storage, (&class_id, &id));
+            BURNT.save(storage, (&class_id, &id), &Empty {})?;
+            Ok(event(
+                Event::new("burn")
+                    .add_attribute("class_id", class_id)
+                    .add_attribute("id", id),
+            ))
+        }
+        assetnft::Msg::Freeze { class_id, id } => {
+            load_class_as_issuer(storage, &sender, &class_id, FREEZING)?;
+            load_nft(storage, &class_id, &id)?;
+            FROZEN.save(storage, (&class_id, &id), &Empty {})?;
+            Ok(event(Event::new("freeze").add_attribute("id", id)))
+        }
+        assetnft::Msg::Unfreeze { class_id, id } => {
+            load_class_as_issuer(storage, &sender, &class_id, FREEZING)?;
+            FROZEN.remove(storage, (&class_id, &id));
+            Ok(event(Event::new("unfreeze").add_attribute("id", id)))
+        }
+        assetnft::Msg::AddToWhitelist { class_id, id, account } => {
+            load_class_as_issuer(storage, &sender, &class_id, WHITELISTING)?;
+            WHITELISTED.save(storage, (&class_id, &id, &account), &Empty {})?;
+            Ok(event(Event::new("add_to_whitelist").add_attribute("account", account)))
+        }
+        assetnft::Msg::RemoveFromWhitelist { class_id, id, account } => {
+            load_class_as_issuer(storage, &sender, &class_id, WHITELISTING)?;
+            WHITELISTED.remove(storage, (&class_id, &id, &account));
+            Ok(event(Event::new("remove_from_whitelist").add_attribute("account", account)))
+        }
+        assetnft::Msg::ClassFreeze { class_id, account } => {
+            load_class_as_issuer(storage, &sender, &class_id, FREEZING)?;
+            CLASS_FROZEN.save(storage, (&class_id, &account), &Empty {})?;
+            Ok(event(Event::new("class_freeze").add_attribute("account", account)))
+        }
+        assetnft::Msg::ClassUnfreeze { class_id, account } => {
+            load_class_as_issuer(storage, &sender, &class_id, FREEZING)?;
+            CLASS_FROZEN.remove(storage, (&class_id, &account));
+            Ok(event(Event::new("class_unfreeze").add_attribute("account", account)))
+        }
+        assetnft::Msg::AddToClassWhitelist { class_id, account } => {
+            load_class_as_issuer(storage, &sender, &class_id, WHITELISTING)?;
+            CLASS_WHITELISTED.save(storage, (&class_id, &account), &Empty {})?;
+            Ok(event(Event::new("add_to_class_whitelist").add_attribute("account", account)))
+        }
+        assetnft::Msg::RemoveFromClassWhitelist { class_id, account } => {
+            load_class_as_issuer(storage, &sender, &class_id, WHITELISTING)?;
+            CLASS_WHITELISTED.remove(storage, (&class_id, &account));
+            Ok(event(Event::new("remove_from_class_whitelist").add_attribute("account", account)))
+        }
+        msg => bail!("coreum-mock doesn't support {:?}", msg),
+    }
+}
+
+pub fn execute_nft(storage: &mut dyn Storage, sender: Addr, msg: nft::Msg) -> AnyResult<AppResponse> {
+    match msg {
+        nft::Msg::Send { class_id, id, receiver } => {
+            let class = load_class(storage, &class_id)?;
+            let mut nft = load_nft(storage, &class_id, &id)?;
+            ensure!(nft.owner == sender.as_str(), "{} doesn't own {}/{}", sender, class_id, id);
+            // the issuer is exempt from every transfer restriction
+            if sender.as_str() != class.issuer {
+                ensure!(!class.has_feature(DISABLE_SENDING), "sending is disabled for {}", class_id);
+                ensure!(!is_frozen(storage, &class, &nft), "{}/{} is frozen", class_id, id);
+            }
+            if receiver != class.issuer && class.has_feature(WHITELISTING) {
+                let whitelisted = WHITELISTED.has(storage, (&class_id, &id, &receiver))
+                    || CLASS_WHITELISTED.has(storage, (&class_id, &receiver));
+                ensure!(whitelisted, "{} is not whitelisted for {}/{}", receiver, class_id, id);
+            }
+            nft.owner = receiver.clone();
+            NFTS.save(storage, (&class_id, &id), &nft)?;
+            Ok(event(
+                Event::new("send")
+                    .add_attribute("class_id", class_id)
+                    .add_attribute("id", id)
+                    .add_attribute("receiver", receiver),
+            ))
+        }
+        #[allow(unreachable_patterns)]
+        msg => bail!("coreum-mock doesn't support {:?}", msg),
+    }
+}
+
+/// Mints `nft` as the class issuer; an empty owner means the issuer keeps it
+pub fn mint(storage: &mut dyn Storage, sender: &Addr, mut nft: Nft) -> AnyResult<AppResponse> {
+    let class = load_class(storage, &nft.class_id)?;
+    ensure!(class.issuer == sender.as_str(), "{} is not the issuer of {}", sender, nft.class_id);
+    let key = (nft.class_id.as_str(), nft.id.as_str());
+    ensure!(!NFTS.has(storage, key), "{}/{} already exists", nft.class_id, nft.id);
+    ensure!(!BURNT.has(storage, key), "{}/{} was burnt", nft.class_id, nft.id);
+
+    if nft.owner.is_empty() {
+        nft.owner = sender.to_string();
+    }
+    NFTS.save(storage, key, &nft)?;
+    Ok(event(
+        Event::new("mint")
+            .add_attribute("class_id", &nft.class_id)
+            .add_attribute("id", &nft.id)
+            .add_attribute("owner", &nft.owner),
+    ))
+}
+
+pub fn query(storage: &dyn Storage, request: assetnft::Query) -> AnyResult<Binary> {
+    let res = match request {
+        assetnft::Query::Class { id } => {
+            let class = load_class(storage, &id)?;
+            to_json_binary(&json!({ "class": class }))?
+        }
+        assetnft::Query::Frozen { id, class_id } => {
+            let class = load_class(storage, &class_id)?;
+            let nft = load_nft(storage, &class_id, &id)?;
+            to_json_binary(&json!({ "frozen": is_frozen(storage, &class, &nft) }))?
+        }
+        assetnft::Query::Whitelisted { id, class_id, account } => {
+            let whitelisted = WHITELISTED.has(storage, (&class_id, &id, &account))
+                || CLASS_WHITELISTED.has(storage, (&class_id, &account));
+            to_json_binary(&json!({ "whitelisted": whitelisted }))?
+        }
+        assetnft::Query::BurntNFT { class_id, nft_id } => {
+            to_json_binary(&json!({ "burnt": BURNT.has(storage, (&class_id, &nft_id)) }))?
+        }
+        assetnft::Query::ClassFrozen { class_id, account } => {
+            to_json_binary(&json!({ "frozen": CLASS_FROZEN.has(storage, (&class_id, &account)) }))?
+        }
+        request => bail!("coreum-mock doesn't support {:?}", request),
+    };
+    Ok(res)
+}
+
+pub fn query_nft(storage: &dyn Storage, request: nft::Query) -> AnyResult<Binary> {
+    let res = match request {
+        nft::Query::Balance { class_id, owner } => {
+            let nfts = nfts_in(storage, &class_id)?;
+            let amount = nfts.iter().filter(|nft| nft.owner == owner).count();
+            to_json_binary(&json!({ "amount": amount as u64 }))?
+        }
+        nft::Query::Owner { class_id, id } => {
+            to_json_binary(&json!({ "owner": load_nft(storage, &class_id, &id)?.owner }))?
+        }
+        nft::Query::Supply { class_id } => {
+            to_json_binary(&json!({ "amount": nfts_in(storage, &class_id)?.len() as u64 }))?
+        }
+        nft::Query::NFT { class_id, id } => {
+            let nft = load_nft(storage, &class_id, &id)?;
+            to_json_binary(&json!({ "nft": nft_json(&nft) }))?
+        }
+        nft::Query::NFTs { class_id, owner, pagination } => {
+            let nfts = match class_id {
+                Some(class_id) => nfts_in(storage, &class_id)?,
+                None => NFTS
+                    .range(storage, None, None, Order::Ascending)
+                    .map(|item| item.map(|(_, nft)| nft))
+                    .collect::<Result<Vec<_>, _>>()?,
+            };
+            let nfts = nfts
+                .into_iter()
+                .filter(|nft| owner.as_ref().map_or(true, |owner| &nft.owner == owner))
+                .collect();
+            let (nfts, pagination) = page(nfts, pagination, |nft| nft.id.clone());
+            let nfts: Vec<_> = nfts.iter().map(nft_json).collect();
+            to_json_binary(&json!({ "nfts": nfts, "pagination": pagination }))?
+        }
+        nft::Query::Class { class_id } => {
+            let class = load_class(storage, &class_id)?;
+            to_json_binary(&json!({
+                "class": {
+                    "id": class.id,
+                    "name": class.name,
+                    "symbol": class.symbol,
+                    "description": class.description,
+                    "uri": class.uri,
+                    "uri_hash": class.uri_hash,
+                    "data": class.data,
+                }
+            }))?
+        }
+        request => bail!("coreum-mock doesn't support {:?}", request),
+    };
+    Ok(res)
+}
+
+fn load_class(storage: &dyn Storage, class_id: &str) -> AnyResult<Class> {
+    match CLASSES.may_load(storage, class_id)? {
+        Some(class) => Ok(class),
+        None => bail!("class {} not found", class_id),
+    }
+}
+
+fn load_class_as_issuer(
+    storage: &dyn Storage,
+    sender: &Addr,
+    class_id: &str,
+    feature: u32,
+) -> AnyResult<Class> {
+    let class = load_class(storage, class_id)?;
+    ensure!(class.issuer == sender.as_str(), "{} is not the issuer of {}", sender, class_id);
+    ensure!(class.has_feature(feature), "feature {} is disabled for {}", feature, class_id);
+    Ok(class)
+}
+
+fn load_nft(storage: &dyn Storage, class_id: &str, id: &str) -> AnyResult<Nft> {
+    match NFTS.may_load(storage, (class_id, id))? {
+        Some(nft) => Ok(nft),
+        None => bail!("nft {}/{} not found", class_id, id),
+    }
+}
+
+fn nfts_in(storage: &dyn Storage, class_id: &str) -> AnyResult<Vec<Nft>> {
+    Ok(NFTS
+        .prefix(class_id)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, nft)| nft))
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+// an NFT is frozen on its own or through its owner being frozen for the whole class
+fn is_frozen(storage: &dyn Storage, class: &Class, nft: &Nft) -> bool {
+    FROZEN.has(storage, (&class.id, &nft.id)) || CLASS_FROZEN.has(storage, (&class.id, &nft.owner))
+}
+
+fn nft_json(nft: &Nft) -> serde_json::Value {
+    json!({
+        "class_id": nft.class_id,
+        "id": nft.id,
+        "uri": nft.uri,
+        "uri_hash": nft.uri_hash,
+        "data": nft.data,
+    })
+}
+
+fn event(event: Event) -> AppResponse {
+    AppResponse { events: vec![event], data: None }
+}
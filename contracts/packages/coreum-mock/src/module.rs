@@ -0,0 +1,71 @@
+use anyhow::{bail, Result as AnyResult};
+use coreum_wasm_sdk::core::{CoreumMsg, CoreumQueries};
+use cosmwasm_std::{Addr, Api, Binary, BlockInfo, CustomMsg, CustomQuery, Empty, Querier, Storage};
+use cw_multi_test::{AppResponse, CosmosRouter, Module};
+use serde::de::DeserializeOwned;
+
+use crate::{assetft, assetnft};
+
+/// Custom module dispatching `CoreumMsg` and `CoreumQueries` to the mocked Coreum modules
+#[derive(Default)]
+pub struct CoreumModule {}
+
+impl Module for CoreumModule {
+    type ExecT = CoreumMsg;
+    type QueryT = CoreumQueries;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: CoreumMsg,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        match msg {
+            CoreumMsg::AssetFT(msg) => assetft::execute(api, storage, router, block, sender, msg),
+            CoreumMsg::AssetNFT(msg) => assetnft::execute(storage, sender, msg),
+            CoreumMsg::NFT(msg) => assetnft::execute_nft(storage, sender, msg),
+            #[allow(unreachable_patterns)]
+            msg => bail!("coreum-mock doesn't support {:?}", msg),
+        }
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        storage: &dyn Storage,
+        querier: &dyn Querier,
+        _block: &BlockInfo,
+        request: CoreumQueries,
+    ) -> AnyResult<Binary> {
+        match request {
+            CoreumQueries::AssetFT(request) => assetft::query(storage, querier, request),
+            CoreumQueries::AssetNFT(request) => assetnft::query(storage, request),
+            CoreumQueries::NFT(request) => assetnft::query_nft(storage, request),
+            #[allow(unreachable_patterns)]
+            request => bail!("coreum-mock doesn't support {:?}", request),
+        }
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _msg: Empty,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        bail!("coreum-mock has no sudo messages")
+    }
+}
@@ -0,0 +1,47 @@
+use anyhow::{bail, Result as AnyResult};
+use coreum_wasm_sdk::types::coreum::asset::nft::v1::MsgMint;
+use cosmwasm_std::{Addr, Api, Binary, BlockInfo, CustomMsg, CustomQuery, Storage};
+use cw_multi_test::{AppResponse, CosmosRouter, Stargate};
+use serde::de::DeserializeOwned;
+
+use crate::assetnft::{self, Nft};
+
+const MSG_MINT_NFT: &str = "/coreum.asset.nft.v1.MsgMint";
+
+/// Stargate handler for the protobuf messages contracts send instead of `CoreumMsg`
+#[derive(Default)]
+pub struct CoreumStargate {}
+
+impl Stargate for CoreumStargate {
+    fn execute<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        sender: Addr,
+        type_url: String,
+        value: Binary,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        match type_url.as_str() {
+            MSG_MINT_NFT => {
+                let msg = MsgMint::try_from(value)?;
+                // protobuf has no optional strings, empty means unset; data is kept as the raw Any value
+                let nft = Nft {
+                    class_id: msg.class_id,
+                    id: msg.id,
+                    uri: Some(msg.uri).filter(|uri| !uri.is_empty()),
+                    uri_hash: Some(msg.uri_hash).filter(|hash| !hash.is_empty()),
+                    data: msg.data.map(|data| Binary::from(data.value)),
+                    owner: msg.recipient,
+                };
+                assetnft::mint(storage, &sender, nft)
+            }
+            _ => bail!("coreum-mock doesn't support {}", type_url),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use coreum_wasm_sdk::assetft::{self, FrozenBalanceResponse, TokenResponse};
+use coreum_wasm_sdk::core::{CoreumMsg, CoreumQueries};
+use coreum_wasm_sdk::{assetnft, nft};
+use cosmwasm_std::{coin, Addr, CosmosMsg, QueryRequest, Uint128};
+
+use crate::{coreum_app, ft_denom, nft_class_id};
+
+const ISSUER: &str = "issuer";
+const HOLDER: &str = "holder";
+
+fn issue_msg(features: Vec<u32>) -> CosmosMsg<CoreumMsg> {
+    CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Issue {
+        symbol: "TKN".to_string(),
+        subunit: "utkn".to_string(),
+        precision: 6,
+        initial_amount: Uint128::new(1000),
+        description: None,
+        features: Some(features),
+        burn_rate: "0".to_string(),
+        send_commission_rate: "0".to_string(),
+        uri: None,
+        uri_hash: None,
+    }))
+}
+
+#[test]
+fn ft_lives_in_the_bank() {
+    let mut app = coreum_app();
+    let issuer = Addr::unchecked(ISSUER);
+    let denom = ft_denom("utkn", ISSUER);
+
+    app.execute(issuer.clone(), issue_msg(vec![assetft::MINTING, assetft::FREEZING])).unwrap();
+    assert_eq!(app.wrap().query_balance(ISSUER, &denom).unwrap().amount.u128(), 1000);
+
+    let mint = CoreumMsg::AssetFT(assetft::Msg::Mint {
+        coin: coin(500, &denom),
+        recipient: Some(HOLDER.to_string()),
+    });
+    // only the issuer mints
+    app.execute(Addr::unchecked(HOLDER), CosmosMsg::Custom(mint.clone())).unwrap_err();
+    app.execute(issuer.clone(), CosmosMsg::Custom(mint)).unwrap();
+    assert_eq!(app.wrap().query_balance(HOLDER, &denom).unwrap().amount.u128(), 500);
+
+    let freeze = CoreumMsg::AssetFT(assetft::Msg::Freeze {
+        account: HOLDER.to_string(),
+        coin: coin(200, &denom),
+    });
+    app.execute(issuer, CosmosMsg::Custom(freeze)).unwrap();
+    let request = QueryRequest::Custom(CoreumQueries::AssetFT(assetft::Query::FrozenBalance {
+        account: HOLDER.to_string(),
+        denom: denom.clone(),
+    }));
+    let res: FrozenBalanceResponse = app.wrap().query(&request).unwrap();
+    assert_eq!(res.balance, coin(200, &denom));
+
+    let request = QueryRequest::Custom(CoreumQueries::AssetFT(assetft::Query::Token { denom }));
+    let res: TokenResponse = app.wrap().query(&request).unwrap();
+    assert_eq!(res.token.issuer, ISSUER);
+    assert_eq!(res.token.precision, 6);
+}
+
+#[test]
+fn nft_send_respects_freezing() {
+    let mut app = coreum_app();
+    let issuer = Addr::unchecked(ISSUER);
+    let class_id = nft_class_id("NFT", ISSUER);
+
+    let issue = CoreumMsg::AssetNFT(assetnft::Msg::IssueClass {
+        name: "Collection".to_string(),
+        symbol: "NFT".to_string(),
+        description: None,
+        uri: None,
+        uri_hash: None,
+        data: None,
+        features: Some(vec![1]), // freezing
+        royalty_rate: None,
+    });
+    app.execute(issuer.clone(), CosmosMsg::Custom(issue)).unwrap();
+    let mint = CoreumMsg::AssetNFT(assetnft::Msg::Mint {
+        class_id: class_id.clone(),
+        id: "1".to_string(),
+        uri: None,
+        uri_hash: None,
+        data: None,
+        recipient: Some(HOLDER.to_string()),
+    });
+    app.execute(issuer.clone(), CosmosMsg::Custom(mint)).unwrap();
+
+    let freeze = CoreumMsg::AssetNFT(assetnft::Msg::Freeze {
+        class_id: class_id.clone(),
+        id: "1".to_string(),
+    });
+    app.execute(issuer, CosmosMsg::Custom(freeze)).unwrap();
+    let send = CoreumMsg::NFT(nft::Msg::Send {
+        class_id: class_id.clone(),
+        id: "1".to_string(),
+        receiver: "other".to_string(),
+    });
+    app.execute(Addr::unchecked(HOLDER), CosmosMsg::Custom(send)).unwrap_err();
+
+    let request = QueryRequest::Custom(CoreumQueries::NFT(nft::Query::Owner {
+        class_id,
+        id: "1".to_string(),
+    }));
+    let res: nft::OwnerResponse = app.wrap().query(&request).unwrap();
+    assert_eq!(res.owner, HOLDER);
+}
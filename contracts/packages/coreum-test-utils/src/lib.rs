@@ -0,0 +1,22 @@
+use coreum_wasm_sdk::core::CoreumQueries;
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::OwnedDeps;
+use std::marker::PhantomData;
+
+/// Mock dependencies of a contract whose entry points take `DepsMut<CoreumQueries>`
+pub type CoreumDeps = OwnedDeps<MockStorage, MockApi, MockQuerier<CoreumQueries>, CoreumQueries>;
+
+/// Mock dependencies with an empty querier, Coreum queries fail with an unsupported request
+pub fn mock_coreum_deps() -> CoreumDeps {
+    mock_coreum_deps_with_querier(MockQuerier::new(&[]))
+}
+
+/// Mock dependencies answering queries through `querier`, e.g. one built with a custom handler
+pub fn mock_coreum_deps_with_querier(querier: MockQuerier<CoreumQueries>) -> CoreumDeps {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier,
+        custom_query_type: PhantomData,
+    }
+}
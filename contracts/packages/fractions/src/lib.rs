@@ -0,0 +1,58 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::Map;
+
+/// Fractions held by an address, keyed by (token_id, holder) so an asset's holders can be paged through
+pub const FRACTIONAL_BALANCES: Map<(u64, Addr), Uint128> = Map::new("fractional_balances");
+/// Layout of FRACTIONAL_BALANCES before it was keyed by asset, (holder, token_id)
+pub const LEGACY_FRACTIONAL_BALANCES: Map<(Addr, u64), Uint128> = Map::new("fractional_balances");
+
+/// Aggregate holder figures of an asset, maintained on every balance change
+#[cw_serde]
+#[derive(Default)]
+pub struct HolderStats {
+    pub holder_count: u64,
+    pub held_supply: Uint128,
+}
+
+pub const HOLDER_STATS: Map<u64, HolderStats> = Map::new("holder_stats");
+
+pub fn fraction_balance(storage: &dyn Storage, token_id: u64, holder: &Addr) -> StdResult<Uint128> {
+    Ok(FRACTIONAL_BALANCES.may_load(storage, (token_id, holder.clone()))?.unwrap_or_default())
+}
+
+/// Store the balance of a holder, keeping the holder statistics of the asset in sync
+pub fn set_fraction_balance(storage: &mut dyn Storage, token_id: u64, holder: &Addr, balance: Uint128) -> StdResult<()> {
+    let previous = fraction_balance(storage, token_id, holder)?;
+    let mut stats = HOLDER_STATS.may_load(storage, token_id)?.unwrap_or_default();
+    stats.held_supply = stats.held_supply.checked_sub(previous)?.checked_add(balance)?;
+
+    if balance.is_zero() {
+        FRACTIONAL_BALANCES.remove(storage, (token_id, holder.clone()));
+        if !previous.is_zero() {
+            stats.holder_count -= 1;
+        }
+    } else {
+        FRACTIONAL_BALANCES.save(storage, (token_id, holder.clone()), &balance)?;
+        if previous.is_zero() {
+            stats.holder_count += 1;
+        }
+    }
+
+    HOLDER_STATS.save(storage, token_id, &stats)
+}
+
+/// Move every balance of the legacy layout under its asset, rebuilding the holder statistics,
+/// and return how many balances were moved. Must only run on storage still in the legacy layout.
+pub fn migrate_legacy_balances(storage: &mut dyn Storage) -> StdResult<usize> {
+    let legacy = LEGACY_FRACTIONAL_BALANCES
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for ((holder, token_id), _) in &legacy {
+        LEGACY_FRACTIONAL_BALANCES.remove(storage, (holder.clone(), *token_id));
+    }
+    for ((holder, token_id), balance) in &legacy {
+        set_fraction_balance(storage, *token_id, holder, *balance)?;
+    }
+    Ok(legacy.len())
+}
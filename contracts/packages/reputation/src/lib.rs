@@ -0,0 +1,91 @@
+//! Stable query interface of the reputationAndTrust contract.
+//!
+//! Contracts gating behaviour on reputation (marketplace, DAO, airdrop) depend on this crate
+//! rather than on the contract itself and read scores through [`ReputationQuerier`].
+
+use cosmwasm_std::{
+    to_binary, Addr, CustomQuery, QuerierWrapper, QueryRequest, StdResult, WasmQuery,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Subset of the reputationAndTrust `QueryMsg` that other contracts may rely on.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReputationQueryMsg {
+    /// Returns the `ReputationRaw` of a user, zero when the user has no reputation yet.
+    GetReputationRaw {
+        /// The address of the user whose reputation is to be queried.
+        user: String,
+    },
+}
+
+/// The `ReputationRaw` struct is returned by the `GetReputationRaw` query.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReputationRaw {
+    /// The address of the user.
+    pub user: Addr,
+    /// The reputation value of the user.
+    pub reputation: u64,
+}
+
+/// Typed access to a deployed reputationAndTrust contract.
+pub struct ReputationQuerier<'a, C: CustomQuery> {
+    querier: &'a QuerierWrapper<'a, C>,
+    contract: Addr,
+}
+
+impl<'a, C: CustomQuery> ReputationQuerier<'a, C> {
+    pub fn new(querier: &'a QuerierWrapper<'a, C>, contract: Addr) -> Self {
+        ReputationQuerier { querier, contract }
+    }
+
+    /// Queries the raw reputation record of `user`.
+    pub fn reputation_raw(&self, user: impl Into<String>) -> StdResult<ReputationRaw> {
+        let msg = ReputationQueryMsg::GetReputationRaw { user: user.into() };
+        self.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.contract.to_string(),
+            msg: to_binary(&msg)?,
+        }))
+    }
+
+    /// Queries the reputation score of `user`.
+    pub fn reputation(&self, user: impl Into<String>) -> StdResult<u64> {
+        Ok(self.reputation_raw(user)?.reputation)
+    }
+
+    /// Checks whether `user` has at least `min_score` reputation.
+    pub fn meets(&self, user: impl Into<String>, min_score: u64) -> StdResult<bool> {
+        Ok(self.reputation(user)? >= min_score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockQuerier;
+    use cosmwasm_std::{from_binary, ContractResult, Empty, SystemResult};
+
+    #[test]
+    fn queries_reputation_raw() {
+        let mut querier = MockQuerier::<Empty>::new(&[]);
+        querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "reputation" => {
+                let ReputationQueryMsg::GetReputationRaw { user } = from_binary(msg).unwrap();
+                let reputation = if user == "alice" { 42 } else { 0 };
+                let res = ReputationRaw {
+                    user: Addr::unchecked(user),
+                    reputation,
+                };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+        let reputation = ReputationQuerier::new(&wrapper, Addr::unchecked("reputation"));
+
+        assert_eq!(reputation.reputation("alice").unwrap(), 42);
+        assert!(reputation.meets("alice", 42).unwrap());
+        assert!(!reputation.meets("bob", 1).unwrap());
+    }
+}
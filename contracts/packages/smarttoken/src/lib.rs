@@ -0,0 +1,701 @@
+//! Smart token shared by the tokenization contracts, issued through Coreum assetft.
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    coin, to_binary, Addr, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Storage, Uint128,
+};
+use cw_storage_plus::{Item, Map, SnapshotMap, Strategy};
+use serde::{Deserialize, Serialize};
+use coreum_wasm_sdk::assetft;
+use coreum_wasm_sdk::core::{CoreumMsg, CoreumQueries};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct InstantiateMsg {
+    pub owner: String,
+    pub symbol: String,
+    pub subunit: String,
+    pub precision: u32,
+    pub initial_amount: Uint128,
+    /// keep balances in the bank module as the issued denom instead of the internal ledger
+    #[serde(default)]
+    pub native: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ExecuteMsg {
+    Mint { to: String, amount: Uint128 },
+    Transfer { to: String, amount: Uint128 },
+    Burn { amount: Uint128 },
+    IncreaseAllowance { spender: String, amount: Uint128 },
+    DecreaseAllowance { spender: String, amount: Uint128 },
+    TransferFrom { owner: String, to: String, amount: Uint128 },
+    BurnFrom { owner: String, amount: Uint128 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum QueryMsg {
+    Balance { address: String },
+    Allowance { owner: String, spender: String },
+    /// ledger balance of `address` as it stood just before block `height`
+    BalanceAt { address: String, height: u64 },
+    TokenInfo {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TokenInfoResponse {
+    pub denom: String,
+    pub owner: String,
+    pub total_supply: Uint128,
+    pub precision: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TokenInfo {
+    pub owner: Addr,
+    pub total_supply: Uint128,
+    pub denom: String,
+    #[serde(default)]
+    pub precision: u32,
+}
+
+pub const TOKEN_INFO: Item<TokenInfo> = Item::new("token_info");
+// checkpointed every block so holders' past balances can back dividend and vote snapshots
+pub const BALANCES: SnapshotMap<Addr, Uint128> = SnapshotMap::new(
+    "balances",
+    "balances__checkpoints",
+    "balances__changelog",
+    Strategy::EveryBlock,
+);
+// (owner, spender) -> amount the spender may still move out of the owner's balance
+pub const ALLOWANCES: Map<(Addr, Addr), Uint128> = Map::new("allowances");
+// set when balances are held as the issued denom rather than in BALANCES
+pub const NATIVE: Item<bool> = Item::new("native");
+
+fn is_native(storage: &dyn Storage) -> StdResult<bool> {
+    Ok(NATIVE.may_load(storage)?.unwrap_or(false))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response<CoreumMsg>> {
+    let owner = deps.api.addr_validate(&msg.owner)?;
+    let denom = format!("{}-{}", msg.subunit, env.contract.address).to_lowercase();
+
+    let token_info = TokenInfo {
+        owner: owner.clone(),
+        total_supply: msg.initial_amount,
+        denom: denom.clone(),
+        precision: msg.precision,
+    };
+    TOKEN_INFO.save(deps.storage, &token_info)?;
+    NATIVE.save(deps.storage, &msg.native)?;
+    if !msg.native && !msg.initial_amount.is_zero() {
+        BALANCES.save(deps.storage, owner.clone(), &msg.initial_amount, env.block.height)?;
+    }
+
+    let issue_msg = CoreumMsg::AssetFT(assetft::Msg::Issue {
+        symbol: msg.symbol,
+        subunit: msg.subunit.clone(),
+        precision: msg.precision,
+        initial_amount: msg.initial_amount,
+        description: None,
+        features: Some(vec![0]), // 0 - minting
+        burn_rate: Some("0".into()),
+        send_commission_rate: Some("0.1".into()), // 10% commission for sending
+    });
+
+    let mut res = Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("owner", owner.to_string())
+        .add_attribute("denom", denom.clone())
+        .add_message(issue_msg);
+    // the initial amount is issued to the contract, hand it to the owner
+    if msg.native && !msg.initial_amount.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: owner.to_string(),
+            amount: vec![coin(msg.initial_amount.u128(), denom)],
+        });
+    }
+    Ok(res)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response<CoreumMsg>> {
+    match msg {
+        ExecuteMsg::Mint { to, amount } => execute_mint(deps, env, info, to, amount),
+        ExecuteMsg::Transfer { to, amount } => execute_transfer(deps, env, info, to, amount),
+        ExecuteMsg::Burn { amount } => execute_burn(deps, env, info, amount),
+        ExecuteMsg::IncreaseAllowance { spender, amount } => {
+            execute_increase_allowance(deps, info, spender, amount)
+        }
+        ExecuteMsg::DecreaseAllowance { spender, amount } => {
+            execute_decrease_allowance(deps, info, spender, amount)
+        }
+        ExecuteMsg::TransferFrom { owner, to, amount } => {
+            execute_transfer_from(deps, env, info, owner, to, amount)
+        }
+        ExecuteMsg::BurnFrom { owner, amount } => {
+            execute_burn_from(deps, env, info, owner, amount)
+        }
+    }
+}
+
+fn execute_mint(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    to: String,
+    amount: Uint128,
+) -> StdResult<Response<CoreumMsg>> {
+    let mut token_info = TOKEN_INFO.load(deps.storage)?;
+    if info.sender != token_info.owner {
+        return Err(StdError::generic_err("Only the owner can mint tokens"));
+    }
+    token_info.total_supply = token_info.total_supply.checked_add(amount)?;
+    TOKEN_INFO.save(deps.storage, &token_info)?;
+
+    let to_addr = deps.api.addr_validate(&to)?;
+    if is_native(deps.storage)? {
+        // mint to the contract, then pass the coins on
+        let minted = coin(amount.u128(), token_info.denom);
+        return Ok(Response::new()
+            .add_message(CoreumMsg::AssetFT(assetft::Msg::Mint { coin: minted.clone() }))
+            .add_message(BankMsg::Send { to_address: to_addr.to_string(), amount: vec![minted] })
+            .add_attribute("method", "mint")
+            .add_attribute("to", to_addr.to_string())
+            .add_attribute("amount", amount.to_string()));
+    }
+    let balance = BALANCES.may_load(deps.storage, to_addr.clone())?.unwrap_or_default();
+    BALANCES.save(deps.storage, to_addr.clone(), &(balance + amount), env.block.height)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "mint")
+        .add_attribute("to", to_addr.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+fn execute_transfer(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    to: String,
+    amount: Uint128,
+) -> StdResult<Response<CoreumMsg>> {
+    let sender_addr = info.sender.clone();
+    let to_addr = deps.api.addr_validate(&to)?;
+
+    if is_native(deps.storage)? {
+        // the coins are attached to the message and forwarded
+        let denom = TOKEN_INFO.load(deps.storage)?.denom;
+        must_send(&info, &denom, amount)?;
+        return Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: to_addr.to_string(),
+                amount: vec![coin(amount.u128(), denom)],
+            })
+            .add_attribute("method", "transfer")
+            .add_attribute("from", sender_addr.to_string())
+            .add_attribute("to", to_addr.to_string())
+            .add_attribute("amount", amount.to_string()));
+    }
+
+    let sender_balance = BALANCES.load(deps.storage, sender_addr.clone())?;
+    if sender_balance < amount {
+        return Err(StdError::generic_err("Insufficient balance"));
+    }
+
+    BALANCES.save(deps.storage, sender_addr.clone(), &(sender_balance - amount), env.block.height)?;
+
+    let recipient_balance = BALANCES.may_load(deps.storage, to_addr.clone())?.unwrap_or_default();
+    BALANCES.save(deps.storage, to_addr.clone(), &(recipient_balance + amount), env.block.height)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "transfer")
+        .add_attribute("from", sender_addr.to_string())
+        .add_attribute("to", to_addr.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+fn execute_burn(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> StdResult<Response<CoreumMsg>> {
+    let mut res = Response::new();
+    if is_native(deps.storage)? {
+        // the coins are attached to the message and burnt by the contract as issuer
+        let denom = TOKEN_INFO.load(deps.storage)?.denom;
+        must_send(&info, &denom, amount)?;
+        res = res.add_message(CoreumMsg::AssetFT(assetft::Msg::Burn {
+            coin: coin(amount.u128(), denom),
+        }));
+    } else {
+        deduct_balance(deps.storage, &info.sender, amount, env.block.height)?;
+    }
+    reduce_supply(deps.storage, amount)?;
+
+    Ok(res
+        .add_attribute("method", "burn")
+        .add_attribute("from", info.sender.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+fn execute_increase_allowance(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    spender: String,
+    amount: Uint128,
+) -> StdResult<Response<CoreumMsg>> {
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    if spender_addr == info.sender {
+        return Err(StdError::generic_err("Cannot set allowance to own account"));
+    }
+
+    let key = (info.sender.clone(), spender_addr.clone());
+    let allowance = ALLOWANCES.may_load(deps.storage, key.clone())?.unwrap_or_default();
+    ALLOWANCES.save(deps.storage, key, &allowance.checked_add(amount)?)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "increase_allowance")
+        .add_attribute("owner", info.sender.to_string())
+        .add_attribute("spender", spender_addr.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+fn execute_decrease_allowance(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    spender: String,
+    amount: Uint128,
+) -> StdResult<Response<CoreumMsg>> {
+    let spender_addr = deps.api.addr_validate(&spender)?;
+
+    // lowering an allowance below zero simply removes it
+    let key = (info.sender.clone(), spender_addr.clone());
+    let allowance = ALLOWANCES.may_load(deps.storage, key.clone())?.unwrap_or_default();
+    let remaining = allowance.saturating_sub(amount);
+    if remaining.is_zero() {
+        ALLOWANCES.remove(deps.storage, key);
+    } else {
+        ALLOWANCES.save(deps.storage, key, &remaining)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "decrease_allowance")
+        .add_attribute("owner", info.sender.to_string())
+        .add_attribute("spender", spender_addr.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+fn execute_transfer_from(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    to: String,
+    amount: Uint128,
+) -> StdResult<Response<CoreumMsg>> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let to_addr = deps.api.addr_validate(&to)?;
+
+    assert_ledger(deps.storage)?;
+    deduct_allowance(deps.storage, &owner_addr, &info.sender, amount)?;
+    deduct_balance(deps.storage, &owner_addr, amount, env.block.height)?;
+    let recipient_balance = BALANCES.may_load(deps.storage, to_addr.clone())?.unwrap_or_default();
+    BALANCES.save(
+        deps.storage,
+        to_addr.clone(),
+        &recipient_balance.checked_add(amount)?,
+        env.block.height,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("method", "transfer_from")
+        .add_attribute("from", owner_addr.to_string())
+        .add_attribute("to", to_addr.to_string())
+        .add_attribute("by", info.sender.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+fn execute_burn_from(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    amount: Uint128,
+) -> StdResult<Response<CoreumMsg>> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+
+    assert_ledger(deps.storage)?;
+    deduct_allowance(deps.storage, &owner_addr, &info.sender, amount)?;
+    deduct_balance(deps.storage, &owner_addr, amount, env.block.height)?;
+    reduce_supply(deps.storage, amount)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "burn_from")
+        .add_attribute("from", owner_addr.to_string())
+        .add_attribute("by", info.sender.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+// native balances sit in the holders' own accounts, out of reach of allowances
+fn assert_ledger(storage: &dyn Storage) -> StdResult<()> {
+    if is_native(storage)? {
+        return Err(StdError::generic_err("Allowances can't move native balances"));
+    }
+    Ok(())
+}
+
+fn must_send(info: &MessageInfo, denom: &str, amount: Uint128) -> StdResult<()> {
+    let sent: Uint128 = info.funds.iter().filter(|c| c.denom == denom).map(|c| c.amount).sum();
+    if sent != amount {
+        return Err(StdError::generic_err(format!("Must send exactly {} {}", amount, denom)));
+    }
+    Ok(())
+}
+
+fn deduct_balance(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    amount: Uint128,
+    height: u64,
+) -> StdResult<()> {
+    let balance = BALANCES.may_load(storage, owner.clone())?.unwrap_or_default();
+    if balance < amount {
+        return Err(StdError::generic_err("Insufficient balance"));
+    }
+    BALANCES.save(storage, owner.clone(), &(balance - amount), height)
+}
+
+fn reduce_supply(storage: &mut dyn Storage, amount: Uint128) -> StdResult<()> {
+    TOKEN_INFO.update(storage, |mut token_info| -> StdResult<_> {
+        token_info.total_supply = token_info.total_supply.checked_sub(amount)?;
+        Ok(token_info)
+    })?;
+    Ok(())
+}
+
+fn deduct_allowance(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    spender: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    let key = (owner.clone(), spender.clone());
+    let allowance = ALLOWANCES.may_load(storage, key.clone())?.unwrap_or_default();
+    if allowance < amount {
+        return Err(StdError::generic_err("Insufficient allowance"));
+    }
+    ALLOWANCES.save(storage, key, &(allowance - amount))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps<CoreumQueries>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Balance { address } => to_binary(&query_balance(deps, address)?),
+        QueryMsg::Allowance { owner, spender } => {
+            to_binary(&query_allowance(deps, owner, spender)?)
+        }
+        QueryMsg::BalanceAt { address, height } => {
+            to_binary(&query_balance_at(deps, address, height)?)
+        }
+        QueryMsg::TokenInfo {} => to_binary(&query_token_info(deps)?),
+    }
+}
+
+fn query_balance(deps: Deps<CoreumQueries>, address: String) -> StdResult<Uint128> {
+    let addr = deps.api.addr_validate(&address)?;
+    if is_native(deps.storage)? {
+        let denom = TOKEN_INFO.load(deps.storage)?.denom;
+        return Ok(deps.querier.query_balance(addr, denom)?.amount);
+    }
+    let balance = BALANCES.may_load(deps.storage, addr)?.unwrap_or_default();
+    Ok(balance)
+}
+
+fn query_balance_at(deps: Deps<CoreumQueries>, address: String, height: u64) -> StdResult<Uint128> {
+    let addr = deps.api.addr_validate(&address)?;
+    // the bank module keeps no history we could read back
+    if is_native(deps.storage)? {
+        return Err(StdError::generic_err("Native balances are not snapshotted"));
+    }
+    balance_at(deps.storage, &addr, height)
+}
+
+/// Ledger balance of `holder` just before block `height`, for contracts embedding the token
+pub fn balance_at(storage: &dyn Storage, holder: &Addr, height: u64) -> StdResult<Uint128> {
+    Ok(BALANCES.may_load_at_height(storage, holder.clone(), height)?.unwrap_or_default())
+}
+
+fn query_token_info(deps: Deps<CoreumQueries>) -> StdResult<TokenInfoResponse> {
+    let token_info = TOKEN_INFO.load(deps.storage)?;
+    Ok(TokenInfoResponse {
+        denom: token_info.denom,
+        owner: token_info.owner.to_string(),
+        total_supply: token_info.total_supply,
+        precision: token_info.precision,
+    })
+}
+
+fn query_allowance(deps: Deps<CoreumQueries>, owner: String, spender: String) -> StdResult<Uint128> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    let allowance = ALLOWANCES
+        .may_load(deps.storage, (owner_addr, spender_addr))?
+        .unwrap_or_default();
+    Ok(allowance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coreum_test_utils::{mock_coreum_deps, mock_coreum_deps_with_querier, CoreumDeps};
+    use cosmwasm_std::testing::{mock_env, mock_info, MockQuerier};
+    use cosmwasm_std::{coins, from_binary, CosmosMsg};
+
+    fn denom() -> String {
+        format!("utkn-{}", mock_env().contract.address)
+    }
+
+    fn setup(deps: &mut CoreumDeps, native: bool) {
+        let msg = InstantiateMsg {
+            owner: "owner".to_string(),
+            symbol: "TKN".to_string(),
+            subunit: "utkn".to_string(),
+            precision: 6,
+            initial_amount: Uint128::new(1000),
+            native,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    fn balance(deps: &CoreumDeps, address: &str) -> Uint128 {
+        let msg = QueryMsg::Balance {
+            address: address.to_string(),
+        };
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+    }
+
+    fn total_supply(deps: &CoreumDeps) -> Uint128 {
+        let res: TokenInfoResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap())
+                .unwrap();
+        res.total_supply
+    }
+
+    #[test]
+    fn burn_reduces_balance_and_supply() {
+        let mut deps = mock_coreum_deps();
+        setup(&mut deps, false);
+
+        let msg = ExecuteMsg::Burn {
+            amount: Uint128::new(1001),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+
+        let msg = ExecuteMsg::Burn {
+            amount: Uint128::new(300),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(balance(&deps, "owner"), Uint128::new(700));
+        assert_eq!(total_supply(&deps), Uint128::new(700));
+    }
+
+    #[test]
+    fn allowances_cap_transfer_and_burn_from() {
+        let mut deps = mock_coreum_deps();
+        setup(&mut deps, false);
+
+        let allowance = |deps: &CoreumDeps| -> Uint128 {
+            let msg = QueryMsg::Allowance {
+                owner: "owner".to_string(),
+                spender: "spender".to_string(),
+            };
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+        };
+
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: "owner".to_string(),
+            amount: Uint128::new(1),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: "spender".to_string(),
+            amount: Uint128::new(500),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        let msg = ExecuteMsg::DecreaseAllowance {
+            spender: "spender".to_string(),
+            amount: Uint128::new(100),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        assert_eq!(allowance(&deps), Uint128::new(400));
+
+        let transfer = |amount| ExecuteMsg::TransferFrom {
+            owner: "owner".to_string(),
+            to: "alice".to_string(),
+            amount: Uint128::new(amount),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("spender", &[]),
+            transfer(401),
+        )
+        .unwrap_err();
+        // only the approved spender may move the owner's tokens
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            transfer(1),
+        )
+        .unwrap_err();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("spender", &[]),
+            transfer(250),
+        )
+        .unwrap();
+        assert_eq!(balance(&deps, "alice"), Uint128::new(250));
+        assert_eq!(allowance(&deps), Uint128::new(150));
+
+        let msg = ExecuteMsg::BurnFrom {
+            owner: "owner".to_string(),
+            amount: Uint128::new(150),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("spender", &[]), msg).unwrap();
+        assert_eq!(balance(&deps, "owner"), Uint128::new(600));
+        assert_eq!(total_supply(&deps), Uint128::new(850));
+        assert_eq!(allowance(&deps), Uint128::zero());
+
+        // decreasing past zero removes the allowance
+        let msg = ExecuteMsg::DecreaseAllowance {
+            spender: "spender".to_string(),
+            amount: Uint128::new(10),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        assert!(!ALLOWANCES.has(
+            &deps.storage,
+            (Addr::unchecked("owner"), Addr::unchecked("spender"))
+        ));
+    }
+
+    #[test]
+    fn native_mode_moves_bank_coins() {
+        let mut deps =
+            mock_coreum_deps_with_querier(MockQuerier::new(&[("alice", &coins(40, denom()))]));
+        let msg = InstantiateMsg {
+            owner: "owner".to_string(),
+            symbol: "TKN".to_string(),
+            subunit: "utkn".to_string(),
+            precision: 6,
+            initial_amount: Uint128::new(1000),
+            native: true,
+        };
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "owner".to_string(),
+                amount: coins(1000, denom())
+            })
+        );
+        assert!(BALANCES
+            .may_load(&deps.storage, Addr::unchecked("owner"))
+            .unwrap()
+            .is_none());
+
+        // balances are read from the bank module
+        assert_eq!(balance(&deps, "alice"), Uint128::new(40));
+        let msg = QueryMsg::BalanceAt {
+            address: "alice".to_string(),
+            height: 1,
+        };
+        query(deps.as_ref(), mock_env(), msg).unwrap_err();
+
+        let msg = ExecuteMsg::Mint {
+            to: "alice".to_string(),
+            amount: Uint128::new(10),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Mint {
+                coin: coin(10, denom())
+            }))
+        );
+
+        // transfers and burns need the coins attached
+        let msg = ExecuteMsg::Transfer {
+            to: "bob".to_string(),
+            amount: Uint128::new(20),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &coins(19, denom())),
+            msg.clone(),
+        )
+        .unwrap_err();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &coins(20, denom())),
+            msg,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bob".to_string(),
+                amount: coins(20, denom())
+            })
+        );
+
+        let msg = ExecuteMsg::Burn {
+            amount: Uint128::new(5),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &coins(5, denom())),
+            msg,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Burn {
+                coin: coin(5, denom())
+            }))
+        );
+        assert_eq!(total_supply(&deps), Uint128::new(1005));
+
+        // allowances can't reach coins held in the bank
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: "spender".to_string(),
+            amount: Uint128::new(10),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        let msg = ExecuteMsg::TransferFrom {
+            owner: "alice".to_string(),
+            to: "bob".to_string(),
+            amount: Uint128::new(10),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("spender", &[]), msg).unwrap_err();
+    }
+}
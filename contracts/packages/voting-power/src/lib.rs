@@ -0,0 +1,124 @@
+//! Stable voting power interface of the staking contract.
+//!
+//! Governance contracts (voting, nonGatedDao) weight ballots by staked derivative through
+//! [`VotingPowerQuerier`]: they record the height a proposal opens at and read every voter's
+//! power, and the total for quorum, at that height. Power is the derivative an address locked
+//! in the staking contract. Locked tokens can't be transferred, and unlocking removes their
+//! power from the next block on, so the same tokens never count for two addresses at one height.
+
+use cosmwasm_std::{
+    to_binary, Addr, CustomQuery, QuerierWrapper, QueryRequest, StdResult, Uint128, WasmQuery,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Subset of the staking `QueryMsg` that governance contracts may rely on.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VotingPowerQueryMsg {
+    /// Returns the `VotingPowerResponse` of an address at the start of `height`,
+    /// or at the current block when no height is given.
+    VotingPowerAt {
+        address: String,
+        height: Option<u64>,
+    },
+    /// Returns the `TotalPowerResponse` at the start of `height`,
+    /// or at the current block when no height is given.
+    TotalPowerAt { height: Option<u64> },
+}
+
+/// The `VotingPowerResponse` struct is returned by the `VotingPowerAt` query.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VotingPowerResponse {
+    /// derivative tokens the address locked
+    pub power: Uint128,
+    /// the height the power was read at
+    pub height: u64,
+}
+
+/// The `TotalPowerResponse` struct is returned by the `TotalPowerAt` query.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalPowerResponse {
+    /// sum of the voting power of all addresses
+    pub power: Uint128,
+    /// the height the power was read at
+    pub height: u64,
+}
+
+/// Typed access to the voting power of a deployed staking contract.
+pub struct VotingPowerQuerier<'a, C: CustomQuery> {
+    querier: &'a QuerierWrapper<'a, C>,
+    contract: Addr,
+}
+
+impl<'a, C: CustomQuery> VotingPowerQuerier<'a, C> {
+    pub fn new(querier: &'a QuerierWrapper<'a, C>, contract: Addr) -> Self {
+        VotingPowerQuerier { querier, contract }
+    }
+
+    fn query<T: serde::de::DeserializeOwned>(&self, msg: &VotingPowerQueryMsg) -> StdResult<T> {
+        self.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.contract.to_string(),
+            msg: to_binary(msg)?,
+        }))
+    }
+
+    /// Queries the voting power of `address` at the start of `height`.
+    pub fn voting_power_at(&self, address: impl Into<String>, height: u64) -> StdResult<Uint128> {
+        let msg = VotingPowerQueryMsg::VotingPowerAt {
+            address: address.into(),
+            height: Some(height),
+        };
+        Ok(self.query::<VotingPowerResponse>(&msg)?.power)
+    }
+
+    /// Queries the total voting power at the start of `height`.
+    pub fn total_power_at(&self, height: u64) -> StdResult<Uint128> {
+        let msg = VotingPowerQueryMsg::TotalPowerAt {
+            height: Some(height),
+        };
+        Ok(self.query::<TotalPowerResponse>(&msg)?.power)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockQuerier;
+    use cosmwasm_std::{from_binary, ContractResult, Empty, SystemResult};
+
+    #[test]
+    fn queries_voting_power_at_height() {
+        let mut querier = MockQuerier::<Empty>::new(&[]);
+        querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "staking" => {
+                let res = match from_binary(msg).unwrap() {
+                    VotingPowerQueryMsg::VotingPowerAt { address, height } => {
+                        let power = if address == "alice" { 300u128 } else { 0 };
+                        to_binary(&VotingPowerResponse {
+                            power: power.into(),
+                            height: height.unwrap(),
+                        })
+                    }
+                    VotingPowerQueryMsg::TotalPowerAt { height } => {
+                        to_binary(&TotalPowerResponse {
+                            power: Uint128::new(1000),
+                            height: height.unwrap(),
+                        })
+                    }
+                };
+                SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+        let staking = VotingPowerQuerier::new(&wrapper, Addr::unchecked("staking"));
+
+        assert_eq!(
+            staking.voting_power_at("alice", 12).unwrap(),
+            Uint128::new(300)
+        );
+        assert_eq!(staking.voting_power_at("bob", 12).unwrap(), Uint128::zero());
+        assert_eq!(staking.total_power_at(12).unwrap(), Uint128::new(1000));
+    }
+}
@@ -1,13 +1,18 @@
+use coreum_wasm_sdk::core::CoreumQueries;
+use coreum_wasm_sdk::nft;
 use cosmwasm_std::{
     attr, coin, to_binary, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order,
-    Response, StdResult,
+    QueryRequest, Response, StdResult,
 };
 
 use crate::error::ContractError;
 use crate::helper::extract_budget_coin;
 use crate::matching::{calculate_clr, QuadraticFundingAlgorithm, RawGrant};
-use crate::msg::{AllProposalsResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, Proposal, Vote, CONFIG, PROPOSALS, PROPOSAL_SEQ, VOTES};
+use crate::msg::{AllProposalsResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::state::{
+    Config, Proposal, ProposalLimits, Vote, CONFIG, CREDENTIAL_VOTES, PROPOSALS, PROPOSAL_SEQ,
+    PROPOSAL_TITLES, VOTES,
+};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
@@ -15,7 +20,7 @@ use cosmwasm_std::entry_point;
 // make use of the custom errors
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
@@ -51,6 +56,8 @@ pub fn instantiate(
         proposal_period: msg.proposal_period,
         algorithm: msg.algorithm,
         budget,
+        proposal_limits: msg.proposal_limits.unwrap_or_default(),
+        credential_class: msg.credential_class,
     };
     CONFIG.save(deps.storage, &cfg)?;
     PROPOSAL_SEQ.save(deps.storage, &0)?;
@@ -61,7 +68,7 @@ pub fn instantiate(
 // And declare a custom Error variant for the ones where you will want to make use of it
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
@@ -73,15 +80,16 @@ pub fn execute(
             metadata,
             fund_address,
         } => execute_create_proposal(deps, env, info, title, description, metadata, fund_address),
-        ExecuteMsg::VoteProposal { proposal_id } => {
-            execute_vote_proposal(deps, env, info, proposal_id)
-        }
+        ExecuteMsg::VoteProposal {
+            proposal_id,
+            credential_id,
+        } => execute_vote_proposal(deps, env, info, proposal_id, credential_id),
         ExecuteMsg::TriggerDistribution { .. } => execute_trigger_distribution(deps, env, info),
     }
 }
 
 pub fn execute_create_proposal(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     env: Env,
     info: MessageInfo,
     title: String,
@@ -106,8 +114,16 @@ pub fn execute_create_proposal(
     // validate fund address
     deps.api.addr_validate(fund_address.as_str())?;
 
+    // validate content so spam cannot bloat state
+    validate_proposal(&config.proposal_limits, &title, &description, &metadata)?;
+    let normalized_title = title.trim().to_lowercase();
+    if PROPOSAL_TITLES.has(deps.storage, &normalized_title) {
+        return Err(ContractError::DuplicateProposalTitle { title });
+    }
+
     let id = PROPOSAL_SEQ.load(deps.storage)? + 1;
     PROPOSAL_SEQ.save(deps.storage, &id)?;
+    PROPOSAL_TITLES.save(deps.storage, &normalized_title, &id)?;
     let p = Proposal {
         id,
         title: title.clone(),
@@ -125,11 +141,42 @@ pub fn execute_create_proposal(
     ]))
 }
 
+fn validate_proposal(
+    limits: &ProposalLimits,
+    title: &str,
+    description: &str,
+    metadata: &Option<Binary>,
+) -> Result<(), ContractError> {
+    let title_length = title.trim().chars().count() as u32;
+    if title_length < limits.min_title_length || title_length > limits.max_title_length {
+        return Err(ContractError::InvalidTitleLength {
+            min: limits.min_title_length,
+            max: limits.max_title_length,
+        });
+    }
+    let description_length = description.chars().count() as u32;
+    if description_length < limits.min_description_length
+        || description_length > limits.max_description_length
+    {
+        return Err(ContractError::InvalidDescriptionLength {
+            min: limits.min_description_length,
+            max: limits.max_description_length,
+        });
+    }
+    if let (Some(max), Some(metadata)) = (limits.max_metadata_size, metadata) {
+        if metadata.len() > max as usize {
+            return Err(ContractError::MetadataTooLarge { max });
+        }
+    }
+    Ok(())
+}
+
 pub fn execute_vote_proposal(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     env: Env,
     info: MessageInfo,
     proposal_id: u64,
+    credential_id: Option<String>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
@@ -169,6 +216,26 @@ pub fn execute_vote_proposal(
         return Err(ContractError::AddressAlreadyVotedProject {});
     }
 
+    // in credential mode a credential votes once per proposal, whichever address holds it
+    if let Some(class_id) = config.credential_class {
+        let id = credential_id.ok_or(ContractError::CredentialRequired {
+            class_id: class_id.clone(),
+        })?;
+        let request: QueryRequest<CoreumQueries> = CoreumQueries::NFT(nft::Query::Owner {
+            class_id,
+            id: id.clone(),
+        })
+        .into();
+        let res: nft::OwnerResponse = deps.querier.query(&request)?;
+        if res.owner != info.sender {
+            return Err(ContractError::NotCredentialOwner { id });
+        }
+        if CREDENTIAL_VOTES.has(deps.storage, (proposal_id, &id)) {
+            return Err(ContractError::CredentialAlreadyVoted { id });
+        }
+        CREDENTIAL_VOTES.save(deps.storage, (proposal_id, &id), &vote.voter)?;
+    }
+
     // save vote
     vote_key.save(deps.storage, &vote)?;
 
@@ -181,7 +248,7 @@ pub fn execute_vote_proposal(
 }
 
 pub fn execute_trigger_distribution(
-    deps: DepsMut,
+    deps: DepsMut<CoreumQueries>,
     env: Env,
     info: MessageInfo,
 ) -> Result<Response, ContractError> {
@@ -251,18 +318,35 @@ pub fn execute_trigger_distribution(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps<CoreumQueries>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::ProposalByID { id } => to_binary(&query_proposal_id(deps, id)?),
         QueryMsg::AllProposals {} => to_binary(&query_all_proposals(deps)?),
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
     }
 }
 
-fn query_proposal_id(deps: Deps, id: u64) -> StdResult<Proposal> {
+fn query_config(deps: Deps<CoreumQueries>) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        admin: config.admin,
+        leftover_addr: config.leftover_addr,
+        create_proposal_whitelist: config.create_proposal_whitelist,
+        vote_proposal_whitelist: config.vote_proposal_whitelist,
+        voting_period: config.voting_period,
+        proposal_period: config.proposal_period,
+        budget: config.budget,
+        algorithm: config.algorithm,
+        proposal_limits: config.proposal_limits,
+        credential_class: config.credential_class,
+    })
+}
+
+fn query_proposal_id(deps: Deps<CoreumQueries>, id: u64) -> StdResult<Proposal> {
     PROPOSALS.load(deps.storage, id)
 }
 
-fn query_all_proposals(deps: Deps) -> StdResult<AllProposalsResponse> {
+fn query_all_proposals(deps: Deps<CoreumQueries>) -> StdResult<AllProposalsResponse> {
     let all: StdResult<Vec<_>> = PROPOSALS
         .range(deps.storage, None, None, Order::Ascending)
         .collect();
@@ -275,20 +359,27 @@ fn query_all_proposals(deps: Deps) -> StdResult<AllProposalsResponse> {
 
 #[cfg(test)]
 mod tests {
-    use crate::contract::{execute, instantiate, query_all_proposals, query_proposal_id};
+    use crate::contract::{
+        execute, instantiate, query_all_proposals, query_config, query_proposal_id,
+    };
     use crate::error::ContractError;
     use crate::matching::QuadraticFundingAlgorithm;
     use crate::msg::{AllProposalsResponse, ExecuteMsg, InstantiateMsg};
-    use crate::state::{Proposal, PROPOSALS};
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coin, BankMsg, Binary, CosmosMsg, SubMsg};
+    use crate::state::{Proposal, ProposalLimits, PROPOSALS};
+    use coreum_wasm_sdk::core::CoreumQueries;
+    use coreum_wasm_sdk::nft;
+    use coreum_test_utils::{mock_coreum_deps, mock_coreum_deps_with_querier};
+    use cosmwasm_std::testing::{mock_env, mock_info, MockQuerier};
+    use cosmwasm_std::{
+        coin, to_binary, BankMsg, Binary, ContractResult, CosmosMsg, Storage, SubMsg, SystemResult,
+    };
     use cw0::Expiration;
 
     #[test]
     fn create_proposal() {
         let mut env = mock_env();
         let info = mock_info("addr", &[coin(1000, "ucosm")]);
-        let mut deps = mock_dependencies();
+        let mut deps = mock_coreum_deps();
 
         let init_msg = InstantiateMsg {
             admin: "addr".to_string(),
@@ -298,6 +389,8 @@ mod tests {
             voting_period: Expiration::AtHeight(env.block.height + 15),
             proposal_period: Expiration::AtHeight(env.block.height + 10),
             budget_denom: String::from("ucosm"),
+            proposal_limits: None,
+            credential_class: None,
             algorithm: QuadraticFundingAlgorithm::CapitalConstrainedLiberalRadicalism {
                 parameter: "".to_string(),
             },
@@ -325,7 +418,7 @@ mod tests {
         // unauthorised
         let env = mock_env();
         let info = mock_info("true", &[coin(1000, "ucosm")]);
-        let mut deps = mock_dependencies();
+        let mut deps = mock_coreum_deps();
         let init_msg = InstantiateMsg {
             leftover_addr: "addr".to_string(),
             admin: "person".to_string(),
@@ -334,6 +427,8 @@ mod tests {
             voting_period: Default::default(),
             proposal_period: Default::default(),
             budget_denom: String::from("ucosm"),
+            proposal_limits: None,
+            credential_class: None,
             algorithm: QuadraticFundingAlgorithm::CapitalConstrainedLiberalRadicalism {
                 parameter: "".to_string(),
             },
@@ -352,7 +447,7 @@ mod tests {
     fn vote_proposal() {
         let mut env = mock_env();
         let info = mock_info("addr", &[coin(1000, "ucosm")]);
-        let mut deps = mock_dependencies();
+        let mut deps = mock_coreum_deps();
 
         let mut init_msg = InstantiateMsg {
             leftover_addr: "addr".to_string(),
@@ -365,6 +460,8 @@ mod tests {
             voting_period: Expiration::AtHeight(env.block.height + 15),
             proposal_period: Expiration::AtHeight(env.block.height + 10),
             budget_denom: String::from("ucosm"),
+            proposal_limits: None,
+            credential_class: None,
         };
         instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg.clone()).unwrap();
 
@@ -383,7 +480,10 @@ mod tests {
         )
         .unwrap();
 
-        let msg = ExecuteMsg::VoteProposal { proposal_id: 1 };
+        let msg = ExecuteMsg::VoteProposal {
+            proposal_id: 1,
+            credential_id: None,
+        };
         let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
 
         // double vote prevention
@@ -395,7 +495,7 @@ mod tests {
         }
 
         // whitelist check
-        let mut deps = mock_dependencies();
+        let mut deps = mock_coreum_deps();
         init_msg.vote_proposal_whitelist = Some(vec!["admin".to_string()]);
         instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg.clone()).unwrap();
         let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
@@ -406,7 +506,7 @@ mod tests {
         }
 
         // proposal period expired
-        let mut deps = mock_dependencies();
+        let mut deps = mock_coreum_deps();
         init_msg.vote_proposal_whitelist = None;
         instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
         env.block.height += 15;
@@ -424,7 +524,7 @@ mod tests {
         let env = mock_env();
         let budget = 550000u128;
         let info = mock_info("admin", &[coin(budget, "ucosm")]);
-        let mut deps = mock_dependencies();
+        let mut deps = mock_coreum_deps();
 
         let init_msg = InstantiateMsg {
             leftover_addr: "addr".to_string(),
@@ -437,6 +537,8 @@ mod tests {
             voting_period: Expiration::AtHeight(env.block.height + 15),
             proposal_period: Expiration::AtHeight(env.block.height + 10),
             budget_denom: String::from("ucosm"),
+            proposal_limits: None,
+            credential_class: None,
         };
 
         instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
@@ -475,7 +577,10 @@ mod tests {
 
         // insert votes
         // proposal1
-        let msg = ExecuteMsg::VoteProposal { proposal_id: 1 };
+        let msg = ExecuteMsg::VoteProposal {
+            proposal_id: 1,
+            credential_id: None,
+        };
         let vote11_fund = 1200u128;
         let info = mock_info("address1", &[coin(vote11_fund, "ucosm")]);
         let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
@@ -493,7 +598,10 @@ mod tests {
         let proposal1 = vote11_fund + vote12_fund + vote13_fund;
 
         // proposal2
-        let msg = ExecuteMsg::VoteProposal { proposal_id: 2 };
+        let msg = ExecuteMsg::VoteProposal {
+            proposal_id: 2,
+            credential_id: None,
+        };
 
         let vote21_fund = 30000u128;
         let info = mock_info("address4", &[coin(vote21_fund, "ucosm")]);
@@ -508,7 +616,10 @@ mod tests {
         let proposal2 = vote21_fund + vote22_fund;
 
         // proposal3
-        let msg = ExecuteMsg::VoteProposal { proposal_id: 3 };
+        let msg = ExecuteMsg::VoteProposal {
+            proposal_id: 3,
+            credential_id: None,
+        };
         let vote31_fund = 230000u128;
         let info = mock_info("address6", &[coin(vote31_fund, "ucosm")]);
         let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
@@ -522,7 +633,10 @@ mod tests {
         let proposal3 = vote31_fund + vote32_fund;
 
         // proposal4
-        let msg = ExecuteMsg::VoteProposal { proposal_id: 4 };
+        let msg = ExecuteMsg::VoteProposal {
+            proposal_id: 4,
+            credential_id: None,
+        };
         let vote41_fund = 100000u128;
         let info = mock_info("address8", &[coin(vote41_fund, "ucosm")]);
         let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
@@ -590,7 +704,7 @@ mod tests {
 
     #[test]
     fn query_proposal() {
-        let mut deps = mock_dependencies();
+        let mut deps = mock_coreum_deps();
 
         let proposal = Proposal {
             id: 1,
@@ -611,7 +725,7 @@ mod tests {
 
     #[test]
     fn query_all_proposal() {
-        let mut deps = mock_dependencies();
+        let mut deps = mock_coreum_deps();
 
         let proposal = Proposal {
             id: 1,
@@ -641,4 +755,181 @@ mod tests {
             res
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn create_proposal_limits() {
+        let env = mock_env();
+        let info = mock_info("addr", &[coin(1000, "ucosm")]);
+        let mut deps = mock_coreum_deps();
+        let limits = ProposalLimits {
+            min_title_length: 3,
+            max_title_length: 10,
+            min_description_length: 0,
+            max_description_length: 20,
+            max_metadata_size: Some(4),
+        };
+
+        let mut init_msg = InstantiateMsg {
+            admin: "addr".to_string(),
+            leftover_addr: "addr".to_string(),
+            create_proposal_whitelist: None,
+            vote_proposal_whitelist: None,
+            voting_period: Expiration::AtHeight(env.block.height + 15),
+            proposal_period: Expiration::AtHeight(env.block.height + 10),
+            budget_denom: String::from("ucosm"),
+            algorithm: QuadraticFundingAlgorithm::CapitalConstrainedLiberalRadicalism {
+                parameter: "".to_string(),
+            },
+            proposal_limits: Some(ProposalLimits {
+                min_title_length: 11,
+                ..limits.clone()
+            }),
+            credential_class: None,
+        };
+        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg.clone());
+        match res {
+            Ok(_) => panic!("expected error"),
+            Err(ContractError::InvalidProposalLimits { .. }) => {}
+            e => panic!("unexpected error, got {:?}", e),
+        }
+        init_msg.proposal_limits = Some(limits.clone());
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+        assert_eq!(query_config(deps.as_ref()).unwrap().proposal_limits, limits);
+
+        let proposal =
+            |title: &str, description: &str, metadata: &[u8]| ExecuteMsg::CreateProposal {
+                title: title.to_string(),
+                description: description.to_string(),
+                metadata: Some(Binary::from(metadata)),
+                fund_address: "fund_address".to_string(),
+            };
+
+        let msg = proposal("ab", "test", b"test");
+        match execute(deps.as_mut(), env.clone(), info.clone(), msg) {
+            Err(ContractError::InvalidTitleLength { min: 3, max: 10 }) => {}
+            e => panic!("unexpected result, got {:?}", e),
+        }
+        let msg = proposal("title", &"x".repeat(21), b"test");
+        match execute(deps.as_mut(), env.clone(), info.clone(), msg) {
+            Err(ContractError::InvalidDescriptionLength { min: 0, max: 20 }) => {}
+            e => panic!("unexpected result, got {:?}", e),
+        }
+        let msg = proposal("title", "test", b"large");
+        match execute(deps.as_mut(), env.clone(), info.clone(), msg) {
+            Err(ContractError::MetadataTooLarge { max: 4 }) => {}
+            e => panic!("unexpected result, got {:?}", e),
+        }
+
+        let msg = proposal("title", "", b"test");
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = proposal(" Title ", "test", b"");
+        match execute(deps.as_mut(), env, info, msg) {
+            Err(ContractError::DuplicateProposalTitle { .. }) => {}
+            e => panic!("unexpected result, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn vote_proposal_with_credential() {
+        let env = mock_env();
+        let info = mock_info("addr", &[coin(1000, "ucosm")]);
+        // credentials are owned by the address named in their id
+        let querier = MockQuerier::<CoreumQueries>::new(&[]).with_custom_handler(|query| {
+            let owner = match query {
+                CoreumQueries::NFT(nft::Query::Owner { id, .. }) => {
+                    id.trim_start_matches("badge-").to_string()
+                }
+                _ => String::new(),
+            };
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&nft::OwnerResponse { owner }).unwrap(),
+            ))
+        });
+        let mut deps = mock_coreum_deps_with_querier(querier);
+
+        let init_msg = InstantiateMsg {
+            admin: "addr".to_string(),
+            leftover_addr: "addr".to_string(),
+            create_proposal_whitelist: None,
+            vote_proposal_whitelist: None,
+            voting_period: Expiration::AtHeight(env.block.height + 15),
+            proposal_period: Expiration::AtHeight(env.block.height + 10),
+            budget_denom: String::from("ucosm"),
+            algorithm: QuadraticFundingAlgorithm::CapitalConstrainedLiberalRadicalism {
+                parameter: "".to_string(),
+            },
+            proposal_limits: None,
+            credential_class: Some("humans".to_string()),
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+        assert_eq!(
+            query_config(deps.as_ref()).unwrap().credential_class,
+            Some("humans".to_string())
+        );
+        let create_proposal_msg = ExecuteMsg::CreateProposal {
+            title: String::from("test"),
+            description: String::from("test"),
+            metadata: None,
+            fund_address: "fund_address".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info, create_proposal_msg).unwrap();
+
+        let vote = |credential_id: Option<&str>| ExecuteMsg::VoteProposal {
+            proposal_id: 1,
+            credential_id: credential_id.map(String::from),
+        };
+        let alice = mock_info("alice", &[coin(100, "ucosm")]);
+        match execute(deps.as_mut(), env.clone(), alice.clone(), vote(None)) {
+            Err(ContractError::CredentialRequired { .. }) => {}
+            e => panic!("unexpected result, got {:?}", e),
+        }
+        match execute(
+            deps.as_mut(),
+            env.clone(),
+            alice.clone(),
+            vote(Some("badge-bob")),
+        ) {
+            Err(ContractError::NotCredentialOwner { .. }) => {}
+            e => panic!("unexpected result, got {:?}", e),
+        }
+        execute(deps.as_mut(), env.clone(), alice, vote(Some("badge-alice"))).unwrap();
+
+        // the credential changed hands, the new holder cannot vote with it again
+        let bob = mock_info("bob", &[coin(100, "ucosm")]);
+        execute(deps.as_mut(), env.clone(), bob, vote(Some("badge-bob"))).unwrap();
+        deps.querier = MockQuerier::<CoreumQueries>::new(&[]).with_custom_handler(|_| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&nft::OwnerResponse {
+                    owner: "carol".to_string(),
+                })
+                .unwrap(),
+            ))
+        });
+        let carol = mock_info("carol", &[coin(100, "ucosm")]);
+        match execute(deps.as_mut(), env, carol, vote(Some("badge-alice"))) {
+            Err(ContractError::CredentialAlreadyVoted { .. }) => {}
+            e => panic!("unexpected result, got {:?}", e),
+        }
+        assert_eq!(
+            query_proposal_id(deps.as_ref(), 1)
+                .unwrap()
+                .collected_funds
+                .u128(),
+            200
+        );
+    }
+
+    #[test]
+    fn legacy_config_loads_default_proposal_limits() {
+        let mut deps = mock_coreum_deps();
+        // config as stored before proposal limits and credentials were added
+        deps.storage.set(
+            b"config",
+            br#"{"admin":"addr","leftover_addr":"addr","create_proposal_whitelist":null,"vote_proposal_whitelist":null,"voting_period":{"at_height":15},"proposal_period":{"at_height":10},"budget":{"denom":"ucosm","amount":"1000"},"algorithm":{"capital_constrained_liberal_radicalism":{"parameter":""}}}"#,
+        );
+
+        let config = query_config(deps.as_ref()).unwrap();
+        assert_eq!(config.proposal_limits, ProposalLimits::default());
+        assert_eq!(config.credential_class, None);
+    }
+}
@@ -32,4 +32,28 @@ pub enum ContractError {
 
     #[error("CLR algorithm requires a budget constrain")]
     CLRConstrainRequired {},
+
+    #[error("Invalid proposal limits: {reason}")]
+    InvalidProposalLimits { reason: String },
+
+    #[error("Title must be between {min} and {max} characters")]
+    InvalidTitleLength { min: u32, max: u32 },
+
+    #[error("Description must be between {min} and {max} characters")]
+    InvalidDescriptionLength { min: u32, max: u32 },
+
+    #[error("Metadata exceeds {max} bytes")]
+    MetadataTooLarge { max: u32 },
+
+    #[error("A proposal titled {title} already exists")]
+    DuplicateProposalTitle { title: String },
+
+    #[error("Voting requires a credential from class {class_id}")]
+    CredentialRequired { class_id: String },
+
+    #[error("Sender does not own credential {id}")]
+    NotCredentialOwner { id: String },
+
+    #[error("Credential {id} already voted on this proposal")]
+    CredentialAlreadyVoted { id: String },
 }
\ No newline at end of file
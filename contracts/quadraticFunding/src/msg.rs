@@ -1,8 +1,8 @@
 use crate::error::ContractError;
 use crate::matching::QuadraticFundingAlgorithm;
-use crate::state::Proposal;
+use crate::state::{Proposal, ProposalLimits};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Binary, Env};
+use cosmwasm_std::{Binary, Coin, Env};
 use cw0::Expiration;
 
 #[cw_serde]
@@ -15,6 +15,8 @@ pub struct InstantiateMsg {
     pub proposal_period: Expiration,
     pub budget_denom: String,
     pub algorithm: QuadraticFundingAlgorithm,
+    pub proposal_limits: Option<ProposalLimits>,
+    pub credential_class: Option<String>,
 }
 
 impl InstantiateMsg {
@@ -27,11 +29,30 @@ impl InstantiateMsg {
         if self.voting_period.is_expired(&env.block) {
             return Err(ContractError::VotingPeriodExpired {});
         }
+        if let Some(limits) = &self.proposal_limits {
+            limits.validate()?;
+        }
 
         Ok(())
     }
 }
 
+impl ProposalLimits {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.min_title_length == 0 || self.min_title_length > self.max_title_length {
+            return Err(ContractError::InvalidProposalLimits {
+                reason: "title length range".to_string(),
+            });
+        }
+        if self.min_description_length > self.max_description_length {
+            return Err(ContractError::InvalidProposalLimits {
+                reason: "description length range".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     CreateProposal {
@@ -42,6 +63,8 @@ pub enum ExecuteMsg {
     },
     VoteProposal {
         proposal_id: u64,
+        // id of an NFT from the credential class owned by the voter, required in credential mode
+        credential_id: Option<String>,
     },
     TriggerDistribution {},
 }
@@ -53,6 +76,8 @@ pub enum QueryMsg {
     ProposalByID { id: u64 },
     #[returns(AllProposalsResponse)]
     AllProposals {},
+    #[returns(ConfigResponse)]
+    Config {},
 }
 
 #[cw_serde]
@@ -60,6 +85,20 @@ pub struct AllProposalsResponse {
     pub proposals: Vec<Proposal>,
 }
 
+#[cw_serde]
+pub struct ConfigResponse {
+    pub admin: String,
+    pub leftover_addr: String,
+    pub create_proposal_whitelist: Option<Vec<String>>,
+    pub vote_proposal_whitelist: Option<Vec<String>>,
+    pub voting_period: Expiration,
+    pub proposal_period: Expiration,
+    pub budget: Coin,
+    pub algorithm: QuadraticFundingAlgorithm,
+    pub proposal_limits: ProposalLimits,
+    pub credential_class: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,6 +117,8 @@ mod tests {
             voting_period: Default::default(),
             proposal_period: Default::default(),
             budget_denom: "".to_string(),
+            proposal_limits: None,
+            credential_class: None,
             algorithm: QuadraticFundingAlgorithm::CapitalConstrainedLiberalRadicalism {
                 parameter: "".to_string(),
             },
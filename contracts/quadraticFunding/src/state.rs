@@ -15,9 +15,36 @@ pub struct Config {
     pub proposal_period: Expiration,
     pub budget: Coin,
     pub algorithm: QuadraticFundingAlgorithm,
+    // configs stored before limits existed load with the defaults
+    #[serde(default)]
+    pub proposal_limits: ProposalLimits,
+    // Coreum NFT class whose tokens act as voting credentials, one vote per credential and proposal
+    #[serde(default)]
+    pub credential_class: Option<String>,
 }
 pub const CONFIG: Item<Config> = Item::new("config");
 #[cw_serde]
+pub struct ProposalLimits {
+    // lengths are counted in characters
+    pub min_title_length: u32,
+    pub max_title_length: u32,
+    pub min_description_length: u32,
+    pub max_description_length: u32,
+    // size of the metadata in bytes, unlimited when not set
+    pub max_metadata_size: Option<u32>,
+}
+impl Default for ProposalLimits {
+    fn default() -> Self {
+        ProposalLimits {
+            min_title_length: 1,
+            max_title_length: 128,
+            min_description_length: 1,
+            max_description_length: 4096,
+            max_metadata_size: Some(4096),
+        }
+    }
+}
+#[cw_serde]
 #[derive(Default)]
 pub struct Proposal {
     pub id: u64,
@@ -29,10 +56,14 @@ pub struct Proposal {
 }
 pub const PROPOSALS: Map<u64, Proposal> = Map::new("proposal");
 pub const PROPOSAL_SEQ: Item<u64> = Item::new("proposal_seq");
+// normalized proposal title -> proposal id, titles are unique within the round
+pub const PROPOSAL_TITLES: Map<&str, u64> = Map::new("proposal_titles");
 #[cw_serde]
 pub struct Vote {
     pub proposal_id: u64,
     pub voter: String,
     pub fund: Coin,
 }
-pub const VOTES: Map<(u64, &[u8]), Vote> = Map::new("votes");
\ No newline at end of file
+pub const VOTES: Map<(u64, &[u8]), Vote> = Map::new("votes");
+// (proposal id, credential id) -> voter that presented the credential
+pub const CREDENTIAL_VOTES: Map<(u64, &str), String> = Map::new("credential_votes");
\ No newline at end of file
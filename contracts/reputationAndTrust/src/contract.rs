@@ -1,17 +1,29 @@
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{State, UserReputation, BALANCES, REPUTATIONS, STATE};
+use crate::msg::{
+    DisputeConfigResponse, ExecuteMsg, InstantiateMsg, ListCasesResponse, ListManagersResponse,
+    ManagerInfo, ManagerResponse, QueryMsg, Verdict,
+};
+use crate::state::{
+    Case, CaseStatus, DisputeConfig, ManagerPermissions, State, UserReputation, BALANCES, CASES,
+    CASE_SEQ, DISPUTE_CONFIG, MANAGERS, REPUTATIONS, STATE,
+};
 use coreum_wasm_sdk::assetft;
 use coreum_wasm_sdk::core::{CoreumMsg, CoreumQueries};
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo,
+    entry_point, to_binary, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Order,
     QueryRequest, Response, StdResult, Uint128,
 };
 use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+use reputation::ReputationRaw;
 
 const CONTRACT_NAME: &str = "reputation-trust";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// Pagination defaults for the ListManagers and ListCases queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 /// The instantiate function initializes the contract with the given parameters.
 #[entry_point]
 pub fn instantiate(
@@ -57,7 +69,7 @@ pub fn instantiate(
 #[entry_point]
 pub fn execute(
     deps: DepsMut<CoreumQueries>,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response<CoreumMsg>, ContractError> {
@@ -67,10 +79,27 @@ pub fn execute(
         }
         ExecuteMsg::ResetReputation { user } => reset_reputation(deps, info, user),
         ExecuteMsg::Transfer { recipient, amount } => transfer(deps, info, recipient, amount),
+        ExecuteMsg::Deposit {} => deposit(deps, info),
+        ExecuteMsg::Withdraw { amount } => withdraw(deps, info, amount),
+        ExecuteMsg::GrantManager { manager, max_change } => {
+            grant_manager(deps, info, manager, max_change)
+        }
+        ExecuteMsg::RevokeManager { manager } => revoke_manager(deps, info, manager),
+        ExecuteMsg::SetDisputeConfig {
+            arbiter,
+            bond_denom,
+            bond_amount,
+        } => set_dispute_config(deps, info, arbiter, bond_denom, bond_amount),
+        ExecuteMsg::FlagUser { user, evidence_uri } => {
+            flag_user(deps, env, info, user, evidence_uri)
+        }
+        ExecuteMsg::ResolveCase { case_id, verdict } => {
+            resolve_case(deps, env, info, case_id, verdict)
+        }
     }
 }
 
-/// The update_reputation function allows the contract owner to update a user's reputation.
+/// The update_reputation function allows the contract owner or a manager to update a user's reputation.
 fn update_reputation(
     deps: DepsMut<CoreumQueries>,
     info: MessageInfo,
@@ -79,10 +108,16 @@ fn update_reputation(
 ) -> Result<Response<CoreumMsg>, ContractError> {
     // Load the current state from the storage
     let state = STATE.load(deps.storage)?;
-    // Check if the sender is the owner of the contract
-    if info.sender != state.owner {
-        return Err(ContractError::Unauthorized {});
-    }
+    // Managers are only looked up when the sender is not the owner
+    let manager = if info.sender == state.owner {
+        None
+    } else {
+        Some(
+            MANAGERS
+                .may_load(deps.storage, &info.sender)?
+                .ok_or(ContractError::Unauthorized {})?,
+        )
+    };
 
     // Validate the user address
     let user_addr = deps.api.addr_validate(&user)?;
@@ -90,6 +125,16 @@ fn update_reputation(
     let mut user_reputation =
         REPUTATIONS.may_load(deps.storage, &user_addr)?.unwrap_or(UserReputation { reputation: 0 });
 
+    // Managers may only move a reputation within their granted range
+    if let Some(permissions) = manager {
+        let change = reputation.abs_diff(user_reputation.reputation);
+        if change > permissions.max_change {
+            return Err(ContractError::ChangeOutOfRange {
+                max_change: permissions.max_change,
+            });
+        }
+    }
+
     // Update the user's reputation
     user_reputation.reputation = reputation;
     // Save the updated reputation in the storage
@@ -98,7 +143,8 @@ fn update_reputation(
     // Return a response with the method and user attributes
     Ok(Response::new()
         .add_attribute("method", "update_reputation")
-        .add_attribute("user", user))
+        .add_attribute("user", user)
+        .add_attribute("updater", info.sender))
 }
 
 /// The reset_reputation function allows the contract owner to reset a user's reputation.
@@ -125,6 +171,184 @@ fn reset_reputation(
         .add_attribute("user", user))
 }
 
+/// The grant_manager function allows the contract owner to appoint a reputation manager.
+fn grant_manager(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    manager: String,
+    max_change: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    // Load the current state from the storage
+    let state = STATE.load(deps.storage)?;
+    // Check if the sender is the owner of the contract
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Validate the manager address and store its permissions
+    let manager_addr = deps.api.addr_validate(&manager)?;
+    MANAGERS.save(deps.storage, &manager_addr, &ManagerPermissions { max_change })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "grant_manager")
+        .add_attribute("manager", manager)
+        .add_attribute("max_change", max_change.to_string()))
+}
+
+/// The revoke_manager function allows the contract owner to remove a reputation manager.
+fn revoke_manager(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    manager: String,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    // Load the current state from the storage
+    let state = STATE.load(deps.storage)?;
+    // Check if the sender is the owner of the contract
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Validate the manager address and remove its permissions
+    let manager_addr = deps.api.addr_validate(&manager)?;
+    MANAGERS.remove(deps.storage, &manager_addr);
+
+    Ok(Response::new()
+        .add_attribute("method", "revoke_manager")
+        .add_attribute("manager", manager))
+}
+
+/// The set_dispute_config function allows the contract owner to enable or update disputes.
+fn set_dispute_config(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    arbiter: String,
+    bond_denom: String,
+    bond_amount: Uint128,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    // Load the current state from the storage
+    let state = STATE.load(deps.storage)?;
+    // Check if the sender is the owner of the contract
+    if info.sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    // A free flag would let anyone open cases without risking anything
+    if bond_amount.is_zero() {
+        return Err(ContractError::ZeroBond {});
+    }
+
+    let config = DisputeConfig {
+        arbiter: deps.api.addr_validate(&arbiter)?,
+        bond: Coin::new(bond_amount.u128(), bond_denom),
+    };
+    DISPUTE_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_dispute_config")
+        .add_attribute("arbiter", arbiter)
+        .add_attribute("bond", config.bond.to_string()))
+}
+
+/// The flag_user function opens a case against a user, holding the flagger's bond until the
+/// arbiter decides.
+fn flag_user(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    user: String,
+    evidence_uri: String,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = DISPUTE_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::DisputesDisabled {})?;
+    // The exact bond must be attached and nothing else
+    if info.funds != [config.bond.clone()] {
+        return Err(ContractError::InvalidBond {
+            bond: config.bond.to_string(),
+        });
+    }
+
+    // Validate the user address
+    let user_addr = deps.api.addr_validate(&user)?;
+    if user_addr == info.sender {
+        return Err(ContractError::SelfFlag {});
+    }
+
+    let id = CASE_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    CASE_SEQ.save(deps.storage, &id)?;
+    let case = Case {
+        id,
+        flagger: info.sender,
+        user: user_addr,
+        evidence_uri,
+        bond: config.bond,
+        status: CaseStatus::Open,
+        opened_at: env.block.time.seconds(),
+        resolved_at: None,
+    };
+    CASES.save(deps.storage, id, &case)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "flag_user")
+        .add_attribute("case_id", id.to_string())
+        .add_attribute("flagger", case.flagger)
+        .add_attribute("user", user))
+}
+
+/// The resolve_case function lets the arbiter decide on an open case, either slashing the
+/// flagged user's reputation or, when the flag was frivolous, the flagger's bond.
+fn resolve_case(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    case_id: u64,
+    verdict: Verdict,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = DISPUTE_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::DisputesDisabled {})?;
+    // Check if the sender is the arbiter
+    if info.sender != config.arbiter {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut case = CASES
+        .may_load(deps.storage, case_id)?
+        .ok_or(ContractError::CaseNotFound { case_id })?;
+    if case.status != CaseStatus::Open {
+        return Err(ContractError::CaseResolved { case_id });
+    }
+
+    // The bond goes back to the flagger unless the flag was frivolous
+    let (status, bond_recipient) = match verdict {
+        Verdict::Uphold { slash_percent } => {
+            if slash_percent == 0 || slash_percent > 100 {
+                return Err(ContractError::InvalidSlash {});
+            }
+            let mut user_reputation = REPUTATIONS
+                .may_load(deps.storage, &case.user)?
+                .unwrap_or(UserReputation { reputation: 0 });
+            let slashed = (user_reputation.reputation as u128 * slash_percent as u128 / 100) as u64;
+            user_reputation.reputation -= slashed;
+            REPUTATIONS.save(deps.storage, &case.user, &user_reputation)?;
+            (CaseStatus::Upheld { slashed }, case.flagger.clone())
+        }
+        Verdict::Dismiss {} => (CaseStatus::Dismissed, case.flagger.clone()),
+        Verdict::Frivolous {} => (CaseStatus::Frivolous, case.user.clone()),
+    };
+    case.status = status;
+    case.resolved_at = Some(env.block.time.seconds());
+    CASES.save(deps.storage, case_id, &case)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "resolve_case")
+        .add_attribute("case_id", case_id.to_string())
+        .add_attribute("bond_recipient", bond_recipient.to_string())
+        .add_message(BankMsg::Send {
+            to_address: bond_recipient.into(),
+            amount: vec![case.bond],
+        }))
+}
+
 /// The transfer function allows a user to transfer a specified amount of tokens to another user.
 pub fn transfer(
     deps: DepsMut<CoreumQueries>,
@@ -158,13 +382,78 @@ pub fn transfer(
         .add_attribute("amount", amount.to_string()))
     }
 
+/// The deposit function credits the tokens of the issued denom attached to the message to the
+/// sender's internal balance.
+fn deposit(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    // Exactly one non-zero coin of the issued denom must be attached
+    let amount = match info.funds.as_slice() {
+        [coin] if coin.denom == state.denom && !coin.amount.is_zero() => coin.amount,
+        _ => return Err(ContractError::InvalidDeposit { denom: state.denom }),
+    };
+
+    let balance = BALANCES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default()
+        .checked_add(amount)
+        .map_err(|_| ContractError::Overflow {})?;
+    BALANCES.save(deps.storage, &info.sender, &balance)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "deposit")
+        .add_attribute("user", info.sender)
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// The withdraw function debits the sender's internal balance and sends the tokens back.
+/// The contract is the issuer of the denom, so no send commission is charged on the way out.
+fn withdraw(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    let state = STATE.load(deps.storage)?;
+
+    let balance = BALANCES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default()
+        .checked_sub(amount)
+        .map_err(|_| ContractError::InsufficientBalance {})?;
+    BALANCES.save(deps.storage, &info.sender, &balance)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "withdraw")
+        .add_attribute("user", info.sender.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_message(BankMsg::Send {
+            to_address: info.sender.into(),
+            amount: vec![Coin::new(amount.u128(), state.denom)],
+        }))
+}
+
 /// The query function handles different query messages and returns the corresponding data.
 #[entry_point]
 pub fn query(deps: Deps<CoreumQueries>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Token {} => token(deps),
         QueryMsg::GetReputation { user } => query_reputation(deps, user),
+        QueryMsg::GetReputationRaw { user } => query_reputation_raw(deps, user),
         QueryMsg::GetBalance { user } => query_balance(deps, user),
+        QueryMsg::GetManager { manager } => query_manager(deps, manager),
+        QueryMsg::ListManagers { start_after, limit } => {
+            query_list_managers(deps, start_after, limit)
+        }
+        QueryMsg::GetDisputeConfig {} => to_binary(&DisputeConfigResponse {
+            config: DISPUTE_CONFIG.may_load(deps.storage)?,
+        }),
+        QueryMsg::GetCase { case_id } => to_binary(&CASES.load(deps.storage, case_id)?),
+        QueryMsg::ListCases { start_after, limit } => query_list_cases(deps, start_after, limit),
     }
 }
 
@@ -192,6 +481,19 @@ fn query_reputation(deps: Deps<CoreumQueries>, user: String) -> StdResult<Binary
     to_binary(&reputation)
 }
 
+/// The query_reputation_raw function returns the reputation of a specified user in the format
+/// other contracts read through the `reputation` package.
+fn query_reputation_raw(deps: Deps<CoreumQueries>, user: String) -> StdResult<Binary> {
+    let user_addr = deps.api.addr_validate(&user)?;
+    let reputation = REPUTATIONS
+        .may_load(deps.storage, &user_addr)?
+        .map_or(0, |r| r.reputation);
+    to_binary(&ReputationRaw {
+        user: user_addr,
+        reputation,
+    })
+}
+
 /// The query_balance function queries and returns the token balance of a specified user.
 fn query_balance(deps: Deps<CoreumQueries>, user: String) -> StdResult<Binary> {
     // Validate the user address
@@ -200,4 +502,239 @@ fn query_balance(deps: Deps<CoreumQueries>, user: String) -> StdResult<Binary> {
     let balance = BALANCES.may_load(deps.storage, &user_addr)?.unwrap_or(Uint128::zero());
     // Return the user's balance as binary
     to_binary(&balance)
-}
\ No newline at end of file
+}
+
+/// The query_manager function queries and returns the permissions of a specified manager.
+fn query_manager(deps: Deps<CoreumQueries>, manager: String) -> StdResult<Binary> {
+    // Validate the manager address
+    let manager_addr = deps.api.addr_validate(&manager)?;
+    let permissions = MANAGERS.may_load(deps.storage, &manager_addr)?;
+    to_binary(&ManagerResponse { permissions })
+}
+
+/// The query_list_managers function queries and returns a page of managers.
+fn query_list_managers(
+    deps: Deps<CoreumQueries>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+
+    let managers = MANAGERS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(manager, permissions)| ManagerInfo {
+                manager: manager.to_string(),
+                permissions,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    to_binary(&ListManagersResponse { managers })
+}
+
+/// The query_list_cases function queries and returns a page of the case log.
+fn query_list_cases(
+    deps: Deps<CoreumQueries>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Binary> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let cases = CASES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, case)| case))
+        .collect::<StdResult<Vec<_>>>()?;
+    to_binary(&ListCasesResponse { cases })
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Case;
+    use coreum_test_utils::{mock_coreum_deps, CoreumDeps};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, CosmosMsg};
+
+    const BOND: &str = "ucore";
+
+    fn setup() -> CoreumDeps {
+        let mut deps = mock_coreum_deps();
+        let msg = InstantiateMsg {
+            symbol: "REP".to_string(),
+            subunit: "urep".to_string(),
+            precision: 6,
+            initial_amount: Uint128::new(1000),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        let msg = ExecuteMsg::SetDisputeConfig {
+            arbiter: "arbiter".to_string(),
+            bond_denom: BOND.to_string(),
+            bond_amount: Uint128::new(100),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateReputation {
+            user: "user".to_string(),
+            reputation: 80,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        deps
+    }
+
+    fn flag(deps: &mut CoreumDeps) -> u64 {
+        let msg = ExecuteMsg::FlagUser {
+            user: "user".to_string(),
+            evidence_uri: "ipfs://evidence".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("flagger", &coins(100, BOND)), msg).unwrap();
+        res.attributes[1].value.parse().unwrap()
+    }
+
+    fn resolve(deps: &mut CoreumDeps, case_id: u64, verdict: Verdict) -> Result<Response<CoreumMsg>, ContractError> {
+        let msg = ExecuteMsg::ResolveCase { case_id, verdict };
+        execute(deps.as_mut(), mock_env(), mock_info("arbiter", &[]), msg)
+    }
+
+    fn bond_paid_to(res: &Response<CoreumMsg>) -> String {
+        match &res.messages[0].msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                assert_eq!(amount, &coins(100, BOND));
+                to_address.clone()
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        }
+    }
+
+    fn reputation(deps: &CoreumDeps, user: &str) -> u64 {
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetReputation { user: user.to_string() }).unwrap();
+        from_binary::<UserReputation>(&res).unwrap().reputation
+    }
+
+    #[test]
+    fn dispute_config_requires_a_bond() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::SetDisputeConfig {
+            arbiter: "arbiter".to_string(),
+            bond_denom: BOND.to_string(),
+            bond_amount: Uint128::zero(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::ZeroBond {}));
+    }
+
+    #[test]
+    fn flag_requires_the_exact_bond() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::FlagUser {
+            user: "user".to_string(),
+            evidence_uri: "ipfs://evidence".to_string(),
+        };
+        for funds in [vec![], coins(99, BOND), coins(100, "uother")] {
+            let err = execute(deps.as_mut(), mock_env(), mock_info("flagger", &funds), msg.clone()).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidBond { .. }));
+        }
+        let err = execute(deps.as_mut(), mock_env(), mock_info("user", &coins(100, BOND)), msg).unwrap_err();
+        assert!(matches!(err, ContractError::SelfFlag {}));
+    }
+
+    #[test]
+    fn upheld_case_slashes_the_user_and_refunds_the_flagger() {
+        let mut deps = setup();
+        let case_id = flag(&mut deps);
+
+        let msg = ExecuteMsg::ResolveCase { case_id, verdict: Verdict::Dismiss {} };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("flagger", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let err = resolve(&mut deps, case_id, Verdict::Uphold { slash_percent: 101 }).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidSlash {}));
+
+        let res = resolve(&mut deps, case_id, Verdict::Uphold { slash_percent: 25 }).unwrap();
+        assert_eq!(bond_paid_to(&res), "flagger");
+        assert_eq!(reputation(&deps, "user"), 60);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetCase { case_id }).unwrap();
+        let case: Case = from_binary(&res).unwrap();
+        assert_eq!(case.status, CaseStatus::Upheld { slashed: 20 });
+
+        // A case is decided once
+        let err = resolve(&mut deps, case_id, Verdict::Dismiss {}).unwrap_err();
+        assert!(matches!(err, ContractError::CaseResolved { .. }));
+    }
+
+    #[test]
+    fn dismissed_and_frivolous_cases_keep_the_reputation() {
+        let mut deps = setup();
+        let dismissed = flag(&mut deps);
+        let frivolous = flag(&mut deps);
+
+        let res = resolve(&mut deps, dismissed, Verdict::Dismiss {}).unwrap();
+        assert_eq!(bond_paid_to(&res), "flagger");
+        let res = resolve(&mut deps, frivolous, Verdict::Frivolous {}).unwrap();
+        assert_eq!(bond_paid_to(&res), "user");
+        assert_eq!(reputation(&deps, "user"), 80);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ListCases { start_after: None, limit: None }).unwrap();
+        let cases: ListCasesResponse = from_binary(&res).unwrap();
+        let statuses: Vec<CaseStatus> = cases.cases.into_iter().map(|case| case.status).collect();
+        assert_eq!(statuses, vec![CaseStatus::Dismissed, CaseStatus::Frivolous]);
+    }
+
+    fn balance(deps: &CoreumDeps, user: &str) -> Uint128 {
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetBalance { user: user.to_string() }).unwrap();
+        from_binary(&res).unwrap()
+    }
+
+    #[test]
+    fn deposit_transfer_and_withdraw_round_trip() {
+        let mut deps = setup();
+        let denom = STATE.load(&deps.storage).unwrap().denom;
+
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &coins(300, &denom)), ExecuteMsg::Deposit {}).unwrap();
+        let msg = ExecuteMsg::Transfer { recipient: "bob".to_string(), amount: Uint128::new(120) };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        assert_eq!(balance(&deps, "alice"), Uint128::new(180));
+        assert_eq!(balance(&deps, "bob"), Uint128::new(120));
+
+        let msg = ExecuteMsg::Withdraw { amount: Uint128::new(120) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: "bob".to_string(), amount: coins(120, &denom) })
+        );
+        assert_eq!(balance(&deps, "bob"), Uint128::zero());
+    }
+
+    #[test]
+    fn deposit_rejects_other_denoms() {
+        let mut deps = setup();
+        let denom = STATE.load(&deps.storage).unwrap().denom;
+
+        for funds in [vec![], coins(100, "ucore"), coins(0, &denom), vec![Coin::new(100, &denom), Coin::new(1, "ucore")]] {
+            let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &funds), ExecuteMsg::Deposit {}).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidDeposit { .. }));
+        }
+        assert_eq!(balance(&deps, "alice"), Uint128::zero());
+    }
+
+    #[test]
+    fn transfer_and_withdraw_reject_insufficient_balance() {
+        let mut deps = setup();
+        let denom = STATE.load(&deps.storage).unwrap().denom;
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &coins(50, &denom)), ExecuteMsg::Deposit {}).unwrap();
+
+        let msg = ExecuteMsg::Transfer { recipient: "bob".to_string(), amount: Uint128::new(51) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientBalance {}));
+        let msg = ExecuteMsg::Withdraw { amount: Uint128::new(51) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientBalance {}));
+        let msg = ExecuteMsg::Withdraw { amount: Uint128::zero() };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidZeroAmount {}));
+        assert_eq!(balance(&deps, "alice"), Uint128::new(50));
+    }
+}
@@ -15,5 +15,35 @@ pub enum ContractError {
     #[error("Overflow error")]
     Overflow {},
 
+    #[error("Reputation change exceeds the manager limit of {max_change}")]
+    ChangeOutOfRange { max_change: u64 },
+
+    #[error("Disputes are not enabled")]
+    DisputesDisabled {},
+
+    #[error("A bond of exactly {bond} is required")]
+    InvalidBond { bond: String },
+
+    #[error("Case {case_id} not found")]
+    CaseNotFound { case_id: u64 },
+
+    #[error("Case {case_id} is already resolved")]
+    CaseResolved { case_id: u64 },
+
+    #[error("Slash percentage must be between 1 and 100")]
+    InvalidSlash {},
+
+    #[error("The dispute bond must be greater than zero")]
+    ZeroBond {},
+
+    #[error("Users cannot flag themselves")]
+    SelfFlag {},
+
+    #[error("Deposits must attach a non-zero amount of {denom} and nothing else")]
+    InvalidDeposit { denom: String },
+
+    #[error("Amount must be greater than zero")]
+    InvalidZeroAmount {},
+
     // other variants...
 }
\ No newline at end of file
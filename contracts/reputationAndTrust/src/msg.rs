@@ -1,3 +1,4 @@
+use crate::state::{Case, DisputeConfig, ManagerPermissions};
 use cosmwasm_std::Uint128;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -20,7 +21,8 @@ pub struct InstantiateMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    /// Updates the reputation of a specified user. Only callable by the contract owner.
+    /// Updates the reputation of a specified user. Callable by the contract owner, or by a
+    /// manager as long as the change stays within the manager's `max_change`.
     UpdateReputation { 
         /// The address of the user whose reputation is to be updated.
         user: String, 
@@ -39,6 +41,63 @@ pub enum ExecuteMsg {
         /// The amount of tokens to be transferred.
         amount: Uint128 
     },
+    /// Credits the attached tokens of the issued denom to the sender's internal balance.
+    Deposit {},
+    /// Debits the sender's internal balance and sends the tokens back to the sender.
+    Withdraw {
+        /// The amount of tokens to be withdrawn.
+        amount: Uint128,
+    },
+    /// Appoints or updates a reputation manager. Only callable by the contract owner.
+    GrantManager {
+        /// The address of the manager to be appointed.
+        manager: String,
+        /// The maximum reputation change the manager may apply in a single call.
+        max_change: u64,
+    },
+    /// Removes a reputation manager. Only callable by the contract owner.
+    RevokeManager {
+        /// The address of the manager to be removed.
+        manager: String,
+    },
+    /// Enables or updates the dispute process. Only callable by the contract owner.
+    SetDisputeConfig {
+        /// The address that decides on flagged cases.
+        arbiter: String,
+        /// The denomination of the bond required to flag a user.
+        bond_denom: String,
+        /// The amount of the bond required to flag a user.
+        bond_amount: Uint128,
+    },
+    /// Opens a case against a user for reputation abuse. The configured bond must be attached.
+    FlagUser {
+        /// The address of the user being flagged.
+        user: String,
+        /// Where the evidence supporting the flag can be found.
+        evidence_uri: String,
+    },
+    /// Decides on an open case. Only callable by the arbiter.
+    ResolveCase {
+        /// The identifier of the case to be resolved.
+        case_id: u64,
+        /// The decision of the arbiter.
+        verdict: Verdict,
+    },
+}
+
+/// The `Verdict` enum defines the decisions the arbiter can take on a case.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// Confirms the abuse, slashing part of the user's reputation and refunding the bond.
+    Uphold {
+        /// The percentage of the user's reputation to be removed, between 1 and 100.
+        slash_percent: u8,
+    },
+    /// Rejects the flag for lack of evidence and refunds the bond.
+    Dismiss {},
+    /// Rejects the flag as frivolous, the bond is paid to the flagged user.
+    Frivolous {},
 }
 
 /// The `QueryMsg` enum defines the different query messages that can be sent to the contract.
@@ -52,9 +111,78 @@ pub enum QueryMsg {
         /// The address of the user whose reputation is to be queried.
         user: String 
     },
+    /// Queries and returns the reputation of a specified user as a `reputation::ReputationRaw`.
+    /// Stable interface for other contracts, see the `reputation` package.
+    GetReputationRaw {
+        /// The address of the user whose reputation is to be queried.
+        user: String,
+    },
     /// Queries and returns the token balance of a specified user.
     GetBalance { 
         /// The address of the user whose balance is to be queried.
         user: String 
     },
+    /// Queries and returns the permissions of a specified manager, if any.
+    GetManager {
+        /// The address of the manager to be queried.
+        manager: String,
+    },
+    /// Queries and returns all managers, paginated by address.
+    ListManagers {
+        /// The address after which to start listing.
+        start_after: Option<String>,
+        /// The maximum number of managers to return.
+        limit: Option<u32>,
+    },
+    /// Queries and returns the dispute configuration, if disputes are enabled.
+    GetDisputeConfig {},
+    /// Queries and returns a single case.
+    GetCase {
+        /// The identifier of the case to be queried.
+        case_id: u64,
+    },
+    /// Queries and returns the case log, paginated by case id.
+    ListCases {
+        /// The case id after which to start listing.
+        start_after: Option<u64>,
+        /// The maximum number of cases to return.
+        limit: Option<u32>,
+    },
+}
+
+/// The `ManagerResponse` struct is returned by the `GetManager` query.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ManagerResponse {
+    /// The permissions of the manager, or `None` if the address is not a manager.
+    pub permissions: Option<ManagerPermissions>,
+}
+
+/// The `ManagerInfo` struct describes a single manager in the `ListManagers` query.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ManagerInfo {
+    /// The address of the manager.
+    pub manager: String,
+    /// The permissions granted to the manager.
+    pub permissions: ManagerPermissions,
+}
+
+/// The `ListManagersResponse` struct is returned by the `ListManagers` query.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListManagersResponse {
+    /// The managers in ascending address order.
+    pub managers: Vec<ManagerInfo>,
+}
+
+/// The `DisputeConfigResponse` struct is returned by the `GetDisputeConfig` query.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DisputeConfigResponse {
+    /// The dispute configuration, or `None` if disputes are not enabled.
+    pub config: Option<DisputeConfig>,
+}
+
+/// The `ListCasesResponse` struct is returned by the `ListCases` query.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListCasesResponse {
+    /// The cases in ascending id order.
+    pub cases: Vec<Case>,
 }
\ No newline at end of file
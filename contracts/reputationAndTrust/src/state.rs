@@ -1,4 +1,4 @@
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Coin, Uint128};
 use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -26,4 +26,68 @@ pub struct UserReputation {
 pub const REPUTATIONS: Map<&Addr, UserReputation> = Map::new("reputations");
 
 /// `BALANCES` is a `Map` storage entry that maps a user's address to their token balance.
-pub const BALANCES: Map<&Addr, Uint128> = Map::new("balances");
\ No newline at end of file
+pub const BALANCES: Map<&Addr, Uint128> = Map::new("balances");
+
+/// The `ManagerPermissions` struct holds the scope granted to a delegated reputation manager.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ManagerPermissions {
+    /// The maximum amount by which a manager may raise or lower a reputation in a single call.
+    pub max_change: u64,
+}
+
+/// `MANAGERS` is a `Map` storage entry that maps a manager's address to their `ManagerPermissions`.
+pub const MANAGERS: Map<&Addr, ManagerPermissions> = Map::new("managers");
+
+/// The `DisputeConfig` struct holds the parameters of the dispute process.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DisputeConfig {
+    /// The address that decides on flagged cases.
+    pub arbiter: Addr,
+    /// The bond a flagger must attach to `FlagUser`.
+    pub bond: Coin,
+}
+
+/// `DISPUTE_CONFIG` is an `Item` storage entry that holds the `DisputeConfig`, disputes are
+/// disabled until the owner sets it.
+pub const DISPUTE_CONFIG: Item<DisputeConfig> = Item::new("dispute_config");
+
+/// The `CaseStatus` enum tracks the outcome of a flagged case.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseStatus {
+    /// The case awaits a decision of the arbiter.
+    Open,
+    /// The abuse was confirmed and `slashed` reputation was removed, the bond was refunded.
+    Upheld { slashed: u64 },
+    /// The evidence was insufficient, the bond was refunded.
+    Dismissed,
+    /// The flag was frivolous, the bond was paid to the flagged user.
+    Frivolous,
+}
+
+/// The `Case` struct records a single reputation abuse report.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Case {
+    /// The identifier of the case.
+    pub id: u64,
+    /// The address that raised the flag.
+    pub flagger: Addr,
+    /// The address accused of reputation abuse.
+    pub user: Addr,
+    /// Where the evidence supporting the flag can be found.
+    pub evidence_uri: String,
+    /// The bond deposited by the flagger.
+    pub bond: Coin,
+    /// The current status of the case.
+    pub status: CaseStatus,
+    /// The block time in seconds at which the case was opened.
+    pub opened_at: u64,
+    /// The block time in seconds at which the arbiter decided, if any.
+    pub resolved_at: Option<u64>,
+}
+
+/// `CASES` is a `Map` storage entry that maps a case id to its `Case`.
+pub const CASES: Map<u64, Case> = Map::new("cases");
+
+/// `CASE_SEQ` is an `Item` storage entry that holds the id of the last opened case.
+pub const CASE_SEQ: Item<u64> = Item::new("case_seq");
@@ -0,0 +1,465 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Order,
+    Response, StdResult, Uint128,
+};
+use cw2::set_contract_version;
+use std::collections::BTreeSet;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ClaimableResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, RecipientMsg,
+    UndistributedResponse,
+};
+use crate::state::{Config, Recipient, CLAIMABLE, CONFIG, TOTAL_CLAIMABLE};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:splitter";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Denominator of basis-point shares
+const BPS_DENOMINATOR: u32 = 10_000;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let config = Config {
+        owner: info.sender.clone(),
+        recipients: validate_recipients(deps.as_ref(), msg.recipients)?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "instantiate")
+        .add_attribute("owner", info.sender))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Distribute { denoms } => execute_distribute(deps, env, denoms),
+        ExecuteMsg::Claim {} => execute_claim(deps, info),
+        ExecuteMsg::UpdateRecipients { recipients } => {
+            execute_update_recipients(deps, info, recipients)
+        }
+        ExecuteMsg::UpdateOwner { owner } => execute_update_owner(deps, info, owner),
+    }
+}
+
+pub fn execute_distribute(
+    deps: DepsMut,
+    env: Env,
+    denoms: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let balances = match denoms {
+        // balances are read once up front, so each denom may only be split once
+        Some(denoms) => BTreeSet::from_iter(denoms)
+            .iter()
+            .map(|denom| deps.querier.query_balance(&env.contract.address, denom))
+            .collect::<StdResult<Vec<_>>>()?,
+        None => deps.querier.query_all_balances(&env.contract.address)?,
+    };
+
+    let mut messages = vec![];
+    let mut distributed = vec![];
+    for balance in balances {
+        let amount = undistributed(deps.as_ref(), &balance)?;
+        let mut split = Uint128::zero();
+        for recipient in &config.recipients {
+            let share = amount.multiply_ratio(recipient.share_bps as u128, BPS_DENOMINATOR as u128);
+            if share.is_zero() {
+                continue;
+            }
+            split += share;
+            if recipient.push {
+                messages.push(BankMsg::Send {
+                    to_address: recipient.address.to_string(),
+                    amount: coins(share.u128(), &balance.denom),
+                });
+            } else {
+                let key = (&recipient.address, balance.denom.as_str());
+                let claimable = CLAIMABLE.may_load(deps.storage, key)?.unwrap_or_default();
+                CLAIMABLE.save(deps.storage, key, &(claimable + share))?;
+                let total = TOTAL_CLAIMABLE
+                    .may_load(deps.storage, &balance.denom)?
+                    .unwrap_or_default();
+                TOTAL_CLAIMABLE.save(deps.storage, &balance.denom, &(total + share))?;
+            }
+        }
+        // rounding dust stays for the next distribution
+        if !split.is_zero() {
+            distributed.push(format!("{}{}", split, balance.denom));
+        }
+    }
+    if distributed.is_empty() {
+        return Err(ContractError::NothingToDistribute {});
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "distribute")
+        .add_attribute("amount", distributed.join(",")))
+}
+
+pub fn execute_claim(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let claimable = CLAIMABLE
+        .prefix(&info.sender)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    if claimable.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    let mut funds = vec![];
+    for (denom, amount) in claimable {
+        CLAIMABLE.remove(deps.storage, (&info.sender, denom.as_str()));
+        let total = TOTAL_CLAIMABLE.load(deps.storage, &denom)?;
+        TOTAL_CLAIMABLE.save(deps.storage, &denom, &total.checked_sub(amount)?)?;
+        funds.push(Coin { denom, amount });
+    }
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: funds,
+        })
+        .add_attribute("action", "claim")
+        .add_attribute("recipient", info.sender))
+}
+
+pub fn execute_update_recipients(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipients: Vec<RecipientMsg>,
+) -> Result<Response, ContractError> {
+    let mut config = assert_owner(deps.as_ref(), &info.sender)?;
+    config.recipients = validate_recipients(deps.as_ref(), recipients)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_recipients"))
+}
+
+pub fn execute_update_owner(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let mut config = assert_owner(deps.as_ref(), &info.sender)?;
+    config.owner = deps.api.addr_validate(&owner)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_owner")
+        .add_attribute("owner", config.owner))
+}
+
+fn assert_owner(deps: Deps, sender: &Addr) -> Result<Config, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if &config.owner != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(config)
+}
+
+fn validate_recipients(
+    deps: Deps,
+    recipients: Vec<RecipientMsg>,
+) -> Result<Vec<Recipient>, ContractError> {
+    let total: u32 = recipients.iter().map(|r| r.share_bps as u32).sum();
+    if total != BPS_DENOMINATOR {
+        return Err(ContractError::InvalidShares { total });
+    }
+
+    let mut validated: Vec<Recipient> = Vec::with_capacity(recipients.len());
+    for recipient in recipients {
+        let address = deps.api.addr_validate(&recipient.address)?;
+        if validated.iter().any(|r| r.address == address) {
+            return Err(ContractError::DuplicateRecipient {
+                address: recipient.address,
+            });
+        }
+        validated.push(Recipient {
+            address,
+            share_bps: recipient.share_bps,
+            push: recipient.push,
+        });
+    }
+    Ok(validated)
+}
+
+/// Part of `balance` not yet owed to pull recipients
+fn undistributed(deps: Deps, balance: &Coin) -> StdResult<Uint128> {
+    let owed = TOTAL_CLAIMABLE
+        .may_load(deps.storage, &balance.denom)?
+        .unwrap_or_default();
+    Ok(balance.amount.saturating_sub(owed))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Claimable { address } => to_json_binary(&query_claimable(deps, address)?),
+        QueryMsg::Undistributed {} => to_json_binary(&query_undistributed(deps, env)?),
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: config.owner,
+        recipients: config.recipients,
+    })
+}
+
+fn query_claimable(deps: Deps, address: String) -> StdResult<ClaimableResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let funds = CLAIMABLE
+        .prefix(&address)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+        .collect::<StdResult<_>>()?;
+    Ok(ClaimableResponse { funds })
+}
+
+fn query_undistributed(deps: Deps, env: Env) -> StdResult<UndistributedResponse> {
+    let mut funds = vec![];
+    for balance in deps.querier.query_all_balances(&env.contract.address)? {
+        let amount = undistributed(deps, &balance)?;
+        if !amount.is_zero() {
+            funds.push(Coin {
+                denom: balance.denom,
+                amount,
+            });
+        }
+    }
+    Ok(UndistributedResponse { funds })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{coin, CosmosMsg, OwnedDeps};
+
+    const OWNER: &str = "owner";
+    const ALICE: &str = "alice";
+    const BOB: &str = "bob";
+    const CAROL: &str = "carol";
+
+    fn recipient(address: &str, share_bps: u16, push: bool) -> RecipientMsg {
+        RecipientMsg {
+            address: address.to_string(),
+            share_bps,
+            push,
+        }
+    }
+
+    fn setup(recipients: Vec<RecipientMsg>) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg { recipients };
+        instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+        deps
+    }
+
+    fn set_balance(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, funds: Vec<Coin>) {
+        deps.querier
+            .update_balance(mock_env().contract.address, funds);
+    }
+
+    fn distribute(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Distribute { denoms: None };
+        execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg)
+    }
+
+    #[test]
+    fn shares_must_add_up() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            recipients: vec![recipient(ALICE, 6_000, true), recipient(BOB, 3_000, true)],
+        };
+        let err = instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidShares { total: 9_000 });
+
+        let msg = InstantiateMsg {
+            recipients: vec![
+                recipient(ALICE, 5_000, true),
+                recipient(ALICE, 5_000, false),
+            ],
+        };
+        let err = instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::DuplicateRecipient {
+                address: ALICE.to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn push_distribution() {
+        let mut deps = setup(vec![
+            recipient(ALICE, 7_000, true),
+            recipient(BOB, 3_000, true),
+        ]);
+        let err = distribute(&mut deps).unwrap_err();
+        assert_eq!(err, ContractError::NothingToDistribute {});
+
+        set_balance(&mut deps, vec![coin(1_000, "ucore"), coin(10, "uroyalty")]);
+        let res = distribute(&mut deps).unwrap();
+        let sent: Vec<CosmosMsg> = vec![
+            BankMsg::Send {
+                to_address: ALICE.to_string(),
+                amount: coins(700, "ucore"),
+            }
+            .into(),
+            BankMsg::Send {
+                to_address: BOB.to_string(),
+                amount: coins(300, "ucore"),
+            }
+            .into(),
+            BankMsg::Send {
+                to_address: ALICE.to_string(),
+                amount: coins(7, "uroyalty"),
+            }
+            .into(),
+            BankMsg::Send {
+                to_address: BOB.to_string(),
+                amount: coins(3, "uroyalty"),
+            }
+            .into(),
+        ];
+        assert_eq!(
+            res.messages.into_iter().map(|m| m.msg).collect::<Vec<_>>(),
+            sent
+        );
+    }
+
+    #[test]
+    fn pull_claims_are_set_aside() {
+        let mut deps = setup(vec![
+            recipient(ALICE, 5_000, true),
+            recipient(BOB, 3_333, false),
+            recipient(CAROL, 1_667, false),
+        ]);
+        set_balance(&mut deps, vec![coin(100, "ucore")]);
+        let res = distribute(&mut deps).unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        // alice was paid, bob and carol's shares stay, one unit of dust is left over
+        set_balance(&mut deps, vec![coin(50, "ucore")]);
+        let undistributed = query_undistributed(deps.as_ref(), mock_env()).unwrap();
+        assert_eq!(undistributed.funds, vec![coin(1, "ucore")]);
+        assert_eq!(
+            query_claimable(deps.as_ref(), BOB.to_string())
+                .unwrap()
+                .funds,
+            vec![coin(33, "ucore")]
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOB, &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: BOB.to_string(),
+                amount: coins(33, "ucore"),
+            })
+        );
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BOB, &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NothingToClaim {});
+        assert_eq!(
+            TOTAL_CLAIMABLE.load(&deps.storage, "ucore").unwrap(),
+            Uint128::new(16)
+        );
+    }
+
+    #[test]
+    fn repeated_denoms_split_once() {
+        let mut deps = setup(vec![
+            recipient(ALICE, 1_000, true),
+            recipient(BOB, 9_000, false),
+        ]);
+        set_balance(&mut deps, vec![coin(1_000, "ucore")]);
+
+        let msg = ExecuteMsg::Distribute {
+            denoms: Some(vec!["ucore".to_string(), "ucore".to_string()]),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|m| m.msg)
+                .collect::<Vec<CosmosMsg>>(),
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: ALICE.to_string(),
+                amount: coins(100, "ucore"),
+            })]
+        );
+        assert_eq!(
+            TOTAL_CLAIMABLE.load(&deps.storage, "ucore").unwrap(),
+            Uint128::new(900)
+        );
+        assert_eq!(
+            query_claimable(deps.as_ref(), BOB.to_string())
+                .unwrap()
+                .funds,
+            vec![coin(900, "ucore")]
+        );
+    }
+
+    #[test]
+    fn owner_updates_recipients() {
+        let mut deps = setup(vec![recipient(ALICE, 10_000, true)]);
+
+        let msg = ExecuteMsg::UpdateRecipients {
+            recipients: vec![recipient(BOB, 10_000, true)],
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ALICE, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+
+        set_balance(&mut deps, vec![coin(10, "ucore")]);
+        let res = distribute(&mut deps).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: BOB.to_string(),
+                amount: coins(10, "ucore"),
+            })
+        );
+    }
+}
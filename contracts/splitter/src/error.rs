@@ -0,0 +1,26 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Shares must add up to 10000 basis points, got {total}")]
+    InvalidShares { total: u32 },
+
+    #[error("Recipient {address} is listed more than once")]
+    DuplicateRecipient { address: String },
+
+    #[error("Nothing to distribute")]
+    NothingToDistribute {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+}
@@ -0,0 +1,58 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Coin};
+
+use crate::state::Recipient;
+
+#[cw_serde]
+pub struct RecipientMsg {
+    pub address: String,
+    pub share_bps: u16,
+    pub push: bool,
+}
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub recipients: Vec<RecipientMsg>,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Splits the undistributed balance of `denoms`, or of every denom held when `None`.
+    /// Push recipients are paid right away, the others can claim their share. Anyone can call it,
+    /// funds attached to the call are split too.
+    Distribute { denoms: Option<Vec<String>> },
+    /// Pays the sender everything distributed to them so far.
+    Claim {},
+    /// Owner only; undistributed funds are split by the new shares.
+    UpdateRecipients { recipients: Vec<RecipientMsg> },
+    /// Owner only
+    UpdateOwner { owner: String },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(ClaimableResponse)]
+    Claimable { address: String },
+    /// Balance not split yet, rounding dust included
+    #[returns(UndistributedResponse)]
+    Undistributed {},
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub recipients: Vec<Recipient>,
+}
+
+#[cw_serde]
+pub struct ClaimableResponse {
+    pub funds: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct UndistributedResponse {
+    pub funds: Vec<Coin>,
+}
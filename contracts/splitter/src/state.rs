@@ -0,0 +1,24 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Recipient {
+    pub address: Addr,
+    /// Basis points of every distribution, all shares add up to 10000
+    pub share_bps: u16,
+    /// Whether the share is sent right away or kept for the recipient to claim
+    pub push: bool,
+}
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,
+    pub recipients: Vec<Recipient>,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+/// Amounts recipients can claim, by recipient and denom
+pub const CLAIMABLE: Map<(&Addr, &str), Uint128> = Map::new("claimable");
+/// Sum of `CLAIMABLE` per denom, this part of the balance is not distributed again
+pub const TOTAL_CLAIMABLE: Map<&str, Uint128> = Map::new("total_claimable");
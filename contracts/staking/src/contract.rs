@@ -1,21 +1,27 @@
+use coreum_wasm_sdk::assetft;
+use coreum_wasm_sdk::core::{CoreumMsg, CoreumResult};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coin, log, to_binary, Api, BankMsg, Binary, Decimal, Env, Extern, HandleResponse, HumanAddr,
-    InitResponse, Querier, StakingMsg, StdError, StdResult, Storage, Uint128, WasmMsg,
+    coin, to_binary, Addr, BankMsg, Binary, Decimal, Deps, DepsMut, DistributionMsg, Empty, Env,
+    Event, MessageInfo, Order, QuerierWrapper, Response, StakingMsg, StdError, StdResult, Storage,
+    Uint128, WasmMsg,
 };
-use cw2::set_contract_version;
-use cw20_base::allowances::{
-    handle_burn_from, handle_decrease_allowance, handle_increase_allowance, handle_send_from,
-    handle_transfer_from, query_allowance,
+use cw2::{get_contract_version, set_contract_version};
+use cw_controllers::Claim;
+use cw_storage_plus::Bound;
+use cw_utils::{must_pay, Duration};
+use semver::Version;
+use voting_power::{TotalPowerResponse, VotingPowerResponse};
+
+use crate::error::ContractError;
+use crate::msg::{
+    AccountTierResponse, ClaimsResponse, ExchangeRateResponse, ExecuteMsg, InstantiateMsg,
+    InvestmentResponse, MigrateMsg, PendingUnbond, PendingUnbondsResponse, QueryMsg,
 };
-use cw20_base::contract::{
-    handle_burn, handle_mint, handle_send, handle_transfer, query_balance, query_token_info,
-};
-use cw20_base::state::{token_info, MinterData, TokenInfo};
-
-use crate::msg::{ClaimsResponse, HandleMsg, InitMsg, InvestmentResponse, QueryMsg};
 use crate::state::{
-    claims, claims_read, invest_info, invest_info_read, total_supply, total_supply_read, Claim,
-    InvestmentInfo, Supply,
+    Account, Epoch, InvestmentInfo, Supply, UnbondEpoch, ACCOUNTS, ACCOUNT_UNBONDS, CLAIMS, DENOM,
+    EPOCH, INVESTMENT, PENDING_UNBONDS, TOTAL_POWER, TOTAL_SUPPLY, UNBOND_EPOCHS, VOTING_POWER,
 };
 
 const FALLBACK_RATIO: Decimal = Decimal::one();
@@ -24,155 +30,286 @@ const FALLBACK_RATIO: Decimal = Decimal::one();
 const CONTRACT_NAME: &str = "crates.io:cw20-staking";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-pub fn init<S: Storage, A: Api, Q: Querier>(
-    deps: &mut Extern<S, A, Q>,
+/// Stores written before the derivative moved to a Coreum smart token keep cw20 balances
+/// that this version cannot honour
+const FIRST_MIGRATABLE_VERSION: &str = "0.4.0";
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
     env: Env,
-    msg: InitMsg,
-) -> StdResult<InitResponse> {
-    set_contract_version(&mut deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> CoreumResult<ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if msg.reinvest_incentive >= Decimal::one() {
+        return Err(ContractError::InvalidReinvestIncentive {});
+    }
+    if msg.referral_share > Decimal::one() {
+        return Err(ContractError::InvalidReferralShare {});
+    }
+    // tiers must be strictly ordered so exactly one applies to any bonding time
+    let ordered = msg
+        .exit_tax_tiers
+        .windows(2)
+        .all(|w| w[0].bonded_for < w[1].bonded_for);
+    if !ordered
+        || msg
+            .exit_tax_tiers
+            .iter()
+            .any(|t| t.exit_tax >= Decimal::one())
+    {
+        return Err(ContractError::InvalidExitTaxTiers {});
+    }
 
     // ensure the validator is registered
-    let vals = deps.querier.query_validators()?;
+    let vals = deps.querier.query_all_validators()?;
     if !vals.iter().any(|v| v.address == msg.validator) {
-        return Err(StdError::generic_err(format!(
-            "{} is not in the current validator set",
-            msg.validator
-        )));
+        return Err(ContractError::NotInValidatorSet {
+            validator: msg.validator,
+        });
     }
 
-    // store token info using cw20-base format
-    let data = TokenInfo {
-        name: msg.name,
+    // issue the derivative as a smart token, the contract is its only minter
+    let issue_msg = CoreumMsg::AssetFT(assetft::Msg::Issue {
         symbol: msg.symbol,
-        decimals: msg.decimals,
-        total_supply: Uint128(0),
-        // set self as minter, so we can properly handle mint and burn
-        mint: Some(MinterData {
-            minter: deps.api.canonical_address(&env.contract.address)?,
-            cap: None,
-        }),
-    };
-    token_info(&mut deps.storage).save(&data)?;
+        subunit: msg.subunit.clone(),
+        precision: msg.precision,
+        initial_amount: Uint128::zero(),
+        description: msg.description,
+        features: Some(vec![assetft::MINTING]),
+        burn_rate: "0".into(),
+        send_commission_rate: "0".into(),
+        uri: None,
+        uri_hash: None,
+    });
+    let token_denom = format!("{}-{}", msg.subunit, env.contract.address).to_lowercase();
+    DENOM.save(deps.storage, &token_denom)?;
 
     let denom = deps.querier.query_bonded_denom()?;
     let invest = InvestmentInfo {
-        owner: deps.api.canonical_address(&env.message.sender)?,
+        owner: info.sender,
         exit_tax: msg.exit_tax,
         unbonding_period: msg.unbonding_period,
         bond_denom: denom,
         validator: msg.validator,
         min_withdrawal: msg.min_withdrawal,
+        reinvest_incentive: msg.reinvest_incentive,
+        exit_tax_tiers: msg.exit_tax_tiers,
+        referral_share: msg.referral_share,
     };
-    invest_info(&mut deps.storage).save(&invest)?;
+    INVESTMENT.save(deps.storage, &invest)?;
 
     // set supply to 0
     let supply = Supply::default();
-    total_supply(&mut deps.storage).save(&supply)?;
+    TOTAL_SUPPLY.save(deps.storage, &supply)?;
 
-    Ok(InitResponse::default())
+    let epoch = Epoch {
+        id: 0,
+        period: msg.unbond_epoch,
+        ends: msg.unbond_epoch.after(&env.block),
+        requested: Uint128::zero(),
+    };
+    EPOCH.save(deps.storage, &epoch)?;
+
+    Ok(Response::new()
+        .add_attribute("denom", token_denom)
+        .add_message(issue_msg))
 }
 
-pub fn handle<S: Storage, A: Api, Q: Querier>(
-    deps: &mut Extern<S, A, Q>,
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
     env: Env,
-    msg: HandleMsg,
-) -> StdResult<HandleResponse> {
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> CoreumResult<ContractError> {
     match msg {
-        HandleMsg::Bond {} => bond(deps, env),
-        HandleMsg::Unbond { amount } => unbond(deps, env, amount),
-        HandleMsg::Claim {} => claim(deps, env),
-        HandleMsg::Reinvest {} => reinvest(deps, env),
-        HandleMsg::_BondAllTokens {} => _bond_all_tokens(deps, env),
-
-        // these all come from cw20-base to implement the cw20 standard
-        HandleMsg::Transfer { recipient, amount } => handle_transfer(deps, env, recipient, amount),
-        HandleMsg::Burn { amount } => handle_burn(deps, env, amount),
-        HandleMsg::Send {
-            contract,
-            amount,
-            msg,
-        } => handle_send(deps, env, contract, amount, msg),
-        HandleMsg::IncreaseAllowance {
-            spender,
-            amount,
-            expires,
-        } => handle_increase_allowance(deps, env, spender, amount, expires),
-        HandleMsg::DecreaseAllowance {
-            spender,
-            amount,
-            expires,
-        } => handle_decrease_allowance(deps, env, spender, amount, expires),
-        HandleMsg::TransferFrom {
-            owner,
-            recipient,
-            amount,
-        } => handle_transfer_from(deps, env, owner, recipient, amount),
-        HandleMsg::BurnFrom { owner, amount } => handle_burn_from(deps, env, owner, amount),
-        HandleMsg::SendFrom {
-            owner,
-            contract,
-            amount,
-            msg,
-        } => handle_send_from(deps, env, owner, contract, amount, msg),
+        ExecuteMsg::Bond { referrer } => bond(deps, env, info, referrer),
+        ExecuteMsg::Unbond {} => unbond(deps, env, info),
+        ExecuteMsg::Claim {} => claim(deps, env, info),
+        ExecuteMsg::AdvanceEpoch {} => advance_epoch(deps, env, info),
+        ExecuteMsg::ClaimMatured {} => claim_matured(deps, env, info),
+        ExecuteMsg::Lock {} => lock(deps, env, info),
+        ExecuteMsg::Unlock { amount } => unlock(deps, env, info, amount),
+        ExecuteMsg::Reinvest {} => reinvest(deps, env, info),
+        ExecuteMsg::_BondAllTokens {} => _bond_all_tokens(deps, env, info),
     }
 }
 
 // get_bonded returns the total amount of delegations from contract
 // it ensures they are all the same denom
-fn get_bonded<Q: Querier>(querier: &Q, contract: &HumanAddr) -> StdResult<Uint128> {
+fn get_bonded(querier: &QuerierWrapper, contract: &Addr) -> Result<Uint128, ContractError> {
     let bonds = querier.query_all_delegations(contract)?;
     if bonds.is_empty() {
-        return Ok(Uint128(0));
+        return Ok(Uint128::zero());
     }
     let denom = bonds[0].amount.denom.as_str();
-    bonds.iter().fold(Ok(Uint128(0)), |racc, d| {
+    bonds.iter().fold(Ok(Uint128::zero()), |racc, d| {
         let acc = racc?;
         if d.amount.denom.as_str() != denom {
-            Err(StdError::generic_err(format!(
-                "different denoms in bonds: '{}' vs '{}'",
-                denom, &d.amount.denom
-            )))
+            Err(ContractError::DifferentBondDenom {
+                denom1: denom.into(),
+                denom2: d.amount.denom.to_string(),
+            })
         } else {
             Ok(acc + d.amount.amount)
         }
     })
 }
 
-fn assert_bonds(supply: &Supply, bonded: Uint128) -> StdResult<()> {
-    if supply.bonded != bonded {
-        Err(StdError::generic_err(format!(
-            "Stored bonded {}, but query bonded: {}",
-            supply.bonded, bonded
-        )))
+/// absorb_slashing reconciles the cached bonded and queued amounts with the delegation the
+/// chain reports, which holds both until the current epoch is advanced.
+/// Less delegated than recorded means the validator was slashed: the loss is spread over all
+/// holders by lowering the exchange rate and over the queued unbonds in the same proportion,
+/// and the returned event records it.
+/// More delegated than recorded can't happen through slashing, so it is still an error.
+fn absorb_slashing(
+    supply: &mut Supply,
+    delegated: Uint128,
+) -> Result<Option<Event>, ContractError> {
+    let recorded = supply.bonded + supply.queued;
+    if delegated > recorded {
+        return Err(ContractError::BondedMismatch {
+            stored: recorded,
+            queried: delegated,
+        });
+    }
+    if delegated == recorded {
+        return Ok(None);
+    }
+
+    let queued = supply.queued.multiply_ratio(delegated, recorded);
+    let event = Event::new("slashing_absorbed")
+        .add_attribute("recorded", recorded)
+        .add_attribute("bonded", delegated)
+        .add_attribute("slashed", recorded - delegated)
+        .add_attribute("queued_slashed", supply.queued - queued);
+    supply.queued = queued;
+    supply.bonded = delegated - queued;
+    Ok(Some(event))
+}
+
+/// unsettled_unbonds lists the requests of an address in epochs that were already
+/// undelegated, each worth its share of what the epoch undelegated
+fn unsettled_unbonds(
+    storage: &dyn Storage,
+    addr: &Addr,
+    current_epoch: u64,
+) -> StdResult<Vec<(u64, Claim)>> {
+    ACCOUNT_UNBONDS
+        .prefix(addr)
+        .keys(
+            storage,
+            None,
+            Some(Bound::exclusive(current_epoch)),
+            Order::Ascending,
+        )
+        .map(|id| -> StdResult<_> {
+            let id = id?;
+            let epoch = UNBOND_EPOCHS.load(storage, id)?;
+            let requested = PENDING_UNBONDS.load(storage, (id, addr))?;
+            let claim = Claim {
+                amount: requested.multiply_ratio(epoch.undelegated, epoch.requested),
+                release_at: epoch.release_at,
+            };
+            Ok((id, claim))
+        })
+        .collect()
+}
+
+/// settle_unbonds turns the unsettled requests of an address into claims. It runs before
+/// every unbond and claim of the address, so there are at most a couple of them
+fn settle_unbonds(storage: &mut dyn Storage, addr: &Addr) -> StdResult<()> {
+    let current = EPOCH.load(storage)?.id;
+    for (id, claim) in unsettled_unbonds(storage, addr, current)? {
+        if !claim.amount.is_zero() {
+            CLAIMS.create_claim(storage, addr, claim.amount, claim.release_at)?;
+        }
+        PENDING_UNBONDS.remove(storage, (id, addr));
+        ACCOUNT_UNBONDS.remove(storage, (addr, id));
+    }
+    Ok(())
+}
+
+/// bonded_for is how many seconds the account has been bonded, zero once it has
+/// unbonded everything it was minted
+fn bonded_for(account: Option<&Account>, env: &Env) -> u64 {
+    match account {
+        Some(account) if !account.minted.is_zero() => env
+            .block
+            .time
+            .seconds()
+            .saturating_sub(account.bonded_since.seconds()),
+        _ => 0,
+    }
+}
+
+/// exit_tax_for picks the highest tier reached after being bonded for the given seconds,
+/// falling back to the base exit tax
+fn exit_tax_for(invest: &InvestmentInfo, bonded_for: u64) -> Decimal {
+    invest
+        .exit_tax_tiers
+        .iter()
+        .rev()
+        .find(|t| t.bonded_for <= bonded_for)
+        .map_or(invest.exit_tax, |t| t.exit_tax)
+}
+
+fn exchange_rate(supply: &Supply) -> Decimal {
+    if supply.issued.is_zero() {
+        FALLBACK_RATIO
     } else {
-        Ok(())
+        Decimal::from_ratio(supply.bonded, supply.issued)
     }
 }
 
-pub fn bond<S: Storage, A: Api, Q: Querier>(
-    deps: &mut Extern<S, A, Q>,
+pub fn bond(
+    deps: DepsMut,
     env: Env,
-) -> StdResult<HandleResponse> {
+    info: MessageInfo,
+    referrer: Option<String>,
+) -> CoreumResult<ContractError> {
     // ensure we have the proper denom
-    let invest = invest_info_read(&deps.storage).load()?;
+    let invest = INVESTMENT.load(deps.storage)?;
     // payment finds the proper coin (or throws an error)
-    let payment = env
-        .message
-        .sent_funds
+    let payment = info
+        .funds
         .iter()
         .find(|x| x.denom == invest.bond_denom)
-        .ok_or_else(|| StdError::generic_err(format!("No {} tokens sent", &invest.bond_denom)))?;
+        .ok_or_else(|| ContractError::EmptyBalance {
+            denom: invest.bond_denom.clone(),
+        })?;
+    // the referrer only counts on the first bond of an account
+    let mut account = match ACCOUNTS.may_load(deps.storage, &info.sender)? {
+        Some(account) => account,
+        None => {
+            let referrer = referrer.map(|r| deps.api.addr_validate(&r)).transpose()?;
+            if referrer.as_ref() == Some(&info.sender) {
+                return Err(ContractError::SelfReferral {});
+            }
+            Account {
+                bonded_since: env.block.time,
+                minted: Uint128::zero(),
+                referrer,
+            }
+        }
+    };
 
-    // bonded is the total number of tokens we have delegated from this address
-    let bonded = get_bonded(&deps.querier, &env.contract.address)?;
+    // delegated is the total number of tokens we have delegated from this address
+    let mut supply = TOTAL_SUPPLY.load(deps.storage)?;
+    let delegated = get_bonded(&deps.querier, &env.contract.address)?;
 
     // calculate to_mint and update total supply
-    let mut totals = total_supply(&mut deps.storage);
-    let mut supply = totals.load()?;
-    // TODO: this is just a safety assertion - do we keep it, or remove caching?
-    // in the end supply is just there to cache the (expected) results of get_bonded() so we don't
-    // have expensive queries everywhere
-    assert_bonds(&supply, bonded)?;
+    // supply is there to cache the (expected) results of get_bonded() so we don't
+    // have expensive queries everywhere, bring it up to date with any slashing first
+    let slashing = absorb_slashing(&mut supply, delegated)?;
+    let bonded = supply.bonded;
     let to_mint = if supply.issued.is_zero() || bonded.is_zero() {
         FALLBACK_RATIO * payment.amount
     } else {
@@ -180,304 +317,663 @@ pub fn bond<S: Storage, A: Api, Q: Querier>(
     };
     supply.bonded = bonded + payment.amount;
     supply.issued += to_mint;
-    totals.save(&supply)?;
+    TOTAL_SUPPLY.save(deps.storage, &supply)?;
+
+    // track how long the sender has been bonded, moving the bond time forward in
+    // proportion to what is minted now
+    if !to_mint.is_zero() {
+        let elapsed = bonded_for(Some(&account), &env);
+        let shift = Uint128::from(elapsed).multiply_ratio(to_mint, account.minted + to_mint);
+        account.bonded_since = env.block.time.minus_seconds(elapsed - shift.u128() as u64);
+        account.minted += to_mint;
+    }
+    ACCOUNTS.save(deps.storage, &info.sender, &account)?;
 
-    // call into cw20-base to mint the token, call as self as no one else is allowed
-    let mut sub_env = env.clone();
-    sub_env.message.sender = env.contract.address.clone();
-    handle_mint(deps, sub_env, env.message.sender.clone(), to_mint)?;
+    // mint the derivative straight to the sender
+    let denom = DENOM.load(deps.storage)?;
+    let mint_msg = CoreumMsg::AssetFT(assetft::Msg::Mint {
+        coin: coin(to_mint.u128(), denom),
+        recipient: Some(info.sender.to_string()),
+    });
 
     // bond them to the validator
-    let res = HandleResponse {
-        messages: vec![StakingMsg::Delegate {
+    let res = Response::new()
+        .add_message(mint_msg)
+        .add_message(StakingMsg::Delegate {
             validator: invest.validator,
             amount: payment.clone(),
-        }
-        .into()],
-        log: vec![
-            log("action", "bond"),
-            log("from", env.message.sender),
-            log("bonded", payment.amount),
-            log("minted", to_mint),
-        ],
-        data: None,
-    };
+        })
+        .add_events(slashing)
+        .add_attribute("action", "bond")
+        .add_attribute("from", info.sender)
+        .add_attribute("bonded", payment.amount)
+        .add_attribute("minted", to_mint);
     Ok(res)
 }
 
-pub fn unbond<S: Storage, A: Api, Q: Querier>(
-    deps: &mut Extern<S, A, Q>,
+/// checkpoint the voting power of an address whose locked derivative changed in this block,
+/// moving the total by the same delta
+fn save_voting_power(
+    storage: &mut dyn Storage,
+    addr: &Addr,
+    power: Uint128,
+    height: u64,
+) -> StdResult<()> {
+    let previous = VOTING_POWER.may_load(storage, addr)?.unwrap_or_default();
+    let total = TOTAL_POWER.may_load(storage)?.unwrap_or_default();
+    TOTAL_POWER.save(storage, &(total - previous + power), height)?;
+    VOTING_POWER.save(storage, addr, &power, height)
+}
+
+/// lock takes the derivative sent with the message out of circulation until it is unlocked,
+/// so the same tokens can't back the voting power of two addresses at any height
+pub fn lock(deps: DepsMut, env: Env, info: MessageInfo) -> CoreumResult<ContractError> {
+    let denom = DENOM.load(deps.storage)?;
+    let amount = must_pay(&info, &denom)?;
+    let locked = VOTING_POWER
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default()
+        + amount;
+    save_voting_power(deps.storage, &info.sender, locked, env.block.height)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "lock")
+        .add_attribute("from", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("locked", locked))
+}
+
+pub fn unlock(
+    deps: DepsMut,
     env: Env,
+    info: MessageInfo,
     amount: Uint128,
-) -> StdResult<HandleResponse> {
-    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
+) -> CoreumResult<ContractError> {
+    let denom = DENOM.load(deps.storage)?;
+    let locked = VOTING_POWER
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    let remaining = locked
+        .checked_sub(amount)
+        .map_err(|_| ContractError::InsufficientLocked { locked })?;
+    save_voting_power(deps.storage, &info.sender, remaining, env.block.height)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(amount.u128(), denom)],
+        })
+        .add_attribute("action", "unlock")
+        .add_attribute("to", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("locked", remaining))
+}
 
-    let invest = invest_info_read(&deps.storage).load()?;
+pub fn unbond(deps: DepsMut, env: Env, info: MessageInfo) -> CoreumResult<ContractError> {
+    let invest = INVESTMENT.load(deps.storage)?;
+    let denom = DENOM.load(deps.storage)?;
+    let amount = must_pay(&info, &denom)?;
     // ensure it is big enough to care
     if amount < invest.min_withdrawal {
-        return Err(StdError::generic_err(format!(
-            "Must unbond at least {} {}",
-            invest.min_withdrawal, invest.bond_denom
-        )));
+        return Err(ContractError::UnbondTooSmall {
+            min_bonded: invest.min_withdrawal,
+            denom: invest.bond_denom,
+        });
     }
-    // calculate tax and remainer to unbond
-    let tax = amount * invest.exit_tax;
-
-    // burn from the original caller
-    handle_burn(deps, env.clone(), amount)?;
-    if tax > Uint128(0) {
-        let mut sub_env = env.clone();
-        sub_env.message.sender = env.contract.address.clone();
-        // call into cw20-base to mint tokens to owner, call as self as no one else is allowed
-        let human_owner = deps.api.human_address(&invest.owner)?;
-        handle_mint(deps, sub_env, human_owner, tax)?;
+    // calculate tax and remainer to unbond, the rate drops the longer the sender was bonded.
+    // Only the derivative minted to the sender earned that, anything received from others
+    // pays the base rate
+    let mut account = ACCOUNTS.may_load(deps.storage, &info.sender)?;
+    let exit_tax = exit_tax_for(&invest, bonded_for(account.as_ref(), &env));
+    let minted = account
+        .as_ref()
+        .map_or(Uint128::zero(), |a| a.minted.min(amount));
+    let tax = minted * exit_tax + (amount - minted) * invest.exit_tax;
+    let referrer = account.as_ref().and_then(|a| a.referrer.clone());
+    let referral_cut = match referrer {
+        Some(_) => tax * invest.referral_share,
+        None => Uint128::zero(),
+    };
+    let owner_cut = tax - referral_cut;
+    if let Some(account) = account.as_mut() {
+        account.minted = account.minted.saturating_sub(amount);
+        ACCOUNTS.save(deps.storage, &info.sender, account)?;
     }
 
     // re-calculate bonded to ensure we have real values
-    // bonded is the total number of tokens we have delegated from this address
-    let bonded = get_bonded(&deps.querier, &env.contract.address)?;
+    // delegated is the total number of tokens we have delegated from this address
+    let mut supply = TOTAL_SUPPLY.load(deps.storage)?;
+    let delegated = get_bonded(&deps.querier, &env.contract.address)?;
 
     // calculate how many native tokens this is worth and update supply
-    let remainder = (amount - tax)?;
-    let mut totals = total_supply(&mut deps.storage);
-    let mut supply = totals.load()?;
-    // TODO: this is just a safety assertion - do we keep it, or remove caching?
-    // in the end supply is just there to cache the (expected) results of get_bonded() so we don't
-    // have expensive queries everywhere
-    assert_bonds(&supply, bonded)?;
-    let unbond = remainder.multiply_ratio(bonded, supply.issued);
-    supply.bonded = (bonded - unbond)?;
-    supply.issued = (supply.issued - remainder)?;
-    supply.claims += unbond;
-    totals.save(&supply)?;
-
-    // add a claim to this user to get their tokens after the unbonding period
-    claims(&mut deps.storage).update(sender_raw.as_slice(), |old| {
-        let mut claims = old.unwrap_or_default();
-        claims.push(Claim {
-            amount: unbond,
-            released: invest.unbonding_period.after(&env.block),
+    let remainder = amount.checked_sub(tax)?;
+    // supply is there to cache the (expected) results of get_bonded() so we don't
+    // have expensive queries everywhere, bring it up to date with any slashing first
+    let slashing = absorb_slashing(&mut supply, delegated)?;
+    let unbond = remainder.multiply_ratio(supply.bonded, supply.issued);
+    supply.bonded = supply.bonded.checked_sub(unbond)?;
+    supply.issued = supply.issued.checked_sub(remainder)?;
+    supply.queued += unbond;
+    TOTAL_SUPPLY.save(deps.storage, &supply)?;
+
+    // queue the request, it becomes a claim once the epoch is undelegated
+    settle_unbonds(deps.storage, &info.sender)?;
+    let mut epoch = EPOCH.load(deps.storage)?;
+    PENDING_UNBONDS.update(
+        deps.storage,
+        (epoch.id, &info.sender),
+        |queued| -> StdResult<_> { Ok(queued.unwrap_or_default() + unbond) },
+    )?;
+    ACCOUNT_UNBONDS.save(deps.storage, (&info.sender, epoch.id), &Empty {})?;
+    epoch.requested += unbond;
+    EPOCH.save(deps.storage, &epoch)?;
+
+    // burn what was sent, except the tax which is passed on to the owner and referrer
+    let mut res = Response::new().add_message(CoreumMsg::AssetFT(assetft::Msg::Burn {
+        coin: coin(remainder.u128(), &denom),
+    }));
+    if !owner_cut.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: invest.owner.to_string(),
+            amount: vec![coin(owner_cut.u128(), &denom)],
         });
-        Ok(claims)
-    })?;
+    }
+    if let Some(referrer) = referrer.filter(|_| !referral_cut.is_zero()) {
+        res = res.add_message(BankMsg::Send {
+            to_address: referrer.to_string(),
+            amount: vec![coin(referral_cut.u128(), &denom)],
+        });
+    }
 
-    // unbond them
-    let res = HandleResponse {
-        messages: vec![StakingMsg::Undelegate {
-            validator: invest.validator,
-            amount: coin(unbond.u128(), &invest.bond_denom),
-        }
-        .into()],
-        log: vec![
-            log("action", "unbond"),
-            log("to", env.message.sender),
-            log("unbonded", unbond),
-            log("burnt", amount),
-        ],
-        data: None,
-    };
+    let res = res
+        .add_events(slashing)
+        .add_attribute("action", "unbond")
+        .add_attribute("to", info.sender)
+        .add_attribute("epoch", epoch.id.to_string())
+        .add_attribute("unbonded", unbond)
+        .add_attribute("burnt", amount)
+        .add_attribute("exit_tax", exit_tax.to_string())
+        .add_attribute("referral", referral_cut);
     Ok(res)
 }
 
-pub fn claim<S: Storage, A: Api, Q: Querier>(
-    deps: &mut Extern<S, A, Q>,
-    env: Env,
-) -> StdResult<HandleResponse> {
+/// advance_epoch undelegates all unbonds queued in the current epoch in a single message,
+/// so the chain's limit on concurrent undelegations is hit per epoch rather than per user.
+/// The requests are not touched here, each becomes a claim when its sender is back
+pub fn advance_epoch(deps: DepsMut, env: Env, _info: MessageInfo) -> CoreumResult<ContractError> {
+    let invest = INVESTMENT.load(deps.storage)?;
+    let mut epoch = EPOCH.load(deps.storage)?;
+    if !epoch.ends.is_expired(&env.block) {
+        return Err(ContractError::EpochNotEnded {
+            epoch: epoch.id,
+            ends: epoch.ends,
+        });
+    }
+
+    // the queued tokens take their share of any slashing up to now
+    let mut supply = TOTAL_SUPPLY.load(deps.storage)?;
+    let delegated = get_bonded(&deps.querier, &env.contract.address)?;
+    let slashing = absorb_slashing(&mut supply, delegated)?;
+    let total = supply.queued;
+    supply.queued = Uint128::zero();
+    supply.claims += total;
+    TOTAL_SUPPLY.save(deps.storage, &supply)?;
+
+    // every request becomes a claim maturing one unbonding period from now
+    if !epoch.requested.is_zero() {
+        let unbond_epoch = UnbondEpoch {
+            requested: epoch.requested,
+            undelegated: total,
+            release_at: invest.unbonding_period.after(&env.block),
+        };
+        UNBOND_EPOCHS.save(deps.storage, epoch.id, &unbond_epoch)?;
+    }
+
+    let advanced = epoch.id;
+    let requested = epoch.requested;
+    epoch.id += 1;
+    epoch.ends = epoch.period.after(&env.block);
+    epoch.requested = Uint128::zero();
+    EPOCH.save(deps.storage, &epoch)?;
+
+    let mut res = Response::new();
+    if !total.is_zero() {
+        res = res.add_message(StakingMsg::Undelegate {
+            validator: invest.validator,
+            amount: coin(total.u128(), &invest.bond_denom),
+        });
+    }
+    Ok(res
+        .add_events(slashing)
+        .add_attribute("action", "advance_epoch")
+        .add_attribute("epoch", advanced.to_string())
+        .add_attribute("requested", requested)
+        .add_attribute("undelegated", total))
+}
+
+pub fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> CoreumResult<ContractError> {
     // find how many tokens the contract has
-    let invest = invest_info_read(&deps.storage).load()?;
+    let invest = INVESTMENT.load(deps.storage)?;
     let mut balance = deps
         .querier
         .query_balance(&env.contract.address, &invest.bond_denom)?;
     if balance.amount < invest.min_withdrawal {
-        return Err(StdError::generic_err(
-            "Insufficient balance in contract to process claim",
-        ));
+        return Err(ContractError::BalanceTooSmall {});
     }
+    settle_unbonds(deps.storage, &info.sender)?;
 
     // check how much to send - min(balance, claims[sender]), and reduce the claim
-    let sender_raw = deps.api.canonical_address(&env.message.sender)?;
-    // Ensure we have enough balance to cover this and
-    // only send some claims if that is all we can cover
-    let cap = balance.amount;
-    let mut to_send = Uint128(0);
-    claims(&mut deps.storage).update(sender_raw.as_slice(), |claim| {
-        let (_send, waiting): (Vec<_>, _) =
-            claim.unwrap_or_default().iter().cloned().partition(|c| {
-                // if mature and we can pay, then include in _send
-                if c.released.is_expired(&env.block) && to_send + c.amount <= cap {
-                    to_send += c.amount;
-                    true
-                } else {
-                    // not to send, leave in waiting and save again
-                    false
-                }
-            });
-        Ok(waiting)
-    })?;
-
-    if to_send == Uint128(0) {
-        return Err(StdError::generic_err("No claims to release"));
+    // Ensure we have enough balance to cover this and only send some claims if that is all we can cover
+    let to_send =
+        CLAIMS.claim_tokens(deps.storage, &info.sender, &env.block, Some(balance.amount))?;
+    if to_send == Uint128::zero() {
+        return Err(ContractError::NothingToClaim {});
     }
 
     // update total supply (lower claim)
-    total_supply(&mut deps.storage).update(|mut supply| {
-        supply.claims = (supply.claims - to_send)?;
+    TOTAL_SUPPLY.update(deps.storage, |mut supply| -> StdResult<_> {
+        supply.claims = supply.claims.checked_sub(to_send)?;
         Ok(supply)
     })?;
 
     // transfer tokens to the sender
     balance.amount = to_send;
-    let res = HandleResponse {
-        messages: vec![BankMsg::Send {
-            from_address: env.contract.address,
-            to_address: env.message.sender.clone(),
+    let res = Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
             amount: vec![balance],
-        }
-        .into()],
-        log: vec![
-            log("action", "claim"),
-            log("from", env.message.sender),
-            log("amount", to_send),
-        ],
-        data: None,
-    };
+        })
+        .add_attribute("action", "claim")
+        .add_attribute("from", info.sender)
+        .add_attribute("amount", to_send);
     Ok(res)
 }
 
-/// reinvest will withdraw all pending rewards,
-/// then issue a callback to itself via _bond_all_tokens
-/// to reinvest the new earnings (and anything else that accumulated)
-pub fn reinvest<S: Storage, A: Api, Q: Querier>(
-    deps: &mut Extern<S, A, Q>,
+/// claim_matured releases all matured claims of the sender at once, rather than as many
+/// as the current balance happens to cover
+pub fn claim_matured(
+    deps: DepsMut,
     env: Env,
-) -> StdResult<HandleResponse> {
-    let contract_addr = env.contract.address;
-    let invest = invest_info_read(&deps.storage).load()?;
-    let msg = to_binary(&HandleMsg::_BondAllTokens {})?;
+    info: MessageInfo,
+) -> CoreumResult<ContractError> {
+    let invest = INVESTMENT.load(deps.storage)?;
+    settle_unbonds(deps.storage, &info.sender)?;
+    let claims = query_claims(deps.as_ref(), env.clone(), info.sender.to_string())?;
+    if claims.matured_amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &invest.bond_denom)?;
+    if balance.amount < claims.matured_amount {
+        return Err(ContractError::BalanceTooSmall {});
+    }
 
-    // and bond them to the validator
-    let res = HandleResponse {
-        messages: vec![
-            StakingMsg::Withdraw {
-                validator: invest.validator,
-                recipient: Some(contract_addr.clone()),
-            }
-            .into(),
-            WasmMsg::Execute {
-                contract_addr,
-                msg,
-                send: vec![],
-            }
-            .into(),
-        ],
-        log: vec![],
-        data: None,
-    };
+    let to_send = CLAIMS.claim_tokens(deps.storage, &info.sender, &env.block, None)?;
+    TOTAL_SUPPLY.update(deps.storage, |mut supply| -> StdResult<_> {
+        supply.claims = supply.claims.checked_sub(to_send)?;
+        Ok(supply)
+    })?;
+
+    let res = Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(to_send.u128(), &invest.bond_denom)],
+        })
+        .add_attribute("action", "claim_matured")
+        .add_attribute("from", info.sender)
+        .add_attribute("claims", claims.matured.len().to_string())
+        .add_attribute("amount", to_send);
+    Ok(res)
+}
+
+/// reinvest will withdraw all pending rewards, mint the owner's exit tax on them,
+/// pay the caller their incentive, then issue a callback to itself via _bond_all_tokens
+/// to reinvest the new earnings (and anything else that accumulated)
+pub fn reinvest(deps: DepsMut, env: Env, info: MessageInfo) -> CoreumResult<ContractError> {
+    let invest = INVESTMENT.load(deps.storage)?;
+
+    // only pull rewards out when it is worth the staking tx
+    let rewards: Uint128 = deps
+        .querier
+        .query_delegation(&env.contract.address, &invest.validator)?
+        .map(|d| {
+            d.accumulated_rewards
+                .iter()
+                .filter(|c| c.denom == invest.bond_denom)
+                .map(|c| c.amount)
+                .sum()
+        })
+        .unwrap_or_default();
+    if rewards < invest.min_withdrawal {
+        return Err(ContractError::NothingToReinvest {
+            min_withdrawal: invest.min_withdrawal,
+            denom: invest.bond_denom,
+        });
+    }
+    let incentive = rewards * invest.reinvest_incentive;
+
+    // the owner's cut is issued as derivative tokens at the price before the rewards are bonded,
+    // so the remainder of the rewards raises the value of every token including those
+    let tax = rewards.checked_sub(incentive)? * invest.exit_tax;
+    let mut to_mint = Uint128::zero();
+    let mut res = Response::new().add_message(DistributionMsg::WithdrawDelegatorReward {
+        validator: invest.validator,
+    });
+    if !tax.is_zero() {
+        let mut supply = TOTAL_SUPPLY.load(deps.storage)?;
+        let delegated = get_bonded(&deps.querier, &env.contract.address)?;
+        res = res.add_events(absorb_slashing(&mut supply, delegated)?);
+        to_mint = if supply.issued.is_zero() || supply.bonded.is_zero() {
+            FALLBACK_RATIO * tax
+        } else {
+            tax.multiply_ratio(supply.issued, supply.bonded)
+        };
+        supply.issued += to_mint;
+        TOTAL_SUPPLY.save(deps.storage, &supply)?;
+
+        let denom = DENOM.load(deps.storage)?;
+        res = res.add_message(CoreumMsg::AssetFT(assetft::Msg::Mint {
+            coin: coin(to_mint.u128(), denom),
+            recipient: Some(invest.owner.to_string()),
+        }));
+    }
+    if !incentive.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(incentive.u128(), &invest.bond_denom)],
+        });
+    }
+
+    // and bond the rest to the validator
+    let msg = to_binary(&ExecuteMsg::_BondAllTokens {})?;
+    let res = res
+        .add_message(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg,
+            funds: vec![],
+        })
+        .add_attribute("action", "reinvest")
+        .add_attribute("caller", info.sender)
+        .add_attribute("rewards", rewards)
+        .add_attribute("incentive", incentive)
+        .add_attribute("owner_minted", to_mint);
     Ok(res)
 }
 
-pub fn _bond_all_tokens<S: Storage, A: Api, Q: Querier>(
-    deps: &mut Extern<S, A, Q>,
+pub fn _bond_all_tokens(
+    deps: DepsMut,
     env: Env,
-) -> StdResult<HandleResponse> {
+    info: MessageInfo,
+) -> CoreumResult<ContractError> {
     // this is just meant as a call-back to ourself
-    if env.message.sender != env.contract.address {
-        return Err(StdError::unauthorized());
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
     }
 
     // find how many tokens we have to bond
-    let invest = invest_info_read(&deps.storage).load()?;
+    let invest = INVESTMENT.load(deps.storage)?;
     let mut balance = deps
         .querier
         .query_balance(&env.contract.address, &invest.bond_denom)?;
 
     // we deduct pending claims from our account balance before reinvesting.
     // if there is not enough funds, we just return a no-op
-    match total_supply(&mut deps.storage).update(|mut supply| {
-        balance.amount = (balance.amount - supply.claims)?;
+    match TOTAL_SUPPLY.update(deps.storage, |mut supply| -> StdResult<_> {
+        balance.amount = balance.amount.checked_sub(supply.claims)?;
         // this just triggers the "no op" case if we don't have min_withdrawal left to reinvest
-        (balance.amount - invest.min_withdrawal)?;
+        balance.amount.checked_sub(invest.min_withdrawal)?;
         supply.bonded += balance.amount;
         Ok(supply)
     }) {
         Ok(_) => {}
         // if it is below the minimum, we do a no-op (do not revert other state from withdrawal)
-        Err(StdError::Underflow { .. }) => return Ok(HandleResponse::default()),
-        Err(e) => return Err(e),
+        Err(StdError::Overflow { .. }) => return Ok(Response::default()),
+        Err(e) => return Err(ContractError::Std(e)),
     }
 
     // and bond them to the validator
-    let res = HandleResponse {
-        messages: vec![StakingMsg::Delegate {
+    let res = Response::new()
+        .add_message(StakingMsg::Delegate {
             validator: invest.validator,
             amount: balance.clone(),
-        }
-        .into()],
-        log: vec![log("action", "reinvest"), log("bonded", balance.amount)],
-        data: None,
-    };
+        })
+        .add_attribute("action", "reinvest")
+        .add_attribute("bonded", balance.amount);
     Ok(res)
 }
 
-pub fn query<S: Storage, A: Api, Q: Querier>(
-    deps: &Extern<S, A, Q>,
-    msg: QueryMsg,
-) -> StdResult<Binary> {
+/// Only stores written by this version of the contract layout (0.3.0 and later) can be migrated;
+/// older deployments used cosmwasm-storage buckets and must be redeployed
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> CoreumResult<ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::InvalidContract {
+            contract: stored.contract,
+        });
+    }
+
+    let version: Version = stored.version.parse().map_err(|_| {
+        ContractError::InvalidMigrationVersion {
+            version: stored.version.clone(),
+        }
+    })?;
+    let first: Version = FIRST_MIGRATABLE_VERSION.parse().unwrap();
+    let current: Version = CONTRACT_VERSION.parse().unwrap();
+    if version < first || version > current {
+        return Err(ContractError::InvalidMigrationVersion {
+            version: stored.version,
+        });
+    }
+
+    // stores written before unbonds were batched start with their first epoch
+    if EPOCH.may_load(deps.storage)?.is_none() {
+        let period = msg.unbond_epoch.unwrap_or(Duration::Time(0));
+        let epoch = Epoch {
+            id: 0,
+            period,
+            ends: period.after(&env.block),
+            requested: Uint128::zero(),
+        };
+        EPOCH.save(deps.storage, &epoch)?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         // custom queries
-        QueryMsg::Claims { address } => to_binary(&query_claims(deps, address)?),
+        QueryMsg::Claims { address } => to_binary(&query_claims(deps, env, address)?),
         QueryMsg::Investment {} => to_binary(&query_investment(deps)?),
-        // inherited from cw20-base
-        QueryMsg::TokenInfo {} => to_binary(&query_token_info(deps)?),
-        QueryMsg::Balance { address } => to_binary(&query_balance(deps, address)?),
-        QueryMsg::Allowance { owner, spender } => {
-            to_binary(&query_allowance(deps, owner, spender)?)
+        QueryMsg::ExchangeRate {} => to_binary(&query_exchange_rate(deps, env)?),
+        QueryMsg::PendingUnbonds {
+            epoch,
+            start_after,
+            limit,
+        } => to_binary(&query_pending_unbonds(deps, epoch, start_after, limit)?),
+        QueryMsg::AccountTier { address } => to_binary(&query_account_tier(deps, env, address)?),
+        QueryMsg::VotingPowerAt { address, height } => {
+            to_binary(&query_voting_power_at(deps, env, address, height)?)
         }
+        QueryMsg::TotalPowerAt { height } => to_binary(&query_total_power_at(deps, env, height)?),
     }
 }
 
-pub fn query_claims<S: Storage, A: Api, Q: Querier>(
-    deps: &Extern<S, A, Q>,
-    address: HumanAddr,
-) -> StdResult<ClaimsResponse> {
-    let address_raw = deps.api.canonical_address(&address)?;
-    let claims = claims_read(&deps.storage)
-        .may_load(address_raw.as_slice())?
-        .unwrap_or_default();
-    Ok(ClaimsResponse { claims })
+pub fn query_claims(deps: Deps, env: Env, address: String) -> StdResult<ClaimsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    // requests of advanced epochs count as the claims they become
+    let current = EPOCH.load(deps.storage)?.id;
+    let unsettled = unsettled_unbonds(deps.storage, &address, current)?
+        .into_iter()
+        .map(|(_, claim)| claim)
+        .filter(|claim| !claim.amount.is_zero());
+    let (matured, pending): (Vec<_>, Vec<_>) = CLAIMS
+        .query_claims(deps, &address)?
+        .claims
+        .into_iter()
+        .chain(unsettled)
+        .partition(|c| c.release_at.is_expired(&env.block));
+    let matured_amount = matured.iter().map(|c| c.amount).sum();
+    Ok(ClaimsResponse {
+        matured,
+        matured_amount,
+        pending,
+    })
 }
 
-pub fn query_investment<S: Storage, A: Api, Q: Querier>(
-    deps: &Extern<S, A, Q>,
-) -> StdResult<InvestmentResponse> {
-    let invest = invest_info_read(&deps.storage).load()?;
-    let supply = total_supply_read(&deps.storage).load()?;
+pub fn query_investment(deps: Deps) -> StdResult<InvestmentResponse> {
+    let invest = INVESTMENT.load(deps.storage)?;
+    let supply = TOTAL_SUPPLY.load(deps.storage)?;
 
     let res = InvestmentResponse {
-        owner: deps.api.human_address(&invest.owner)?,
+        denom: DENOM.load(deps.storage)?,
+        owner: invest.owner.to_string(),
         exit_tax: invest.exit_tax,
         validator: invest.validator,
         min_withdrawal: invest.min_withdrawal,
+        reinvest_incentive: invest.reinvest_incentive,
+        exit_tax_tiers: invest.exit_tax_tiers,
+        referral_share: invest.referral_share,
         token_supply: supply.issued,
         staked_tokens: coin(supply.bonded.u128(), &invest.bond_denom),
-        nominal_value: if supply.issued.is_zero() {
-            FALLBACK_RATIO
-        } else {
-            Decimal::from_ratio(supply.bonded, supply.issued)
-        },
+        nominal_value: exchange_rate(&supply),
     };
     Ok(res)
 }
 
+pub fn query_pending_unbonds(
+    deps: Deps,
+    epoch: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PendingUnbondsResponse> {
+    let current = EPOCH.load(deps.storage)?;
+    let (total, undelegated) = if epoch == current.id {
+        (current.requested, None)
+    } else {
+        UNBOND_EPOCHS
+            .may_load(deps.storage, epoch)?
+            .map_or((Uint128::zero(), None), |e| (e.requested, Some(e.undelegated)))
+    };
+    let start = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let unbonds = PENDING_UNBONDS
+        .prefix(epoch)
+        .range(
+            deps.storage,
+            start.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            item.map(|(addr, amount)| PendingUnbond {
+                address: addr.to_string(),
+                amount,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PendingUnbondsResponse {
+        epoch,
+        total,
+        undelegated,
+        unbonds,
+        current_epoch: current.id,
+        current_epoch_ends: current.ends,
+    })
+}
+
+pub fn query_account_tier(deps: Deps, env: Env, address: String) -> StdResult<AccountTierResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let invest = INVESTMENT.load(deps.storage)?;
+    let account = ACCOUNTS.may_load(deps.storage, &address)?;
+    let bonded_for = bonded_for(account.as_ref(), &env);
+    let next_tier = invest
+        .exit_tax_tiers
+        .iter()
+        .find(|t| t.bonded_for > bonded_for)
+        .cloned();
+
+    Ok(AccountTierResponse {
+        bonded_since: account
+            .as_ref()
+            .filter(|a| !a.minted.is_zero())
+            .map(|a| a.bonded_since),
+        bonded_for,
+        exit_tax: exit_tax_for(&invest, bonded_for),
+        next_tier,
+        referrer: account.and_then(|a| a.referrer).map(|r| r.to_string()),
+    })
+}
+
+pub fn query_voting_power_at(
+    deps: Deps,
+    env: Env,
+    address: String,
+    height: Option<u64>,
+) -> StdResult<VotingPowerResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let height = height.unwrap_or(env.block.height);
+    let power = VOTING_POWER
+        .may_load_at_height(deps.storage, &address, height)?
+        .unwrap_or_default();
+    Ok(VotingPowerResponse { power, height })
+}
+
+pub fn query_total_power_at(
+    deps: Deps,
+    env: Env,
+    height: Option<u64>,
+) -> StdResult<TotalPowerResponse> {
+    let height = height.unwrap_or(env.block.height);
+    let power = TOTAL_POWER
+        .may_load_at_height(deps.storage, height)?
+        .unwrap_or_default();
+    Ok(TotalPowerResponse { power, height })
+}
+
+pub fn query_exchange_rate(deps: Deps, env: Env) -> StdResult<ExchangeRateResponse> {
+    let mut supply = TOTAL_SUPPLY.load(deps.storage)?;
+    let delegated = get_bonded(&deps.querier, &env.contract.address)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    // report slashing the next bond or unbond will absorb
+    let unabsorbed_slashing = (supply.bonded + supply.queued).saturating_sub(delegated);
+    if !unabsorbed_slashing.is_zero() {
+        absorb_slashing(&mut supply, delegated)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+    }
+
+    Ok(ExchangeRateResponse {
+        exchange_rate: exchange_rate(&supply),
+        bonded: supply.bonded,
+        issued: supply.issued,
+        unabsorbed_slashing,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, MockQuerier, MOCK_CONTRACT_ADDR};
+    use crate::state::ExitTaxTier;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockQuerier, MOCK_CONTRACT_ADDR,
+    };
     use cosmwasm_std::{coins, Coin, CosmosMsg, Decimal, FullDelegation, Validator};
-    use cw0::{Duration, DAY, HOUR, WEEK};
+    use cw_controllers::Claim;
+    use cw_utils::{Duration, PaymentError, DAY, HOUR, WEEK};
     use std::str::FromStr;
 
-    fn sample_validator<U: Into<HumanAddr>>(addr: U) -> Validator {
+    fn sample_validator(addr: &str) -> Validator {
         Validator {
             address: addr.into(),
             commission: Decimal::percent(3),
@@ -486,12 +982,12 @@ mod tests {
         }
     }
 
-    fn sample_delegation<U: Into<HumanAddr>>(addr: U, amount: Coin) -> FullDelegation {
+    fn sample_delegation(addr: &str, amount: Coin, rewards: u128) -> FullDelegation {
         let can_redelegate = amount.clone();
-        let accumulated_rewards = coin(0, &amount.denom);
+        let accumulated_rewards = coins(rewards, &amount.denom);
         FullDelegation {
             validator: addr.into(),
-            delegator: HumanAddr::from(MOCK_CONTRACT_ADDR),
+            delegator: Addr::unchecked(MOCK_CONTRACT_ADDR),
             amount,
             can_redelegate,
             accumulated_rewards,
@@ -506,7 +1002,19 @@ mod tests {
         querier.update_staking(
             "ustake",
             &[sample_validator(DEFAULT_VALIDATOR)],
-            &[sample_delegation(DEFAULT_VALIDATOR, coin(amount, denom))],
+            &[sample_delegation(DEFAULT_VALIDATOR, coin(amount, denom), 0)],
+        );
+    }
+
+    fn set_rewards(querier: &mut MockQuerier, amount: u128, rewards: u128) {
+        querier.update_staking(
+            "ustake",
+            &[sample_validator(DEFAULT_VALIDATOR)],
+            &[sample_delegation(
+                DEFAULT_VALIDATOR,
+                coin(amount, "ustake"),
+                rewards,
+            )],
         );
     }
 
@@ -517,69 +1025,88 @@ mod tests {
             _ => panic!("Must provide duration in time"),
         };
         let mut res = env.clone();
-        res.block.time += time_delta;
+        res.block.time = res.block.time.plus_seconds(time_delta);
         res
     }
 
     const DEFAULT_VALIDATOR: &str = "default-validator";
+    const DRV: &str = "udrv-cosmos2contract";
 
-    fn default_init(tax_percent: u64, min_withdrawal: u128) -> InitMsg {
-        InitMsg {
-            name: "Cool Derivative".to_string(),
+    fn default_instantiate(tax_percent: u64, min_withdrawal: u128) -> InstantiateMsg {
+        InstantiateMsg {
             symbol: "DRV".to_string(),
-            decimals: 9,
-            validator: HumanAddr::from(DEFAULT_VALIDATOR),
+            subunit: "udrv".to_string(),
+            precision: 9,
+            description: None,
+            validator: String::from(DEFAULT_VALIDATOR),
             unbonding_period: DAY * 3,
+            // every unbond can be undelegated right away unless a test says otherwise
+            unbond_epoch: Duration::Time(0),
             exit_tax: Decimal::percent(tax_percent),
-            min_withdrawal: Uint128(min_withdrawal),
+            min_withdrawal: Uint128::new(min_withdrawal),
+            reinvest_incentive: Decimal::percent(1),
+            exit_tax_tiers: vec![],
+            referral_share: Decimal::zero(),
         }
     }
 
-    fn get_balance<S: Storage, A: Api, Q: Querier, U: Into<HumanAddr>>(
-        deps: &Extern<S, A, Q>,
-        addr: U,
-    ) -> Uint128 {
-        query_balance(&deps, addr.into()).unwrap().balance
+    fn mint_msg(amount: u128, recipient: &str) -> CosmosMsg<CoreumMsg> {
+        CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Mint {
+            coin: coin(amount, DRV),
+            recipient: Some(recipient.to_string()),
+        }))
     }
 
-    fn get_claims<S: Storage, A: Api, Q: Querier, U: Into<HumanAddr>>(
-        deps: &Extern<S, A, Q>,
-        addr: U,
-    ) -> Vec<Claim> {
-        query_claims(&deps, addr.into()).unwrap().claims
+    fn advance_epoch(deps: DepsMut, env: Env) -> Response<CoreumMsg> {
+        let info = mock_info("cranker", &[]);
+        execute(deps, env, info, ExecuteMsg::AdvanceEpoch {}).unwrap()
+    }
+
+    fn get_claims<U: Into<String>>(deps: Deps, addr: U) -> Vec<Claim> {
+        let res = query_claims(deps, mock_env(), addr.into()).unwrap();
+        res.matured.into_iter().chain(res.pending).collect()
     }
 
     #[test]
     fn initialization_with_missing_validator() {
-        let mut deps = mock_dependencies(20, &[]);
+        let mut deps = mock_dependencies();
         deps.querier
             .update_staking("ustake", &[sample_validator("john")], &[]);
 
-        let creator = HumanAddr::from("creator");
-        let msg = InitMsg {
-            name: "Cool Derivative".to_string(),
+        let creator = String::from("creator");
+        let msg = InstantiateMsg {
             symbol: "DRV".to_string(),
-            decimals: 9,
-            validator: HumanAddr::from("my-validator"),
+            subunit: "udrv".to_string(),
+            precision: 9,
+            description: None,
+            validator: String::from("my-validator"),
             unbonding_period: WEEK,
+            unbond_epoch: DAY,
             exit_tax: Decimal::percent(2),
-            min_withdrawal: Uint128(50),
+            min_withdrawal: Uint128::new(50),
+            reinvest_incentive: Decimal::percent(1),
+            exit_tax_tiers: vec![],
+            referral_share: Decimal::zero(),
         };
-        let env = mock_env(&creator, &[]);
+        let info = mock_info(&creator, &[]);
 
-        // make sure we can init with this
-        let res = init(&mut deps, env, msg.clone());
-        match res.unwrap_err() {
-            StdError::GenericErr { msg, .. } => {
-                assert_eq!(msg, "my-validator is not in the current validator set")
+        // make sure we can instantiate with this
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "my-validator is not in the current validator set"
+        );
+        match err {
+            ContractError::NotInValidatorSet { validator } => {
+                assert_eq!(validator, "my-validator")
             }
-            _ => panic!("expected unregistered validator error"),
+            e => panic!("expected unregistered validator error, got: {:?}", e),
         }
     }
 
     #[test]
     fn proper_initialization() {
-        let mut deps = mock_dependencies(20, &[]);
+        let mut deps = mock_dependencies();
         deps.querier.update_staking(
             "ustake",
             &[
@@ -590,68 +1117,87 @@ mod tests {
             &[],
         );
 
-        let creator = HumanAddr::from("creator");
-        let msg = InitMsg {
-            name: "Cool Derivative".to_string(),
+        let creator = String::from("creator");
+        let msg = InstantiateMsg {
             symbol: "DRV".to_string(),
-            decimals: 0,
-            validator: HumanAddr::from("my-validator"),
+            subunit: "udrv".to_string(),
+            precision: 0,
+            description: Some("Cool Derivative".to_string()),
+            validator: String::from("my-validator"),
             unbonding_period: HOUR * 12,
+            unbond_epoch: HOUR,
             exit_tax: Decimal::percent(2),
-            min_withdrawal: Uint128(50),
+            min_withdrawal: Uint128::new(50),
+            reinvest_incentive: Decimal::percent(1),
+            exit_tax_tiers: vec![],
+            referral_share: Decimal::zero(),
         };
-        let env = mock_env(&creator, &[]);
+        let info = mock_info(&creator, &[]);
 
-        // make sure we can init with this
-        let res = init(&mut deps, env, msg.clone()).unwrap();
-        assert_eq!(0, res.messages.len());
+        // make sure we can instantiate with this
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+        assert_eq!(1, res.messages.len());
 
-        // token info is proper
-        let token = query_token_info(&deps).unwrap();
-        assert_eq!(&token.name, &msg.name);
-        assert_eq!(&token.symbol, &msg.symbol);
-        assert_eq!(token.decimals, msg.decimals);
-        assert_eq!(token.total_supply, Uint128(0));
+        // the derivative is issued as a smart token only the contract can mint
+        match &res.messages[0].msg {
+            CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Issue {
+                symbol,
+                subunit,
+                precision,
+                initial_amount,
+                features,
+                ..
+            })) => {
+                assert_eq!(symbol, &msg.symbol);
+                assert_eq!(subunit, &msg.subunit);
+                assert_eq!(*precision, msg.precision);
+                assert_eq!(*initial_amount, Uint128::zero());
+                assert_eq!(features, &Some(vec![assetft::MINTING]));
+            }
+            m => panic!("Unexpected message: {:?}", m),
+        }
 
-        // no balance
-        assert_eq!(get_balance(&deps, &creator), Uint128(0));
         // no claims
-        assert_eq!(get_claims(&deps, &creator), vec![]);
+        assert_eq!(get_claims(deps.as_ref(), &creator), vec![]);
 
         // investment info correct
-        let invest = query_investment(&deps).unwrap();
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.denom, DRV);
         assert_eq!(&invest.owner, &creator);
         assert_eq!(&invest.validator, &msg.validator);
         assert_eq!(invest.exit_tax, msg.exit_tax);
         assert_eq!(invest.min_withdrawal, msg.min_withdrawal);
+        assert_eq!(invest.reinvest_incentive, msg.reinvest_incentive);
 
-        assert_eq!(invest.token_supply, Uint128(0));
+        assert_eq!(invest.token_supply, Uint128::zero());
         assert_eq!(invest.staked_tokens, coin(0, "ustake"));
         assert_eq!(invest.nominal_value, Decimal::one());
     }
 
     #[test]
     fn bonding_issues_tokens() {
-        let mut deps = mock_dependencies(20, &[]);
+        let mut deps = mock_dependencies();
         set_validator(&mut deps.querier);
 
-        let creator = HumanAddr::from("creator");
-        let init_msg = default_init(2, 50);
-        let env = mock_env(&creator, &[]);
+        let creator = String::from("creator");
+        let instantiate_msg = default_instantiate(2, 50);
+        let info = mock_info(&creator, &[]);
 
-        // make sure we can init with this
-        let res = init(&mut deps, env, init_msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        // make sure we can instantiate with this
+        let res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+        assert_eq!(1, res.messages.len());
 
         // let's bond some tokens now
-        let bob = HumanAddr::from("bob");
-        let bond_msg = HandleMsg::Bond {};
-        let env = mock_env(&bob, &[coin(10, "random"), coin(1000, "ustake")]);
+        let bob = String::from("bob");
+        let bond_msg = ExecuteMsg::Bond { referrer: None };
+        let info = mock_info(&bob, &[coin(10, "random"), coin(1000, "ustake")]);
 
         // try to bond and make sure we trigger delegation
-        let res = handle(&mut deps, env, bond_msg).unwrap();
-        assert_eq!(1, res.messages.len());
-        let delegate = &res.messages[0];
+        let res = execute(deps.as_mut(), mock_env(), info, bond_msg).unwrap();
+        assert_eq!(2, res.messages.len());
+        // bob got 1000 DRV for 1000 stake at a 1.0 ratio
+        assert_eq!(res.messages[0].msg, mint_msg(1000, &bob));
+        let delegate = &res.messages[1].msg;
         match delegate {
             CosmosMsg::Staking(StakingMsg::Delegate { validator, amount }) => {
                 assert_eq!(validator.as_str(), DEFAULT_VALIDATOR);
@@ -660,166 +1206,301 @@ mod tests {
             _ => panic!("Unexpected message: {:?}", delegate),
         }
 
-        // bob got 1000 DRV for 1000 stake at a 1.0 ratio
-        assert_eq!(get_balance(&deps, &bob), Uint128(1000));
-
         // investment info correct (updated supply)
-        let invest = query_investment(&deps).unwrap();
-        assert_eq!(invest.token_supply, Uint128(1000));
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.token_supply, Uint128::new(1000));
         assert_eq!(invest.staked_tokens, coin(1000, "ustake"));
         assert_eq!(invest.nominal_value, Decimal::one());
-
-        // token info also properly updated
-        let token = query_token_info(&deps).unwrap();
-        assert_eq!(token.total_supply, Uint128(1000));
     }
 
     #[test]
     fn rebonding_changes_pricing() {
-        let mut deps = mock_dependencies(20, &[]);
+        let mut deps = mock_dependencies();
         set_validator(&mut deps.querier);
 
-        let creator = HumanAddr::from("creator");
-        let init_msg = default_init(2, 50);
-        let env = mock_env(&creator, &[]);
+        let creator = String::from("creator");
+        let instantiate_msg = default_instantiate(2, 50);
+        let info = mock_info(&creator, &[]);
 
-        // make sure we can init with this
-        let res = init(&mut deps, env, init_msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        // make sure we can instantiate with this
+        let res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+        assert_eq!(1, res.messages.len());
 
         // let's bond some tokens now
-        let bob = HumanAddr::from("bob");
-        let bond_msg = HandleMsg::Bond {};
-        let env = mock_env(&bob, &[coin(10, "random"), coin(1000, "ustake")]);
-        let contract_addr = env.contract.address.clone();
-        let res = handle(&mut deps, env, bond_msg).unwrap();
-        assert_eq!(1, res.messages.len());
+        let bob = String::from("bob");
+        let bond_msg = ExecuteMsg::Bond { referrer: None };
+        let info = mock_info(&bob, &[coin(10, "random"), coin(1000, "ustake")]);
+        let res = execute(deps.as_mut(), mock_env(), info, bond_msg).unwrap();
+        assert_eq!(2, res.messages.len());
 
         // update the querier with new bond
         set_delegation(&mut deps.querier, 1000, "ustake");
 
         // fake a reinvestment (this must be sent by the contract itself)
-        let rebond_msg = HandleMsg::_BondAllTokens {};
-        let env = mock_env(&contract_addr, &[]);
+        let rebond_msg = ExecuteMsg::_BondAllTokens {};
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
         deps.querier
-            .update_balance(&contract_addr, coins(500, "ustake"));
-        let _ = handle(&mut deps, env, rebond_msg).unwrap();
+            .update_balance(MOCK_CONTRACT_ADDR, coins(500, "ustake"));
+        let _ = execute(deps.as_mut(), mock_env(), info, rebond_msg).unwrap();
 
         // update the querier with new bond
         set_delegation(&mut deps.querier, 1500, "ustake");
 
         // we should now see 1000 issues and 1500 bonded (and a price of 1.5)
-        let invest = query_investment(&deps).unwrap();
-        assert_eq!(invest.token_supply, Uint128(1000));
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.token_supply, Uint128::new(1000));
         assert_eq!(invest.staked_tokens, coin(1500, "ustake"));
         let ratio = Decimal::from_str("1.5").unwrap();
         assert_eq!(invest.nominal_value, ratio);
 
         // we bond some other tokens and get a different issuance price (maintaining the ratio)
-        let alice = HumanAddr::from("alice");
-        let bond_msg = HandleMsg::Bond {};
-        let env = mock_env(&alice, &[coin(3000, "ustake")]);
-        let res = handle(&mut deps, env, bond_msg).unwrap();
-        assert_eq!(1, res.messages.len());
+        let alice = String::from("alice");
+        let bond_msg = ExecuteMsg::Bond { referrer: None };
+        let info = mock_info(&alice, &[coin(3000, "ustake")]);
+        let res = execute(deps.as_mut(), mock_env(), info, bond_msg).unwrap();
+        assert_eq!(2, res.messages.len());
+        // alice should have gotten 2000 DRV for the 3000 stake, keeping the ratio at 1.5
+        assert_eq!(res.messages[0].msg, mint_msg(2000, &alice));
 
         // update the querier with new bond
         set_delegation(&mut deps.querier, 3000, "ustake");
 
-        // alice should have gotten 2000 DRV for the 3000 stake, keeping the ratio at 1.5
-        assert_eq!(get_balance(&deps, &alice), Uint128(2000));
-
-        let invest = query_investment(&deps).unwrap();
-        assert_eq!(invest.token_supply, Uint128(3000));
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.token_supply, Uint128::new(3000));
         assert_eq!(invest.staked_tokens, coin(4500, "ustake"));
         assert_eq!(invest.nominal_value, ratio);
     }
 
+    #[test]
+    fn reinvest_pays_owner_and_caller() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let creator = String::from("creator");
+        let info = mock_info(&creator, &[]);
+        instantiate(deps.as_mut(), mock_env(), info, default_instantiate(10, 50)).unwrap();
+
+        let bob = String::from("bob");
+        let info = mock_info(&bob, &coins(1000, "ustake"));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Bond { referrer: None },
+        )
+        .unwrap();
+
+        // too little rewards are not worth withdrawing
+        set_rewards(&mut deps.querier, 1000, 40);
+        let bot = String::from("bot");
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(&bot, &[]),
+            ExecuteMsg::Reinvest {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NothingToReinvest { .. }));
+
+        // 200 rewards: 2 go to the bot, 19.8 (rounded down) are minted to the owner as DRV
+        set_rewards(&mut deps.querier, 1000, 200);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(&bot, &[]),
+            ExecuteMsg::Reinvest {},
+        )
+        .unwrap();
+        assert_eq!(4, res.messages.len());
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Distribution(DistributionMsg::WithdrawDelegatorReward {
+                validator: DEFAULT_VALIDATOR.to_string(),
+            })
+        );
+        assert_eq!(res.messages[1].msg, mint_msg(19, &creator));
+        assert_eq!(
+            res.messages[2].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: bot.clone(),
+                amount: coins(2, "ustake"),
+            })
+        );
+
+        // the callback bonds everything that is left after the incentive
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(198, "ustake"));
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::_BondAllTokens {}).unwrap();
+
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.token_supply, Uint128::new(1019));
+        assert_eq!(invest.staked_tokens, coin(1198, "ustake"));
+    }
+
+    #[test]
+    fn slashing_lowers_exchange_rate() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, default_instantiate(0, 50)).unwrap();
+
+        let bob = String::from("bob");
+        let info = mock_info(&bob, &coins(1000, "ustake"));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Bond { referrer: None },
+        )
+        .unwrap();
+
+        // the validator loses 10% of the delegation
+        set_delegation(&mut deps.querier, 900, "ustake");
+        let rate = query_exchange_rate(deps.as_ref(), mock_env()).unwrap();
+        assert_eq!(rate.exchange_rate, Decimal::percent(90));
+        assert_eq!(rate.bonded, Uint128::new(900));
+        assert_eq!(rate.unabsorbed_slashing, Uint128::new(100));
+
+        // the next bond is priced at the slashed rate and records the loss
+        let alice = String::from("alice");
+        let info = mock_info(&alice, &coins(900, "ustake"));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Bond { referrer: None },
+        )
+        .unwrap();
+        assert_eq!(res.messages[0].msg, mint_msg(1000, &alice));
+        assert_eq!(
+            res.events,
+            vec![Event::new("slashing_absorbed")
+                .add_attribute("recorded", "1000")
+                .add_attribute("bonded", "900")
+                .add_attribute("slashed", "100")
+                .add_attribute("queued_slashed", "0")]
+        );
+
+        set_delegation(&mut deps.querier, 1800, "ustake");
+        let rate = query_exchange_rate(deps.as_ref(), mock_env()).unwrap();
+        assert_eq!(rate.exchange_rate, Decimal::percent(90));
+        assert_eq!(rate.unabsorbed_slashing, Uint128::zero());
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.staked_tokens, coin(1800, "ustake"));
+        assert_eq!(invest.nominal_value, Decimal::percent(90));
+
+        // bonded growing without the contract knowing is still refused
+        set_delegation(&mut deps.querier, 2000, "ustake");
+        let info = mock_info(&alice, &coins(900, "ustake"));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Bond { referrer: None },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::BondedMismatch { .. }));
+    }
+
     #[test]
     fn bonding_fails_with_wrong_denom() {
-        let mut deps = mock_dependencies(20, &[]);
+        let mut deps = mock_dependencies();
         set_validator(&mut deps.querier);
 
-        let creator = HumanAddr::from("creator");
-        let init_msg = default_init(2, 50);
-        let env = mock_env(&creator, &[]);
+        let creator = String::from("creator");
+        let instantiate_msg = default_instantiate(2, 50);
+        let info = mock_info(&creator, &[]);
 
-        // make sure we can init with this
-        let res = init(&mut deps, env, init_msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        // make sure we can instantiate with this
+        let res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+        assert_eq!(1, res.messages.len());
 
         // let's bond some tokens now
-        let bob = HumanAddr::from("bob");
-        let bond_msg = HandleMsg::Bond {};
-        let env = mock_env(&bob, &[coin(500, "photon")]);
+        let bob = String::from("bob");
+        let bond_msg = ExecuteMsg::Bond { referrer: None };
+        let info = mock_info(&bob, &[coin(500, "photon")]);
 
         // try to bond and make sure we trigger delegation
-        let res = handle(&mut deps, env, bond_msg);
+        let res = execute(deps.as_mut(), mock_env(), info, bond_msg);
         match res.unwrap_err() {
-            StdError::GenericErr { msg, .. } => assert_eq!(msg, "No ustake tokens sent"),
+            ContractError::EmptyBalance { denom } => assert_eq!(denom, "ustake"),
             e => panic!("Expected wrong denom error, got: {:?}", e),
         };
     }
 
     #[test]
     fn unbonding_maintains_price_ratio() {
-        let mut deps = mock_dependencies(20, &[]);
+        let mut deps = mock_dependencies();
         set_validator(&mut deps.querier);
 
-        let creator = HumanAddr::from("creator");
-        let init_msg = default_init(10, 50);
-        let env = mock_env(&creator, &[]);
+        let creator = String::from("creator");
+        let instantiate_msg = default_instantiate(10, 50);
+        let info = mock_info(&creator, &[]);
 
-        // make sure we can init with this
-        let res = init(&mut deps, env, init_msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        // make sure we can instantiate with this
+        let res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+        assert_eq!(1, res.messages.len());
 
         // let's bond some tokens now
-        let bob = HumanAddr::from("bob");
-        let bond_msg = HandleMsg::Bond {};
-        let env = mock_env(&bob, &[coin(10, "random"), coin(1000, "ustake")]);
-        let contract_addr = env.contract.address.clone();
-        let res = handle(&mut deps, env, bond_msg).unwrap();
-        assert_eq!(1, res.messages.len());
+        let bob = String::from("bob");
+        let bond_msg = ExecuteMsg::Bond { referrer: None };
+        let info = mock_info(&bob, &[coin(10, "random"), coin(1000, "ustake")]);
+        let res = execute(deps.as_mut(), mock_env(), info, bond_msg).unwrap();
+        assert_eq!(2, res.messages.len());
 
         // update the querier with new bond
         set_delegation(&mut deps.querier, 1000, "ustake");
 
         // fake a reinvestment (this must be sent by the contract itself)
         // after this, we see 1000 issues and 1500 bonded (and a price of 1.5)
-        let rebond_msg = HandleMsg::_BondAllTokens {};
-        let env = mock_env(&contract_addr, &[]);
+        let rebond_msg = ExecuteMsg::_BondAllTokens {};
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
         deps.querier
-            .update_balance(&contract_addr, coins(500, "ustake"));
-        let _ = handle(&mut deps, env, rebond_msg).unwrap();
+            .update_balance(MOCK_CONTRACT_ADDR, coins(500, "ustake"));
+        let _ = execute(deps.as_mut(), mock_env(), info, rebond_msg).unwrap();
 
         // update the querier with new bond, lower balance
         set_delegation(&mut deps.querier, 1500, "ustake");
-        deps.querier.update_balance(&contract_addr, vec![]);
+        deps.querier.update_balance(MOCK_CONTRACT_ADDR, vec![]);
 
-        // creator now tries to unbond these tokens - this must fail
-        let unbond_msg = HandleMsg::Unbond {
-            amount: Uint128(600),
-        };
-        let env = mock_env(&creator, &[]);
-        let res = handle(&mut deps, env, unbond_msg);
+        // creator now tries to unbond without sending any tokens - this must fail
+        let info = mock_info(&creator, &coins(600, "ustake"));
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Unbond {});
         match res.unwrap_err() {
-            StdError::Underflow { .. } => {}
+            ContractError::Payment(PaymentError::MissingDenom(denom)) => assert_eq!(denom, DRV),
             e => panic!("unexpected error: {}", e),
         }
 
         // bob unbonds 600 tokens at 10% tax...
         // 60 are taken and send to the owner
-        // 540 are unbonded in exchange for 540 * 1.5 = 810 native tokens
-        let unbond_msg = HandleMsg::Unbond {
-            amount: Uint128(600),
-        };
-        let owner_cut = Uint128(60);
-        let bobs_claim = Uint128(810);
-        let bobs_balance = Uint128(400);
-        let env = mock_env(&bob, &[]);
-        let res = handle(&mut deps, env.clone(), unbond_msg).unwrap();
+        // 540 are burnt in exchange for 540 * 1.5 = 810 native tokens
+        let owner_cut = Uint128::new(60);
+        let bobs_claim = Uint128::new(810);
+        let bobs_balance = Uint128::new(400);
+        let env = mock_env();
+        let info = mock_info(&bob, &coins(600, DRV));
+        let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Unbond {}).unwrap();
+        assert_eq!(2, res.messages.len());
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Burn {
+                coin: coin(540, DRV),
+            }))
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: creator.clone(),
+                amount: coins(owner_cut.u128(), DRV),
+            })
+        );
+
+        // the native tokens are undelegated when the epoch is advanced
+        let res = advance_epoch(deps.as_mut(), env.clone());
         assert_eq!(1, res.messages.len());
-        let delegate = &res.messages[0];
+        let delegate = &res.messages[0].msg;
         match delegate {
             CosmosMsg::Staking(StakingMsg::Undelegate { validator, amount }) => {
                 assert_eq!(validator.as_str(), DEFAULT_VALIDATOR);
@@ -831,20 +1512,17 @@ mod tests {
         // update the querier with new bond, lower balance
         set_delegation(&mut deps.querier, 690, "ustake");
 
-        // check balances
-        assert_eq!(get_balance(&deps, &bob), bobs_balance);
-        assert_eq!(get_balance(&deps, &creator), owner_cut);
         // proper claims
         let expected_claims = vec![Claim {
             amount: bobs_claim,
-            released: (DAY * 3).after(&env.block),
+            release_at: (DAY * 3).after(&env.block),
         }];
-        assert_eq!(expected_claims, get_claims(&deps, &bob));
+        assert_eq!(expected_claims, get_claims(deps.as_ref(), &bob));
 
         // supplies updated, ratio the same (1.5)
         let ratio = Decimal::from_str("1.5").unwrap();
 
-        let invest = query_investment(&deps).unwrap();
+        let invest = query_investment(deps.as_ref()).unwrap();
         assert_eq!(invest.token_supply, bobs_balance + owner_cut);
         assert_eq!(invest.staked_tokens, coin(690, "ustake")); // 1500 - 810
         assert_eq!(invest.nominal_value, ratio);
@@ -852,147 +1530,584 @@ mod tests {
 
     #[test]
     fn claims_paid_out_properly() {
-        let mut deps = mock_dependencies(20, &[]);
+        let mut deps = mock_dependencies();
         set_validator(&mut deps.querier);
 
         // create contract
-        let creator = HumanAddr::from("creator");
-        let init_msg = default_init(10, 50);
-        let env = mock_env(&creator, &[]);
-        init(&mut deps, env, init_msg).unwrap();
+        let creator = String::from("creator");
+        let instantiate_msg = default_instantiate(10, 50);
+        let info = mock_info(&creator, &[]);
+        instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
 
         // bond some tokens
-        let bob = HumanAddr::from("bob");
-        let env = mock_env(&bob, &coins(1000, "ustake"));
-        handle(&mut deps, env, HandleMsg::Bond {}).unwrap();
+        let bob = String::from("bob");
+        let info = mock_info(&bob, &coins(1000, "ustake"));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Bond { referrer: None },
+        )
+        .unwrap();
         set_delegation(&mut deps.querier, 1000, "ustake");
 
         // unbond part of them
-        let unbond_msg = HandleMsg::Unbond {
-            amount: Uint128(600),
-        };
-        let env = mock_env(&bob, &[]);
-        handle(&mut deps, env.clone(), unbond_msg).unwrap();
+        let env = mock_env();
+        let info = mock_info(&bob, &coins(600, DRV));
+        execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Unbond {}).unwrap();
+        advance_epoch(deps.as_mut(), env.clone());
+        let info = mock_info(&bob, &[]);
         set_delegation(&mut deps.querier, 460, "ustake");
 
         // ensure claims are proper
-        let bobs_claim = Uint128(540);
+        let bobs_claim = Uint128::new(540);
         let original_claims = vec![Claim {
             amount: bobs_claim,
-            released: (DAY * 3).after(&env.block),
+            release_at: (DAY * 3).after(&env.block),
         }];
-        assert_eq!(original_claims, get_claims(&deps, &bob));
+        assert_eq!(original_claims, get_claims(deps.as_ref(), &bob));
 
         // bob cannot exercise claims without enough balance
         let claim_ready = later(&env, (DAY * 3 + HOUR).unwrap());
         let too_soon = later(&env, DAY);
-        let fail = handle(&mut deps, claim_ready.clone(), HandleMsg::Claim {});
+        let fail = execute(
+            deps.as_mut(),
+            claim_ready.clone(),
+            info.clone(),
+            ExecuteMsg::Claim {},
+        );
         assert!(fail.is_err(), "{:?}", fail);
 
         // provide the balance, but claim not yet mature - also prohibited
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(540, "ustake"));
-        let fail = handle(&mut deps, too_soon, HandleMsg::Claim {});
+        let fail = execute(deps.as_mut(), too_soon, info.clone(), ExecuteMsg::Claim {});
         assert!(fail.is_err(), "{:?}", fail);
 
         // this should work with cash and claims ready
-        let res = handle(&mut deps, claim_ready, HandleMsg::Claim {}).unwrap();
+        let res = execute(deps.as_mut(), claim_ready, info, ExecuteMsg::Claim {}).unwrap();
         assert_eq!(1, res.messages.len());
-        let payout = &res.messages[0];
+        let payout = &res.messages[0].msg;
         match payout {
-            CosmosMsg::Bank(BankMsg::Send {
-                from_address,
-                to_address,
-                amount,
-            }) => {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
                 assert_eq!(amount, &coins(540, "ustake"));
-                assert_eq!(from_address.as_str(), MOCK_CONTRACT_ADDR);
                 assert_eq!(to_address, &bob);
             }
             _ => panic!("Unexpected message: {:?}", payout),
         }
 
         // claims have been removed
-        assert_eq!(get_claims(&deps, &bob), vec![]);
+        assert_eq!(get_claims(deps.as_ref(), &bob), vec![]);
     }
 
     #[test]
-    fn cw20_imports_work() {
-        let mut deps = mock_dependencies(20, &[]);
+    fn matured_claims_paid_in_one_call() {
+        let mut deps = mock_dependencies();
         set_validator(&mut deps.querier);
 
-        // set the actors... bob stakes, sends coins to carl, and gives allowance to alice
-        let bob = HumanAddr::from("bob");
-        let alice = HumanAddr::from("alice");
-        let carl = HumanAddr::from("carl");
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, default_instantiate(0, 50)).unwrap();
+
+        let bob = String::from("bob");
+        let info = mock_info(&bob, &coins(1000, "ustake"));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Bond { referrer: None },
+        )
+        .unwrap();
+        set_delegation(&mut deps.querier, 1000, "ustake");
 
-        // create the contract
-        let creator = HumanAddr::from("creator");
-        let init_msg = default_init(2, 50);
-        let env = mock_env(&creator, &[]);
-        init(&mut deps, env, init_msg).unwrap();
+        // unbond once, then twice more a day later
+        let env = mock_env();
+        let unbond_info = mock_info(&bob, &coins(100, DRV));
+        let unbond = ExecuteMsg::Unbond {};
+        execute(deps.as_mut(), env.clone(), unbond_info.clone(), unbond.clone()).unwrap();
+        advance_epoch(deps.as_mut(), env.clone());
+        set_delegation(&mut deps.querier, 900, "ustake");
+        execute(
+            deps.as_mut(),
+            later(&env, DAY),
+            unbond_info.clone(),
+            unbond.clone(),
+        )
+        .unwrap();
+        execute(deps.as_mut(), later(&env, DAY), unbond_info, unbond).unwrap();
+        advance_epoch(deps.as_mut(), later(&env, DAY));
+        set_delegation(&mut deps.querier, 700, "ustake");
+        let info = mock_info(&bob, &[]);
+
+        // only the first has matured
+        let check = later(&env, (DAY * 3 + HOUR).unwrap());
+        let claims = query_claims(deps.as_ref(), check.clone(), bob.clone()).unwrap();
+        assert_eq!(claims.matured.len(), 1);
+        assert_eq!(claims.matured_amount, Uint128::new(100));
+        assert_eq!(claims.pending.len(), 1);
+
+        // nothing is paid before the contract holds the full matured amount
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(50, "ustake"));
+        let err = execute(
+            deps.as_mut(),
+            check.clone(),
+            info.clone(),
+            ExecuteMsg::ClaimMatured {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::BalanceTooSmall {}));
+
+        // once both unbonding periods are over, both are paid together
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(300, "ustake"));
+        let all_ready = later(&env, (DAY * 4 + HOUR).unwrap());
+        let res = execute(
+            deps.as_mut(),
+            all_ready.clone(),
+            info.clone(),
+            ExecuteMsg::ClaimMatured {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: bob.clone(),
+                amount: coins(300, "ustake"),
+            })
+        );
+        let claims = query_claims(deps.as_ref(), all_ready.clone(), bob).unwrap();
+        assert_eq!(claims.matured, vec![]);
+        assert_eq!(claims.pending, vec![]);
 
-        // bond some tokens to create a balance
-        let env = mock_env(&bob, &[coin(10, "random"), coin(1000, "ustake")]);
-        handle(&mut deps, env, HandleMsg::Bond {}).unwrap();
+        // and there is nothing left to claim
+        let err = execute(deps.as_mut(), all_ready, info, ExecuteMsg::ClaimMatured {}).unwrap_err();
+        assert!(matches!(err, ContractError::NothingToClaim {}));
+    }
 
-        // bob got 1000 DRV for 1000 stake at a 1.0 ratio
-        assert_eq!(get_balance(&deps, &bob), Uint128(1000));
+    #[test]
+    fn unbonds_batched_per_epoch() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
 
-        // send coins to carl
-        let bob_env = mock_env(&bob, &[]);
-        let transfer = HandleMsg::Transfer {
-            recipient: carl.clone(),
-            amount: Uint128(200),
+        let mut instantiate_msg = default_instantiate(0, 50);
+        instantiate_msg.unbond_epoch = DAY;
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+        let bob = String::from("bob");
+        let alice = String::from("alice");
+        for (staker, delegated) in [(&bob, 1000), (&alice, 2000)] {
+            let info = mock_info(staker, &coins(1000, "ustake"));
+            let bond_msg = ExecuteMsg::Bond { referrer: None };
+            execute(deps.as_mut(), mock_env(), info, bond_msg).unwrap();
+            set_delegation(&mut deps.querier, delegated, "ustake");
+        }
+
+        // both unbond within the first epoch, bob twice
+        let env = mock_env();
+        for (staker, amount) in [(&bob, 100), (&alice, 300), (&bob, 200)] {
+            let info = mock_info(staker, &coins(amount, DRV));
+            let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Unbond {}).unwrap();
+            assert_eq!(1, res.messages.len());
+        }
+
+        let pending = query_pending_unbonds(deps.as_ref(), 0, None, None).unwrap();
+        assert_eq!(pending.total, Uint128::new(600));
+        assert_eq!(pending.undelegated, None);
+        assert_eq!(
+            pending.unbonds,
+            vec![
+                PendingUnbond {
+                    address: alice.clone(),
+                    amount: Uint128::new(300),
+                },
+                PendingUnbond {
+                    address: bob.clone(),
+                    amount: Uint128::new(300),
+                },
+            ]
+        );
+        assert_eq!(pending.current_epoch, 0);
+        assert_eq!(pending.current_epoch_ends, DAY.after(&env.block));
+        assert_eq!(get_claims(deps.as_ref(), &bob), vec![]);
+        let page = query_pending_unbonds(deps.as_ref(), 0, Some(alice.clone()), Some(1)).unwrap();
+        assert_eq!(page.total, Uint128::new(600));
+        assert_eq!(
+            page.unbonds,
+            vec![PendingUnbond {
+                address: bob.clone(),
+                amount: Uint128::new(300),
+            }]
+        );
+
+        // the queued tokens are still delegated, but no longer priced into the token
+        let rate = query_exchange_rate(deps.as_ref(), env.clone()).unwrap();
+        assert_eq!(rate.bonded, Uint128::new(1400));
+        assert_eq!(rate.exchange_rate, Decimal::one());
+        assert_eq!(rate.unabsorbed_slashing, Uint128::zero());
+
+        // the epoch can't be closed early
+        let info = mock_info("cranker", &[]);
+        let err = execute(
+            deps.as_mut(),
+            later(&env, HOUR),
+            info,
+            ExecuteMsg::AdvanceEpoch {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::EpochNotEnded { epoch: 0, .. }));
+
+        // once it has, everything is undelegated in one message
+        let next_day = later(&env, DAY);
+        let res = advance_epoch(deps.as_mut(), next_day.clone());
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Undelegate {
+                validator: DEFAULT_VALIDATOR.to_string(),
+                amount: coin(600, "ustake"),
+            })
+        );
+        set_delegation(&mut deps.querier, 1400, "ustake");
+
+        let release_at = (DAY * 3).after(&next_day.block);
+        assert_eq!(
+            get_claims(deps.as_ref(), &bob),
+            vec![Claim {
+                amount: Uint128::new(300),
+                release_at,
+            }]
+        );
+        let pending = query_pending_unbonds(deps.as_ref(), 0, None, None).unwrap();
+        assert_eq!(pending.undelegated, Some(Uint128::new(600)));
+        assert_eq!(pending.current_epoch, 1);
+        assert_eq!(pending.current_epoch_ends, DAY.after(&next_day.block));
+
+        // the request becomes a claim when bob is back
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(600, "ustake"));
+        let claim_ready = later(&next_day, DAY * 3);
+        execute(
+            deps.as_mut(),
+            claim_ready,
+            mock_info(&bob, &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap();
+        let pending = query_pending_unbonds(deps.as_ref(), 0, None, None).unwrap();
+        assert_eq!(
+            pending.unbonds,
+            vec![PendingUnbond {
+                address: alice.clone(),
+                amount: Uint128::new(300),
+            }]
+        );
+
+        // an empty epoch advances without undelegating
+        let res = advance_epoch(deps.as_mut(), later(&next_day, DAY));
+        assert_eq!(0, res.messages.len());
+    }
+
+    #[test]
+    fn queued_unbonds_share_slashing() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let mut instantiate_msg = default_instantiate(0, 50);
+        instantiate_msg.unbond_epoch = DAY;
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+        let bob = String::from("bob");
+        let info = mock_info(&bob, &coins(1000, "ustake"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond { referrer: None }).unwrap();
+        set_delegation(&mut deps.querier, 1000, "ustake");
+        let env = mock_env();
+        let info = mock_info(&bob, &coins(500, DRV));
+        execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Unbond {}).unwrap();
+
+        // the validator loses 10% while half of the delegation waits in the epoch
+        set_delegation(&mut deps.querier, 900, "ustake");
+        let rate = query_exchange_rate(deps.as_ref(), env.clone()).unwrap();
+        assert_eq!(rate.unabsorbed_slashing, Uint128::new(100));
+        assert_eq!(rate.bonded, Uint128::new(450));
+        assert_eq!(rate.exchange_rate, Decimal::percent(90));
+
+        let next_day = later(&env, DAY);
+        let res = advance_epoch(deps.as_mut(), next_day.clone());
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Undelegate {
+                validator: DEFAULT_VALIDATOR.to_string(),
+                amount: coin(450, "ustake"),
+            })
+        );
+        assert_eq!(
+            res.events,
+            vec![Event::new("slashing_absorbed")
+                .add_attribute("recorded", "1000")
+                .add_attribute("bonded", "900")
+                .add_attribute("slashed", "100")
+                .add_attribute("queued_slashed", "50")]
+        );
+        assert_eq!(
+            get_claims(deps.as_ref(), &bob),
+            vec![Claim {
+                amount: Uint128::new(450),
+                release_at: (DAY * 3).after(&next_day.block),
+            }]
+        );
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.staked_tokens, coin(450, "ustake"));
+        assert_eq!(invest.nominal_value, Decimal::percent(90));
+    }
+
+    #[test]
+    fn exit_tax_tiers_and_referrals() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        // 10% base tax, 5% after a week and 2% after four, half of it goes to referrers
+        let week = 7 * 24 * 3600;
+        let mut instantiate_msg = default_instantiate(10, 50);
+        instantiate_msg.exit_tax_tiers = vec![
+            ExitTaxTier {
+                bonded_for: 4 * week,
+                exit_tax: Decimal::percent(2),
+            },
+            ExitTaxTier {
+                bonded_for: week,
+                exit_tax: Decimal::percent(5),
+            },
+        ];
+        instantiate_msg.referral_share = Decimal::percent(50);
+        let info = mock_info("creator", &[]);
+        let err = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            instantiate_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidExitTaxTiers {}));
+        instantiate_msg.exit_tax_tiers.reverse();
+        instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+        // bob can't refer himself, alice's referral is recorded at his first bond
+        let bob = String::from("bob");
+        let info = mock_info(&bob, &coins(1000, "ustake"));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Bond {
+                referrer: Some(bob.clone()),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::SelfReferral {}));
+        let bond_msg = ExecuteMsg::Bond {
+            referrer: Some("alice".to_string()),
         };
-        handle(&mut deps, bob_env.clone(), transfer).unwrap();
-        assert_eq!(get_balance(&deps, &bob), Uint128(800));
-        assert_eq!(get_balance(&deps, &carl), Uint128(200));
-
-        // allow alice
-        let allow = HandleMsg::IncreaseAllowance {
-            spender: alice.clone(),
-            amount: Uint128(350),
-            expires: None,
+        let env = mock_env();
+        execute(deps.as_mut(), env.clone(), info, bond_msg).unwrap();
+        set_delegation(&mut deps.querier, 1000, "ustake");
+
+        let tier = query_account_tier(deps.as_ref(), env.clone(), bob.clone()).unwrap();
+        assert_eq!(tier.bonded_since, Some(env.block.time));
+        assert_eq!(tier.bonded_for, 0);
+        assert_eq!(tier.exit_tax, Decimal::percent(10));
+        assert_eq!(tier.next_tier.unwrap().bonded_for, week);
+        assert_eq!(tier.referrer, Some("alice".to_string()));
+
+        // topping up after two weeks moves the bond time forward by half of that
+        let two_weeks = later(&env, Duration::Time(2 * week));
+        let info = mock_info(&bob, &coins(1000, "ustake"));
+        let bond_msg = ExecuteMsg::Bond {
+            referrer: Some("carl".to_string()),
         };
-        handle(&mut deps, bob_env.clone(), allow).unwrap();
-        assert_eq!(get_balance(&deps, &bob), Uint128(800));
-        assert_eq!(get_balance(&deps, &alice), Uint128(0));
+        execute(deps.as_mut(), two_weeks.clone(), info, bond_msg).unwrap();
+        set_delegation(&mut deps.querier, 2000, "ustake");
+
+        let tier = query_account_tier(deps.as_ref(), two_weeks.clone(), bob.clone()).unwrap();
+        assert_eq!(tier.bonded_for, week);
+        assert_eq!(tier.exit_tax, Decimal::percent(5));
+        assert_eq!(tier.next_tier.unwrap().bonded_for, 4 * week);
+        assert_eq!(tier.referrer, Some("alice".to_string()));
+
+        // unbonding 1000 at 5% splits the 50 tax between the owner and alice
+        let info = mock_info(&bob, &coins(1000, DRV));
+        let res = execute(deps.as_mut(), two_weeks.clone(), info, ExecuteMsg::Unbond {}).unwrap();
+        assert_eq!(3, res.messages.len());
         assert_eq!(
-            query_allowance(&deps, bob.clone(), alice.clone())
-                .unwrap()
-                .allowance,
-            Uint128(350)
+            res.messages[0].msg,
+            CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Burn {
+                coin: coin(950, DRV),
+            }))
         );
+        for (msg, to) in res.messages[1..].iter().zip(["creator", "alice"]) {
+            assert_eq!(
+                msg.msg,
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: to.to_string(),
+                    amount: coins(25, DRV),
+                })
+            );
+        }
 
-        // alice takes some for herself
-        let self_pay = HandleMsg::TransferFrom {
-            owner: bob.clone(),
-            recipient: alice.clone(),
-            amount: Uint128(250),
-        };
-        let alice_env = mock_env(&alice, &[]);
-        handle(&mut deps, alice_env.clone(), self_pay).unwrap();
-        assert_eq!(get_balance(&deps, &bob), Uint128(550));
-        assert_eq!(get_balance(&deps, &alice), Uint128(250));
+        // tokens received from others are taxed at the base rate
+        let tier = query_account_tier(deps.as_ref(), env, "alice".to_string()).unwrap();
+        assert_eq!(tier.bonded_since, None);
+        assert_eq!(tier.exit_tax, Decimal::percent(10));
+        assert_eq!(tier.referrer, None);
+
+        // also when they are unbonded together with minted ones: bob got 100 from carl,
+        // his 1000 minted pay 5% and those 100 pay 10%
+        let info = mock_info("carl", &coins(1000, "ustake"));
+        let bond_msg = ExecuteMsg::Bond { referrer: None };
+        execute(deps.as_mut(), two_weeks.clone(), info, bond_msg).unwrap();
+        set_delegation(&mut deps.querier, 3000, "ustake");
+        let info = mock_info(&bob, &coins(1100, DRV));
+        let res = execute(deps.as_mut(), two_weeks, info, ExecuteMsg::Unbond {}).unwrap();
         assert_eq!(
-            query_allowance(&deps, bob.clone(), alice.clone())
-                .unwrap()
-                .allowance,
-            Uint128(100)
+            res.messages[0].msg,
+            CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Burn {
+                coin: coin(1040, DRV),
+            }))
         );
+        for (msg, to) in res.messages[1..].iter().zip(["creator", "alice"]) {
+            assert_eq!(
+                msg.msg,
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: to.to_string(),
+                    amount: coins(30, DRV),
+                })
+            );
+        }
+    }
 
-        // burn some, but not too much
-        let burn_too_much = HandleMsg::Burn {
-            amount: Uint128(1000),
+    #[test]
+    fn migrate_only_from_ported_versions() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, default_instantiate(2, 50)).unwrap();
+
+        // the current version can always be migrated in place
+        let msg = MigrateMsg { unbond_epoch: None };
+        migrate(deps.as_mut(), mock_env(), msg.clone()).unwrap();
+
+        // stores from before the epochs get their first one
+        EPOCH.remove(deps.as_mut().storage);
+        let with_epoch = MigrateMsg {
+            unbond_epoch: Some(DAY),
         };
-        let failed = handle(&mut deps, bob_env.clone(), burn_too_much);
-        assert!(failed.is_err());
-        assert_eq!(get_balance(&deps, &bob), Uint128(550));
-        let burn = HandleMsg::Burn {
-            amount: Uint128(130),
+        migrate(deps.as_mut(), mock_env(), with_epoch).unwrap();
+        let epoch = EPOCH.load(&deps.storage).unwrap();
+        assert_eq!(epoch.id, 0);
+        assert_eq!(epoch.period, DAY);
+        assert_eq!(epoch.ends, DAY.after(&mock_env().block));
+
+        // cw20 ledgers of earlier versions cannot be carried over
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.3.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), msg.clone()).unwrap_err();
+        match err {
+            ContractError::InvalidMigrationVersion { version } => assert_eq!(version, "0.3.0"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // nor can some other contract be taken over
+        set_contract_version(deps.as_mut().storage, "crates.io:other", CONTRACT_VERSION).unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), msg).unwrap_err();
+        match err {
+            ContractError::InvalidContract { contract } => {
+                assert_eq!(contract, "crates.io:other")
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn voting_power_follows_locked_derivative_per_height() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, default_instantiate(10, 50)).unwrap();
+        let info = mock_info("bob", &coins(1000, "ustake"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond { referrer: None }).unwrap();
+        set_delegation(&mut deps.querier, 1000, "ustake");
+
+        let power = |deps: Deps, addr: &str, height: u64| {
+            query_voting_power_at(deps, mock_env(), addr.to_string(), Some(height))
+                .unwrap()
+                .power
+        };
+        let at = |height: u64| {
+            let mut env = mock_env();
+            env.block.height = height;
+            env
+        };
+
+        // bonding alone gives no power, bob locks his derivative which counts from the next block
+        let start = mock_env().block.height;
+        assert_eq!(power(deps.as_ref(), "bob", start + 1), Uint128::zero());
+        let info = mock_info("bob", &coins(1000, DRV));
+        execute(deps.as_mut(), at(start), info, ExecuteMsg::Lock {}).unwrap();
+        assert_eq!(power(deps.as_ref(), "bob", start), Uint128::zero());
+        assert_eq!(power(deps.as_ref(), "bob", start + 1), Uint128::new(1000));
+
+        // unlocked tokens move on with their voting power gone, so they can't vote twice
+        let err = execute(
+            deps.as_mut(),
+            at(start + 5),
+            mock_info("bob", &[]),
+            ExecuteMsg::Unlock {
+                amount: Uint128::new(1001),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InsufficientLocked { locked } if locked == Uint128::new(1000)
+        ));
+        let res = execute(
+            deps.as_mut(),
+            at(start + 5),
+            mock_info("bob", &[]),
+            ExecuteMsg::Unlock {
+                amount: Uint128::new(600),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bob".to_string(),
+                amount: coins(600, DRV),
+            })
+        );
+        let info = mock_info("alice", &coins(600, DRV));
+        execute(deps.as_mut(), at(start + 5), info, ExecuteMsg::Lock {}).unwrap();
+
+        assert_eq!(power(deps.as_ref(), "bob", start + 5), Uint128::new(1000));
+        assert_eq!(power(deps.as_ref(), "bob", start + 6), Uint128::new(400));
+        assert_eq!(power(deps.as_ref(), "alice", start + 5), Uint128::zero());
+        assert_eq!(power(deps.as_ref(), "alice", start + 6), Uint128::new(600));
+
+        // totals track the same checkpoints for quorum
+        let total = |height| {
+            query_total_power_at(deps.as_ref(), mock_env(), Some(height))
+                .unwrap()
+                .power
         };
-        handle(&mut deps, bob_env.clone(), burn).unwrap();
-        assert_eq!(get_balance(&deps, &bob), Uint128(420));
+        assert_eq!(total(start), Uint128::zero());
+        assert_eq!(total(start + 1), Uint128::new(1000));
+        assert_eq!(total(start + 6), Uint128::new(1000));
+
+        // without a height the current block is used
+        let res =
+            query_voting_power_at(deps.as_ref(), mock_env(), "bob".to_string(), None).unwrap();
+        assert_eq!(res.height, start);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,66 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use cw_utils::{Expiration, PaymentError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("{validator} is not in the current validator set")]
+    NotInValidatorSet { validator: String },
+
+    #[error("different denoms in bonds: '{denom1}' vs '{denom2}'")]
+    DifferentBondDenom { denom1: String, denom2: String },
+
+    #[error("Stored bonded {stored}, but query bonded: {queried}")]
+    BondedMismatch { stored: Uint128, queried: Uint128 },
+
+    #[error("No {denom} tokens sent")]
+    EmptyBalance { denom: String },
+
+    #[error("Must unbond at least {min_bonded} {denom}")]
+    UnbondTooSmall { min_bonded: Uint128, denom: String },
+
+    #[error("Insufficient balance in contract to process claim")]
+    BalanceTooSmall {},
+
+    #[error("Reinvest incentive must be less than 100%")]
+    InvalidReinvestIncentive {},
+
+    #[error("Exit tax tiers must be ordered by bonding time and below 100%")]
+    InvalidExitTaxTiers {},
+
+    #[error("Referral share must not exceed 100%")]
+    InvalidReferralShare {},
+
+    #[error("Cannot refer yourself")]
+    SelfReferral {},
+
+    #[error("Less than {min_withdrawal} {denom} of rewards to reinvest")]
+    NothingToReinvest { min_withdrawal: Uint128, denom: String },
+
+    #[error("Epoch {epoch} can't be advanced before it ends ({ends})")]
+    EpochNotEnded { epoch: u64, ends: Expiration },
+
+    #[error("No claims to release")]
+    NothingToClaim {},
+
+    #[error("Only {locked} derivative tokens are locked")]
+    InsufficientLocked { locked: Uint128 },
+
+    #[error("Cannot migrate from a different contract: {contract}")]
+    InvalidContract { contract: String },
+
+    #[error("Cannot migrate from version {version}")]
+    InvalidMigrationVersion { version: String },
+}
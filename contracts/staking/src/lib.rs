@@ -1,6 +1,6 @@
 pub mod contract;
+mod error;
 pub mod msg;
 pub mod state;
 
-#[cfg(all(target_arch = "wasm32", not(feature = "library")))]
-cosmwasm_std::create_entry_points!(contract);
\ No newline at end of file
+pub use crate::error::ContractError;
@@ -1,142 +1,194 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Binary, Coin, Decimal, HumanAddr, Uint128};
-use cw0::Duration;
-use cw20::Expiration;
+use cosmwasm_std::{Coin, Decimal, Timestamp, Uint128};
+use cw_controllers::Claim;
+use cw_utils::{Duration, Expiration};
 
-use crate::state::Claim;
+use crate::state::ExitTaxTier;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct InitMsg {
-    /// name of the derivative token
-    pub name: String,
+pub struct InstantiateMsg {
     /// symbol / ticker of the derivative token
     pub symbol: String,
+    /// subunit of the derivative token, its denom is `{subunit}-{contract address}`
+    pub subunit: String,
     /// decimal places of the derivative token (for UI)
-    pub decimals: u8,
+    pub precision: u32,
+    pub description: Option<String>,
 
     /// This is the validator that all tokens will be bonded to
-    pub validator: HumanAddr,
+    pub validator: String,
     /// This is the unbonding period of the native staking module
     /// We need this to only allow claims to be redeemed after the money has arrived
     pub unbonding_period: Duration,
+    /// Unbond requests are batched and undelegated once per epoch of this length
+    pub unbond_epoch: Duration,
 
     /// this is how much the owner takes as a cut when someone unbonds
     pub exit_tax: Decimal,
     /// This is the minimum amount we will pull out to reinvest, as well as a minumum
     /// that can be unbonded (to avoid needless staking tx)
     pub min_withdrawal: Uint128,
+    /// share of the withdrawn rewards paid to whoever triggers Reinvest
+    pub reinvest_incentive: Decimal,
+    /// lower exit taxes for accounts bonded at least a given time, ordered by that time
+    #[serde(default)]
+    pub exit_tax_tiers: Vec<ExitTaxTier>,
+    /// share of the exit tax paid to the referrer of the unbonding account
+    #[serde(default)]
+    pub referral_share: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub enum HandleMsg {
-    /// Bond will bond all staking tokens sent with the message and release derivative tokens
-    Bond {},
-    /// Unbond will "burn" the given amount of derivative tokens and send the unbonded
-    /// staking tokens to the message sender (after exit tax is deducted)
-    Unbond { amount: Uint128 },
+pub enum ExecuteMsg {
+    /// Bond will bond all staking tokens sent with the message and release derivative tokens.
+    /// The referrer is only recorded on the first bond of an account.
+    Bond { referrer: Option<String> },
+    /// Unbond will burn the derivative tokens sent with the message and queue the matching
+    /// staking tokens (after exit tax is deducted) to be undelegated with the current epoch.
+    /// Queued tokens are still delegated and share any slashing until then
+    Unbond {},
     /// Claim is used to claim your native tokens that you previously "unbonded"
     /// after the chain-defined waiting period (eg. 3 weeks)
     Claim {},
+    /// AdvanceEpoch undelegates everything queued in the current epoch once it has ended
+    /// and opens the next epoch. Anyone can call this. Each request of the epoch becomes
+    /// a claim the next time its sender unbonds or claims.
+    AdvanceEpoch {},
+    /// ClaimMatured pays out every claim of the sender whose unbonding period has passed
+    /// in a single call, failing if the contract cannot yet cover all of them
+    ClaimMatured {},
+    /// Lock keeps the derivative tokens sent with the message in the contract, where they
+    /// count as voting power of the sender from the next block on
+    Lock {},
+    /// Unlock returns locked derivative tokens to the sender, removing their voting power
+    Unlock { amount: Uint128 },
     /// Reinvest will check for all accumulated rewards, withdraw them, and
     /// re-bond them to the same validator. Anyone can call this, which updates
-    /// the value of the token (how much under custody).
+    /// the value of the token (how much under custody). The owner takes the exit tax
+    /// of the rewards and the caller is paid `reinvest_incentive` of them.
     Reinvest {},
     /// _BondAllTokens can only be called by the contract itself, after all rewards have been
     /// withdrawn. This is an example of using "callbacks" in message flows.
     /// This can only be invoked by the contract itself as a return from Reinvest
     _BondAllTokens {},
-
-    /// Implements CW20. Transfer is a base message to move tokens to another account without triggering actions
-    Transfer {
-        recipient: HumanAddr,
-        amount: Uint128,
-    },
-    /// Implements CW20. Burn is a base message to destroy tokens forever
-    Burn { amount: Uint128 },
-    /// Implements CW20.  Send is a base message to transfer tokens to a contract and trigger an action
-    /// on the receiving contract.
-    Send {
-        contract: HumanAddr,
-        amount: Uint128,
-        msg: Option<Binary>,
-    },
-    /// Implements CW20 "approval" extension. Allows spender to access an additional amount tokens
-    /// from the owner's (env.sender) account. If expires is Some(), overwrites current allowance
-    /// expiration with this one.
-    IncreaseAllowance {
-        spender: HumanAddr,
-        amount: Uint128,
-        expires: Option<Expiration>,
-    },
-    /// Implements CW20 "approval" extension. Lowers the spender's access of tokens
-    /// from the owner's (env.sender) account by amount. If expires is Some(), overwrites current
-    /// allowance expiration with this one.
-    DecreaseAllowance {
-        spender: HumanAddr,
-        amount: Uint128,
-        expires: Option<Expiration>,
-    },
-    /// Implements CW20 "approval" extension. Transfers amount tokens from owner -> recipient
-    /// if `env.sender` has sufficient pre-approval.
-    TransferFrom {
-        owner: HumanAddr,
-        recipient: HumanAddr,
-        amount: Uint128,
-    },
-    /// Implements CW20 "approval" extension. Sends amount tokens from owner -> contract
-    /// if `env.sender` has sufficient pre-approval.
-    SendFrom {
-        owner: HumanAddr,
-        contract: HumanAddr,
-        amount: Uint128,
-        msg: Option<Binary>,
-    },
-    /// Implements CW20 "approval" extension. Destroys tokens forever
-    BurnFrom { owner: HumanAddr, amount: Uint128 },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    /// Claims shows the number of tokens this address can access when they are done unbonding
-    Claims { address: HumanAddr },
+    /// Claims shows the claims of this address, split into those that can be claimed now
+    /// and those still unbonding
+    Claims { address: String },
     /// Investment shows metadata on the staking info of the contract
     Investment {},
-
-    /// Implements CW20. Returns the current balance of the given address, 0 if unset.
-    Balance { address: HumanAddr },
-    /// Implements CW20. Returns metadata on the contract - name, decimals, supply, etc.
-    TokenInfo {},
-    /// Implements CW20 "allowance" extension.
-    /// Returns how much spender can use from owner account, 0 if unset.
-    Allowance {
-        owner: HumanAddr,
-        spender: HumanAddr,
+    /// ExchangeRate shows how many native tokens one derivative token is worth, based on
+    /// the delegation currently reported by the chain
+    ExchangeRate {},
+    /// PendingUnbonds shows the unbond requests of the given epoch that are not claims yet
+    PendingUnbonds {
+        epoch: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// AccountTier shows how long this address has been bonded and the exit tax it pays
+    AccountTier { address: String },
+    /// VotingPowerAt shows the derivative this address had locked at the start of the given
+    /// height (default: the current block), see the voting-power package for governance use
+    VotingPowerAt {
+        address: String,
+        height: Option<u64>,
     },
+    /// TotalPowerAt shows the sum of all voting power at the start of the given height
+    TotalPowerAt { height: Option<u64> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    /// epoch length for stores written before unbonds were batched, which undelegated
+    /// every unbond right away (the default)
+    #[serde(default)]
+    pub unbond_epoch: Option<Duration>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ClaimsResponse {
-    pub claims: Vec<Claim>,
+    /// claims whose release time has passed, payable by ClaimMatured
+    pub matured: Vec<Claim>,
+    /// sum of all matured claims
+    pub matured_amount: Uint128,
+    /// claims still waiting for the unbonding period to end
+    pub pending: Vec<Claim>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingUnbond {
+    pub address: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingUnbondsResponse {
+    pub epoch: u64,
+    /// sum of all requests queued in the epoch
+    pub total: Uint128,
+    /// what the requests were undelegated for, once the epoch was advanced
+    pub undelegated: Option<Uint128>,
+    pub unbonds: Vec<PendingUnbond>,
+    /// the epoch currently collecting requests, and when it can be advanced
+    pub current_epoch: u64,
+    pub current_epoch_ends: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExchangeRateResponse {
+    /// native tokens per derivative token
+    pub exchange_rate: Decimal,
+    /// native tokens currently delegated
+    pub bonded: Uint128,
+    /// derivative tokens in circulation
+    pub issued: Uint128,
+    /// tokens lost to slashing that the contract has not recorded yet
+    pub unabsorbed_slashing: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InvestmentResponse {
+    /// denom of the derivative token
+    pub denom: String,
     pub token_supply: Uint128,
     pub staked_tokens: Coin,
     // ratio of staked_tokens / token_supply (or how many native tokens that one derivative token is nominally worth)
     pub nominal_value: Decimal,
 
     /// owner created the contract and takes a cut
-    pub owner: HumanAddr,
+    pub owner: String,
     /// this is how much the owner takes as a cut when someone unbonds
     pub exit_tax: Decimal,
     /// All tokens are bonded to this validator
-    pub validator: HumanAddr,
+    pub validator: String,
     /// This is the minimum amount we will pull out to reinvest, as well as a minumum
     /// that can be unbonded (to avoid needless staking tx)
     pub min_withdrawal: Uint128,
-}
\ No newline at end of file
+    /// share of the withdrawn rewards paid to whoever triggers Reinvest
+    pub reinvest_incentive: Decimal,
+    pub exit_tax_tiers: Vec<ExitTaxTier>,
+    /// share of the exit tax paid to referrers
+    pub referral_share: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AccountTierResponse {
+    /// None if the address has nothing bonded
+    pub bonded_since: Option<Timestamp>,
+    /// seconds the address has been bonded for
+    pub bonded_for: u64,
+    /// exit tax the address pays when unbonding derivative minted to it now, the rest
+    /// pays the base exit tax
+    pub exit_tax: Decimal,
+    /// the next tier the address reaches, if any
+    pub next_tier: Option<ExitTaxTier>,
+    pub referrer: Option<String>,
+}
@@ -1,38 +1,19 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{CanonicalAddr, Decimal, HumanAddr, ReadonlyStorage, Storage, Uint128};
-use cosmwasm_storage::{
-    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
-    Singleton,
-};
-use cw0::{Duration, Expiration};
-
-pub const KEY_INVESTMENT: &[u8] = b"invest";
-pub const KEY_TOTAL_SUPPLY: &[u8] = b"total_supply";
-
-pub const PREFIX_CLAIMS: &[u8] = b"claim";
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct Claim {
-    pub amount: Uint128,
-    pub released: Expiration,
-}
+use cosmwasm_std::{Addr, Decimal, Empty, Timestamp, Uint128};
+use cw_controllers::Claims;
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, Strategy};
+use cw_utils::{Duration, Expiration};
 
 /// claims are the claims to money being unbonded, index by claimer address
-pub fn claims<S: Storage>(storage: &mut S) -> Bucket<S, Vec<Claim>> {
-    bucket(PREFIX_CLAIMS, storage)
-}
-
-pub fn claims_read<S: ReadonlyStorage>(storage: &S) -> ReadonlyBucket<S, Vec<Claim>> {
-    bucket_read(PREFIX_CLAIMS, storage)
-}
+pub const CLAIMS: Claims = Claims::new("claims");
 
 /// Investment info is fixed at initialization, and is used to control the function of the contract
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InvestmentInfo {
     /// owner created the contract and takes a cut
-    pub owner: CanonicalAddr,
+    pub owner: Addr,
     /// this is the denomination we can stake (and only one we accept for payments)
     pub bond_denom: String,
     /// This is the unbonding period of the native staking module
@@ -41,36 +22,107 @@ pub struct InvestmentInfo {
     /// this is how much the owner takes as a cut when someone unbonds
     pub exit_tax: Decimal,
     /// All tokens are bonded to this validator
-    /// FIXME: humanize/canonicalize address doesn't work for validator addrresses
-    pub validator: HumanAddr,
+    /// addr_validate doesn't work for validator addresses, so this is stored as given
+    pub validator: String,
     /// This is the minimum amount we will pull out to reinvest, as well as a minumum
     /// that can be unbonded (to avoid needless staking tx)
     pub min_withdrawal: Uint128,
+    /// share of the withdrawn rewards paid to whoever triggers Reinvest
+    #[serde(default)]
+    pub reinvest_incentive: Decimal,
+    /// lower exit taxes for accounts bonded at least a given time, ordered by that time.
+    /// Accounts bonded for less than the first tier pay `exit_tax`
+    #[serde(default)]
+    pub exit_tax_tiers: Vec<ExitTaxTier>,
+    /// share of the exit tax paid to the referrer of the unbonding account instead of the owner
+    #[serde(default)]
+    pub referral_share: Decimal,
 }
 
-/// Supply is dynamic and tracks the current supply of staked and ERC20 tokens.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExitTaxTier {
+    /// seconds an account must have been bonded for this tier to apply
+    pub bonded_for: u64,
+    pub exit_tax: Decimal,
+}
+
+/// Account tracks how long an address has been bonded and who referred it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Account {
+    /// bond time averaged over the derivative tokens minted to the account,
+    /// so topping up moves it forward in proportion to the new bond
+    pub bonded_since: Timestamp,
+    /// derivative tokens minted to the account that it has not unbonded yet
+    pub minted: Uint128,
+    /// recorded at the first bond and never changed afterwards
+    pub referrer: Option<Addr>,
+}
+
+/// Supply is dynamic and tracks the current supply of staked and derivative tokens.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
 pub struct Supply {
     /// issued is how many derivative tokens this contract has issued
     pub issued: Uint128,
     /// bonded is how many native tokens exist bonded to the validator
     pub bonded: Uint128,
-    /// claims is how many tokens need to be reserved paying back those who unbonded
+    /// claims is how many tokens need to be reserved paying back those whose unbonds
+    /// were undelegated
     pub claims: Uint128,
+    /// queued is how many native tokens wait in the current epoch to be undelegated,
+    /// they are still part of the delegation, and share its slashing, but no longer back
+    /// any derivative tokens
+    #[serde(default)]
+    pub queued: Uint128,
 }
 
-pub fn invest_info<S: Storage>(storage: &mut S) -> Singleton<S, InvestmentInfo> {
-    singleton(storage, KEY_INVESTMENT)
-}
-
-pub fn invest_info_read<S: ReadonlyStorage>(storage: &S) -> ReadonlySingleton<S, InvestmentInfo> {
-    singleton_read(storage, KEY_INVESTMENT)
+/// Unbond requests are collected per epoch and undelegated together when it is advanced,
+/// keeping the number of concurrent undelegations on the chain low
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Epoch {
+    pub id: u64,
+    /// how long each epoch collects unbond requests
+    pub period: Duration,
+    /// AdvanceEpoch is accepted once this has passed
+    pub ends: Expiration,
+    /// sum of the unbond requests queued in this epoch, `Supply::queued` is what they are
+    /// still worth after slashing
+    #[serde(default)]
+    pub requested: Uint128,
 }
 
-pub fn total_supply<S: Storage>(storage: &mut S) -> Singleton<S, Supply> {
-    singleton(storage, KEY_TOTAL_SUPPLY)
+/// An epoch that was undelegated, its requests become claims on the undelegated tokens
+/// in proportion to what was requested
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondEpoch {
+    pub requested: Uint128,
+    pub undelegated: Uint128,
+    pub release_at: Expiration,
 }
 
-pub fn total_supply_read<S: ReadonlyStorage>(storage: &S) -> ReadonlySingleton<S, Supply> {
-    singleton_read(storage, KEY_TOTAL_SUPPLY)
-}
\ No newline at end of file
+/// denom of the derivative token issued by this contract
+pub const DENOM: Item<String> = Item::new("denom");
+pub const INVESTMENT: Item<InvestmentInfo> = Item::new("invest");
+pub const TOTAL_SUPPLY: Item<Supply> = Item::new("total_supply");
+pub const EPOCH: Item<Epoch> = Item::new("epoch");
+/// native tokens each address asked to unbond, by epoch, until they are turned into a claim
+pub const PENDING_UNBONDS: Map<(u64, &Addr), Uint128> = Map::new("pending_unbonds");
+/// epochs in which an address has pending unbonds
+pub const ACCOUNT_UNBONDS: Map<(&Addr, u64), Empty> = Map::new("account_unbonds");
+pub const UNBOND_EPOCHS: Map<u64, UnbondEpoch> = Map::new("unbond_epochs");
+pub const ACCOUNTS: Map<&Addr, Account> = Map::new("accounts");
+/// governance weight of each address, the derivative it locked in the contract checkpointed
+/// per block. Stored apart from the earlier weights based on minted derivative, which ignored
+/// transfers and are not carried over
+pub const VOTING_POWER: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "locked_power",
+    "locked_power__checkpoints",
+    "locked_power__changelog",
+    Strategy::EveryBlock,
+);
+/// sum of all voting power, checkpointed per block for quorum calculations
+pub const TOTAL_POWER: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_locked_power",
+    "total_locked_power__checkpoints",
+    "total_locked_power__changelog",
+    Strategy::EveryBlock,
+);
@@ -0,0 +1,410 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdResult, Uint128,
+};
+use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+use cw_utils::one_coin;
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, StreamResponse, StreamsResponse};
+use crate::state::{Stream, NEXT_STREAM_ID, STREAMS};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:streams";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    NEXT_STREAM_ID.save(deps.storage, &1)?;
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreateStream {
+            recipient,
+            start_time,
+            end_time,
+        } => execute_create_stream(deps, env, info, recipient, start_time, end_time),
+        ExecuteMsg::Withdraw { id, amount } => execute_withdraw(deps, env, info, id, amount),
+        ExecuteMsg::Cancel { id } => execute_cancel(deps, env, info, id),
+    }
+}
+
+pub fn execute_create_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    start_time: u64,
+    end_time: u64,
+) -> Result<Response, ContractError> {
+    let deposit = one_coin(&info)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    if recipient == info.sender {
+        return Err(ContractError::InvalidRecipient {});
+    }
+    if start_time >= end_time || end_time <= env.block.time.seconds() {
+        return Err(ContractError::InvalidTimes {});
+    }
+
+    let id = NEXT_STREAM_ID.load(deps.storage)?;
+    NEXT_STREAM_ID.save(deps.storage, &(id + 1))?;
+    let stream = Stream {
+        sender: info.sender.clone(),
+        recipient: recipient.clone(),
+        denom: deposit.denom,
+        deposit: deposit.amount,
+        withdrawn: Uint128::zero(),
+        start_time,
+        end_time,
+    };
+    STREAMS.save(deps.storage, id, &stream)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_stream")
+        .add_attribute("stream_id", id.to_string())
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("deposit", stream.deposit))
+}
+
+pub fn execute_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut stream = STREAMS.load(deps.storage, id)?;
+    if info.sender != stream.recipient {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let available = stream.withdrawable(env.block.time.seconds());
+    let amount = amount.unwrap_or(available);
+    if amount > available {
+        return Err(ContractError::AmountTooLarge { available });
+    }
+    if amount.is_zero() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+
+    stream.withdrawn += amount;
+    if stream.withdrawn == stream.deposit {
+        STREAMS.remove(deps.storage, id);
+    } else {
+        STREAMS.save(deps.storage, id, &stream)?;
+    }
+
+    Ok(Response::new()
+        .add_message(send_tokens(&stream.recipient, amount, &stream.denom))
+        .add_attribute("action", "withdraw")
+        .add_attribute("stream_id", id.to_string())
+        .add_attribute("amount", amount))
+}
+
+pub fn execute_cancel(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let stream = STREAMS.load(deps.storage, id)?;
+    if info.sender != stream.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // pro-rata split at the current time
+    let now = env.block.time.seconds();
+    let recipient_amount = stream.withdrawable(now);
+    let sender_amount = stream.deposit - stream.streamed(now);
+    STREAMS.remove(deps.storage, id);
+
+    let mut response = Response::new();
+    if !recipient_amount.is_zero() {
+        response = response.add_message(send_tokens(
+            &stream.recipient,
+            recipient_amount,
+            &stream.denom,
+        ));
+    }
+    if !sender_amount.is_zero() {
+        response = response.add_message(send_tokens(&stream.sender, sender_amount, &stream.denom));
+    }
+
+    Ok(response
+        .add_attribute("action", "cancel")
+        .add_attribute("stream_id", id.to_string())
+        .add_attribute("recipient_amount", recipient_amount)
+        .add_attribute("sender_amount", sender_amount))
+}
+
+fn send_tokens(to: &Addr, amount: Uint128, denom: &str) -> BankMsg {
+    BankMsg::Send {
+        to_address: to.to_string(),
+        amount: coins(amount.u128(), denom),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Stream { id } => to_json_binary(&query_stream(deps, env, id)?),
+        QueryMsg::Streams {
+            party,
+            start_after,
+            limit,
+        } => to_json_binary(&query_streams(deps, env, party, start_after, limit)?),
+    }
+}
+
+fn query_stream(deps: Deps, env: Env, id: u64) -> StdResult<StreamResponse> {
+    let stream = STREAMS.load(deps.storage, id)?;
+    Ok(to_response(id, stream, env.block.time.seconds()))
+}
+
+fn query_streams(
+    deps: Deps,
+    env: Env,
+    party: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<StreamsResponse> {
+    let party = party
+        .map(|party| deps.api.addr_validate(&party))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let now = env.block.time.seconds();
+
+    let streams = STREAMS
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match (item, &party) {
+            (Ok((_, stream)), Some(party)) => &stream.sender == party || &stream.recipient == party,
+            _ => true,
+        })
+        .take(limit)
+        .map(|item| item.map(|(id, stream)| to_response(id, stream, now)))
+        .collect::<StdResult<_>>()?;
+
+    Ok(StreamsResponse { streams })
+}
+
+fn to_response(id: u64, stream: Stream, now: u64) -> StreamResponse {
+    StreamResponse {
+        id,
+        streamed: stream.streamed(now),
+        withdrawable: stream.withdrawable(now),
+        sender: stream.sender,
+        recipient: stream.recipient,
+        denom: stream.denom,
+        deposit: stream.deposit,
+        withdrawn: stream.withdrawn,
+        start_time: stream.start_time,
+        end_time: stream.end_time,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{coin, Coin, CosmosMsg, OwnedDeps, StdError, Timestamp};
+
+    use super::*;
+
+    const DAO: &str = "dao";
+    const EMPLOYEE: &str = "employee";
+    const DENOM: &str = "utkn-issuer";
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    }
+
+    // streams 1000 over [100, 200)
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            env_at(0),
+            mock_info(DAO, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::CreateStream {
+            recipient: EMPLOYEE.to_string(),
+            start_time: 100,
+            end_time: 200,
+        };
+        let info = mock_info(DAO, &coins(1000, DENOM));
+        execute(deps.as_mut(), env_at(50), info, msg).unwrap();
+        deps
+    }
+
+    fn sent(res: &Response, index: usize) -> (String, Vec<Coin>) {
+        match &res.messages[index].msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                (to_address.clone(), amount.clone())
+            }
+            msg => panic!("unexpected message {:?}", msg),
+        }
+    }
+
+    #[test]
+    fn create_validates_times() {
+        let mut deps = setup();
+
+        let msg = ExecuteMsg::CreateStream {
+            recipient: EMPLOYEE.to_string(),
+            start_time: 100,
+            end_time: 200,
+        };
+        let info = mock_info(DAO, &coins(1000, DENOM));
+        let err = execute(deps.as_mut(), env_at(200), info.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidTimes {});
+
+        let msg = ExecuteMsg::CreateStream {
+            recipient: EMPLOYEE.to_string(),
+            start_time: 200,
+            end_time: 200,
+        };
+        let err = execute(deps.as_mut(), env_at(0), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidTimes {});
+
+        let stream = query_stream(deps.as_ref(), env_at(150), 1).unwrap();
+        assert_eq!(stream.streamed, Uint128::new(500));
+        assert_eq!(stream.withdrawable, Uint128::new(500));
+    }
+
+    #[test]
+    fn recipient_withdraws_accrued() {
+        let mut deps = setup();
+
+        let msg = ExecuteMsg::Withdraw {
+            id: 1,
+            amount: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info(EMPLOYEE, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NothingToWithdraw {});
+        let err =
+            execute(deps.as_mut(), env_at(150), mock_info(DAO, &[]), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let res = execute(
+            deps.as_mut(),
+            env_at(125),
+            mock_info(EMPLOYEE, &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(sent(&res, 0), (EMPLOYEE.to_string(), coins(250, DENOM)));
+
+        let too_much = ExecuteMsg::Withdraw {
+            id: 1,
+            amount: Some(Uint128::new(300)),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(150),
+            mock_info(EMPLOYEE, &[]),
+            too_much,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AmountTooLarge {
+                available: Uint128::new(250)
+            }
+        );
+
+        // once everything is out the stream is gone
+        let res = execute(deps.as_mut(), env_at(500), mock_info(EMPLOYEE, &[]), msg).unwrap();
+        assert_eq!(sent(&res, 0), (EMPLOYEE.to_string(), coins(750, DENOM)));
+        let err = query_stream(deps.as_ref(), env_at(500), 1).unwrap_err();
+        assert!(matches!(err, StdError::NotFound { .. }));
+    }
+
+    #[test]
+    fn sender_cancels_pro_rata() {
+        let mut deps = setup();
+
+        let msg = ExecuteMsg::Withdraw {
+            id: 1,
+            amount: Some(Uint128::new(100)),
+        };
+        execute(deps.as_mut(), env_at(130), mock_info(EMPLOYEE, &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::Cancel { id: 1 };
+        let err = execute(
+            deps.as_mut(),
+            env_at(140),
+            mock_info(EMPLOYEE, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let res = execute(deps.as_mut(), env_at(140), mock_info(DAO, &[]), msg).unwrap();
+        assert_eq!(sent(&res, 0), (EMPLOYEE.to_string(), coins(300, DENOM)));
+        assert_eq!(sent(&res, 1), (DAO.to_string(), vec![coin(600, DENOM)]));
+        assert!(STREAMS.may_load(&deps.storage, 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn list_streams_by_party() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::CreateStream {
+            recipient: "contractor".to_string(),
+            start_time: 100,
+            end_time: 200,
+        };
+        execute(
+            deps.as_mut(),
+            env_at(50),
+            mock_info(EMPLOYEE, &coins(10, "ucore")),
+            msg,
+        )
+        .unwrap();
+
+        let all = query_streams(deps.as_ref(), env_at(50), None, None, None).unwrap();
+        assert_eq!(all.streams.len(), 2);
+        let page = query_streams(deps.as_ref(), env_at(50), None, Some(1), None).unwrap();
+        assert_eq!(page.streams[0].id, 2);
+        let dao =
+            query_streams(deps.as_ref(), env_at(50), Some(DAO.to_string()), None, None).unwrap();
+        assert_eq!(
+            dao.streams.iter().map(|s| s.id).collect::<Vec<_>>(),
+            vec![1]
+        );
+    }
+}
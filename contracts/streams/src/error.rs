@@ -0,0 +1,27 @@
+use cosmwasm_std::{StdError, Uint128};
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Stream must end after it starts and not be over already")]
+    InvalidTimes {},
+
+    #[error("Recipient must differ from the sender")]
+    InvalidRecipient {},
+
+    #[error("Only {available} can be withdrawn")]
+    AmountTooLarge { available: Uint128 },
+
+    #[error("Nothing to withdraw")]
+    NothingToWithdraw {},
+}
@@ -0,0 +1,54 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Uint128};
+
+#[cw_serde]
+pub struct InstantiateMsg {}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Streams the single attached coin to `recipient` linearly between the two timestamps
+    /// (seconds since epoch).
+    CreateStream {
+        recipient: String,
+        start_time: u64,
+        end_time: u64,
+    },
+    /// Recipient only; withdraws `amount`, or everything accrued so far.
+    Withdraw { id: u64, amount: Option<Uint128> },
+    /// Sender only; pays the recipient what has accrued and refunds the rest to the sender.
+    Cancel { id: u64 },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(StreamResponse)]
+    Stream { id: u64 },
+    /// Streams in id order, optionally only those `party` sends or receives.
+    #[returns(StreamsResponse)]
+    Streams {
+        party: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct StreamResponse {
+    pub id: u64,
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub denom: String,
+    pub deposit: Uint128,
+    pub withdrawn: Uint128,
+    pub start_time: u64,
+    pub end_time: u64,
+    /// Accrued so far, withdrawn or not
+    pub streamed: Uint128,
+    pub withdrawable: Uint128,
+}
+
+#[cw_serde]
+pub struct StreamsResponse {
+    pub streams: Vec<StreamResponse>,
+}
@@ -0,0 +1,39 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Stream {
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub denom: String,
+    /// Total amount paid out between `start_time` and `end_time`
+    pub deposit: Uint128,
+    pub withdrawn: Uint128,
+    /// Seconds since epoch
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+impl Stream {
+    /// Amount accrued to the recipient by `now`, withdrawn or not
+    pub fn streamed(&self, now: u64) -> Uint128 {
+        if now <= self.start_time {
+            Uint128::zero()
+        } else if now >= self.end_time {
+            self.deposit
+        } else {
+            self.deposit
+                .multiply_ratio(now - self.start_time, self.end_time - self.start_time)
+        }
+    }
+
+    /// Amount the recipient can withdraw at `now`
+    pub fn withdrawable(&self, now: u64) -> Uint128 {
+        self.streamed(now) - self.withdrawn
+    }
+}
+
+pub const NEXT_STREAM_ID: Item<u64> = Item::new("next_stream_id");
+/// Streams are removed once settled, either fully withdrawn or canceled
+pub const STREAMS: Map<u64, Stream> = Map::new("streams");
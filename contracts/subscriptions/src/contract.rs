@@ -0,0 +1,507 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdResult, Storage, Uint128,
+};
+use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+use cw_utils::PaymentError;
+
+use crate::error::ContractError;
+use crate::msg::{
+    BalanceResponse, ExecuteMsg, InstantiateMsg, PlansResponse, QueryMsg, SubscriptionResponse,
+};
+use crate::state::{Plan, Subscription, BALANCES, PLANS, PLAN_COUNT, SUBSCRIPTIONS};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:subscriptions";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    PLAN_COUNT.save(deps.storage, &0)?;
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreatePlan {
+            denom,
+            amount,
+            period,
+            grace_period,
+        } => execute_create_plan(deps, info, denom, amount, period, grace_period),
+        ExecuteMsg::ClosePlan { plan_id } => execute_close_plan(deps, info, plan_id),
+        ExecuteMsg::Deposit {} => execute_deposit(deps, info),
+        ExecuteMsg::Withdraw { denom, amount } => execute_withdraw(deps, info, denom, amount),
+        ExecuteMsg::Subscribe { plan_id } => execute_subscribe(deps, env, info, plan_id),
+        ExecuteMsg::Charge {
+            plan_id,
+            subscriber,
+        } => execute_charge(deps, env, plan_id, subscriber),
+        ExecuteMsg::Cancel {
+            plan_id,
+            subscriber,
+        } => execute_cancel(deps, info, plan_id, subscriber),
+    }
+}
+
+pub fn execute_create_plan(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    amount: Uint128,
+    period: u64,
+    grace_period: u64,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() || period == 0 {
+        return Err(ContractError::InvalidPlan {});
+    }
+    let id = PLAN_COUNT.load(deps.storage)? + 1;
+    let plan = Plan {
+        id,
+        merchant: info.sender,
+        denom,
+        amount,
+        period,
+        grace_period,
+        open: true,
+    };
+    PLANS.save(deps.storage, id, &plan)?;
+    PLAN_COUNT.save(deps.storage, &id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_plan")
+        .add_attribute("plan_id", id.to_string())
+        .add_attribute("merchant", plan.merchant))
+}
+
+pub fn execute_close_plan(
+    deps: DepsMut,
+    info: MessageInfo,
+    plan_id: u64,
+) -> Result<Response, ContractError> {
+    let mut plan = PLANS.load(deps.storage, plan_id)?;
+    if info.sender != plan.merchant {
+        return Err(ContractError::Unauthorized {});
+    }
+    plan.open = false;
+    PLANS.save(deps.storage, plan_id, &plan)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "close_plan")
+        .add_attribute("plan_id", plan_id.to_string()))
+}
+
+pub fn execute_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    if info.funds.is_empty() {
+        return Err(PaymentError::NoFunds {}.into());
+    }
+    for coin in &info.funds {
+        BALANCES.update(
+            deps.storage,
+            (&info.sender, &coin.denom),
+            |balance| -> StdResult<_> { Ok(balance.unwrap_or_default() + coin.amount) },
+        )?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "deposit")
+        .add_attribute("subscriber", info.sender))
+}
+
+pub fn execute_withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    debit(deps.storage, &info.sender, &denom, amount)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(amount.u128(), denom),
+        })
+        .add_attribute("action", "withdraw")
+        .add_attribute("subscriber", info.sender)
+        .add_attribute("amount", amount))
+}
+
+pub fn execute_subscribe(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    plan_id: u64,
+) -> Result<Response, ContractError> {
+    let plan = PLANS.load(deps.storage, plan_id)?;
+    if !plan.open {
+        return Err(ContractError::PlanClosed { plan_id });
+    }
+    if SUBSCRIPTIONS.has(deps.storage, (plan_id, &info.sender)) {
+        return Err(ContractError::AlreadySubscribed { plan_id });
+    }
+
+    debit(deps.storage, &info.sender, &plan.denom, plan.amount)?;
+    let now = env.block.time.seconds();
+    let subscription = Subscription {
+        plan_id,
+        subscriber: info.sender.clone(),
+        started_at: now,
+        next_charge: now + plan.period,
+    };
+    SUBSCRIPTIONS.save(deps.storage, (plan_id, &info.sender), &subscription)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: plan.merchant.to_string(),
+            amount: coins(plan.amount.u128(), &plan.denom),
+        })
+        .add_attribute("action", "subscribe")
+        .add_attribute("plan_id", plan_id.to_string())
+        .add_attribute("subscriber", info.sender)
+        .add_attribute("next_charge", subscription.next_charge.to_string()))
+}
+
+pub fn execute_charge(
+    deps: DepsMut,
+    env: Env,
+    plan_id: u64,
+    subscriber: String,
+) -> Result<Response, ContractError> {
+    let subscriber = deps.api.addr_validate(&subscriber)?;
+    let plan = PLANS.load(deps.storage, plan_id)?;
+    let mut subscription = SUBSCRIPTIONS
+        .may_load(deps.storage, (plan_id, &subscriber))?
+        .ok_or(ContractError::NotSubscribed { plan_id })?;
+    let now = env.block.time.seconds();
+    if now < subscription.next_charge {
+        return Err(ContractError::NotDue {
+            next_charge: subscription.next_charge,
+        });
+    }
+
+    let response = Response::new()
+        .add_attribute("action", "charge")
+        .add_attribute("plan_id", plan_id.to_string())
+        .add_attribute("subscriber", subscriber.as_str());
+    let balance = BALANCES
+        .may_load(deps.storage, (&subscriber, &plan.denom))?
+        .unwrap_or_default();
+    if balance < plan.amount {
+        // unpaid past the grace period, the subscription lapses
+        if now > subscription.next_charge + plan.grace_period {
+            SUBSCRIPTIONS.remove(deps.storage, (plan_id, &subscriber));
+            return Ok(response.add_attribute("status", "lapsed"));
+        }
+        return Err(ContractError::InsufficientBalance {
+            required: plan.amount,
+        });
+    }
+
+    debit(deps.storage, &subscriber, &plan.denom, plan.amount)?;
+    // one period per charge, overdue periods are caught up by charging again
+    subscription.next_charge += plan.period;
+    SUBSCRIPTIONS.save(deps.storage, (plan_id, &subscriber), &subscription)?;
+
+    Ok(response
+        .add_message(BankMsg::Send {
+            to_address: plan.merchant.to_string(),
+            amount: coins(plan.amount.u128(), &plan.denom),
+        })
+        .add_attribute("status", "paid")
+        .add_attribute("next_charge", subscription.next_charge.to_string()))
+}
+
+pub fn execute_cancel(
+    deps: DepsMut,
+    info: MessageInfo,
+    plan_id: u64,
+    subscriber: String,
+) -> Result<Response, ContractError> {
+    let subscriber = deps.api.addr_validate(&subscriber)?;
+    let plan = PLANS.load(deps.storage, plan_id)?;
+    if info.sender != subscriber && info.sender != plan.merchant {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !SUBSCRIPTIONS.has(deps.storage, (plan_id, &subscriber)) {
+        return Err(ContractError::NotSubscribed { plan_id });
+    }
+    SUBSCRIPTIONS.remove(deps.storage, (plan_id, &subscriber));
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel")
+        .add_attribute("plan_id", plan_id.to_string())
+        .add_attribute("subscriber", subscriber))
+}
+
+fn debit(
+    storage: &mut dyn Storage,
+    address: &Addr,
+    denom: &str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let balance = BALANCES
+        .may_load(storage, (address, denom))?
+        .unwrap_or_default();
+    let remaining = balance
+        .checked_sub(amount)
+        .map_err(|_| ContractError::InsufficientBalance { required: amount })?;
+    if remaining.is_zero() {
+        BALANCES.remove(storage, (address, denom));
+    } else {
+        BALANCES.save(storage, (address, denom), &remaining)?;
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Plan { plan_id } => to_json_binary(&PLANS.load(deps.storage, plan_id)?),
+        QueryMsg::Plans { start_after, limit } => {
+            to_json_binary(&query_plans(deps, start_after, limit)?)
+        }
+        QueryMsg::Subscription {
+            plan_id,
+            subscriber,
+        } => to_json_binary(&query_subscription(deps, env, plan_id, subscriber)?),
+        QueryMsg::Balance { address, denom } => {
+            to_json_binary(&query_balance(deps, address, denom)?)
+        }
+    }
+}
+
+fn query_plans(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PlansResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let plans = PLANS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, plan)| plan))
+        .collect::<StdResult<_>>()?;
+    Ok(PlansResponse { plans })
+}
+
+fn query_subscription(
+    deps: Deps,
+    env: Env,
+    plan_id: u64,
+    subscriber: String,
+) -> StdResult<SubscriptionResponse> {
+    let subscriber = deps.api.addr_validate(&subscriber)?;
+    let plan = PLANS.load(deps.storage, plan_id)?;
+    let subscription = SUBSCRIPTIONS.load(deps.storage, (plan_id, &subscriber))?;
+    let now = env.block.time.seconds();
+    Ok(SubscriptionResponse {
+        due: now >= subscription.next_charge,
+        active: now <= subscription.next_charge + plan.grace_period,
+        subscription,
+    })
+}
+
+fn query_balance(deps: Deps, address: String, denom: String) -> StdResult<BalanceResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let balance = BALANCES
+        .may_load(deps.storage, (&address, &denom))?
+        .unwrap_or_default();
+    Ok(BalanceResponse { balance })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{attr, from_json, CosmosMsg, OwnedDeps, Timestamp};
+
+    const MERCHANT: &str = "merchant";
+    const ALICE: &str = "alice";
+    const DENOM: &str = "ucore";
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    }
+
+    // plan 1 charges 10 every 100 seconds with a 20 second grace period,
+    // alice deposits 25 and subscribes at 0
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            env_at(0),
+            mock_info("creator", &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        let msg = ExecuteMsg::CreatePlan {
+            denom: DENOM.to_string(),
+            amount: Uint128::new(10),
+            period: 100,
+            grace_period: 20,
+        };
+        execute(deps.as_mut(), env_at(0), mock_info(MERCHANT, &[]), msg).unwrap();
+        execute(
+            deps.as_mut(),
+            env_at(0),
+            mock_info(ALICE, &coins(25, DENOM)),
+            ExecuteMsg::Deposit {},
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env_at(0),
+            mock_info(ALICE, &[]),
+            ExecuteMsg::Subscribe { plan_id: 1 },
+        )
+        .unwrap();
+        deps
+    }
+
+    fn charge(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        seconds: u64,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Charge {
+            plan_id: 1,
+            subscriber: ALICE.to_string(),
+        };
+        execute(
+            deps.as_mut(),
+            env_at(seconds),
+            mock_info("anyone", &[]),
+            msg,
+        )
+    }
+
+    fn balance(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>) -> Uint128 {
+        let msg = QueryMsg::Balance {
+            address: ALICE.to_string(),
+            denom: DENOM.to_string(),
+        };
+        let res: BalanceResponse =
+            from_json(query(deps.as_ref(), env_at(0), msg).unwrap()).unwrap();
+        res.balance
+    }
+
+    #[test]
+    fn charging() {
+        let mut deps = setup();
+        assert_eq!(balance(&deps), Uint128::new(15));
+
+        let err = charge(&mut deps, 99).unwrap_err();
+        assert_eq!(err, ContractError::NotDue { next_charge: 100 });
+
+        let res = charge(&mut deps, 100).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: MERCHANT.to_string(),
+                amount: coins(10, DENOM),
+            })
+        );
+        assert_eq!(balance(&deps), Uint128::new(5));
+
+        // short on funds but still within the grace period
+        let err = charge(&mut deps, 210).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientBalance {
+                required: Uint128::new(10)
+            }
+        );
+        let msg = QueryMsg::Subscription {
+            plan_id: 1,
+            subscriber: ALICE.to_string(),
+        };
+        let res: SubscriptionResponse =
+            from_json(query(deps.as_ref(), env_at(210), msg).unwrap()).unwrap();
+        assert!(res.due);
+        assert!(res.active);
+
+        execute(
+            deps.as_mut(),
+            env_at(210),
+            mock_info(ALICE, &coins(5, DENOM)),
+            ExecuteMsg::Deposit {},
+        )
+        .unwrap();
+        charge(&mut deps, 210).unwrap();
+        assert_eq!(balance(&deps), Uint128::zero());
+    }
+
+    #[test]
+    fn lapses_after_grace_period() {
+        let mut deps = setup();
+        charge(&mut deps, 100).unwrap();
+
+        let res = charge(&mut deps, 221).unwrap();
+        assert!(res.messages.is_empty());
+        assert!(res.attributes.contains(&attr("status", "lapsed")));
+
+        let err = charge(&mut deps, 221).unwrap_err();
+        assert_eq!(err, ContractError::NotSubscribed { plan_id: 1 });
+    }
+
+    #[test]
+    fn cancelling() {
+        let mut deps = setup();
+        let msg = ExecuteMsg::Cancel {
+            plan_id: 1,
+            subscriber: ALICE.to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(10),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(deps.as_mut(), env_at(10), mock_info(MERCHANT, &[]), msg).unwrap();
+        let err = charge(&mut deps, 100).unwrap_err();
+        assert_eq!(err, ContractError::NotSubscribed { plan_id: 1 });
+
+        // closed plans take no new subscribers
+        execute(
+            deps.as_mut(),
+            env_at(10),
+            mock_info(MERCHANT, &[]),
+            ExecuteMsg::ClosePlan { plan_id: 1 },
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env_at(10),
+            mock_info(ALICE, &[]),
+            ExecuteMsg::Subscribe { plan_id: 1 },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::PlanClosed { plan_id: 1 });
+    }
+}
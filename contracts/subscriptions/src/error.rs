@@ -0,0 +1,36 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Plan amount and period must be positive")]
+    InvalidPlan {},
+
+    #[error("Plan {plan_id} no longer accepts subscribers")]
+    PlanClosed { plan_id: u64 },
+
+    #[error("Already subscribed to plan {plan_id}")]
+    AlreadySubscribed { plan_id: u64 },
+
+    #[error("No subscription to plan {plan_id}")]
+    NotSubscribed { plan_id: u64 },
+
+    #[error("Next charge is due at {next_charge}")]
+    NotDue { next_charge: u64 },
+
+    #[error("Deposited balance is below the {required} needed")]
+    InsufficientBalance { required: Uint128 },
+}
@@ -0,0 +1,79 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Uint128;
+
+use crate::state::{Plan, Subscription};
+
+#[cw_serde]
+pub struct InstantiateMsg {}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Registers a plan with the sender as merchant.
+    CreatePlan {
+        denom: String,
+        amount: Uint128,
+        period: u64,
+        grace_period: u64,
+    },
+    /// Stops new subscriptions to the plan. Merchant only.
+    ClosePlan {
+        plan_id: u64,
+    },
+    /// Adds the funds sent along to the sender's balance.
+    Deposit {},
+    Withdraw {
+        denom: String,
+        amount: Uint128,
+    },
+    /// Authorizes the plan's merchant to pull every period's amount from the sender's
+    /// balance. The first period is charged right away.
+    Subscribe {
+        plan_id: u64,
+    },
+    /// Charges a due period. Anyone can call it. A subscription that can't pay once its
+    /// grace period is over is cancelled instead.
+    Charge {
+        plan_id: u64,
+        subscriber: String,
+    },
+    /// Ends a subscription. Callable by the subscriber or the plan's merchant.
+    Cancel {
+        plan_id: u64,
+        subscriber: String,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(Plan)]
+    Plan { plan_id: u64 },
+    #[returns(PlansResponse)]
+    Plans {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(SubscriptionResponse)]
+    Subscription { plan_id: u64, subscriber: String },
+    #[returns(BalanceResponse)]
+    Balance { address: String, denom: String },
+}
+
+#[cw_serde]
+pub struct PlansResponse {
+    pub plans: Vec<Plan>,
+}
+
+#[cw_serde]
+pub struct SubscriptionResponse {
+    pub subscription: Subscription,
+    /// The next charge can be triggered
+    pub due: bool,
+    /// The subscription is paid up or still within its grace period
+    pub active: bool,
+}
+
+#[cw_serde]
+pub struct BalanceResponse {
+    pub balance: Uint128,
+}
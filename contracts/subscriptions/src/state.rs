@@ -0,0 +1,33 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Plan {
+    pub id: u64,
+    pub merchant: Addr,
+    pub denom: String,
+    /// Charged once per period
+    pub amount: Uint128,
+    /// Seconds between charges
+    pub period: u64,
+    /// Seconds a due charge may stay unpaid before the subscription lapses
+    pub grace_period: u64,
+    /// Closed plans keep charging existing subscribers but take no new ones
+    pub open: bool,
+}
+
+#[cw_serde]
+pub struct Subscription {
+    pub plan_id: u64,
+    pub subscriber: Addr,
+    pub started_at: u64,
+    /// Block time in seconds the next charge becomes due
+    pub next_charge: u64,
+}
+
+pub const PLAN_COUNT: Item<u64> = Item::new("plan_count");
+pub const PLANS: Map<u64, Plan> = Map::new("plans");
+pub const SUBSCRIPTIONS: Map<(u64, &Addr), Subscription> = Map::new("subscriptions");
+/// Pre-deposited funds subscriptions are charged from, by subscriber and denom
+pub const BALANCES: Map<(&Addr, &str), Uint128> = Map::new("balances");
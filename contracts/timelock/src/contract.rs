@@ -2,8 +2,8 @@
 use cosmwasm_std::entry_point;
 use cosmwasm_std::WasmMsg::Execute;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError,
-    StdResult, Uint64,
+    from_binary, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, Event, MessageInfo, Order,
+    Response, StdError, StdResult, Storage, Uint64,
 };
 use cw2::set_contract_version;
 use cw_storage_plus::Bound;
@@ -11,7 +11,7 @@ use cw_utils::{Duration, Scheduled};
 use std::ops::Add;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, OperationListResponse, QueryMsg};
+use crate::msg::{ExecuteMsg, InstantiateMsg, OperationListResponse, OperationResponse, QueryMsg};
 use crate::state::{Operation, OperationStatus, Timelock, CONFIG, OPERATION_LIST, OPERATION_SEQ};
 
 // version info for migration info
@@ -136,6 +136,10 @@ pub fn execute_schedule(
         return Err(ContractError::MinDelayNotSatisfied {});
     }
 
+    if target == env.contract.address && !is_self_administration(&from_binary(&data)?) {
+        return Err(ContractError::InvalidSelfOperation {});
+    }
+
     let id = OPERATION_SEQ.update::<_, StdError>(deps.storage, |id| Ok(id.add(Uint64::new(1))))?;
 
     let mut executors = None;
@@ -164,6 +168,7 @@ pub fn execute_schedule(
     OPERATION_LIST.save(deps.storage, id.u64(), &new_operation)?;
 
     Ok(Response::new()
+        .add_event(operation_event("schedule", &new_operation))
         .add_attribute("Schedule ", "success")
         .add_attribute("Operation ID: ", id)
         .add_attribute("Proposer: ", new_operation.proposer)
@@ -177,7 +182,7 @@ pub fn execute_execute(
     info: MessageInfo,
     operation_id: Uint64,
 ) -> Result<Response, ContractError> {
-    let mut operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
+    let mut operation = load_operation(deps.storage, operation_id)?;
 
     //is delay ended
     if !operation.execution_time.is_triggered(&env.block) {
@@ -191,7 +196,9 @@ pub fn execute_execute(
             .map(|c| c.contains(&info.sender))
             .unwrap()
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::ExecutorNotAllowed {
+            addr: info.sender.to_string(),
+        });
     }
 
     if operation.status == OperationStatus::Done {
@@ -202,7 +209,17 @@ pub fn execute_execute(
     operation.status = OperationStatus::Done;
     OPERATION_LIST.save(deps.storage, operation_id.u64(), &operation)?;
 
+    //operations targeting the timelock itself are handled in place with the timelock as sender
+    if operation.target == env.contract.address {
+        let msg: ExecuteMsg = from_binary(&operation.data)?;
+        let res = execute_self_administration(deps, env, msg)?;
+        return Ok(res
+            .add_event(operation_event("execute", &operation))
+            .add_attribute("executor", &info.sender.to_string()));
+    }
+
     Ok(Response::new()
+        .add_event(operation_event("execute", &operation))
         .add_message(CosmosMsg::Wasm(Execute {
             contract_addr: operation.target.to_string(),
             msg: operation.data,
@@ -217,7 +234,7 @@ pub fn execute_cancel(
     info: MessageInfo,
     operation_id: Uint64,
 ) -> Result<Response, ContractError> {
-    let operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
+    let operation = load_operation(deps.storage, operation_id)?;
 
     if operation.status == OperationStatus::Done {
         return Err(ContractError::NotDeletable {});
@@ -230,12 +247,70 @@ pub fn execute_cancel(
     OPERATION_LIST.remove(deps.storage, operation_id.u64());
 
     Ok(Response::new()
+        .add_event(operation_event("cancel", &operation))
         .add_attribute("Method", "cancel")
         .add_attribute("sender", &info.sender.to_string())
         .add_attribute("operation_id", operation_id.to_string())
         .add_attribute("Result", "Success"))
 }
 
+fn load_operation(storage: &dyn Storage, operation_id: Uint64) -> Result<Operation, ContractError> {
+    OPERATION_LIST
+        .may_load(storage, operation_id.u64())?
+        .ok_or(ContractError::OperationNotFound { id: operation_id })
+}
+
+fn is_self_administration(msg: &ExecuteMsg) -> bool {
+    matches!(
+        msg,
+        ExecuteMsg::RevokeAdmin { .. }
+            | ExecuteMsg::AddProposer { .. }
+            | ExecuteMsg::RemoveProposer { .. }
+            | ExecuteMsg::UpdateMinDelay { .. }
+            | ExecuteMsg::Freeze {}
+    )
+}
+
+fn execute_self_administration(
+    deps: DepsMut,
+    env: Env,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let info = MessageInfo {
+        sender: env.contract.address.clone(),
+        funds: vec![],
+    };
+    match msg {
+        ExecuteMsg::RevokeAdmin { admin_address } => {
+            execute_revoke_admin(deps, env, info, admin_address)
+        }
+        ExecuteMsg::AddProposer { proposer_address } => {
+            execute_add_proposer(deps, env, info, proposer_address)
+        }
+        ExecuteMsg::RemoveProposer { proposer_address } => {
+            execute_remove_proposer(deps, env, info, proposer_address)
+        }
+        ExecuteMsg::UpdateMinDelay { new_delay } => {
+            execute_update_min_delay(deps, env, info, new_delay)
+        }
+        ExecuteMsg::Freeze {} => execute_freeze(deps, env, info),
+        _ => Err(ContractError::InvalidSelfOperation {}),
+    }
+}
+
+/// Event describing the operation payload so indexers don't need to query it back
+fn operation_event(action: &str, operation: &Operation) -> Event {
+    Event::new("timelock_operation")
+        .add_attribute("action", action)
+        .add_attribute("operation_id", operation.id)
+        .add_attribute("proposer", operation.proposer.as_str())
+        .add_attribute("target", operation.target.as_str())
+        .add_attribute("title", operation.title.as_str())
+        .add_attribute("description", operation.description.as_str())
+        .add_attribute("execution_time", operation.execution_time.to_string())
+        .add_attribute("data", operation.data.to_base64())
+}
+
 pub fn execute_revoke_admin(
     deps: DepsMut,
     _env: Env,
@@ -387,6 +462,9 @@ pub fn execute_freeze(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
+        QueryMsg::GetOperation { operation_id } => {
+            to_binary(&query_get_operation(deps, operation_id)?)
+        }
         QueryMsg::GetOperationStatus { operation_id } => {
             to_binary(&query_get_operation_status(deps, operation_id)?)
         }
@@ -394,9 +472,18 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_binary(&query_get_execution_time(deps, operation_id)?)
         }
         QueryMsg::GetAdmins {} => to_binary(&query_get_admins(deps)?),
-        QueryMsg::GetOperations { start_after, limit } => {
-            to_binary(&query_get_operations(deps, start_after, limit)?)
-        }
+        QueryMsg::GetOperations {
+            start_after,
+            start_before,
+            limit,
+            order,
+        } => to_binary(&query_get_operations(
+            deps,
+            start_after,
+            start_before,
+            limit,
+            order,
+        )?),
         QueryMsg::GetMinDelay {} => to_binary(&query_get_min_delay(deps)?),
         QueryMsg::GetProposers {} => to_binary(&query_get_proposers(deps)?),
         QueryMsg::GetExecutors { operation_id } => {
@@ -405,13 +492,23 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     }
 }
 
+// queries can only fail with StdError, the typed error is kept as its message
+fn query_operation(deps: Deps, operation_id: Uint64) -> StdResult<Operation> {
+    load_operation(deps.storage, operation_id).map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+pub fn query_get_operation(deps: Deps, operation_id: Uint64) -> StdResult<OperationResponse> {
+    let operation = query_operation(deps, operation_id)?;
+    Ok(operation.into())
+}
+
 pub fn query_get_operation_status(deps: Deps, operation_id: Uint64) -> StdResult<OperationStatus> {
-    let operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
+    let operation = query_operation(deps, operation_id)?;
     Ok(operation.status)
 }
 
 pub fn query_get_execution_time(deps: Deps, operation_id: Uint64) -> StdResult<String> {
-    let operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
+    let operation = query_operation(deps, operation_id)?;
     Ok(operation.execution_time.to_string())
 }
 
@@ -427,17 +524,29 @@ const DEFAULT_LIMIT: u32 = 10;
 pub fn query_get_operations(
     deps: Deps,
     start_after: Option<u64>,
+    start_before: Option<u64>,
     limit: Option<u32>,
+    order: Option<Order>,
 ) -> StdResult<OperationListResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = start_after.map(Bound::exclusive);
-    let operations: StdResult<Vec<_>> = OPERATION_LIST
-        .range(deps.storage, start, None, Order::Ascending)
-        .take(limit)
-        .collect();
-
+    let min = start_after.map(Bound::exclusive);
+    let max = start_before.map(Bound::exclusive);
+    let order = order.unwrap_or(Order::Ascending);
+    // read one extra entry to know whether another page follows
+    let mut operations = OPERATION_LIST
+        .range(deps.storage, min, max, order)
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let next_start_after = if operations.len() > limit {
+        operations.truncate(limit);
+        operations.last().map(|(id, _)| *id)
+    } else {
+        None
+    };
     let res = OperationListResponse {
-        operationList: operations?.into_iter().map(|l| l.1.into()).collect(),
+        operationList: operations.into_iter().map(|l| l.1.into()).collect(),
+        next_start_after,
     };
     Ok(res)
 }
@@ -453,14 +562,14 @@ pub fn query_get_proposers(deps: Deps) -> StdResult<Vec<Addr>> {
 }
 
 pub fn query_get_executors(deps: Deps, operation_id: Uint64) -> StdResult<Vec<Addr>> {
-    let operation = OPERATION_LIST.load(deps.storage, operation_id.u64())?;
+    let operation = query_operation(deps, operation_id)?;
     Ok(operation.executors.unwrap_or_default())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
     use cosmwasm_std::Timestamp;
     use cw_utils::Scheduled;
 
@@ -532,6 +641,15 @@ mod tests {
         let res = query_get_execution_time(deps.as_ref(), Uint64::new(1));
         println!("{:?}, {}", res, env.block.time);
 
+        let operation = query_get_operation(deps.as_ref(), Uint64::new(1)).unwrap();
+        assert_eq!(operation.status, OperationStatus::Pending);
+        assert_eq!(operation.proposer, Addr::unchecked("prop1"));
+        assert_eq!(operation.target, Addr::unchecked("target"));
+        assert_eq!(operation.data, data);
+        assert_eq!(operation.title, title);
+        assert_eq!(operation.description, description);
+        assert_eq!(operation.executors, None);
+
         //try Execute() sender "prop1" execution_time > env.block.time
         let res =
             execute_execute(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap_err();
@@ -543,6 +661,15 @@ mod tests {
         let res =
             execute_execute(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap();
         println!("{:?}", res);
+        let event = &res.events[0];
+        assert_eq!(event.ty, "timelock_operation");
+        assert!(event
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "title" && attr.value == title));
+
+        let operation = query_get_operation(deps.as_ref(), Uint64::new(1)).unwrap();
+        assert_eq!(operation.status, OperationStatus::Done);
     }
 
     #[test]
@@ -582,8 +709,14 @@ mod tests {
         .unwrap();
         println!("{:?}", res);
 
-        let res =
-            query_get_operations(deps.as_ref(), Option::Some(0u64), Option::Some(1u32)).unwrap();
+        let res = query_get_operations(
+            deps.as_ref(),
+            Option::Some(0u64),
+            None,
+            Option::Some(1u32),
+            None,
+        )
+        .unwrap();
         println!("{:?}", res);
         //time pass
         env.block.time = Timestamp::from_seconds(120);
@@ -591,7 +724,12 @@ mod tests {
         //try Execute() sender "prop1" execution_time <= env.block.time executors "exec1, exec2"
         let res =
             execute_execute(deps.as_mut(), env.clone(), info.clone(), Uint64::new(1)).unwrap_err();
-        assert_eq!(res, ContractError::Unauthorized {});
+        assert_eq!(
+            res,
+            ContractError::ExecutorNotAllowed {
+                addr: "prop1".to_string()
+            }
+        );
 
         let info = mock_info("exec1", &[]);
         //Execute() sender "exec1" execution_time <= env.block.time executors "exec1, exec2"
@@ -600,6 +738,91 @@ mod tests {
         println!("{:?}", res);
     }
 
+    #[test]
+    fn test_missing_operation() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            admins: None,
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(10),
+        };
+        let info = mock_info("prop1", &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let res =
+            execute_execute(deps.as_mut(), mock_env(), info.clone(), Uint64::new(7)).unwrap_err();
+        assert_eq!(res, ContractError::OperationNotFound { id: Uint64::new(7) });
+        let res = execute_cancel(deps.as_mut(), mock_env(), info, Uint64::new(7)).unwrap_err();
+        assert_eq!(res, ContractError::OperationNotFound { id: Uint64::new(7) });
+
+        let res = query_get_operation_status(deps.as_ref(), Uint64::new(7)).unwrap_err();
+        assert_eq!(res, StdError::generic_err("Operation 7 not found."));
+    }
+
+    #[test]
+    fn test_get_operations_paging() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = InstantiateMsg {
+            admins: None,
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(10),
+        };
+        let info = mock_info("prop1", &[]);
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        for i in 0..5 {
+            execute_schedule(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                "target".to_string(),
+                to_binary(&"data").unwrap(),
+                format!("op {}", i),
+                "test desc".to_string(),
+                Scheduled::AtTime(Timestamp::from_seconds(120)),
+                None,
+            )
+            .unwrap();
+        }
+        let ids = |res: &OperationListResponse| {
+            res.operationList
+                .iter()
+                .map(|op| op.id.u64())
+                .collect::<Vec<_>>()
+        };
+
+        // newest first, following the returned cursor
+        let res = query_get_operations(deps.as_ref(), None, None, Some(2), Some(Order::Descending))
+            .unwrap();
+        assert_eq!(ids(&res), vec![5, 4]);
+        assert_eq!(res.next_start_after, Some(4));
+        let res = query_get_operations(
+            deps.as_ref(),
+            None,
+            res.next_start_after,
+            Some(2),
+            Some(Order::Descending),
+        )
+        .unwrap();
+        assert_eq!(ids(&res), vec![3, 2]);
+        let res = query_get_operations(
+            deps.as_ref(),
+            None,
+            res.next_start_after,
+            Some(2),
+            Some(Order::Descending),
+        )
+        .unwrap();
+        assert_eq!(ids(&res), vec![1]);
+        assert_eq!(res.next_start_after, None);
+
+        // both bounds are exclusive
+        let res = query_get_operations(deps.as_ref(), Some(1), Some(5), None, None).unwrap();
+        assert_eq!(ids(&res), vec![2, 3, 4]);
+        assert_eq!(res.next_start_after, None);
+    }
+
     #[test]
     fn test_cancel() {
         let mut deps = mock_dependencies();
@@ -860,4 +1083,85 @@ mod tests {
         .unwrap_err();
         assert_eq!(res, ContractError::Unauthorized {});
     }
+
+    #[test]
+    fn test_self_administration() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = InstantiateMsg {
+            admins: None,
+            proposers: vec!["prop1".to_string()],
+            min_delay: Duration::Time(10),
+        };
+        instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let info = mock_info("prop1", &[]);
+        fn schedule(deps: DepsMut, msg: &ExecuteMsg) -> Result<Response, ContractError> {
+            let mut env = mock_env();
+            env.block.time = Timestamp::from_seconds(100);
+            execute_schedule(
+                deps,
+                env,
+                mock_info("prop1", &[]),
+                MOCK_CONTRACT_ADDR.to_string(),
+                to_binary(msg).unwrap(),
+                "Self administration".to_string(),
+                "".to_string(),
+                Scheduled::AtTime(Timestamp::from_seconds(120)),
+                None,
+            )
+        }
+
+        //only admin methods can target the timelock itself
+        let res = schedule(
+            deps.as_mut(),
+            &ExecuteMsg::Execute {
+                operation_id: Uint64::new(1),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::InvalidSelfOperation {});
+
+        schedule(
+            deps.as_mut(),
+            &ExecuteMsg::AddProposer {
+                proposer_address: "prop2".to_string(),
+            },
+        )
+        .unwrap();
+        schedule(
+            deps.as_mut(),
+            &ExecuteMsg::UpdateMinDelay {
+                new_delay: Duration::Time(50),
+            },
+        )
+        .unwrap();
+        schedule(
+            deps.as_mut(),
+            &ExecuteMsg::RevokeAdmin {
+                admin_address: "creator".to_string(),
+            },
+        )
+        .unwrap();
+
+        env.block.time = Timestamp::from_seconds(120);
+        for id in 1..=3 {
+            let res =
+                execute_execute(deps.as_mut(), env.clone(), info.clone(), Uint64::new(id)).unwrap();
+            assert!(res.messages.is_empty());
+        }
+
+        assert_eq!(
+            query_get_proposers(deps.as_ref()).unwrap(),
+            vec![Addr::unchecked("prop1"), Addr::unchecked("prop2")]
+        );
+        assert_eq!(
+            query_get_min_delay(deps.as_ref()).unwrap(),
+            Duration::Time(50).to_string()
+        );
+        assert_eq!(
+            query_get_admins(deps.as_ref()).unwrap(),
+            vec![Addr::unchecked(MOCK_CONTRACT_ADDR)]
+        );
+    }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint64};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -29,6 +29,15 @@ pub enum ContractError {
 
     #[error("Changes can not be made on a frozen Timelock contract.")]
     TimelockFrozen {},
+
+    #[error("Operations targeting the Timelock itself can only call admin methods.")]
+    InvalidSelfOperation {},
+
+    #[error("Operation {id} not found.")]
+    OperationNotFound { id: Uint64 },
+
+    #[error("Address {addr} is not an executor of this operation.")]
+    ExecutorNotAllowed { addr: String },
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
 use crate::state::{Operation, OperationStatus};
-use cosmwasm_std::{Addr, Binary, Uint64};
+use cosmwasm_std::{Addr, Binary, Order, Uint64};
 use cw_utils::{Duration, Scheduled};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -52,6 +52,10 @@ pub enum ExecuteMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
+    GetOperation {
+        operation_id: Uint64,
+    },
+
     GetOperationStatus {
         operation_id: Uint64,
     },
@@ -62,9 +66,13 @@ pub enum QueryMsg {
 
     GetAdmins {},
 
+    /// Lists operations by id, oldest first unless `order` is descending.
+    /// `start_after` and `start_before` are exclusive bounds and can be combined.
     GetOperations {
         start_after: Option<u64>,
+        start_before: Option<u64>,
         limit: Option<u32>,
+        order: Option<Order>,
     },
 
     GetMinDelay {},
@@ -111,4 +119,7 @@ impl From<Operation> for OperationResponse {
 #[allow(non_snake_case)]
 pub struct OperationListResponse {
     pub operationList: Vec<OperationResponse>,
+    /// Id of the last returned operation when more follow, to be passed back as `start_after`
+    /// (or `start_before` when listing in descending order) to fetch the next page
+    pub next_start_after: Option<u64>,
 }
\ No newline at end of file
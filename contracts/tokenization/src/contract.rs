@@ -1,46 +1,104 @@
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, AssetType as MsgAssetType};
-use crate::state::{TokenizedAsset, ASSETS, FRACTIONAL_BALANCES, NEXT_TOKEN_ID, AssetType as StateAssetType};
+use crate::msg::{AssetComplianceResponse, AssetHistoryResponse, AssetHoldersResponse, AssetInfoResponse, AssetSummaryResponse, EligibilityResponse, ExecuteMsg, HolderBalance, InstantiateMsg, ListingResponse, ListingsResponse, MetadataVersionResponse, MigrateMsg, QueryMsg, SmartTokenInfoResponse, AssetType as MsgAssetType, ValuationHistoryResponse, ValuationResponse};
+use crate::state::{AssetCompliance, AssetStatus, Buyout, Config, Listing, MetadataVersion, TokenizedAsset, ALLOWLIST, ASSETS, ASSET_COMPLIANCE, ASSET_HISTORY, BUYOUTS, COMPLIANCE_OFFICERS, CONFIG, FRACTIONAL_BALANCES, HOLDER_STATS, LISTINGS, METADATA_VERSIONS, NEXT_LISTING_ID, NEXT_TOKEN_ID, REGISTRARS, AssetType as StateAssetType, Valuation, APPRAISERS, VALUATION_HISTORY, VALUATION_VERSIONS};
 use cosmwasm_std::{
-    entry_point, to_binary, BankMsg, Binary, CanonicalAddr, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Uint128, WasmMsg
+    entry_point, to_binary, Addr, BankMsg, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128, WasmMsg
 };
-use cw2::set_contract_version;
-use crate::smarttoken::{BALANCES, TOKEN_INFO};
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
+use smarttoken::{balance_at, TokenInfo, BALANCES, TOKEN_INFO};
+use coreum_wasm_sdk::assetft;
 use coreum_wasm_sdk::core::{CoreumMsg, CoreumQueries};
+use fractions::{fraction_balance, migrate_legacy_balances, set_fraction_balance};
 
 const CONTRACT_NAME: &str = "asset-tokenization";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Last version storing fractional balances keyed by holder first
+const LEGACY_VERSION: &str = "0.1.0";
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
 
 #[entry_point]
 pub fn instantiate(
-    deps: DepsMut,
-    _env: Env,
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response<CoreumMsg>, ContractError> {
     let owner = deps.api.addr_validate(&msg.owner)?;
+    if msg.platform_fee > Decimal::one() {
+        return Err(ContractError::InvalidPlatformFee {});
+    }
+    if msg.buyout_threshold <= Decimal::percent(50) || msg.buyout_threshold > Decimal::one() {
+        return Err(ContractError::InvalidBuyoutThreshold {});
+    }
+    let oracle = msg.oracle.map(|oracle| deps.api.addr_validate(&oracle)).transpose()?;
+    CONFIG.save(deps.storage, &Config { owner: owner.clone(), payment_denom: msg.payment_denom, platform_fee: msg.platform_fee, buyout_threshold: msg.buyout_threshold, oracle })?;
     NEXT_TOKEN_ID.save(deps.storage, &1)?;
+    NEXT_LISTING_ID.save(deps.storage, &1)?;
+
+    // Issue the smart token backing the platform; the chain gives the initial supply to the contract as
+    // issuer, so it is passed on to the owner who is credited with it
+    let denom = format!("{}-{}", msg.subunit, env.contract.address).to_lowercase();
+    TOKEN_INFO.save(deps.storage, &TokenInfo { owner: owner.clone(), total_supply: msg.initial_amount, denom: denom.clone(), precision: u32::from(msg.precision) })?;
+    if !msg.initial_amount.is_zero() {
+        BALANCES.save(deps.storage, owner.clone(), &msg.initial_amount, env.block.height)?;
+    }
+    let issue_msg = CoreumMsg::AssetFT(assetft::Msg::Issue {
+        symbol: msg.symbol,
+        subunit: msg.subunit,
+        precision: u32::from(msg.precision),
+        initial_amount: msg.initial_amount,
+        description: None,
+        features: Some(vec![0]), // 0 - minting
+        burn_rate: Some("0".into()),
+        send_commission_rate: Some("0".into()),
+    });
+
+    let mut res = Response::new().add_message(issue_msg);
+    if !msg.initial_amount.is_zero() {
+        res = res.add_message(BankMsg::Send { to_address: owner.to_string(), amount: vec![Coin::new(msg.initial_amount.u128(), denom.clone())] });
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    Ok(Response::new().add_attribute("method", "instantiate").add_attribute("owner", owner.to_string()))
+    Ok(res.add_attribute("method", "instantiate").add_attribute("owner", owner.to_string()).add_attribute("denom", denom))
 }
 
 #[entry_point]
 pub fn execute(
     deps:  DepsMut<CoreumQueries>,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response<CoreumMsg>, ContractError> {
     match msg {
-        ExecuteMsg::CreateAsset { total_supply, price, uri, asset_type } => create_asset(deps, info, total_supply, price, uri, asset_type),
+        ExecuteMsg::CreateAsset { total_supply, price, uri, asset_type } => create_asset(deps, env, info, total_supply, price, uri, asset_type),
         ExecuteMsg::TransferOwnership { token_id, to, amount } => transfer_ownership(deps, info, token_id, to, amount),
-        ExecuteMsg::MintSmartToken { to, amount } => execute_mint_smart_token(deps, info, to, amount),
-        ExecuteMsg::TransferSmartToken { to, amount } => execute_transfer_smart_token(deps, info, to, amount),
+        ExecuteMsg::BuyFraction { token_id, amount } => buy_fraction(deps, info, token_id, amount),
+        ExecuteMsg::TransferFraction { token_id, to, amount } => transfer_fraction(deps, info, token_id, to, amount),
+        ExecuteMsg::ListFraction { token_id, amount, price } => list_fraction(deps, info, token_id, amount, price),
+        ExecuteMsg::BuyListedFraction { listing_id, amount } => buy_listed_fraction(deps, info, listing_id, amount),
+        ExecuteMsg::CancelListing { listing_id } => cancel_listing(deps, info, listing_id),
+        ExecuteMsg::SetRegistrar { address, enabled } => set_registrar(deps, info, address, enabled),
+        ExecuteMsg::UpdateAssetMetadata { token_id, uri, document_hashes } => update_asset_metadata(deps, env, info, token_id, uri, document_hashes),
+        ExecuteMsg::SetComplianceOfficer { address, enabled } => set_compliance_officer(deps, info, address, enabled),
+        ExecuteMsg::SetAssetCompliance { token_id, allowlist_enabled, holding_cap } => set_asset_compliance(deps, info, token_id, allowlist_enabled, holding_cap),
+        ExecuteMsg::UpdateAllowlist { token_id, add, remove } => update_allowlist(deps, info, token_id, add, remove),
+        ExecuteMsg::RedeemAsset { token_id } => redeem_asset(deps, info, token_id),
+        ExecuteMsg::ForceBuyout { token_id, price } => force_buyout(deps, info, token_id, price),
+        ExecuteMsg::ClaimBuyout { token_id } => claim_buyout(deps, info, token_id),
+        ExecuteMsg::MintSmartToken { to, amount } => execute_mint_smart_token(deps, env, info, to, amount),
+        ExecuteMsg::TransferSmartToken { to, amount } => execute_transfer_smart_token(deps, env, info, to, amount),
+        ExecuteMsg::SetAppraiser { address, enabled } => set_appraiser(deps, info, address, enabled),
+        ExecuteMsg::SetOracle { address } => set_oracle(deps, info, address),
+        ExecuteMsg::UpdateValuation { token_id, new_price, appraisal_uri } => update_valuation(deps, env, info, token_id, new_price, appraisal_uri),
     }
 }
 
 fn create_asset(
     deps:DepsMut<CoreumQueries>,
+    env: Env,
     info: MessageInfo,
     total_supply: Uint128,
     price: Uint128,
@@ -59,12 +117,15 @@ fn create_asset(
         total_supply,
         remaining_supply: total_supply,
         price,
-        uri,
+        uri: uri.clone(),
         asset_type,
+        status: AssetStatus::Active,
     };
 
     ASSETS.save(deps.storage, token_id, &asset)?;
     NEXT_TOKEN_ID.save(deps.storage, &(token_id + 1))?;
+    record_metadata_version(deps.storage, token_id, MetadataVersion { uri, document_hashes: vec![], updated_by: owner.clone(), updated_at: env.block.time })?;
+    record_valuation(deps.storage, token_id, Valuation { price, appraisal_uri: None, appraised_by: owner.clone(), appraised_at: env.block.time })?;
 
     Ok(Response::new().add_attribute("method", "create_asset").add_attribute("token_id", token_id.to_string()).add_attribute("owner", owner.to_string()))
 }
@@ -76,7 +137,7 @@ fn transfer_ownership(
     to: String,
     amount: Uint128,
 ) -> Result<Response<CoreumMsg>, ContractError> {
-    let mut asset = ASSETS.load(deps.storage, token_id)?;
+    let mut asset = load_active_asset(deps.storage, token_id)?;
 
     if info.sender != asset.owner {
         return Err(ContractError::Unauthorized {});
@@ -90,32 +151,525 @@ fn transfer_ownership(
     ASSETS.save(deps.storage, token_id, &asset)?;
 
     let to_addr = deps.api.addr_validate(&to)?;
-    let balance = FRACTIONAL_BALANCES.may_load(deps.storage, (to_addr.clone(), token_id))?.unwrap_or_default();
-    FRACTIONAL_BALANCES.save(deps.storage, (to_addr.clone(), token_id), &(balance + amount))?;
+    credit_fraction(deps.storage, token_id, &to_addr, amount)?;
 
     Ok(Response::new().add_attribute("method", "transfer_ownership").add_attribute("token_id", token_id.to_string()).add_attribute("from", info.sender.to_string()).add_attribute("to", to_addr.to_string()).add_attribute("amount", amount.to_string()))
 }
 
+/// Enable or disable a registrar allowed to update the metadata of any asset
+fn set_registrar(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    address: String,
+    enabled: bool,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let addr = deps.api.addr_validate(&address)?;
+    if enabled {
+        REGISTRARS.save(deps.storage, addr.clone(), &true)?;
+    } else {
+        REGISTRARS.remove(deps.storage, addr.clone());
+    }
+
+    Ok(Response::new().add_attribute("method", "set_registrar").add_attribute("address", addr.to_string()).add_attribute("enabled", enabled.to_string()))
+}
+
+/// Enable or disable a compliance officer allowed to manage transfer restrictions of any asset
+fn set_compliance_officer(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    address: String,
+    enabled: bool,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let addr = deps.api.addr_validate(&address)?;
+    if enabled {
+        COMPLIANCE_OFFICERS.save(deps.storage, addr.clone(), &true)?;
+    } else {
+        COMPLIANCE_OFFICERS.remove(deps.storage, addr.clone());
+    }
+
+    Ok(Response::new().add_attribute("method", "set_compliance_officer").add_attribute("address", addr.to_string()).add_attribute("enabled", enabled.to_string()))
+}
+
+/// Configure whether an asset requires an allowlist and how many fractions a single address may hold
+fn set_asset_compliance(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    token_id: u64,
+    allowlist_enabled: bool,
+    holding_cap: Option<Uint128>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    assert_compliance_officer(deps.as_ref().storage, &info.sender)?;
+    load_active_asset(deps.storage, token_id)?;
+
+    ASSET_COMPLIANCE.save(deps.storage, token_id, &AssetCompliance { allowlist_enabled, holding_cap })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_asset_compliance")
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("allowlist_enabled", allowlist_enabled.to_string())
+        .add_attribute("holding_cap", holding_cap.map_or_else(|| "none".to_string(), |cap| cap.to_string())))
+}
+
+/// Add and remove KYC'd addresses on the allowlist of an asset
+fn update_allowlist(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    token_id: u64,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    assert_compliance_officer(deps.as_ref().storage, &info.sender)?;
+    ASSETS.load(deps.storage, token_id)?;
+
+    for address in &add {
+        let addr = deps.api.addr_validate(address)?;
+        ALLOWLIST.save(deps.storage, (token_id, addr), &true)?;
+    }
+    for address in &remove {
+        let addr = deps.api.addr_validate(address)?;
+        ALLOWLIST.remove(deps.storage, (token_id, addr));
+    }
+
+    Ok(Response::new().add_attribute("method", "update_allowlist").add_attribute("token_id", token_id.to_string()).add_attribute("added", add.len().to_string()).add_attribute("removed", remove.len().to_string()))
+}
+
+fn assert_compliance_officer(storage: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    let config = CONFIG.load(storage)?;
+    let is_officer = COMPLIANCE_OFFICERS.may_load(storage, sender.clone())?.unwrap_or(false);
+    if *sender != config.owner && !is_officer {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+/// Check the transfer restrictions of an asset for a holder that would end up with `new_balance` fractions
+fn assert_eligible(storage: &dyn Storage, token_id: u64, holder: &Addr, new_balance: Uint128) -> Result<(), ContractError> {
+    let compliance = ASSET_COMPLIANCE.may_load(storage, token_id)?.unwrap_or_default();
+    if compliance.allowlist_enabled && !ALLOWLIST.has(storage, (token_id, holder.clone())) {
+        return Err(ContractError::NotAllowlisted { token_id, address: holder.to_string() });
+    }
+    if let Some(cap) = compliance.holding_cap {
+        if new_balance > cap {
+            return Err(ContractError::HoldingCapExceeded { token_id, cap });
+        }
+    }
+    Ok(())
+}
+
+/// Replace the URI and document hashes of an asset, keeping the previous versions in its history
+fn update_asset_metadata(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    token_id: u64,
+    uri: String,
+    document_hashes: Vec<String>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut asset = load_active_asset(deps.storage, token_id)?;
+    let is_registrar = REGISTRARS.may_load(deps.storage, info.sender.clone())?.unwrap_or(false);
+    if info.sender != asset.owner && !is_registrar {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for hash in &document_hashes {
+        if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ContractError::InvalidDocumentHash { hash: hash.clone() });
+        }
+    }
+
+    asset.uri = uri.clone();
+    ASSETS.save(deps.storage, token_id, &asset)?;
+    let version = record_metadata_version(deps.storage, token_id, MetadataVersion { uri, document_hashes, updated_by: info.sender.clone(), updated_at: env.block.time })?;
+
+    Ok(Response::new().add_attribute("method", "update_asset_metadata").add_attribute("token_id", token_id.to_string()).add_attribute("version", version.to_string()).add_attribute("updated_by", info.sender.to_string()))
+}
+
+/// Append a metadata version to the history of an asset and return its version number
+fn record_metadata_version(storage: &mut dyn Storage, token_id: u64, metadata: MetadataVersion) -> Result<u32, ContractError> {
+    let version = METADATA_VERSIONS.may_load(storage, token_id)?.map_or(0, |latest| latest + 1);
+    ASSET_HISTORY.save(storage, (token_id, version), &metadata)?;
+    METADATA_VERSIONS.save(storage, token_id, &version)?;
+    Ok(version)
+}
+
+/// Enable or disable an appraiser allowed to update the valuation of any asset
+fn set_appraiser(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    address: String,
+    enabled: bool,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let addr = deps.api.addr_validate(&address)?;
+    if enabled {
+        APPRAISERS.save(deps.storage, addr.clone(), &true)?;
+    } else {
+        APPRAISERS.remove(deps.storage, addr.clone());
+    }
+
+    Ok(Response::new().add_attribute("method", "set_appraiser").add_attribute("address", addr.to_string()).add_attribute("enabled", enabled.to_string()))
+}
+
+/// Set or clear the price oracle allowed to update the valuation of any asset
+fn set_oracle(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    address: Option<String>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.oracle = address.map(|address| deps.api.addr_validate(&address)).transpose()?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("method", "set_oracle").add_attribute("oracle", config.oracle.map_or_else(|| "none".to_string(), |oracle| oracle.to_string())))
+}
+
+/// Reprice an asset from an appraisal or oracle feed; new fractions are sold and buyouts are floored at the new price
+fn update_valuation(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    token_id: u64,
+    new_price: Uint128,
+    appraisal_uri: String,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let is_appraiser = APPRAISERS.may_load(deps.storage, info.sender.clone())?.unwrap_or(false);
+    if config.oracle.as_ref() != Some(&info.sender) && !is_appraiser {
+        return Err(ContractError::Unauthorized {});
+    }
+    if new_price.is_zero() {
+        return Err(ContractError::InvalidValuation {});
+    }
+
+    let mut asset = load_active_asset(deps.storage, token_id)?;
+    let old_price = asset.price;
+    asset.price = new_price;
+    ASSETS.save(deps.storage, token_id, &asset)?;
+    let version = record_valuation(deps.storage, token_id, Valuation { price: new_price, appraisal_uri: Some(appraisal_uri), appraised_by: info.sender.clone(), appraised_at: env.block.time })?;
+
+    Ok(Response::new().add_attribute("method", "update_valuation").add_attribute("token_id", token_id.to_string()).add_attribute("version", version.to_string()).add_attribute("old_price", old_price.to_string()).add_attribute("new_price", new_price.to_string()).add_attribute("appraised_by", info.sender.to_string()))
+}
+
+/// Append a valuation to the history of an asset and return its version number
+fn record_valuation(storage: &mut dyn Storage, token_id: u64, valuation: Valuation) -> Result<u32, ContractError> {
+    let version = VALUATION_VERSIONS.may_load(storage, token_id)?.map_or(0, |latest| latest + 1);
+    VALUATION_HISTORY.save(storage, (token_id, version), &valuation)?;
+    VALUATION_VERSIONS.save(storage, token_id, &version)?;
+    Ok(version)
+}
+
+/// Buy fractions of an asset out of its remaining supply, paying `price * amount` in the payment denom
+fn buy_fraction(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    token_id: u64,
+    amount: Uint128,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut asset = load_active_asset(deps.storage, token_id)?;
+
+    if amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err("Amount must be greater than zero")));
+    }
+    if amount > asset.remaining_supply {
+        return Err(ContractError::InsufficientSupply { remaining: asset.remaining_supply });
+    }
+
+    let cost = asset.price.checked_mul(amount)?;
+    assert_payment(&config, &info, cost)?;
+
+    asset.remaining_supply = asset.remaining_supply.checked_sub(amount)?;
+    ASSETS.save(deps.storage, token_id, &asset)?;
+    credit_fraction(deps.storage, token_id, &info.sender, amount)?;
+
+    // Route the payment to the asset owner, keeping the platform fee for the contract owner
+    let (payments, fee) = split_payment(&config, &asset.owner, cost)?;
+
+    Ok(Response::new().add_messages(payments).add_attribute("method", "buy_fraction").add_attribute("token_id", token_id.to_string()).add_attribute("buyer", info.sender.to_string()).add_attribute("amount", amount.to_string()).add_attribute("cost", cost.to_string()).add_attribute("fee", fee.to_string()))
+}
+
+/// Move fractions of an asset from the sender to another address
+fn transfer_fraction(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    token_id: u64,
+    to: String,
+    amount: Uint128,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    load_active_asset(deps.storage, token_id)?;
+    let to_addr = deps.api.addr_validate(&to)?;
+
+    debit_fraction(deps.storage, token_id, &info.sender, amount)?;
+    credit_fraction(deps.storage, token_id, &to_addr, amount)?;
+
+    Ok(Response::new().add_attribute("method", "transfer_fraction").add_attribute("token_id", token_id.to_string()).add_attribute("from", info.sender.to_string()).add_attribute("to", to_addr.to_string()).add_attribute("amount", amount.to_string()))
+}
+
+/// List fractions for sale at a price per fraction; the listed fractions are held by the listing until sold or cancelled
+fn list_fraction(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    token_id: u64,
+    amount: Uint128,
+    price: Uint128,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    load_active_asset(deps.storage, token_id)?;
+    if amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err("Amount must be greater than zero")));
+    }
+
+    debit_fraction(deps.storage, token_id, &info.sender, amount)?;
+
+    let listing_id = NEXT_LISTING_ID.load(deps.storage)?;
+    LISTINGS.save(deps.storage, listing_id, &Listing { seller: info.sender.clone(), token_id, amount, price })?;
+    NEXT_LISTING_ID.save(deps.storage, &(listing_id + 1))?;
+
+    Ok(Response::new().add_attribute("method", "list_fraction").add_attribute("listing_id", listing_id.to_string()).add_attribute("token_id", token_id.to_string()).add_attribute("seller", info.sender.to_string()).add_attribute("amount", amount.to_string()).add_attribute("price", price.to_string()))
+}
+
+/// Buy some or all of the fractions offered by a listing
+fn buy_listed_fraction(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    listing_id: u64,
+    amount: Uint128,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut listing = LISTINGS.load(deps.storage, listing_id)?;
+    load_active_asset(deps.storage, listing.token_id)?;
+
+    if amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err("Amount must be greater than zero")));
+    }
+    if amount > listing.amount {
+        return Err(ContractError::ListingExceeded { listing_id, available: listing.amount });
+    }
+
+    let cost = listing.price.checked_mul(amount)?;
+    assert_payment(&config, &info, cost)?;
+
+    listing.amount = listing.amount.checked_sub(amount)?;
+    if listing.amount.is_zero() {
+        LISTINGS.remove(deps.storage, listing_id);
+    } else {
+        LISTINGS.save(deps.storage, listing_id, &listing)?;
+    }
+    credit_fraction(deps.storage, listing.token_id, &info.sender, amount)?;
+
+    let (payments, fee) = split_payment(&config, &listing.seller, cost)?;
+
+    Ok(Response::new().add_messages(payments).add_attribute("method", "buy_listed_fraction").add_attribute("listing_id", listing_id.to_string()).add_attribute("token_id", listing.token_id.to_string()).add_attribute("buyer", info.sender.to_string()).add_attribute("seller", listing.seller.to_string()).add_attribute("amount", amount.to_string()).add_attribute("cost", cost.to_string()).add_attribute("fee", fee.to_string()))
+}
+
+/// Cancel a listing and return the unsold fractions to the seller
+fn cancel_listing(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    listing_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let listing = LISTINGS.load(deps.storage, listing_id)?;
+    if info.sender != listing.seller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    LISTINGS.remove(deps.storage, listing_id);
+    // Returning fractions the seller already held is not subject to transfer restrictions
+    add_fraction(deps.storage, listing.token_id, &listing.seller, listing.amount)?;
+
+    Ok(Response::new().add_attribute("method", "cancel_listing").add_attribute("listing_id", listing_id.to_string()).add_attribute("amount", listing.amount.to_string()))
+}
+
+/// Retire an asset whose fractions are all held by the sender, burning the sender's balance
+fn redeem_asset(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    token_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut asset = load_active_asset(deps.storage, token_id)?;
+
+    // The owner implicitly holds the fractions that were never sold
+    let balance = fraction_balance(deps.storage, token_id, &info.sender)?;
+    let held = if info.sender == asset.owner { balance.checked_add(asset.remaining_supply)? } else { balance };
+    if held != asset.total_supply {
+        return Err(ContractError::IncompleteOwnership { total_supply: asset.total_supply });
+    }
+
+    set_fraction_balance(deps.storage, token_id, &info.sender, Uint128::zero())?;
+    asset.remaining_supply = Uint128::zero();
+    asset.status = AssetStatus::Redeemed;
+    ASSETS.save(deps.storage, token_id, &asset)?;
+
+    Ok(Response::new().add_attribute("method", "redeem_asset").add_attribute("token_id", token_id.to_string()).add_attribute("redeemer", info.sender.to_string()).add_attribute("burned", held.to_string()))
+}
+
+/// Retire an asset on behalf of a supermajority holder, who funds the buyout of every outstanding fraction at `price`
+fn force_buyout(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    token_id: u64,
+    price: Uint128,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut asset = load_active_asset(deps.storage, token_id)?;
+
+    if price < asset.price {
+        return Err(ContractError::BuyoutPriceTooLow { min_price: asset.price });
+    }
+
+    let balance = fraction_balance(deps.storage, token_id, &info.sender)?;
+    let is_owner = info.sender == asset.owner;
+    let held = if is_owner { balance.checked_add(asset.remaining_supply)? } else { balance };
+    if asset.total_supply.is_zero() || Decimal::from_ratio(held, asset.total_supply) < config.buyout_threshold {
+        return Err(ContractError::BelowBuyoutThreshold { threshold: config.buyout_threshold.to_string() });
+    }
+
+    // The buyer pays for every fraction they do not hold yet
+    let outstanding = asset.total_supply.checked_sub(held)?;
+    let cost = price.checked_mul(outstanding)?;
+    assert_payment(&config, &info, cost)?;
+
+    // Unsold fractions belong to the owner, who is paid right away; other holders claim their share
+    let mut response = Response::new();
+    if !is_owner && !asset.remaining_supply.is_zero() {
+        response = response.add_message(BankMsg::Send { to_address: asset.owner.to_string(), amount: vec![Coin { denom: config.payment_denom, amount: price.checked_mul(asset.remaining_supply)? }] });
+    }
+
+    set_fraction_balance(deps.storage, token_id, &info.sender, Uint128::zero())?;
+    asset.remaining_supply = Uint128::zero();
+    asset.status = AssetStatus::Redeemed;
+    ASSETS.save(deps.storage, token_id, &asset)?;
+    BUYOUTS.save(deps.storage, token_id, &Buyout { buyer: info.sender.clone(), price })?;
+
+    Ok(response.add_attribute("method", "force_buyout").add_attribute("token_id", token_id.to_string()).add_attribute("buyer", info.sender.to_string()).add_attribute("price", price.to_string()).add_attribute("outstanding", outstanding.to_string()))
+}
+
+/// Burn the sender's fractions of a bought out asset in exchange for the buyout price
+fn claim_buyout(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    token_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let buyout = BUYOUTS.may_load(deps.storage, token_id)?.ok_or(ContractError::NoBuyout { token_id })?;
+
+    let balance = fraction_balance(deps.storage, token_id, &info.sender)?;
+    if balance.is_zero() {
+        return Err(ContractError::InsufficientBalance { balance });
+    }
+    set_fraction_balance(deps.storage, token_id, &info.sender, Uint128::zero())?;
+
+    let payout = buyout.price.checked_mul(balance)?;
+    Ok(Response::new()
+        .add_message(BankMsg::Send { to_address: info.sender.to_string(), amount: vec![Coin { denom: config.payment_denom, amount: payout }] })
+        .add_attribute("method", "claim_buyout").add_attribute("token_id", token_id.to_string()).add_attribute("holder", info.sender.to_string()).add_attribute("burned", balance.to_string()).add_attribute("payout", payout.to_string()))
+}
+
+/// Load an asset, failing if it has already been redeemed
+fn load_active_asset(storage: &dyn Storage, token_id: u64) -> Result<TokenizedAsset, ContractError> {
+    let asset = ASSETS.load(storage, token_id)?;
+    if asset.status == AssetStatus::Redeemed {
+        return Err(ContractError::AssetRedeemed { token_id });
+    }
+    Ok(asset)
+}
+
+/// Ensure the sender attached exactly `cost` in the payment denom
+fn assert_payment(config: &Config, info: &MessageInfo, cost: Uint128) -> Result<(), ContractError> {
+    let expected = Coin { denom: config.payment_denom.clone(), amount: cost };
+    if cost.is_zero() && info.funds.is_empty() {
+        return Ok(());
+    }
+    if info.funds.len() != 1 || info.funds[0] != expected {
+        return Err(ContractError::InvalidPayment { expected: expected.to_string() });
+    }
+    Ok(())
+}
+
+/// Split a payment between its recipient and the platform, returning the bank sends and the fee taken
+fn split_payment(config: &Config, recipient: &Addr, cost: Uint128) -> Result<(Vec<BankMsg>, Uint128), ContractError> {
+    let fee = cost * config.platform_fee;
+    let proceeds = cost.checked_sub(fee)?;
+    let mut payments = vec![];
+    if !proceeds.is_zero() {
+        payments.push(BankMsg::Send { to_address: recipient.to_string(), amount: vec![Coin { denom: config.payment_denom.clone(), amount: proceeds }] });
+    }
+    if !fee.is_zero() {
+        payments.push(BankMsg::Send { to_address: config.owner.to_string(), amount: vec![Coin { denom: config.payment_denom.clone(), amount: fee }] });
+    }
+    Ok((payments, fee))
+}
+
+/// Remove `amount` fractions of `token_id` from the balance of `holder`
+fn debit_fraction(storage: &mut dyn Storage, token_id: u64, holder: &Addr, amount: Uint128) -> Result<(), ContractError> {
+    let balance = fraction_balance(storage, token_id, holder)?;
+    if balance < amount {
+        return Err(ContractError::InsufficientBalance { balance });
+    }
+    set_fraction_balance(storage, token_id, holder, balance.checked_sub(amount)?)?;
+    Ok(())
+}
+
+/// Add `amount` fractions of `token_id` to the balance of `holder`, enforcing the asset's transfer restrictions
+fn credit_fraction(storage: &mut dyn Storage, token_id: u64, holder: &Addr, amount: Uint128) -> Result<(), ContractError> {
+    let new_balance = add_fraction(storage, token_id, holder, amount)?;
+    assert_eligible(storage, token_id, holder, new_balance)
+}
+
+/// Add `amount` fractions of `token_id` to the balance of `holder` and return the new balance
+fn add_fraction(storage: &mut dyn Storage, token_id: u64, holder: &Addr, amount: Uint128) -> Result<Uint128, ContractError> {
+    let new_balance = fraction_balance(storage, token_id, holder)?.checked_add(amount)?;
+    set_fraction_balance(storage, token_id, holder, new_balance)?;
+    Ok(new_balance)
+}
+
 /// Mint new smart tokens
 fn execute_mint_smart_token(
     deps: DepsMut<CoreumQueries>,
+    env: Env,
     info: MessageInfo,
     to: String,
     amount: Uint128,
 ) -> Result<Response<CoreumMsg>, ContractError> {
-    let token_info = TOKEN_INFO.load(deps.storage)?;
+    let mut token_info = TOKEN_INFO.load(deps.storage)?;
 
     // Ensure the sender is the owner of the token
     if info.sender != token_info.owner {
         return Err(ContractError::Unauthorized {});
     }
 
-    // Update the recipient's balance
+    // Update the recipient's balance and the total supply
     let to_addr = deps.api.addr_validate(&to)?;
     let balance = BALANCES.may_load(deps.storage, to_addr.clone())?.unwrap_or_default();
-    BALANCES.save(deps.storage, to_addr.clone(), &(balance + amount))?;
+    BALANCES.save(deps.storage, to_addr.clone(), &balance.checked_add(amount)?, env.block.height)?;
+    token_info.total_supply = token_info.total_supply.checked_add(amount)?;
+    TOKEN_INFO.save(deps.storage, &token_info)?;
+
+    // Mint the matching amount on chain; it goes to the contract as issuer and is sent on to the recipient
+    let coin = Coin::new(amount.u128(), token_info.denom);
+    let mint_msg = CoreumMsg::AssetFT(assetft::Msg::Mint { coin: coin.clone() });
+    let send_msg = BankMsg::Send { to_address: to_addr.to_string(), amount: vec![coin] };
 
     Ok(Response::new()
+        .add_message(mint_msg)
+        .add_message(send_msg)
         .add_attribute("method", "mint_smart_token")
         .add_attribute("to", to_addr.to_string())
         .add_attribute("amount", amount.to_string()))
@@ -124,6 +678,7 @@ fn execute_mint_smart_token(
 /// Transfer smart tokens
 fn execute_transfer_smart_token(
     deps: DepsMut<CoreumQueries>,
+    env: Env,
     info: MessageInfo,
     to: String,
     amount: Uint128,
@@ -132,17 +687,24 @@ fn execute_transfer_smart_token(
     let to_addr = deps.api.addr_validate(&to)?;
 
     // Ensure the sender has enough balance
-    let sender_balance = BALANCES.load(deps.storage, sender_addr.clone())?;
+    let sender_balance = BALANCES.may_load(deps.storage, sender_addr.clone())?.unwrap_or_default();
     if sender_balance < amount {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::InsufficientBalance { balance: sender_balance });
+    }
+    // The coins travel with the ledger entry so the chain and the ledger keep the same holder
+    let denom = TOKEN_INFO.load(deps.storage)?.denom;
+    match info.funds.as_slice() {
+        [coin] if coin.denom == denom && coin.amount == amount => {}
+        _ => return Err(ContractError::InvalidFunds { denom }),
     }
 
     // Update the sender's and recipient's balances
-    BALANCES.save(deps.storage, sender_addr.clone(), &(sender_balance - amount))?;
+    BALANCES.save(deps.storage, sender_addr.clone(), &sender_balance.checked_sub(amount)?, env.block.height)?;
     let recipient_balance = BALANCES.may_load(deps.storage, to_addr.clone())?.unwrap_or_default();
-    BALANCES.save(deps.storage, to_addr.clone(), &(recipient_balance + amount))?;
+    BALANCES.save(deps.storage, to_addr.clone(), &recipient_balance.checked_add(amount)?, env.block.height)?;
 
     Ok(Response::new()
+        .add_message(BankMsg::Send { to_address: to_addr.to_string(), amount: info.funds })
         .add_attribute("method", "transfer_smart_token")
         .add_attribute("from", sender_addr.to_string())
         .add_attribute("to", to_addr.to_string())
@@ -150,20 +712,596 @@ fn execute_transfer_smart_token(
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn migrate(deps: DepsMut<CoreumQueries>, _env: Env, _msg: MigrateMsg) -> Result<Response<CoreumMsg>, ContractError> {
+    let version = get_contract_version(deps.storage)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    if version.version != LEGACY_VERSION {
+        return Ok(Response::new().add_attribute("method", "migrate"));
+    }
+
+    let migrated = migrate_legacy_balances(deps.storage)?;
+    Ok(Response::new().add_attribute("method", "migrate").add_attribute("migrated_balances", migrated.to_string()))
+}
+
+#[entry_point]
+pub fn query(deps: Deps<CoreumQueries>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::FractionalOwnership { token_id, owner } => to_binary(&query_fractional_ownership(deps, token_id, owner)?),
         QueryMsg::TokenURI { token_id } => to_binary(&query_token_uri(deps, token_id)?),
+        QueryMsg::SmartTokenInfo {} => to_binary(&query_smart_token_info(deps)?),
+        QueryMsg::SmartTokenBalance { address } => to_binary(&query_smart_token_balance(deps, address)?),
+        QueryMsg::SmartTokenBalanceAt { address, height } => to_binary(&query_smart_token_balance_at(deps, address, height)?),
+        QueryMsg::AssetInfo { token_id } => to_binary(&query_asset_info(deps, token_id)?),
+        QueryMsg::AssetHolders { token_id, start_after, limit } => to_binary(&query_asset_holders(deps, token_id, start_after, limit)?),
+        QueryMsg::AssetSummary { token_id } => to_binary(&query_asset_summary(deps, token_id)?),
+        QueryMsg::AssetCompliance { token_id } => to_binary(&query_asset_compliance(deps, token_id)?),
+        QueryMsg::Eligibility { token_id, address, amount } => to_binary(&query_eligibility(deps, token_id, address, amount)?),
+        QueryMsg::Listing { listing_id } => to_binary(&query_listing(deps, listing_id)?),
+        QueryMsg::Listings { token_id, start_after, limit } => to_binary(&query_listings(deps, token_id, start_after, limit)?),
+        QueryMsg::AssetHistory { token_id, start_after, limit } => to_binary(&query_asset_history(deps, token_id, start_after, limit)?),
+        QueryMsg::CurrentValuation { token_id } => to_binary(&query_current_valuation(deps, token_id)?),
+        QueryMsg::ValuationHistory { token_id, start_after, limit } => to_binary(&query_valuation_history(deps, token_id, start_after, limit)?),
     }
 }
 
-fn query_fractional_ownership(deps: Deps, token_id: u64, owner: String) -> StdResult<Uint128> {
+fn query_fractional_ownership(deps: Deps<CoreumQueries>, token_id: u64, owner: String) -> StdResult<Uint128> {
     let owner_addr = deps.api.addr_validate(&owner)?;
-    let balance = FRACTIONAL_BALANCES.may_load(deps.storage, (owner_addr, token_id))?.unwrap_or_default();
+    let balance = fraction_balance(deps.storage, token_id, &owner_addr)?;
     Ok(balance)
 }
 
-fn query_token_uri(deps: Deps, token_id: u64) -> StdResult<String> {
+fn query_token_uri(deps: Deps<CoreumQueries>, token_id: u64) -> StdResult<String> {
     let asset = ASSETS.load(deps.storage, token_id)?;
     Ok(asset.uri)
 }
+
+
+fn query_smart_token_info(deps: Deps<CoreumQueries>) -> StdResult<SmartTokenInfoResponse> {
+    let token_info = TOKEN_INFO.load(deps.storage)?;
+    Ok(SmartTokenInfoResponse { owner: token_info.owner.to_string(), denom: token_info.denom, total_supply: token_info.total_supply, precision: token_info.precision })
+}
+
+fn query_smart_token_balance(deps: Deps<CoreumQueries>, address: String) -> StdResult<Uint128> {
+    let addr = deps.api.addr_validate(&address)?;
+    Ok(BALANCES.may_load(deps.storage, addr)?.unwrap_or_default())
+}
+
+fn query_smart_token_balance_at(deps: Deps<CoreumQueries>, address: String, height: u64) -> StdResult<Uint128> {
+    let addr = deps.api.addr_validate(&address)?;
+    balance_at(deps.storage, &addr, height)
+}
+
+fn query_asset_info(deps: Deps<CoreumQueries>, token_id: u64) -> StdResult<AssetInfoResponse> {
+    let asset = ASSETS.load(deps.storage, token_id)?;
+    let buyout = BUYOUTS.may_load(deps.storage, token_id)?;
+    Ok(AssetInfoResponse {
+        token_id,
+        owner: asset.owner.to_string(),
+        total_supply: asset.total_supply,
+        remaining_supply: asset.remaining_supply,
+        price: asset.price,
+        uri: asset.uri,
+        redeemed: asset.status == AssetStatus::Redeemed,
+        buyout_price: buyout.map(|buyout| buyout.price),
+    })
+}
+
+fn query_asset_holders(deps: Deps<CoreumQueries>, token_id: u64, start_after: Option<String>, limit: Option<u32>) -> StdResult<AssetHoldersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = start_after.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    let start = start_addr.map(Bound::exclusive);
+    let holders = FRACTIONAL_BALANCES
+        .prefix(token_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(holder, balance)| HolderBalance { holder: holder.to_string(), balance }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(AssetHoldersResponse { token_id, holders })
+}
+
+fn query_asset_summary(deps: Deps<CoreumQueries>, token_id: u64) -> StdResult<AssetSummaryResponse> {
+    let asset = ASSETS.load(deps.storage, token_id)?;
+    let stats = HOLDER_STATS.may_load(deps.storage, token_id)?.unwrap_or_default();
+
+    // Whatever is neither unsold nor held sits in listings, unless the asset was redeemed and burned
+    let listed_supply = match asset.status {
+        AssetStatus::Active => asset.total_supply.checked_sub(asset.remaining_supply)?.checked_sub(stats.held_supply)?,
+        AssetStatus::Redeemed => Uint128::zero(),
+    };
+    let average_holding = if stats.holder_count == 0 {
+        Uint128::zero()
+    } else {
+        stats.held_supply / Uint128::from(stats.holder_count)
+    };
+
+    Ok(AssetSummaryResponse {
+        token_id,
+        total_supply: asset.total_supply,
+        remaining_supply: asset.remaining_supply,
+        held_supply: stats.held_supply,
+        listed_supply,
+        holder_count: stats.holder_count,
+        average_holding,
+    })
+}
+
+fn query_asset_compliance(deps: Deps<CoreumQueries>, token_id: u64) -> StdResult<AssetComplianceResponse> {
+    ASSETS.load(deps.storage, token_id)?;
+    let compliance = ASSET_COMPLIANCE.may_load(deps.storage, token_id)?.unwrap_or_default();
+    Ok(AssetComplianceResponse { token_id, allowlist_enabled: compliance.allowlist_enabled, holding_cap: compliance.holding_cap })
+}
+
+fn query_eligibility(deps: Deps<CoreumQueries>, token_id: u64, address: String, amount: Uint128) -> StdResult<EligibilityResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    let balance = fraction_balance(deps.storage, token_id, &addr)?;
+    let allowlisted = ALLOWLIST.has(deps.storage, (token_id, addr.clone()));
+    let eligible = match balance.checked_add(amount) {
+        Ok(new_balance) => assert_eligible(deps.storage, token_id, &addr, new_balance).is_ok(),
+        Err(_) => false,
+    };
+    Ok(EligibilityResponse { allowlisted, balance, eligible })
+}
+
+fn query_listing(deps: Deps<CoreumQueries>, listing_id: u64) -> StdResult<ListingResponse> {
+    let listing = LISTINGS.load(deps.storage, listing_id)?;
+    Ok(to_listing_response(listing_id, listing))
+}
+
+fn query_listings(deps: Deps<CoreumQueries>, token_id: Option<u64>, start_after: Option<u64>, limit: Option<u32>) -> StdResult<ListingsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let listings = LISTINGS
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match (item, token_id) {
+            (Ok((_, listing)), Some(token_id)) => listing.token_id == token_id,
+            _ => true,
+        })
+        .take(limit)
+        .map(|item| item.map(|(listing_id, listing)| to_listing_response(listing_id, listing)))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ListingsResponse { listings })
+}
+
+fn to_listing_response(listing_id: u64, listing: Listing) -> ListingResponse {
+    ListingResponse { listing_id, seller: listing.seller.to_string(), token_id: listing.token_id, amount: listing.amount, price: listing.price }
+}
+
+fn query_asset_history(deps: Deps<CoreumQueries>, token_id: u64, start_after: Option<u32>, limit: Option<u32>) -> StdResult<AssetHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let versions = ASSET_HISTORY
+        .prefix(token_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(version, metadata)| MetadataVersionResponse {
+                version,
+                uri: metadata.uri,
+                document_hashes: metadata.document_hashes,
+                updated_by: metadata.updated_by.to_string(),
+                updated_at: metadata.updated_at,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(AssetHistoryResponse { token_id, versions })
+}
+
+fn query_current_valuation(deps: Deps<CoreumQueries>, token_id: u64) -> StdResult<ValuationResponse> {
+    let version = VALUATION_VERSIONS.load(deps.storage, token_id)?;
+    let valuation = VALUATION_HISTORY.load(deps.storage, (token_id, version))?;
+    Ok(to_valuation_response(version, valuation))
+}
+
+fn query_valuation_history(deps: Deps<CoreumQueries>, token_id: u64, start_after: Option<u32>, limit: Option<u32>) -> StdResult<ValuationHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let valuations = VALUATION_HISTORY
+        .prefix(token_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(version, valuation)| to_valuation_response(version, valuation)))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ValuationHistoryResponse { token_id, valuations })
+}
+
+fn to_valuation_response(version: u32, valuation: Valuation) -> ValuationResponse {
+    ValuationResponse { version, price: valuation.price, appraisal_uri: valuation.appraisal_uri, appraised_by: valuation.appraised_by.to_string(), appraised_at: valuation.appraised_at }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coin, coins, from_binary, CosmosMsg};
+    use coreum_test_utils::{mock_coreum_deps, CoreumDeps};
+
+    fn setup(deps: DepsMut<CoreumQueries>) {
+        let msg = InstantiateMsg {
+            owner: "owner".to_string(),
+            symbol: "RWA".to_string(),
+            subunit: "urwa".to_string(),
+            precision: 6,
+            initial_amount: Uint128::new(1000),
+            payment_denom: "ucore".to_string(),
+            platform_fee: Decimal::percent(10),
+            buyout_threshold: Decimal::percent(75),
+            oracle: Some("oracle".to_string()),
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    // asset 1: 100 fractions issued by "issuer" at 10ucore each
+    fn setup_with_asset() -> CoreumDeps {
+        let mut deps = mock_coreum_deps();
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::CreateAsset { total_supply: Uint128::new(100), price: Uint128::new(10), uri: "ipfs://asset".to_string(), asset_type: MsgAssetType::RealWorldAsset };
+        execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), msg).unwrap();
+        deps
+    }
+
+    fn buy(deps: &mut CoreumDeps, buyer: &str, amount: u128) -> Result<Response<CoreumMsg>, ContractError> {
+        let msg = ExecuteMsg::BuyFraction { token_id: 1, amount: Uint128::new(amount) };
+        execute(deps.as_mut(), mock_env(), mock_info(buyer, &coins(amount * 10, "ucore")), msg)
+    }
+
+    fn balance(deps: &CoreumDeps, holder: &str) -> Uint128 {
+        fraction_balance(&deps.storage, 1, &Addr::unchecked(holder)).unwrap()
+    }
+
+    #[test]
+    fn instantiate_issues_smart_token() {
+        let mut deps = mock_coreum_deps();
+        let msg = InstantiateMsg {
+            owner: "owner".to_string(),
+            symbol: "RWA".to_string(),
+            subunit: "urwa".to_string(),
+            precision: 6,
+            initial_amount: Uint128::new(1000),
+            payment_denom: "ucore".to_string(),
+            platform_fee: Decimal::percent(10),
+            buyout_threshold: Decimal::percent(75),
+            oracle: None,
+        };
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        // the initial supply is issued to the contract and handed to the owner
+        assert_eq!(res.messages.len(), 2);
+        assert!(matches!(res.messages[0].msg, CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Issue { .. }))));
+        assert_eq!(res.messages[1].msg, CosmosMsg::Bank(BankMsg::Send { to_address: "owner".to_string(), amount: coins(1000, format!("urwa-{}", mock_env().contract.address)) }));
+
+        let res: SmartTokenInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::SmartTokenInfo {}).unwrap()).unwrap();
+        assert_eq!(res, SmartTokenInfoResponse { owner: "owner".to_string(), denom: format!("urwa-{}", mock_env().contract.address), total_supply: Uint128::new(1000), precision: 6 });
+
+        let balance: Uint128 = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::SmartTokenBalance { address: "owner".to_string() }).unwrap()).unwrap();
+        assert_eq!(balance, Uint128::new(1000));
+    }
+
+    #[test]
+    fn mint_and_transfer_smart_token() {
+        let mut deps = mock_coreum_deps();
+        setup(deps.as_mut());
+
+        // only the owner can mint
+        let msg = ExecuteMsg::MintSmartToken { to: "alice".to_string(), amount: Uint128::new(500) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // the contract mints as issuer and sends the coins on to the recipient
+        let denom = format!("urwa-{}", mock_env().contract.address);
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(res.messages[0].msg, CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Mint { coin: coin(500, &denom) })));
+        assert_eq!(res.messages[1].msg, CosmosMsg::Bank(BankMsg::Send { to_address: "alice".to_string(), amount: coins(500, &denom) }));
+
+        // transfers move the coins along with the ledger entry
+        let msg = ExecuteMsg::TransferSmartToken { to: "bob".to_string(), amount: Uint128::new(200) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFunds { .. }));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &coins(199, &denom)), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFunds { .. }));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &coins(200, &denom)), msg).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send { to_address: "bob".to_string(), amount: coins(200, &denom) }));
+
+        // bob cannot spend more than he received
+        let msg = ExecuteMsg::TransferSmartToken { to: "alice".to_string(), amount: Uint128::new(201) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("bob", &coins(201, &denom)), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientBalance { .. }));
+
+        let info: SmartTokenInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::SmartTokenInfo {}).unwrap()).unwrap();
+        assert_eq!(info.total_supply, Uint128::new(1500));
+        let alice: Uint128 = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::SmartTokenBalance { address: "alice".to_string() }).unwrap()).unwrap();
+        assert_eq!(alice, Uint128::new(300));
+        let bob: Uint128 = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::SmartTokenBalance { address: "bob".to_string() }).unwrap()).unwrap();
+        assert_eq!(bob, Uint128::new(200));
+    }
+
+    #[test]
+    fn smart_token_balance_snapshots() {
+        let mut deps = mock_coreum_deps();
+        setup(deps.as_mut());
+
+        let mut env = mock_env();
+        let minted_at = env.block.height + 1;
+        env.block.height = minted_at;
+        let msg = ExecuteMsg::MintSmartToken { to: "alice".to_string(), amount: Uint128::new(500) };
+        execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+
+        env.block.height += 5;
+        let msg = ExecuteMsg::TransferSmartToken { to: "bob".to_string(), amount: Uint128::new(200) };
+        let funds = coins(200, format!("urwa-{}", env.contract.address));
+        execute(deps.as_mut(), env.clone(), mock_info("alice", &funds), msg).unwrap();
+
+        let balance_at = |address: &str, height: u64| -> Uint128 {
+            let msg = QueryMsg::SmartTokenBalanceAt { address: address.to_string(), height };
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+        };
+        // a snapshot reflects balances as they were before the block's own changes
+        assert_eq!(balance_at("alice", minted_at), Uint128::zero());
+        assert_eq!(balance_at("alice", minted_at + 1), Uint128::new(500));
+        assert_eq!(balance_at("alice", env.block.height), Uint128::new(500));
+        assert_eq!(balance_at("alice", env.block.height + 1), Uint128::new(300));
+        assert_eq!(balance_at("bob", env.block.height + 1), Uint128::new(200));
+    }
+
+    #[test]
+    fn buy_and_transfer_fractions() {
+        let mut deps = mock_coreum_deps();
+        setup(deps.as_mut());
+
+        let msg = ExecuteMsg::CreateAsset { total_supply: Uint128::new(100), price: Uint128::new(10), uri: "ipfs://asset".to_string(), asset_type: MsgAssetType::RealWorldAsset };
+        execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), msg).unwrap();
+
+        // the payment must match price * amount
+        let msg = ExecuteMsg::BuyFraction { token_id: 1, amount: Uint128::new(10) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &coins(50, "ucore")), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPayment { .. }));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &coins(100, "ucore")), msg).unwrap();
+        assert_eq!(res.messages.len(), 2);
+
+        let msg = ExecuteMsg::TransferFraction { token_id: 1, to: "bob".to_string(), amount: Uint128::new(4) };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+
+        let summary: AssetSummaryResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::AssetSummary { token_id: 1 }).unwrap()).unwrap();
+        assert_eq!(summary.remaining_supply, Uint128::new(90));
+        assert_eq!(summary.held_supply, Uint128::new(10));
+        assert_eq!(summary.holder_count, 2);
+    }
+
+    #[test]
+    fn valuation_updates() {
+        let mut deps = mock_coreum_deps();
+        setup(deps.as_mut());
+
+        let msg = ExecuteMsg::CreateAsset { total_supply: Uint128::new(100), price: Uint128::new(10), uri: "ipfs://asset".to_string(), asset_type: MsgAssetType::RealWorldAsset };
+        execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), msg).unwrap();
+
+        // only appraisers and the oracle can reprice an asset
+        let msg = ExecuteMsg::UpdateValuation { token_id: 1, new_price: Uint128::new(12), appraisal_uri: "ipfs://appraisal-1".to_string() };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("appraiser", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let set_appraiser = ExecuteMsg::SetAppraiser { address: "appraiser".to_string(), enabled: true };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), set_appraiser).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("appraiser", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateValuation { token_id: 1, new_price: Uint128::zero(), appraisal_uri: "ipfs://feed".to_string() };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("oracle", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidValuation {}));
+        let msg = ExecuteMsg::UpdateValuation { token_id: 1, new_price: Uint128::new(15), appraisal_uri: "ipfs://feed".to_string() };
+        execute(deps.as_mut(), mock_env(), mock_info("oracle", &[]), msg).unwrap();
+
+        let current: ValuationResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::CurrentValuation { token_id: 1 }).unwrap()).unwrap();
+        assert_eq!(current.version, 2);
+        assert_eq!(current.price, Uint128::new(15));
+        assert_eq!(current.appraised_by, "oracle");
+
+        let history: ValuationHistoryResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::ValuationHistory { token_id: 1, start_after: None, limit: None }).unwrap()).unwrap();
+        let prices: Vec<_> = history.valuations.iter().map(|valuation| valuation.price.u128()).collect();
+        assert_eq!(prices, vec![10, 12, 15]);
+        assert_eq!(history.valuations[0].appraisal_uri, None);
+
+        // fractions are now sold at the new valuation
+        let msg = ExecuteMsg::BuyFraction { token_id: 1, amount: Uint128::new(2) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &coins(20, "ucore")), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPayment { .. }));
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &coins(30, "ucore")), msg).unwrap();
+    }
+
+    #[test]
+    fn migrate_rekeys_fractional_balances() {
+        let mut deps = mock_coreum_deps();
+        setup(deps.as_mut());
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, LEGACY_VERSION).unwrap();
+        for (holder, token_id, balance) in [("alice", 1, 30), ("bob", 1, 20), ("alice", 2, 5)] {
+            fractions::LEGACY_FRACTIONAL_BALANCES.save(deps.as_mut().storage, (Addr::unchecked(holder), token_id), &Uint128::new(balance)).unwrap();
+        }
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(res.attributes[1].value, "3");
+        assert_eq!(get_contract_version(&deps.storage).unwrap().version, CONTRACT_VERSION);
+
+        let msg = QueryMsg::AssetHolders { token_id: 1, start_after: None, limit: None };
+        let res: AssetHoldersResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(res.holders, vec![
+            HolderBalance { holder: "alice".to_string(), balance: Uint128::new(30) },
+            HolderBalance { holder: "bob".to_string(), balance: Uint128::new(20) },
+        ]);
+        let stats = HOLDER_STATS.load(&deps.storage, 1).unwrap();
+        assert_eq!((stats.holder_count, stats.held_supply), (2, Uint128::new(50)));
+        assert_eq!(fraction_balance(&deps.storage, 2, &Addr::unchecked("alice")).unwrap(), Uint128::new(5));
+
+        // migrating again leaves the new layout alone
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(HOLDER_STATS.load(&deps.storage, 1).unwrap().holder_count, 2);
+    }
+
+    #[test]
+    fn list_buy_and_cancel_fractions() {
+        let mut deps = setup_with_asset();
+        buy(&mut deps, "alice", 20).unwrap();
+
+        let list = |amount: u128| ExecuteMsg::ListFraction { token_id: 1, amount: Uint128::new(amount), price: Uint128::new(12) };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), list(0)).unwrap_err();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), list(21)).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientBalance { .. }));
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), list(15)).unwrap();
+        // listed fractions are held by the listing
+        assert_eq!(balance(&deps, "alice"), Uint128::new(5));
+
+        let listing: ListingResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Listing { listing_id: 1 }).unwrap()).unwrap();
+        assert_eq!(listing, ListingResponse { listing_id: 1, seller: "alice".to_string(), token_id: 1, amount: Uint128::new(15), price: Uint128::new(12) });
+
+        let msg = ExecuteMsg::BuyListedFraction { listing_id: 1, amount: Uint128::new(16) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("bob", &coins(192, "ucore")), msg).unwrap_err();
+        assert!(matches!(err, ContractError::ListingExceeded { listing_id: 1, available } if available == Uint128::new(15)));
+        let msg = ExecuteMsg::BuyListedFraction { listing_id: 1, amount: Uint128::new(10) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("bob", &coins(100, "ucore")), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPayment { .. }));
+
+        // the seller is paid at the listing price, less the platform fee
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bob", &coins(120, "ucore")), msg).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send { to_address: "alice".to_string(), amount: coins(108, "ucore") }));
+        assert_eq!(res.messages[1].msg, CosmosMsg::Bank(BankMsg::Send { to_address: "owner".to_string(), amount: coins(12, "ucore") }));
+        assert_eq!(balance(&deps, "bob"), Uint128::new(10));
+
+        // only the seller can cancel, getting the unsold fractions back
+        let err = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), ExecuteMsg::CancelListing { listing_id: 1 }).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::CancelListing { listing_id: 1 }).unwrap();
+        assert_eq!(balance(&deps, "alice"), Uint128::new(10));
+        let msg = QueryMsg::Listings { token_id: Some(1), start_after: None, limit: None };
+        let listings: ListingsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(listings.listings.is_empty());
+    }
+
+    #[test]
+    fn redeem_requires_every_fraction() {
+        let mut deps = setup_with_asset();
+        buy(&mut deps, "alice", 10).unwrap();
+
+        // neither the owner nor alice holds the whole supply
+        for holder in ["issuer", "alice"] {
+            let err = execute(deps.as_mut(), mock_env(), mock_info(holder, &[]), ExecuteMsg::RedeemAsset { token_id: 1 }).unwrap_err();
+            assert!(matches!(err, ContractError::IncompleteOwnership { .. }));
+        }
+
+        // the owner holds the unsold fractions and gets the sold ones back
+        let msg = ExecuteMsg::TransferFraction { token_id: 1, to: "issuer".to_string(), amount: Uint128::new(10) };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), ExecuteMsg::RedeemAsset { token_id: 1 }).unwrap();
+        assert!(res.attributes.iter().any(|attr| attr.key == "burned" && attr.value == "100"));
+        assert_eq!(ASSETS.load(&deps.storage, 1).unwrap().status, AssetStatus::Redeemed);
+        assert_eq!(balance(&deps, "issuer"), Uint128::zero());
+
+        // a redeemed asset can no longer be traded or redeemed
+        let err = buy(&mut deps, "alice", 1).unwrap_err();
+        assert!(matches!(err, ContractError::AssetRedeemed { token_id: 1 }));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), ExecuteMsg::RedeemAsset { token_id: 1 }).unwrap_err();
+        assert!(matches!(err, ContractError::AssetRedeemed { token_id: 1 }));
+    }
+
+    #[test]
+    fn force_buyout_and_claim() {
+        let mut deps = setup_with_asset();
+        buy(&mut deps, "alice", 10).unwrap();
+        buy(&mut deps, "bob", 15).unwrap();
+
+        let buyout = |price: u128| ExecuteMsg::ForceBuyout { token_id: 1, price: Uint128::new(price) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &coins(1080, "ucore")), buyout(12)).unwrap_err();
+        assert!(matches!(err, ContractError::BelowBuyoutThreshold { .. }));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("issuer", &coins(225, "ucore")), buyout(9)).unwrap_err();
+        assert!(matches!(err, ContractError::BuyoutPriceTooLow { min_price } if min_price == Uint128::new(10)));
+
+        // with the 75 unsold fractions the owner reaches the threshold and pays for the other 25
+        let err = execute(deps.as_mut(), mock_env(), mock_info("issuer", &coins(250, "ucore")), buyout(12)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPayment { .. }));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("issuer", &coins(300, "ucore")), buyout(12)).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(ASSETS.load(&deps.storage, 1).unwrap().status, AssetStatus::Redeemed);
+
+        let err = execute(deps.as_mut(), mock_env(), mock_info("carol", &[]), ExecuteMsg::ClaimBuyout { token_id: 1 }).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientBalance { .. }));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::ClaimBuyout { token_id: 1 }).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send { to_address: "alice".to_string(), amount: coins(120, "ucore") }));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), ExecuteMsg::ClaimBuyout { token_id: 1 }).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send { to_address: "bob".to_string(), amount: coins(180, "ucore") }));
+
+        // fractions are burned by the claim, so it pays out once
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::ClaimBuyout { token_id: 1 }).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientBalance { .. }));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::ClaimBuyout { token_id: 2 }).unwrap_err();
+        assert!(matches!(err, ContractError::NoBuyout { token_id: 2 }));
+    }
+
+    #[test]
+    fn holder_buyout_pays_owner_for_unsold_fractions() {
+        let mut deps = setup_with_asset();
+        buy(&mut deps, "alice", 80).unwrap();
+
+        let msg = ExecuteMsg::ForceBuyout { token_id: 1, price: Uint128::new(10) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &coins(200, "ucore")), msg).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send { to_address: "issuer".to_string(), amount: coins(200, "ucore") }));
+        assert_eq!(balance(&deps, "alice"), Uint128::zero());
+    }
+
+    // asset 1 restricted to allowlisted holders of at most 30 fractions; alice holds 20 and lists 10 more
+    fn setup_restricted_asset() -> CoreumDeps {
+        let mut deps = setup_with_asset();
+        let msg = ExecuteMsg::SetComplianceOfficer { address: "officer".to_string(), enabled: true };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        let msg = ExecuteMsg::SetAssetCompliance { token_id: 1, allowlist_enabled: true, holding_cap: Some(Uint128::new(30)) };
+        execute(deps.as_mut(), mock_env(), mock_info("officer", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateAllowlist { token_id: 1, add: vec!["alice".to_string(), "bob".to_string()], remove: vec![] };
+        execute(deps.as_mut(), mock_env(), mock_info("officer", &[]), msg).unwrap();
+        buy(&mut deps, "alice", 30).unwrap();
+        let msg = ExecuteMsg::ListFraction { token_id: 1, amount: Uint128::new(10), price: Uint128::new(10) };
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+        deps
+    }
+
+    #[test]
+    fn compliance_applies_to_every_credit() {
+        let mut deps = setup_with_asset();
+        let msg = ExecuteMsg::SetAssetCompliance { token_id: 1, allowlist_enabled: true, holding_cap: None };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let not_allowlisted = ContractError::NotAllowlisted { token_id: 1, address: "carol".to_string() }.to_string();
+        let cap_exceeded = ContractError::HoldingCapExceeded { token_id: 1, cap: Uint128::new(30) }.to_string();
+        let transfer = |to: &str, amount: u128| ExecuteMsg::TransferFraction { token_id: 1, to: to.to_string(), amount: Uint128::new(amount) };
+        let grant = |to: &str, amount: u128| ExecuteMsg::TransferOwnership { token_id: 1, to: to.to_string(), amount: Uint128::new(amount) };
+        let buy_listed = |amount: u128| ExecuteMsg::BuyListedFraction { listing_id: 1, amount: Uint128::new(amount) };
+        let cases = [
+            ("carol", coins(10, "ucore"), ExecuteMsg::BuyFraction { token_id: 1, amount: Uint128::new(1) }, &not_allowlisted),
+            ("bob", coins(310, "ucore"), ExecuteMsg::BuyFraction { token_id: 1, amount: Uint128::new(31) }, &cap_exceeded),
+            ("issuer", vec![], grant("carol", 1), &not_allowlisted),
+            // listed fractions don't count towards the seller's cap
+            ("issuer", vec![], grant("alice", 11), &cap_exceeded),
+            ("alice", vec![], transfer("carol", 1), &not_allowlisted),
+            ("carol", coins(10, "ucore"), buy_listed(1), &not_allowlisted),
+        ];
+        // each rejection runs on its own copy of the restricted asset
+        for (sender, funds, msg, expected) in cases {
+            let mut deps = setup_restricted_asset();
+            let err = execute(deps.as_mut(), mock_env(), mock_info(sender, &funds), msg).unwrap_err();
+            assert_eq!(&err.to_string(), expected);
+        }
+
+        // allowlisted holders are credited up to the cap on every path
+        let mut deps = setup_restricted_asset();
+        buy(&mut deps, "bob", 10).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), grant("bob", 10)).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), transfer("bob", 5)).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("bob", &coins(50, "ucore")), buy_listed(5)).unwrap();
+        assert_eq!(balance(&deps, "bob"), Uint128::new(30));
+
+        let msg = QueryMsg::Eligibility { token_id: 1, address: "bob".to_string(), amount: Uint128::new(1) };
+        let res: EligibilityResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(res, EligibilityResponse { allowlisted: true, balance: Uint128::new(30), eligible: false });
+
+        // a cancelled listing returns to its seller even after they left the allowlist
+        let msg = ExecuteMsg::UpdateAllowlist { token_id: 1, add: vec![], remove: vec!["alice".to_string()] };
+        execute(deps.as_mut(), mock_env(), mock_info("officer", &[]), msg).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), ExecuteMsg::CancelListing { listing_id: 1 }).unwrap();
+        assert_eq!(balance(&deps, "alice"), Uint128::new(20));
+    }
+}
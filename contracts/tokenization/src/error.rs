@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,9 +6,60 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Invalid funds, expected a single {denom} coin")]
+    InvalidFunds { denom: String },
+
+    #[error("Insufficient remaining supply: {remaining}")]
+    InsufficientSupply { remaining: Uint128 },
+
+    #[error("Insufficient fractional balance: {balance}")]
+    InsufficientBalance { balance: Uint128 },
+
+    #[error("Listing {listing_id} has only {available} fractions available")]
+    ListingExceeded { listing_id: u64, available: Uint128 },
+
+    #[error("Invalid payment, expected {expected}")]
+    InvalidPayment { expected: String },
+
+    #[error("Invalid document hash {hash}, expected a hex encoded SHA-256 digest")]
+    InvalidDocumentHash { hash: String },
+
+    #[error("{address} is not allowlisted for asset {token_id}")]
+    NotAllowlisted { token_id: u64, address: String },
+
+    #[error("Holding cap of {cap} exceeded for asset {token_id}")]
+    HoldingCapExceeded { token_id: u64, cap: Uint128 },
+
+    #[error("Platform fee must not exceed 100%")]
+    InvalidPlatformFee {},
+
+    #[error("Buyout threshold must be a supermajority between 50% and 100%")]
+    InvalidBuyoutThreshold {},
+
+    #[error("Asset {token_id} has been redeemed")]
+    AssetRedeemed { token_id: u64 },
+
+    #[error("Redemption requires holding all {total_supply} fractions")]
+    IncompleteOwnership { total_supply: Uint128 },
+
+    #[error("Forced buyout requires holding at least {threshold} of the supply")]
+    BelowBuyoutThreshold { threshold: String },
+
+    #[error("Buyout price must be at least the issuance price of {min_price}")]
+    BuyoutPriceTooLow { min_price: Uint128 },
+
+    #[error("Valuation price must be greater than zero")]
+    InvalidValuation {},
+
+    #[error("No buyout to claim for asset {token_id}")]
+    NoBuyout { token_id: u64 },
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Decimal, Timestamp, Uint128};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -8,14 +8,38 @@ pub struct InstantiateMsg {
     pub subunit: String,
     pub precision: u8,
     pub initial_amount: Uint128,
+    pub payment_denom: String,
+    pub platform_fee: Decimal,
+    pub buyout_threshold: Decimal,
+    /// Price oracle allowed to update asset valuations alongside the appraisers
+    pub oracle: Option<String>,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     CreateAsset { total_supply: Uint128, price: Uint128, uri: String, asset_type: AssetType },
     TransferOwnership { token_id: u64, to: String, amount: Uint128 },
+    BuyFraction { token_id: u64, amount: Uint128 },
+    TransferFraction { token_id: u64, to: String, amount: Uint128 },
+    ListFraction { token_id: u64, amount: Uint128, price: Uint128 },
+    BuyListedFraction { listing_id: u64, amount: Uint128 },
+    CancelListing { listing_id: u64 },
+    SetRegistrar { address: String, enabled: bool },
+    UpdateAssetMetadata { token_id: u64, uri: String, document_hashes: Vec<String> },
+    SetComplianceOfficer { address: String, enabled: bool },
+    SetAssetCompliance { token_id: u64, allowlist_enabled: bool, holding_cap: Option<Uint128> },
+    UpdateAllowlist { token_id: u64, add: Vec<String>, remove: Vec<String> },
+    RedeemAsset { token_id: u64 },
+    ForceBuyout { token_id: u64, price: Uint128 },
+    ClaimBuyout { token_id: u64 },
     MintSmartToken { to: String, amount: Uint128 },
     TransferSmartToken { to: String, amount: Uint128 },
+    SetAppraiser { address: String, enabled: bool },
+    SetOracle { address: Option<String> },
+    UpdateValuation { token_id: u64, new_price: Uint128, appraisal_uri: String },
 }
 
 #[cw_serde]
@@ -25,6 +49,138 @@ pub enum QueryMsg {
     FractionalOwnership { token_id: u64, owner: String },
     #[returns(String)]
     TokenURI { token_id: u64 },
+    #[returns(SmartTokenInfoResponse)]
+    SmartTokenInfo {},
+    #[returns(Uint128)]
+    SmartTokenBalance { address: String },
+    /// Smart token balance as it stood just before block `height`
+    #[returns(Uint128)]
+    SmartTokenBalanceAt { address: String, height: u64 },
+    #[returns(AssetInfoResponse)]
+    AssetInfo { token_id: u64 },
+    #[returns(ListingResponse)]
+    Listing { listing_id: u64 },
+    #[returns(ListingsResponse)]
+    Listings { token_id: Option<u64>, start_after: Option<u64>, limit: Option<u32> },
+    #[returns(AssetHoldersResponse)]
+    AssetHolders { token_id: u64, start_after: Option<String>, limit: Option<u32> },
+    #[returns(AssetSummaryResponse)]
+    AssetSummary { token_id: u64 },
+    #[returns(AssetComplianceResponse)]
+    AssetCompliance { token_id: u64 },
+    #[returns(EligibilityResponse)]
+    Eligibility { token_id: u64, address: String, amount: Uint128 },
+    #[returns(AssetHistoryResponse)]
+    AssetHistory { token_id: u64, start_after: Option<u32>, limit: Option<u32> },
+    #[returns(ValuationResponse)]
+    CurrentValuation { token_id: u64 },
+    #[returns(ValuationHistoryResponse)]
+    ValuationHistory { token_id: u64, start_after: Option<u32>, limit: Option<u32> },
+}
+
+#[cw_serde]
+pub struct SmartTokenInfoResponse {
+    pub owner: String,
+    pub denom: String,
+    pub total_supply: Uint128,
+    pub precision: u32,
+}
+
+#[cw_serde]
+pub struct AssetInfoResponse {
+    pub token_id: u64,
+    pub owner: String,
+    pub total_supply: Uint128,
+    pub remaining_supply: Uint128,
+    pub price: Uint128,
+    pub uri: String,
+    pub redeemed: bool,
+    pub buyout_price: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct HolderBalance {
+    pub holder: String,
+    pub balance: Uint128,
+}
+
+#[cw_serde]
+pub struct AssetHoldersResponse {
+    pub token_id: u64,
+    pub holders: Vec<HolderBalance>,
+}
+
+#[cw_serde]
+pub struct AssetSummaryResponse {
+    pub token_id: u64,
+    pub total_supply: Uint128,
+    /// Fractions not sold by the asset owner yet
+    pub remaining_supply: Uint128,
+    /// Fractions held by holders, excluding those escrowed in listings
+    pub held_supply: Uint128,
+    /// Fractions escrowed in open listings
+    pub listed_supply: Uint128,
+    pub holder_count: u64,
+    pub average_holding: Uint128,
+}
+
+#[cw_serde]
+pub struct AssetComplianceResponse {
+    pub token_id: u64,
+    pub allowlist_enabled: bool,
+    pub holding_cap: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct EligibilityResponse {
+    pub allowlisted: bool,
+    pub balance: Uint128,
+    pub eligible: bool,
+}
+
+#[cw_serde]
+pub struct ListingResponse {
+    pub listing_id: u64,
+    pub seller: String,
+    pub token_id: u64,
+    pub amount: Uint128,
+    pub price: Uint128,
+}
+
+#[cw_serde]
+pub struct ListingsResponse {
+    pub listings: Vec<ListingResponse>,
+}
+
+#[cw_serde]
+pub struct MetadataVersionResponse {
+    pub version: u32,
+    pub uri: String,
+    pub document_hashes: Vec<String>,
+    pub updated_by: String,
+    pub updated_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct AssetHistoryResponse {
+    pub token_id: u64,
+    pub versions: Vec<MetadataVersionResponse>,
+}
+
+#[cw_serde]
+pub struct ValuationResponse {
+    pub version: u32,
+    pub price: Uint128,
+    /// Appraisal report backing the valuation, `None` for the issuance price
+    pub appraisal_uri: Option<String>,
+    pub appraised_by: String,
+    pub appraised_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct ValuationHistoryResponse {
+    pub token_id: u64,
+    pub valuations: Vec<ValuationResponse>,
 }
 
 #[cw_serde]
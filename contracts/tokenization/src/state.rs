@@ -1,8 +1,17 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map};
 use serde::{Deserialize, Serialize};
 
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,
+    pub payment_denom: String,
+    pub platform_fee: Decimal,
+    pub buyout_threshold: Decimal,
+    pub oracle: Option<Addr>,
+}
+
 #[cw_serde]
 pub struct TokenizedAsset {
     pub owner: Addr,
@@ -11,6 +20,7 @@ pub struct TokenizedAsset {
     pub price: Uint128,
     pub uri: String,
     pub asset_type: AssetType,
+    pub status: AssetStatus,
 }
 
 #[cw_serde]
@@ -18,6 +28,66 @@ pub enum AssetType {
     RealWorldAsset
 }
 
+#[cw_serde]
+pub enum AssetStatus {
+    Active,
+    Redeemed,
+}
+
+#[cw_serde]
+pub struct Buyout {
+    pub buyer: Addr,
+    pub price: Uint128,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
 pub const ASSETS: Map<u64, TokenizedAsset> = Map::new("assets");
 pub const NEXT_TOKEN_ID: Item<u64> = Item::new("next_token_id");
-pub const FRACTIONAL_BALANCES: Map<(Addr, u64), Uint128> = Map::new("fractional_balances");
\ No newline at end of file
+pub use fractions::{HolderStats, FRACTIONAL_BALANCES, HOLDER_STATS};
+
+#[cw_serde]
+pub struct Listing {
+    pub seller: Addr,
+    pub token_id: u64,
+    pub amount: Uint128,
+    pub price: Uint128,
+}
+
+pub const LISTINGS: Map<u64, Listing> = Map::new("listings");
+pub const NEXT_LISTING_ID: Item<u64> = Item::new("next_listing_id");
+
+#[cw_serde]
+pub struct MetadataVersion {
+    pub uri: String,
+    pub document_hashes: Vec<String>,
+    pub updated_by: Addr,
+    pub updated_at: Timestamp,
+}
+
+pub const REGISTRARS: Map<Addr, bool> = Map::new("registrars");
+pub const ASSET_HISTORY: Map<(u64, u32), MetadataVersion> = Map::new("asset_history");
+pub const METADATA_VERSIONS: Map<u64, u32> = Map::new("metadata_versions");
+pub const BUYOUTS: Map<u64, Buyout> = Map::new("buyouts");
+
+#[cw_serde]
+#[derive(Default)]
+pub struct AssetCompliance {
+    pub allowlist_enabled: bool,
+    pub holding_cap: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct Valuation {
+    pub price: Uint128,
+    pub appraisal_uri: Option<String>,
+    pub appraised_by: Addr,
+    pub appraised_at: Timestamp,
+}
+
+pub const APPRAISERS: Map<Addr, bool> = Map::new("appraisers");
+pub const VALUATION_HISTORY: Map<(u64, u32), Valuation> = Map::new("valuation_history");
+pub const VALUATION_VERSIONS: Map<u64, u32> = Map::new("valuation_versions");
+
+pub const COMPLIANCE_OFFICERS: Map<Addr, bool> = Map::new("compliance_officers");
+pub const ASSET_COMPLIANCE: Map<u64, AssetCompliance> = Map::new("asset_compliance");
+pub const ALLOWLIST: Map<(u64, Addr), bool> = Map::new("allowlist");
\ No newline at end of file
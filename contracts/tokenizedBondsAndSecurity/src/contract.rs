@@ -1,58 +1,136 @@
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, AssetType as MsgAssetType};
-use crate::state::{TokenizedAsset, ASSETS, FRACTIONAL_BALANCES, NEXT_TOKEN_ID, AssetType as StateAssetType};
+use crate::msg::{BondInfoResponse, RateHistoryResponse, RatePoint, RateKind as MsgRateKind, CommitmentResponse, DistributionResponse, ExecuteMsg, HolderBalance, HoldersResponse, MigrateMsg, SmartTokenInfoResponse, SubscriptionResponse, AllocationMode as MsgAllocationMode, SubscriptionStatus as MsgSubscriptionStatus, InstantiateMsg, QueryMsg, AssetType as MsgAssetType, CouponFrequency as MsgCouponFrequency};
+use crate::state::{Config, CouponAccount, CouponState, Distribution, Subscription, TokenizedAsset, ASSETS, CONFIG, RATE_HISTORY, RateKind as StateRateKind, COMMITMENTS, COUPON_ACCOUNTS, COUPON_STATE, DISTRIBUTIONS, DIVIDEND_ENTITLEMENTS, FRACTIONAL_BALANCES, LEGACY_COUPON_ACCOUNTS, LEGACY_FRACTIONAL_BALANCES, NEXT_DISTRIBUTION_ID, NEXT_TOKEN_ID, SUBSCRIPTIONS, AllocationMode as StateAllocationMode, AssetType as StateAssetType, SubscriptionStatus as StateSubscriptionStatus, CouponFrequency as StateCouponFrequency};
 use cosmwasm_std::{
-    entry_point, to_binary, BankMsg, Binary, CanonicalAddr, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Uint128, WasmMsg
+    entry_point, to_binary, Addr, BankMsg, Binary, CanonicalAddr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Storage, Timestamp, Uint128, WasmMsg
 };
-use cw2::set_contract_version;
-use crate::smarttoken::{BALANCES, TOKEN_INFO};
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
+use smarttoken::{TokenInfo, BALANCES, TOKEN_INFO};
+use coreum_wasm_sdk::assetft;
 use coreum_wasm_sdk::core::{CoreumMsg, CoreumQueries};
 
 const CONTRACT_NAME: &str = "tokenized-bonds-securities";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Last version storing balances and coupon accounts keyed by holder first
+const LEGACY_VERSION: &str = "0.1.0";
+
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
 
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut<CoreumQueries>,
-    _env: Env,
+    env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response<CoreumMsg>, ContractError> {
     let owner = deps.api.addr_validate(&msg.owner)?;
+    let rate_oracle = msg.rate_oracle.map(|oracle| deps.api.addr_validate(&oracle)).transpose()?;
+    CONFIG.save(deps.storage, &Config { owner: owner.clone(), rate_oracle, max_rate_age: msg.max_rate_age })?;
     NEXT_TOKEN_ID.save(deps.storage, &1)?;
+    NEXT_DISTRIBUTION_ID.save(deps.storage, &1)?;
+
+    // Issue the smart token backing the platform; the chain gives the initial supply to the contract as
+    // issuer, so it is passed on to the owner who is credited with it
+    let denom = format!("{}-{}", msg.subunit, env.contract.address).to_lowercase();
+    TOKEN_INFO.save(deps.storage, &TokenInfo { owner: owner.clone(), total_supply: msg.initial_amount, denom: denom.clone(), precision: u32::from(msg.precision) })?;
+    if !msg.initial_amount.is_zero() {
+        BALANCES.save(deps.storage, owner.clone(), &msg.initial_amount, env.block.height)?;
+    }
+    let issue_msg = CoreumMsg::AssetFT(assetft::Msg::Issue {
+        symbol: msg.symbol,
+        subunit: msg.subunit,
+        precision: u32::from(msg.precision),
+        initial_amount: msg.initial_amount,
+        description: None,
+        features: Some(vec![0]), // 0 - minting
+        burn_rate: Some("0".into()),
+        send_commission_rate: Some("0".into()),
+    });
+
+    let mut res = Response::new().add_message(issue_msg);
+    if !msg.initial_amount.is_zero() {
+        res = res.add_message(BankMsg::Send { to_address: owner.to_string(), amount: vec![Coin::new(msg.initial_amount.u128(), denom.clone())] });
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    Ok(Response::new().add_attribute("method", "instantiate").add_attribute("owner", owner.to_string()))
+    Ok(res.add_attribute("method", "instantiate").add_attribute("owner", owner.to_string()).add_attribute("denom", denom))
 }
 
 #[entry_point]
 pub fn execute(
     deps: DepsMut<CoreumQueries>,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response<CoreumMsg>, ContractError> {
     match msg {
-        ExecuteMsg::CreateAsset { total_supply, price, uri, asset_type } => create_asset(deps, info, total_supply, price, uri, asset_type),
-        ExecuteMsg::PayoutDividends { token_id } => payout_dividends(deps, info, token_id),
-        ExecuteMsg::MintSmartToken { to, amount } => execute_mint_smart_token(deps, info, to, amount),
-        ExecuteMsg::TransferSmartToken { to, amount } => execute_transfer_smart_token(deps, info, to, amount),
+        ExecuteMsg::CreateAsset { total_supply, price, uri, asset_type, denom, face_value, coupon_rate, rate_kind, coupon_frequency, maturity } => {
+            let terms = BondTerms { denom, face_value, coupon_rate, rate_kind, coupon_frequency, maturity };
+            create_asset(deps, env, info, total_supply, price, uri, asset_type, terms)
+        }
+        ExecuteMsg::UpdateRateOracle { rate_oracle, max_rate_age } => update_rate_oracle(deps, info, rate_oracle, max_rate_age),
+        ExecuteMsg::PushRate { rate, timestamp } => push_rate(deps, env, info, rate, timestamp),
+        ExecuteMsg::DistributeDividend { token_id, claim_deadline, limit } => distribute_dividend(deps, env, info, token_id, claim_deadline, limit),
+        ExecuteMsg::RecordEntitlements { token_id, distribution_id, limit } => execute_record_entitlements(deps, token_id, distribution_id, limit),
+        ExecuteMsg::ClaimDividend { token_id, distribution_id } => claim_dividend(deps, env, info, token_id, distribution_id),
+        ExecuteMsg::ReclaimDividend { token_id, distribution_id } => reclaim_dividend(deps, env, info, token_id, distribution_id),
+        ExecuteMsg::OpenSubscription { token_id, start, end, mode } => open_subscription(deps, env, info, token_id, start, end, mode),
+        ExecuteMsg::Subscribe { token_id } => subscribe(deps, env, info, token_id),
+        ExecuteMsg::CloseSubscription { token_id, limit } => close_subscription(deps, env, token_id, limit),
+        ExecuteMsg::FundBond { token_id } => fund_bond(deps, info, token_id),
+        ExecuteMsg::PayCoupon { token_id } => pay_coupon(deps, env, token_id),
+        ExecuteMsg::ClaimCoupon { token_id } => claim_coupon(deps, info, token_id),
+        ExecuteMsg::RedeemAtMaturity { token_id } => redeem_at_maturity(deps, env, info, token_id),
+        ExecuteMsg::MintSmartToken { to, amount } => execute_mint_smart_token(deps, env, info, to, amount),
+        ExecuteMsg::TransferSmartToken { to, amount } => execute_transfer_smart_token(deps, env, info, to, amount),
     }
 }
 
+/// Bond specific parameters of `ExecuteMsg::CreateAsset`
+struct BondTerms {
+    denom: String,
+    face_value: Uint128,
+    coupon_rate: Decimal,
+    rate_kind: MsgRateKind,
+    coupon_frequency: MsgCouponFrequency,
+    maturity: Timestamp,
+}
+
+#[allow(clippy::too_many_arguments)]
 fn create_asset(
     deps: DepsMut<CoreumQueries>,
+    env: Env,
     info: MessageInfo,
     total_supply: Uint128,
     price: Uint128,
     uri: String,
     asset_type: MsgAssetType,
+    terms: BondTerms,
 ) -> Result<Response<CoreumMsg>, ContractError> {
     let owner = info.sender.clone();
     let token_id = NEXT_TOKEN_ID.load(deps.storage)?;
 
+    if terms.maturity <= env.block.time {
+        return Err(ContractError::InvalidMaturity {});
+    }
+
     let asset_type = match asset_type {
         MsgAssetType::BondOrSecurity => StateAssetType::BondOrSecurity,
     };
+    let coupon_frequency = match terms.coupon_frequency {
+        MsgCouponFrequency::Annual => StateCouponFrequency::Annual,
+        MsgCouponFrequency::SemiAnnual => StateCouponFrequency::SemiAnnual,
+        MsgCouponFrequency::Quarterly => StateCouponFrequency::Quarterly,
+        MsgCouponFrequency::Monthly => StateCouponFrequency::Monthly,
+    };
+    let rate_kind = match terms.rate_kind {
+        MsgRateKind::Fixed => StateRateKind::Fixed,
+        MsgRateKind::Floating => StateRateKind::Floating,
+    };
 
     let asset = TokenizedAsset {
         owner: owner.clone(),
@@ -61,68 +139,587 @@ fn create_asset(
         price,
         uri,
         asset_type,
+        denom: terms.denom,
+        face_value: terms.face_value,
+        coupon_rate: terms.coupon_rate,
+        rate_kind,
+        coupon_frequency,
+        issued_at: env.block.time,
+        maturity: terms.maturity,
     };
 
     ASSETS.save(deps.storage, token_id, &asset)?;
+    COUPON_STATE.save(deps.storage, token_id, &CouponState::default())?;
     NEXT_TOKEN_ID.save(deps.storage, &(token_id + 1))?;
 
     Ok(Response::new().add_attribute("method", "create_asset").add_attribute("token_id", token_id.to_string()).add_attribute("owner", owner.to_string()))
 }
 
-fn payout_dividends(
+/// Set the address allowed to publish benchmark rates and how old a rate may be when used
+fn update_rate_oracle(
     deps: DepsMut<CoreumQueries>,
-    _info: MessageInfo,
-    token_id: u64,
+    info: MessageInfo,
+    rate_oracle: Option<String>,
+    max_rate_age: u64,
 ) -> Result<Response<CoreumMsg>, ContractError> {
-    let asset = ASSETS.load(deps.storage, token_id)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.rate_oracle = rate_oracle.map(|oracle| deps.api.addr_validate(&oracle)).transpose()?;
+    config.max_rate_age = max_rate_age;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_rate_oracle")
+        .add_attribute("rate_oracle", config.rate_oracle.map(|oracle| oracle.to_string()).unwrap_or_default())
+        .add_attribute("max_rate_age", max_rate_age.to_string()))
+}
 
-    if asset.asset_type != StateAssetType::BondOrSecurity {
+/// Publish a benchmark rate observed at `timestamp`; only the configured oracle may publish
+fn push_rate(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    rate: Decimal,
+    timestamp: Timestamp,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.rate_oracle.as_ref() != Some(&info.sender) {
         return Err(ContractError::Unauthorized {});
     }
 
-    let total_dividends = asset.total_supply.checked_sub(asset.remaining_supply).map_err(|e| ContractError::Std(StdError::generic_err(format!("Overflow error: {}", e))))?;
-    if total_dividends.is_zero() {
+    // Rates must be published in order and cannot come from the future
+    let latest = latest_rate(deps.storage)?;
+    if timestamp > env.block.time || latest.map_or(false, |(published, _)| timestamp.seconds() <= published) {
+        return Err(ContractError::InvalidRateTimestamp {});
+    }
+    RATE_HISTORY.save(deps.storage, timestamp.seconds(), &rate)?;
+
+    Ok(Response::new().add_attribute("method", "push_rate").add_attribute("rate", rate.to_string()).add_attribute("timestamp", timestamp.seconds().to_string()))
+}
+
+fn latest_rate(storage: &dyn Storage) -> StdResult<Option<(u64, Decimal)>> {
+    RATE_HISTORY.range(storage, None, None, Order::Descending).next().transpose()
+}
+
+/// Annual rate a bond pays at `now`; floating-rate bonds add their spread to a benchmark rate that is not stale
+fn annual_coupon_rate(storage: &dyn Storage, asset: &TokenizedAsset, now: Timestamp) -> Result<Decimal, ContractError> {
+    if asset.rate_kind == StateRateKind::Fixed {
+        return Ok(asset.coupon_rate);
+    }
+
+    let config = CONFIG.load(storage)?;
+    let (published, benchmark) = latest_rate(storage)?.ok_or(ContractError::NoRate {})?;
+    let age = now.seconds().saturating_sub(published);
+    if age > config.max_rate_age {
+        return Err(ContractError::StaleRate { age, max_age: config.max_rate_age });
+    }
+    Ok(benchmark + asset.coupon_rate)
+}
+
+/// Offer the remaining supply of an asset to investors during a subscription window
+fn open_subscription(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    token_id: u64,
+    start: Timestamp,
+    end: Timestamp,
+    mode: MsgAllocationMode,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let asset = ASSETS.load(deps.storage, token_id)?;
+    if info.sender != asset.owner {
         return Err(ContractError::Unauthorized {});
     }
+    if SUBSCRIPTIONS.has(deps.storage, token_id) {
+        return Err(ContractError::SubscriptionExists { token_id });
+    }
+    if end <= start || end <= env.block.time || end >= asset.maturity {
+        return Err(ContractError::InvalidWindow {});
+    }
+
+    let mode = match mode {
+        MsgAllocationMode::ProRata => StateAllocationMode::ProRata,
+        MsgAllocationMode::FirstComeFirstServed => StateAllocationMode::FirstComeFirstServed,
+    };
+    let subscription = Subscription {
+        start,
+        end,
+        mode,
+        offered: asset.remaining_supply,
+        requested: Uint128::zero(),
+        allocated: Uint128::zero(),
+        status: StateSubscriptionStatus::Open,
+    };
+    SUBSCRIPTIONS.save(deps.storage, token_id, &subscription)?;
+
+    Ok(Response::new().add_attribute("method", "open_subscription").add_attribute("token_id", token_id.to_string()).add_attribute("offered", subscription.offered.to_string()))
+}
+
+/// Commit the attached funds to buy fractions at the issuance price
+fn subscribe(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    token_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let asset = ASSETS.load(deps.storage, token_id)?;
+    let mut subscription = SUBSCRIPTIONS.may_load(deps.storage, token_id)?.ok_or(ContractError::SubscriptionNotOpen { token_id })?;
+    let now = env.block.time;
+    if subscription.status != StateSubscriptionStatus::Open || now < subscription.start || now > subscription.end {
+        return Err(ContractError::SubscriptionNotOpen { token_id });
+    }
+
+    // Funds must buy a whole number of fractions
+    let funds = match info.funds.as_slice() {
+        [coin] if coin.denom == asset.denom => coin.amount,
+        _ => return Err(ContractError::InvalidFunds { denom: asset.denom }),
+    };
+    if asset.price.is_zero() || funds.is_zero() || !(funds % asset.price).is_zero() {
+        return Err(ContractError::InvalidFunds { denom: asset.denom });
+    }
+    let fractions = funds / asset.price;
+
+    if subscription.mode == StateAllocationMode::FirstComeFirstServed {
+        let available = subscription.offered.checked_sub(subscription.requested)?;
+        if fractions > available {
+            return Err(ContractError::Oversubscribed { available });
+        }
+    }
+    subscription.requested = subscription.requested.checked_add(fractions)?;
+    SUBSCRIPTIONS.save(deps.storage, token_id, &subscription)?;
+
+    let committed = COMMITMENTS.may_load(deps.storage, (token_id, info.sender.clone()))?.unwrap_or_default();
+    COMMITMENTS.save(deps.storage, (token_id, info.sender.clone()), &committed.checked_add(fractions)?)?;
+
+    Ok(Response::new().add_attribute("method", "subscribe").add_attribute("token_id", token_id.to_string()).add_attribute("investor", info.sender.to_string()).add_attribute("fractions", fractions.to_string()))
+}
+
+/// Allocate fractions to up to `limit` commitments once the window has ended, refunding unfilled amounts;
+/// the subscription is finalized once every commitment has been processed
+fn close_subscription(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    token_id: u64,
+    limit: Option<u32>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut asset = ASSETS.load(deps.storage, token_id)?;
+    let mut subscription = SUBSCRIPTIONS.may_load(deps.storage, token_id)?.ok_or(ContractError::SubscriptionNotOpen { token_id })?;
+    if subscription.status == StateSubscriptionStatus::Finalized {
+        return Err(ContractError::SubscriptionNotOpen { token_id });
+    }
+    if env.block.time <= subscription.end {
+        return Err(ContractError::SubscriptionNotEnded { token_id });
+    }
+    subscription.status = StateSubscriptionStatus::Closing;
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let commitments = COMMITMENTS
+        .prefix(token_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
 
     let mut messages = vec![];
-    let balances: StdResult<Vec<_>> = FRACTIONAL_BALANCES.range(deps.storage, None, None, Order::Ascending).collect();
-    let balances = balances?;
-
-    for ((owner_raw, balance_token_id), balance) in balances {
-        if balance_token_id == token_id {
-            let owner = deps.api.addr_humanize(&CanonicalAddr::from(owner_raw.as_bytes()))?;
-            let dividend = total_dividends.multiply_ratio(balance, asset.total_supply);
-            messages.push(CosmosMsg::Bank(BankMsg::Send {
-                to_address: owner.to_string(),
-                amount: vec![Coin { denom: "uasset".to_string(), amount: dividend }],
-            }));
+    let mut proceeds = Uint128::zero();
+    for (investor, requested) in commitments {
+        let allocation = allocation_for(&subscription, requested);
+        let refund = requested.checked_sub(allocation)?.checked_mul(asset.price)?;
+        COMMITMENTS.remove(deps.storage, (token_id, investor.clone()));
+
+        if !allocation.is_zero() {
+            // Settle coupons before the balance changes so the new fractions only earn future coupons
+            settle_coupons(deps.storage, token_id, &investor)?;
+            let balance = FRACTIONAL_BALANCES.may_load(deps.storage, (token_id, investor.clone()))?.unwrap_or_default();
+            FRACTIONAL_BALANCES.save(deps.storage, (token_id, investor.clone()), &balance.checked_add(allocation)?)?;
+            subscription.allocated = subscription.allocated.checked_add(allocation)?;
+            asset.remaining_supply = asset.remaining_supply.checked_sub(allocation)?;
+            proceeds = proceeds.checked_add(allocation.checked_mul(asset.price)?)?;
+        }
+        if !refund.is_zero() {
+            messages.push(BankMsg::Send { to_address: investor.to_string(), amount: vec![Coin { denom: asset.denom.clone(), amount: refund }] });
+        }
+    }
+    if !proceeds.is_zero() {
+        messages.push(BankMsg::Send { to_address: asset.owner.to_string(), amount: vec![Coin { denom: asset.denom.clone(), amount: proceeds }] });
+    }
+
+    if COMMITMENTS.prefix(token_id).range(deps.storage, None, None, Order::Ascending).next().is_none() {
+        subscription.status = StateSubscriptionStatus::Finalized;
+    }
+    ASSETS.save(deps.storage, token_id, &asset)?;
+    SUBSCRIPTIONS.save(deps.storage, token_id, &subscription)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "close_subscription")
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("allocated", subscription.allocated.to_string())
+        .add_attribute("finalized", (subscription.status == StateSubscriptionStatus::Finalized).to_string()))
+}
+
+/// Fractions granted to a commitment, scaled down pro-rata when the offering is oversubscribed
+fn allocation_for(subscription: &Subscription, requested: Uint128) -> Uint128 {
+    if subscription.requested <= subscription.offered {
+        requested
+    } else {
+        requested.multiply_ratio(subscription.offered, subscription.requested)
+    }
+}
+
+/// Escrow the attached funds as a dividend pot and record the pro-rata entitlement of the first `limit` holders
+fn distribute_dividend(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    token_id: u64,
+    claim_deadline: Timestamp,
+    limit: Option<u32>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let asset = ASSETS.load(deps.storage, token_id)?;
+    if info.sender != asset.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if claim_deadline <= env.block.time {
+        return Err(ContractError::InvalidDeadline {});
+    }
+
+    let total = match info.funds.as_slice() {
+        [coin] if coin.denom == asset.denom && !coin.amount.is_zero() => coin.amount,
+        _ => return Err(ContractError::InvalidFunds { denom: asset.denom }),
+    };
+
+    // Dividends go to the fractions held by investors
+    let outstanding = asset.total_supply.checked_sub(asset.remaining_supply)?;
+    if outstanding.is_zero() {
+        return Err(ContractError::NoHolders { token_id });
+    }
+
+    let distribution_id = NEXT_DISTRIBUTION_ID.load(deps.storage)?;
+    let mut distribution = Distribution {
+        token_id,
+        denom: asset.denom,
+        total,
+        claimed: Uint128::zero(),
+        claim_deadline,
+        reclaimed: false,
+        outstanding,
+        recorded: Uint128::zero(),
+        cursor: None,
+        complete: false,
+    };
+    let processed = record_entitlements(deps.storage, distribution_id, &mut distribution, limit)?;
+    DISTRIBUTIONS.save(deps.storage, distribution_id, &distribution)?;
+    NEXT_DISTRIBUTION_ID.save(deps.storage, &(distribution_id + 1))?;
+
+    Ok(Response::new()
+        .add_attribute("method", "distribute_dividend")
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("distribution_id", distribution_id.to_string())
+        .add_attribute("total", total.to_string())
+        .add_attribute("processed", processed.to_string())
+        .add_attribute("complete", distribution.complete.to_string()))
+}
+
+/// Continue recording the entitlements of a distribution that did not fit in a single call
+fn execute_record_entitlements(
+    deps: DepsMut<CoreumQueries>,
+    token_id: u64,
+    distribution_id: u64,
+    limit: Option<u32>,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut distribution = load_distribution(deps.storage, token_id, distribution_id)?;
+    if distribution.complete {
+        return Err(ContractError::EntitlementsRecorded { distribution_id });
+    }
+
+    let processed = record_entitlements(deps.storage, distribution_id, &mut distribution, limit)?;
+    DISTRIBUTIONS.save(deps.storage, distribution_id, &distribution)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "record_entitlements")
+        .add_attribute("distribution_id", distribution_id.to_string())
+        .add_attribute("processed", processed.to_string())
+        .add_attribute("complete", distribution.complete.to_string()))
+}
+
+/// Record the entitlements of the next `limit` holders of the distributed asset, returning how many were processed
+fn record_entitlements(
+    storage: &mut dyn Storage,
+    distribution_id: u64,
+    distribution: &mut Distribution,
+    limit: Option<u32>,
+) -> Result<usize, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = distribution.cursor.clone().map(Bound::exclusive);
+    let holders = FRACTIONAL_BALANCES
+        .prefix(distribution.token_id)
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (holder, balance) in &holders {
+        // Never hand out more than the pot, even if balances moved between pages
+        let available = distribution.total.checked_sub(distribution.recorded)?;
+        let entitlement = distribution.total.multiply_ratio(*balance, distribution.outstanding).min(available);
+        if !entitlement.is_zero() {
+            DIVIDEND_ENTITLEMENTS.save(storage, (distribution_id, holder.clone()), &entitlement)?;
+            distribution.recorded = distribution.recorded.checked_add(entitlement)?;
         }
     }
 
-    Ok(Response::new().add_attribute("method", "payout_dividends").add_attribute("token_id", token_id.to_string()).add_messages(messages))
+    distribution.complete = holders.len() < limit;
+    distribution.cursor = holders.last().map(|(holder, _)| holder.clone()).or_else(|| distribution.cursor.clone());
+    Ok(holders.len())
+}
+
+/// Withdraw the sender's entitlement from a dividend distribution before its deadline
+fn claim_dividend(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    token_id: u64,
+    distribution_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let mut distribution = load_distribution(deps.storage, token_id, distribution_id)?;
+    if distribution.reclaimed {
+        return Err(ContractError::DistributionReclaimed { distribution_id });
+    }
+    if env.block.time > distribution.claim_deadline {
+        return Err(ContractError::ClaimDeadlinePassed { distribution_id });
+    }
+
+    let amount = DIVIDEND_ENTITLEMENTS
+        .may_load(deps.storage, (distribution_id, info.sender.clone()))?
+        .ok_or(ContractError::NothingToClaim {})?;
+    DIVIDEND_ENTITLEMENTS.remove(deps.storage, (distribution_id, info.sender.clone()));
+
+    distribution.claimed = distribution.claimed.checked_add(amount)?;
+    DISTRIBUTIONS.save(deps.storage, distribution_id, &distribution)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send { to_address: info.sender.to_string(), amount: vec![Coin { denom: distribution.denom, amount }] })
+        .add_attribute("method", "claim_dividend").add_attribute("distribution_id", distribution_id.to_string()).add_attribute("holder", info.sender.to_string()).add_attribute("amount", amount.to_string()))
+}
+
+/// Return whatever was not claimed by the deadline to the issuer
+fn reclaim_dividend(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    token_id: u64,
+    distribution_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let asset = ASSETS.load(deps.storage, token_id)?;
+    if info.sender != asset.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut distribution = load_distribution(deps.storage, token_id, distribution_id)?;
+    if distribution.reclaimed {
+        return Err(ContractError::DistributionReclaimed { distribution_id });
+    }
+    if env.block.time <= distribution.claim_deadline {
+        return Err(ContractError::DistributionOpen { distribution_id });
+    }
+
+    let amount = distribution.total.checked_sub(distribution.claimed)?;
+    distribution.reclaimed = true;
+    DISTRIBUTIONS.save(deps.storage, distribution_id, &distribution)?;
+
+    let mut response = Response::new();
+    if !amount.is_zero() {
+        response = response.add_message(BankMsg::Send { to_address: asset.owner.to_string(), amount: vec![Coin { denom: distribution.denom, amount }] });
+    }
+    Ok(response.add_attribute("method", "reclaim_dividend").add_attribute("distribution_id", distribution_id.to_string()).add_attribute("amount", amount.to_string()))
+}
+
+fn load_distribution(storage: &dyn Storage, token_id: u64, distribution_id: u64) -> Result<Distribution, ContractError> {
+    let distribution = DISTRIBUTIONS.load(storage, distribution_id)?;
+    if distribution.token_id != token_id {
+        return Err(ContractError::Std(StdError::not_found("Distribution")));
+    }
+    Ok(distribution)
+}
+
+/// Deposit coupon and principal funds for a bond; only the issuer may fund it
+fn fund_bond(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    token_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let asset = ASSETS.load(deps.storage, token_id)?;
+    if info.sender != asset.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let amount = match info.funds.as_slice() {
+        [coin] if coin.denom == asset.denom && !coin.amount.is_zero() => coin.amount,
+        _ => return Err(ContractError::InvalidFunds { denom: asset.denom }),
+    };
+
+    let mut coupons = COUPON_STATE.load(deps.storage, token_id)?;
+    coupons.reserve = coupons.reserve.checked_add(amount)?;
+    COUPON_STATE.save(deps.storage, token_id, &coupons)?;
+
+    Ok(Response::new().add_attribute("method", "fund_bond").add_attribute("token_id", token_id.to_string()).add_attribute("amount", amount.to_string()).add_attribute("reserve", coupons.reserve.to_string()))
+}
+
+/// Accrue every coupon period elapsed so far to the holders, paid out of the issuer's reserve;
+/// floating-rate bonds accrue all due periods at the current benchmark rate
+fn pay_coupon(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    token_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let asset = ASSETS.load(deps.storage, token_id)?;
+    let mut coupons = COUPON_STATE.load(deps.storage, token_id)?;
+
+    let due_periods = elapsed_periods(&asset, env.block.time) - coupons.periods_paid;
+    if due_periods == 0 {
+        return Err(ContractError::NoCouponDue { token_id });
+    }
+
+    // Every fraction in the hands of investors earns the coupon
+    let rate = annual_coupon_rate(deps.storage, &asset, env.block.time)?;
+    let accrual = coupon_per_period(&asset, rate) * Decimal::from_ratio(due_periods, 1u64);
+    let outstanding = asset.total_supply.checked_sub(asset.remaining_supply)?;
+    let required = outstanding * accrual;
+    if coupons.reserve < required {
+        return Err(ContractError::InsufficientReserve { reserve: coupons.reserve, required });
+    }
+
+    coupons.reserve = coupons.reserve.checked_sub(required)?;
+    coupons.index = coupons.index + accrual;
+    coupons.periods_paid += due_periods;
+    COUPON_STATE.save(deps.storage, token_id, &coupons)?;
+
+    Ok(Response::new().add_attribute("method", "pay_coupon").add_attribute("token_id", token_id.to_string()).add_attribute("periods", due_periods.to_string()).add_attribute("rate", rate.to_string()).add_attribute("amount", required.to_string()))
+}
+
+/// Withdraw the coupons accrued to the sender
+fn claim_coupon(
+    deps: DepsMut<CoreumQueries>,
+    info: MessageInfo,
+    token_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let asset = ASSETS.load(deps.storage, token_id)?;
+    let mut account = settle_coupons(deps.storage, token_id, &info.sender)?;
+
+    let amount = account.pending;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    account.pending = Uint128::zero();
+    COUPON_ACCOUNTS.save(deps.storage, (token_id, info.sender.clone()), &account)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send { to_address: info.sender.to_string(), amount: vec![Coin { denom: asset.denom, amount }] })
+        .add_attribute("method", "claim_coupon").add_attribute("token_id", token_id.to_string()).add_attribute("holder", info.sender.to_string()).add_attribute("amount", amount.to_string()))
+}
+
+/// Return the face value of the sender's fractions after maturity, together with any unclaimed coupons
+fn redeem_at_maturity(
+    deps: DepsMut<CoreumQueries>,
+    env: Env,
+    info: MessageInfo,
+    token_id: u64,
+) -> Result<Response<CoreumMsg>, ContractError> {
+    let asset = ASSETS.load(deps.storage, token_id)?;
+    if env.block.time < asset.maturity {
+        return Err(ContractError::NotMatured { token_id });
+    }
+
+    let mut coupons = COUPON_STATE.load(deps.storage, token_id)?;
+    if coupons.periods_paid < total_periods(&asset) {
+        return Err(ContractError::OutstandingCoupons { token_id });
+    }
+
+    let balance = FRACTIONAL_BALANCES.may_load(deps.storage, (token_id, info.sender.clone()))?.unwrap_or_default();
+    if balance.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    let principal = balance.checked_mul(asset.face_value)?;
+    if coupons.reserve < principal {
+        return Err(ContractError::InsufficientReserve { reserve: coupons.reserve, required: principal });
+    }
+    coupons.reserve = coupons.reserve.checked_sub(principal)?;
+    COUPON_STATE.save(deps.storage, token_id, &coupons)?;
+
+    // Burn the fractions and close the holder's coupon account
+    let account = settle_coupons(deps.storage, token_id, &info.sender)?;
+    FRACTIONAL_BALANCES.remove(deps.storage, (token_id, info.sender.clone()));
+    COUPON_ACCOUNTS.remove(deps.storage, (token_id, info.sender.clone()));
+
+    let amount = principal.checked_add(account.pending)?;
+    Ok(Response::new()
+        .add_message(BankMsg::Send { to_address: info.sender.to_string(), amount: vec![Coin { denom: asset.denom, amount }] })
+        .add_attribute("method", "redeem_at_maturity").add_attribute("token_id", token_id.to_string()).add_attribute("holder", info.sender.to_string()).add_attribute("principal", principal.to_string()).add_attribute("coupons", account.pending.to_string()))
+}
+
+/// Bring a holder's coupon account up to the current coupon index; must run before the holder's balance changes
+fn settle_coupons(storage: &mut dyn Storage, token_id: u64, holder: &Addr) -> Result<CouponAccount, ContractError> {
+    let coupons = COUPON_STATE.load(storage, token_id)?;
+    let balance = FRACTIONAL_BALANCES.may_load(storage, (token_id, holder.clone()))?.unwrap_or_default();
+    let mut account = COUPON_ACCOUNTS.may_load(storage, (token_id, holder.clone()))?.unwrap_or_default();
+
+    account.pending = account.pending.checked_add(balance * (coupons.index - account.index))?;
+    account.index = coupons.index;
+    COUPON_ACCOUNTS.save(storage, (token_id, holder.clone()), &account)?;
+    Ok(account)
+}
+
+fn coupon_per_period(asset: &TokenizedAsset, annual_rate: Decimal) -> Decimal {
+    annual_rate * Decimal::from_ratio(asset.face_value, asset.coupon_frequency.periods_per_year())
+}
+
+fn coupon_interval(asset: &TokenizedAsset) -> u64 {
+    SECONDS_PER_YEAR / asset.coupon_frequency.periods_per_year()
+}
+
+/// Number of whole coupon periods between issuance and maturity
+fn total_periods(asset: &TokenizedAsset) -> u64 {
+    (asset.maturity.seconds() - asset.issued_at.seconds()) / coupon_interval(asset)
+}
+
+/// Number of coupon periods completed at `now`, capped at maturity
+fn elapsed_periods(asset: &TokenizedAsset, now: Timestamp) -> u64 {
+    let elapsed = now.seconds().saturating_sub(asset.issued_at.seconds()) / coupon_interval(asset);
+    elapsed.min(total_periods(asset))
 }
 
 /// Mint new smart tokens
 fn execute_mint_smart_token(
     deps: DepsMut<CoreumQueries>,
+    env: Env,
     info: MessageInfo,
     to: String,
     amount: Uint128,
 ) -> Result<Response<CoreumMsg>, ContractError> {
-    let token_info = TOKEN_INFO.load(deps.storage)?;
+    let mut token_info = TOKEN_INFO.load(deps.storage)?;
 
     // Ensure the sender is the owner of the token
     if info.sender != token_info.owner {
         return Err(ContractError::Unauthorized {});
     }
 
-    // Update the recipient's balance
+    // Update the recipient's balance and the total supply
     let to_addr = deps.api.addr_validate(&to)?;
     let balance = BALANCES.may_load(deps.storage, to_addr.clone())?.unwrap_or_default();
-    BALANCES.save(deps.storage, to_addr.clone(), &(balance + amount))?;
+    BALANCES.save(deps.storage, to_addr.clone(), &balance.checked_add(amount)?, env.block.height)?;
+    token_info.total_supply = token_info.total_supply.checked_add(amount)?;
+    TOKEN_INFO.save(deps.storage, &token_info)?;
+
+    // Mint the matching amount on chain; it goes to the contract as issuer and is sent on to the recipient
+    let coin = Coin::new(amount.u128(), token_info.denom);
+    let mint_msg = CoreumMsg::AssetFT(assetft::Msg::Mint { coin: coin.clone() });
+    let send_msg = BankMsg::Send { to_address: to_addr.to_string(), amount: vec![coin] };
 
     Ok(Response::new()
+        .add_message(mint_msg)
+        .add_message(send_msg)
         .add_attribute("method", "mint_smart_token")
         .add_attribute("to", to_addr.to_string())
         .add_attribute("amount", amount.to_string()))
@@ -131,6 +728,7 @@ fn execute_mint_smart_token(
 /// Transfer smart tokens
 fn execute_transfer_smart_token(
     deps: DepsMut<CoreumQueries>,
+    env: Env,
     info: MessageInfo,
     to: String,
     amount: Uint128,
@@ -139,17 +737,24 @@ fn execute_transfer_smart_token(
     let to_addr = deps.api.addr_validate(&to)?;
 
     // Ensure the sender has enough balance
-    let sender_balance = BALANCES.load(deps.storage, sender_addr.clone())?;
+    let sender_balance = BALANCES.may_load(deps.storage, sender_addr.clone())?.unwrap_or_default();
     if sender_balance < amount {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::InsufficientBalance { balance: sender_balance });
+    }
+    // The coins travel with the ledger entry so the chain and the ledger keep the same holder
+    let denom = TOKEN_INFO.load(deps.storage)?.denom;
+    match info.funds.as_slice() {
+        [coin] if coin.denom == denom && coin.amount == amount => {}
+        _ => return Err(ContractError::InvalidFunds { denom }),
     }
 
     // Update the sender's and recipient's balances
-    BALANCES.save(deps.storage, sender_addr.clone(), &(sender_balance - amount))?;
+    BALANCES.save(deps.storage, sender_addr.clone(), &sender_balance.checked_sub(amount)?, env.block.height)?;
     let recipient_balance = BALANCES.may_load(deps.storage, to_addr.clone())?.unwrap_or_default();
-    BALANCES.save(deps.storage, to_addr.clone(), &(recipient_balance + amount))?;
+    BALANCES.save(deps.storage, to_addr.clone(), &recipient_balance.checked_add(amount)?, env.block.height)?;
 
     Ok(Response::new()
+        .add_message(BankMsg::Send { to_address: to_addr.to_string(), amount: info.funds })
         .add_attribute("method", "transfer_smart_token")
         .add_attribute("from", sender_addr.to_string())
         .add_attribute("to", to_addr.to_string())
@@ -157,20 +762,406 @@ fn execute_transfer_smart_token(
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn migrate(deps: DepsMut<CoreumQueries>, _env: Env, _msg: MigrateMsg) -> Result<Response<CoreumMsg>, ContractError> {
+    let version = get_contract_version(deps.storage)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    if version.version != LEGACY_VERSION {
+        return Ok(Response::new().add_attribute("method", "migrate"));
+    }
+
+    // move balances and coupon accounts under their asset so its holders can be paged through
+    let balances = LEGACY_FRACTIONAL_BALANCES
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for ((holder, token_id), _) in &balances {
+        LEGACY_FRACTIONAL_BALANCES.remove(deps.storage, (holder.clone(), *token_id));
+    }
+    for ((holder, token_id), balance) in &balances {
+        FRACTIONAL_BALANCES.save(deps.storage, (*token_id, holder.clone()), balance)?;
+    }
+
+    let accounts = LEGACY_COUPON_ACCOUNTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for ((holder, token_id), _) in &accounts {
+        LEGACY_COUPON_ACCOUNTS.remove(deps.storage, (holder.clone(), *token_id));
+    }
+    for ((holder, token_id), account) in &accounts {
+        COUPON_ACCOUNTS.save(deps.storage, (*token_id, holder.clone()), account)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("migrated_balances", balances.len().to_string())
+        .add_attribute("migrated_coupon_accounts", accounts.len().to_string()))
+}
+
+#[entry_point]
+pub fn query(deps: Deps<CoreumQueries>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::FractionalOwnership { token_id, owner } => to_binary(&query_fractional_ownership(deps, token_id, owner)?),
         QueryMsg::TokenURI { token_id } => to_binary(&query_token_uri(deps, token_id)?),
+        QueryMsg::Holders { token_id, start_after, limit } => to_binary(&query_holders(deps, token_id, start_after, limit)?),
+        QueryMsg::BondInfo { token_id } => to_binary(&query_bond_info(deps, token_id)?),
+        QueryMsg::RateHistory { start_before, limit } => to_binary(&query_rate_history(deps, start_before, limit)?),
+        QueryMsg::PendingCoupon { token_id, holder } => to_binary(&query_pending_coupon(deps, token_id, holder)?),
+        QueryMsg::Subscription { token_id } => to_binary(&query_subscription(deps, token_id)?),
+        QueryMsg::Commitment { token_id, investor } => to_binary(&query_commitment(deps, token_id, investor)?),
+        QueryMsg::Distribution { distribution_id } => to_binary(&query_distribution(deps, distribution_id)?),
+        QueryMsg::DividendEntitlement { distribution_id, holder } => to_binary(&query_dividend_entitlement(deps, distribution_id, holder)?),
+        QueryMsg::SmartTokenInfo {} => to_binary(&query_smart_token_info(deps)?),
+        QueryMsg::SmartTokenBalance { address } => to_binary(&query_smart_token_balance(deps, address)?),
     }
 }
 
-fn query_fractional_ownership(deps: Deps, token_id: u64, owner: String) -> StdResult<Uint128> {
+fn query_fractional_ownership(deps: Deps<CoreumQueries>, token_id: u64, owner: String) -> StdResult<Uint128> {
     let owner_addr = deps.api.addr_validate(&owner)?;
-    let balance = FRACTIONAL_BALANCES.may_load(deps.storage, (owner_addr, token_id))?.unwrap_or_default();
+    let balance = FRACTIONAL_BALANCES.may_load(deps.storage, (token_id, owner_addr))?.unwrap_or_default();
     Ok(balance)
 }
 
-fn query_token_uri(deps: Deps, token_id: u64) -> StdResult<String> {
+fn query_token_uri(deps: Deps<CoreumQueries>, token_id: u64) -> StdResult<String> {
     let asset = ASSETS.load(deps.storage, token_id)?;
     Ok(asset.uri)
 }
+
+fn query_holders(deps: Deps<CoreumQueries>, token_id: u64, start_after: Option<String>, limit: Option<u32>) -> StdResult<HoldersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = start_after.map(|addr| deps.api.addr_validate(&addr)).transpose()?;
+    let start = start_addr.map(Bound::exclusive);
+    let holders = FRACTIONAL_BALANCES
+        .prefix(token_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(holder, balance)| HolderBalance { holder: holder.to_string(), balance }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(HoldersResponse { holders })
+}
+
+fn query_bond_info(deps: Deps<CoreumQueries>, token_id: u64) -> StdResult<BondInfoResponse> {
+    let asset = ASSETS.load(deps.storage, token_id)?;
+    let coupons = COUPON_STATE.load(deps.storage, token_id)?;
+    let total_periods = total_periods(&asset);
+    let next_coupon_at = (coupons.periods_paid < total_periods)
+        .then(|| asset.issued_at.plus_seconds((coupons.periods_paid + 1) * coupon_interval(&asset)));
+    let coupon_frequency = match asset.coupon_frequency {
+        StateCouponFrequency::Annual => MsgCouponFrequency::Annual,
+        StateCouponFrequency::SemiAnnual => MsgCouponFrequency::SemiAnnual,
+        StateCouponFrequency::Quarterly => MsgCouponFrequency::Quarterly,
+        StateCouponFrequency::Monthly => MsgCouponFrequency::Monthly,
+    };
+    let rate_kind = match asset.rate_kind {
+        StateRateKind::Fixed => MsgRateKind::Fixed,
+        StateRateKind::Floating => MsgRateKind::Floating,
+    };
+
+    Ok(BondInfoResponse {
+        token_id,
+        denom: asset.denom,
+        face_value: asset.face_value,
+        coupon_rate: asset.coupon_rate,
+        rate_kind,
+        coupon_frequency,
+        issued_at: asset.issued_at,
+        maturity: asset.maturity,
+        periods_paid: coupons.periods_paid,
+        total_periods,
+        next_coupon_at,
+        reserve: coupons.reserve,
+    })
+}
+
+fn query_rate_history(deps: Deps<CoreumQueries>, start_before: Option<Timestamp>, limit: Option<u32>) -> StdResult<RateHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let end = start_before.map(|timestamp| Bound::exclusive(timestamp.seconds()));
+    let rates = RATE_HISTORY
+        .range(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .map(|item| item.map(|(published, rate)| RatePoint { rate, timestamp: Timestamp::from_seconds(published) }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(RateHistoryResponse { rates })
+}
+
+fn query_pending_coupon(deps: Deps<CoreumQueries>, token_id: u64, holder: String) -> StdResult<Uint128> {
+    let holder_addr = deps.api.addr_validate(&holder)?;
+    let coupons = COUPON_STATE.load(deps.storage, token_id)?;
+    let balance = FRACTIONAL_BALANCES.may_load(deps.storage, (token_id, holder_addr.clone()))?.unwrap_or_default();
+    let account = COUPON_ACCOUNTS.may_load(deps.storage, (token_id, holder_addr))?.unwrap_or_default();
+    Ok(account.pending.checked_add(balance * (coupons.index - account.index))?)
+}
+
+fn query_subscription(deps: Deps<CoreumQueries>, token_id: u64) -> StdResult<SubscriptionResponse> {
+    let subscription = SUBSCRIPTIONS.load(deps.storage, token_id)?;
+    let mode = match subscription.mode {
+        StateAllocationMode::ProRata => MsgAllocationMode::ProRata,
+        StateAllocationMode::FirstComeFirstServed => MsgAllocationMode::FirstComeFirstServed,
+    };
+    let status = match subscription.status {
+        StateSubscriptionStatus::Open => MsgSubscriptionStatus::Open,
+        StateSubscriptionStatus::Closing => MsgSubscriptionStatus::Closing,
+        StateSubscriptionStatus::Finalized => MsgSubscriptionStatus::Finalized,
+    };
+    Ok(SubscriptionResponse {
+        token_id,
+        start: subscription.start,
+        end: subscription.end,
+        mode,
+        offered: subscription.offered,
+        requested: subscription.requested,
+        allocated: subscription.allocated,
+        oversubscribed: subscription.requested > subscription.offered,
+        status,
+    })
+}
+
+fn query_commitment(deps: Deps<CoreumQueries>, token_id: u64, investor: String) -> StdResult<CommitmentResponse> {
+    let investor_addr = deps.api.addr_validate(&investor)?;
+    let subscription = SUBSCRIPTIONS.load(deps.storage, token_id)?;
+    let requested = COMMITMENTS.may_load(deps.storage, (token_id, investor_addr))?.unwrap_or_default();
+    Ok(CommitmentResponse { requested, expected_allocation: allocation_for(&subscription, requested) })
+}
+
+fn query_distribution(deps: Deps<CoreumQueries>, distribution_id: u64) -> StdResult<DistributionResponse> {
+    let distribution = DISTRIBUTIONS.load(deps.storage, distribution_id)?;
+    Ok(DistributionResponse {
+        distribution_id,
+        token_id: distribution.token_id,
+        denom: distribution.denom,
+        total: distribution.total,
+        claimed: distribution.claimed,
+        claim_deadline: distribution.claim_deadline,
+        reclaimed: distribution.reclaimed,
+        complete: distribution.complete,
+    })
+}
+
+fn query_dividend_entitlement(deps: Deps<CoreumQueries>, distribution_id: u64, holder: String) -> StdResult<Uint128> {
+    let holder_addr = deps.api.addr_validate(&holder)?;
+    Ok(DIVIDEND_ENTITLEMENTS.may_load(deps.storage, (distribution_id, holder_addr))?.unwrap_or_default())
+}
+
+fn query_smart_token_info(deps: Deps<CoreumQueries>) -> StdResult<SmartTokenInfoResponse> {
+    let token_info = TOKEN_INFO.load(deps.storage)?;
+    Ok(SmartTokenInfoResponse { owner: token_info.owner.to_string(), denom: token_info.denom, total_supply: token_info.total_supply, precision: token_info.precision })
+}
+
+fn query_smart_token_balance(deps: Deps<CoreumQueries>, address: String) -> StdResult<Uint128> {
+    let addr = deps.api.addr_validate(&address)?;
+    Ok(BALANCES.may_load(deps.storage, addr)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coin, coins, from_binary};
+    use coreum_test_utils::{mock_coreum_deps, CoreumDeps};
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(seconds);
+        env
+    }
+
+    fn setup(deps: DepsMut<CoreumQueries>) {
+        let msg = InstantiateMsg {
+            owner: "issuer".to_string(),
+            symbol: "BOND".to_string(),
+            subunit: "ubond".to_string(),
+            precision: 6,
+            initial_amount: Uint128::zero(),
+            rate_oracle: Some("oracle".to_string()),
+            max_rate_age: 24 * 60 * 60,
+        };
+        instantiate(deps, mock_env(), mock_info("issuer", &[]), msg).unwrap();
+    }
+
+    /// Issue a one year bond and sell 30/10/20 fractions to alice, bob and carol
+    fn sell_fractions(deps: &mut CoreumDeps, rate_kind: MsgRateKind) {
+        let msg = ExecuteMsg::CreateAsset {
+            total_supply: Uint128::new(100),
+            price: Uint128::new(10),
+            uri: "ipfs://bond".to_string(),
+            asset_type: MsgAssetType::BondOrSecurity,
+            denom: "ucore".to_string(),
+            face_value: Uint128::new(10),
+            coupon_rate: Decimal::percent(5),
+            rate_kind,
+            coupon_frequency: MsgCouponFrequency::Annual,
+            maturity: env_at(SECONDS_PER_YEAR).block.time,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::OpenSubscription {
+            token_id: 1,
+            start: mock_env().block.time,
+            end: env_at(100).block.time,
+            mode: MsgAllocationMode::ProRata,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), msg).unwrap();
+
+        for (investor, amount) in [("alice", 300), ("bob", 100), ("carol", 200)] {
+            let info = mock_info(investor, &coins(amount, "ucore"));
+            execute(deps.as_mut(), env_at(10), info, ExecuteMsg::Subscribe { token_id: 1 }).unwrap();
+        }
+        let msg = ExecuteMsg::CloseSubscription { token_id: 1, limit: None };
+        execute(deps.as_mut(), env_at(101), mock_info("anyone", &[]), msg).unwrap();
+    }
+
+    fn entitlement(deps: &CoreumDeps, holder: &str) -> Option<Uint128> {
+        DIVIDEND_ENTITLEMENTS.may_load(&deps.storage, (1, Addr::unchecked(holder))).unwrap()
+    }
+
+    #[test]
+    fn dividend_entitlements_are_recorded_in_pages() {
+        let mut deps = mock_coreum_deps();
+        setup(deps.as_mut());
+        sell_fractions(&mut deps, MsgRateKind::Fixed);
+
+        let msg = ExecuteMsg::DistributeDividend { token_id: 1, claim_deadline: env_at(1000).block.time, limit: Some(1) };
+        execute(deps.as_mut(), env_at(200), mock_info("issuer", &coins(600, "ucore")), msg).unwrap();
+
+        let distribution = DISTRIBUTIONS.load(&deps.storage, 1).unwrap();
+        assert!(!distribution.complete);
+        assert_eq!(entitlement(&deps, "alice"), Some(Uint128::new(300)));
+        assert_eq!(entitlement(&deps, "bob"), None);
+
+        let msg = ExecuteMsg::RecordEntitlements { token_id: 1, distribution_id: 1, limit: None };
+        execute(deps.as_mut(), env_at(200), mock_info("anyone", &[]), msg.clone()).unwrap();
+
+        let distribution = DISTRIBUTIONS.load(&deps.storage, 1).unwrap();
+        assert!(distribution.complete);
+        assert_eq!(distribution.recorded, Uint128::new(600));
+        assert_eq!(entitlement(&deps, "bob"), Some(Uint128::new(100)));
+        assert_eq!(entitlement(&deps, "carol"), Some(Uint128::new(200)));
+
+        let err = execute(deps.as_mut(), env_at(200), mock_info("anyone", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::EntitlementsRecorded { distribution_id: 1 }));
+    }
+
+    #[test]
+    fn holders_claim_their_dividend_once() {
+        let mut deps = mock_coreum_deps();
+        setup(deps.as_mut());
+        sell_fractions(&mut deps, MsgRateKind::Fixed);
+
+        let msg = ExecuteMsg::DistributeDividend { token_id: 1, claim_deadline: env_at(1000).block.time, limit: None };
+        execute(deps.as_mut(), env_at(200), mock_info("issuer", &coins(600, "ucore")), msg).unwrap();
+
+        let claim = ExecuteMsg::ClaimDividend { token_id: 1, distribution_id: 1 };
+        let res = execute(deps.as_mut(), env_at(300), mock_info("bob", &[]), claim.clone()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: "bob".to_string(), amount: coins(100, "ucore") })
+        );
+
+        let err = execute(deps.as_mut(), env_at(300), mock_info("bob", &[]), claim.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NothingToClaim {}));
+
+        // only holders of this asset are entitled
+        let err = execute(deps.as_mut(), env_at(300), mock_info("issuer", &[]), claim).unwrap_err();
+        assert!(matches!(err, ContractError::NothingToClaim {}));
+
+        let distribution = DISTRIBUTIONS.load(&deps.storage, 1).unwrap();
+        assert_eq!(distribution.claimed, Uint128::new(100));
+    }
+
+    #[test]
+    fn floating_coupon_uses_fresh_benchmark_rate() {
+        let mut deps = mock_coreum_deps();
+        setup(deps.as_mut());
+        sell_fractions(&mut deps, MsgRateKind::Floating);
+        execute(deps.as_mut(), env_at(200), mock_info("issuer", &coins(1000, "ucore")), ExecuteMsg::FundBond { token_id: 1 }).unwrap();
+
+        // only the oracle publishes rates
+        let push = ExecuteMsg::PushRate { rate: Decimal::percent(3), timestamp: env_at(1000).block.time };
+        let err = execute(deps.as_mut(), env_at(1000), mock_info("issuer", &[]), push.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), env_at(1000), mock_info("oracle", &[]), push.clone()).unwrap();
+        let err = execute(deps.as_mut(), env_at(1000), mock_info("oracle", &[]), push).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidRateTimestamp {}));
+
+        let err = execute(deps.as_mut(), env_at(SECONDS_PER_YEAR), mock_info("anyone", &[]), ExecuteMsg::PayCoupon { token_id: 1 }).unwrap_err();
+        assert!(matches!(err, ContractError::StaleRate { .. }));
+
+        let push = ExecuteMsg::PushRate { rate: Decimal::percent(3), timestamp: env_at(SECONDS_PER_YEAR - 60).block.time };
+        execute(deps.as_mut(), env_at(SECONDS_PER_YEAR), mock_info("oracle", &[]), push).unwrap();
+        execute(deps.as_mut(), env_at(SECONDS_PER_YEAR), mock_info("anyone", &[]), ExecuteMsg::PayCoupon { token_id: 1 }).unwrap();
+
+        // 60 fractions earn (3% + 5%) of a face value of 10
+        let coupons = COUPON_STATE.load(&deps.storage, 1).unwrap();
+        assert_eq!(coupons.reserve, Uint128::new(1000 - 48));
+
+        let latest = latest_rate(&deps.storage).unwrap();
+        assert_eq!(latest, Some((env_at(SECONDS_PER_YEAR - 60).block.time.seconds(), Decimal::percent(3))));
+    }
+
+    #[test]
+    fn instantiate_issues_smart_token() {
+        let mut deps = mock_coreum_deps();
+        setup(deps.as_mut());
+
+        let res: SmartTokenInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::SmartTokenInfo {}).unwrap()).unwrap();
+        assert_eq!(res, SmartTokenInfoResponse { owner: "issuer".to_string(), denom: format!("ubond-{}", mock_env().contract.address), total_supply: Uint128::zero(), precision: 6 });
+    }
+
+    #[test]
+    fn mint_and_transfer_smart_token() {
+        let mut deps = mock_coreum_deps();
+        setup(deps.as_mut());
+
+        // only the issuer can mint
+        let msg = ExecuteMsg::MintSmartToken { to: "alice".to_string(), amount: Uint128::new(500) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // the contract mints as issuer and sends the coins on to the recipient
+        let denom = format!("ubond-{}", mock_env().contract.address);
+        let res = execute(deps.as_mut(), mock_env(), mock_info("issuer", &[]), msg).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(res.messages[0].msg, CosmosMsg::Custom(CoreumMsg::AssetFT(assetft::Msg::Mint { coin: coin(500, &denom) })));
+        assert_eq!(res.messages[1].msg, CosmosMsg::Bank(BankMsg::Send { to_address: "alice".to_string(), amount: coins(500, &denom) }));
+
+        // transfers move the coins along with the ledger entry
+        let msg = ExecuteMsg::TransferSmartToken { to: "bob".to_string(), amount: Uint128::new(200) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFunds { .. }));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &coins(200, &denom)), msg).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send { to_address: "bob".to_string(), amount: coins(200, &denom) }));
+
+        // a holder without a balance entry gets an error rather than a storage miss
+        let msg = ExecuteMsg::TransferSmartToken { to: "alice".to_string(), amount: Uint128::new(1) };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("carol", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientBalance { .. }));
+
+        let info: SmartTokenInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::SmartTokenInfo {}).unwrap()).unwrap();
+        assert_eq!(info.total_supply, Uint128::new(500));
+        let bob: Uint128 = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::SmartTokenBalance { address: "bob".to_string() }).unwrap()).unwrap();
+        assert_eq!(bob, Uint128::new(200));
+    }
+
+    #[test]
+    fn migrate_rekeys_balances_and_coupon_accounts() {
+        let mut deps = mock_coreum_deps();
+        setup(deps.as_mut());
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, LEGACY_VERSION).unwrap();
+        for (holder, token_id, balance) in [("bob", 1, 10), ("carol", 1, 20), ("bob", 2, 5)] {
+            LEGACY_FRACTIONAL_BALANCES.save(deps.as_mut().storage, (Addr::unchecked(holder), token_id), &Uint128::new(balance)).unwrap();
+        }
+        let account = CouponAccount { pending: Uint128::new(7), ..Default::default() };
+        LEGACY_COUPON_ACCOUNTS.save(deps.as_mut().storage, (Addr::unchecked("bob"), 1), &account).unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(res.attributes[1].value, "3");
+        assert_eq!(res.attributes[2].value, "1");
+
+        let holders: Vec<_> = FRACTIONAL_BALANCES.prefix(1).range(&deps.storage, None, None, Order::Ascending).collect::<StdResult<_>>().unwrap();
+        assert_eq!(holders, vec![(Addr::unchecked("bob"), Uint128::new(10)), (Addr::unchecked("carol"), Uint128::new(20))]);
+        assert_eq!(FRACTIONAL_BALANCES.load(&deps.storage, (2, Addr::unchecked("bob"))).unwrap(), Uint128::new(5));
+        assert_eq!(COUPON_ACCOUNTS.load(&deps.storage, (1, Addr::unchecked("bob"))).unwrap(), account);
+
+        // migrating again leaves the new layout alone
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(res.attributes.len(), 1);
+    }
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,9 +6,78 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Invalid funds, expected a single {denom} coin")]
+    InvalidFunds { denom: String },
+
+    #[error("Maturity must be in the future")]
+    InvalidMaturity {},
+
+    #[error("No coupon is due for asset {token_id}")]
+    NoCouponDue { token_id: u64 },
+
+    #[error("Insufficient balance: {balance}")]
+    InsufficientBalance { balance: Uint128 },
+
+    #[error("Reserve of {reserve} cannot cover the {required} due")]
+    InsufficientReserve { reserve: Uint128, required: Uint128 },
+
+    #[error("Asset {token_id} has not matured yet")]
+    NotMatured { token_id: u64 },
+
+    #[error("Coupons of asset {token_id} must be paid before redemption")]
+    OutstandingCoupons { token_id: u64 },
+
+    #[error("Asset {token_id} has no investors to distribute to")]
+    NoHolders { token_id: u64 },
+
+    #[error("Claim deadline must be in the future")]
+    InvalidDeadline {},
+
+    #[error("Distribution {distribution_id} claim deadline has passed")]
+    ClaimDeadlinePassed { distribution_id: u64 },
+
+    #[error("Distribution {distribution_id} is still open for claims")]
+    DistributionOpen { distribution_id: u64 },
+
+    #[error("Distribution {distribution_id} has already been reclaimed")]
+    DistributionReclaimed { distribution_id: u64 },
+
+    #[error("Subscription window must end after it starts and before maturity")]
+    InvalidWindow {},
+
+    #[error("Asset {token_id} already has a subscription")]
+    SubscriptionExists { token_id: u64 },
+
+    #[error("Subscription for asset {token_id} is not open")]
+    SubscriptionNotOpen { token_id: u64 },
+
+    #[error("Subscription for asset {token_id} cannot be closed yet")]
+    SubscriptionNotEnded { token_id: u64 },
+
+    #[error("Only {available} fractions are left in the subscription")]
+    Oversubscribed { available: Uint128 },
+
+    #[error("No benchmark rate has been published")]
+    NoRate {},
+
+    #[error("Benchmark rate is {age}s old, older than the allowed {max_age}s")]
+    StaleRate { age: u64, max_age: u64 },
+
+    #[error("Rate timestamp must be newer than the last published rate and not in the future")]
+    InvalidRateTimestamp {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("Entitlements of distribution {distribution_id} are already recorded")]
+    EntitlementsRecorded { distribution_id: u64 },
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
 }
\ No newline at end of file
@@ -1,5 +1,4 @@
 pub mod msg;
 pub mod contract;
 pub mod error;
-pub mod state;
-pub mod smarttoken;
\ No newline at end of file
+pub mod state;
\ No newline at end of file
@@ -1,5 +1,5 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Decimal, Timestamp, Uint128};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -8,12 +8,40 @@ pub struct InstantiateMsg {
     pub subunit: String,
     pub precision: u8,
     pub initial_amount: Uint128,
+    pub rate_oracle: Option<String>,
+    pub max_rate_age: u64,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
-    CreateAsset { total_supply: Uint128, price: Uint128, uri: String, asset_type: AssetType },
-    PayoutDividends { token_id: u64 },
+    CreateAsset {
+        total_supply: Uint128,
+        price: Uint128,
+        uri: String,
+        asset_type: AssetType,
+        denom: String,
+        face_value: Uint128,
+        coupon_rate: Decimal,
+        rate_kind: RateKind,
+        coupon_frequency: CouponFrequency,
+        maturity: Timestamp,
+    },
+    UpdateRateOracle { rate_oracle: Option<String>, max_rate_age: u64 },
+    PushRate { rate: Decimal, timestamp: Timestamp },
+    DistributeDividend { token_id: u64, claim_deadline: Timestamp, limit: Option<u32> },
+    RecordEntitlements { token_id: u64, distribution_id: u64, limit: Option<u32> },
+    ClaimDividend { token_id: u64, distribution_id: u64 },
+    ReclaimDividend { token_id: u64, distribution_id: u64 },
+    OpenSubscription { token_id: u64, start: Timestamp, end: Timestamp, mode: AllocationMode },
+    Subscribe { token_id: u64 },
+    CloseSubscription { token_id: u64, limit: Option<u32> },
+    FundBond { token_id: u64 },
+    PayCoupon { token_id: u64 },
+    ClaimCoupon { token_id: u64 },
+    RedeemAtMaturity { token_id: u64 },
     MintSmartToken { to: String, amount: Uint128 },
     TransferSmartToken { to: String, amount: Uint128 },
 }
@@ -24,10 +52,136 @@ pub enum QueryMsg {
     #[returns(Uint128)]
     FractionalOwnership { token_id: u64, owner: String },
     #[returns(String)]
-    TokenURI { token_id: u64 }
+    TokenURI { token_id: u64 },
+    #[returns(HoldersResponse)]
+    Holders { token_id: u64, start_after: Option<String>, limit: Option<u32> },
+    #[returns(BondInfoResponse)]
+    BondInfo { token_id: u64 },
+    #[returns(RateHistoryResponse)]
+    RateHistory { start_before: Option<Timestamp>, limit: Option<u32> },
+    #[returns(Uint128)]
+    PendingCoupon { token_id: u64, holder: String },
+    #[returns(SubscriptionResponse)]
+    Subscription { token_id: u64 },
+    #[returns(CommitmentResponse)]
+    Commitment { token_id: u64, investor: String },
+    #[returns(DistributionResponse)]
+    Distribution { distribution_id: u64 },
+    #[returns(Uint128)]
+    DividendEntitlement { distribution_id: u64, holder: String },
+    #[returns(SmartTokenInfoResponse)]
+    SmartTokenInfo {},
+    #[returns(Uint128)]
+    SmartTokenBalance { address: String },
+}
+
+#[cw_serde]
+pub struct SmartTokenInfoResponse {
+    pub owner: String,
+    pub denom: String,
+    pub total_supply: Uint128,
+    pub precision: u32,
+}
+
+#[cw_serde]
+pub struct SubscriptionResponse {
+    pub token_id: u64,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub mode: AllocationMode,
+    pub offered: Uint128,
+    pub requested: Uint128,
+    pub allocated: Uint128,
+    pub oversubscribed: bool,
+    pub status: SubscriptionStatus,
+}
+
+#[cw_serde]
+pub struct CommitmentResponse {
+    pub requested: Uint128,
+    /// Fractions the commitment would receive if the subscription closed now
+    pub expected_allocation: Uint128,
+}
+
+#[cw_serde]
+pub struct DistributionResponse {
+    pub distribution_id: u64,
+    pub token_id: u64,
+    pub denom: String,
+    pub total: Uint128,
+    pub claimed: Uint128,
+    pub claim_deadline: Timestamp,
+    pub reclaimed: bool,
+    pub complete: bool,
+}
+
+#[cw_serde]
+pub struct HolderBalance {
+    pub holder: String,
+    pub balance: Uint128,
+}
+
+#[cw_serde]
+pub struct HoldersResponse {
+    pub holders: Vec<HolderBalance>,
+}
+
+#[cw_serde]
+pub struct BondInfoResponse {
+    pub token_id: u64,
+    pub denom: String,
+    pub face_value: Uint128,
+    pub coupon_rate: Decimal,
+    pub rate_kind: RateKind,
+    pub coupon_frequency: CouponFrequency,
+    pub issued_at: Timestamp,
+    pub maturity: Timestamp,
+    pub periods_paid: u64,
+    pub total_periods: u64,
+    pub next_coupon_at: Option<Timestamp>,
+    pub reserve: Uint128,
+}
+
+#[cw_serde]
+pub struct RatePoint {
+    pub rate: Decimal,
+    pub timestamp: Timestamp,
+}
+
+#[cw_serde]
+pub struct RateHistoryResponse {
+    /// Published rates, newest first
+    pub rates: Vec<RatePoint>,
 }
 
 #[cw_serde]
 pub enum AssetType {
     BondOrSecurity
+}
+
+#[cw_serde]
+pub enum CouponFrequency {
+    Annual,
+    SemiAnnual,
+    Quarterly,
+    Monthly,
+}
+
+#[cw_serde]
+pub enum RateKind {
+    Fixed,
+    Floating,
+}
+
+#[cw_serde]
+pub enum AllocationMode {
+    ProRata,
+    FirstComeFirstServed,
+}
+
+#[cw_serde]
+pub enum SubscriptionStatus {
+    Open,
+    Closing,
+    Finalized,
 }
\ No newline at end of file
@@ -1,8 +1,17 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map};
 use serde::{Deserialize, Serialize};
 
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,
+    /// Address allowed to publish the benchmark rate used by floating-rate bonds
+    pub rate_oracle: Option<Addr>,
+    /// Maximum age in seconds of the benchmark rate when a floating coupon is paid
+    pub max_rate_age: u64,
+}
+
 #[cw_serde]
 pub struct TokenizedAsset {
     pub owner: Addr,
@@ -11,6 +20,16 @@ pub struct TokenizedAsset {
     pub price: Uint128,
     pub uri: String,
     pub asset_type: AssetType,
+    /// Denom in which coupons and principal are paid
+    pub denom: String,
+    /// Principal repaid per fraction at maturity
+    pub face_value: Uint128,
+    /// Annual coupon rate applied to the face value, or the spread over the benchmark rate for floating-rate bonds
+    pub coupon_rate: Decimal,
+    pub rate_kind: RateKind,
+    pub coupon_frequency: CouponFrequency,
+    pub issued_at: Timestamp,
+    pub maturity: Timestamp,
 }
 
 #[cw_serde]
@@ -18,6 +37,121 @@ pub enum AssetType {
     BondOrSecurity
 }
 
+#[cw_serde]
+pub enum CouponFrequency {
+    Annual,
+    SemiAnnual,
+    Quarterly,
+    Monthly,
+}
+
+#[cw_serde]
+pub enum RateKind {
+    Fixed,
+    /// Coupon rate is the benchmark rate published by the oracle plus the asset's spread
+    Floating,
+}
+
+impl CouponFrequency {
+    pub fn periods_per_year(&self) -> u64 {
+        match self {
+            CouponFrequency::Annual => 1,
+            CouponFrequency::SemiAnnual => 2,
+            CouponFrequency::Quarterly => 4,
+            CouponFrequency::Monthly => 12,
+        }
+    }
+}
+
+/// Coupon bookkeeping of a bond, funded by issuer deposits
+#[cw_serde]
+#[derive(Default)]
+pub struct CouponState {
+    /// Number of coupon periods accrued to holders so far
+    pub periods_paid: u64,
+    /// Cumulative coupon accrued per fraction
+    pub index: Decimal,
+    /// Issuer deposits not yet owed to holders
+    pub reserve: Uint128,
+}
+
+/// Coupon position of a single holder
+#[cw_serde]
+#[derive(Default)]
+pub struct CouponAccount {
+    /// Value of the coupon index when the account was last settled
+    pub index: Decimal,
+    /// Settled coupons not claimed yet
+    pub pending: Uint128,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
 pub const ASSETS: Map<u64, TokenizedAsset> = Map::new("assets");
 pub const NEXT_TOKEN_ID: Item<u64> = Item::new("next_token_id");
-pub const FRACTIONAL_BALANCES: Map<(Addr, u64), Uint128> = Map::new("fractional_balances");
\ No newline at end of file
+/// Fractions held by an investor, keyed by (token_id, holder) so an asset's holders can be paged through
+pub const FRACTIONAL_BALANCES: Map<(u64, Addr), Uint128> = Map::new("fractional_balances");
+pub const COUPON_STATE: Map<u64, CouponState> = Map::new("coupon_state");
+pub const COUPON_ACCOUNTS: Map<(u64, Addr), CouponAccount> = Map::new("coupon_accounts");
+/// Layouts of FRACTIONAL_BALANCES and COUPON_ACCOUNTS up to version 0.1.0, keyed by (holder, token_id)
+pub const LEGACY_FRACTIONAL_BALANCES: Map<(Addr, u64), Uint128> = Map::new("fractional_balances");
+pub const LEGACY_COUPON_ACCOUNTS: Map<(Addr, u64), CouponAccount> = Map::new("coupon_accounts");
+
+/// A dividend pot deposited by the issuer and split pro-rata between the asset's holders
+#[cw_serde]
+pub struct Distribution {
+    pub token_id: u64,
+    pub denom: String,
+    pub total: Uint128,
+    pub claimed: Uint128,
+    pub claim_deadline: Timestamp,
+    pub reclaimed: bool,
+    /// Fractions held by investors when the distribution was created
+    pub outstanding: Uint128,
+    /// Sum of the entitlements recorded so far
+    pub recorded: Uint128,
+    /// Last holder whose entitlement was recorded
+    pub cursor: Option<Addr>,
+    /// Whether every holder's entitlement has been recorded
+    pub complete: bool,
+}
+
+pub const DISTRIBUTIONS: Map<u64, Distribution> = Map::new("distributions");
+pub const NEXT_DISTRIBUTION_ID: Item<u64> = Item::new("next_distribution_id");
+/// Unclaimed dividend owed to a holder, keyed by (distribution_id, holder)
+pub const DIVIDEND_ENTITLEMENTS: Map<(u64, Addr), Uint128> = Map::new("dividend_entitlements");
+
+#[cw_serde]
+pub enum AllocationMode {
+    /// Commitments are scaled down pro-rata when the offering is oversubscribed
+    ProRata,
+    /// Commitments are accepted in arrival order until the offering is filled
+    FirstComeFirstServed,
+}
+
+#[cw_serde]
+pub enum SubscriptionStatus {
+    Open,
+    Closing,
+    Finalized,
+}
+
+/// Primary issuance window during which investors commit funds for fractions
+#[cw_serde]
+pub struct Subscription {
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub mode: AllocationMode,
+    /// Fractions offered, taken from the remaining supply when the window opens
+    pub offered: Uint128,
+    /// Fractions requested by all commitments
+    pub requested: Uint128,
+    /// Fractions credited to investors so far
+    pub allocated: Uint128,
+    pub status: SubscriptionStatus,
+}
+
+pub const SUBSCRIPTIONS: Map<u64, Subscription> = Map::new("subscriptions");
+/// Fractions requested by an investor, keyed by (token_id, investor)
+pub const COMMITMENTS: Map<(u64, Addr), Uint128> = Map::new("commitments");
+/// Benchmark rates published by the oracle, keyed by publication time in seconds
+pub const RATE_HISTORY: Map<u64, Decimal> = Map::new("rate_history");
@@ -1,12 +1,13 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Deps, DepsMut, Env, MessageInfo, QueryResponse, Response, StdError, Addr, Uint128,
+    to_binary, Addr, Deps, DepsMut, Env, MessageInfo, QueryResponse, Response, StdError, StdResult,
+    Uint128,
 };
 use cw2::set_contract_version;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, PendingRewardsResponse, QueryMsg};
 use crate::state::*;
 
 // version info for migration info
@@ -17,21 +18,50 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-   let total_supply=Uint128::zero();
-   let token_info=TokenInfo{ token_denom: msg.token_symbol, token_address: msg.token_contract_address };
+    let total_supply = Uint128::zero();
+    let token_info = TokenInfo {
+        token_denom: msg.token_symbol,
+        token_address: msg.token_contract_address,
+        deposit_cap: msg.deposit_cap,
+    };
     TOTAL_SUPPLY.save(deps.storage, &total_supply)?;
     TOKEN_INFO.save(deps.storage, &token_info)?;
+    REWARD_ADMIN.save(deps.storage, &info.sender)?;
+    REWARD_DURATION.save(
+        deps.storage,
+        &msg.reward_duration.unwrap_or(rewards::DEFAULT_DURATION),
+    )?;
 
-    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION);
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
         .add_attribute("total_supply", total_supply))
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let reward_admin = deps.api.addr_validate(&msg.reward_admin)?;
+    REWARD_ADMIN.save(deps.storage, &reward_admin)?;
+    // a duration chosen at instantiation is kept unless a new one is given
+    let reward_duration = match msg.reward_duration {
+        Some(duration) => duration,
+        None => REWARD_DURATION
+            .may_load(deps.storage)?
+            .unwrap_or(rewards::DEFAULT_DURATION),
+    };
+    REWARD_DURATION.save(deps.storage, &reward_duration)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("reward_admin", reward_admin))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -39,11 +69,34 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    match msg {ExecuteMsg::Deposit{amount}=>execute::execute_deposit(deps,env,info,amount),
-             ExecuteMsg::Withdraw { shares } => execute::execute_withdraw(deps,env,info,shares), }
+    match msg {
+        ExecuteMsg::Deposit { amount } => execute::execute_deposit(deps, env, info, amount),
+        ExecuteMsg::Withdraw { shares } => execute::execute_withdraw(deps, env, info, shares),
+        ExecuteMsg::AddReward { denom } => rewards::execute_add_reward(deps, info, denom),
+        ExecuteMsg::NotifyReward {} => rewards::execute_notify_reward(deps, env, info),
+        ExecuteMsg::ClaimRewards {} => rewards::execute_claim_rewards(deps, env, info),
+        ExecuteMsg::TransferShares { to, amount } => {
+            shares::execute_transfer_shares(deps, env, info, to, amount)
+        }
+        ExecuteMsg::TransferSharesFrom { owner, to, amount } => {
+            shares::execute_transfer_shares_from(deps, env, info, owner, to, amount)
+        }
+        ExecuteMsg::IncreaseShareAllowance {
+            spender,
+            amount,
+            expires,
+        } => shares::execute_increase_allowance(deps, env, info, spender, amount, expires),
+        ExecuteMsg::DecreaseShareAllowance {
+            spender,
+            amount,
+            expires,
+        } => shares::execute_decrease_allowance(deps, env, info, spender, amount, expires),
+    }
 }
+
 pub mod execute {
     use cosmwasm_std::{CosmosMsg, WasmQuery};
+    use cw20::Cw20ExecuteMsg;
 
     use super::*;
 
@@ -53,29 +106,38 @@ pub mod execute {
         info: MessageInfo,
         amount: Uint128,
     ) -> Result<Response, ContractError> {
+        // settle rewards at the old share balance before it changes
+        rewards::checkpoint(deps.storage, env.block.time.seconds(), Some(&info.sender))?;
         let token_info = TOKEN_INFO.load(deps.storage)?;
         let mut total_supply = TOTAL_SUPPLY.load(deps.storage)?;
-        let mut shares = Uint128::zero();
-        let mut balance = BALANCE_OF.load(deps.storage, info.sender.clone()).unwrap_or(Uint128::zero());
-        let balance_of = get_token_balance_of(&deps, info.sender.clone(), token_info.token_address.clone())?;
-    
-        if balance_of.is_zero(){
+        let mut balance = BALANCE_OF
+            .may_load(deps.storage, info.sender.clone())?
+            .unwrap_or_default();
+        let sender_balance =
+            get_token_balance_of(&deps, info.sender.clone(), token_info.token_address.clone())?;
+        let total_assets = get_token_balance_of(
+            &deps,
+            env.contract.address.clone(),
+            token_info.token_address.clone(),
+        )?;
+
+        if sender_balance < amount {
             return Err(ContractError::InsufficientBalance {});
         }
-        if total_supply.is_zero() {
-            shares = shares.checked_add(amount).ok_or(ContractError::Overflow)?;
-        } else {
-            let mul_res = amount.checked_mul(total_supply).ok_or(ContractError::Overflow)?;
-            shares = shares.checked_add(mul_res.checked_div(balance_of).ok_or(ContractError::DivideByZero)?).ok_or(ContractError::Overflow)?;
+        if let Some(cap) = token_info.deposit_cap {
+            if total_assets.checked_add(amount)? > cap {
+                return Err(ContractError::CapExceeded { cap });
+            }
         }
-    
-        give_allowance(env.clone(), info.clone(), amount, token_info.token_address.clone())?;
-    
-        total_supply = total_supply.checked_add(shares).ok_or(ContractError::Overflow)?;
+
+        let shares = math::shares_for_deposit(amount, total_supply, total_assets)?;
+
+        total_supply = total_supply.checked_add(shares)?;
         TOTAL_SUPPLY.save(deps.storage, &total_supply)?;
-        balance = balance.checked_add(shares).ok_or(ContractError::Overflow)?;
+        balance = balance.checked_add(shares)?;
         BALANCE_OF.save(deps.storage, info.sender.clone(), &balance)?;
-    
+
+        // the sender has to approve the vault on the token contract beforehand
         let transfer_from_msg = Cw20ExecuteMsg::TransferFrom {
             owner: info.sender.to_string(),
             recipient: env.contract.address.to_string(),
@@ -86,76 +148,483 @@ pub mod execute {
             msg: to_binary(&transfer_from_msg)?,
             funds: info.funds,
         });
-    
+
         Ok(Response::new()
             .add_attribute("action", "deposit")
+            .add_attribute("amount", amount)
+            .add_attribute("shares", shares)
             .add_message(msg))
     }
 
     pub fn execute_withdraw(
         deps: DepsMut,
-        _env: Env,
+        env: Env,
         info: MessageInfo,
         shares: Uint128,
     ) -> Result<Response, ContractError> {
-        let token_info=TOKEN_INFO.load(deps.storage)?;
-        let mut total_supply=TOTAL_SUPPLY.load(deps.storage)?;
-        let mut balance=BALANCE_OF.load(deps.storage, info.sender.clone()).unwrap_or(Uint128::zero());
-        let balance_of=get_token_balance_of(&deps, info.sender.clone(), token_info.token_address.clone())?;
-
-           // Check if the user's balance is sufficient
-        if balance < shares {
-        return Err(ContractError::InsufficientFunds {});
+        // settle rewards at the old share balance before it changes
+        rewards::checkpoint(deps.storage, env.block.time.seconds(), Some(&info.sender))?;
+        let token_info = TOKEN_INFO.load(deps.storage)?;
+        let mut total_supply = TOTAL_SUPPLY.load(deps.storage)?;
+        let mut balance = BALANCE_OF
+            .may_load(deps.storage, info.sender.clone())?
+            .unwrap_or_default();
+        let total_assets = get_token_balance_of(
+            &deps,
+            env.contract.address.clone(),
+            token_info.token_address.clone(),
+        )?;
+
+        if shares.is_zero() {
+            return Err(ContractError::ZeroShares {});
         }
-
-        if total_supply < shares {
+        // Check if the user's balance is sufficient
+        if balance < shares || total_supply < shares {
             return Err(ContractError::InsufficientFunds {});
-            }
+        }
 
-        let amount=shares.checked_mul(balance_of).map_err(StdError::overflow)?.checked_div(total_supply).map_err(StdError::divide_by_zero)?;
-        total_supply-=shares;
+        let amount = math::assets_for_shares(shares, total_supply, total_assets)?;
+        total_supply = total_supply.checked_sub(shares)?;
         TOTAL_SUPPLY.save(deps.storage, &total_supply)?;
-        balance-=shares;
+        balance = balance.checked_sub(shares)?;
         BALANCE_OF.save(deps.storage, info.sender.clone(), &balance)?;
 
-        let transfer_msg=cw20::Cw20ExecuteMsg::Transfer { recipient: info.sender.to_string(), amount};
-        let msg=CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute { contract_addr: token_info.token_address.to_string(), msg: to_binary(&transfer_msg)?, funds: info.funds });
-
-        Ok(Response::new().add_attribute("action", "withdraw").add_message(msg))
+        let transfer_msg = Cw20ExecuteMsg::Transfer {
+            recipient: info.sender.to_string(),
+            amount,
+        };
+        let msg = CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute {
+            contract_addr: token_info.token_address.to_string(),
+            msg: to_binary(&transfer_msg)?,
+            funds: info.funds,
+        });
 
-        
+        Ok(Response::new()
+            .add_attribute("action", "withdraw")
+            .add_attribute("shares", shares)
+            .add_attribute("amount", amount)
+            .add_message(msg))
     }
-    
-  
+
     pub fn get_token_balance_of(
         deps: &DepsMut,
         user_address: Addr,
         cw20_contract_addr: Addr,
     ) -> Result<Uint128, ContractError> {
-        let query_msg=cw20::Cw20QueryMsg::Balance { address: user_address.to_string() };
-       let msg=deps.querier.query(&cosmwasm_std::QueryRequest::Wasm(WasmQuery::Smart { contract_addr: cw20_contract_addr.to_string(), msg: to_binary(&query_msg)? }))?;
-    
-        Ok(msg)
+        let query_msg = cw20::Cw20QueryMsg::Balance {
+            address: user_address.to_string(),
+        };
+        let res: cw20::BalanceResponse = deps.querier.query(&cosmwasm_std::QueryRequest::Wasm(
+            WasmQuery::Smart {
+                contract_addr: cw20_contract_addr.to_string(),
+                msg: to_binary(&query_msg)?,
+            },
+        ))?;
+
+        Ok(res.balance)
+    }
+}
+
+/// Share accounting. Both conversions round down so rounding always favours the vault, and a
+/// virtual offset of shares and assets keeps a first depositor from inflating the share price
+/// by donating tokens to the vault.
+pub mod math {
+    use cosmwasm_std::Uint256;
+
+    use super::*;
+
+    pub const VIRTUAL_SHARES: Uint128 = Uint128::new(1_000);
+    pub const VIRTUAL_ASSETS: Uint128 = Uint128::new(1);
+
+    pub fn shares_for_deposit(
+        amount: Uint128,
+        total_supply: Uint128,
+        total_assets: Uint128,
+    ) -> Result<Uint128, ContractError> {
+        let shares = mul_div(
+            amount,
+            total_supply.checked_add(VIRTUAL_SHARES)?,
+            total_assets.checked_add(VIRTUAL_ASSETS)?,
+        )?;
+        if shares.is_zero() {
+            return Err(ContractError::ZeroShares {});
+        }
+        Ok(shares)
+    }
+
+    pub fn assets_for_shares(
+        shares: Uint128,
+        total_supply: Uint128,
+        total_assets: Uint128,
+    ) -> Result<Uint128, ContractError> {
+        if total_assets.is_zero() {
+            return Err(ContractError::VaultEmpty {});
+        }
+        mul_div(
+            shares,
+            total_assets.checked_add(VIRTUAL_ASSETS)?,
+            total_supply.checked_add(VIRTUAL_SHARES)?,
+        )
+    }
+
+    fn mul_div(
+        value: Uint128,
+        numerator: Uint128,
+        denominator: Uint128,
+    ) -> Result<Uint128, ContractError> {
+        let result = value
+            .full_mul(numerator)
+            .checked_div(Uint256::from(denominator))?;
+        Ok(Uint128::try_from(result).map_err(StdError::from)?)
+    }
+}
+
+/// Synthetix style reward streaming. Every reward accrues `rate * elapsed / total_supply` per
+/// share, and each holder is settled against it whenever their share balance changes.
+pub mod rewards {
+    use cosmwasm_std::{coin, BankMsg, Coin, Order, Storage, Uint256};
+
+    use super::*;
+
+    pub const DEFAULT_DURATION: u64 = 7 * 24 * 60 * 60;
+    pub const REWARD_SCALE: Uint256 = Uint256::from_u128(1_000_000_000_000_000_000);
+
+    // what streams while there are no shares is kept aside for the next period
+    fn accrue(reward: &mut RewardInfo, total_supply: Uint128, now: u64) {
+        let until = now.min(reward.period_finish);
+        if until <= reward.last_update {
+            return;
+        }
+        let emitted = Uint256::from(until - reward.last_update) * reward.rate;
+        if total_supply.is_zero() {
+            reward.idle += emitted;
+        } else {
+            reward.reward_per_share += emitted / Uint256::from(total_supply);
+        }
+        reward.last_update = until;
+    }
+
+    fn earned(reward: &RewardInfo, user: &UserReward, shares: Uint128) -> StdResult<Uint128> {
+        let earned = Uint256::from(shares) * (reward.reward_per_share - user.reward_per_share_paid)
+            / REWARD_SCALE;
+        Ok(user.pending + Uint128::try_from(earned)?)
+    }
+
+    fn reward_denoms(storage: &dyn Storage) -> StdResult<Vec<String>> {
+        REWARDS
+            .keys(storage, None, None, Order::Ascending)
+            .collect()
+    }
+
+    /// Brings every reward up to `now` and settles what `user` earned so far
+    pub fn checkpoint(
+        storage: &mut dyn Storage,
+        now: u64,
+        user: Option<&Addr>,
+    ) -> Result<(), ContractError> {
+        let total_supply = TOTAL_SUPPLY.load(storage)?;
+        let shares = match user {
+            Some(user) => BALANCE_OF.may_load(storage, user.clone())?.unwrap_or_default(),
+            None => Uint128::zero(),
+        };
+        for denom in reward_denoms(storage)? {
+            let mut reward = REWARDS.load(storage, &denom)?;
+            accrue(&mut reward, total_supply, now);
+            REWARDS.save(storage, &denom, &reward)?;
+
+            if let Some(user) = user {
+                let mut user_reward = USER_REWARDS
+                    .may_load(storage, (user, &denom))?
+                    .unwrap_or_default();
+                user_reward.pending = earned(&reward, &user_reward, shares)?;
+                user_reward.reward_per_share_paid = reward.reward_per_share;
+                USER_REWARDS.save(storage, (user, &denom), &user_reward)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn execute_add_reward(
+        deps: DepsMut,
+        info: MessageInfo,
+        denom: String,
+    ) -> Result<Response, ContractError> {
+        if info.sender != REWARD_ADMIN.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        if REWARDS.has(deps.storage, &denom) {
+            return Err(ContractError::RewardExists { denom });
+        }
+        let reward = RewardInfo {
+            rate: Uint256::zero(),
+            period_finish: 0,
+            last_update: 0,
+            reward_per_share: Uint256::zero(),
+            idle: Uint256::zero(),
+        };
+        REWARDS.save(deps.storage, &denom, &reward)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "add_reward")
+            .add_attribute("denom", denom))
+    }
+
+    pub fn execute_notify_reward(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        if info.sender != REWARD_ADMIN.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        if info.funds.is_empty() {
+            return Err(ContractError::InsufficientFunds {});
+        }
+        let now = env.block.time.seconds();
+        checkpoint(deps.storage, now, None)?;
+
+        // what is left of a running period, and what streamed to nobody, is rolled into the new one
+        let duration = REWARD_DURATION.load(deps.storage)?;
+        for funds in &info.funds {
+            let mut reward = REWARDS
+                .may_load(deps.storage, &funds.denom)?
+                .ok_or_else(|| ContractError::UnknownReward {
+                    denom: funds.denom.clone(),
+                })?;
+            let mut amount = Uint256::from(funds.amount) * REWARD_SCALE;
+            if now < reward.period_finish {
+                amount += Uint256::from(reward.period_finish - now) * reward.rate;
+            }
+            amount += reward.idle;
+            reward.idle = Uint256::zero();
+            reward.rate = amount / Uint256::from(duration);
+            reward.last_update = now;
+            reward.period_finish = now + duration;
+            REWARDS.save(deps.storage, &funds.denom, &reward)?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "notify_reward")
+            .add_attribute("period_finish", (now + duration).to_string()))
     }
 
-    pub fn give_allowance(
+    pub fn execute_claim_rewards(
+        deps: DepsMut,
         env: Env,
         info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        checkpoint(deps.storage, env.block.time.seconds(), Some(&info.sender))?;
+
+        let mut claimed: Vec<Coin> = vec![];
+        for denom in reward_denoms(deps.storage)? {
+            let key = (&info.sender, denom.as_str());
+            let mut user_reward = USER_REWARDS.load(deps.storage, key)?;
+            if user_reward.pending.is_zero() {
+                continue;
+            }
+            claimed.push(coin(user_reward.pending.u128(), &denom));
+            user_reward.pending = Uint128::zero();
+            USER_REWARDS.save(deps.storage, key, &user_reward)?;
+        }
+        if claimed.is_empty() {
+            return Err(ContractError::NothingToClaim {});
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "claim_rewards")
+            .add_attribute("recipient", info.sender.as_str())
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: claimed,
+            }))
+    }
+
+    /// Rewards `address` could claim right now
+    pub fn pending_rewards(deps: Deps, env: Env, address: Addr) -> StdResult<Vec<Coin>> {
+        let total_supply = TOTAL_SUPPLY.load(deps.storage)?;
+        let shares = BALANCE_OF
+            .may_load(deps.storage, address.clone())?
+            .unwrap_or_default();
+        let mut pending = vec![];
+        for denom in reward_denoms(deps.storage)? {
+            let mut reward = REWARDS.load(deps.storage, &denom)?;
+            accrue(&mut reward, total_supply, env.block.time.seconds());
+            let user_reward = USER_REWARDS
+                .may_load(deps.storage, (&address, &denom))?
+                .unwrap_or_default();
+            let amount = earned(&reward, &user_reward, shares)?;
+            if !amount.is_zero() {
+                pending.push(coin(amount.u128(), denom));
+            }
+        }
+        Ok(pending)
+    }
+}
+
+/// Cw20 style share transfers and allowances, so positions can move without a withdrawal
+pub mod shares {
+    use cosmwasm_std::Storage;
+    use cw20::Expiration;
+
+    use super::*;
+
+    // both sides are settled at their old share balances before they change
+    fn move_shares(
+        storage: &mut dyn Storage,
+        now: u64,
+        from: &Addr,
+        to: &Addr,
+        amount: Uint128,
+    ) -> Result<(), ContractError> {
+        if amount.is_zero() {
+            return Err(ContractError::InvalidZeroAmount {});
+        }
+        rewards::checkpoint(storage, now, Some(from))?;
+        rewards::checkpoint(storage, now, Some(to))?;
+
+        BALANCE_OF.update(storage, from.clone(), |balance| {
+            balance
+                .unwrap_or_default()
+                .checked_sub(amount)
+                .map_err(|_| ContractError::InsufficientFunds {})
+        })?;
+        BALANCE_OF.update(storage, to.clone(), |balance| -> Result<_, ContractError> {
+            Ok(balance.unwrap_or_default().checked_add(amount)?)
+        })?;
+        Ok(())
+    }
+
+    pub fn execute_transfer_shares(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        to: String,
         amount: Uint128,
-        cw20_contract_addr: Addr,
     ) -> Result<Response, ContractError> {
-        let allowance_msg=cw20::Cw20ExecuteMsg::IncreaseAllowance { spender: env.contract.address.to_string(), amount , expires: None };
-       let msg=CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute { contract_addr: cw20_contract_addr.to_string(), msg: to_binary(&allowance_msg)?, funds: info.funds });
-    
-        Ok(Response::new().add_attribute("action", "give_Allowance").add_message(msg))
+        let to = deps.api.addr_validate(&to)?;
+        move_shares(deps.storage, env.block.time.seconds(), &info.sender, &to, amount)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "transfer_shares")
+            .add_attribute("from", info.sender)
+            .add_attribute("to", to)
+            .add_attribute("amount", amount))
+    }
+
+    pub fn execute_transfer_shares_from(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        owner: String,
+        to: String,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        let owner = deps.api.addr_validate(&owner)?;
+        let to = deps.api.addr_validate(&to)?;
+
+        let key = (&owner, &info.sender);
+        let mut allowance = SHARE_ALLOWANCES
+            .may_load(deps.storage, key)?
+            .ok_or(ContractError::NoAllowance {})?;
+        if allowance.expires.is_expired(&env.block) {
+            return Err(ContractError::AllowanceExpired {});
+        }
+        allowance.allowance = allowance
+            .allowance
+            .checked_sub(amount)
+            .map_err(|_| ContractError::NoAllowance {})?;
+        if allowance.allowance.is_zero() {
+            SHARE_ALLOWANCES.remove(deps.storage, key);
+        } else {
+            SHARE_ALLOWANCES.save(deps.storage, key, &allowance)?;
+        }
+        move_shares(deps.storage, env.block.time.seconds(), &owner, &to, amount)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "transfer_shares_from")
+            .add_attribute("from", owner)
+            .add_attribute("to", to)
+            .add_attribute("by", info.sender)
+            .add_attribute("amount", amount))
+    }
+
+    pub fn execute_increase_allowance(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    ) -> Result<Response, ContractError> {
+        let spender = deps.api.addr_validate(&spender)?;
+        if spender == info.sender {
+            return Err(ContractError::CannotApproveSelf {});
+        }
+        let key = (&info.sender, &spender);
+        let mut allowance = SHARE_ALLOWANCES
+            .may_load(deps.storage, key)?
+            .unwrap_or_default();
+        if let Some(expires) = expires {
+            if expires.is_expired(&env.block) {
+                return Err(ContractError::InvalidExpiration {});
+            }
+            allowance.expires = expires;
+        }
+        allowance.allowance = allowance.allowance.checked_add(amount)?;
+        SHARE_ALLOWANCES.save(deps.storage, key, &allowance)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "increase_share_allowance")
+            .add_attribute("owner", info.sender)
+            .add_attribute("spender", spender)
+            .add_attribute("amount", amount))
+    }
+
+    pub fn execute_decrease_allowance(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    ) -> Result<Response, ContractError> {
+        let spender = deps.api.addr_validate(&spender)?;
+        if spender == info.sender {
+            return Err(ContractError::CannotApproveSelf {});
+        }
+        let key = (&info.sender, &spender);
+        let mut allowance = SHARE_ALLOWANCES
+            .may_load(deps.storage, key)?
+            .ok_or(ContractError::NoAllowance {})?;
+        allowance.allowance = allowance.allowance.saturating_sub(amount);
+        if allowance.allowance.is_zero() {
+            SHARE_ALLOWANCES.remove(deps.storage, key);
+        } else {
+            if let Some(expires) = expires {
+                if expires.is_expired(&env.block) {
+                    return Err(ContractError::InvalidExpiration {});
+                }
+                allowance.expires = expires;
+            }
+            SHARE_ALLOWANCES.save(deps.storage, key, &allowance)?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "decrease_share_allowance")
+            .add_attribute("owner", info.sender)
+            .add_attribute("spender", spender)
+            .add_attribute("amount", amount))
     }
-    
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<QueryResponse, StdError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, StdError> {
     match msg {QueryMsg::GetTotalSupply{}=>query::get_total_supply(deps),
-    QueryMsg::GetBalanceOf { address } => query::get_balance_of(deps,address) }
+    QueryMsg::GetBalanceOf { address } => query::get_balance_of(deps,address),
+    QueryMsg::PendingRewards { address } => to_binary(&PendingRewardsResponse {
+        rewards: rewards::pending_rewards(deps, env, address)?,
+    }),
+    QueryMsg::GetShareAllowance { owner, spender } => query::get_share_allowance(deps, owner, spender) }
 }
 
 pub mod query {
@@ -173,6 +642,18 @@ pub mod query {
     
         to_binary(&balance_of)
     }
+
+    pub fn get_share_allowance(
+        deps: Deps,
+        owner: Addr,
+        spender: Addr,
+    ) -> Result<QueryResponse, StdError> {
+        let allowance = SHARE_ALLOWANCES
+            .may_load(deps.storage, (&owner, &spender))?
+            .unwrap_or_default();
+
+        to_binary(&allowance)
+    }
     
 }
 
@@ -180,7 +661,9 @@ pub mod query {
 mod tests {
     use cosmwasm_std::{testing::{mock_dependencies, mock_env, mock_info}, coins, Uint128, Addr, StdError};
 
-    use crate::{msg::{InstantiateMsg, ExecuteMsg}, contract::{instantiate,execute,}, ContractError};
+    use crate::{msg::{InstantiateMsg, ExecuteMsg}, contract::{instantiate,execute,math}, ContractError};
+    use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+    use cosmwasm_std::{from_binary, to_binary, ContractResult, SystemError, SystemResult, WasmQuery};
 
 
 
@@ -188,7 +671,7 @@ mod tests {
 fn test_instantiate() {
     let mut deps = mock_dependencies();
 
-    let msg = InstantiateMsg { token_symbol: "ABC".to_string(), token_contract_address: Addr::unchecked("abcdef") };
+    let msg = InstantiateMsg { token_symbol: "ABC".to_string(), token_contract_address: Addr::unchecked("abcdef"), deposit_cap: None, reward_duration: None };
     let info = mock_info("creator", &coins(1000, "earth"));
 
     // we can just call .unwrap() to assert this was a success
@@ -210,7 +693,7 @@ fn test_execute_receive() {
     let info = mock_info("sender", &[]);
 
     
-    let msg = InstantiateMsg { token_symbol: "ABC".to_string(), token_contract_address: Addr::unchecked("abcdef") };
+    let msg = InstantiateMsg { token_symbol: "ABC".to_string(), token_contract_address: Addr::unchecked("abcdef"), deposit_cap: None, reward_duration: None };
     // we can just call .unwrap() to assert this was a success
     let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg);
     assert!(res.is_ok());
@@ -238,7 +721,7 @@ fn test_execute_withdraw() {
     let info = mock_info("sender", &[]);
 
     
-    let msg = InstantiateMsg { token_symbol: "ABC".to_string(), token_contract_address: Addr::unchecked("abcdef") };
+    let msg = InstantiateMsg { token_symbol: "ABC".to_string(), token_contract_address: Addr::unchecked("abcdef"), deposit_cap: None, reward_duration: None };
     // we can just call .unwrap() to assert this was a success
     let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg);
     assert!(res.is_ok());
@@ -259,4 +742,263 @@ fn test_execute_withdraw() {
        ContractError::Std(StdError::GenericErr {msg: "Querier system error: No such contract: abcdef".to_string()}));
     
 }
-}
\ No newline at end of file
+
+/// Deterministic pseudo random amounts spanning many orders of magnitude
+fn random_amount(state: &mut u64) -> Uint128 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    let magnitude = 10u64.pow((*state >> 59) as u32 % 19);
+    Uint128::from((*state >> 1) % magnitude + 1)
+}
+
+#[test]
+fn test_share_math_never_pays_out_more_than_deposited() {
+    let mut state = 7;
+    for _ in 0..1_000 {
+        let total_supply = random_amount(&mut state);
+        let total_assets = random_amount(&mut state);
+        let amount = random_amount(&mut state);
+
+        let shares = match math::shares_for_deposit(amount, total_supply, total_assets) {
+            Ok(shares) => shares,
+            Err(err) => {
+                assert_eq!(err, ContractError::ZeroShares {});
+                continue;
+            }
+        };
+        let redeemed =
+            math::assets_for_shares(shares, total_supply + shares, total_assets + amount).unwrap();
+        assert!(redeemed <= amount, "deposit {} redeemed {}", amount, redeemed);
+    }
+}
+
+#[test]
+fn test_first_deposit_inflation_is_unprofitable() {
+    let mut state = 11;
+    for _ in 0..1_000 {
+        let donation = random_amount(&mut state);
+        let deposit = random_amount(&mut state);
+
+        // attacker deposits a single token and donates to the vault
+        let attacker_shares = math::shares_for_deposit(Uint128::one(), Uint128::zero(), Uint128::zero()).unwrap();
+        let total_assets = Uint128::one() + donation;
+
+        // a victim deposit either mints shares or is rejected outright
+        let victim_shares = match math::shares_for_deposit(deposit, attacker_shares, total_assets) {
+            Ok(shares) => shares,
+            Err(err) => {
+                assert_eq!(err, ContractError::ZeroShares {});
+                continue;
+            }
+        };
+        let total_supply = attacker_shares + victim_shares;
+        let total_assets = total_assets + deposit;
+
+        let attacker_out = math::assets_for_shares(attacker_shares, total_supply, total_assets).unwrap();
+        assert!(attacker_out <= Uint128::one() + donation);
+        let victim_out = math::assets_for_shares(victim_shares, total_supply, total_assets).unwrap();
+        assert!(victim_out <= deposit);
+    }
+}
+
+#[test]
+fn test_deposit_cap_and_empty_vault() {
+    let mut deps = mock_dependencies();
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { msg, .. } => {
+            let balance = match from_binary::<cw20::Cw20QueryMsg>(msg).unwrap() {
+                cw20::Cw20QueryMsg::Balance { address } if address == MOCK_CONTRACT_ADDR => Uint128::zero(),
+                _ => Uint128::new(1_000),
+            };
+            SystemResult::Ok(ContractResult::Ok(to_binary(&cw20::BalanceResponse { balance }).unwrap()))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "wasm".to_string() }),
+    });
+    let info = mock_info("sender", &[]);
+    let msg = InstantiateMsg { token_symbol: "ABC".to_string(), token_contract_address: Addr::unchecked("abcdef"), deposit_cap: Some(Uint128::new(500)), reward_duration: None };
+    instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::Deposit { amount: Uint128::new(600) };
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::CapExceeded { cap: Uint128::new(500) });
+
+    let msg = ExecuteMsg::Deposit { amount: Uint128::zero() };
+    let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::ZeroShares {});
+
+    let msg = ExecuteMsg::Deposit { amount: Uint128::new(100) };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    assert_eq!(res.attributes[2].value, "100000");
+
+    // the deposit transfer never landed in the mocked vault balance
+    let msg = ExecuteMsg::Withdraw { shares: Uint128::new(1_000) };
+    let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::VaultEmpty {});
+}
+
+#[test]
+fn test_rewards_stream_to_share_holders() {
+    use crate::msg::{PendingRewardsResponse, QueryMsg};
+    use crate::state::{BALANCE_OF, TOTAL_SUPPLY};
+    use cosmwasm_std::{coin, Timestamp};
+    use crate::contract::query;
+
+    let env_at = |seconds: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    };
+    let pending = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, seconds: u64, address: &str| {
+        let msg = QueryMsg::PendingRewards { address: Addr::unchecked(address) };
+        let res: PendingRewardsResponse = from_binary(&query(deps.as_ref(), env_at(seconds), msg).unwrap()).unwrap();
+        res.rewards
+    };
+
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg { token_symbol: "ABC".to_string(), token_contract_address: Addr::unchecked("abcdef"), deposit_cap: None, reward_duration: Some(100) };
+    instantiate(deps.as_mut(), env_at(0), mock_info("admin", &[]), msg).unwrap();
+
+    // alice holds 3 of the 4 shares, bob the last one
+    BALANCE_OF.save(deps.as_mut().storage, Addr::unchecked("alice"), &Uint128::new(3)).unwrap();
+    BALANCE_OF.save(deps.as_mut().storage, Addr::unchecked("bob"), &Uint128::new(1)).unwrap();
+    TOTAL_SUPPLY.save(deps.as_mut().storage, &Uint128::new(4)).unwrap();
+
+    let msg = ExecuteMsg::AddReward { denom: "ucore".to_string() };
+    let err = execute(deps.as_mut(), env_at(0), mock_info("alice", &[]), msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(deps.as_mut(), env_at(0), mock_info("admin", &[]), msg).unwrap();
+
+    let err = execute(deps.as_mut(), env_at(0), mock_info("admin", &coins(10, "uother")), ExecuteMsg::NotifyReward {}).unwrap_err();
+    assert_eq!(err, ContractError::UnknownReward { denom: "uother".to_string() });
+    execute(deps.as_mut(), env_at(0), mock_info("admin", &coins(1_000, "ucore")), ExecuteMsg::NotifyReward {}).unwrap();
+
+    // half way through the period half of the rewards have streamed
+    assert_eq!(pending(&deps, 50, "alice"), vec![coin(375, "ucore")]);
+    assert_eq!(pending(&deps, 50, "bob"), vec![coin(125, "ucore")]);
+
+    let res = execute(deps.as_mut(), env_at(50), mock_info("alice", &[]), ExecuteMsg::ClaimRewards {}).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert!(pending(&deps, 50, "alice").is_empty());
+    let err = execute(deps.as_mut(), env_at(50), mock_info("alice", &[]), ExecuteMsg::ClaimRewards {}).unwrap_err();
+    assert_eq!(err, ContractError::NothingToClaim {});
+
+    // nothing streams past the end of the period
+    assert_eq!(pending(&deps, 500, "alice"), vec![coin(375, "ucore")]);
+    assert_eq!(pending(&deps, 500, "bob"), vec![coin(250, "ucore")]);
+}
+
+#[test]
+fn test_share_transfers_and_allowances() {
+    use crate::msg::QueryMsg;
+    use crate::state::{BALANCE_OF, TOTAL_SUPPLY};
+    use crate::contract::query;
+    use cw20::{AllowanceResponse, Expiration};
+
+    let balance = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, address: &str| {
+        BALANCE_OF.load(deps.as_ref().storage, Addr::unchecked(address)).unwrap()
+    };
+
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg { token_symbol: "ABC".to_string(), token_contract_address: Addr::unchecked("abcdef"), deposit_cap: None, reward_duration: None };
+    instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    BALANCE_OF.save(deps.as_mut().storage, Addr::unchecked("alice"), &Uint128::new(100)).unwrap();
+    TOTAL_SUPPLY.save(deps.as_mut().storage, &Uint128::new(100)).unwrap();
+
+    let msg = ExecuteMsg::TransferShares { to: "cold".to_string(), amount: Uint128::new(30) };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+    assert_eq!(res.attributes[0].value, "transfer_shares");
+    assert_eq!(balance(&deps, "alice"), Uint128::new(70));
+    assert_eq!(balance(&deps, "cold"), Uint128::new(30));
+
+    let msg = ExecuteMsg::TransferShares { to: "cold".to_string(), amount: Uint128::new(71) };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::InsufficientFunds {});
+
+    // bob buys 50 shares OTC through an allowance
+    let transfer_from = |amount: u128| ExecuteMsg::TransferSharesFrom { owner: "alice".to_string(), to: "bob".to_string(), amount: Uint128::new(amount) };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), transfer_from(50)).unwrap_err();
+    assert_eq!(err, ContractError::NoAllowance {});
+
+    let expires = Expiration::AtHeight(mock_env().block.height + 10);
+    let msg = ExecuteMsg::IncreaseShareAllowance { spender: "bob".to_string(), amount: Uint128::new(60), expires: Some(expires) };
+    execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+    execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), transfer_from(50)).unwrap();
+    assert_eq!(balance(&deps, "alice"), Uint128::new(20));
+    assert_eq!(balance(&deps, "bob"), Uint128::new(50));
+
+    let msg = QueryMsg::GetShareAllowance { owner: Addr::unchecked("alice"), spender: Addr::unchecked("bob") };
+    let allowance: AllowanceResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(allowance, AllowanceResponse { allowance: Uint128::new(10), expires });
+
+    let err = execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), transfer_from(11)).unwrap_err();
+    assert_eq!(err, ContractError::NoAllowance {});
+    let mut env = mock_env();
+    env.block.height += 10;
+    let err = execute(deps.as_mut(), env, mock_info("bob", &[]), transfer_from(10)).unwrap_err();
+    assert_eq!(err, ContractError::AllowanceExpired {});
+}
+
+
+#[test]
+fn test_idle_rewards_roll_into_the_next_period() {
+    use crate::msg::{PendingRewardsResponse, QueryMsg};
+    use crate::state::{BALANCE_OF, REWARDS, TOTAL_SUPPLY};
+    use cosmwasm_std::{coin, Timestamp, Uint256};
+    use crate::contract::{query, rewards};
+
+    let env_at = |seconds: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    };
+    let pending = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, seconds: u64, address: &str| {
+        let msg = QueryMsg::PendingRewards { address: Addr::unchecked(address) };
+        let res: PendingRewardsResponse = from_binary(&query(deps.as_ref(), env_at(seconds), msg).unwrap()).unwrap();
+        res.rewards
+    };
+
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg { token_symbol: "ABC".to_string(), token_contract_address: Addr::unchecked("abcdef"), deposit_cap: None, reward_duration: Some(100) };
+    instantiate(deps.as_mut(), env_at(0), mock_info("admin", &[]), msg).unwrap();
+    execute(deps.as_mut(), env_at(0), mock_info("admin", &[]), ExecuteMsg::AddReward { denom: "ucore".to_string() }).unwrap();
+    execute(deps.as_mut(), env_at(0), mock_info("admin", &coins(1_000, "ucore")), ExecuteMsg::NotifyReward {}).unwrap();
+
+    // nobody holds shares for the first half, alice deposits then
+    rewards::checkpoint(deps.as_mut().storage, 50, None).unwrap();
+    let reward = REWARDS.load(deps.as_ref().storage, "ucore").unwrap();
+    assert_eq!(reward.idle, Uint256::from(500u128) * rewards::REWARD_SCALE);
+    BALANCE_OF.save(deps.as_mut().storage, Addr::unchecked("alice"), &Uint128::new(1)).unwrap();
+    TOTAL_SUPPLY.save(deps.as_mut().storage, &Uint128::new(1)).unwrap();
+    assert_eq!(pending(&deps, 100, "alice"), vec![coin(500, "ucore")]);
+
+    // the idle half streams again with the next period
+    execute(deps.as_mut(), env_at(100), mock_info("admin", &coins(1_000, "ucore")), ExecuteMsg::NotifyReward {}).unwrap();
+    assert_eq!(pending(&deps, 200, "alice"), vec![coin(2_000, "ucore")]);
+    let reward = REWARDS.load(deps.as_ref().storage, "ucore").unwrap();
+    assert!(reward.idle.is_zero());
+}
+
+#[test]
+fn test_migrate_sets_the_reward_admin() {
+    use crate::contract::migrate;
+    use crate::msg::MigrateMsg;
+    use crate::state::{REWARD_ADMIN, REWARD_DURATION};
+
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg { token_symbol: "ABC".to_string(), token_contract_address: Addr::unchecked("abcdef"), deposit_cap: None, reward_duration: None };
+    instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+    // vaults from before rewards have neither
+    REWARD_ADMIN.remove(deps.as_mut().storage);
+    REWARD_DURATION.remove(deps.as_mut().storage);
+    let add_reward = ExecuteMsg::AddReward { denom: "ucore".to_string() };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), add_reward.clone()).unwrap_err();
+    assert!(matches!(err, ContractError::Std(StdError::NotFound { .. })));
+
+    let msg = MigrateMsg { reward_admin: "dao".to_string(), reward_duration: None };
+    let res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+    assert_eq!(res.attributes[1].value, "dao");
+    assert_eq!(REWARD_DURATION.load(deps.as_ref().storage).unwrap(), 7 * 24 * 60 * 60);
+    execute(deps.as_mut(), mock_env(), mock_info("dao", &[]), add_reward).unwrap();
+}
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{DivideByZeroError, OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug,PartialEq)]
@@ -15,14 +15,47 @@ pub enum ContractError {
     #[error("Address not whitelisted")]
     NotWhitelisted {},
 
-    #[error("Overflow error")]
-    Overflow {},
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    DivideByZero(#[from] DivideByZeroError),
+
+    #[error("Deposit is too small to mint any shares")]
+    ZeroShares {},
 
-    #[error("Divide by zero error")]
-    DivideByZero {},
+    #[error("Vault holds no assets")]
+    VaultEmpty {},
+
+    #[error("Deposit exceeds the vault cap of {cap}")]
+    CapExceeded { cap: Uint128 },
 
     #[error("Insufficient funds")]
     InsufficientFunds {},
+
+    #[error("{denom} is already a reward")]
+    RewardExists { denom: String },
+
+    #[error("{denom} is not a reward")]
+    UnknownReward { denom: String },
+
+    #[error("No rewards to claim")]
+    NothingToClaim {},
+
+    #[error("Amount must be greater than zero")]
+    InvalidZeroAmount {},
+
+    #[error("Cannot set an allowance for your own account")]
+    CannotApproveSelf {},
+
+    #[error("Share allowance is too low")]
+    NoAllowance {},
+
+    #[error("Share allowance is expired")]
+    AllowanceExpired {},
+
+    #[error("Allowance expiration is already in the past")]
+    InvalidExpiration {},
     
 
     #[error("To Do Error")]
@@ -1,10 +1,22 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Coin, Uint128};
+use cw20::{AllowanceResponse, Expiration};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub token_symbol: String,
     pub token_contract_address: Addr,
+    // maximum amount of tokens the vault accepts, unlimited when not set
+    pub deposit_cap: Option<Uint128>,
+    // seconds each NotifyReward streams its incentives over, one week when not set
+    pub reward_duration: Option<u64>,
+}
+
+// vaults instantiated before rewards existed get their reward admin and duration here
+#[cw_serde]
+pub struct MigrateMsg {
+    pub reward_admin: String,
+    pub reward_duration: Option<u64>,
 }
 
 
@@ -16,7 +28,37 @@ pub enum ExecuteMsg {
     },
     Withdraw {
         shares: Uint128
-    }
+    },
+    // registers a native denom as incentive, instantiator only
+    AddReward {
+        denom: String
+    },
+    // streams the attached incentives to share holders over the reward duration,
+    // instantiator only
+    NotifyReward {},
+    ClaimRewards {},
+    // moves shares of the sender to another address
+    TransferShares {
+        to: String,
+        amount: Uint128
+    },
+    // moves shares of `owner` within the allowance it gave the sender
+    TransferSharesFrom {
+        owner: String,
+        to: String,
+        amount: Uint128
+    },
+    // allows `spender` to transfer that many more of the sender's shares
+    IncreaseShareAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>
+    },
+    DecreaseShareAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>
+    },
 }
 
 
@@ -31,5 +73,21 @@ pub enum QueryMsg {
     #[returns(Uint128)]
     GetBalanceOf {
         address: Addr
-    }
+    },
+
+    #[returns(PendingRewardsResponse)]
+    PendingRewards {
+        address: Addr
+    },
+
+    #[returns(AllowanceResponse)]
+    GetShareAllowance {
+        owner: Addr,
+        spender: Addr
+    },
+}
+
+#[cw_serde]
+pub struct PendingRewardsResponse {
+    pub rewards: Vec<Coin>,
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Uint128, Uint256};
+use cw20::AllowanceResponse;
 use cw_storage_plus::{Item, Map};
 
 
@@ -10,12 +11,43 @@ pub const TOTAL_SUPPLY: Item<Uint128> = Item::new("total_supply");
 // Balance of
 pub const BALANCE_OF: Map<Addr,Uint128>=Map::new("balance_of");
 
+// Shares an owner allows a spender to transfer, keyed by (owner, spender)
+pub const SHARE_ALLOWANCES: Map<(&Addr, &Addr), AllowanceResponse> = Map::new("share_allowances");
+
 
 #[cw_serde]
 pub struct  TokenInfo{
     pub token_denom: String,
-    pub token_address: Addr
+    pub token_address: Addr,
+    pub deposit_cap: Option<Uint128>,
+}
+
+pub const TOKEN_INFO: Item<TokenInfo> = Item::new("token_info");
 
+// Incentives streamed to share holders
+pub const REWARD_ADMIN: Item<Addr> = Item::new("reward_admin");
+pub const REWARD_DURATION: Item<u64> = Item::new("reward_duration");
+
+#[cw_serde]
+pub struct RewardInfo {
+    // reward tokens per second, scaled by 1e18
+    pub rate: Uint256,
+    pub period_finish: u64,
+    pub last_update: u64,
+    // rewards earned per share since the reward was added, scaled by 1e18
+    pub reward_per_share: Uint256,
+    // streamed while there were no shares, scaled by 1e18 and added to the next period
+    #[serde(default)]
+    pub idle: Uint256,
+}
+
+pub const REWARDS: Map<&str, RewardInfo> = Map::new("rewards");
+
+#[cw_serde]
+#[derive(Default)]
+pub struct UserReward {
+    pub reward_per_share_paid: Uint256,
+    pub pending: Uint128,
 }
 
-pub const TOKEN_INFO: Item<TokenInfo> = Item::new("token_info");
\ No newline at end of file
+pub const USER_REWARDS: Map<(&Addr, &str), UserReward> = Map::new("user_rewards");
\ No newline at end of file
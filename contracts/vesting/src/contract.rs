@@ -1,28 +1,23 @@
+use std::collections::BTreeMap;
+
 use cosmwasm_std::{
-    attr, coins, ensure, entry_point, from_json, to_json_binary, wasm_execute, Addr, Binary, Deps,
-    DepsMut, Env, MessageInfo, Response, StdError, StdResult, SubMsg, Uint128,
-};
-use cw2::{get_contract_version, set_contract_version};
-use cw20::Cw20ReceiveMsg;
-use cw_utils::must_pay;
-
-use astroport::asset::{addr_opt_validate, token_asset_info, AssetInfo, AssetInfoExt};
-use astroport::astro_converter;
-use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
-use astroport::vesting::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, OrderBy, QueryMsg,
-    VestingAccount, VestingAccountResponse, VestingAccountsResponse, VestingInfo, VestingSchedule,
-    VestingSchedulePoint,
+    attr, coin, entry_point, to_json_binary, BankMsg, Binary, Coin, Deps, DepsMut, Env,
+    MessageInfo, Order, Response, StdResult, Uint128,
 };
+use cw2::set_contract_version;
 
 use crate::error::ContractError;
-use crate::state::{read_vesting_infos, Config, CONFIG, OWNERSHIP_PROPOSAL, VESTING_INFO};
+use crate::msg::{
+    ClaimableResponse, ConfigResponse, ExecuteMsg, GrantMsg, GrantResponse, GrantsResponse,
+    InstantiateMsg, QueryMsg, VestingSchedule,
+};
+use crate::state::{read_grants, Config, Grant, CONFIG, GRANTS, NEXT_GRANT_ID};
 
 /// Contract name that is used for migration.
-const CONTRACT_NAME: &str = "astroport-vesting";
+const CONTRACT_NAME: &str = "coreum-vesting";
 /// Contract version that is used for migration.
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
-/// Maximum limit of schedules per user
+/// Maximum limit of unfinished grants per beneficiary
 const SCHEDULES_LIMIT: usize = 8;
 
 /// Creates a new contract with the specified parameters in [`InstantiateMsg`].
@@ -35,38 +30,26 @@ pub fn instantiate(
 ) -> StdResult<Response> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    msg.vesting_token.check(deps.api)?;
-
     CONFIG.save(
         deps.storage,
         &Config {
             owner: deps.api.addr_validate(&msg.owner)?,
-            vesting_token: msg.vesting_token,
         },
     )?;
+    NEXT_GRANT_ID.save(deps.storage, &1)?;
 
     Ok(Response::new())
 }
 
 /// Exposes execute functions available in the contract.
 ///
-/// * **ExecuteMsg::Claim { recipient, amount }** Claims vested tokens and transfers them to the vesting recipient.
-///
-/// * **ExecuteMsg::Receive(msg)** Receives a message of type [`Cw20ReceiveMsg`] and processes it
-/// depending on the received template.
-///
-/// * **ExecuteMsg::RegisterVestingAccounts { vesting_accounts }** Registers vesting accounts
-/// using the provided vector of [`VestingAccount`] structures.
-///
-/// * **ExecuteMsg::WithdrawFromActiveSchedule { account, recipient, withdraw_amount }**
-/// Withdraws tokens from the only one active vesting schedule of the specified account.
+/// * **ExecuteMsg::RegisterGrants { grants }** Registers grants funded by the attached coins.
 ///
-/// * **ExecuteMsg::ProposeNewOwner { owner, expires_in }** Creates a new request to change contract ownership.
+/// * **ExecuteMsg::Claim { grant_ids, recipient }** Claims vested tokens and transfers them to the recipient.
 ///
-/// * **ExecuteMsg::DropOwnershipProposal {}** Removes a request to change contract ownership.
-///
-/// * **ExecuteMsg::ClaimOwnership {}** Claims contract ownership.
+/// * **ExecuteMsg::Revoke { beneficiary, grant_id, recipient }** Revokes the unvested part of a grant.
 ///
+/// * **ExecuteMsg::UpdateOwner { owner }** Changes the contract owner.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -75,383 +58,318 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Claim { recipient, amount } => claim(deps, env, info, recipient, amount),
-        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
-        ExecuteMsg::RegisterVestingAccounts { vesting_accounts } => {
-            let config = CONFIG.load(deps.storage)?;
-
-            match &config.vesting_token {
-                AssetInfo::NativeToken { denom } if info.sender == config.owner => {
-                    let amount = must_pay(&info, denom)?;
-                    register_vesting_accounts(deps, env, vesting_accounts, amount)
-                }
-                _ => Err(ContractError::Unauthorized {}),
-            }
-        }
-        ExecuteMsg::WithdrawFromActiveSchedule {
-            account,
+        ExecuteMsg::RegisterGrants { grants } => register_grants(deps, env, info, grants),
+        ExecuteMsg::Claim {
+            grant_ids,
             recipient,
-            withdraw_amount,
-        } => withdraw_from_active_schedule(deps, env, info, account, recipient, withdraw_amount),
-        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
-            let config: Config = CONFIG.load(deps.storage)?;
-
-            propose_new_owner(
-                deps,
-                info,
-                env,
-                owner,
-                expires_in,
-                config.owner,
-                OWNERSHIP_PROPOSAL,
-            )
-            .map_err(Into::into)
-        }
-        ExecuteMsg::DropOwnershipProposal {} => {
-            let config: Config = CONFIG.load(deps.storage)?;
+        } => claim(deps, env, info, grant_ids, recipient),
+        ExecuteMsg::Revoke {
+            beneficiary,
+            grant_id,
+            recipient,
+        } => revoke(deps, env, info, beneficiary, grant_id, recipient),
+        ExecuteMsg::UpdateOwner { owner } => {
+            let mut config = CONFIG.load(deps.storage)?;
+            if info.sender != config.owner {
+                return Err(ContractError::Unauthorized {});
+            }
+            config.owner = deps.api.addr_validate(&owner)?;
+            CONFIG.save(deps.storage, &config)?;
 
-            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
-                .map_err(Into::into)
-        }
-        ExecuteMsg::ClaimOwnership {} => {
-            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
-                CONFIG.update::<_, StdError>(deps.storage, |mut v| {
-                    v.owner = new_owner;
-                    Ok(v)
-                })?;
-
-                Ok(())
-            })
-            .map_err(Into::into)
+            Ok(Response::new().add_attributes(vec![
+                attr("action", "update_owner"),
+                attr("owner", config.owner),
+            ]))
         }
     }
 }
 
-/// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
+/// Creates new grants.
 ///
-/// * **cw20_msg** CW20 message to process.
-fn receive_cw20(
+/// * **grants** list of grants to create.
+///
+/// The coins attached by the owner must match the granted amounts denom by denom.
+pub fn register_grants(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    cw20_msg: Cw20ReceiveMsg,
+    grants: Vec<GrantMsg>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-
-    // Permission check
-    if cw20_msg.sender != config.owner || token_asset_info(info.sender) != config.vesting_token {
+    if info.sender != config.owner {
         return Err(ContractError::Unauthorized {});
     }
 
-    match from_json(&cw20_msg.msg)? {
-        Cw20HookMsg::RegisterVestingAccounts { vesting_accounts } => {
-            register_vesting_accounts(deps, env, vesting_accounts, cw20_msg.amount)
-        }
-    }
-}
-
-/// Create new vesting schedules.
-///
-/// * **vesting_accounts** list of accounts and associated vesting schedules to create.
-///
-/// * **cw20_amount** sets the amount that confirms the total amount of all accounts to register.
-pub fn register_vesting_accounts(
-    deps: DepsMut,
-    env: Env,
-    vesting_accounts: Vec<VestingAccount>,
-    amount: Uint128,
-) -> Result<Response, ContractError> {
-    let response = Response::new();
-
-    let mut to_deposit = Uint128::zero();
+    let mut to_deposit: BTreeMap<String, Uint128> = BTreeMap::new();
+    let mut next_id = NEXT_GRANT_ID.load(deps.storage)?;
 
-    for mut vesting_account in vesting_accounts {
-        let mut released_amount = Uint128::zero();
-        let account_address = deps.api.addr_validate(&vesting_account.address)?;
-
-        assert_vesting_schedules(&env, &account_address, &vesting_account.schedules)?;
-
-        for sch in &vesting_account.schedules {
-            let amount = if let Some(end_point) = &sch.end_point {
-                end_point.amount
-            } else {
-                sch.start_point.amount
-            };
-            to_deposit = to_deposit.checked_add(amount)?;
+    for grant in grants {
+        let beneficiary = deps.api.addr_validate(&grant.beneficiary)?;
+        if grant.amount.is_zero() || !is_valid_schedule(&env, &grant.schedule) {
+            return Err(ContractError::VestingScheduleError(grant.beneficiary));
         }
 
-        if let Some(mut old_info) = VESTING_INFO.may_load(deps.storage, &account_address)? {
-            if old_info.schedules.len() + 1 > SCHEDULES_LIMIT {
-                return Err(ContractError::ExceedSchedulesMaximumLimit(
-                    vesting_account.address,
-                ));
-            };
-            released_amount = old_info.released_amount;
-            vesting_account.schedules.append(&mut old_info.schedules);
+        let mut granted = 0;
+        for item in GRANTS
+            .prefix(&beneficiary)
+            .range(deps.storage, None, None, Order::Ascending)
+        {
+            let (_, existing) = item?;
+            if !is_finished(&existing) {
+                granted += 1;
+            }
         }
+        if granted + 1 > SCHEDULES_LIMIT {
+            return Err(ContractError::ExceedSchedulesMaximumLimit(
+                grant.beneficiary,
+            ));
+        }
+
+        let deposit = to_deposit.entry(grant.denom.clone()).or_default();
+        *deposit = deposit.checked_add(grant.amount)?;
 
-        VESTING_INFO.save(
+        GRANTS.save(
             deps.storage,
-            &account_address,
-            &VestingInfo {
-                schedules: vesting_account.schedules,
-                released_amount,
+            (&beneficiary, next_id),
+            &Grant {
+                denom: grant.denom,
+                amount: grant.amount,
+                schedule: grant.schedule,
+                revocable: grant.revocable,
+                revoked_at: None,
+                claimed: Uint128::zero(),
             },
         )?;
+        next_id += 1;
     }
+    NEXT_GRANT_ID.save(deps.storage, &next_id)?;
 
-    if to_deposit != amount {
+    let mut received: BTreeMap<String, Uint128> = BTreeMap::new();
+    for fund in &info.funds {
+        let amount = received.entry(fund.denom.clone()).or_default();
+        *amount = amount.checked_add(fund.amount)?;
+    }
+    received.retain(|_, amount| !amount.is_zero());
+    if to_deposit != received {
         return Err(ContractError::VestingScheduleAmountError {});
     }
 
-    Ok(response.add_attributes({
-        vec![
-            attr("action", "register_vesting_accounts"),
-            attr("deposited", to_deposit),
-        ]
-    }))
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_grants"),
+        attr("deposited", coins_to_string(&to_deposit)),
+    ]))
 }
 
-/// Asserts the validity of a list of vesting schedules.
-///
-/// * **addr** receiver of the vested tokens.
-///
-/// * **vesting_schedules** vesting schedules to validate.
-fn assert_vesting_schedules(
-    env: &Env,
-    addr: &Addr,
-    vesting_schedules: &[VestingSchedule],
-) -> Result<(), ContractError> {
-    for sch in vesting_schedules {
-        if let Some(end_point) = &sch.end_point {
-            if !(sch.start_point.time < end_point.time
-                && end_point.time > env.block.time.seconds()
-                && sch.start_point.amount < end_point.amount)
-            {
-                return Err(ContractError::VestingScheduleError(addr.to_string()));
-            }
+/// Returns whether a schedule is well formed and not over yet.
+fn is_valid_schedule(env: &Env, schedule: &VestingSchedule) -> bool {
+    let now = env.block.time.seconds();
+    match *schedule {
+        VestingSchedule::Linear { start, cliff, end } => {
+            start <= cliff && cliff <= end && start < end && end > now
+        }
+        VestingSchedule::Periodic {
+            start,
+            period,
+            periods,
+        } => {
+            period > 0
+                && periods > 0
+                && period
+                    .checked_mul(periods.into())
+                    .and_then(|duration| duration.checked_add(start))
+                    .map_or(false, |end| end > now)
         }
     }
+}
 
-    Ok(())
+/// Returns whether everything a grant will ever vest has been claimed.
+fn is_finished(grant: &Grant) -> bool {
+    grant.claimed >= compute_vested_amount(grant, u64::MAX)
 }
 
-/// Claims vested tokens and transfers them to the vesting recipient.
+/// Claims vested tokens and transfers them to the recipient.
 ///
-/// * **recipient** vesting recipient for which to claim tokens.
+/// * **grant_ids** grants to claim from, all grants of the sender by default.
 ///
-/// * **amount** amount of vested tokens to claim.
+/// * **recipient** receiver of the tokens, the sender by default.
 pub fn claim(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    grant_ids: Option<Vec<u64>>,
     recipient: Option<String>,
-    amount: Option<Uint128>,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    let mut vesting_info = VESTING_INFO.load(deps.storage, &info.sender)?;
-
-    let available_amount = compute_available_amount(env.block.time.seconds(), &vesting_info)?;
-
-    let claim_amount = if let Some(a) = amount {
-        if a > available_amount {
-            return Err(ContractError::AmountIsNotAvailable {});
-        };
-        a
-    } else {
-        available_amount
+    let now = env.block.time.seconds();
+    let grant_ids = match grant_ids {
+        Some(ids) => ids,
+        None => GRANTS
+            .prefix(&info.sender)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()?,
     };
 
-    let mut response = Response::new();
-
-    if !claim_amount.is_zero() {
-        let transfer_msg = config.vesting_token.with_balance(claim_amount).into_msg(
-            addr_opt_validate(deps.api, &recipient)?.unwrap_or_else(|| info.sender.clone()),
-        )?;
-        response = response.add_submessage(SubMsg::new(transfer_msg));
-
-        vesting_info.released_amount = vesting_info.released_amount.checked_add(claim_amount)?;
-        VESTING_INFO.save(deps.storage, &info.sender, &vesting_info)?;
-    };
-
-    Ok(response.add_attributes(vec![
-        attr("action", "claim"),
-        attr("address", &info.sender),
-        attr("available_amount", available_amount),
-        attr("claimed_amount", claim_amount),
-    ]))
-}
-
-/// Computes the amount of vested and yet unclaimed tokens for a specific vesting recipient.
-/// Returns the computed amount if the operation is successful.
-///
-/// * **current_time** timestamp from which to start querying for vesting schedules.
-/// Schedules that started later than current_time will be omitted.
-///
-/// * **vesting_info** vesting schedules for which to compute the amount of tokens
-/// that are vested and can be claimed by the recipient.
-fn compute_available_amount(current_time: u64, vesting_info: &VestingInfo) -> StdResult<Uint128> {
-    let mut available_amount: Uint128 = Uint128::zero();
-    for sch in &vesting_info.schedules {
-        if sch.start_point.time > current_time {
+    let mut claimed: BTreeMap<String, Uint128> = BTreeMap::new();
+    for id in grant_ids {
+        let mut grant = GRANTS.load(deps.storage, (&info.sender, id))?;
+        let claimable = compute_vested_amount(&grant, now).checked_sub(grant.claimed)?;
+        if claimable.is_zero() {
             continue;
         }
 
-        let unlocked_amount = calc_schedule_unlocked_amount(sch, current_time)?;
-        available_amount = available_amount.checked_add(unlocked_amount)?;
+        grant.claimed += claimable;
+        GRANTS.save(deps.storage, (&info.sender, id), &grant)?;
+        *claimed.entry(grant.denom).or_default() += claimable;
     }
 
-    available_amount
-        .checked_sub(vesting_info.released_amount)
-        .map_err(StdError::from)
-}
-
-/// Calculate unlocked amount for particular [`VestingSchedule`].
-/// This function does not consider released amount.
-fn calc_schedule_unlocked_amount(
-    schedule: &VestingSchedule,
-    current_time: u64,
-) -> StdResult<Uint128> {
-    let mut available_amount = schedule.start_point.amount;
-
-    if let Some(end_point) = &schedule.end_point {
-        let passed_time = current_time.min(end_point.time) - schedule.start_point.time;
-        let time_period = end_point.time - schedule.start_point.time;
-        if passed_time != 0 {
-            let release_amount = Uint128::from(passed_time).multiply_ratio(
-                end_point.amount.checked_sub(schedule.start_point.amount)?,
-                time_period,
-            );
-            available_amount = available_amount.checked_add(release_amount)?;
-        }
+    if claimed.is_empty() {
+        return Err(ContractError::NothingToClaim {});
     }
 
-    Ok(available_amount)
+    let recipient = recipient
+        .map(|recipient| deps.api.addr_validate(&recipient))
+        .transpose()?
+        .unwrap_or_else(|| info.sender.clone());
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: to_coins(&claimed),
+        })
+        .add_attributes(vec![
+            attr("action", "claim"),
+            attr("address", &info.sender),
+            attr("recipient", recipient),
+            attr("claimed_amount", coins_to_string(&claimed)),
+        ]))
 }
 
-/// Withdraw tokens from active vesting schedule.
-///
-/// Withdraw is possible if there is only one active vesting schedule.
-/// Only schedules with end_point are considered as active.
-/// Active schedule's remaining amount must be greater than withdraw amount.
-/// This function changes the current active schedule
-/// setting current block time and already unlocked amount for start point
-/// and reducing end point amount by the withdrawn amount.
-///
-/// * **account** whose schedule to withdraw from.
+/// Revokes a grant, sending its unvested part back.
 ///
-/// * **receiver** who will receive the withdrawn amount.
-/// **info.sender** is used if it is not specified.
+/// * **beneficiary** owner of the grant.
 ///
-/// * **amount** amount to withdraw from the only one active schedule.
+/// * **grant_id** grant to revoke.
 ///
-fn withdraw_from_active_schedule(
+/// * **recipient** receiver of the unvested tokens, the contract owner by default.
+pub fn revoke(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    account: String,
-    receiver: Option<String>,
-    amount: Uint128,
+    beneficiary: String,
+    grant_id: u64,
+    recipient: Option<String>,
 ) -> Result<Response, ContractError> {
-    if amount.is_zero() {
-        return Err(ContractError::ZeroAmountWithdrawal {});
-    }
-
     let config = CONFIG.load(deps.storage)?;
     if info.sender != config.owner {
         return Err(ContractError::Unauthorized {});
     }
 
-    let acc = deps.api.addr_validate(&account)?;
-    let mut vesting_info = VESTING_INFO.load(deps.storage, &acc)?;
-    let block_time = env.block.time.seconds();
+    let beneficiary = deps.api.addr_validate(&beneficiary)?;
+    let mut grant = GRANTS.load(deps.storage, (&beneficiary, grant_id))?;
+    if !grant.revocable {
+        return Err(ContractError::NotRevocable(grant_id));
+    }
+    if grant.revoked_at.is_some() {
+        return Err(ContractError::AlreadyRevoked(grant_id));
+    }
 
-    let mut active_schedules = vesting_info.schedules.iter_mut().filter(|schedule| {
-        if let Some(end_point) = schedule.end_point {
-            block_time >= schedule.start_point.time && block_time < end_point.time
-        } else {
-            false
-        }
-    });
+    let now = env.block.time.seconds();
+    let unvested = grant
+        .amount
+        .checked_sub(compute_vested_amount(&grant, now))?;
+    grant.revoked_at = Some(now);
+    GRANTS.save(deps.storage, (&beneficiary, grant_id), &grant)?;
 
-    if let Some(schedule) = active_schedules.next() {
-        // Withdraw is not allowed if there are multiple active schedules
-        if active_schedules.next().is_some() {
-            return Err(ContractError::MultipleActiveSchedules(account));
-        }
+    let recipient = recipient
+        .map(|recipient| deps.api.addr_validate(&recipient))
+        .transpose()?
+        .unwrap_or(config.owner);
 
-        // It's safe to unwrap here because we checked that there is an end_point
-        let mut end_point = schedule.end_point.unwrap();
+    let mut response = Response::new();
+    if !unvested.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin(unvested.u128(), &grant.denom)],
+        });
+    }
 
-        let sch_unlocked_amount = calc_schedule_unlocked_amount(schedule, block_time)?;
+    Ok(response.add_attributes(vec![
+        attr("action", "revoke"),
+        attr("beneficiary", beneficiary),
+        attr("grant_id", grant_id.to_string()),
+        attr("recipient", recipient),
+        attr("unvested_amount", unvested),
+    ]))
+}
 
-        let amount_left = end_point.amount.checked_sub(sch_unlocked_amount)?;
-        if amount >= amount_left {
-            return Err(ContractError::NotEnoughTokens(amount_left));
+/// Computes the amount of a grant that has vested by `current_time`, claimed or not.
+/// A revoked grant stops vesting at the time it was revoked.
+fn compute_vested_amount(grant: &Grant, current_time: u64) -> Uint128 {
+    let time = grant
+        .revoked_at
+        .map_or(current_time, |revoked_at| current_time.min(revoked_at));
+
+    match grant.schedule {
+        VestingSchedule::Linear { start, cliff, end } => {
+            if time < cliff {
+                Uint128::zero()
+            } else if time >= end {
+                grant.amount
+            } else {
+                grant.amount.multiply_ratio(time - start, end - start)
+            }
         }
+        VestingSchedule::Periodic {
+            start,
+            period,
+            periods,
+        } => {
+            if time < start {
+                return Uint128::zero();
+            }
+            let elapsed = ((time - start) / period).min(periods.into());
+            grant.amount.multiply_ratio(elapsed, periods)
+        }
+    }
+}
 
-        schedule.start_point = VestingSchedulePoint {
-            time: block_time,
-            amount: sch_unlocked_amount,
-        };
-
-        end_point.amount -= amount;
-        schedule.end_point = Some(end_point);
-    } else {
-        return Err(ContractError::NoActiveVestingSchedule(account));
-    };
-
-    VESTING_INFO.save(deps.storage, &acc, &vesting_info)?;
-
-    let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or(info.sender);
-    let transfer_msg = config
-        .vesting_token
-        .with_balance(amount)
-        .into_msg(receiver.clone())?;
+fn to_coins(amounts: &BTreeMap<String, Uint128>) -> Vec<Coin> {
+    amounts
+        .iter()
+        .map(|(denom, amount)| coin(amount.u128(), denom))
+        .collect()
+}
 
-    Ok(Response::new().add_message(transfer_msg).add_attributes([
-        attr("action", "withdraw_from_active_schedule"),
-        attr("account", account),
-        attr("amount", amount),
-        attr("receiver", receiver),
-    ]))
+fn coins_to_string(amounts: &BTreeMap<String, Uint128>) -> String {
+    to_coins(amounts)
+        .iter()
+        .map(Coin::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
 }
 
 /// Exposes all the queries available in the contract.
 ///
 /// ## Queries
-/// * **QueryMsg::Config {}** Returns the contract configuration in an object of type [`Config`].
+/// * **QueryMsg::Config {}** Returns the contract configuration in an object of type [`ConfigResponse`].
 ///
-/// * **QueryMsg::VestingAccount { address }** Returns information about the vesting schedules that have a specific vesting recipient.
+/// * **QueryMsg::Grants { beneficiary, start_after, limit }** Returns the grants of a beneficiary.
 ///
-/// * **QueryMsg::VestingAccounts {
-///             start_after,
-///             limit,
-///             order_by,
-///         }** Returns a list of vesting schedules together with their vesting recipients.
+/// * **QueryMsg::Claimable { beneficiary }** Returns the amounts a beneficiary can claim now.
 ///
-/// * **QueryMsg::AvailableAmount { address }** Returns the available amount of tokens that can be claimed by a specific vesting recipient.
+/// * **QueryMsg::Timestamp {}** Returns the current block time.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::Config {} => Ok(to_json_binary(&query_config(deps)?)?),
-        QueryMsg::VestingAccount { address } => {
-            Ok(to_json_binary(&query_vesting_account(deps, address)?)?)
-        }
-        QueryMsg::VestingAccounts {
-            start_after,
-            limit,
-            order_by,
-        } => Ok(to_json_binary(&query_vesting_accounts(
-            deps,
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Grants {
+            beneficiary,
             start_after,
             limit,
-            order_by,
-        )?)?),
-        QueryMsg::AvailableAmount { address } => Ok(to_json_binary(
-            &query_vesting_available_amount(deps, env, address)?,
-        )?),
-        QueryMsg::Timestamp {} => Ok(to_json_binary(&query_timestamp(env)?)?),
+        } => to_json_binary(&query_grants(deps, env, beneficiary, start_after, limit)?),
+        QueryMsg::Claimable { beneficiary } => {
+            to_json_binary(&query_claimable(deps, env, beneficiary)?)
+        }
+        QueryMsg::Timestamp {} => to_json_binary(&query_timestamp(env)?),
     }
 }
 
@@ -461,7 +379,6 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
 
     Ok(ConfigResponse {
         owner: config.owner,
-        vesting_token: config.vesting_token,
     })
 }
 
@@ -471,116 +388,55 @@ pub fn query_timestamp(env: Env) -> StdResult<u64> {
     Ok(env.block.time.seconds())
 }
 
-/// Returns the vesting data for a specific vesting recipient using a [`VestingAccountResponse`] object.
-///
-/// * **address** vesting recipient for which to return vesting data.
-pub fn query_vesting_account(deps: Deps, address: String) -> StdResult<VestingAccountResponse> {
-    let address = deps.api.addr_validate(&address)?;
-    let info = VESTING_INFO.load(deps.storage, &address)?;
-
-    Ok(VestingAccountResponse { address, info })
-}
-
-/// Returns a list of vesting schedules using a [`VestingAccountsResponse`] object.
-///
-/// * **start_after** index from which to start reading vesting schedules.
+/// Returns the grants of a beneficiary using a [`GrantsResponse`] object.
 ///
-/// * **limit** amount of vesting schedules to return.
+/// * **start_after** grant id from which to start reading.
 ///
-/// * **order_by** whether results should be returned in an ascending or descending order.
-pub fn query_vesting_accounts(
+/// * **limit** amount of grants to return.
+pub fn query_grants(
     deps: Deps,
-    start_after: Option<String>,
+    env: Env,
+    beneficiary: String,
+    start_after: Option<u64>,
     limit: Option<u32>,
-    order_by: Option<OrderBy>,
-) -> StdResult<VestingAccountsResponse> {
-    let start_after = addr_opt_validate(deps.api, &start_after)?;
-
-    let vesting_infos = read_vesting_infos(deps, start_after, limit, order_by)?;
+) -> StdResult<GrantsResponse> {
+    let beneficiary = deps.api.addr_validate(&beneficiary)?;
+    let now = env.block.time.seconds();
 
-    let vesting_accounts: Vec<_> = vesting_infos
+    let grants = read_grants(deps, &beneficiary, start_after, limit)?
         .into_iter()
-        .map(|(address, info)| VestingAccountResponse { address, info })
+        .map(|(id, grant)| GrantResponse {
+            id,
+            vested: compute_vested_amount(&grant, now),
+            denom: grant.denom,
+            amount: grant.amount,
+            schedule: grant.schedule,
+            revocable: grant.revocable,
+            revoked_at: grant.revoked_at,
+            claimed: grant.claimed,
+        })
         .collect();
 
-    Ok(VestingAccountsResponse { vesting_accounts })
+    Ok(GrantsResponse { grants })
 }
 
-/// Returns the available amount of vested and yet to be claimed tokens for a specific vesting recipient.
-///
-/// * **address** vesting recipient for which to return the available amount of tokens to claim.
-pub fn query_vesting_available_amount(deps: Deps, env: Env, address: String) -> StdResult<Uint128> {
-    let address = deps.api.addr_validate(&address)?;
-
-    let info = VESTING_INFO.load(deps.storage, &address)?;
-    let available_amount = compute_available_amount(env.block.time.seconds(), &info)?;
-    Ok(available_amount)
-}
-
-/// Manages contract migration.
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
-    let contract_version = get_contract_version(deps.storage)?;
-
-    let mut resp = Response::default();
-
-    match contract_version.contract.as_ref() {
-        "astroport-vesting" => match contract_version.version.as_ref() {
-            // injective-888 1.1.0
-            // pacific-1, injective-1, pisco-1, atlantic-2 1.2.0
-            // phoenix-1 1.3.0
-            // neutron-1, pion-1 1.3.1
-            "1.1.0" | "1.2.0" | "1.3.0" | "1.3.1" => {
-                let mut config = CONFIG.load(deps.storage)?;
-
-                let converter_config: astro_converter::Config = deps.querier.query_wasm_smart(
-                    &msg.converter_contract,
-                    &astro_converter::QueryMsg::Config {},
-                )?;
-
-                ensure!(
-                    converter_config.old_astro_asset_info == config.vesting_token,
-                    StdError::generic_err(format!(
-                        "Old astro asset info mismatch between vesting {} and converter {}",
-                        config.vesting_token, converter_config.old_astro_asset_info
-                    ))
-                );
-
-                let total_amount = config
-                    .vesting_token
-                    .query_pool(&deps.querier, env.contract.address)?;
-
-                let convert_msg = match &config.vesting_token {
-                    AssetInfo::Token { contract_addr } => wasm_execute(
-                        contract_addr,
-                        &cw20::Cw20ExecuteMsg::Send {
-                            contract: msg.converter_contract,
-                            amount: total_amount,
-                            msg: to_json_binary(&astro_converter::Cw20HookMsg { receiver: None })?,
-                        },
-                        vec![],
-                    )?,
-                    AssetInfo::NativeToken { denom } => wasm_execute(
-                        &msg.converter_contract,
-                        &astro_converter::ExecuteMsg::Convert { receiver: None },
-                        coins(total_amount.u128(), denom.to_string()),
-                    )?,
-                };
-                resp.messages.push(SubMsg::new(convert_msg));
-
-                config.vesting_token = AssetInfo::native(&converter_config.new_astro_denom);
-                CONFIG.save(deps.storage, &config)?;
-            }
-            _ => return Err(ContractError::MigrationError {}),
-        },
-        _ => return Err(ContractError::MigrationError {}),
+/// Returns the vested and yet to be claimed amounts of a beneficiary using a [`ClaimableResponse`] object.
+pub fn query_claimable(deps: Deps, env: Env, beneficiary: String) -> StdResult<ClaimableResponse> {
+    let beneficiary = deps.api.addr_validate(&beneficiary)?;
+    let now = env.block.time.seconds();
+
+    let mut claimable: BTreeMap<String, Uint128> = BTreeMap::new();
+    for item in GRANTS
+        .prefix(&beneficiary)
+        .range(deps.storage, None, None, Order::Ascending)
+    {
+        let (_, grant) = item?;
+        let amount = compute_vested_amount(&grant, now).checked_sub(grant.claimed)?;
+        *claimable.entry(grant.denom).or_default() += amount;
     }
+    claimable.retain(|_, amount| !amount.is_zero());
 
-    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-
-    Ok(resp
-        .add_attribute("previous_contract_name", &contract_version.contract)
-        .add_attribute("previous_contract_version", &contract_version.version)
-        .add_attribute("new_contract_name", CONTRACT_NAME)
-        .add_attribute("new_contract_version", CONTRACT_VERSION))
-}
\ No newline at end of file
+    Ok(ClaimableResponse {
+        amounts: to_coins(&claimable),
+    })
+}
@@ -1,26 +1,19 @@
-use cosmwasm_std::{OverflowError, StdError, Uint128};
-use cw_utils::PaymentError;
+use cosmwasm_std::{OverflowError, StdError};
 use thiserror::Error;
 
-/// This enum describes generator vesting contract errors
+/// This enum describes vesting contract errors
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
-    #[error("{0}")]
-    PaymentError(#[from] PaymentError),
-
-    #[error("Withdrawn amount must not be zero")]
-    ZeroAmountWithdrawal {},
-
     #[error("Unauthorized")]
     Unauthorized {},
 
-    #[error("Amount is not available!")]
-    AmountIsNotAvailable {},
+    #[error("Nothing to claim")]
+    NothingToClaim {},
 
-    #[error("Vesting schedule error on addr: {0}. Should satisfy: (start < end, end > current_time and start_amount < end_amount)")]
+    #[error("Vesting schedule error on addr: {0}. Should satisfy: (start <= cliff <= end, start < end, end > current_time, period > 0 and periods > 0)")]
     VestingScheduleError(String),
 
     #[error(
@@ -28,24 +21,18 @@ pub enum ContractError {
     )]
     VestingScheduleAmountError {},
 
-    #[error("Contract can't be migrated!")]
-    MigrationError {},
-
-    #[error("Failed to withdraw tokens due to multiple active schedules for account {0}")]
-    MultipleActiveSchedules(String),
-
-    #[error("Account {0} has no active vesting schedule")]
-    NoActiveVestingSchedule(String),
-
-    #[error("For account {0} number of schedules exceeds maximum limit")]
+    #[error("For account {0} number of grants exceeds maximum limit")]
     ExceedSchedulesMaximumLimit(String),
 
-    #[error("Failed to withdraw from active schedule: amount left {0}")]
-    NotEnoughTokens(Uint128),
+    #[error("Grant {0} is not revocable")]
+    NotRevocable(u64),
+
+    #[error("Grant {0} is already revoked")]
+    AlreadyRevoked(u64),
 }
 
 impl From<OverflowError> for ContractError {
     fn from(o: OverflowError) -> Self {
         StdError::from(o).into()
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,7 @@
 pub mod contract;
 pub mod error;
+pub mod msg;
 pub mod state;
 
 #[cfg(test)]
-mod testing;
\ No newline at end of file
+mod testing;
@@ -0,0 +1,104 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Coin, Uint128};
+
+/// This structure describes the parameters used for creating a contract.
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Address allowed to register and revoke grants
+    pub owner: String,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Registers new grants; the attached funds must cover exactly the granted amounts.
+    RegisterGrants { grants: Vec<GrantMsg> },
+    /// Claims everything vested so far from the sender's grants, or only from `grant_ids`.
+    Claim {
+        grant_ids: Option<Vec<u64>>,
+        recipient: Option<String>,
+    },
+    /// Stops a revocable grant; the vested part stays claimable and the rest goes back to
+    /// `recipient`, the owner by default.
+    Revoke {
+        beneficiary: String,
+        grant_id: u64,
+        recipient: Option<String>,
+    },
+    /// Hands the contract over to a new owner
+    UpdateOwner { owner: String },
+}
+
+/// How a grant unlocks over time. Times are in seconds since the epoch.
+#[cw_serde]
+pub enum VestingSchedule {
+    /// Vests linearly from `start` to `end`, nothing is unlocked before `cliff`.
+    Linear { start: u64, cliff: u64, end: u64 },
+    /// Unlocks an equal share of the grant every `period` seconds after `start`, `periods` times.
+    Periodic {
+        start: u64,
+        period: u64,
+        periods: u32,
+    },
+}
+
+/// This structure describes a grant to register.
+#[cw_serde]
+pub struct GrantMsg {
+    pub beneficiary: String,
+    /// Any bank denom, native or a Coreum smart token
+    pub denom: String,
+    pub amount: Uint128,
+    pub schedule: VestingSchedule,
+    /// Whether the owner can revoke the unvested part later
+    pub revocable: bool,
+}
+
+/// This structure describes the query messages available in the contract.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    /// Grants of a beneficiary in id order
+    #[returns(GrantsResponse)]
+    Grants {
+        beneficiary: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Vested and not yet claimed amounts of a beneficiary, one coin per denom
+    #[returns(ClaimableResponse)]
+    Claimable { beneficiary: String },
+    /// Current block time in seconds
+    #[returns(u64)]
+    Timestamp {},
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+}
+
+#[cw_serde]
+pub struct GrantResponse {
+    pub id: u64,
+    pub denom: String,
+    pub amount: Uint128,
+    pub schedule: VestingSchedule,
+    pub revocable: bool,
+    /// Time the grant was revoked at, vesting stops there
+    pub revoked_at: Option<u64>,
+    pub vested: Uint128,
+    pub claimed: Uint128,
+}
+
+#[cw_serde]
+pub struct GrantsResponse {
+    pub grants: Vec<GrantResponse>,
+}
+
+#[cw_serde]
+pub struct ClaimableResponse {
+    pub amounts: Vec<Coin>,
+}
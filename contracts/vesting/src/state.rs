@@ -1,66 +1,62 @@
 use cosmwasm_schema::cw_serde;
 
-use astroport::asset::AssetInfo;
-use astroport::common::OwnershipProposal;
-use astroport::vesting::{OrderBy, VestingInfo};
-use cosmwasm_std::{Addr, Deps, StdResult};
+use cosmwasm_std::{Addr, Deps, Order, StdResult, Uint128};
 use cw_storage_plus::{Bound, Item, Map};
 
-/// This structure stores the main parameters for the generator vesting contract.
+use crate::msg::VestingSchedule;
+
+/// This structure stores the main parameters for the vesting contract.
 #[cw_serde]
 pub struct Config {
-    /// Address that's allowed to change contract parameters
+    /// Address that's allowed to register and revoke grants
     pub owner: Addr,
-    /// [`AssetInfo`] of the ASTRO token
-    pub vesting_token: AssetInfo,
+}
+
+/// This structure stores a single grant of a beneficiary.
+#[cw_serde]
+pub struct Grant {
+    pub denom: String,
+    /// Total amount granted, vested or not
+    pub amount: Uint128,
+    pub schedule: VestingSchedule,
+    pub revocable: bool,
+    /// Vesting stops at this time once the grant is revoked
+    pub revoked_at: Option<u64>,
+    pub claimed: Uint128,
 }
 
 /// Stores the contract config at the given key.
 pub const CONFIG: Item<Config> = Item::new("config");
 
-/// The first key is the address of an account that's vesting, the second key is an object of type [`VestingInfo`].
-pub const VESTING_INFO: Map<&Addr, VestingInfo> = Map::new("vesting_info");
+/// Id the next registered grant gets.
+pub const NEXT_GRANT_ID: Item<u64> = Item::new("next_grant_id");
 
-/// Contains a proposal to change contract ownership.
-pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+/// The first key is the beneficiary, the second the grant id.
+pub const GRANTS: Map<(&Addr, u64), Grant> = Map::new("grants");
 
 const MAX_LIMIT: u32 = 30;
 const DEFAULT_LIMIT: u32 = 10;
 
-/// Returns an empty vector if it does not find data, otherwise returns a vector that
-/// contains objects of type [`VESTING_INFO`].
+/// Returns the grants of a beneficiary in ascending id order.
 /// ## Params
 ///
-/// * **start_after** index from which to start reading vesting schedules.
-///
-/// * **limit** amount of vesting schedules to read.
+/// * **start_after** grant id from which to start reading.
 ///
-/// * **order_by** whether results should be returned in an ascending or descending order.
-pub fn read_vesting_infos(
+/// * **limit** amount of grants to read.
+pub fn read_grants(
     deps: Deps,
-    start_after: Option<Addr>,
+    beneficiary: &Addr,
+    start_after: Option<u64>,
     limit: Option<u32>,
-    order_by: Option<OrderBy>,
-) -> StdResult<Vec<(Addr, VestingInfo)>> {
+) -> StdResult<Vec<(u64, Grant)>> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start_after = start_after.as_ref().map(Bound::exclusive);
-    let (start, end) = match &order_by {
-        Some(OrderBy::Asc) => (start_after, None),
-        _ => (None, start_after),
-    };
-
-    let info: Vec<(Addr, VestingInfo)> = VESTING_INFO
-        .range(
-            deps.storage,
-            start,
-            end,
-            order_by.unwrap_or(OrderBy::Desc).into(),
-        )
-        .take(limit)
-        .filter_map(|v| v.ok())
-        .collect();
+    let start = start_after.map(Bound::exclusive);
 
-    Ok(info)
+    GRANTS
+        .prefix(beneficiary)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
 }
 
 #[cfg(test)]
@@ -68,70 +64,41 @@ mod testing {
     use super::*;
 
     #[test]
-    fn read_vesting_infos_as_expected() {
-        use cosmwasm_std::{testing::mock_dependencies, Uint128};
+    fn read_grants_as_expected() {
+        use cosmwasm_std::testing::mock_dependencies;
 
         let mut deps = mock_dependencies();
 
-        let vi_mock = VestingInfo {
-            released_amount: Uint128::zero(),
-            schedules: vec![],
+        let grant = Grant {
+            denom: "ucore".to_string(),
+            amount: Uint128::new(100),
+            schedule: VestingSchedule::Linear {
+                start: 0,
+                cliff: 0,
+                end: 100,
+            },
+            revocable: false,
+            revoked_at: None,
+            claimed: Uint128::zero(),
         };
 
-        for i in 1..5 {
-            let key = Addr::unchecked(format! {"address{}", i});
-
-            VESTING_INFO
-                .save(&mut deps.storage, &key, &vi_mock)
+        let beneficiary = Addr::unchecked("address1");
+        for id in 1..5 {
+            GRANTS
+                .save(&mut deps.storage, (&beneficiary, id), &grant)
                 .unwrap();
         }
+        GRANTS
+            .save(&mut deps.storage, (&Addr::unchecked("address2"), 5), &grant)
+            .unwrap();
+
+        let res = read_grants(deps.as_ref(), &beneficiary, Some(2), None).unwrap();
+        assert_eq!(res, vec![(3, grant.clone()), (4, grant.clone())]);
 
-        let res = read_vesting_infos(
-            deps.as_ref(),
-            Some(Addr::unchecked("address2")),
-            None,
-            Some(OrderBy::Asc),
-        )
-        .unwrap();
-        assert_eq!(
-            res,
-            vec![
-                (Addr::unchecked("address3"), vi_mock.clone()),
-                (Addr::unchecked("address4"), vi_mock.clone()),
-            ]
-        );
-
-        let res = read_vesting_infos(
-            deps.as_ref(),
-            Some(Addr::unchecked("address2")),
-            Some(1),
-            Some(OrderBy::Asc),
-        )
-        .unwrap();
-        assert_eq!(res, vec![(Addr::unchecked("address3"), vi_mock.clone())]);
-
-        let res = read_vesting_infos(
-            deps.as_ref(),
-            Some(Addr::unchecked("address3")),
-            None,
-            Some(OrderBy::Desc),
-        )
-        .unwrap();
-        assert_eq!(
-            res,
-            vec![
-                (Addr::unchecked("address2"), vi_mock.clone()),
-                (Addr::unchecked("address1"), vi_mock.clone()),
-            ]
-        );
-
-        let res = read_vesting_infos(
-            deps.as_ref(),
-            Some(Addr::unchecked("address3")),
-            Some(1),
-            Some(OrderBy::Desc),
-        )
-        .unwrap();
-        assert_eq!(res, vec![(Addr::unchecked("address2"), vi_mock.clone())]);
+        let res = read_grants(deps.as_ref(), &beneficiary, None, Some(1)).unwrap();
+        assert_eq!(res, vec![(1, grant.clone())]);
+
+        let res = read_grants(deps.as_ref(), &Addr::unchecked("address3"), None, None).unwrap();
+        assert_eq!(res, vec![]);
     }
-}
\ No newline at end of file
+}
@@ -1,9 +1,66 @@
 use crate::contract::{execute, instantiate, query};
-use astroport::vesting::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::error::ContractError;
+use crate::msg::{
+    ClaimableResponse, ConfigResponse, ExecuteMsg, GrantMsg, GrantsResponse, InstantiateMsg,
+    QueryMsg, VestingSchedule,
+};
 
-use astroport::asset::{token_asset_info, AssetInfo};
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-use cosmwasm_std::{from_json, Addr};
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
+use cosmwasm_std::{
+    coin, coins, from_json, Addr, BankMsg, CosmosMsg, Env, MemoryStorage, OwnedDeps, Timestamp,
+    Uint128,
+};
+
+const OWNER: &str = "owner";
+const TEAM: &str = "team";
+const DENOM: &str = "utkn-core1issuer";
+
+fn env_at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(seconds);
+    env
+}
+
+fn grant(amount: u128, schedule: VestingSchedule, revocable: bool) -> GrantMsg {
+    GrantMsg {
+        beneficiary: TEAM.to_string(),
+        denom: DENOM.to_string(),
+        amount: Uint128::new(amount),
+        schedule,
+        revocable,
+    }
+}
+
+fn setup(grants: Vec<GrantMsg>) -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+    };
+    instantiate(deps.as_mut(), env_at(0), mock_info(OWNER, &[]), msg).unwrap();
+
+    let total = grants.iter().map(|g| g.amount.u128()).sum();
+    let msg = ExecuteMsg::RegisterGrants { grants };
+    execute(
+        deps.as_mut(),
+        env_at(0),
+        mock_info(OWNER, &coins(total, DENOM)),
+        msg,
+    )
+    .unwrap();
+    deps
+}
+
+fn claimable(deps: &OwnedDeps<MemoryStorage, MockApi, MockQuerier>, seconds: u64) -> u128 {
+    let msg = QueryMsg::Claimable {
+        beneficiary: TEAM.to_string(),
+    };
+    let res: ClaimableResponse =
+        from_json(query(deps.as_ref(), env_at(seconds), msg).unwrap()).unwrap();
+    res.amounts
+        .iter()
+        .find(|c| c.denom == DENOM)
+        .map_or(0, |c| c.amount.u128())
+}
 
 #[test]
 fn proper_initialization() {
@@ -11,7 +68,6 @@ fn proper_initialization() {
 
     let msg = InstantiateMsg {
         owner: "owner".to_string(),
-        vesting_token: token_asset_info(Addr::unchecked("astro_token")),
     };
 
     let env = mock_env();
@@ -23,112 +79,241 @@ fn proper_initialization() {
             .unwrap(),
         ConfigResponse {
             owner: Addr::unchecked("owner"),
-            vesting_token: token_asset_info(Addr::unchecked("astro_token")),
         }
     );
 }
 
 #[test]
 fn update_owner() {
-    let mut deps = mock_dependencies();
-    let owner = "owner0000";
-
-    let msg = InstantiateMsg {
-        owner: owner.to_string(),
-        vesting_token: AssetInfo::NativeToken {
-            denom: "ucosmos".to_owned(),
-        },
+    let mut deps = setup(vec![]);
+    let new_owner = String::from("new_owner");
+    let msg = ExecuteMsg::UpdateOwner {
+        owner: new_owner.clone(),
     };
 
-    let env = mock_env();
-    let info = mock_info(owner, &[]);
+    // Unauthorized check
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(new_owner.as_str(), &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
 
-    // We can just call .unwrap() to assert this was a success
-    instantiate(deps.as_mut(), env, info, msg).unwrap();
+    let res = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    assert_eq!(0, res.messages.len());
 
-    let new_owner = String::from("new_owner");
+    // Let's query the state
+    let config: ConfigResponse =
+        from_json(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(Addr::unchecked(new_owner), config.owner);
 
-    // New owner
-    let env = mock_env();
-    let msg = ExecuteMsg::ProposeNewOwner {
-        owner: new_owner.clone(),
-        expires_in: 100, // seconds
+    // the previous owner has no rights anymore
+    let msg = ExecuteMsg::UpdateOwner {
+        owner: OWNER.to_string(),
     };
+    let err = execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
 
-    let info = mock_info(new_owner.as_str(), &[]);
+#[test]
+fn register_requires_exact_funding() {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+    };
+    instantiate(deps.as_mut(), env_at(0), mock_info(OWNER, &[]), msg).unwrap();
 
-    // Unauthorized check
-    let err = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
-    assert_eq!(err.to_string(), "Generic error: Unauthorized");
+    let schedule = VestingSchedule::Linear {
+        start: 0,
+        cliff: 10,
+        end: 100,
+    };
+    let msg = ExecuteMsg::RegisterGrants {
+        grants: vec![grant(1000, schedule.clone(), false)],
+    };
 
-    // Claim before proposal
-    let info = mock_info(new_owner.as_str(), &[]);
-    execute(
+    // only the owner funds grants
+    let err = execute(
         deps.as_mut(),
-        env.clone(),
-        info,
-        ExecuteMsg::ClaimOwnership {},
+        env_at(0),
+        mock_info(TEAM, &coins(1000, DENOM)),
+        msg.clone(),
     )
     .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
 
-    // Propose new owner
-    let info = mock_info(owner, &[]);
-    let res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
-    assert_eq!(0, res.messages.len());
+    let err = execute(
+        deps.as_mut(),
+        env_at(0),
+        mock_info(OWNER, &[coin(1000, DENOM), coin(1, "ucore")]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::VestingScheduleAmountError {});
 
-    // Unauthorized ownership claim
-    let info = mock_info("invalid_addr", &[]);
+    // a schedule that is already over is rejected
     let err = execute(
         deps.as_mut(),
-        env.clone(),
-        info,
-        ExecuteMsg::ClaimOwnership {},
+        env_at(100),
+        mock_info(OWNER, &coins(1000, DENOM)),
+        msg.clone(),
     )
     .unwrap_err();
-    assert_eq!(err.to_string(), "Generic error: Unauthorized");
+    assert_eq!(err, ContractError::VestingScheduleError(TEAM.to_string()));
 
-    // Drop new owner
-    let info = mock_info(owner, &[]);
     execute(
         deps.as_mut(),
-        env.clone(),
-        info,
-        ExecuteMsg::DropOwnershipProposal {},
+        env_at(0),
+        mock_info(OWNER, &coins(1000, DENOM)),
+        msg,
     )
     .unwrap();
+}
 
-    // Claim ownership
-    let info = mock_info(new_owner.as_str(), &[]);
-    let err = execute(
-        deps.as_mut(),
-        env.clone(),
-        info,
-        ExecuteMsg::ClaimOwnership {},
-    )
-    .unwrap_err();
+#[test]
+fn linear_with_cliff() {
+    let mut deps = setup(vec![grant(
+        1000,
+        VestingSchedule::Linear {
+            start: 0,
+            cliff: 25,
+            end: 100,
+        },
+        false,
+    )]);
+
+    assert_eq!(claimable(&deps, 24), 0);
+    assert_eq!(claimable(&deps, 25), 250);
+    assert_eq!(claimable(&deps, 200), 1000);
+
+    let msg = ExecuteMsg::Claim {
+        grant_ids: None,
+        recipient: None,
+    };
+    let err = execute(deps.as_mut(), env_at(10), mock_info(TEAM, &[]), msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::NothingToClaim {});
+
+    let res = execute(deps.as_mut(), env_at(50), mock_info(TEAM, &[]), msg.clone()).unwrap();
     assert_eq!(
-        err.to_string(),
-        "Generic error: Ownership proposal not found"
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: TEAM.to_string(),
+            amount: coins(500, DENOM),
+        })
     );
+    assert_eq!(claimable(&deps, 50), 0);
+    assert_eq!(claimable(&deps, 100), 500);
+}
 
-    // Propose new owner
-    let info = mock_info(owner, &[]);
-    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-    assert_eq!(0, res.messages.len());
+#[test]
+fn periodic_unlocks() {
+    let deps = setup(vec![grant(
+        900,
+        VestingSchedule::Periodic {
+            start: 100,
+            period: 30,
+            periods: 3,
+        },
+        false,
+    )]);
 
-    // Claim ownership
-    let info = mock_info(new_owner.as_str(), &[]);
-    let res = execute(
-        deps.as_mut(),
-        env.clone(),
-        info,
-        ExecuteMsg::ClaimOwnership {},
-    )
-    .unwrap();
-    assert_eq!(0, res.messages.len());
+    assert_eq!(claimable(&deps, 99), 0);
+    assert_eq!(claimable(&deps, 129), 0);
+    assert_eq!(claimable(&deps, 130), 300);
+    assert_eq!(claimable(&deps, 189), 600);
+    assert_eq!(claimable(&deps, 1000), 900);
+}
 
-    // Let's query the state
-    let config: ConfigResponse =
-        from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
-    assert_eq!(new_owner, config.owner);
-}
\ No newline at end of file
+#[test]
+fn revoke_returns_unvested() {
+    let schedule = VestingSchedule::Linear {
+        start: 0,
+        cliff: 0,
+        end: 100,
+    };
+    let mut deps = setup(vec![
+        grant(1000, schedule.clone(), true),
+        grant(1000, schedule, false),
+    ]);
+
+    let revoke = |id| ExecuteMsg::Revoke {
+        beneficiary: TEAM.to_string(),
+        grant_id: id,
+        recipient: None,
+    };
+    let err = execute(deps.as_mut(), env_at(40), mock_info(OWNER, &[]), revoke(2)).unwrap_err();
+    assert_eq!(err, ContractError::NotRevocable(2));
+    let err = execute(deps.as_mut(), env_at(40), mock_info(TEAM, &[]), revoke(1)).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(deps.as_mut(), env_at(40), mock_info(OWNER, &[]), revoke(1)).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: OWNER.to_string(),
+            amount: coins(600, DENOM),
+        })
+    );
+    let err = execute(deps.as_mut(), env_at(50), mock_info(OWNER, &[]), revoke(1)).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyRevoked(1));
+
+    // the revoked grant stays at what vested before revocation
+    let msg = QueryMsg::Grants {
+        beneficiary: TEAM.to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let res: GrantsResponse = from_json(query(deps.as_ref(), env_at(100), msg).unwrap()).unwrap();
+    assert_eq!(res.grants[0].vested, Uint128::new(400));
+    assert_eq!(res.grants[0].revoked_at, Some(40));
+    assert_eq!(res.grants[1].vested, Uint128::new(1000));
+    assert_eq!(claimable(&deps, 100), 1400);
+}
+
+#[test]
+fn schedules_limit_skips_finished_grants() {
+    let schedule = VestingSchedule::Linear {
+        start: 0,
+        cliff: 0,
+        end: 100,
+    };
+    let mut deps = setup(vec![grant(100, schedule, false); 8]);
+
+    let schedule = VestingSchedule::Linear {
+        start: 100,
+        cliff: 100,
+        end: 200,
+    };
+    let register = |deps: &mut OwnedDeps<MemoryStorage, MockApi, MockQuerier>, seconds| {
+        execute(
+            deps.as_mut(),
+            env_at(seconds),
+            mock_info(OWNER, &coins(100, DENOM)),
+            ExecuteMsg::RegisterGrants {
+                grants: vec![grant(100, schedule.clone(), false)],
+            },
+        )
+    };
+    let err = register(&mut deps, 50).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ExceedSchedulesMaximumLimit(TEAM.to_string())
+    );
+
+    // vested but unclaimed grants still count
+    let err = register(&mut deps, 100).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ExceedSchedulesMaximumLimit(TEAM.to_string())
+    );
+
+    let msg = ExecuteMsg::Claim {
+        grant_ids: Some(vec![1]),
+        recipient: None,
+    };
+    execute(deps.as_mut(), env_at(100), mock_info(TEAM, &[]), msg).unwrap();
+    register(&mut deps, 100).unwrap();
+    register(&mut deps, 100).unwrap_err();
+}
@@ -1,13 +1,21 @@
 use crate::coinHelpers::validate_sent_sufficient_coin;
 use crate::error::ContractError;
 use crate::msg::{
-    CreatePollResponse, ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg, TokenStakeResponse,
+    CreatePollResponse, ExecuteMsg, FtFrozenBalanceResponse, FtTokenResponse,
+    FungibleTokenQueryMsg, FungibleTokenResponse, IncentiveClaimResponse, InstantiateMsg,
+    PollResponse, QueryMsg, TimelockExecuteMsg, TimelockResponse, TokenStakeResponse,
+};
+use crate::state::{
+    Incentive, Poll, PollStatus, State, TimelockOperation, Voter, BANK, CONFIG, FUNGIBLE_TOKEN,
+    INCENTIVES, INCENTIVE_CLAIMS, POLLS, TIMELOCK,
 };
-use crate::state::{Poll, PollStatus, State, Voter, BANK, CONFIG, POLLS};
 use cosmwasm_std::{
     attr, coin, entry_point, to_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env,
-    MessageInfo, Response, StdError, StdResult, Storage, SubMsg, Uint128,
+    MessageInfo, Reply, Response, StdError, StdResult, Storage, SubMsg, SubMsgResult, Uint128,
+    WasmMsg,
 };
+use cw_utils::{must_pay, Scheduled};
+use std::ops::Add;
 
 pub const VOTING_TOKEN: &str = "voting_token";
 pub const DEFAULT_END_HEIGHT_BLOCKS: &u64 = &100_800_u64;
@@ -30,6 +38,19 @@ pub fn instantiate(
     };
 
     CONFIG.save(deps.storage, &state)?;
+    if let Some(timelock) = msg.timelock {
+        TIMELOCK.save(deps.storage, &deps.api.addr_validate(&timelock)?)?;
+    }
+    if let Some(fungible_token) = msg.fungible_token {
+        let fungible_token = deps.api.addr_validate(&fungible_token)?;
+        let res: FtTokenResponse = deps
+            .querier
+            .query_wasm_smart(&fungible_token, &FungibleTokenQueryMsg::Token {})?;
+        if res.token.denom != state.denom || res.token.issuer != fungible_token.as_str() {
+            return Err(ContractError::InvalidVotingToken { denom: state.denom });
+        }
+        FUNGIBLE_TOKEN.save(deps.storage, &fungible_token)?;
+    }
 
     Ok(Response::default())
 }
@@ -57,6 +78,8 @@ pub fn execute(
             description,
             start_height,
             end_height,
+            timelock_operation,
+            incentive_claim_period,
         } => create_poll(
             deps,
             env,
@@ -65,7 +88,13 @@ pub fn execute(
             description,
             start_height,
             end_height,
+            timelock_operation,
+            incentive_claim_period,
         ),
+        ExecuteMsg::ClaimIncentive { poll_id } => claim_incentive(deps, env, info, poll_id),
+        ExecuteMsg::ReturnIncentive { poll_id } => return_incentive(deps, env, poll_id),
+        ExecuteMsg::UpdateTimelock { timelock } => update_timelock(deps, info, timelock),
+        ExecuteMsg::UpdateOwner { owner } => update_owner(deps, info, owner),
     }
 }
 
@@ -175,7 +204,23 @@ fn validate_end_height(end_height: Option<u64>, env: Env) -> Result<(), Contract
     }
 }
 
+/// validate_timelock_operation returns an error if a timelock operation is given but no
+/// timelock contract is configured to schedule it on
+fn validate_timelock_operation(
+    deps: Deps,
+    timelock_operation: &Option<TimelockOperation>,
+) -> Result<(), ContractError> {
+    if let Some(operation) = timelock_operation {
+        if TIMELOCK.may_load(deps.storage)?.is_none() {
+            return Err(ContractError::TimelockNotConfigured {});
+        }
+        deps.api.addr_validate(&operation.target_address)?;
+    }
+    Ok(())
+}
+
 /// create a new poll
+#[allow(clippy::too_many_arguments)]
 pub fn create_poll(
     deps: DepsMut,
     env: Env,
@@ -184,16 +229,31 @@ pub fn create_poll(
     description: String,
     start_height: Option<u64>,
     end_height: Option<u64>,
+    timelock_operation: Option<TimelockOperation>,
+    incentive_claim_period: Option<u64>,
 ) -> Result<Response, ContractError> {
     validate_quorum_percentage(quorum_percentage)?;
     validate_end_height(end_height, env.clone())?;
     validate_description(&description)?;
+    validate_timelock_operation(deps.as_ref(), &timelock_operation)?;
 
     let mut state = CONFIG.load(deps.storage)?;
     let poll_count = state.poll_count;
     let poll_id = poll_count + 1;
     state.poll_count = poll_id;
 
+    let incentive = match incentive_claim_period {
+        Some(claim_period) => Some(Incentive {
+            amount: must_pay(&info, &state.denom)?,
+            claim_period,
+            claim_deadline: None,
+            total_weight: Uint128::zero(),
+            claimed: Uint128::zero(),
+            returned: false,
+        }),
+        None => None,
+    };
+
     let new_poll = Poll {
         creator: info.sender,
         status: PollStatus::InProgress,
@@ -205,12 +265,14 @@ pub fn create_poll(
         end_height: end_height.unwrap_or(env.block.height + DEFAULT_END_HEIGHT_BLOCKS),
         start_height,
         description,
+        timelock_operation,
+        schedule_error: None,
     };
     let key = state.poll_count.to_be_bytes();
     POLLS.save(deps.storage, &key, &new_poll)?;
 
     CONFIG.save(deps.storage, &state)?;
-    let attributes = vec![
+    let mut attributes = vec![
         attr("action", "create_poll"),
         attr("creator", new_poll.creator),
         attr("poll_id", &poll_id.to_string()),
@@ -221,6 +283,10 @@ pub fn create_poll(
         attr("end_height", new_poll.end_height.to_string()),
         attr("start_height", start_height.unwrap_or(0).to_string()),
     ];
+    if let Some(incentive) = incentive {
+        attributes.push(attr("incentive", incentive.amount));
+        INCENTIVES.save(deps.storage, poll_id, &incentive)?;
+    }
 
     let data = to_binary(&CreatePollResponse { poll_id })?;
 
@@ -276,23 +342,23 @@ pub fn end_poll(
 
     let mut rejected_reason = "";
     let mut passed = false;
+    let mut quorum_reached = false;
 
     if tallied_weight > 0 {
         let state = CONFIG.load(deps.storage)?;
 
-        let staked_weight = deps
-            .querier
-            .query_balance(&env.contract.address, &state.denom)
-            .unwrap()
-            .amount
-            .u128();
+        // Only tokens staked through StakeVotingTokens count, tokens sent to the contract
+        // directly must not be able to push a poll below its quorum.
+        let staked_weight = state.staked_tokens.u128();
 
         if staked_weight == 0 {
             return Err(ContractError::PollNoStake {});
         }
 
-        let quorum = ((tallied_weight / staked_weight) * 100) as u8;
-        if a_poll.quorum_percentage.is_some() && quorum < a_poll.quorum_percentage.unwrap() {
+        let quorum = tallied_weight * 100 / staked_weight;
+        if a_poll.quorum_percentage.is_some()
+            && quorum < u128::from(a_poll.quorum_percentage.unwrap())
+        {
             // Quorum: More than quorum_percentage of the total staked tokens at the end of the voting
             // period need to have participated in the vote.
             rejected_reason = "Quorum not reached";
@@ -301,7 +367,9 @@ pub fn end_poll(
             // (after excluding “Abstain” votes) need to have voted in favor of the proposal (“Yes”).
             a_poll.status = PollStatus::Passed;
             passed = true;
+            quorum_reached = true;
         } else {
+            quorum_reached = true;
             rejected_reason = "Threshold not reached";
         }
     } else {
@@ -323,7 +391,195 @@ pub fn end_poll(
         attr("passed", passed.to_string()),
     ];
 
-    Ok(Response::new().add_attributes(attributes))
+    let mut response = Response::new().add_attributes(attributes);
+    if let Some(mut incentive) = INCENTIVES.may_load(deps.storage, poll_id)? {
+        if quorum_reached {
+            incentive.claim_deadline = Some(env.block.height + incentive.claim_period);
+            incentive.total_weight = Uint128::from(tallied_weight);
+        } else {
+            // nobody earned a share, the whole pool goes back to the creator right away
+            let state = CONFIG.load(deps.storage)?;
+            incentive.returned = true;
+            response = response.add_message(BankMsg::Send {
+                to_address: a_poll.creator.to_string(),
+                amount: vec![coin(incentive.amount.u128(), state.denom)],
+            });
+        }
+        INCENTIVES.save(deps.storage, poll_id, &incentive)?;
+    }
+    if let (true, Some(operation)) = (passed, a_poll.timelock_operation) {
+        // a rejected schedule must not undo the tally, the reply records it on the poll instead
+        let schedule = schedule_operation(
+            deps.storage,
+            &env,
+            poll_id,
+            a_poll.description,
+            operation,
+        )?;
+        response = response.add_submessage(SubMsg::reply_on_error(schedule, poll_id));
+    }
+
+    Ok(response)
+}
+
+/// Records why the timelock rejected the operation of the poll the reply id refers to
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let error = match msg.result {
+        SubMsgResult::Err(error) => error,
+        SubMsgResult::Ok(_) => return Ok(Response::default()),
+    };
+    let key = &msg.id.to_be_bytes();
+    let mut a_poll = POLLS.load(deps.storage, key)?;
+    a_poll.schedule_error = Some(error.clone());
+    POLLS.save(deps.storage, key, &a_poll)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "schedule_failed"),
+        attr("poll_id", msg.id.to_string()),
+        attr("error", error),
+    ]))
+}
+
+/// Message scheduling a passed poll's operation on the timelock contract
+fn schedule_operation(
+    storage: &dyn Storage,
+    env: &Env,
+    poll_id: u64,
+    title: String,
+    operation: TimelockOperation,
+) -> Result<WasmMsg, ContractError> {
+    let timelock = TIMELOCK
+        .may_load(storage)?
+        .ok_or(ContractError::TimelockNotConfigured {})?;
+    let execution_time = Scheduled::AtTime(env.block.time).add(operation.delay)?;
+    let msg = TimelockExecuteMsg::Schedule {
+        target_address: operation.target_address,
+        data: operation.data,
+        title,
+        description: format!("Passed poll {}", poll_id),
+        execution_time,
+        executors: operation.executors,
+    };
+    Ok(WasmMsg::Execute {
+        contract_addr: timelock.to_string(),
+        msg: to_binary(&msg)?,
+        funds: vec![],
+    })
+}
+
+pub fn claim_incentive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: u64,
+) -> Result<Response, ContractError> {
+    let mut incentive = INCENTIVES
+        .may_load(deps.storage, poll_id)?
+        .ok_or(ContractError::IncentiveNotClaimable {})?;
+    let claim_deadline = incentive
+        .claim_deadline
+        .ok_or(ContractError::IncentiveNotClaimable {})?;
+    if env.block.height > claim_deadline {
+        return Err(ContractError::IncentiveClaimExpired { claim_deadline });
+    }
+    if INCENTIVE_CLAIMS
+        .may_load(deps.storage, (poll_id, &info.sender))?
+        .is_some()
+    {
+        return Err(ContractError::IncentiveAlreadyClaimed {});
+    }
+
+    let a_poll = POLLS.load(deps.storage, &poll_id.to_be_bytes())?;
+    let weight = voted_weight(&info.sender, &a_poll).ok_or(ContractError::PollSenderNotVoted {})?;
+    let amount = incentive
+        .amount
+        .multiply_ratio(weight, incentive.total_weight);
+    if amount.is_zero() {
+        return Err(ContractError::IncentiveNotClaimable {});
+    }
+    incentive.claimed += amount;
+    INCENTIVES.save(deps.storage, poll_id, &incentive)?;
+    INCENTIVE_CLAIMS.save(deps.storage, (poll_id, &info.sender), &amount)?;
+
+    let state = CONFIG.load(deps.storage)?;
+    Ok(send_tokens(
+        &info.sender,
+        vec![coin(amount.u128(), state.denom)],
+        "claim_incentive",
+    )
+    .add_attribute("poll_id", poll_id.to_string())
+    .add_attribute("amount", amount))
+}
+
+pub fn return_incentive(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, ContractError> {
+    let mut incentive = INCENTIVES
+        .may_load(deps.storage, poll_id)?
+        .ok_or(ContractError::IncentiveNotClaimable {})?;
+    let claim_deadline = match incentive.claim_deadline {
+        Some(claim_deadline) if !incentive.returned => claim_deadline,
+        _ => return Err(ContractError::IncentiveNotClaimable {}),
+    };
+    if env.block.height <= claim_deadline {
+        return Err(ContractError::IncentiveClaimNotExpired { claim_deadline });
+    }
+
+    incentive.returned = true;
+    INCENTIVES.save(deps.storage, poll_id, &incentive)?;
+
+    let a_poll = POLLS.load(deps.storage, &poll_id.to_be_bytes())?;
+    let unclaimed = incentive.amount.checked_sub(incentive.claimed)?;
+    let mut response = Response::new().add_attributes(vec![
+        attr("action", "return_incentive"),
+        attr("poll_id", poll_id.to_string()),
+        attr("amount", unclaimed),
+    ]);
+    if !unclaimed.is_zero() {
+        let state = CONFIG.load(deps.storage)?;
+        response = response.add_message(BankMsg::Send {
+            to_address: a_poll.creator.to_string(),
+            amount: vec![coin(unclaimed.u128(), state.denom)],
+        });
+    }
+    Ok(response)
+}
+
+pub fn update_timelock(
+    deps: DepsMut,
+    info: MessageInfo,
+    timelock: Option<String>,
+) -> Result<Response, ContractError> {
+    let state = CONFIG.load(deps.storage)?;
+    if state.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    match &timelock {
+        Some(timelock) => TIMELOCK.save(deps.storage, &deps.api.addr_validate(timelock)?)?,
+        None => TIMELOCK.remove(deps.storage),
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_timelock"),
+        attr("timelock", timelock.unwrap_or_default()),
+    ]))
+}
+
+pub fn update_owner(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let mut state = CONFIG.load(deps.storage)?;
+    if state.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    state.owner = deps.api.addr_validate(&owner)?;
+    CONFIG.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_owner"),
+        attr("owner", state.owner),
+    ]))
 }
 
 // unlock voter's tokens in a given poll
@@ -356,6 +612,11 @@ fn has_voted(voter: &Addr, a_poll: &Poll) -> bool {
     a_poll.voters.iter().any(|i| i == voter)
 }
 
+fn voted_weight(voter: &Addr, a_poll: &Poll) -> Option<Uint128> {
+    let index = a_poll.voters.iter().position(|i| i == voter)?;
+    Some(a_poll.voter_info[index].weight)
+}
+
 pub fn cast_vote(
     deps: DepsMut,
     _env: Env,
@@ -386,6 +647,10 @@ pub fn cast_vote(
     if token_manager.token_balance < weight {
         return Err(ContractError::PollInsufficientStake {});
     }
+    let frozen = frozen_stake(deps.as_ref(), &info.sender, &state.denom)?;
+    if token_manager.token_balance.saturating_sub(frozen) < weight {
+        return Err(ContractError::PollFrozenStake { frozen });
+    }
     token_manager.participated_polls.push(poll_id);
     token_manager.locked_tokens.push((poll_id, weight));
     BANK.save(deps.storage, key, &token_manager)?;
@@ -407,6 +672,23 @@ pub fn cast_vote(
     Ok(Response::new().add_attributes(attributes))
 }
 
+// Part of the stake covered by the freeze on the voter's account, zero for plain denoms.
+// The freeze applies to the wallet and the stake together, the wallet balance is used up first
+fn frozen_stake(deps: Deps, voter: &Addr, denom: &str) -> StdResult<Uint128> {
+    let fungible_token = match FUNGIBLE_TOKEN.may_load(deps.storage)? {
+        Some(fungible_token) => fungible_token,
+        None => return Ok(Uint128::zero()),
+    };
+    let res: FtFrozenBalanceResponse = deps.querier.query_wasm_smart(
+        fungible_token,
+        &FungibleTokenQueryMsg::FrozenBalance {
+            account: voter.to_string(),
+        },
+    )?;
+    let wallet = deps.querier.query_balance(voter, denom)?;
+    Ok(res.balance.amount.saturating_sub(wallet.amount))
+}
+
 fn send_tokens(to_address: &Addr, amount: Vec<Coin>, action: &str) -> Response {
     let attributes = vec![attr("action", action), attr("to", to_address.clone())];
 
@@ -426,7 +708,43 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             token_balance(deps, deps.api.addr_validate(address.as_str())?)
         }
         QueryMsg::Poll { poll_id } => query_poll(deps, poll_id),
+        QueryMsg::Timelock {} => to_binary(&TimelockResponse {
+            timelock: TIMELOCK.may_load(deps.storage)?.map(String::from),
+        }),
+        QueryMsg::FungibleToken {} => to_binary(&FungibleTokenResponse {
+            fungible_token: FUNGIBLE_TOKEN.may_load(deps.storage)?.map(String::from),
+        }),
+        QueryMsg::Incentive { poll_id } => to_binary(&INCENTIVES.may_load(deps.storage, poll_id)?),
+        QueryMsg::IncentiveClaim { poll_id, address } => {
+            query_incentive_claim(deps, poll_id, deps.api.addr_validate(&address)?)
+        }
+    }
+}
+
+fn query_incentive_claim(deps: Deps, poll_id: u64, address: Addr) -> StdResult<Binary> {
+    if let Some(amount) = INCENTIVE_CLAIMS.may_load(deps.storage, (poll_id, &address))? {
+        return to_binary(&IncentiveClaimResponse {
+            amount,
+            claimed: true,
+        });
     }
+    let amount = match INCENTIVES.may_load(deps.storage, poll_id)? {
+        Some(incentive) if incentive.claim_deadline.is_some() => {
+            let a_poll = POLLS.load(deps.storage, &poll_id.to_be_bytes())?;
+            voted_weight(&address, &a_poll)
+                .map(|weight| {
+                    incentive
+                        .amount
+                        .multiply_ratio(weight, incentive.total_weight)
+                })
+                .unwrap_or_default()
+        }
+        _ => Uint128::zero(),
+    };
+    to_binary(&IncentiveClaimResponse {
+        amount,
+        claimed: false,
+    })
 }
 
 fn query_poll(deps: Deps, poll_id: u64) -> StdResult<Binary> {
@@ -445,6 +763,8 @@ fn query_poll(deps: Deps, poll_id: u64) -> StdResult<Binary> {
         end_height: Some(poll.end_height),
         start_height: poll.start_height,
         description: poll.description,
+        timelock_operation: poll.timelock_operation,
+        schedule_error: poll.schedule_error,
     };
     to_binary(&resp)
 }
@@ -1,4 +1,5 @@
 use cosmwasm_std::{OverflowError, StdError, Uint128};
+use cw_utils::PaymentError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,6 +10,18 @@ pub enum ContractError {
     #[error("{0}")]
     OverflowError(#[from] OverflowError),
 
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("unauthorized")]
+    Unauthorized {},
+
+    #[error("no timelock contract configured")]
+    TimelockNotConfigured {},
+
+    #[error("denom {denom} was not issued by the fungible token contract")]
+    InvalidVotingToken { denom: String },
+
     #[error("insufficient funds sent")]
     InsufficientFundsSent {},
 
@@ -48,6 +61,24 @@ pub enum ContractError {
     #[error("sender staked tokens insufficient")]
     PollInsufficientStake {},
 
+    #[error("frozen tokens can't vote (frozen {frozen})")]
+    PollFrozenStake { frozen: Uint128 },
+
     #[error("quorum percentage must be 0 to 100 (quorum_percentage: {quorum_percentage})")]
     PollQuorumPercentageMismatch { quorum_percentage: u8 },
+
+    #[error("sender did not vote in poll")]
+    PollSenderNotVoted {},
+
+    #[error("poll has no incentive to claim")]
+    IncentiveNotClaimable {},
+
+    #[error("incentive already claimed")]
+    IncentiveAlreadyClaimed {},
+
+    #[error("incentive claim period is over (claim_deadline {claim_deadline})")]
+    IncentiveClaimExpired { claim_deadline: u64 },
+
+    #[error("incentive claim period is not over (claim_deadline {claim_deadline})")]
+    IncentiveClaimNotExpired { claim_deadline: u64 },
 }
\ No newline at end of file
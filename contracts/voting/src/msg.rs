@@ -1,10 +1,20 @@
-use crate::state::{PollStatus, State};
+use crate::state::{Incentive, PollStatus, State, TimelockOperation};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Binary, Coin, Uint128};
+use cw_utils::Scheduled;
+use serde::{Deserialize, Serialize};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub denom: String,
+    /// Timelock contract passed polls schedule their operation on. This contract must be one of
+    /// its proposers.
+    pub timelock: Option<String>,
+    /// fungibleToken contract that issued `denom`. When set, its issuer is checked on
+    /// instantiate and tokens it froze on a voter's account can't be voted with. Whitelisted
+    /// denoms also need this contract whitelisted to receive stakes.
+    #[serde(default)]
+    pub fungible_token: Option<String>,
 }
 
 #[cw_serde]
@@ -23,10 +33,32 @@ pub enum ExecuteMsg {
         description: String,
         start_height: Option<u64>,
         end_height: Option<u64>,
+        /// Scheduled on the timelock contract when the poll passes
+        timelock_operation: Option<TimelockOperation>,
+        /// Turns the voting tokens sent along into an incentive pool for the poll's voters,
+        /// claimable for this many blocks after a poll that reached quorum ends
+        incentive_claim_period: Option<u64>,
     },
     EndPoll {
         poll_id: u64,
     },
+    /// Pays the sender's share of a poll's incentive pool, proportional to the weight voted
+    ClaimIncentive {
+        poll_id: u64,
+    },
+    /// Returns what is left of a poll's incentive pool to its creator once the claim period
+    /// is over
+    ReturnIncentive {
+        poll_id: u64,
+    },
+    /// Owner only
+    UpdateTimelock {
+        timelock: Option<String>,
+    },
+    /// Hands the contract over to a new owner, e.g. the timelock of a DAO. Owner only
+    UpdateOwner {
+        owner: String,
+    },
 }
 
 #[cw_serde]
@@ -38,6 +70,14 @@ pub enum QueryMsg {
     TokenStake { address: String },
     #[returns(PollResponse)]
     Poll { poll_id: u64 },
+    #[returns(TimelockResponse)]
+    Timelock {},
+    #[returns(FungibleTokenResponse)]
+    FungibleToken {},
+    #[returns(Option<Incentive>)]
+    Incentive { poll_id: u64 },
+    #[returns(IncentiveClaimResponse)]
+    IncentiveClaim { poll_id: u64, address: String },
 }
 
 #[cw_serde]
@@ -48,6 +88,8 @@ pub struct PollResponse {
     pub end_height: Option<u64>,
     pub start_height: Option<u64>,
     pub description: String,
+    pub timelock_operation: Option<TimelockOperation>,
+    pub schedule_error: Option<String>,
 }
 
 #[cw_serde]
@@ -63,4 +105,58 @@ pub struct PollCountResponse {
 #[cw_serde]
 pub struct TokenStakeResponse {
     pub token_balance: Uint128,
+}
+
+#[cw_serde]
+pub struct TimelockResponse {
+    pub timelock: Option<String>,
+}
+
+#[cw_serde]
+pub struct FungibleTokenResponse {
+    pub fungible_token: Option<String>,
+}
+
+#[cw_serde]
+pub struct IncentiveClaimResponse {
+    /// Share of the pool the address is entitled to, zero until the poll ends with quorum
+    pub amount: Uint128,
+    pub claimed: bool,
+}
+
+/// Subset of the fungibleToken contract's queries the voting contract sends
+#[cw_serde]
+pub enum FungibleTokenQueryMsg {
+    Token {},
+    FrozenBalance { account: String },
+}
+
+/// Part of the fungibleToken `Token` response read here, other fields are ignored
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FtTokenResponse {
+    pub token: FtToken,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FtToken {
+    pub denom: String,
+    pub issuer: String,
+}
+
+#[cw_serde]
+pub struct FtFrozenBalanceResponse {
+    pub balance: Coin,
+}
+
+/// Subset of the timelock contract's execute messages the voting contract sends
+#[cw_serde]
+pub enum TimelockExecuteMsg {
+    Schedule {
+        target_address: String,
+        data: Binary,
+        title: String,
+        description: String,
+        execution_time: Scheduled,
+        executors: Option<Vec<String>>,
+    },
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Binary, Uint128};
 use cw_storage_plus::{Item, Map};
+use cw_utils::Duration;
 
 #[cw_serde]
 pub struct State {
@@ -44,8 +45,43 @@ pub struct Poll {
     pub end_height: u64,
     pub start_height: Option<u64>,
     pub description: String,
+    pub timelock_operation: Option<TimelockOperation>,
+    /// Why the timelock rejected the operation of the passed poll, e.g. a delay below its
+    /// minimum. The poll stays passed, the operation has to be proposed again
+    #[serde(default)]
+    pub schedule_error: Option<String>,
+}
+
+/// Wasm execute message a passed poll schedules on the timelock contract
+#[cw_serde]
+pub struct TimelockOperation {
+    pub target_address: String,
+    pub data: Binary,
+    /// Counted from the end of the poll, must cover the timelock's minimum delay
+    pub delay: Duration,
+    pub executors: Option<Vec<String>>,
+}
+
+/// Voting tokens the poll creator set aside for the poll's voters
+#[cw_serde]
+pub struct Incentive {
+    pub amount: Uint128,
+    /// Blocks after the poll ends during which voters can claim their share
+    pub claim_period: u64,
+    /// Set when the poll ends having reached quorum
+    pub claim_deadline: Option<u64>,
+    /// Weight voted in the poll, shares are proportional to it
+    pub total_weight: Uint128,
+    pub claimed: Uint128,
+    /// Whether what was left of the pool went back to the creator
+    pub returned: bool,
 }
 
 pub const CONFIG: Item<State> = Item::new("config");
+pub const TIMELOCK: Item<Addr> = Item::new("timelock");
+pub const FUNGIBLE_TOKEN: Item<Addr> = Item::new("fungible_token");
 pub const POLLS: Map<&[u8], Poll> = Map::new("polls");
-pub const BANK: Map<&[u8], TokenManager> = Map::new("bank");
\ No newline at end of file
+pub const BANK: Map<&[u8], TokenManager> = Map::new("bank");
+pub const INCENTIVES: Map<u64, Incentive> = Map::new("incentives");
+/// Amount each voter claimed from a poll's incentive pool
+pub const INCENTIVE_CLAIMS: Map<(u64, &Addr), Uint128> = Map::new("incentive_claims");
\ No newline at end of file
@@ -1,16 +1,21 @@
 #[cfg(test)]
 mod test_module {
-    use crate::contract::{execute, instantiate, query, VOTING_TOKEN};
+    use crate::contract::{execute, instantiate, query, reply, VOTING_TOKEN};
     use crate::error::ContractError;
-    use crate::msg::{ExecuteMsg, InstantiateMsg, PollResponse, QueryMsg};
-    use crate::state::{PollStatus, State, CONFIG};
+    use crate::msg::{
+        ExecuteMsg, FtFrozenBalanceResponse, FtToken, FtTokenResponse, FungibleTokenQueryMsg,
+        IncentiveClaimResponse, InstantiateMsg, PollResponse, QueryMsg, TimelockExecuteMsg,
+    };
+    use crate::state::{PollStatus, State, TimelockOperation, CONFIG};
     use cosmwasm_std::testing::{
-        mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
+        mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info, MockQuerier,
     };
     use cosmwasm_std::{
-        attr, coins, from_binary, Addr, BankMsg, Coin, DepsMut, Env, MessageInfo, Response,
-        StdError, SubMsg, Timestamp, Uint128,
+        attr, coin, coins, from_binary, to_binary, Addr, BankMsg, Binary, Coin, ContractResult,
+        CosmosMsg, DepsMut, Env, MessageInfo, Reply, Response, StdError, SubMsg, SubMsgResult,
+        SystemError, SystemResult, Timestamp, Uint128, WasmMsg, WasmQuery,
     };
+    use cw_utils::{Duration, Scheduled};
 
     const DEFAULT_END_HEIGHT: u64 = 100800u64;
     const TEST_CREATOR: &str = "creator";
@@ -20,6 +25,8 @@ mod test_module {
     fn mock_instantiate(deps: DepsMut) {
         let msg = InstantiateMsg {
             denom: String::from(VOTING_TOKEN),
+            timelock: None,
+            fungible_token: None,
         };
 
         let info = mock_info(TEST_CREATOR, &coins(2, &msg.denom));
@@ -38,9 +45,35 @@ mod test_module {
     fn init_msg() -> InstantiateMsg {
         InstantiateMsg {
             denom: String::from(VOTING_TOKEN),
+            timelock: None,
+            fungible_token: None,
         }
     }
 
+    #[test]
+    fn owner_hands_over_the_contract() {
+        let mut deps = mock_dependencies();
+        mock_instantiate(deps.as_mut());
+
+        let msg = ExecuteMsg::UpdateOwner {
+            owner: "timelock".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(TEST_VOTER, &[]), msg.clone())
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg.clone()).unwrap();
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap().owner,
+            Addr::unchecked("timelock")
+        );
+
+        // the previous owner can no longer update the timelock
+        let msg = ExecuteMsg::UpdateTimelock { timelock: None };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg)
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+    }
+
     #[test]
     fn proper_initialization() {
         let mut deps = mock_dependencies();
@@ -133,6 +166,8 @@ mod test_module {
             description,
             start_height,
             end_height,
+            timelock_operation: None,
+            incentive_claim_period: None,
         }
     }
 
@@ -486,6 +521,56 @@ mod test_module {
         assert_eq!(PollStatus::Rejected, value.status);
     }
 
+    #[test]
+    fn end_poll_quorum_ignores_dusting() {
+        let stake_amount = 100;
+        // someone sends a large amount straight to the contract without staking it
+        let mut deps = mock_dependencies_with_balance(&coins(1_000_000, VOTING_TOKEN));
+        mock_instantiate(deps.as_mut());
+        let (mut creator_env, creator_info) =
+            mock_info_height(TEST_CREATOR, &coins(2, VOTING_TOKEN), 0, 0);
+
+        let msg = create_poll_msg(
+            50,
+            "test".to_string(),
+            None,
+            Some(creator_env.block.height + 1),
+        );
+        execute(
+            deps.as_mut(),
+            creator_env.clone(),
+            creator_info.clone(),
+            msg,
+        )
+        .unwrap();
+
+        let info = mock_info(TEST_VOTER, &coins(stake_amount, VOTING_TOKEN));
+        let msg = ExecuteMsg::StakeVotingTokens {};
+        let execute_res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_stake_tokens_result(stake_amount, Some(1), execute_res, deps.as_mut());
+
+        // 60 of the 100 staked tokens vote, the dust doesn't count towards the quorum
+        let msg = ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote: "yes".to_string(),
+            weight: Uint128::from(60u128),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        creator_env.block.height = &creator_env.block.height + 2;
+        let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+        let execute_res = execute(deps.as_mut(), creator_env, creator_info, msg).unwrap();
+        assert_eq!(
+            execute_res.attributes,
+            vec![
+                attr("action", "end_poll"),
+                attr("poll_id", "1"),
+                attr("rejected_reason", ""),
+                attr("passed", "true"),
+            ]
+        );
+    }
+
     #[test]
     fn fails_end_poll_before_start_height() {
         let mut deps = mock_dependencies();
@@ -825,6 +910,337 @@ mod test_module {
         }
     }
 
+    fn timelock_operation() -> TimelockOperation {
+        TimelockOperation {
+            target_address: "treasury".to_string(),
+            data: Binary::from(br#"{"release":{}}"#.to_vec()),
+            delay: Duration::Time(3600),
+            executors: None,
+        }
+    }
+
+    #[test]
+    fn fails_create_poll_timelock_operation_without_timelock() {
+        let mut deps = mock_dependencies();
+        mock_instantiate(deps.as_mut());
+
+        let msg = ExecuteMsg::CreatePoll {
+            quorum_percentage: None,
+            description: "test".to_string(),
+            start_height: None,
+            end_height: None,
+            timelock_operation: Some(timelock_operation()),
+            incentive_claim_period: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+
+        match res {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::TimelockNotConfigured {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn passed_poll_schedules_timelock_operation() {
+        let stake_amount = 1000;
+        let mut deps = mock_dependencies_with_balance(&coins(stake_amount, VOTING_TOKEN));
+        let msg = InstantiateMsg {
+            denom: String::from(VOTING_TOKEN),
+            timelock: Some("timelock".to_string()),
+            fungible_token: None,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+        let (mut creator_env, creator_info) = mock_info_height(TEST_CREATOR, &[], 1000, 10000);
+        let msg = ExecuteMsg::CreatePoll {
+            quorum_percentage: None,
+            description: "release funds".to_string(),
+            start_height: None,
+            end_height: Some(1001),
+            timelock_operation: Some(timelock_operation()),
+            incentive_claim_period: None,
+        };
+        execute(
+            deps.as_mut(),
+            creator_env.clone(),
+            creator_info.clone(),
+            msg,
+        )
+        .unwrap();
+
+        let info = mock_info(TEST_VOTER, &coins(stake_amount, VOTING_TOKEN));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::StakeVotingTokens {},
+        )
+        .unwrap();
+        let msg = ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote: "yes".to_string(),
+            weight: Uint128::from(stake_amount),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        creator_env.block.height = 1001;
+        let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+        let execute_res = execute(deps.as_mut(), creator_env.clone(), creator_info, msg).unwrap();
+
+        let schedule = TimelockExecuteMsg::Schedule {
+            target_address: "treasury".to_string(),
+            data: timelock_operation().data,
+            title: "release funds".to_string(),
+            description: "Passed poll 1".to_string(),
+            execution_time: Scheduled::AtTime(creator_env.block.time.plus_seconds(3600)),
+            executors: None,
+        };
+        assert_eq!(
+            execute_res.messages,
+            vec![SubMsg::reply_on_error(
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "timelock".to_string(),
+                    msg: to_binary(&schedule).unwrap(),
+                    funds: vec![],
+                }),
+                1
+            )]
+        );
+
+        // the timelock rejecting the operation leaves the poll passed with the error recorded
+        let error = "Minimum delay not reached".to_string();
+        let msg = Reply {
+            id: 1,
+            result: SubMsgResult::Err(error.clone()),
+        };
+        reply(deps.as_mut(), mock_env(), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 }).unwrap();
+        let poll: PollResponse = from_binary(&res).unwrap();
+        assert_eq!(poll.status, PollStatus::Passed);
+        assert_eq!(poll.schedule_error, Some(error));
+    }
+
+    // answers the fungibleToken queries for "fungible_token", which issued VOTING_TOKEN
+    fn mock_fungible_token(querier: &mut MockQuerier, frozen: u128) {
+        querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "fungible_token" => {
+                let res = match from_binary(msg).unwrap() {
+                    FungibleTokenQueryMsg::Token {} => to_binary(&FtTokenResponse {
+                        token: FtToken {
+                            denom: VOTING_TOKEN.to_string(),
+                            issuer: "fungible_token".to_string(),
+                        },
+                    }),
+                    FungibleTokenQueryMsg::FrozenBalance { .. } => {
+                        to_binary(&FtFrozenBalanceResponse {
+                            balance: coin(frozen, VOTING_TOKEN),
+                        })
+                    }
+                };
+                SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "wasm".to_string(),
+            }),
+        });
+    }
+
+    #[test]
+    fn fungible_token_binding() {
+        let mut deps = mock_dependencies();
+        mock_fungible_token(&mut deps.querier, 400);
+
+        let msg = InstantiateMsg {
+            denom: "other".to_string(),
+            timelock: None,
+            fungible_token: Some("fungible_token".to_string()),
+        };
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+        match res {
+            Err(ContractError::InvalidVotingToken { denom }) => assert_eq!(denom, "other"),
+            _ => panic!("Must return invalid voting token error"),
+        }
+
+        let msg = InstantiateMsg {
+            denom: String::from(VOTING_TOKEN),
+            timelock: None,
+            fungible_token: Some("fungible_token".to_string()),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            quorum_percentage: None,
+            description: "test".to_string(),
+            start_height: None,
+            end_height: None,
+            timelock_operation: None,
+            incentive_claim_period: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+        let info = mock_info(TEST_VOTER, &coins(1000, VOTING_TOKEN));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::StakeVotingTokens {},
+        )
+        .unwrap();
+
+        // 400 are frozen on the voter's account while 100 are left in the wallet, so the freeze
+        // covers 300 of the 1000 staked
+        deps.querier.update_balance(TEST_VOTER, coins(100, VOTING_TOKEN));
+        let msg = ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote: "yes".to_string(),
+            weight: Uint128::from(701u128),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+        match res {
+            Err(ContractError::PollFrozenStake { frozen }) => {
+                assert_eq!(frozen, Uint128::from(300u128))
+            }
+            _ => panic!("Must return frozen stake error"),
+        }
+
+        let msg = ExecuteMsg::CastVote {
+            poll_id: 1,
+            vote: "yes".to_string(),
+            weight: Uint128::from(700u128),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_cast_vote_success(TEST_VOTER, 700, 1, res);
+    }
+
+    #[test]
+    fn poll_incentive_claims_and_expiry() {
+        let mut deps = mock_dependencies();
+        mock_instantiate(deps.as_mut());
+
+        let (mut creator_env, creator_info) =
+            mock_info_height(TEST_CREATOR, &coins(100, VOTING_TOKEN), 1000, 10000);
+        let msg = ExecuteMsg::CreatePoll {
+            quorum_percentage: Some(50),
+            description: "test".to_string(),
+            start_height: None,
+            end_height: Some(1001),
+            timelock_operation: None,
+            incentive_claim_period: Some(10),
+        };
+        let res = execute(deps.as_mut(), creator_env.clone(), creator_info, msg).unwrap();
+        assert!(res.attributes.contains(&attr("incentive", "100")));
+
+        for (voter, weight) in [(TEST_VOTER, 600u128), (TEST_VOTER_2, 400)] {
+            let info = mock_info(voter, &coins(weight, VOTING_TOKEN));
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                ExecuteMsg::StakeVotingTokens {},
+            )
+            .unwrap();
+            let msg = ExecuteMsg::CastVote {
+                poll_id: 1,
+                vote: "yes".to_string(),
+                weight: Uint128::from(weight),
+            };
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        // nothing to claim before the poll ends
+        let msg = ExecuteMsg::ClaimIncentive { poll_id: 1 };
+        let res = execute(
+            deps.as_mut(),
+            creator_env.clone(),
+            mock_info(TEST_VOTER, &[]),
+            msg,
+        );
+        match res {
+            Err(ContractError::IncentiveNotClaimable {}) => {}
+            _ => panic!("Must return incentive not claimable error"),
+        }
+
+        creator_env.block.height = 1001;
+        let msg = ExecuteMsg::EndPoll { poll_id: 1 };
+        let info = mock_info(TEST_CREATOR, &[]);
+        execute(deps.as_mut(), creator_env.clone(), info, msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IncentiveClaim {
+                poll_id: 1,
+                address: TEST_VOTER_2.to_string(),
+            },
+        )
+        .unwrap();
+        let value: IncentiveClaimResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value,
+            IncentiveClaimResponse {
+                amount: Uint128::from(40u128),
+                claimed: false,
+            }
+        );
+
+        let mut env = creator_env.clone();
+        env.block.height = 1005;
+        let msg = ExecuteMsg::ClaimIncentive { poll_id: 1 };
+        let info = mock_info(TEST_VOTER, &[]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: TEST_VOTER.to_string(),
+                amount: coins(60, VOTING_TOKEN),
+            })]
+        );
+        let res = execute(deps.as_mut(), env, info, msg);
+        match res {
+            Err(ContractError::IncentiveAlreadyClaimed {}) => {}
+            _ => panic!("Must return incentive already claimed error"),
+        }
+
+        let msg = ExecuteMsg::ReturnIncentive { poll_id: 1 };
+        env = creator_env;
+        env.block.height = 1011;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::IncentiveClaimNotExpired { claim_deadline }) => {
+                assert_eq!(claim_deadline, 1011)
+            }
+            _ => panic!("Must return incentive claim not expired error"),
+        }
+
+        env.block.height = 1012;
+        let claim = ExecuteMsg::ClaimIncentive { poll_id: 1 };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(TEST_VOTER_2, &[]),
+            claim,
+        );
+        match res {
+            Err(ContractError::IncentiveClaimExpired { claim_deadline }) => {
+                assert_eq!(claim_deadline, 1011)
+            }
+            _ => panic!("Must return incentive claim expired error"),
+        }
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: TEST_CREATOR.to_string(),
+                amount: coins(40, VOTING_TOKEN),
+            })]
+        );
+    }
+
     // helper to confirm the expected create_poll response
     fn assert_create_poll_result(
         poll_id: u64,
@@ -891,4 +1307,4 @@ mod test_module {
             ]
         );
     }
-}
\ No newline at end of file
+}